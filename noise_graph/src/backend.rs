@@ -0,0 +1,296 @@
+use {
+    super::{
+        expr::{DistanceFunction, ReturnType, SourceType, MAX_FRACTAL_OCTAVES},
+        noise_fns::{AxisTurbulence, FractionalFractal, RoundF32},
+    },
+    noise::{
+        core::worley,
+        core::worley::distance_functions::{chebyshev, euclidean, euclidean_squared, manhattan},
+        BasicMulti, Billow, Fbm, HybridMulti, MultiFractal, NoiseFn, OpenSimplex, Perlin,
+        PerlinSurflet, RidgedMulti, Seedable, Simplex, SuperSimplex, Turbulence, Value, Worley,
+    },
+    serde::{Deserialize, Serialize},
+};
+
+/// The implementation of the seeded noise functions of a project.
+///
+/// Expressions only combine and transform the noise they are given, so each backend decides the
+/// output of every generator and fractal of a graph.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Backend {
+    #[default]
+    Noise,
+}
+
+impl Backend {
+    pub const ALL: [Self; 1] = [Self::Noise];
+
+    pub fn get(self) -> &'static dyn NoiseBackend {
+        match self {
+            Self::Noise => &NoiseCrate,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.get().name()
+    }
+}
+
+/// The kinds of fractal which sum octaves of a source.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FractalKind {
+    BasicMulti,
+    Billow,
+    Fbm,
+    HybridMulti,
+    RidgedMulti,
+}
+
+/// The evaluated settings of a fractal.
+#[derive(Clone, Copy, Debug)]
+pub struct FractalParams {
+    pub kind: FractalKind,
+    pub source_ty: SourceType,
+    pub seed: u32,
+    pub octaves: u32,
+    pub frequency: f64,
+    pub lacunarity: f64,
+    pub persistence: f64,
+
+    /// Only used by ridged fractals.
+    pub attenuation: f64,
+
+    /// The weight of one more octave after the whole ones, within [0, 1].
+    pub octave_remainder: f64,
+}
+
+/// Generator evaluation which a project may swap out, such as for a SIMD implementation or a
+/// deterministic port of the same algorithms.
+///
+/// Backends are given evaluated settings, so they do not depend on the expressions of the graph.
+pub trait NoiseBackend: Sync {
+    fn fractal(&self, params: FractalParams) -> Box<dyn NoiseFn<f64, 3>>;
+
+    /// The name shown when choosing the backend of a project.
+    fn name(&self) -> &'static str;
+
+    fn source(&self, source_ty: SourceType, seed: u32) -> Box<dyn NoiseFn<f64, 3>>;
+
+    fn turbulence(
+        &self,
+        source: Box<dyn NoiseFn<f64, 3>>,
+        params: TurbulenceParams,
+    ) -> Box<dyn NoiseFn<f64, 3>>;
+
+    fn worley(
+        &self,
+        seed: u32,
+        frequency: f64,
+        distance_fn: DistanceFunction,
+        return_ty: ReturnType,
+    ) -> Box<dyn NoiseFn<f64, 3>>;
+}
+
+/// The `noise` crate, which every project used before backends could be chosen.
+pub struct NoiseCrate;
+
+impl NoiseCrate {
+    fn fractal<T>(params: FractalParams) -> Box<dyn NoiseFn<f64, 3>>
+    where
+        T: Default + Seedable + NoiseFn<f64, 3> + 'static,
+    {
+        fn multi_fractal<F>(fractal: F, params: FractalParams) -> F
+        where
+            F: MultiFractal,
+        {
+            fractal
+                .set_octaves(params.octaves.clamp(1, MAX_FRACTAL_OCTAVES) as _)
+                .set_frequency(params.frequency)
+                .set_lacunarity(params.lacunarity)
+                .set_persistence(params.persistence)
+        }
+
+        // Fractals without a remainder are left to the `noise` crate, so that they stay the same
+        if params.octave_remainder > 0.0 {
+            return Box::new(
+                multi_fractal(
+                    FractionalFractal::<T>::new(params.kind, params.seed),
+                    params,
+                )
+                .set_attenuation(params.attenuation)
+                .set_remainder(params.octave_remainder),
+            );
+        }
+
+        match params.kind {
+            FractalKind::BasicMulti => {
+                Box::new(multi_fractal(BasicMulti::<T>::new(params.seed), params))
+            }
+            FractalKind::Billow => Box::new(multi_fractal(Billow::<T>::new(params.seed), params)),
+            FractalKind::Fbm => Box::new(multi_fractal(Fbm::<T>::new(params.seed), params)),
+            FractalKind::HybridMulti => {
+                Box::new(multi_fractal(HybridMulti::<T>::new(params.seed), params))
+            }
+            FractalKind::RidgedMulti => Box::new(
+                multi_fractal(RidgedMulti::<T>::new(params.seed), params)
+                    .set_attenuation(params.attenuation),
+            ),
+        }
+    }
+
+    fn turbulence<T>(
+        source: Box<dyn NoiseFn<f64, 3>>,
+        params: TurbulenceParams,
+    ) -> Box<dyn NoiseFn<f64, 3>>
+    where
+        T: Default + Seedable + NoiseFn<f64, 3> + 'static,
+    {
+        if params.axis_frequencies.is_none() && params.axis_powers.is_none() {
+            return Box::new(
+                Turbulence::<Box<dyn NoiseFn<f64, 3>>, T>::new(source)
+                    .set_seed(params.seed)
+                    .set_frequency(params.frequency)
+                    .set_power(params.power)
+                    .set_roughness(params.roughness as _),
+            );
+        }
+
+        Box::new(AxisTurbulence::<_, T>::new(
+            source,
+            params.seed,
+            params.axis_frequencies.unwrap_or([params.frequency; 3]),
+            params.axis_powers.unwrap_or([params.power; 3]),
+            params.roughness as _,
+        ))
+    }
+}
+
+impl NoiseBackend for NoiseCrate {
+    fn fractal(&self, params: FractalParams) -> Box<dyn NoiseFn<f64, 3>> {
+        // Super Simplex fractals have always been built from Open Simplex, which is kept so that
+        // existing seeds do not change
+        match params.source_ty {
+            SourceType::OpenSimplex => Self::fractal::<OpenSimplex>(params),
+            SourceType::Perlin => Self::fractal::<Perlin>(params),
+            SourceType::PerlinSurflet => Self::fractal::<PerlinSurflet>(params),
+            SourceType::Simplex => Self::fractal::<Simplex>(params),
+            SourceType::SuperSimplex => Self::fractal::<OpenSimplex>(params),
+            SourceType::Value => Self::fractal::<Value>(params),
+            SourceType::Worley => Self::fractal::<Worley>(params),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "noise 0.8"
+    }
+
+    fn source(&self, source_ty: SourceType, seed: u32) -> Box<dyn NoiseFn<f64, 3>> {
+        match source_ty {
+            SourceType::OpenSimplex => Box::new(OpenSimplex::new(seed)),
+            SourceType::Perlin => Box::new(Perlin::new(seed)),
+            SourceType::PerlinSurflet => Box::new(PerlinSurflet::new(seed)),
+            SourceType::Simplex => Box::new(Simplex::new(seed)),
+            SourceType::SuperSimplex => Box::new(SuperSimplex::new(seed)),
+            SourceType::Value => Box::new(Value::new(seed)),
+            SourceType::Worley => Box::new(Worley::new(seed)),
+        }
+    }
+
+    fn turbulence(
+        &self,
+        source: Box<dyn NoiseFn<f64, 3>>,
+        params: TurbulenceParams,
+    ) -> Box<dyn NoiseFn<f64, 3>> {
+        // Super Simplex turbulence has always been displaced by Open Simplex, like the fractals
+        match params.source_ty {
+            SourceType::OpenSimplex => Self::turbulence::<OpenSimplex>(source, params),
+            SourceType::Perlin => Self::turbulence::<Perlin>(source, params),
+            SourceType::PerlinSurflet => Self::turbulence::<PerlinSurflet>(source, params),
+            SourceType::Simplex => Self::turbulence::<Simplex>(source, params),
+            SourceType::SuperSimplex => Self::turbulence::<OpenSimplex>(source, params),
+            SourceType::Value => Self::turbulence::<Value>(source, params),
+            SourceType::Worley => Self::turbulence::<Worley>(source, params),
+        }
+    }
+
+    fn worley(
+        &self,
+        seed: u32,
+        frequency: f64,
+        distance_fn: DistanceFunction,
+        return_ty: ReturnType,
+    ) -> Box<dyn NoiseFn<f64, 3>> {
+        Box::new(
+            Worley::new(seed)
+                .set_frequency(frequency)
+                .set_distance_function(match distance_fn {
+                    DistanceFunction::Chebyshev => chebyshev,
+                    DistanceFunction::Euclidean => euclidean,
+                    DistanceFunction::EuclideanSquared => euclidean_squared,
+                    DistanceFunction::Manhattan => manhattan,
+                })
+                .set_return_type(match return_ty {
+                    ReturnType::Distance => worley::ReturnType::Distance,
+                    ReturnType::Value => worley::ReturnType::Value,
+                }),
+        )
+    }
+}
+
+/// Approximates another backend evaluated in single precision, as GPUs and many engines evaluate
+/// noise, by rounding the positions sampled by each generator and fractal and the values they
+/// return to `f32`.
+///
+/// Arithmetic within generators and between nodes is still done in double precision, so this
+/// shows the drift caused by large coordinates rather than the exact output of an `f32` port.
+pub struct SinglePrecision(pub &'static dyn NoiseBackend);
+
+impl NoiseBackend for SinglePrecision {
+    fn fractal(&self, params: FractalParams) -> Box<dyn NoiseFn<f64, 3>> {
+        Box::new(RoundF32::new(self.0.fractal(params)))
+    }
+
+    fn name(&self) -> &'static str {
+        "f32 approximation"
+    }
+
+    fn source(&self, source_ty: SourceType, seed: u32) -> Box<dyn NoiseFn<f64, 3>> {
+        Box::new(RoundF32::new(self.0.source(source_ty, seed)))
+    }
+
+    fn turbulence(
+        &self,
+        source: Box<dyn NoiseFn<f64, 3>>,
+        params: TurbulenceParams,
+    ) -> Box<dyn NoiseFn<f64, 3>> {
+        Box::new(RoundF32::new(self.0.turbulence(source, params)))
+    }
+
+    fn worley(
+        &self,
+        seed: u32,
+        frequency: f64,
+        distance_fn: DistanceFunction,
+        return_ty: ReturnType,
+    ) -> Box<dyn NoiseFn<f64, 3>> {
+        Box::new(RoundF32::new(self.0.worley(
+            seed,
+            frequency,
+            distance_fn,
+            return_ty,
+        )))
+    }
+}
+
+/// The evaluated settings of a turbulence, where the per-axis overrides replace the frequency or
+/// power along every axis.
+#[derive(Clone, Copy, Debug)]
+pub struct TurbulenceParams {
+    pub source_ty: SourceType,
+    pub seed: u32,
+    pub frequency: f64,
+    pub power: f64,
+    pub roughness: u32,
+    pub axis_frequencies: Option<[f64; 3]>,
+    pub axis_powers: Option<[f64; 3]>,
+}
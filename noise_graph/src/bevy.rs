@@ -0,0 +1,157 @@
+//! Loads exported graphs as Bevy assets, so that games can generate textures and terrain from
+//! them at runtime.
+//!
+//! ```ignore
+//! use {bevy::prelude::*, noise_graph::bevy::{NoiseGraph, NoiseGraphPlugin}};
+//!
+//! App::new()
+//!     .add_plugins((DefaultPlugins, NoiseGraphPlugin))
+//!     .add_systems(Startup, |server: Res<AssetServer>, mut commands: Commands| {
+//!         commands.insert_resource(Terrain(server.load("terrain.ron")));
+//!     })
+//!     .add_systems(Update, |terrain: Res<Terrain>, graphs: Res<Assets<NoiseGraph>>| {
+//!         if let Some(graph) = graphs.get(&terrain.0) {
+//!             let heights = graph.heightmap(UVec2::splat(256), Vec2::ZERO, 4.0);
+//!         }
+//!     })
+//!     .run();
+//! ```
+
+use {
+    super::{Expr, Graph},
+    bevy::{
+        app::{App, Plugin},
+        asset::{io::Reader, Asset, AssetApp, AssetLoader, AsyncReadExt, LoadContext},
+        math::{UVec2, Vec2},
+        reflect::TypePath,
+        render::{
+            render_resource::{Extent3d, TextureDimension, TextureFormat},
+            texture::Image,
+        },
+        utils::BoxedFuture,
+    },
+    ron::error::SpannedError,
+    std::{
+        error::Error,
+        fmt::{Display, Formatter},
+        io,
+    },
+};
+
+/// Adds the [`NoiseGraph`] asset, which is loaded from the `.ron` files the editor exports.
+pub struct NoiseGraphPlugin;
+
+impl Plugin for NoiseGraphPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<NoiseGraph>()
+            .init_asset_loader::<NoiseGraphLoader>();
+    }
+}
+
+/// An exported expression.
+///
+/// Noise functions cannot be shared between threads, so each of the sampling functions builds a
+/// [`Graph`] of its own; use [`NoiseGraph::graph`] to sample the same graph repeatedly.
+#[derive(Asset, Clone, Debug, TypePath)]
+pub struct NoiseGraph {
+    pub expr: Expr,
+}
+
+impl NoiseGraph {
+    pub fn graph(&self) -> Graph {
+        Graph::new(self.expr.clone())
+    }
+
+    /// Samples `size` values in rows, where the pixel at `(x, y)` samples the center of the area
+    /// `scale / size` units wide at `origin + (x, y) * scale / size`.
+    pub fn heightmap(&self, size: UVec2, origin: Vec2, scale: f32) -> Vec<f32> {
+        let graph = self.graph();
+        let step = scale as f64 / size.as_dvec2();
+        let origin = origin.as_dvec2() + step / 2.0;
+
+        (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let point = origin + step * UVec2::new(x, y).as_dvec2();
+
+                graph.sample(point.x, point.y, 0.0) as f32
+            })
+            .collect()
+    }
+
+    /// Samples the same values as [`NoiseGraph::heightmap`] into a single-channel `R32Float`
+    /// image, which materials and shaders may use directly.
+    pub fn image(&self, size: UVec2, origin: Vec2, scale: f32) -> Image {
+        let data = self
+            .heightmap(size, origin, scale)
+            .into_iter()
+            .flat_map(f32::to_ne_bytes)
+            .collect();
+
+        Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::R32Float,
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct NoiseGraphLoader;
+
+impl AssetLoader for NoiseGraphLoader {
+    type Asset = NoiseGraph;
+    type Settings = ();
+    type Error = NoiseGraphLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut buf = vec![];
+            reader
+                .read_to_end(&mut buf)
+                .await
+                .map_err(NoiseGraphLoaderError::Io)?;
+            let expr = ron::de::from_bytes(&buf).map_err(NoiseGraphLoaderError::Ron)?;
+
+            Ok(NoiseGraph { expr })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+#[derive(Debug)]
+pub enum NoiseGraphLoaderError {
+    Io(io::Error),
+    Ron(SpannedError),
+}
+
+impl Display for NoiseGraphLoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Unable to read noise graph: {err}"),
+            Self::Ron(err) => write!(f, "Invalid noise graph: {err}"),
+        }
+    }
+}
+
+impl Error for NoiseGraphLoaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Ron(err) => Some(err),
+        }
+    }
+}
@@ -0,0 +1,2290 @@
+//! Generates GLSL or WGSL which samples an expression on the GPU, using ports of the algorithms of
+//! the `noise` crate backend, or Rust which builds it with the `noise` crate.
+
+mod rust;
+
+pub use self::rust::RustParameters;
+
+pub(crate) use self::rust::rust;
+
+use {
+    super::expr::{
+        DistanceFunction, Expr, ExprCost, FractalExpr, OpType, RepeatMode, ReturnType, SourceType,
+        ThresholdMode, Variable, WrapMode, MAX_FRACTAL_OCTAVES,
+    },
+    noise::permutationtable::{NoiseHasher, PermutationTable},
+    std::{
+        collections::{BTreeMap, HashMap},
+        error::Error,
+        fmt::{Display, Formatter},
+    },
+};
+
+/// A function of the shader library, emitted before the nodes which call it.
+struct Helper {
+    name: &'static str,
+
+    /// Helpers which must be emitted first.
+    deps: &'static [&'static str],
+
+    glsl: &'static str,
+    wgsl: &'static str,
+}
+
+impl Helper {
+    fn src(&self, language: Language) -> &'static str {
+        match language {
+            Language::Glsl => self.glsl,
+            Language::Wgsl => self.wgsl,
+        }
+    }
+}
+
+/// Helpers which sum octaves of a source, emitted once for each source they are used with by
+/// replacing `NAME` with the name of the source and `SOURCE` with the function which samples it.
+const FRACTAL_TEMPLATES: [Helper; 6] = [
+    Helper {
+        name: "basic_multi",
+        deps: &[],
+        glsl: r#"float ng_basic_multi_NAME(int t, int octaves, float frequency, float lacunarity, float persistence, float remainder, vec3 p) {
+    p *= frequency;
+    float res = SOURCE(t, p);
+    float amplitude = 1.0;
+    for (int i = 1; i < octaves; i++) {
+        p *= lacunarity;
+        amplitude *= persistence;
+        res += SOURCE(t + i * 256, p) * amplitude * res;
+    }
+    if (remainder > 0.0) {
+        p *= lacunarity;
+        amplitude *= persistence;
+        res += SOURCE(t + octaves * 256, p) * amplitude * res * remainder;
+    }
+    return res * 0.5;
+}
+"#,
+        wgsl: r#"fn ng_basic_multi_NAME(t: i32, octaves: i32, frequency: f32, lacunarity: f32, persistence: f32, remainder: f32, p_in: vec3<f32>) -> f32 {
+    var p = p_in * frequency;
+    var res = SOURCE(t, p);
+    var amplitude = 1.0;
+    for (var i = 1; i < octaves; i++) {
+        p *= lacunarity;
+        amplitude *= persistence;
+        res += SOURCE(t + i * 256, p) * amplitude * res;
+    }
+    if (remainder > 0.0) {
+        p *= lacunarity;
+        amplitude *= persistence;
+        res += SOURCE(t + octaves * 256, p) * amplitude * res * remainder;
+    }
+    return res * 0.5;
+}
+"#,
+    },
+    Helper {
+        name: "billow",
+        deps: &[],
+        glsl: r#"float ng_billow_NAME(int t, int octaves, float frequency, float lacunarity, float persistence, float remainder, vec3 p) {
+    p *= frequency;
+    float res = 0.0;
+    float amplitude = 1.0;
+    for (int i = 0; i < octaves; i++) {
+        res += (abs(SOURCE(t + i * 256, p)) * 2.0 - 1.0) * amplitude;
+        amplitude *= persistence;
+        p *= lacunarity;
+    }
+    // The scale moves towards that of one more octave with its weight
+    float scale = 1.0 - amplitude;
+    if (remainder > 0.0) {
+        res += (abs(SOURCE(t + octaves * 256, p)) * 2.0 - 1.0) * amplitude * remainder;
+        scale += amplitude * (1.0 - persistence) * remainder;
+    }
+    return res / scale;
+}
+"#,
+        wgsl: r#"fn ng_billow_NAME(t: i32, octaves: i32, frequency: f32, lacunarity: f32, persistence: f32, remainder: f32, p_in: vec3<f32>) -> f32 {
+    var p = p_in * frequency;
+    var res = 0.0;
+    var amplitude = 1.0;
+    for (var i = 0; i < octaves; i++) {
+        res += (abs(SOURCE(t + i * 256, p)) * 2.0 - 1.0) * amplitude;
+        amplitude *= persistence;
+        p *= lacunarity;
+    }
+    // The scale moves towards that of one more octave with its weight
+    var scale = 1.0 - amplitude;
+    if (remainder > 0.0) {
+        res += (abs(SOURCE(t + octaves * 256, p)) * 2.0 - 1.0) * amplitude * remainder;
+        scale += amplitude * (1.0 - persistence) * remainder;
+    }
+    return res / scale;
+}
+"#,
+    },
+    Helper {
+        name: "fbm",
+        deps: &[],
+        glsl: r#"float ng_fbm_NAME(int t, int octaves, float frequency, float lacunarity, float persistence, float remainder, vec3 p) {
+    p *= frequency;
+    float res = 0.0;
+    float amplitude = 1.0;
+    for (int i = 0; i < octaves; i++) {
+        res += SOURCE(t + i * 256, p) * amplitude;
+        amplitude *= persistence;
+        p *= lacunarity;
+    }
+    // The scale moves towards that of one more octave with its weight
+    float scale = 1.0 - amplitude;
+    if (remainder > 0.0) {
+        res += SOURCE(t + octaves * 256, p) * amplitude * remainder;
+        scale += amplitude * (1.0 - persistence) * remainder;
+    }
+    return res / scale;
+}
+"#,
+        wgsl: r#"fn ng_fbm_NAME(t: i32, octaves: i32, frequency: f32, lacunarity: f32, persistence: f32, remainder: f32, p_in: vec3<f32>) -> f32 {
+    var p = p_in * frequency;
+    var res = 0.0;
+    var amplitude = 1.0;
+    for (var i = 0; i < octaves; i++) {
+        res += SOURCE(t + i * 256, p) * amplitude;
+        amplitude *= persistence;
+        p *= lacunarity;
+    }
+    // The scale moves towards that of one more octave with its weight
+    var scale = 1.0 - amplitude;
+    if (remainder > 0.0) {
+        res += SOURCE(t + octaves * 256, p) * amplitude * remainder;
+        scale += amplitude * (1.0 - persistence) * remainder;
+    }
+    return res / scale;
+}
+"#,
+    },
+    Helper {
+        name: "hybrid_multi",
+        deps: &[],
+        glsl: r#"float ng_hybrid_multi_NAME(int t, int octaves, float frequency, float lacunarity, float persistence, float remainder, vec3 p) {
+    p *= frequency;
+    float res = SOURCE(t, p) * persistence;
+    float weight = res;
+    float amplitude = 1.0;
+    for (int i = 1; i < octaves; i++) {
+        weight = max(weight, 1.0);
+        p *= lacunarity;
+        amplitude *= persistence;
+        float signal = SOURCE(t + i * 256, p) * amplitude;
+        res += weight * signal;
+        weight *= signal;
+    }
+    if (remainder > 0.0) {
+        weight = max(weight, 1.0);
+        p *= lacunarity;
+        amplitude *= persistence;
+        res += weight * SOURCE(t + octaves * 256, p) * amplitude * remainder;
+    }
+    return res * 3.0;
+}
+"#,
+        wgsl: r#"fn ng_hybrid_multi_NAME(t: i32, octaves: i32, frequency: f32, lacunarity: f32, persistence: f32, remainder: f32, p_in: vec3<f32>) -> f32 {
+    var p = p_in * frequency;
+    var res = SOURCE(t, p) * persistence;
+    var weight = res;
+    var amplitude = 1.0;
+    for (var i = 1; i < octaves; i++) {
+        weight = max(weight, 1.0);
+        p *= lacunarity;
+        amplitude *= persistence;
+        let signal = SOURCE(t + i * 256, p) * amplitude;
+        res += weight * signal;
+        weight *= signal;
+    }
+    if (remainder > 0.0) {
+        weight = max(weight, 1.0);
+        p *= lacunarity;
+        amplitude *= persistence;
+        res += weight * SOURCE(t + octaves * 256, p) * amplitude * remainder;
+    }
+    return res * 3.0;
+}
+"#,
+    },
+    Helper {
+        name: "ridged_multi",
+        deps: &[],
+        glsl: r#"float ng_ridged_multi_NAME(int t, int octaves, float frequency, float lacunarity, float persistence, float attenuation, float remainder, vec3 p) {
+    p *= frequency;
+    float res = 0.0;
+    float weight = 1.0;
+    float amplitude = 1.0;
+    for (int i = 0; i < octaves; i++) {
+        float signal = 1.0 - abs(SOURCE(t + i * 256, p));
+        signal *= signal * weight;
+        weight = clamp(signal / attenuation, 0.0, 1.0);
+        res += signal * amplitude;
+        amplitude *= persistence;
+        p *= lacunarity;
+    }
+    float scale = 2.0 - pow(0.5, float(octaves - 1));
+    if (remainder > 0.0) {
+        float signal = 1.0 - abs(SOURCE(t + octaves * 256, p));
+        res += signal * signal * weight * amplitude * remainder;
+        scale += pow(0.5, float(octaves)) * remainder;
+    }
+    return abs(res) * (2.0 / scale) - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_ridged_multi_NAME(t: i32, octaves: i32, frequency: f32, lacunarity: f32, persistence: f32, attenuation: f32, remainder: f32, p_in: vec3<f32>) -> f32 {
+    var p = p_in * frequency;
+    var res = 0.0;
+    var weight = 1.0;
+    var amplitude = 1.0;
+    for (var i = 0; i < octaves; i++) {
+        var signal = 1.0 - abs(SOURCE(t + i * 256, p));
+        signal *= signal * weight;
+        weight = clamp(signal / attenuation, 0.0, 1.0);
+        res += signal * amplitude;
+        amplitude *= persistence;
+        p *= lacunarity;
+    }
+    var scale = 2.0 - pow(0.5, f32(octaves - 1));
+    if (remainder > 0.0) {
+        let signal = 1.0 - abs(SOURCE(t + octaves * 256, p));
+        res += signal * signal * weight * amplitude * remainder;
+        scale += pow(0.5, f32(octaves)) * remainder;
+    }
+    return abs(res) * (2.0 / scale) - 1.0;
+}
+"#,
+    },
+    Helper {
+        name: "turbulence",
+        deps: &["fbm"],
+        glsl: r#"// Each axis is displaced by a fractal seeded one after the other, sampled at a fixed offset
+vec3 ng_turbulence_NAME(int t, int octaves, vec3 frequency, vec3 power, vec3 p) {
+    const float lacunarity = 2.0943951;
+    float x = ng_fbm_NAME(t, octaves, frequency.x, lacunarity, 0.5, 0.0, p + vec3(12414.0, 65124.0, 31337.0) / 65536.0);
+    float y = ng_fbm_NAME(t + 256, octaves, frequency.y, lacunarity, 0.5, 0.0, p + vec3(26519.0, 18128.0, 60943.0) / 65536.0);
+    float z = ng_fbm_NAME(t + 512, octaves, frequency.z, lacunarity, 0.5, 0.0, p + vec3(53820.0, 11213.0, 44845.0) / 65536.0);
+    return p + vec3(x, y, z) * power;
+}
+"#,
+        wgsl: r#"// Each axis is displaced by a fractal seeded one after the other, sampled at a fixed offset
+fn ng_turbulence_NAME(t: i32, octaves: i32, frequency: vec3<f32>, power: vec3<f32>, p: vec3<f32>) -> vec3<f32> {
+    let lacunarity = 2.0943951;
+    let x = ng_fbm_NAME(t, octaves, frequency.x, lacunarity, 0.5, 0.0, p + vec3(12414.0, 65124.0, 31337.0) / 65536.0);
+    let y = ng_fbm_NAME(t + 256, octaves, frequency.y, lacunarity, 0.5, 0.0, p + vec3(26519.0, 18128.0, 60943.0) / 65536.0);
+    let z = ng_fbm_NAME(t + 512, octaves, frequency.z, lacunarity, 0.5, 0.0, p + vec3(53820.0, 11213.0, 44845.0) / 65536.0);
+    return p + vec3(x, y, z) * power;
+}
+"#,
+    },
+];
+
+const HELPERS: [Helper; 40] = [
+    Helper {
+        name: "ng_blue_noise",
+        deps: &["ng_hash_signed"],
+        glsl: r#"float ng_blue_noise(uint seed, vec3 p) {
+    ivec3 c = ivec3(floor(p));
+    float neighbours = (ng_hash_signed(seed, c - ivec3(1, 0, 0), 0u)
+        + ng_hash_signed(seed, c + ivec3(1, 0, 0), 0u)
+        + ng_hash_signed(seed, c - ivec3(0, 1, 0), 0u)
+        + ng_hash_signed(seed, c + ivec3(0, 1, 0), 0u)
+        + ng_hash_signed(seed, c - ivec3(0, 0, 1), 0u)
+        + ng_hash_signed(seed, c + ivec3(0, 0, 1), 0u)) / 6.0;
+    return (ng_hash_signed(seed, c, 0u) - neighbours) * 0.5;
+}
+"#,
+        wgsl: r#"fn ng_blue_noise(seed: u32, p: vec3<f32>) -> f32 {
+    let c = vec3<i32>(floor(p));
+    let neighbours = (ng_hash_signed(seed, c - vec3(1, 0, 0), 0u)
+        + ng_hash_signed(seed, c + vec3(1, 0, 0), 0u)
+        + ng_hash_signed(seed, c - vec3(0, 1, 0), 0u)
+        + ng_hash_signed(seed, c + vec3(0, 1, 0), 0u)
+        + ng_hash_signed(seed, c - vec3(0, 0, 1), 0u)
+        + ng_hash_signed(seed, c + vec3(0, 0, 1), 0u)) / 6.0;
+    return (ng_hash_signed(seed, c, 0u) - neighbours) * 0.5;
+}
+"#,
+    },
+    Helper {
+        name: "ng_checkerboard",
+        deps: &[],
+        glsl: r#"float ng_checkerboard(int size, vec3 p) {
+    ivec3 c = ivec3(floor(p)) & size;
+    return (c.x ^ c.y ^ c.z) > 0 ? -1.0 : 1.0;
+}
+"#,
+        wgsl: r#"fn ng_checkerboard(size: i32, p: vec3<f32>) -> f32 {
+    let c = vec3<i32>(floor(p)) & vec3(size);
+    return select(1.0, -1.0, (c.x ^ c.y ^ c.z) > 0);
+}
+"#,
+    },
+    Helper {
+        name: "ng_cubic",
+        deps: &[],
+        glsl: r#"float ng_cubic(float n0, float n1, float n2, float n3, float alpha) {
+    float p = (n3 - n2) - (n0 - n1);
+    float q = (n0 - n1) - p;
+    float r = n2 - n0;
+    return p * alpha * alpha * alpha + q * alpha * alpha + r * alpha + n1;
+}
+"#,
+        wgsl: r#"fn ng_cubic(n0: f32, n1: f32, n2: f32, n3: f32, alpha: f32) -> f32 {
+    let p = (n3 - n2) - (n0 - n1);
+    let q = (n0 - n1) - p;
+    let r = n2 - n0;
+    return p * alpha * alpha * alpha + q * alpha * alpha + r * alpha + n1;
+}
+"#,
+    },
+    Helper {
+        name: "ng_cylinders",
+        deps: &[],
+        glsl: r#"float ng_cylinders(float frequency, vec3 p) {
+    float dist = fract(length(p.xy * frequency));
+    return 1.0 - min(dist, 1.0 - dist) * 4.0;
+}
+"#,
+        wgsl: r#"fn ng_cylinders(frequency: f32, p: vec3<f32>) -> f32 {
+    let dist = fract(length(p.xy * frequency));
+    return 1.0 - min(dist, 1.0 - dist) * 4.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_div",
+        deps: &[],
+        glsl: r#"float ng_div(float lhs, float rhs) {
+    return rhs != 0.0 ? lhs / rhs : 0.0;
+}
+"#,
+        wgsl: r#"fn ng_div(lhs: f32, rhs: f32) -> f32 {
+    if (rhs == 0.0) {
+        return 0.0;
+    }
+    return lhs / rhs;
+}
+"#,
+    },
+    Helper {
+        name: "ng_exp",
+        deps: &[],
+        glsl: r#"float ng_exp(float value) {
+    return sign(value) * (exp(abs(value)) - 1.0);
+}
+"#,
+        wgsl: r#"fn ng_exp(value: f32) -> f32 {
+    return sign(value) * (exp(abs(value)) - 1.0);
+}
+"#,
+    },
+    Helper {
+        name: "ng_exponent",
+        deps: &[],
+        glsl: r#"float ng_exponent(float value, float exponent) {
+    return pow(abs((value + 1.0) / 2.0), exponent) * 2.0 - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_exponent(value: f32, exponent: f32) -> f32 {
+    return pow(abs((value + 1.0) / 2.0), exponent) * 2.0 - 1.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_grad3",
+        deps: &[],
+        glsl: r#"// 12 edges repeated twice, then 8 corners
+vec3 ng_grad3(int h) {
+    h &= 31;
+    if (h >= 24) {
+        int c = h - 24;
+        return vec3((c & 1) == 0 ? 1.0 : -1.0, (c & 2) == 0 ? 1.0 : -1.0, (c & 4) == 0 ? 1.0 : -1.0) * 0.57735027;
+    }
+    int e = h % 12;
+    vec2 s = vec2((e & 1) == 0 ? 1.0 : -1.0, (e & 2) == 0 ? 1.0 : -1.0) * 0.70710678;
+    if (e < 4) return vec3(s, 0.0);
+    if (e < 8) return vec3(s.x, 0.0, s.y);
+    return vec3(0.0, s);
+}
+"#,
+        wgsl: r#"// 12 edges repeated twice, then 8 corners
+fn ng_grad3(index: i32) -> vec3<f32> {
+    let h = index & 31;
+    if (h >= 24) {
+        let c = h - 24;
+        return vec3(select(-1.0, 1.0, (c & 1) == 0), select(-1.0, 1.0, (c & 2) == 0), select(-1.0, 1.0, (c & 4) == 0)) * 0.57735027;
+    }
+    let e = h % 12;
+    let s = vec2(select(-1.0, 1.0, (e & 1) == 0), select(-1.0, 1.0, (e & 2) == 0)) * 0.70710678;
+    if (e < 4) {
+        return vec3(s, 0.0);
+    }
+    if (e < 8) {
+        return vec3(s.x, 0.0, s.y);
+    }
+    return vec3(0.0, s);
+}
+"#,
+    },
+    Helper {
+        name: "ng_hash",
+        deps: &["ng_hash_coord"],
+        glsl: r#"uint ng_hash(uint seed, ivec3 cell, uint salt) {
+    uint res = (seed * 0x9e3779b9u) ^ (salt * 0x85ebca6bu);
+    res = ng_hash_coord(res, cell.x, 0x27d4eb2du);
+    res = ng_hash_coord(res, cell.y, 0x165667b1u);
+    res = ng_hash_coord(res, cell.z, 0xc2b2ae35u);
+    res ^= res >> 16;
+    res *= 0x85ebca6bu;
+    res ^= res >> 13;
+    res *= 0xc2b2ae35u;
+    return res ^ (res >> 16);
+}
+"#,
+        wgsl: r#"fn ng_hash(seed: u32, cell: vec3<i32>, salt: u32) -> u32 {
+    var res = (seed * 0x9e3779b9u) ^ (salt * 0x85ebca6bu);
+    res = ng_hash_coord(res, cell.x, 0x27d4eb2du);
+    res = ng_hash_coord(res, cell.y, 0x165667b1u);
+    res = ng_hash_coord(res, cell.z, 0xc2b2ae35u);
+    res ^= res >> 16u;
+    res *= 0x85ebca6bu;
+    res ^= res >> 13u;
+    res *= 0xc2b2ae35u;
+    return res ^ (res >> 16u);
+}
+"#,
+    },
+    Helper {
+        name: "ng_hash3",
+        deps: &[],
+        glsl: r#"int ng_hash3(int t, ivec3 c) {
+    return PERM[t + (PERM[t + (PERM[t + (c.x & 255)] ^ (c.y & 255))] ^ (c.z & 255))];
+}
+"#,
+        wgsl: r#"fn ng_hash3(t: i32, c: vec3<i32>) -> i32 {
+    return PERM[t + (PERM[t + (PERM[t + (c.x & 255)] ^ (c.y & 255))] ^ (c.z & 255))];
+}
+"#,
+    },
+    Helper {
+        name: "ng_hash_coord",
+        deps: &["ng_mul_hi"],
+        glsl: r#"// Mixes in the product of the 64-bit sign extension of a coordinate and a prime, folded to 32 bits
+uint ng_hash_coord(uint res, int coord, uint prime) {
+    uint low = uint(coord) * prime;
+    uint high = ng_mul_hi(uint(coord), prime) - (coord < 0 ? prime : 0u);
+    res ^= low ^ high;
+    return ((res << 13) | (res >> 19)) * 0x5bd1e995u;
+}
+"#,
+        wgsl: r#"// Mixes in the product of the 64-bit sign extension of a coordinate and a prime, folded to 32 bits
+fn ng_hash_coord(hash: u32, coord: i32, prime: u32) -> u32 {
+    let low = u32(coord) * prime;
+    let high = ng_mul_hi(u32(coord), prime) - select(0u, prime, coord < 0);
+    let res = hash ^ low ^ high;
+    return ((res << 13u) | (res >> 19u)) * 0x5bd1e995u;
+}
+"#,
+    },
+    Helper {
+        name: "ng_hash_signed",
+        deps: &["ng_hash"],
+        glsl: r#"float ng_hash_signed(uint seed, ivec3 cell, uint salt) {
+    return float(ng_hash(seed, cell, salt)) / 4294967295.0 * 2.0 - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_hash_signed(seed: u32, cell: vec3<i32>, salt: u32) -> f32 {
+    return f32(ng_hash(seed, cell, salt)) / 4294967295.0 * 2.0 - 1.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_jitter",
+        deps: &["ng_hash_signed"],
+        glsl: r#"vec3 ng_jitter(uint seed, float amount, vec3 p) {
+    ivec3 c = ivec3(floor(p));
+    return p + vec3(ng_hash_signed(seed, c, 0u), ng_hash_signed(seed, c, 1u), ng_hash_signed(seed, c, 2u)) * amount;
+}
+"#,
+        wgsl: r#"fn ng_jitter(seed: u32, amount: f32, p: vec3<f32>) -> vec3<f32> {
+    let c = vec3<i32>(floor(p));
+    return p + vec3(ng_hash_signed(seed, c, 0u), ng_hash_signed(seed, c, 1u), ng_hash_signed(seed, c, 2u)) * amount;
+}
+"#,
+    },
+    Helper {
+        name: "ng_log",
+        deps: &[],
+        glsl: r#"float ng_log(float value) {
+    return sign(value) * log(1.0 + abs(value));
+}
+"#,
+        wgsl: r#"fn ng_log(value: f32) -> f32 {
+    return sign(value) * log(1.0 + abs(value));
+}
+"#,
+    },
+    Helper {
+        name: "ng_map_cubic",
+        deps: &[],
+        glsl: r#"float ng_map_cubic(float x) {
+    x = clamp(x, 0.0, 1.0);
+    return x * x * (3.0 - x * 2.0);
+}
+"#,
+        wgsl: r#"fn ng_map_cubic(value: f32) -> f32 {
+    let x = clamp(value, 0.0, 1.0);
+    return x * x * (3.0 - x * 2.0);
+}
+"#,
+    },
+    Helper {
+        name: "ng_mul_hi",
+        deps: &[],
+        glsl: r#"// The high 32 bits of the 64-bit product, from 16-bit halves
+uint ng_mul_hi(uint a, uint b) {
+    uint a_lo = a & 0xffffu;
+    uint a_hi = a >> 16;
+    uint b_lo = b & 0xffffu;
+    uint b_hi = b >> 16;
+    uint cross = ((a_lo * b_lo) >> 16) + ((a_hi * b_lo) & 0xffffu) + a_lo * b_hi;
+    return a_hi * b_hi + ((a_hi * b_lo) >> 16) + (cross >> 16);
+}
+"#,
+        wgsl: r#"// The high 32 bits of the 64-bit product, from 16-bit halves
+fn ng_mul_hi(a: u32, b: u32) -> u32 {
+    let a_lo = a & 0xffffu;
+    let a_hi = a >> 16u;
+    let b_lo = b & 0xffffu;
+    let b_hi = b >> 16u;
+    let cross = ((a_lo * b_lo) >> 16u) + ((a_hi * b_lo) & 0xffffu) + a_lo * b_hi;
+    return a_hi * b_hi + ((a_hi * b_lo) >> 16u) + (cross >> 16u);
+}
+"#,
+    },
+    Helper {
+        name: "ng_perlin",
+        deps: &["ng_hash3", "ng_perlin_grad", "ng_quintic"],
+        glsl: r#"float ng_perlin(int t, vec3 p) {
+    vec3 f = floor(p);
+    ivec3 c = ivec3(f);
+    vec3 d = p - f;
+    float g000 = ng_perlin_grad(ng_hash3(t, c), d);
+    float g100 = ng_perlin_grad(ng_hash3(t, c + ivec3(1, 0, 0)), d - vec3(1.0, 0.0, 0.0));
+    float g010 = ng_perlin_grad(ng_hash3(t, c + ivec3(0, 1, 0)), d - vec3(0.0, 1.0, 0.0));
+    float g110 = ng_perlin_grad(ng_hash3(t, c + ivec3(1, 1, 0)), d - vec3(1.0, 1.0, 0.0));
+    float g001 = ng_perlin_grad(ng_hash3(t, c + ivec3(0, 0, 1)), d - vec3(0.0, 0.0, 1.0));
+    float g101 = ng_perlin_grad(ng_hash3(t, c + ivec3(1, 0, 1)), d - vec3(1.0, 0.0, 1.0));
+    float g011 = ng_perlin_grad(ng_hash3(t, c + ivec3(0, 1, 1)), d - vec3(0.0, 1.0, 1.0));
+    float g111 = ng_perlin_grad(ng_hash3(t, c + ivec3(1, 1, 1)), d - vec3(1.0, 1.0, 1.0));
+    vec3 w = ng_quintic(d);
+    float k1 = g100 - g000;
+    float k2 = g010 - g000;
+    float k3 = g001 - g000;
+    float k4 = g000 + g110 - g100 - g010;
+    float k5 = g000 + g101 - g100 - g001;
+    float k6 = g000 + g011 - g010 - g001;
+    float k7 = g100 + g010 + g001 + g111 - g000 - g110 - g101 - g011;
+    float res = g000 + k1 * w.x + k2 * w.y + k3 * w.z + k4 * w.x * w.y + k5 * w.x * w.z
+        + k6 * w.y * w.z + k7 * w.x * w.y * w.z;
+    return clamp(res * 1.1547005, -1.0, 1.0);
+}
+"#,
+        wgsl: r#"fn ng_perlin(t: i32, p: vec3<f32>) -> f32 {
+    let f = floor(p);
+    let c = vec3<i32>(f);
+    let d = p - f;
+    let g000 = ng_perlin_grad(ng_hash3(t, c), d);
+    let g100 = ng_perlin_grad(ng_hash3(t, c + vec3(1, 0, 0)), d - vec3(1.0, 0.0, 0.0));
+    let g010 = ng_perlin_grad(ng_hash3(t, c + vec3(0, 1, 0)), d - vec3(0.0, 1.0, 0.0));
+    let g110 = ng_perlin_grad(ng_hash3(t, c + vec3(1, 1, 0)), d - vec3(1.0, 1.0, 0.0));
+    let g001 = ng_perlin_grad(ng_hash3(t, c + vec3(0, 0, 1)), d - vec3(0.0, 0.0, 1.0));
+    let g101 = ng_perlin_grad(ng_hash3(t, c + vec3(1, 0, 1)), d - vec3(1.0, 0.0, 1.0));
+    let g011 = ng_perlin_grad(ng_hash3(t, c + vec3(0, 1, 1)), d - vec3(0.0, 1.0, 1.0));
+    let g111 = ng_perlin_grad(ng_hash3(t, c + vec3(1, 1, 1)), d - vec3(1.0, 1.0, 1.0));
+    let w = ng_quintic(d);
+    let k1 = g100 - g000;
+    let k2 = g010 - g000;
+    let k3 = g001 - g000;
+    let k4 = g000 + g110 - g100 - g010;
+    let k5 = g000 + g101 - g100 - g001;
+    let k6 = g000 + g011 - g010 - g001;
+    let k7 = g100 + g010 + g001 + g111 - g000 - g110 - g101 - g011;
+    let res = g000 + k1 * w.x + k2 * w.y + k3 * w.z + k4 * w.x * w.y + k5 * w.x * w.z
+        + k6 * w.y * w.z + k7 * w.x * w.y * w.z;
+    return clamp(res * 1.1547005, -1.0, 1.0);
+}
+"#,
+    },
+    Helper {
+        name: "ng_perlin_grad",
+        deps: &[],
+        glsl: r#"float ng_perlin_grad(int h, vec3 d) {
+    h &= 15;
+    if (h >= 12) h = h == 12 ? 0 : h == 13 ? 1 : h == 14 ? 9 : 11;
+    vec2 s = vec2((h & 1) == 0 ? 1.0 : -1.0, (h & 2) == 0 ? 1.0 : -1.0);
+    if (h < 4) return dot(s, d.xy);
+    if (h < 8) return dot(s, d.xz);
+    return dot(s, d.yz);
+}
+"#,
+        wgsl: r#"fn ng_perlin_grad(index: i32, d: vec3<f32>) -> f32 {
+    var h = index & 15;
+    if (h >= 12) {
+        var edges = array<i32, 4>(0, 1, 9, 11);
+        h = edges[h - 12];
+    }
+    let s = vec2(select(-1.0, 1.0, (h & 1) == 0), select(-1.0, 1.0, (h & 2) == 0));
+    if (h < 4) {
+        return dot(s, d.xy);
+    }
+    if (h < 8) {
+        return dot(s, d.xz);
+    }
+    return dot(s, d.yz);
+}
+"#,
+    },
+    Helper {
+        name: "ng_plateau",
+        deps: &[],
+        glsl: r#"float ng_plateau(float value, float level, float softness) {
+    if (softness <= 0.0) return min(value, level);
+    float blend = clamp(0.5 + 0.5 * (level - value) / softness, 0.0, 1.0);
+    return level + (value - level) * blend - softness * blend * (1.0 - blend);
+}
+"#,
+        wgsl: r#"fn ng_plateau(value: f32, level: f32, softness: f32) -> f32 {
+    if (softness <= 0.0) {
+        return min(value, level);
+    }
+    let blend = clamp(0.5 + 0.5 * (level - value) / softness, 0.0, 1.0);
+    return level + (value - level) * blend - softness * blend * (1.0 - blend);
+}
+"#,
+    },
+    Helper {
+        name: "ng_pow_signal",
+        deps: &[],
+        glsl: r#"float ng_pow_signal(float base, float exponent) {
+    if (base == 0.0) return exponent == 0.0 ? 1.0 : 0.0;
+    float res = pow(abs(base), exponent);
+    if (isinf(res) || isnan(res)) return 0.0;
+    return base < 0.0 ? -res : res;
+}
+"#,
+        wgsl: r#"fn ng_pow_signal(base: f32, exponent: f32) -> f32 {
+    if (base == 0.0) {
+        return select(0.0, 1.0, exponent == 0.0);
+    }
+    let res = pow(abs(base), exponent);
+    // Infinite and NaN results have every exponent bit set
+    if ((bitcast<u32>(res) & 0x7f800000u) == 0x7f800000u) {
+        return 0.0;
+    }
+    return select(res, -res, base < 0.0);
+}
+"#,
+    },
+    Helper {
+        name: "ng_quantize",
+        deps: &[],
+        glsl: r#"float ng_quantize(int levels, float value) {
+    if (levels < 2) return 0.0;
+    float steps = float(levels - 1);
+    float level = (value + 1.0) * 0.5 * steps;
+    return clamp(floor(level + 0.5), 0.0, steps) / steps * 2.0 - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_quantize(levels: i32, value: f32) -> f32 {
+    if (levels < 2) {
+        return 0.0;
+    }
+    let steps = f32(levels - 1);
+    let level = (value + 1.0) * 0.5 * steps;
+    return clamp(floor(level + 0.5), 0.0, steps) / steps * 2.0 - 1.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_quintic",
+        deps: &[],
+        glsl: r#"vec3 ng_quintic(vec3 x) {
+    x = clamp(x, 0.0, 1.0);
+    return x * x * x * (x * (x * 6.0 - 15.0) + 10.0);
+}
+"#,
+        wgsl: r#"fn ng_quintic(value: vec3<f32>) -> vec3<f32> {
+    let x = clamp(value, vec3(0.0), vec3(1.0));
+    return x * x * x * (x * (x * 6.0 - 15.0) + 10.0);
+}
+"#,
+    },
+    Helper {
+        name: "ng_rebase",
+        deps: &[],
+        glsl: r#"vec3 ng_rebase(float period, vec3 p) {
+    if (period <= 0.0) return p;
+    vec3 periods = p / period;
+    return p - sign(periods) * floor(abs(periods) + 0.5) * period;
+}
+"#,
+        wgsl: r#"fn ng_rebase(period: f32, p: vec3<f32>) -> vec3<f32> {
+    if (period <= 0.0) {
+        return p;
+    }
+    let periods = p / period;
+    return p - sign(periods) * floor(abs(periods) + 0.5) * period;
+}
+"#,
+    },
+    Helper {
+        name: "ng_repeat",
+        deps: &["ng_repeat_coord"],
+        glsl: r#"vec3 ng_repeat(vec3 periods, bool mirror, vec3 p) {
+    return vec3(ng_repeat_coord(p.x, periods.x, mirror), ng_repeat_coord(p.y, periods.y, mirror), ng_repeat_coord(p.z, periods.z, mirror));
+}
+"#,
+        wgsl: r#"fn ng_repeat(periods: vec3<f32>, mirror: bool, p: vec3<f32>) -> vec3<f32> {
+    return vec3(ng_repeat_coord(p.x, periods.x, mirror), ng_repeat_coord(p.y, periods.y, mirror), ng_repeat_coord(p.z, periods.z, mirror));
+}
+"#,
+    },
+    Helper {
+        name: "ng_repeat_coord",
+        deps: &[],
+        glsl: r#"float ng_repeat_coord(float coord, float period, bool mirror) {
+    if (period <= 0.0) return coord;
+    if (!mirror) return mod(coord, period);
+    coord = mod(coord, 2.0 * period);
+    return coord > period ? 2.0 * period - coord : coord;
+}
+"#,
+        wgsl: r#"// Unlike GLSL `mod`, `%` keeps the sign of the dividend, so remainders are floored by hand
+fn ng_repeat_coord(coord: f32, period: f32, mirror: bool) -> f32 {
+    if (period <= 0.0) {
+        return coord;
+    }
+    if (!mirror) {
+        return coord - period * floor(coord / period);
+    }
+    let wrapped = coord - 2.0 * period * floor(coord / (2.0 * period));
+    return select(wrapped, 2.0 * period - wrapped, wrapped > period);
+}
+"#,
+    },
+    Helper {
+        name: "ng_ridge",
+        deps: &[],
+        glsl: r#"float ng_ridge(float value, float sharpness, float offset) {
+    return pow(clamp(1.0 - abs(value - offset), 0.0, 1.0), max(sharpness, 0.0)) * 2.0 - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_ridge(value: f32, sharpness: f32, offset: f32) -> f32 {
+    return pow(clamp(1.0 - abs(value - offset), 0.0, 1.0), max(sharpness, 0.0)) * 2.0 - 1.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_rotate",
+        deps: &[],
+        glsl: r#"vec3 ng_rotate(vec3 angles, vec3 p) {
+    vec3 c = cos(radians(angles));
+    vec3 s = sin(radians(angles));
+    return vec3(
+        dot(vec3(s.x * s.y * s.z + c.y * c.z, c.x * s.z, s.y * c.z - c.y * s.x * s.z), p),
+        dot(vec3(s.y * s.x * c.z - c.y * s.z, c.x * c.z, -c.y * s.x * c.z - s.y * s.z), p),
+        dot(vec3(-s.y * c.x, s.x, c.y * c.x), p));
+}
+"#,
+        wgsl: r#"fn ng_rotate(angles: vec3<f32>, p: vec3<f32>) -> vec3<f32> {
+    let c = cos(radians(angles));
+    let s = sin(radians(angles));
+    return vec3(
+        dot(vec3(s.x * s.y * s.z + c.y * c.z, c.x * s.z, s.y * c.z - c.y * s.x * s.z), p),
+        dot(vec3(s.y * s.x * c.z - c.y * s.z, c.x * c.z, -c.y * s.x * c.z - s.y * s.z), p),
+        dot(vec3(-s.y * c.x, s.x, c.y * c.x), p));
+}
+"#,
+    },
+    Helper {
+        name: "ng_select",
+        deps: &["ng_map_cubic"],
+        glsl: r#"float ng_select(float a, float b, float control, float lower, float upper, float falloff) {
+    if (falloff > 0.0) {
+        if (control < lower - falloff) return a;
+        if (control < lower + falloff) {
+            float curve = ng_map_cubic((control - (lower - falloff)) / ((lower + falloff) - (lower - falloff)));
+            return mix(a, b, curve);
+        }
+        if (control < upper - falloff) return b;
+        if (control < upper + falloff) {
+            float curve = ng_map_cubic((control - (upper - falloff)) / ((upper + falloff) - (upper - falloff)));
+            return mix(b, a, curve);
+        }
+        return a;
+    }
+    return control < lower || control > upper ? a : b;
+}
+"#,
+        wgsl: r#"fn ng_select(a: f32, b: f32, control: f32, lower: f32, upper: f32, falloff: f32) -> f32 {
+    if (falloff > 0.0) {
+        if (control < lower - falloff) {
+            return a;
+        }
+        if (control < lower + falloff) {
+            let curve = ng_map_cubic((control - (lower - falloff)) / ((lower + falloff) - (lower - falloff)));
+            return mix(a, b, curve);
+        }
+        if (control < upper - falloff) {
+            return b;
+        }
+        if (control < upper + falloff) {
+            let curve = ng_map_cubic((control - (upper - falloff)) / ((upper + falloff) - (upper - falloff)));
+            return mix(b, a, curve);
+        }
+        return a;
+    }
+    return select(b, a, control < lower || control > upper);
+}
+"#,
+    },
+    Helper {
+        name: "ng_simplex",
+        deps: &["ng_hash3", "ng_simplex_surflet"],
+        glsl: r#"float ng_simplex(int t, vec3 p) {
+    const float F3 = 1.0 / 3.0;
+    const float G3 = 1.0 / 6.0;
+    ivec3 cell = ivec3(floor(p + (p.x + p.y + p.z) * F3));
+    vec3 d = p - (vec3(cell) - float(cell.x + cell.y + cell.z) * G3);
+    ivec3 o1;
+    ivec3 o2;
+    if (d.x >= d.y) {
+        if (d.y >= d.z) {
+            o1 = ivec3(1, 0, 0);
+            o2 = ivec3(1, 1, 0);
+        } else if (d.x >= d.z) {
+            o1 = ivec3(1, 0, 0);
+            o2 = ivec3(1, 0, 1);
+        } else {
+            o1 = ivec3(0, 0, 1);
+            o2 = ivec3(1, 0, 1);
+        }
+    } else if (d.y < d.z) {
+        o1 = ivec3(0, 0, 1);
+        o2 = ivec3(0, 1, 1);
+    } else if (d.x < d.z) {
+        o1 = ivec3(0, 1, 0);
+        o2 = ivec3(0, 1, 1);
+    } else {
+        o1 = ivec3(0, 1, 0);
+        o2 = ivec3(1, 1, 0);
+    }
+    float res = ng_simplex_surflet(ng_hash3(t, cell), d)
+        + ng_simplex_surflet(ng_hash3(t, cell + o1), d - vec3(o1) + G3)
+        + ng_simplex_surflet(ng_hash3(t, cell + o2), d - vec3(o2) + 2.0 * G3)
+        + ng_simplex_surflet(ng_hash3(t, cell + ivec3(1)), d - 1.0 + 3.0 * G3);
+    return res * 28.0;
+}
+"#,
+        wgsl: r#"fn ng_simplex(t: i32, p: vec3<f32>) -> f32 {
+    let F3 = 1.0 / 3.0;
+    let G3 = 1.0 / 6.0;
+    let cell = vec3<i32>(floor(p + (p.x + p.y + p.z) * F3));
+    let d = p - (vec3<f32>(cell) - f32(cell.x + cell.y + cell.z) * G3);
+    var o1: vec3<i32>;
+    var o2: vec3<i32>;
+    if (d.x >= d.y) {
+        if (d.y >= d.z) {
+            o1 = vec3(1, 0, 0);
+            o2 = vec3(1, 1, 0);
+        } else if (d.x >= d.z) {
+            o1 = vec3(1, 0, 0);
+            o2 = vec3(1, 0, 1);
+        } else {
+            o1 = vec3(0, 0, 1);
+            o2 = vec3(1, 0, 1);
+        }
+    } else if (d.y < d.z) {
+        o1 = vec3(0, 0, 1);
+        o2 = vec3(0, 1, 1);
+    } else if (d.x < d.z) {
+        o1 = vec3(0, 1, 0);
+        o2 = vec3(0, 1, 1);
+    } else {
+        o1 = vec3(0, 1, 0);
+        o2 = vec3(1, 1, 0);
+    }
+    let res = ng_simplex_surflet(ng_hash3(t, cell), d)
+        + ng_simplex_surflet(ng_hash3(t, cell + o1), d - vec3<f32>(o1) + G3)
+        + ng_simplex_surflet(ng_hash3(t, cell + o2), d - vec3<f32>(o2) + 2.0 * G3)
+        + ng_simplex_surflet(ng_hash3(t, cell + vec3(1)), d - 1.0 + 3.0 * G3);
+    return res * 28.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_simplex_surflet",
+        deps: &["ng_grad3"],
+        glsl: r#"float ng_simplex_surflet(int h, vec3 d) {
+    float t = 0.5 - dot(d, d);
+    if (t <= 0.0) return 0.0;
+    t *= t;
+    return t * t * dot(ng_grad3(h), d);
+}
+"#,
+        wgsl: r#"fn ng_simplex_surflet(h: i32, d: vec3<f32>) -> f32 {
+    var t = 0.5 - dot(d, d);
+    if (t <= 0.0) {
+        return 0.0;
+    }
+    t *= t;
+    return t * t * dot(ng_grad3(h), d);
+}
+"#,
+    },
+    Helper {
+        name: "ng_threshold_smooth",
+        deps: &[],
+        glsl: r#"float ng_threshold_smooth(float value, float lower, float upper) {
+    if (upper - lower <= 2.220446e-16) return value >= upper ? 1.0 : -1.0;
+    float t = clamp((value - lower) / (upper - lower), 0.0, 1.0);
+    return t * t * (3.0 - 2.0 * t) * 2.0 - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_threshold_smooth(value: f32, lower: f32, upper: f32) -> f32 {
+    if (upper - lower <= 2.220446e-16) {
+        return select(-1.0, 1.0, value >= upper);
+    }
+    let t = clamp((value - lower) / (upper - lower), 0.0, 1.0);
+    return t * t * (3.0 - 2.0 * t) * 2.0 - 1.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_value",
+        deps: &["ng_hash3", "ng_quintic"],
+        glsl: r#"float ng_value(int t, vec3 p) {
+    vec3 f = floor(p);
+    ivec3 c = ivec3(f);
+    vec3 w = ng_quintic(p - f);
+    float d00 = mix(float(ng_hash3(t, c)), float(ng_hash3(t, c + ivec3(1, 0, 0))), w.x);
+    float d01 = mix(float(ng_hash3(t, c + ivec3(0, 0, 1))), float(ng_hash3(t, c + ivec3(1, 0, 1))), w.x);
+    float d10 = mix(float(ng_hash3(t, c + ivec3(0, 1, 0))), float(ng_hash3(t, c + ivec3(1, 1, 0))), w.x);
+    float d11 = mix(float(ng_hash3(t, c + ivec3(0, 1, 1))), float(ng_hash3(t, c + ivec3(1, 1, 1))), w.x);
+    return mix(mix(d00, d10, w.y), mix(d01, d11, w.y), w.z) / 255.0 * 2.0 - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_value(t: i32, p: vec3<f32>) -> f32 {
+    let f = floor(p);
+    let c = vec3<i32>(f);
+    let w = ng_quintic(p - f);
+    let d00 = mix(f32(ng_hash3(t, c)), f32(ng_hash3(t, c + vec3(1, 0, 0))), w.x);
+    let d01 = mix(f32(ng_hash3(t, c + vec3(0, 0, 1))), f32(ng_hash3(t, c + vec3(1, 0, 1))), w.x);
+    let d10 = mix(f32(ng_hash3(t, c + vec3(0, 1, 0))), f32(ng_hash3(t, c + vec3(1, 1, 0))), w.x);
+    let d11 = mix(f32(ng_hash3(t, c + vec3(0, 1, 1))), f32(ng_hash3(t, c + vec3(1, 1, 1))), w.x);
+    return mix(mix(d00, d10, w.y), mix(d01, d11, w.y), w.z) / 255.0 * 2.0 - 1.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_worley",
+        deps: &[
+            "ng_hash3",
+            "ng_worley_distance",
+            "ng_worley_point",
+            "ng_worley_test",
+        ],
+        glsl: r#"float ng_worley(int t, float frequency, int distance_fn, bool value, vec3 p) {
+    p *= frequency;
+    vec3 cell = floor(p);
+    vec3 frac = p - cell;
+    ivec3 near = ivec3(cell) + ivec3(greaterThan(frac, vec3(0.5)));
+    ivec3 far = ivec3(cell) + ivec3(lessThanEqual(frac, vec3(0.5)));
+    ivec3 seed_cell = near;
+    float dist = ng_worley_distance(distance_fn, p, ng_worley_point(ng_hash3(t, near)) + vec3(near));
+    vec3 range = (0.5 - frac) * (0.5 - frac);
+    if (range.x < dist) ng_worley_test(t, distance_fn, p, ivec3(far.x, near.y, near.z), dist, seed_cell);
+    if (range.y < dist) ng_worley_test(t, distance_fn, p, ivec3(near.x, far.y, near.z), dist, seed_cell);
+    if (range.z < dist) ng_worley_test(t, distance_fn, p, ivec3(near.x, near.y, far.z), dist, seed_cell);
+    if (range.x < dist && range.y < dist) ng_worley_test(t, distance_fn, p, ivec3(far.x, far.y, near.z), dist, seed_cell);
+    if (range.x < dist && range.z < dist) ng_worley_test(t, distance_fn, p, ivec3(far.x, near.y, far.z), dist, seed_cell);
+    if (range.y < dist && range.z < dist) ng_worley_test(t, distance_fn, p, ivec3(near.x, far.y, far.z), dist, seed_cell);
+    if (range.x < dist && range.y < dist && range.z < dist) ng_worley_test(t, distance_fn, p, far, dist, seed_cell);
+    return (value ? float(ng_hash3(t, seed_cell)) / 255.0 : dist) * 2.0 - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_worley(t: i32, frequency: f32, distance_fn: i32, value: bool, p_in: vec3<f32>) -> f32 {
+    let p = p_in * frequency;
+    let cell = floor(p);
+    let frac = p - cell;
+    let near = vec3<i32>(cell) + select(vec3(0), vec3(1), frac > vec3(0.5));
+    let far = vec3<i32>(cell) + select(vec3(0), vec3(1), frac <= vec3(0.5));
+    var seed_cell = near;
+    var dist = ng_worley_distance(distance_fn, p, ng_worley_point(ng_hash3(t, near)) + vec3<f32>(near));
+    let range = (0.5 - frac) * (0.5 - frac);
+    if (range.x < dist) {
+        ng_worley_test(t, distance_fn, p, vec3(far.x, near.y, near.z), &dist, &seed_cell);
+    }
+    if (range.y < dist) {
+        ng_worley_test(t, distance_fn, p, vec3(near.x, far.y, near.z), &dist, &seed_cell);
+    }
+    if (range.z < dist) {
+        ng_worley_test(t, distance_fn, p, vec3(near.x, near.y, far.z), &dist, &seed_cell);
+    }
+    if (range.x < dist && range.y < dist) {
+        ng_worley_test(t, distance_fn, p, vec3(far.x, far.y, near.z), &dist, &seed_cell);
+    }
+    if (range.x < dist && range.z < dist) {
+        ng_worley_test(t, distance_fn, p, vec3(far.x, near.y, far.z), &dist, &seed_cell);
+    }
+    if (range.y < dist && range.z < dist) {
+        ng_worley_test(t, distance_fn, p, vec3(near.x, far.y, far.z), &dist, &seed_cell);
+    }
+    if (range.x < dist && range.y < dist && range.z < dist) {
+        ng_worley_test(t, distance_fn, p, far, &dist, &seed_cell);
+    }
+    return select(dist, f32(ng_hash3(t, seed_cell)) / 255.0, value) * 2.0 - 1.0;
+}
+"#,
+    },
+    Helper {
+        name: "ng_worley_default",
+        deps: &["ng_worley"],
+        glsl: r#"float ng_worley_default(int t, vec3 p) {
+    return ng_worley(t, 1.0, 1, true, p);
+}
+"#,
+        wgsl: r#"fn ng_worley_default(t: i32, p: vec3<f32>) -> f32 {
+    return ng_worley(t, 1.0, 1, true, p);
+}
+"#,
+    },
+    Helper {
+        name: "ng_worley_distance",
+        deps: &[],
+        glsl: r#"// Chebyshev, Euclidean, Euclidean squared or Manhattan distance
+float ng_worley_distance(int distance_fn, vec3 a, vec3 b) {
+    vec3 d = a - b;
+    if (distance_fn == 0) return max(max(abs(d.x), abs(d.y)), abs(d.z));
+    if (distance_fn == 1) return length(d);
+    if (distance_fn == 2) return dot(d, d);
+    return abs(d.x) + abs(d.y) + abs(d.z);
+}
+"#,
+        wgsl: r#"// Chebyshev, Euclidean, Euclidean squared or Manhattan distance
+fn ng_worley_distance(distance_fn: i32, a: vec3<f32>, b: vec3<f32>) -> f32 {
+    let d = a - b;
+    if (distance_fn == 0) {
+        return max(max(abs(d.x), abs(d.y)), abs(d.z));
+    }
+    if (distance_fn == 1) {
+        return length(d);
+    }
+    if (distance_fn == 2) {
+        return dot(d, d);
+    }
+    return abs(d.x) + abs(d.y) + abs(d.z);
+}
+"#,
+    },
+    Helper {
+        name: "ng_worley_point",
+        deps: &[],
+        glsl: r#"vec3 ng_worley_point(int index) {
+    float len = float((index & 224) >> 5) * 0.5 / 7.0;
+    float diag = len * 0.70710678;
+    int i = index % 18;
+    if (i < 12) {
+        vec2 s = vec2((i & 2) == 0 ? diag : -diag, (i & 1) == 0 ? diag : -diag);
+        if (i < 4) return vec3(s, 0.0);
+        if (i < 8) return vec3(s.x, 0.0, s.y);
+        return vec3(0.0, s);
+    }
+    return vec3(equal(ivec3(i % 3), ivec3(0, 1, 2))) * (i < 15 ? len : -len);
+}
+"#,
+        wgsl: r#"fn ng_worley_point(index: i32) -> vec3<f32> {
+    let len = f32((index & 224) >> 5u) * 0.5 / 7.0;
+    let diag = len * 0.70710678;
+    let i = index % 18;
+    if (i < 12) {
+        let s = vec2(select(-diag, diag, (i & 2) == 0), select(-diag, diag, (i & 1) == 0));
+        if (i < 4) {
+            return vec3(s, 0.0);
+        }
+        if (i < 8) {
+            return vec3(s.x, 0.0, s.y);
+        }
+        return vec3(0.0, s);
+    }
+    return select(vec3(0.0), vec3(1.0), vec3(i % 3) == vec3(0, 1, 2)) * select(-len, len, i < 15);
+}
+"#,
+    },
+    Helper {
+        name: "ng_worley_test",
+        deps: &["ng_hash3", "ng_worley_distance", "ng_worley_point"],
+        glsl: r#"void ng_worley_test(int t, int distance_fn, vec3 p, ivec3 cell, inout float dist, inout ivec3 seed_cell) {
+    float cell_dist = ng_worley_distance(distance_fn, p, ng_worley_point(ng_hash3(t, cell)) + vec3(cell));
+    if (cell_dist < dist) {
+        dist = cell_dist;
+        seed_cell = cell;
+    }
+}
+"#,
+        wgsl: r#"fn ng_worley_test(t: i32, distance_fn: i32, p: vec3<f32>, cell: vec3<i32>, dist: ptr<function, f32>, seed_cell: ptr<function, vec3<i32>>) {
+    let cell_dist = ng_worley_distance(distance_fn, p, ng_worley_point(ng_hash3(t, cell)) + vec3<f32>(cell));
+    if (cell_dist < *dist) {
+        *dist = cell_dist;
+        *seed_cell = cell;
+    }
+}
+"#,
+    },
+    Helper {
+        name: "ng_wrap_clamp",
+        deps: &[],
+        glsl: r#"float ng_wrap_clamp(float value) {
+    return clamp(value, -1.0, 1.0);
+}
+"#,
+        wgsl: r#"fn ng_wrap_clamp(value: f32) -> f32 {
+    return clamp(value, -1.0, 1.0);
+}
+"#,
+    },
+    Helper {
+        name: "ng_wrap_mirror",
+        deps: &[],
+        glsl: r#"float ng_wrap_mirror(float value) {
+    value = mod(value + 1.0, 4.0);
+    return value > 2.0 ? 3.0 - value : value - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_wrap_mirror(value: f32) -> f32 {
+    let wrapped = (value + 1.0) - 4.0 * floor((value + 1.0) / 4.0);
+    return select(wrapped - 1.0, 3.0 - wrapped, wrapped > 2.0);
+}
+"#,
+    },
+    Helper {
+        name: "ng_wrap_repeat",
+        deps: &[],
+        glsl: r#"float ng_wrap_repeat(float value) {
+    return mod(value + 1.0, 2.0) - 1.0;
+}
+"#,
+        wgsl: r#"fn ng_wrap_repeat(value: f32) -> f32 {
+    return (value + 1.0) - 2.0 * floor((value + 1.0) / 2.0) - 1.0;
+}
+"#,
+    },
+];
+
+/// A compute shader which samples a grid the way the editor previews do, appended to WGSL.
+const WGSL_SAMPLE_GRID: &str = r#"// The points `sample_grid` samples: `size` by `size` points from `origin`, `step` apart
+struct Grid {
+    origin: vec2<f32>,
+    step: f32,
+    size: u32,
+}
+
+@group(0) @binding(1) var<uniform> grid: Grid;
+@group(0) @binding(2) var<storage, read_write> values: array<f32>;
+
+// Like the editor previews, rows run along the first coordinate
+@compute @workgroup_size(8, 8)
+fn sample_grid(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= grid.size || id.y >= grid.size) {
+        return;
+    }
+
+    let p = vec3(grid.origin + vec2<f32>(id.yx) * grid.step, 0.0);
+    values[id.y * grid.size + id.x] = noise_graph(p);
+}
+"#;
+
+/// An expression which cannot be converted to code.
+#[derive(Debug)]
+pub enum CodegenError {
+    /// A node which has no implementation in the generated language, by name.
+    UnsupportedNode(&'static str),
+
+    /// A fractal or turbulence of a source which has no shader implementation.
+    UnsupportedSource(SourceType),
+}
+
+impl Display for CodegenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedNode(name) => {
+                write!(f, "{name} nodes cannot be converted to code")
+            }
+            Self::UnsupportedSource(source_ty) => write!(
+                f,
+                "Fractals of {source_ty:?} noise cannot be converted to shader code"
+            ),
+        }
+    }
+}
+
+impl Error for CodegenError {}
+
+/// The shading language generated code is written in.
+#[derive(Clone, Copy)]
+enum Language {
+    Glsl,
+    Wgsl,
+}
+
+impl Language {
+    /// Writes a decimal local variable which is not changed after it is declared.
+    fn local(self, name: &str, value: &str) -> String {
+        match self {
+            Self::Glsl => format!("float {name} = {value};"),
+            Self::Wgsl => format!("let {name} = {value};"),
+        }
+    }
+
+    /// Writes a value as a float literal.
+    fn literal(self, value: f64) -> String {
+        let value = value as f32;
+
+        if value.is_finite() {
+            format!("{value:?}")
+        } else {
+            match self {
+                Self::Glsl => format!("uintBitsToFloat({:#x}u)", value.to_bits()),
+                Self::Wgsl => format!("bitcast<f32>({:#x}u)", value.to_bits()),
+            }
+        }
+    }
+
+    fn literals(self, values: impl Iterator<Item = f64>) -> String {
+        values
+            .map(|value| self.literal(value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Writes shader code while walking an expression, where each node becomes a function of the point
+/// it samples and every generator and fractal reads its permutation table from one shared array.
+struct Shader {
+    /// Whether decimal settings which are not named also become uniforms, so that the code does
+    /// not depend on any decimal setting.
+    all_params: bool,
+
+    /// The values of the decimal settings which are not named but became uniforms, in the order
+    /// they are declared.
+    anonymous: Vec<f64>,
+
+    /// Named integer (and control point) settings which are written as literals, with the value of
+    /// each.
+    baked: BTreeMap<String, String>,
+
+    /// The function of each cached source, by id, which every node sharing the cache calls.
+    caches: HashMap<usize, String>,
+
+    functions: Vec<String>,
+
+    /// Used helpers by name, in the order they are written.
+    helpers: Vec<(String, String)>,
+
+    language: Language,
+
+    /// The seed of each permutation table, in the order of the array.
+    seeds: Vec<u32>,
+
+    /// The uniform identifier and editor value of each named decimal setting, by name.
+    uniforms: BTreeMap<String, (String, f64)>,
+}
+
+impl Shader {
+    fn new(language: Language, all_params: bool) -> Self {
+        Self {
+            all_params,
+            anonymous: vec![],
+            baked: Default::default(),
+            caches: Default::default(),
+            functions: vec![],
+            helpers: vec![],
+            language,
+            seeds: vec![],
+            uniforms: Default::default(),
+        }
+    }
+
+    /// Writes a named integer setting as a literal, recording its value.
+    fn bake_u32(&mut self, var: &Variable<u32>) -> u32 {
+        match var {
+            Variable::Anonymous(_) => (),
+            Variable::Named(name, value) => {
+                self.baked.insert(name.clone(), value.to_string());
+            }
+            Variable::Operation(vars, _) => vars.iter().for_each(|var| {
+                self.bake_u32(var);
+            }),
+        }
+
+        var.value()
+    }
+
+    /// Writes a named decimal setting as a literal, for settings which decide the structure of the
+    /// generated code, such as the order of control points.
+    fn bake_f64(&mut self, var: &Variable<f64>) -> f64 {
+        match var {
+            Variable::Anonymous(_) => (),
+            Variable::Named(name, value) => {
+                self.baked.insert(name.clone(), format!("{value:?}"));
+            }
+            Variable::Operation(vars, _) => vars.iter().for_each(|var| {
+                self.bake_f64(var);
+            }),
+        }
+
+        var.value()
+    }
+
+    fn finish(self, entry: &str, cost: &ExprCost) -> String {
+        let mut res = match self.language {
+            Language::Glsl => {
+                "// Generated by noise_gui: `float noise_graph(vec3 p)` samples the graph as the \
+                editor previews it,\n// in single precision so values differ slightly. Requires \
+                GLSL 3.30 or GLSL ES 3.00.\n"
+            }
+            Language::Wgsl => {
+                "// Generated by noise_gui: `fn noise_graph(p: vec3<f32>) -> f32` samples the graph \
+                as the editor\n// previews it, in single precision so values differ slightly. The \
+                `sample_grid` compute\n// shader fills a storage buffer with a grid of samples.\n"
+            }
+        }
+        .to_owned();
+
+        if !self.baked.is_empty() {
+            let baked = self
+                .baked
+                .iter()
+                .map(|(name, value)| format!("{name} = {value}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            res += &format!("//\n// Baked settings: {baked}\n");
+        }
+
+        res += &format!("//\n{}\n", cost.comment("//"));
+
+        if !self.uniforms.is_empty() || !self.anonymous.is_empty() {
+            // Values are left out of the code when every decimal setting is a uniform
+            let named = self.uniforms.iter().map(|(name, (ident, value))| {
+                let comment = if self.all_params {
+                    format!(" // \"{name}\"")
+                } else {
+                    format!(" // \"{name}\", {value:?} in the editor")
+                };
+
+                (ident.clone(), comment)
+            });
+            let anonymous = (0..self.anonymous.len()).map(|idx| (format!("a{idx}"), String::new()));
+            let declarations = named
+                .chain(anonymous)
+                .map(|(ident, comment)| match self.language {
+                    Language::Glsl => format!("uniform float {ident};{comment}\n"),
+                    Language::Wgsl => format!("    {ident}: f32,{comment}\n"),
+                })
+                .collect::<String>();
+
+            res += &match self.language {
+                Language::Glsl => declarations,
+                Language::Wgsl => format!(
+                    "// {} decimal variables, which may be changed while the shader runs\n\
+                    struct Params {{\n{declarations}}}\n\n\
+                    @group(0) @binding(0) var<uniform> params: Params;\n",
+                    if self.all_params { "All" } else { "Named" },
+                ),
+            };
+            res.push('\n');
+        }
+
+        if !self.seeds.is_empty() {
+            let values = self
+                .seeds
+                .iter()
+                .flat_map(|&seed| {
+                    let table = PermutationTable::new(seed);
+                    (0..256).map(move |idx| table.hash(&[idx]))
+                })
+                .collect::<Vec<_>>();
+            let rows = values
+                .chunks(16)
+                .map(|row| {
+                    let row = row.iter().map(usize::to_string).collect::<Vec<_>>();
+                    format!("    {}", row.join(", "))
+                })
+                .collect::<Vec<_>>()
+                .join(",\n");
+            let seeds = self
+                .seeds
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let len = values.len();
+
+            res += &format!("// Permutation tables of seeds {seeds}\n");
+            res += &match self.language {
+                Language::Glsl => format!("const int PERM[{len}] = int[{len}](\n{rows});\n\n"),
+                Language::Wgsl => format!(
+                    "var<private> PERM: array<i32, {len}> = array<i32, {len}>(\n{rows});\n\n"
+                ),
+            };
+        }
+
+        for (_, src) in &self.helpers {
+            res += src;
+            res.push('\n');
+        }
+
+        for function in &self.functions {
+            res += function;
+            res.push('\n');
+        }
+
+        match self.language {
+            Language::Glsl => {
+                res + &format!("float noise_graph(vec3 p) {{\n    return {entry}(p);\n}}\n")
+            }
+            Language::Wgsl => {
+                res + &format!(
+                    "fn noise_graph(p: vec3<f32>) -> f32 {{\n    return {entry}(p);\n}}\n\n"
+                ) + WGSL_SAMPLE_GRID
+            }
+        }
+    }
+
+    /// Writes a decimal setting as an expression, where named settings become uniforms.
+    fn float(&mut self, var: &Variable<f64>) -> String {
+        match var {
+            Variable::Anonymous(value) if self.all_params => {
+                let ident = format!("a{}", self.anonymous.len());
+                self.anonymous.push(*value);
+
+                match self.language {
+                    Language::Glsl => ident,
+                    Language::Wgsl => format!("params.{ident}"),
+                }
+            }
+            Variable::Anonymous(value) => self.language.literal(*value),
+            Variable::Named(name, value) => {
+                let ident = self.uniform(name, *value);
+
+                match self.language {
+                    Language::Glsl => ident,
+                    Language::Wgsl => format!("params.{ident}"),
+                }
+            }
+            Variable::Operation(vars, op) => {
+                let (lhs, rhs) = (self.float(&vars[0]), self.float(&vars[1]));
+
+                match op {
+                    OpType::Add => format!("({lhs} + {rhs})"),
+                    OpType::Divide => {
+                        self.helper("ng_div");
+                        format!("ng_div({lhs}, {rhs})")
+                    }
+                    OpType::Multiply => format!("({lhs} * {rhs})"),
+                    OpType::Subtract => format!("({lhs} - {rhs})"),
+                }
+            }
+        }
+    }
+
+    /// Writes the body of a node which samples a fractal of a source.
+    fn fractal(
+        &mut self,
+        template: &'static str,
+        expr: &FractalExpr,
+        default_seeded: bool,
+    ) -> Result<String, CodegenError> {
+        let (helper, table, octaves, remainder) = self.octaves(
+            template,
+            expr.source_ty,
+            &expr.seed,
+            &expr.octaves,
+            &expr.octave_remainder,
+            default_seeded,
+        )?;
+        let frequency = self.float(&expr.frequency);
+        let lacunarity = self.float(&expr.lacunarity);
+        let persistence = self.float(&expr.persistence);
+
+        Ok(format!(
+            "return {helper}({table}, {octaves}, {frequency}, {lacunarity}, {persistence}, \
+            {remainder}, p);"
+        ))
+    }
+
+    /// Writes the function of a node which returns the given body, returning its name.
+    fn function(&mut self, body: String) -> String {
+        let name = format!("node_{}", self.functions.len());
+        let body = body
+            .lines()
+            .map(|line| format!("    {line}\n"))
+            .collect::<String>();
+        self.functions.push(match self.language {
+            Language::Glsl => format!("float {name}(vec3 p) {{\n{body}}}\n"),
+            Language::Wgsl => format!("fn {name}(p: vec3<f32>) -> f32 {{\n{body}}}\n"),
+        });
+
+        name
+    }
+
+    /// Uses a helper, along with the helpers it calls.
+    fn helper(&mut self, name: &'static str) {
+        if self.helpers.iter().any(|(used, _)| used == name) {
+            return;
+        }
+
+        let helper = HELPERS.iter().find(|helper| helper.name == name).unwrap();
+
+        for dep in helper.deps {
+            self.helper(dep);
+        }
+
+        self.helpers
+            .push((name.to_owned(), helper.src(self.language).to_owned()));
+    }
+
+    /// Writes a node and the nodes it samples, returning the name of its function.
+    fn node(&mut self, expr: &Expr) -> Result<String, CodegenError> {
+        let body = match expr {
+            Expr::Abs(source) => format!("return abs({}(p));", self.node(source)?),
+            Expr::Add([source1, source2]) => format!(
+                "return {}(p) + {}(p);",
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::Bake(_) => return Err(CodegenError::UnsupportedNode("Bake")),
+            Expr::Heightmap(_) => return Err(CodegenError::UnsupportedNode("Heightmap")),
+            Expr::BasicMulti(expr) => self.fractal("basic_multi", expr, false)?,
+            Expr::Billow(expr) => self.fractal("billow", expr, false)?,
+            Expr::Blend(expr) => format!(
+                "return mix({}(p), {}(p), {}(p));",
+                self.node(&expr.sources[0])?,
+                self.node(&expr.sources[1])?,
+                self.node(&expr.control)?
+            ),
+            Expr::BlueNoise(seed) => {
+                self.helper("ng_blue_noise");
+                format!("return ng_blue_noise({}u, p);", self.bake_u32(seed))
+            }
+            Expr::Cache(expr) => {
+                // Shaders have nowhere to keep samples, so the source is only written once
+                if let Some(name) = self.caches.get(&expr.id) {
+                    return Ok(name.clone());
+                }
+
+                let name = self.node(&expr.source)?;
+                self.caches.insert(expr.id, name.clone());
+
+                return Ok(name);
+            }
+            Expr::Checkerboard(size) => {
+                self.helper("ng_checkerboard");
+                format!(
+                    "return ng_checkerboard({}, p);",
+                    1 << self.bake_u32(size).min(30)
+                )
+            }
+            Expr::Clamp(expr) => {
+                let source = self.node(&expr.source)?;
+                let lower_bound = self.float(&expr.lower_bound);
+                let upper_bound = self.float(&expr.upper_bound);
+
+                format!(
+                    "return clamp({source}(p), min({lower_bound}, {upper_bound}), \
+                    max({lower_bound}, {upper_bound}));"
+                )
+            }
+            Expr::Constant(value) => format!("return {};", self.float(value)),
+            Expr::ConstantU32(_) => return Err(CodegenError::UnsupportedNode("Integer")),
+            Expr::Curve(expr) => {
+                let source = self.node(&expr.source)?;
+                let mut control_points: Vec<(f64, f64)> = vec![];
+
+                // Inserted as noise-rs does, which ignores repeated inputs
+                for control_point in &expr.control_points {
+                    let input = self.bake_f64(&control_point.input_value);
+                    let output = self.bake_f64(&control_point.output_value);
+
+                    if control_points
+                        .iter()
+                        .all(|&(other, _)| (other - input).abs() >= f64::EPSILON)
+                    {
+                        let idx = control_points
+                            .iter()
+                            .position(|&(other, _)| other >= input)
+                            .unwrap_or(control_points.len());
+                        control_points.insert(idx, (input, output));
+                    }
+                }
+
+                if control_points.len() < 4 {
+                    "return 0.0;".to_owned()
+                } else {
+                    self.helper("ng_cubic");
+
+                    let len = control_points.len();
+                    let last = len - 1;
+                    let inputs = self
+                        .language
+                        .literals(control_points.iter().map(|&(input, _)| input));
+                    let outputs = self
+                        .language
+                        .literals(control_points.iter().map(|&(_, output)| output));
+                    let cubic = format!(
+                        "return ng_cubic(outputs[i - 2], outputs[i1], outputs[i2], \
+                        outputs[min(i + 1, {last})], alpha);"
+                    );
+
+                    match self.language {
+                        Language::Glsl => format!(
+                            "const float inputs[{len}] = float[{len}]({inputs});\n\
+                            const float outputs[{len}] = float[{len}]({outputs});\n\
+                            float value = {source}(p);\n\
+                            int i = 0;\n\
+                            while (i < {len} && inputs[i] <= value) i++;\n\
+                            i = max(i, 2);\n\
+                            int i1 = i - 1;\n\
+                            int i2 = min(i, {last});\n\
+                            if (i1 == i2) return outputs[i1];\n\
+                            float alpha = (value - inputs[i1]) / (inputs[i2] - inputs[i1]);\n\
+                            {cubic}"
+                        ),
+                        // Arrays are only indexed by variables when they are declared with `var`
+                        Language::Wgsl => [
+                            &format!("var inputs = array<f32, {len}>({inputs});"),
+                            &format!("var outputs = array<f32, {len}>({outputs});"),
+                            &format!("let value = {source}(p);"),
+                            "var i = 0;",
+                            &format!("while (i < {len} && inputs[i] <= value) {{"),
+                            "    i++;",
+                            "}",
+                            "i = max(i, 2);",
+                            "let i1 = i - 1;",
+                            &format!("let i2 = min(i, {last});"),
+                            "if (i1 == i2) {",
+                            "    return outputs[i1];",
+                            "}",
+                            "let alpha = (value - inputs[i1]) / (inputs[i2] - inputs[i1]);",
+                            &cubic,
+                        ]
+                        .join("\n"),
+                    }
+                }
+            }
+            Expr::Cylinders(frequency) => {
+                self.helper("ng_cylinders");
+                format!("return ng_cylinders({}, p);", self.float(frequency))
+            }
+            Expr::Derivative(_) => return Err(CodegenError::UnsupportedNode("Derivative")),
+            Expr::Displace(expr) => format!(
+                "return {}(p + vec3({}(p), {}(p), {}(p)));",
+                self.node(&expr.source)?,
+                self.node(&expr.axes[0])?,
+                self.node(&expr.axes[1])?,
+                self.node(&expr.axes[2])?
+            ),
+            Expr::Exp(source) => {
+                self.helper("ng_exp");
+                format!("return ng_exp({}(p));", self.node(source)?)
+            }
+            Expr::Exponent(expr) => {
+                self.helper("ng_exponent");
+                format!(
+                    "return ng_exponent({}(p), {});",
+                    self.node(&expr.source)?,
+                    self.float(&expr.exponent)
+                )
+            }
+            Expr::FastNoise(_) => return Err(CodegenError::UnsupportedNode("FastNoise Lite")),
+            Expr::Fault(_) => return Err(CodegenError::UnsupportedNode("Fault")),
+            Expr::Fbm(expr) => self.fractal("fbm", expr, false)?,
+            Expr::Gradient(expr) => {
+                let wrap_fn = match expr.wrap {
+                    WrapMode::Clamp => "ng_wrap_clamp",
+                    WrapMode::Mirror => "ng_wrap_mirror",
+                    WrapMode::Repeat => "ng_wrap_repeat",
+                };
+                self.helper(wrap_fn);
+
+                let angle = format!("radians({})", self.float(&expr.angle));
+
+                format!(
+                    "{}\nreturn {wrap_fn}(p.x * cos(angle) + p.y * sin(angle) + {});",
+                    self.language.local("angle", &angle),
+                    self.float(&expr.offset)
+                )
+            }
+            // noise-rs only seeds the octaves of hybrid fractals when the octaves change
+            Expr::HybridMulti(expr) => self.fractal("hybrid_multi", expr, true)?,
+            Expr::Jitter(expr) => {
+                let source = self.node(&expr.source)?;
+                self.helper("ng_jitter");
+
+                format!(
+                    "return {source}(ng_jitter({}u, {}, p));",
+                    self.bake_u32(&expr.seed),
+                    self.float(&expr.amount)
+                )
+            }
+            Expr::Log(source) => {
+                self.helper("ng_log");
+                format!("return ng_log({}(p));", self.node(source)?)
+            }
+            Expr::Max([source1, source2]) => format!(
+                "return max({}(p), {}(p));",
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::Min([source1, source2]) => format!(
+                "return min({}(p), {}(p));",
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::Mix(expr) => format!(
+                "return mix({}(p), {}(p), {}(p));",
+                self.node(&expr.sources[0])?,
+                self.node(&expr.sources[1])?,
+                self.node(&expr.factor)?
+            ),
+            Expr::Multiply([source1, source2]) => format!(
+                "return {}(p) * {}(p);",
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::Negate(source) => format!("return -{}(p);", self.node(source)?),
+            Expr::OpenSimplex(_) => return Err(CodegenError::UnsupportedNode("Open Simplex")),
+            Expr::OutputRange(expr) if self.all_params => {
+                format!("return {}(p);", self.node(&expr.source)?)
+            }
+            Expr::OutputRange(expr) => format!(
+                "// Expected within [{:?}, {:?}]\nreturn {}(p);",
+                expr.range[0],
+                expr.range[1],
+                self.node(&expr.source)?
+            ),
+            Expr::Perlin(seed) => self.source(SourceType::Perlin, seed)?,
+            Expr::PerlinSurflet(_) => {
+                return Err(CodegenError::UnsupportedNode("Perlin Surflet"));
+            }
+            Expr::Plateau(expr) => {
+                self.helper("ng_plateau");
+                format!(
+                    "return ng_plateau({}(p), {}, {});",
+                    self.node(&expr.source)?,
+                    self.float(&expr.level),
+                    self.float(&expr.softness)
+                )
+            }
+            Expr::Power([source1, source2]) => format!(
+                "return pow({}(p), {}(p));",
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::PowSignal([base, exponent]) => {
+                self.helper("ng_pow_signal");
+                format!(
+                    "return ng_pow_signal({}(p), {}(p));",
+                    self.node(base)?,
+                    self.node(exponent)?
+                )
+            }
+            Expr::Quantize(expr) => {
+                // The dither hashes the bits of double precision coordinates
+                if self.bake_f64(&expr.dither) != 0.0 {
+                    return Err(CodegenError::UnsupportedNode("Dithered Quantize"));
+                }
+
+                self.helper("ng_quantize");
+                format!(
+                    "return ng_quantize({}, {}(p));",
+                    self.bake_u32(&expr.levels),
+                    self.node(&expr.source)?
+                )
+            }
+            Expr::Rebase(expr) => {
+                let source = self.node(&expr.source)?;
+                self.helper("ng_rebase");
+
+                format!(
+                    "return {source}(ng_rebase({}, p));",
+                    self.float(&expr.period)
+                )
+            }
+            Expr::Repeat(expr) => {
+                let source = self.node(&expr.source)?;
+                self.helper("ng_repeat");
+
+                format!(
+                    "return {source}(ng_repeat({}, {}, p));",
+                    self.vec3(&expr.periods),
+                    expr.mode == RepeatMode::Mirror
+                )
+            }
+            Expr::Ridge(expr) => {
+                self.helper("ng_ridge");
+                format!(
+                    "return ng_ridge({}(p), {}, {});",
+                    self.node(&expr.source)?,
+                    self.float(&expr.sharpness),
+                    self.float(&expr.offset)
+                )
+            }
+            Expr::RidgedMulti(expr) => {
+                // Like hybrid fractals, the octaves are only seeded when they change
+                let (helper, table, octaves, remainder) = self.octaves(
+                    "ridged_multi",
+                    expr.source_ty,
+                    &expr.seed,
+                    &expr.octaves,
+                    &expr.octave_remainder,
+                    true,
+                )?;
+
+                format!(
+                    "return {helper}({table}, {octaves}, {}, {}, {}, {}, {remainder}, p);",
+                    self.float(&expr.frequency),
+                    self.float(&expr.lacunarity),
+                    self.float(&expr.persistence),
+                    self.float(&expr.attenuation)
+                )
+            }
+            Expr::RotatePoint(expr) => {
+                let source = self.node(&expr.source)?;
+                self.helper("ng_rotate");
+
+                format!(
+                    "return {source}(ng_rotate({}, p));",
+                    self.vec3(&expr.axes[0..3])
+                )
+            }
+            Expr::ScaleBias(expr) => format!(
+                "return {}(p) * {} + {};",
+                self.node(&expr.source)?,
+                self.float(&expr.scale),
+                self.float(&expr.bias)
+            ),
+            Expr::ScalePoint(expr) => format!(
+                "return {}(p * {});",
+                self.node(&expr.source)?,
+                self.vec3(&expr.axes[0..3])
+            ),
+            Expr::Select(expr) => {
+                self.helper("ng_select");
+                format!(
+                    "return ng_select({}(p), {}(p), {}(p), {}, {}, {});",
+                    self.node(&expr.sources[0])?,
+                    self.node(&expr.sources[1])?,
+                    self.node(&expr.control)?,
+                    self.float(&expr.lower_bound),
+                    self.float(&expr.upper_bound),
+                    self.float(&expr.falloff)
+                )
+            }
+            Expr::Simplex(seed) => self.source(SourceType::Simplex, seed)?,
+            Expr::SuperSimplex(_) => return Err(CodegenError::UnsupportedNode("Super Simplex")),
+            Expr::Terrace(expr) => {
+                let source = self.node(&expr.source)?;
+                let mut control_points: Vec<f64> = vec![];
+
+                // Inserted as noise-rs does, which ignores repeated control points
+                for control_point in &expr.control_points {
+                    let control_point = self.bake_f64(control_point);
+
+                    if control_points
+                        .iter()
+                        .all(|&other| (other - control_point).abs() >= f64::EPSILON)
+                    {
+                        let idx = control_points
+                            .iter()
+                            .position(|&other| other >= control_point)
+                            .unwrap_or(control_points.len());
+                        control_points.insert(idx, control_point);
+                    }
+                }
+
+                if control_points.len() < 2 {
+                    "return 0.0;".to_owned()
+                } else {
+                    let len = control_points.len();
+                    let (input0, input1, alpha) = if expr.inverted {
+                        (
+                            "points[i1]",
+                            "points[i0]",
+                            "1.0 - (value - points[i0]) / (points[i1] - points[i0])",
+                        )
+                    } else {
+                        (
+                            "points[i0]",
+                            "points[i1]",
+                            "(value - points[i0]) / (points[i1] - points[i0])",
+                        )
+                    };
+
+                    let last = len - 1;
+                    let points = self.language.literals(control_points.iter().copied());
+                    let terrace = format!("return mix({input0}, {input1}, alpha * alpha);");
+
+                    match self.language {
+                        Language::Glsl => format!(
+                            "const float points[{len}] = float[{len}]({points});\n\
+                            float value = {source}(p);\n\
+                            int i = 0;\n\
+                            while (i < {len} && points[i] < value) i++;\n\
+                            int i0 = clamp(i - 1, 0, {last});\n\
+                            int i1 = min(i, {last});\n\
+                            if (i0 == i1) return points[i1];\n\
+                            float alpha = {alpha};\n\
+                            {terrace}"
+                        ),
+                        Language::Wgsl => [
+                            &format!("var points = array<f32, {len}>({points});"),
+                            &format!("let value = {source}(p);"),
+                            "var i = 0;",
+                            &format!("while (i < {len} && points[i] < value) {{"),
+                            "    i++;",
+                            "}",
+                            &format!("let i0 = clamp(i - 1, 0, {last});"),
+                            &format!("let i1 = min(i, {last});"),
+                            "if (i0 == i1) {",
+                            "    return points[i1];",
+                            "}",
+                            &format!("let alpha = {alpha};"),
+                            &terrace,
+                        ]
+                        .join("\n"),
+                    }
+                }
+            }
+            Expr::Threshold(expr) => {
+                let source = self.node(&expr.source)?;
+                let off_threshold = self.float(&expr.off_threshold);
+                let on_threshold = self.float(&expr.on_threshold);
+                let bounds = [
+                    ("lower", format!("min({off_threshold}, {on_threshold})")),
+                    ("upper", format!("max({off_threshold}, {on_threshold})")),
+                    ("value", format!("{source}(p)")),
+                ]
+                .map(|(name, value)| self.language.local(name, &value) + "\n")
+                .concat();
+
+                match expr.mode {
+                    ThresholdMode::Distance => {
+                        // Matches the central differences of the Threshold noise function
+                        let slopes = [
+                            "vec3(0.015625, 0.0, 0.0)",
+                            "vec3(0.0, 0.015625, 0.0)",
+                            "vec3(0.0, 0.0, 0.015625)",
+                        ]
+                        .map(|step| format!("{source}(p + {step}) - {source}(p - {step})"))
+                        .join(", ");
+                        let gradient = self
+                            .language
+                            .local("gradient", &format!("length(vec3({slopes})) / 0.03125"));
+
+                        format!(
+                            "{bounds}{gradient}\nreturn clamp((value - (lower + upper) * 0.5) / \
+                            max(gradient, 0.000001), -1.0, 1.0);"
+                        )
+                    }
+                    ThresholdMode::Hysteresis => {
+                        // The scan calls the source, so it cannot be a helper
+                        let scan_steps = self.bake_u32(&expr.scan_steps);
+                        let scan = match self.language {
+                            Language::Glsl => [
+                                "if (value >= upper) return 1.0;",
+                                "if (value <= lower) return -1.0;",
+                                "// Values within the band keep the state of the preceding samples along X",
+                                "vec3 q = p;",
+                                &format!("for (int i = 0; i < {scan_steps}; i++) {{"),
+                                "    q.x -= 0.03125;",
+                                &format!("    float scanned = {source}(q);"),
+                                "    if (scanned >= upper) return 1.0;",
+                                "    if (scanned <= lower) return -1.0;",
+                                "}",
+                                "return value * 2.0 >= lower + upper ? 1.0 : -1.0;",
+                            ]
+                            .join("\n"),
+                            Language::Wgsl => [
+                                "if (value >= upper) {",
+                                "    return 1.0;",
+                                "}",
+                                "if (value <= lower) {",
+                                "    return -1.0;",
+                                "}",
+                                "// Values within the band keep the state of the preceding samples along X",
+                                "var q = p;",
+                                &format!("for (var i = 0; i < {scan_steps}; i++) {{"),
+                                "    q.x -= 0.03125;",
+                                &format!("    let scanned = {source}(q);"),
+                                "    if (scanned >= upper) {",
+                                "        return 1.0;",
+                                "    }",
+                                "    if (scanned <= lower) {",
+                                "        return -1.0;",
+                                "    }",
+                                "}",
+                                "return select(-1.0, 1.0, value * 2.0 >= lower + upper);",
+                            ]
+                            .join("\n"),
+                        };
+
+                        bounds + &scan
+                    }
+                    ThresholdMode::Smoothstep => {
+                        self.helper("ng_threshold_smooth");
+                        format!("{bounds}return ng_threshold_smooth(value, lower, upper);")
+                    }
+                }
+            }
+            Expr::TranslatePoint(expr) => format!(
+                "return {}(p + {});",
+                self.node(&expr.source)?,
+                self.vec3(&expr.axes[0..3])
+            ),
+            Expr::Turbulence(expr) => {
+                let source = self.node(&expr.source)?;
+                let roughness = self.bake_u32(&expr.roughness);
+                let seed = self.bake_u32(&expr.seed);
+
+                // Each axis is displaced by a fractal seeded after the one before it
+                let octaves = roughness.clamp(1, MAX_FRACTAL_OCTAVES);
+                let name = self.source_name(expr.source_ty)?;
+                let helper = self.template("turbulence", expr.source_ty, name);
+                let table = self.table((0..octaves + 2).map(|octave| seed.wrapping_add(octave)));
+                let frequencies = match &expr.axis_frequencies {
+                    Some(frequencies) => self.vec3(frequencies),
+                    None => format!("vec3({})", self.float(&expr.frequency)),
+                };
+                let powers = match &expr.axis_powers {
+                    Some(powers) => self.vec3(powers),
+                    None => format!("vec3({})", self.float(&expr.power)),
+                };
+
+                format!(
+                    "return {source}({helper}({table}, {octaves}, {frequencies}, {powers}, p));"
+                )
+            }
+            Expr::Value(seed) => self.source(SourceType::Value, seed)?,
+            Expr::WhiteNoise(seed) => {
+                self.helper("ng_hash_signed");
+                let cell = match self.language {
+                    Language::Glsl => "ivec3(floor(p))",
+                    Language::Wgsl => "vec3<i32>(floor(p))",
+                };
+
+                format!(
+                    "return ng_hash_signed({}u, {cell}, 0u);",
+                    self.bake_u32(seed)
+                )
+            }
+            Expr::Worley(expr) => {
+                self.helper("ng_worley");
+
+                let seed = self.bake_u32(&expr.seed);
+                let table = self.table([seed]);
+                let distance_fn = match expr.distance_fn {
+                    DistanceFunction::Chebyshev => 0,
+                    DistanceFunction::Euclidean => 1,
+                    DistanceFunction::EuclideanSquared => 2,
+                    DistanceFunction::Manhattan => 3,
+                };
+
+                format!(
+                    "return ng_worley({table}, {}, {distance_fn}, {}, p);",
+                    self.float(&expr.frequency),
+                    expr.return_ty == ReturnType::Value
+                )
+            }
+        };
+
+        Ok(self.function(body))
+    }
+
+    /// Uses the fractal helper of a source and the permutation tables of its octaves, returning
+    /// the helper, the offset of the first table, the number of octaves and the weight of the
+    /// octave after them.
+    fn octaves(
+        &mut self,
+        template: &'static str,
+        source_ty: SourceType,
+        seed: &Variable<u32>,
+        octaves: &Variable<u32>,
+        remainder: &Variable<f64>,
+        default_seeded: bool,
+    ) -> Result<(String, usize, u32, String), CodegenError> {
+        let name = self.source_name(source_ty)?;
+        let helper = self.template(template, source_ty, name);
+        let octaves = self.bake_u32(octaves).clamp(1, MAX_FRACTAL_OCTAVES);
+        let mut seed = self.bake_u32(seed);
+
+        if default_seeded && octaves == 6 {
+            seed = 0;
+        }
+
+        // The octave of the remainder needs a table of its own unless it is known to be unused,
+        // which is not the case once its value may change without the shader being rebuilt
+        let has_remainder =
+            self.all_params || !matches!(remainder, Variable::Anonymous(value) if *value <= 0.0);
+        let remainder = if has_remainder {
+            format!("clamp({}, 0.0, 1.0)", self.float(remainder))
+        } else {
+            self.language.literal(0.0)
+        };
+        let table =
+            self.table((0..octaves + has_remainder as u32).map(|octave| seed.wrapping_add(octave)));
+
+        Ok((helper, table, octaves, remainder))
+    }
+
+    /// Writes a generator which samples its permutation table at the point.
+    fn source(
+        &mut self,
+        source_ty: SourceType,
+        seed: &Variable<u32>,
+    ) -> Result<String, CodegenError> {
+        let name = self.source_name(source_ty)?;
+        let seed = self.bake_u32(seed);
+        let table = self.table([seed]);
+
+        Ok(format!("return {name}({table}, p);"))
+    }
+
+    /// Uses the helper which samples a source, returning its name.
+    fn source_name(&mut self, source_ty: SourceType) -> Result<&'static str, CodegenError> {
+        let name = match source_ty {
+            SourceType::Perlin => "ng_perlin",
+            SourceType::Simplex => "ng_simplex",
+            SourceType::Value => "ng_value",
+
+            // Fractals and turbulence use the default frequency, distance and return type
+            SourceType::Worley => "ng_worley_default",
+            SourceType::OpenSimplex | SourceType::PerlinSurflet | SourceType::SuperSimplex => {
+                return Err(CodegenError::UnsupportedSource(source_ty))
+            }
+        };
+        self.helper(name);
+
+        Ok(name)
+    }
+
+    /// Returns the offset within `PERM` of consecutive permutation tables of the given seeds,
+    /// adding them unless the same tables were already added.
+    fn table(&mut self, seeds: impl IntoIterator<Item = u32>) -> usize {
+        let seeds = seeds.into_iter().collect::<Vec<_>>();
+        let idx = self
+            .seeds
+            .windows(seeds.len())
+            .position(|window| window == seeds)
+            .unwrap_or_else(|| {
+                self.seeds.extend(&seeds);
+                self.seeds.len() - seeds.len()
+            });
+
+        idx * 256
+    }
+
+    /// Uses a fractal helper of the given source, returning its name.
+    fn template(&mut self, template: &'static str, source_ty: SourceType, source: &str) -> String {
+        let source_name = format!("{source_ty:?}").to_lowercase();
+        let name = format!("ng_{template}_{source_name}");
+
+        if !self.helpers.iter().any(|(used, _)| *used == name) {
+            let helper = FRACTAL_TEMPLATES
+                .iter()
+                .find(|helper| helper.name == template)
+                .unwrap();
+
+            for dep in helper.deps {
+                self.template(dep, source_ty, source);
+            }
+
+            let src = helper
+                .src(self.language)
+                .replace("NAME", &source_name)
+                .replace("SOURCE", source);
+            self.helpers.push((name.clone(), src));
+        }
+
+        name
+    }
+
+    /// Declares the uniform of a named setting, returning its identifier.
+    fn uniform(&mut self, name: &str, value: f64) -> String {
+        if let Some((ident, _)) = self.uniforms.get(name) {
+            return ident.clone();
+        }
+
+        let mut base = "u_".to_owned();
+        for char in name.chars() {
+            let char = if char.is_ascii_alphanumeric() {
+                char
+            } else {
+                '_'
+            };
+
+            // Identifiers with consecutive underscores are reserved
+            if !(char == '_' && base.ends_with('_')) {
+                base.push(char);
+            }
+        }
+
+        // Distinct names may only differ by characters which are replaced
+        let mut ident = base.clone();
+        let mut suffix = 2;
+        while self.uniforms.values().any(|(other, _)| *other == ident) {
+            ident = format!("{base}{suffix}");
+            suffix += 1;
+        }
+
+        self.uniforms
+            .insert(name.to_owned(), (ident.clone(), value));
+
+        ident
+    }
+
+    fn vec3(&mut self, vars: &[Variable<f64>]) -> String {
+        let coords = vars.iter().map(|var| self.float(var)).collect::<Vec<_>>();
+
+        format!("vec3({})", coords.join(", "))
+    }
+}
+
+pub(crate) fn glsl(expr: &Expr) -> Result<String, CodegenError> {
+    let mut glsl = Shader::new(Language::Glsl, false);
+    let entry = glsl.node(expr)?;
+
+    Ok(glsl.finish(&entry, &expr.cost()))
+}
+
+pub(crate) fn wgsl(expr: &Expr, all_params: bool) -> Result<(String, Vec<f32>), CodegenError> {
+    let mut wgsl = Shader::new(Language::Wgsl, all_params);
+    let entry = wgsl.node(expr)?;
+
+    // The fields of `Params` are declared in the order of the names of the variables, followed by
+    // the variables which are not named
+    let params = wgsl
+        .uniforms
+        .values()
+        .map(|&(_, value)| value)
+        .chain(wgsl.anonymous.iter().copied())
+        .map(|value| value as f32)
+        .collect();
+
+    Ok((wgsl.finish(&entry, &expr.cost()), params))
+}
@@ -0,0 +1,837 @@
+use {
+    super::CodegenError,
+    crate::expr::{
+        Channel, DistanceFunction, Expr, ExprCost, RepeatMode, ReturnType, SourceType,
+        ThresholdMode, Variable, WrapMode, MAX_FRACTAL_OCTAVES,
+    },
+    ordered_float::OrderedFloat,
+    std::{
+        cmp::Ordering,
+        collections::{BTreeMap, BTreeSet, HashMap},
+    },
+};
+
+const BACKEND: &str = "noise_graph::backend";
+const NOISE: &str = "noise";
+const NOISE_GRAPH: &str = "noise_graph::noise_fns";
+const WORLEY: &str = "noise::core::worley";
+
+/// How generated Rust writes the seeds and the octave counts of fractals.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RustParameters {
+    /// `fn noise_graph<const SEED: u32, const OCTAVES: usize>()`, for seeds known when the game
+    /// is compiled.
+    ConstGenerics,
+
+    /// Every seed and octave count is written as a literal.
+    #[default]
+    Literals,
+
+    /// `fn noise_graph(seed: u32, octaves: usize)`, so one function builds the noise of any world
+    /// seed.
+    Parameters,
+}
+
+/// Writes Rust while walking an expression, where each node becomes a local variable holding the
+/// noise function it builds with the `noise` crate.
+#[derive(Default)]
+struct Rust {
+    /// The first octave count written, which the others are offset from when they are parameters.
+    base_octaves: Option<u32>,
+
+    /// The first seed written, which the others are offset from when they are parameters.
+    base_seed: Option<u32>,
+
+    /// The local of each cached source, by id, which every node sharing the cache reads.
+    caches: HashMap<usize, String>,
+
+    /// Imported items, by the path of their module.
+    imports: BTreeMap<&'static str, BTreeSet<&'static str>>,
+
+    /// Statements of the function body, in order.
+    lines: Vec<String>,
+
+    /// The number of locals declared so far, which numbers the next.
+    locals: usize,
+
+    /// Named settings, which are written as literals, with the value of each.
+    named: BTreeMap<String, String>,
+
+    parameters: RustParameters,
+}
+
+impl Rust {
+    /// Writes the builder of a node, returning an expression which is not yet bound to a local.
+    fn build(&mut self, expr: &Expr) -> Result<String, CodegenError> {
+        Ok(match expr {
+            Expr::Abs(source) => format!("{}::new({})", self.noise("Abs"), self.node(source)?),
+            Expr::Add([source1, source2]) => format!(
+                "{}::new({}, {})",
+                self.noise("Add"),
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::Bake(expr) => format!(
+                "{}::new(&{}, {}, {})",
+                self.noise_graph("Bake"),
+                self.node(&expr.source)?,
+                self.u32(&expr.resolution),
+                self.f64(&expr.size)
+            ),
+            Expr::BasicMulti(expr) => self.fractal(
+                "BasicMulti",
+                expr.source_ty,
+                &expr.seed,
+                &expr.octaves,
+                [&expr.frequency, &expr.lacunarity, &expr.persistence],
+                &expr.octave_remainder,
+            ),
+            Expr::Billow(expr) => self.fractal(
+                "Billow",
+                expr.source_ty,
+                &expr.seed,
+                &expr.octaves,
+                [&expr.frequency, &expr.lacunarity, &expr.persistence],
+                &expr.octave_remainder,
+            ),
+            Expr::Blend(expr) => format!(
+                "{}::new({}, {}, {})",
+                self.noise("Blend"),
+                self.node(&expr.sources[0])?,
+                self.node(&expr.sources[1])?,
+                self.node(&expr.control)?
+            ),
+            Expr::BlueNoise(seed) => {
+                format!(
+                    "{}::new({})",
+                    self.noise_graph("BlueNoise"),
+                    self.seed(seed)
+                )
+            }
+            Expr::Cache(expr) => {
+                let cache = match self.caches.get(&expr.id) {
+                    Some(cache) => cache.clone(),
+                    None => {
+                        // Shared as the editor does, which needs the type of the source erased
+                        let value = format!(
+                            "{}::new({}::new(Box::new({}) as Box<dyn NoiseFn<f64, 3>>))",
+                            self.import("std::rc", "Rc"),
+                            self.noise("Cache"),
+                            self.node(&expr.source)?
+                        );
+                        let cache = self.local("cache", value);
+                        self.caches.insert(expr.id, cache.clone());
+
+                        cache
+                    }
+                };
+
+                format!(
+                    "{}::new(Rc::clone(&{cache}))",
+                    self.noise_graph("SharedCache")
+                )
+            }
+            Expr::Checkerboard(size) => {
+                format!("{}::new({})", self.noise("Checkerboard"), self.u32(size))
+            }
+            Expr::Clamp(expr) => {
+                let (lower_bound, upper_bound) = (
+                    self.f64_value(&expr.lower_bound),
+                    self.f64_value(&expr.upper_bound),
+                );
+
+                format!(
+                    "{}::new({})\n    .set_bounds({}, {})",
+                    self.noise("Clamp"),
+                    self.node(&expr.source)?,
+                    literal(lower_bound.min(upper_bound)),
+                    literal(lower_bound.max(upper_bound))
+                )
+            }
+            Expr::Constant(value) => {
+                format!("{}::new({})", self.noise("Constant"), self.f64(value))
+            }
+            Expr::ConstantU32(_) => return Err(CodegenError::UnsupportedNode("Integer")),
+            Expr::Curve(expr) => {
+                let inputs = expr
+                    .control_points
+                    .iter()
+                    .map(|control_point| OrderedFloat(control_point.input_value.value()))
+                    .collect::<BTreeSet<_>>();
+
+                // The editor samples zero instead of panicking, as noise-rs does
+                if inputs.len() < 4 {
+                    format!("{}::new(0.0)", self.noise("Constant"))
+                } else {
+                    let mut res =
+                        format!("{}::new({})", self.noise("Curve"), self.node(&expr.source)?);
+
+                    for control_point in &expr.control_points {
+                        res += &format!(
+                            "\n    .add_control_point({}, {})",
+                            self.f64(&control_point.input_value),
+                            self.f64(&control_point.output_value)
+                        );
+                    }
+
+                    res
+                }
+            }
+            Expr::Cylinders(frequency) => format!(
+                "{}::new()\n    .set_frequency({})",
+                self.noise("Cylinders"),
+                self.f64(frequency)
+            ),
+            Expr::Derivative(expr) => {
+                let constructor = match expr.source_ty {
+                    SourceType::Perlin => "perlin",
+                    SourceType::Simplex => "simplex",
+                    _ => return Ok(format!("{}::new(0.0)", self.noise("Constant"))),
+                };
+                let axis = match expr.channel {
+                    Channel::DerivativeX => "Some(0)",
+                    Channel::DerivativeY => "Some(1)",
+                    Channel::DerivativeZ => "Some(2)",
+                    Channel::Slope | Channel::Value => "None",
+                };
+
+                format!(
+                    "{}::{constructor}({})\n    .set_axis({axis})",
+                    self.noise_graph("Derivative"),
+                    self.seed(&expr.seed)
+                )
+            }
+            Expr::Displace(expr) => format!(
+                "{}::new(\n    {},\n    {},\n    {},\n    {},\n    {},\n)",
+                self.noise("Displace"),
+                self.node(&expr.source)?,
+                self.node(&expr.axes[0])?,
+                self.node(&expr.axes[1])?,
+                self.node(&expr.axes[2])?,
+                self.node(&expr.axes[3])?
+            ),
+            Expr::Exp(source) => {
+                format!("{}::new({})", self.noise_graph("Exp"), self.node(source)?)
+            }
+            Expr::Exponent(expr) => format!(
+                "{}::new({})\n    .set_exponent({})",
+                self.noise("Exponent"),
+                self.node(&expr.source)?,
+                self.f64(&expr.exponent)
+            ),
+            Expr::FastNoise(_) => return Err(CodegenError::UnsupportedNode("FastNoise Lite")),
+            Expr::Fault(expr) => format!(
+                "{}::new({})\n    .set_iterations({})\n    .set_extent({})",
+                self.noise_graph("Fault"),
+                self.seed(&expr.seed),
+                self.u32(&expr.iterations),
+                self.f64(&expr.extent)
+            ),
+            Expr::Fbm(expr) => self.fractal(
+                "Fbm",
+                expr.source_ty,
+                &expr.seed,
+                &expr.octaves,
+                [&expr.frequency, &expr.lacunarity, &expr.persistence],
+                &expr.octave_remainder,
+            ),
+            Expr::Gradient(expr) => {
+                let wrap_fn = match expr.wrap {
+                    WrapMode::Clamp => "wrap_clamp",
+                    WrapMode::Mirror => "wrap_mirror",
+                    WrapMode::Repeat => "wrap_repeat",
+                };
+
+                format!(
+                    "{}::new()\n    .set_angle({})\n    .set_offset({})\n    \
+                    .set_wrap_function({})",
+                    self.noise_graph("Gradient"),
+                    self.f64(&expr.angle),
+                    self.f64(&expr.offset),
+                    self.noise_graph(wrap_fn)
+                )
+            }
+            Expr::Heightmap(_) => return Err(CodegenError::UnsupportedNode("Heightmap")),
+            Expr::HybridMulti(expr) => self.fractal(
+                "HybridMulti",
+                expr.source_ty,
+                &expr.seed,
+                &expr.octaves,
+                [&expr.frequency, &expr.lacunarity, &expr.persistence],
+                &expr.octave_remainder,
+            ),
+            Expr::Jitter(expr) => format!(
+                "{}::new({})\n    .set_seed({})\n    .set_amount({})",
+                self.noise_graph("Jitter"),
+                self.node(&expr.source)?,
+                self.seed(&expr.seed),
+                self.f64(&expr.amount)
+            ),
+            Expr::Log(source) => {
+                format!("{}::new({})", self.noise_graph("Log"), self.node(source)?)
+            }
+            Expr::Max([source1, source2]) => format!(
+                "{}::new({}, {})",
+                self.noise("Max"),
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::Min([source1, source2]) => format!(
+                "{}::new({}, {})",
+                self.noise("Min"),
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::Mix(expr) => format!(
+                "{}::new({}, {}, {})",
+                self.noise_graph("Mix"),
+                self.node(&expr.sources[0])?,
+                self.node(&expr.sources[1])?,
+                self.node(&expr.factor)?
+            ),
+            Expr::Multiply([source1, source2]) => format!(
+                "{}::new({}, {})",
+                self.noise("Multiply"),
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::Negate(source) => {
+                format!("{}::new({})", self.noise("Negate"), self.node(source)?)
+            }
+            Expr::OpenSimplex(seed) => self.source(SourceType::OpenSimplex, seed),
+            Expr::OutputRange(expr) => format!(
+                "{}::new({}, {}, {})",
+                self.noise_graph("OutputRange"),
+                self.node(&expr.source)?,
+                literal(expr.range[0]),
+                literal(expr.range[1])
+            ),
+            Expr::Perlin(seed) => self.source(SourceType::Perlin, seed),
+            Expr::PerlinSurflet(seed) => self.source(SourceType::PerlinSurflet, seed),
+            Expr::Plateau(expr) => format!(
+                "{}::new({})\n    .set_level({})\n    .set_softness({})",
+                self.noise_graph("Plateau"),
+                self.node(&expr.source)?,
+                self.f64(&expr.level),
+                self.f64(&expr.softness)
+            ),
+            Expr::Power([source1, source2]) => format!(
+                "{}::new({}, {})",
+                self.noise("Power"),
+                self.node(source1)?,
+                self.node(source2)?
+            ),
+            Expr::PowSignal([base, exponent]) => format!(
+                "{}::new({}, {})",
+                self.noise_graph("PowSignal"),
+                self.node(base)?,
+                self.node(exponent)?
+            ),
+            Expr::Quantize(expr) => format!(
+                "{}::new({})\n    .set_levels({})\n    .set_dither({})\n    .set_seed({})",
+                self.noise_graph("Quantize"),
+                self.node(&expr.source)?,
+                self.u32(&expr.levels),
+                self.f64(&expr.dither),
+                self.seed(&expr.seed)
+            ),
+            Expr::Rebase(expr) => format!(
+                "{}::new({})\n    .set_period({})",
+                self.noise_graph("Rebase"),
+                self.node(&expr.source)?,
+                self.f64(&expr.period)
+            ),
+            Expr::Repeat(expr) => format!(
+                "{}::new({})\n    .set_all_periods({})\n    .set_mirror({})",
+                self.noise_graph("Repeat"),
+                self.node(&expr.source)?,
+                self.f64s(&expr.periods),
+                expr.mode == RepeatMode::Mirror
+            ),
+            Expr::Ridge(expr) => format!(
+                "{}::new({})\n    .set_sharpness({})\n    .set_offset({})",
+                self.noise_graph("Ridge"),
+                self.node(&expr.source)?,
+                self.f64(&expr.sharpness),
+                self.f64(&expr.offset)
+            ),
+            Expr::RidgedMulti(expr) => {
+                let fractal = self.fractal(
+                    "RidgedMulti",
+                    expr.source_ty,
+                    &expr.seed,
+                    &expr.octaves,
+                    [&expr.frequency, &expr.lacunarity, &expr.persistence],
+                    &expr.octave_remainder,
+                );
+
+                format!(
+                    "{fractal}\n    .set_attenuation({})",
+                    self.f64(&expr.attenuation)
+                )
+            }
+            Expr::RotatePoint(expr) => format!(
+                "{}::new({})\n    .set_angles({})",
+                self.noise("RotatePoint"),
+                self.node(&expr.source)?,
+                self.f64s(&expr.axes)
+            ),
+            Expr::ScaleBias(expr) => format!(
+                "{}::new({})\n    .set_bias({})\n    .set_scale({})",
+                self.noise("ScaleBias"),
+                self.node(&expr.source)?,
+                self.f64(&expr.bias),
+                self.f64(&expr.scale)
+            ),
+            Expr::ScalePoint(expr) => format!(
+                "{}::new({})\n    .set_all_scales({})",
+                self.noise("ScalePoint"),
+                self.node(&expr.source)?,
+                self.f64s(&expr.axes)
+            ),
+            Expr::Select(expr) => format!(
+                "{}::new({}, {}, {})\n    .set_bounds({}, {})\n    .set_falloff({})",
+                self.noise("Select"),
+                self.node(&expr.sources[0])?,
+                self.node(&expr.sources[1])?,
+                self.node(&expr.control)?,
+                self.f64(&expr.lower_bound),
+                self.f64(&expr.upper_bound),
+                self.f64(&expr.falloff)
+            ),
+            Expr::Simplex(seed) => self.source(SourceType::Simplex, seed),
+            Expr::SuperSimplex(seed) => self.source(SourceType::SuperSimplex, seed),
+            Expr::Terrace(expr) => {
+                let control_points = expr
+                    .control_points
+                    .iter()
+                    .map(|control_point| OrderedFloat(control_point.value()))
+                    .collect::<BTreeSet<_>>();
+
+                // The editor samples zero instead of panicking, as noise-rs does
+                if control_points.len() < 2 {
+                    format!("{}::new(0.0)", self.noise("Constant"))
+                } else {
+                    let mut res = format!(
+                        "{}::new({})",
+                        self.noise("Terrace"),
+                        self.node(&expr.source)?
+                    );
+
+                    if expr.inverted {
+                        res += "\n    .invert_terraces(true)";
+                    }
+
+                    for control_point in &expr.control_points {
+                        res += &format!("\n    .add_control_point({})", self.f64(control_point));
+                    }
+
+                    res
+                }
+            }
+            Expr::Threshold(expr) => {
+                let mut res = format!(
+                    "{}::new({})\n    .set_off_threshold({})\n    .set_on_threshold({})\n    \
+                    .set_scan_steps({})\n    .set_smooth({})",
+                    self.noise_graph("Threshold"),
+                    self.node(&expr.source)?,
+                    self.f64(&expr.off_threshold),
+                    self.f64(&expr.on_threshold),
+                    self.u32(&expr.scan_steps),
+                    expr.mode == ThresholdMode::Smoothstep
+                );
+
+                if expr.mode == ThresholdMode::Distance {
+                    res += "\n    .set_distance(true)";
+                }
+
+                res
+            }
+            Expr::TranslatePoint(expr) => format!(
+                "{}::new({})\n    .set_all_translations({})",
+                self.noise("TranslatePoint"),
+                self.node(&expr.source)?,
+                self.f64s(&expr.axes)
+            ),
+            Expr::Turbulence(expr) => {
+                let source = self.node(&expr.source)?;
+                let source_ty = self.source_ty(expr.source_ty, true);
+
+                // Per-axis overrides are only supported by the turbulence of `noise_graph`
+                if expr.axis_frequencies.is_none() && expr.axis_powers.is_none() {
+                    self.noise("Seedable");
+
+                    format!(
+                        "{}::<_, {source_ty}>::new({source})\n    .set_seed({})\n    \
+                        .set_frequency({})\n    .set_power({})\n    .set_roughness({})",
+                        self.noise("Turbulence"),
+                        self.seed(&expr.seed),
+                        self.f64(&expr.frequency),
+                        self.f64(&expr.power),
+                        self.u32(&expr.roughness)
+                    )
+                } else {
+                    let frequencies = match &expr.axis_frequencies {
+                        Some(frequencies) => self.f64s(frequencies),
+                        None => vec![self.f64(&expr.frequency); 3].join(", "),
+                    };
+                    let powers = match &expr.axis_powers {
+                        Some(powers) => self.f64s(powers),
+                        None => vec![self.f64(&expr.power); 3].join(", "),
+                    };
+
+                    format!(
+                        "{}::<_, {source_ty}>::new(\n    {source},\n    {},\n    \
+                        [{frequencies}],\n    [{powers}],\n    {},\n)",
+                        self.noise_graph("AxisTurbulence"),
+                        self.seed(&expr.seed),
+                        self.u32(&expr.roughness)
+                    )
+                }
+            }
+            Expr::Value(seed) => self.source(SourceType::Value, seed),
+            Expr::WhiteNoise(seed) => {
+                format!(
+                    "{}::new({})",
+                    self.noise_graph("WhiteNoise"),
+                    self.seed(seed)
+                )
+            }
+            Expr::Worley(expr) => {
+                let distance_fn = match expr.distance_fn {
+                    DistanceFunction::Chebyshev => "chebyshev",
+                    DistanceFunction::Euclidean => "euclidean",
+                    DistanceFunction::EuclideanSquared => "euclidean_squared",
+                    DistanceFunction::Manhattan => "manhattan",
+                };
+                let return_ty = match expr.return_ty {
+                    ReturnType::Distance => "Distance",
+                    ReturnType::Value => "Value",
+                };
+
+                format!(
+                    "{}::new({})\n    .set_frequency({})\n    \
+                    .set_distance_function({}::{distance_fn})\n    \
+                    .set_return_type({}::{return_ty})",
+                    self.noise("Worley"),
+                    self.seed(&expr.seed),
+                    self.f64(&expr.frequency),
+                    self.import(WORLEY, "distance_functions"),
+                    self.import(WORLEY, "ReturnType")
+                )
+            }
+        })
+    }
+
+    /// Writes a decimal setting as a literal, recording its name if it is named.
+    fn f64(&mut self, var: &Variable<f64>) -> String {
+        literal(self.f64_value(var))
+    }
+
+    fn f64_value(&mut self, var: &Variable<f64>) -> f64 {
+        match var {
+            Variable::Anonymous(_) => (),
+            Variable::Named(name, value) => {
+                self.named.insert(name.clone(), literal(*value));
+            }
+            Variable::Operation(vars, _) => vars.iter().for_each(|var| {
+                self.f64_value(var);
+            }),
+        }
+
+        var.value()
+    }
+
+    fn f64s(&mut self, vars: &[Variable<f64>]) -> String {
+        vars.iter()
+            .map(|var| self.f64(var))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn finish(self, entry: String, cost: &ExprCost) -> String {
+        let mut res =
+            "// Generated by noise_gui: `noise_graph()` builds the noise function of the \
+            graph with the\n// `noise` crate, which samples the values the editor previews.\n"
+                .to_owned();
+
+        if self.imports.contains_key(NOISE_GRAPH) {
+            res += "//\n// Nodes which the `noise` crate does not have are built by the \
+                `noise_graph` crate.\n";
+        }
+
+        // The declaration of each parameter, with the value which builds the editor's noise
+        let params = [
+            self.base_seed
+                .map(|seed| (["const SEED: u32", "seed: u32"], seed)),
+            self.base_octaves
+                .map(|octaves| (["const OCTAVES: usize", "octaves: usize"], octaves)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        let join = |idx: usize| {
+            params
+                .iter()
+                .map(|(decls, _)| decls[idx])
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let args = params
+            .iter()
+            .map(|(_, value)| value.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (generics, params, call) = match self.parameters {
+            _ if params.is_empty() => Default::default(),
+            RustParameters::ConstGenerics => (
+                format!("<{}>", join(0)),
+                String::new(),
+                format!("noise_graph::<{args}>()"),
+            ),
+            RustParameters::Literals => Default::default(),
+            RustParameters::Parameters => (String::new(), join(1), format!("noise_graph({args})")),
+        };
+
+        if !call.is_empty() {
+            res += &format!(
+                "//\n// Seeds and octave counts are offset from the parameters, so `{call}` \
+                builds the\n// noise the editor previews.\n"
+            );
+        }
+
+        if !self.named.is_empty() {
+            let named = self
+                .named
+                .iter()
+                .map(|(name, value)| format!("{name} = {value}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            res += &format!("//\n// Named variables: {named}\n");
+        }
+
+        res += &format!("//\n{}\n", cost.comment("//"));
+
+        for (path, items) in &self.imports {
+            let items = items.iter().copied().collect::<Vec<_>>();
+
+            res += &if items.len() == 1 {
+                format!("use {path}::{};\n", items[0])
+            } else {
+                format!("use {path}::{{{}}};\n", items.join(", "))
+            };
+        }
+
+        res += &format!("\npub fn noise_graph{generics}({params}) -> impl NoiseFn<f64, 3> {{\n");
+
+        for line in &self.lines {
+            for line in line.lines() {
+                res += &format!("    {line}\n");
+            }
+        }
+
+        if !self.lines.is_empty() {
+            res.push('\n');
+        }
+
+        for line in entry.lines() {
+            res += &format!("    {line}\n");
+        }
+
+        res + "}\n"
+    }
+
+    /// Writes the builder of a fractal, which sums octaves of a source.
+    ///
+    /// A fractal with a fraction of one more octave is built by `noise_graph`, and otherwise by the
+    /// `noise` crate, where `ty` names both the fractal and its kind.
+    fn fractal(
+        &mut self,
+        ty: &'static str,
+        source_ty: SourceType,
+        seed: &Variable<u32>,
+        octaves: &Variable<u32>,
+        [frequency, lacunarity, persistence]: [&Variable<f64>; 3],
+        octave_remainder: &Variable<f64>,
+    ) -> String {
+        self.noise("MultiFractal");
+
+        let octave_remainder = self.f64_value(octave_remainder).clamp(0.0, 1.0);
+        let fractal = if octave_remainder > 0.0 {
+            format!(
+                "{}::<{}>::new({}::{ty}, {})",
+                self.noise_graph("FractionalFractal"),
+                self.source_ty(source_ty, true),
+                self.import(BACKEND, "FractalKind"),
+                self.seed(seed)
+            )
+        } else {
+            format!(
+                "{}::<{}>::new({})",
+                self.noise(ty),
+                self.source_ty(source_ty, true),
+                self.seed(seed)
+            )
+        };
+        let mut res = format!(
+            "{fractal}\n    .set_octaves({})\n    .set_frequency({})\n    \
+            .set_lacunarity({})\n    .set_persistence({})",
+            self.octaves(octaves),
+            self.f64(frequency),
+            self.f64(lacunarity),
+            self.f64(persistence)
+        );
+
+        if octave_remainder > 0.0 {
+            res += &format!("\n    .set_remainder({})", literal(octave_remainder));
+        }
+
+        res
+    }
+
+    /// Imports an item, returning its name.
+    fn import(&mut self, path: &'static str, item: &'static str) -> &'static str {
+        self.imports.entry(path).or_default().insert(item);
+
+        item
+    }
+
+    /// Declares a local holding the given value, returning its name.
+    fn local(&mut self, name: &str, value: String) -> String {
+        let name = format!("{name}_{}", self.locals);
+        self.locals += 1;
+        self.lines.push(format!("let {name} = {value};"));
+
+        name
+    }
+
+    /// Writes a node and the nodes it samples, returning the name of the local holding it.
+    fn node(&mut self, expr: &Expr) -> Result<String, CodegenError> {
+        let value = self.build(expr)?;
+
+        // Locals are named after the type they hold, such as `scale_bias_3`
+        let ty = value
+            .split(|char: char| !char.is_ascii_alphanumeric())
+            .next()
+            .unwrap_or_default();
+        let mut name = String::new();
+        for char in ty.chars() {
+            if char.is_ascii_uppercase() && !name.is_empty() {
+                name.push('_');
+            }
+
+            name.push(char.to_ascii_lowercase());
+        }
+
+        Ok(self.local(&name, value))
+    }
+
+    fn noise(&mut self, item: &'static str) -> &'static str {
+        self.import(NOISE, item)
+    }
+
+    fn noise_graph(&mut self, item: &'static str) -> &'static str {
+        self.import(NOISE_GRAPH, item)
+    }
+
+    /// Writes the octave count of a fractal, which is offset from the `octaves` parameter unless
+    /// literals are written.
+    fn octaves(&mut self, var: &Variable<u32>) -> String {
+        let octaves = self.u32(var).clamp(1, MAX_FRACTAL_OCTAVES);
+        let name = match self.parameters {
+            RustParameters::ConstGenerics => "OCTAVES",
+            RustParameters::Literals => return octaves.to_string(),
+            RustParameters::Parameters => "octaves",
+        };
+        let base = *self.base_octaves.get_or_insert(octaves);
+
+        // Fractals are given at least one octave by the `noise` crate
+        match octaves.cmp(&base) {
+            Ordering::Equal => name.to_owned(),
+            Ordering::Greater => format!("{name} + {}", octaves - base),
+            Ordering::Less => format!("{name}.saturating_sub({})", base - octaves),
+        }
+    }
+
+    /// Writes a seed, which is offset from the `seed` parameter unless literals are written, so
+    /// that nodes with different seeds stay different for every world seed.
+    fn seed(&mut self, var: &Variable<u32>) -> String {
+        let seed = self.u32(var);
+        let name = match self.parameters {
+            RustParameters::ConstGenerics => "SEED",
+            RustParameters::Literals => return seed.to_string(),
+            RustParameters::Parameters => "seed",
+        };
+        let base = *self.base_seed.get_or_insert(seed);
+
+        match seed.cmp(&base) {
+            Ordering::Equal => name.to_owned(),
+            Ordering::Greater => format!("{name}.wrapping_add({})", seed - base),
+            Ordering::Less => format!("{name}.wrapping_sub({})", base - seed),
+        }
+    }
+
+    fn source(&mut self, source_ty: SourceType, seed: &Variable<u32>) -> String {
+        format!(
+            "{}::new({})",
+            self.source_ty(source_ty, false),
+            self.seed(seed)
+        )
+    }
+
+    /// Imports the generator of a source type, returning its name.
+    ///
+    /// Fractals and turbulence of Super Simplex noise are built from Open Simplex, as the editor
+    /// has always done.
+    fn source_ty(&mut self, source_ty: SourceType, fractal: bool) -> &'static str {
+        self.noise(match source_ty {
+            SourceType::OpenSimplex => "OpenSimplex",
+            SourceType::Perlin => "Perlin",
+            SourceType::PerlinSurflet => "PerlinSurflet",
+            SourceType::Simplex => "Simplex",
+            SourceType::SuperSimplex if fractal => "OpenSimplex",
+            SourceType::SuperSimplex => "SuperSimplex",
+            SourceType::Value => "Value",
+            SourceType::Worley => "Worley",
+        })
+    }
+
+    /// Writes an integer setting as a literal, recording its name if it is named.
+    fn u32(&mut self, var: &Variable<u32>) -> u32 {
+        match var {
+            Variable::Anonymous(_) => (),
+            Variable::Named(name, value) => {
+                self.named.insert(name.clone(), value.to_string());
+            }
+            Variable::Operation(vars, _) => vars.iter().for_each(|var| {
+                self.u32(var);
+            }),
+        }
+
+        var.value()
+    }
+}
+
+/// Writes a value as an `f64` literal.
+fn literal(value: f64) -> String {
+    if value.is_finite() {
+        format!("{value:?}")
+    } else if value.is_nan() {
+        "f64::NAN".to_owned()
+    } else if value > 0.0 {
+        "f64::INFINITY".to_owned()
+    } else {
+        "f64::NEG_INFINITY".to_owned()
+    }
+}
+
+pub(crate) fn rust(expr: &Expr, parameters: RustParameters) -> Result<String, CodegenError> {
+    let mut rust = Rust {
+        parameters,
+        ..Default::default()
+    };
+    rust.noise("NoiseFn");
+    let entry = rust.build(expr)?;
+
+    Ok(rust.finish(entry, &expr.cost()))
+}
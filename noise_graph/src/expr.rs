@@ -0,0 +1,2083 @@
+use {
+    super::{
+        backend::{Backend, FractalKind, FractalParams, NoiseBackend, TurbulenceParams},
+        codegen::{self, CodegenError, RustParameters},
+        noise_fns::{
+            wrap_clamp, wrap_mirror, wrap_repeat, Bake, BlueNoise, Derivative, Exp, FastNoise,
+            Fault, Gradient, Heightmap, HeightmapImage, Jitter, Log, Mix, Plateau, PowSignal,
+            Quantize, Rebase, Repeat, Ridge, SharedCache, Threshold, WhiteNoise,
+        },
+    },
+    fastnoise_lite::{FastNoiseLite, FractalType, NoiseType, RotationType3D},
+    noise::{
+        Abs, Add, BasicMulti, Blend, Cache, Checkerboard, Clamp, Constant, Curve, Cylinders,
+        Displace, Exponent, Max, Min, Multiply, Negate, NoiseFn, Perlin, Power, RotatePoint,
+        ScaleBias, ScalePoint, Select, Terrace, TranslatePoint,
+    },
+    ordered_float::OrderedFloat,
+    serde::{Deserialize, Serialize},
+    std::{
+        cell::{Cell, RefCell},
+        collections::HashMap,
+        fmt::{Debug, Formatter},
+        rc::Rc,
+        sync::{Arc, OnceLock},
+    },
+};
+
+type Caches = HashMap<usize, Rc<Cache<Box<dyn NoiseFn<f64, 3>>>>>;
+
+thread_local! {
+    /// The number of calls to `Expr::noise_with` this thread is within.
+    static BUILD_DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    /// The cache of each cached source built by the outermost call to `Expr::noise_with`, by id.
+    ///
+    /// Caches are shared with `Rc`, so the noise functions which sample them are neither `Send` nor
+    /// `Sync`, and each thread builds its own.
+    static CACHES: RefCell<Caches> = Default::default();
+}
+
+/// Held by each call to `Expr::noise_with`; the caches of an expression are only shared within
+/// the noise function it builds, so they are forgotten when the outermost call returns.
+struct BuildScope;
+
+impl BuildScope {
+    fn enter() -> Self {
+        BUILD_DEPTH.set(BUILD_DEPTH.get() + 1);
+
+        Self
+    }
+}
+
+impl Drop for BuildScope {
+    fn drop(&mut self) {
+        let depth = BUILD_DEPTH.get() - 1;
+        BUILD_DEPTH.set(depth);
+
+        if depth == 0 {
+            CACHES.with_borrow_mut(Caches::clear);
+        }
+    }
+}
+
+pub const MAX_FRACTAL_OCTAVES: u32 = BasicMulti::<Perlin>::MAX_OCTAVES as _;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Axis {
+    X,
+    Y,
+    #[default]
+    Z,
+}
+
+impl Axis {
+    pub const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::X => "X",
+            Self::Y => "Y",
+            Self::Z => "Z",
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct BakeExpr {
+    pub source: Box<Expr>,
+
+    pub resolution: Variable<u32>,
+    pub size: Variable<f64>,
+
+    /// The baked table, shared by clones of this expression so the source is only evaluated once.
+    #[serde(skip)]
+    pub table: Arc<OnceLock<Bake>>,
+}
+
+impl BakeExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.size.set_if_named(name, value);
+        self.table = Default::default();
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.resolution.set_if_named(name, value);
+        self.table = Default::default();
+    }
+}
+
+// The table is left out so that expressions print (and compare) by their parameters only
+impl Debug for BakeExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BakeExpr")
+            .field("source", &self.source)
+            .field("resolution", &self.resolution)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BlendExpr {
+    pub sources: [Box<Expr>; 2],
+    pub control: Box<Expr>,
+}
+
+impl BlendExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+        self.control.set_f64(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+        self.control.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CacheExpr {
+    /// Identifies the cached source: copies of this expression with the same id within one
+    /// expression, such as one for each node the source feeds, share a single cache.
+    pub id: usize,
+
+    pub source: Box<Expr>,
+}
+
+impl CacheExpr {
+    fn noise_with(&self, backend: &dyn NoiseBackend) -> SharedCache {
+        if let Some(cache) = CACHES.with_borrow(|caches| caches.get(&self.id).cloned()) {
+            return SharedCache::new(cache);
+        }
+
+        let cache = Rc::new(Cache::new(self.source.noise_with(backend)));
+        CACHES.with_borrow_mut(|caches| caches.insert(self.id, Rc::clone(&cache)));
+
+        SharedCache::new(cache)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+/// The value output by a generator which supports analytic derivatives.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Channel {
+    #[default]
+    Value,
+    DerivativeX,
+    DerivativeY,
+    DerivativeZ,
+
+    /// The magnitude of the gradient.
+    Slope,
+}
+
+impl Channel {
+    pub const ALL: [Self; 5] = [
+        Self::Value,
+        Self::DerivativeX,
+        Self::DerivativeY,
+        Self::DerivativeZ,
+        Self::Slope,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Value => "Value",
+            Self::DerivativeX => "d/dx",
+            Self::DerivativeY => "d/dy",
+            Self::DerivativeZ => "d/dz",
+            Self::Slope => "Slope",
+        }
+    }
+}
+
+/// How FastNoise Lite measures the distance to the points of cellular noise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum CellularDistanceFunction {
+    Euclidean,
+
+    #[default]
+    EuclideanSq,
+    Hybrid,
+    Manhattan,
+}
+
+/// The value FastNoise Lite outputs from the nearest points of cellular noise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum CellularReturnType {
+    CellValue,
+
+    #[default]
+    Distance,
+    Distance2,
+    Distance2Add,
+    Distance2Div,
+    Distance2Mul,
+    Distance2Sub,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ClampExpr {
+    pub source: Box<Expr>,
+
+    pub lower_bound: Variable<f64>,
+    pub upper_bound: Variable<f64>,
+}
+
+impl ClampExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.lower_bound.set_if_named(name, value);
+        self.upper_bound.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ControlPointExpr {
+    pub input_value: Variable<f64>,
+    pub output_value: Variable<f64>,
+}
+
+impl ControlPointExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.input_value.set_if_named(name, value);
+        self.output_value.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CurveExpr {
+    pub source: Box<Expr>,
+
+    pub control_points: Vec<ControlPointExpr>,
+}
+
+impl CurveExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.control_points
+            .iter_mut()
+            .for_each(|expr| expr.set_f64(name, value));
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+/// A derivative channel of a Perlin or Simplex generator.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DerivativeExpr {
+    pub source_ty: SourceType,
+    pub seed: Variable<u32>,
+    pub channel: Channel,
+}
+
+impl DerivativeExpr {
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DisplaceExpr {
+    pub source: Box<Expr>,
+
+    pub axes: [Box<Expr>; 4],
+}
+
+impl DisplaceExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.axes.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.axes.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DistanceFunction {
+    Chebyshev,
+    Euclidean,
+    EuclideanSquared,
+    Manhattan,
+}
+
+impl DistanceFunction {
+    pub const ALL: [Self; 4] = [
+        Self::Chebyshev,
+        Self::Euclidean,
+        Self::EuclideanSquared,
+        Self::Manhattan,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Chebyshev => "Chebyshev",
+            Self::Euclidean => "Euclidean",
+            Self::EuclideanSquared => "EuclideanSquared",
+            Self::Manhattan => "Manhattan",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExponentExpr {
+    pub source: Box<Expr>,
+
+    pub exponent: Variable<f64>,
+}
+
+impl ExponentExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.exponent.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+/// The fractal FastNoise Lite sums octaves with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FastFractalType {
+    #[default]
+    None,
+    Fbm,
+    PingPong,
+    Ridged,
+}
+
+impl FastFractalType {
+    pub const ALL: [Self; 4] = [Self::None, Self::Fbm, Self::PingPong, Self::Ridged];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Fbm => "Fbm",
+            Self::PingPong => "PingPong",
+            Self::Ridged => "Ridged",
+        }
+    }
+}
+
+/// Generator settings which match those of FastNoise Lite, so that graphs designed here produce
+/// the same noise as engines using it at runtime.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FastNoiseExpr {
+    pub noise_ty: FastNoiseType,
+    pub fractal_ty: FastFractalType,
+    pub rotation_ty: FastRotationType,
+    pub cellular_distance_fn: CellularDistanceFunction,
+    pub cellular_return_ty: CellularReturnType,
+
+    /// Samples 3D noise, instead of the 2D noise of the X and Y coordinates.
+    pub is_3d: bool,
+
+    pub seed: Variable<u32>,
+    pub octaves: Variable<u32>,
+    pub frequency: Variable<f64>,
+    pub lacunarity: Variable<f64>,
+    pub gain: Variable<f64>,
+    pub weighted_strength: Variable<f64>,
+    pub ping_pong_strength: Variable<f64>,
+    pub cellular_jitter: Variable<f64>,
+}
+
+impl FastNoiseExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.frequency.set_if_named(name, value);
+        self.lacunarity.set_if_named(name, value);
+        self.gain.set_if_named(name, value);
+        self.weighted_strength.set_if_named(name, value);
+        self.ping_pong_strength.set_if_named(name, value);
+        self.cellular_jitter.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+        self.octaves.set_if_named(name, value);
+    }
+}
+
+/// The noise FastNoise Lite generates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FastNoiseType {
+    Cellular,
+
+    #[default]
+    OpenSimplex2,
+    OpenSimplex2S,
+    Perlin,
+    Value,
+    ValueCubic,
+}
+
+impl FastNoiseType {
+    pub const ALL: [Self; 6] = [
+        Self::Cellular,
+        Self::OpenSimplex2,
+        Self::OpenSimplex2S,
+        Self::Perlin,
+        Self::Value,
+        Self::ValueCubic,
+    ];
+
+    /// The estimated operations of one sample, relative to an addition.
+    fn cost(self) -> usize {
+        match self {
+            Self::Cellular => 300,
+            Self::OpenSimplex2 => 90,
+            Self::OpenSimplex2S => 160,
+            Self::Perlin => 60,
+            Self::Value => 40,
+            Self::ValueCubic => 140,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cellular => "Cellular",
+            Self::OpenSimplex2 => "OpenSimplex2",
+            Self::OpenSimplex2S => "OpenSimplex2S",
+            Self::Perlin => "Perlin",
+            Self::Value => "Value",
+            Self::ValueCubic => "ValueCubic",
+        }
+    }
+}
+
+/// The rotation FastNoise Lite applies to 3D coordinates, which reduces the grid artifacts of
+/// planes sliced through the noise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FastRotationType {
+    ImproveXYPlanes,
+    ImproveXZPlanes,
+
+    #[default]
+    None,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FaultExpr {
+    pub seed: Variable<u32>,
+    pub iterations: Variable<u32>,
+
+    /// The distance from the origin within which the fault planes pass.
+    #[serde(default = "FaultExpr::default_extent")]
+    pub extent: Variable<f64>,
+}
+
+impl FaultExpr {
+    /// Expressions exported before the extent was added keep their planes within one unit.
+    fn default_extent() -> Variable<f64> {
+        Variable::Anonymous(1.0)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.extent.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+        self.iterations.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FractalExpr {
+    pub source_ty: SourceType,
+    pub seed: Variable<u32>,
+    pub octaves: Variable<u32>,
+    pub frequency: Variable<f64>,
+    pub lacunarity: Variable<f64>,
+    pub persistence: Variable<f64>,
+
+    /// The weight of one more octave after the whole ones.
+    #[serde(default = "FractalExpr::default_octave_remainder")]
+    pub octave_remainder: Variable<f64>,
+}
+
+impl FractalExpr {
+    fn default_octave_remainder() -> Variable<f64> {
+        Variable::Anonymous(0.0)
+    }
+
+    fn params(&self, kind: FractalKind) -> FractalParams {
+        FractalParams {
+            kind,
+            source_ty: self.source_ty,
+            seed: self.seed.value(),
+            octaves: self.octaves.value(),
+            frequency: self.frequency.value(),
+            lacunarity: self.lacunarity.value(),
+            persistence: self.persistence.value(),
+            attenuation: 0.0,
+            octave_remainder: self.octave_remainder.value().clamp(0.0, 1.0),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.frequency.set_if_named(name, value);
+        self.lacunarity.set_if_named(name, value);
+        self.persistence.set_if_named(name, value);
+        self.octave_remainder.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+        self.octaves.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Expr {
+    Abs(Box<Expr>),
+    Add([Box<Expr>; 2]),
+    Bake(BakeExpr),
+    BasicMulti(FractalExpr),
+    Billow(FractalExpr),
+    Blend(BlendExpr),
+    BlueNoise(Variable<u32>),
+
+    /// Samples the source once for each point, however many nodes it feeds.
+    Cache(CacheExpr),
+
+    Checkerboard(Variable<u32>),
+    Clamp(ClampExpr),
+    Constant(Variable<f64>),
+    ConstantU32(Variable<u32>),
+    Curve(CurveExpr),
+    Cylinders(Variable<f64>),
+    Derivative(DerivativeExpr),
+    Displace(DisplaceExpr),
+    Exp(Box<Expr>),
+    Exponent(ExponentExpr),
+    FastNoise(FastNoiseExpr),
+    Fault(FaultExpr),
+    Fbm(FractalExpr),
+    Gradient(GradientExpr),
+
+    /// Samples a grayscale image, such as a heightmap painted in another program.
+    Heightmap(HeightmapExpr),
+
+    HybridMulti(FractalExpr),
+    Jitter(JitterExpr),
+    Log(Box<Expr>),
+    Max([Box<Expr>; 2]),
+    Min([Box<Expr>; 2]),
+    Mix(MixExpr),
+    Multiply([Box<Expr>; 2]),
+    Negate(Box<Expr>),
+    OpenSimplex(Variable<u32>),
+
+    /// Declares the range the values of the source are expected to be within, which code
+    /// generated from the graph may check. The values are not changed.
+    OutputRange(OutputRangeExpr),
+
+    Perlin(Variable<u32>),
+    PerlinSurflet(Variable<u32>),
+    Plateau(PlateauExpr),
+    Power([Box<Expr>; 2]),
+    PowSignal([Box<Expr>; 2]),
+    Quantize(QuantizeExpr),
+    Rebase(RebaseExpr),
+    Repeat(RepeatExpr),
+    Ridge(RidgeExpr),
+    RidgedMulti(RigidFractalExpr),
+    RotatePoint(TransformExpr),
+    ScaleBias(ScaleBiasExpr),
+    ScalePoint(TransformExpr),
+    Select(SelectExpr),
+    Simplex(Variable<u32>),
+    SuperSimplex(Variable<u32>),
+    Terrace(TerraceExpr),
+    Threshold(ThresholdExpr),
+    TranslatePoint(TransformExpr),
+    Turbulence(TurbulenceExpr),
+    Value(Variable<u32>),
+    WhiteNoise(Variable<u32>),
+    Worley(WorleyExpr),
+}
+
+/// The estimated operations evaluated per sample by an expression, with a breakdown of its
+/// sub-expressions.
+#[derive(Clone, Debug)]
+pub struct ExprCost {
+    pub label: String,
+
+    /// The operations of this expression alone.
+    pub ops: usize,
+
+    pub children: Vec<ExprCost>,
+}
+
+impl ExprCost {
+    /// Formats the estimate as line comments using the given comment prefix (such as `//`), with
+    /// one indented line per sub-expression.
+    pub fn comment(&self, prefix: &str) -> String {
+        fn write_line(res: &mut String, prefix: &str, cost: &ExprCost, depth: usize) {
+            res.push_str(&format!(
+                "{prefix} {:indent$}{}: ~{}",
+                "",
+                cost.label,
+                cost.total(),
+                indent = depth * 2
+            ));
+
+            if !cost.children.is_empty() {
+                res.push_str(&format!(" ({} own)", cost.ops));
+            }
+
+            res.push('\n');
+
+            for child in &cost.children {
+                write_line(res, prefix, child, depth + 1);
+            }
+        }
+
+        let mut res = format!(
+            "{prefix} Estimated cost: ~{} operations per sample\n",
+            self.total()
+        );
+        write_line(&mut res, prefix, self, 1);
+
+        res
+    }
+
+    pub fn total(&self) -> usize {
+        self.ops + self.children.iter().map(Self::total).sum::<usize>()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GradientExpr {
+    pub angle: Variable<f64>,
+    pub offset: Variable<f64>,
+    pub wrap: WrapMode,
+}
+
+impl GradientExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.angle.set_if_named(name, value);
+        self.offset.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HeightmapExpr {
+    pub image: Arc<HeightmapImage>,
+    pub size: Variable<f64>,
+}
+
+impl Expr {
+    /// Estimates the operations evaluated per sample by this expression and its sub-expressions.
+    ///
+    /// Operations are weighted roughly by their cost relative to an addition, so that lookups,
+    /// hashes and transcendental functions count as several operations each.
+    pub fn cost(&self) -> ExprCost {
+        fn fractal(
+            name: &str,
+            source_ty: SourceType,
+            octaves: &Variable<u32>,
+            octave_remainder: &Variable<f64>,
+        ) -> (String, usize) {
+            let octaves = octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as usize;
+
+            // A fraction of an octave costs as much as a whole one
+            if octave_remainder.value() > 0.0 {
+                return (
+                    format!("{name} ({source_ty:?}, {octaves}+ octaves)"),
+                    (octaves + 1) * (source_ty.cost() + 4),
+                );
+            }
+
+            (
+                format!("{name} ({source_ty:?}, {octaves} octaves)"),
+                octaves * (source_ty.cost() + 4),
+            )
+        }
+
+        let (label, ops, children): (String, usize, Vec<&Expr>) = match self {
+            Self::Abs(expr) => ("Abs".to_owned(), 1, vec![expr]),
+            Self::Add([source1, source2]) => ("Add".to_owned(), 1, vec![source1, source2]),
+
+            // The source is evaluated once when baking, not per sample
+            Self::Bake(expr) => {
+                let resolution = expr.resolution.value().clamp(1, Bake::MAX_RESOLUTION);
+
+                (format!("Bake ({resolution}x{resolution})"), 12, vec![])
+            }
+            Self::BasicMulti(expr) => {
+                let (label, ops) = fractal(
+                    "Basic Multi",
+                    expr.source_ty,
+                    &expr.octaves,
+                    &expr.octave_remainder,
+                );
+                (label, ops, vec![])
+            }
+            Self::Billow(expr) => {
+                let (label, ops) = fractal(
+                    "Billow",
+                    expr.source_ty,
+                    &expr.octaves,
+                    &expr.octave_remainder,
+                );
+                (label, ops + 2, vec![])
+            }
+            Self::Blend(expr) => (
+                "Blend".to_owned(),
+                6,
+                vec![&expr.sources[0], &expr.sources[1], &expr.control],
+            ),
+            Self::BlueNoise(_) => ("Blue Noise".to_owned(), 70, vec![]),
+            Self::Cache(expr) => ("Cache".to_owned(), 1, vec![&expr.source]),
+            Self::Checkerboard(_) => ("Checkerboard".to_owned(), 8, vec![]),
+            Self::Clamp(expr) => ("Clamp".to_owned(), 2, vec![&expr.source]),
+            Self::Constant(_) | Self::ConstantU32(_) => ("Constant".to_owned(), 0, vec![]),
+            Self::Curve(expr) => (
+                "Curve".to_owned(),
+                12 + expr.control_points.len(),
+                vec![&expr.source],
+            ),
+            Self::Cylinders(_) => ("Cylinders".to_owned(), 8, vec![]),
+            Self::Derivative(expr) => (
+                format!("Derivative ({:?}, {:?})", expr.source_ty, expr.channel),
+                expr.source_ty.cost() * 2,
+                vec![],
+            ),
+
+            // Only the first three axes are evaluated in three dimensions
+            Self::Displace(expr) => (
+                "Displace".to_owned(),
+                3,
+                vec![&expr.source, &expr.axes[0], &expr.axes[1], &expr.axes[2]],
+            ),
+            Self::Exp(expr) => ("Exp".to_owned(), 20, vec![expr]),
+            Self::Exponent(expr) => ("Exponent".to_owned(), 24, vec![&expr.source]),
+            Self::FastNoise(expr) => {
+                let octaves = if expr.fractal_ty == FastFractalType::None {
+                    1
+                } else {
+                    expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as usize
+                };
+
+                (
+                    format!("FastNoise Lite ({:?}, {octaves} octaves)", expr.noise_ty),
+                    octaves * (expr.noise_ty.cost() + 4),
+                    vec![],
+                )
+            }
+            Self::Fault(expr) => (
+                "Fault".to_owned(),
+                expr.iterations.value() as usize * 10,
+                vec![],
+            ),
+            Self::Fbm(expr) => {
+                let (label, ops) =
+                    fractal("Fbm", expr.source_ty, &expr.octaves, &expr.octave_remainder);
+                (label, ops, vec![])
+            }
+            Self::Gradient(_) => ("Gradient".to_owned(), 10, vec![]),
+            Self::Heightmap(_) => ("Heightmap".to_owned(), 12, vec![]),
+            Self::HybridMulti(expr) => {
+                let (label, ops) = fractal(
+                    "Hybrid Multi",
+                    expr.source_ty,
+                    &expr.octaves,
+                    &expr.octave_remainder,
+                );
+                (label, ops + 2, vec![])
+            }
+            Self::Jitter(expr) => ("Jitter".to_owned(), 20, vec![&expr.source]),
+            Self::Log(expr) => ("Log".to_owned(), 20, vec![expr]),
+            Self::Max([source1, source2]) => ("Max".to_owned(), 1, vec![source1, source2]),
+            Self::Min([source1, source2]) => ("Min".to_owned(), 1, vec![source1, source2]),
+            Self::Mix(expr) => (
+                "Mix".to_owned(),
+                4,
+                vec![&expr.sources[0], &expr.sources[1], &expr.factor],
+            ),
+            Self::Multiply([source1, source2]) => {
+                ("Multiply".to_owned(), 1, vec![source1, source2])
+            }
+            Self::Negate(expr) => ("Negate".to_owned(), 1, vec![expr]),
+            Self::OpenSimplex(_) => (
+                "Open Simplex".to_owned(),
+                SourceType::OpenSimplex.cost(),
+                vec![],
+            ),
+            Self::OutputRange(expr) => (
+                format!("Output Range [{}, {}]", expr.range[0], expr.range[1]),
+                0,
+                vec![&expr.source],
+            ),
+            Self::Perlin(_) => ("Perlin".to_owned(), SourceType::Perlin.cost(), vec![]),
+            Self::PerlinSurflet(_) => (
+                "Perlin Surflet".to_owned(),
+                SourceType::PerlinSurflet.cost(),
+                vec![],
+            ),
+            Self::Plateau(expr) => ("Plateau".to_owned(), 8, vec![&expr.source]),
+            Self::Power([source1, source2]) => ("Power".to_owned(), 20, vec![source1, source2]),
+            Self::PowSignal([base, exponent]) => {
+                ("Pow Signal".to_owned(), 24, vec![base, exponent])
+            }
+            Self::Quantize(expr) => (
+                "Quantize".to_owned(),
+                if expr.dither.value() == 0.0 { 6 } else { 16 },
+                vec![&expr.source],
+            ),
+            Self::Rebase(expr) => ("Rebase".to_owned(), 12, vec![&expr.source]),
+            Self::Repeat(expr) => ("Repeat".to_owned(), 12, vec![&expr.source]),
+            Self::Ridge(expr) => ("Ridge".to_owned(), 24, vec![&expr.source]),
+            Self::RidgedMulti(expr) => {
+                let (label, ops) = fractal(
+                    "Ridged Multi",
+                    expr.source_ty,
+                    &expr.octaves,
+                    &expr.octave_remainder,
+                );
+                (label, ops + 6, vec![])
+            }
+            Self::RotatePoint(expr) => ("Rotate Point".to_owned(), 30, vec![&expr.source]),
+            Self::ScaleBias(expr) => ("Scale Bias".to_owned(), 2, vec![&expr.source]),
+            Self::ScalePoint(expr) => ("Scale Point".to_owned(), 3, vec![&expr.source]),
+            Self::Select(expr) => (
+                "Select".to_owned(),
+                12,
+                vec![&expr.sources[0], &expr.sources[1], &expr.control],
+            ),
+            Self::Simplex(_) => ("Simplex".to_owned(), SourceType::Simplex.cost(), vec![]),
+            Self::SuperSimplex(_) => (
+                "Super Simplex".to_owned(),
+                SourceType::SuperSimplex.cost(),
+                vec![],
+            ),
+            Self::Terrace(expr) => (
+                "Terrace".to_owned(),
+                10 + expr.control_points.len(),
+                vec![&expr.source],
+            ),
+
+            // Distance samples the source either side of the point along each axis, and hysteresis
+            // may sample it at every scan step when the point is within the band
+            Self::Threshold(expr) => {
+                let (label, evaluations) = match expr.mode {
+                    ThresholdMode::Distance => ("Threshold (Distance)".to_owned(), 6),
+                    ThresholdMode::Hysteresis => {
+                        let scan_steps = expr.scan_steps.value() as usize;
+
+                        (
+                            format!("Threshold (Hysteresis, up to {scan_steps} scan steps)"),
+                            scan_steps,
+                        )
+                    }
+                    ThresholdMode::Smoothstep => ("Threshold (Smoothstep)".to_owned(), 0),
+                };
+
+                (
+                    label,
+                    6 + evaluations * (expr.source.cost().total() + 4),
+                    vec![&expr.source],
+                )
+            }
+            Self::TranslatePoint(expr) => ("Translate Point".to_owned(), 3, vec![&expr.source]),
+
+            // Turbulence displaces each axis using a fractal of the source type
+            Self::Turbulence(expr) => {
+                let roughness = expr.roughness.value() as usize;
+
+                (
+                    format!("Turbulence ({:?}, {roughness} roughness)", expr.source_ty),
+                    3 * roughness * (expr.source_ty.cost() + 4) + 6,
+                    vec![&expr.source],
+                )
+            }
+            Self::Value(_) => ("Value".to_owned(), SourceType::Value.cost(), vec![]),
+            Self::WhiteNoise(_) => ("White Noise".to_owned(), 10, vec![]),
+            Self::Worley(_) => ("Worley".to_owned(), SourceType::Worley.cost(), vec![]),
+        };
+
+        ExprCost {
+            label,
+            ops,
+            children: children.into_iter().map(Self::cost).collect(),
+        }
+    }
+
+    fn curve(expr: &CurveExpr, backend: &dyn NoiseBackend) -> Box<dyn NoiseFn<f64, 3>> {
+        fn invalid_inputs(control_points: &[ControlPointExpr]) -> bool {
+            debug_assert!(control_points.len() >= 4);
+
+            type Inputs = Vec<OrderedFloat<f64>>;
+
+            thread_local! {
+                static INPUTS: RefCell<Option<Inputs>> = RefCell::new(Some(Vec::with_capacity(3)));
+            }
+
+            let mut inputs = INPUTS.take().unwrap();
+
+            for ControlPointExpr { input_value, .. } in control_points {
+                let input_value = OrderedFloat(input_value.value());
+                if let Err(idx) = inputs.binary_search(&input_value) {
+                    if inputs.len() == 3 {
+                        inputs.clear();
+                        INPUTS.set(Some(inputs));
+
+                        return false;
+                    }
+
+                    inputs.insert(idx, input_value);
+                }
+            }
+
+            inputs.clear();
+            INPUTS.set(Some(inputs));
+
+            true
+        }
+
+        // Make sure the control points are valid (noise-rs panics!)
+        if expr.control_points.len() < 4 || invalid_inputs(&expr.control_points) {
+            return Box::new(Constant::new(0.0));
+        }
+
+        let mut res = Curve::new(expr.source.noise_with(backend));
+
+        for control_point in &expr.control_points {
+            res = res.add_control_point(
+                control_point.input_value.value(),
+                control_point.output_value.value(),
+            );
+        }
+
+        Box::new(res)
+    }
+
+    fn fast_noise(expr: &FastNoiseExpr) -> Box<dyn NoiseFn<f64, 3>> {
+        let mut noise = FastNoiseLite::with_seed(expr.seed.value() as i32);
+        noise.set_noise_type(Some(match expr.noise_ty {
+            FastNoiseType::Cellular => NoiseType::Cellular,
+            FastNoiseType::OpenSimplex2 => NoiseType::OpenSimplex2,
+            FastNoiseType::OpenSimplex2S => NoiseType::OpenSimplex2S,
+            FastNoiseType::Perlin => NoiseType::Perlin,
+            FastNoiseType::Value => NoiseType::Value,
+            FastNoiseType::ValueCubic => NoiseType::ValueCubic,
+        }));
+        noise.set_fractal_type(Some(match expr.fractal_ty {
+            FastFractalType::None => FractalType::None,
+            FastFractalType::Fbm => FractalType::FBm,
+            FastFractalType::PingPong => FractalType::PingPong,
+            FastFractalType::Ridged => FractalType::Ridged,
+        }));
+        noise.set_rotation_type_3d(Some(match expr.rotation_ty {
+            FastRotationType::ImproveXYPlanes => RotationType3D::ImproveXYPlanes,
+            FastRotationType::ImproveXZPlanes => RotationType3D::ImproveXZPlanes,
+            FastRotationType::None => RotationType3D::None,
+        }));
+        noise.set_cellular_distance_function(Some(match expr.cellular_distance_fn {
+            CellularDistanceFunction::Euclidean => {
+                fastnoise_lite::CellularDistanceFunction::Euclidean
+            }
+            CellularDistanceFunction::EuclideanSq => {
+                fastnoise_lite::CellularDistanceFunction::EuclideanSq
+            }
+            CellularDistanceFunction::Hybrid => fastnoise_lite::CellularDistanceFunction::Hybrid,
+            CellularDistanceFunction::Manhattan => {
+                fastnoise_lite::CellularDistanceFunction::Manhattan
+            }
+        }));
+        noise.set_cellular_return_type(Some(match expr.cellular_return_ty {
+            CellularReturnType::CellValue => fastnoise_lite::CellularReturnType::CellValue,
+            CellularReturnType::Distance => fastnoise_lite::CellularReturnType::Distance,
+            CellularReturnType::Distance2 => fastnoise_lite::CellularReturnType::Distance2,
+            CellularReturnType::Distance2Add => fastnoise_lite::CellularReturnType::Distance2Add,
+            CellularReturnType::Distance2Div => fastnoise_lite::CellularReturnType::Distance2Div,
+            CellularReturnType::Distance2Mul => fastnoise_lite::CellularReturnType::Distance2Mul,
+            CellularReturnType::Distance2Sub => fastnoise_lite::CellularReturnType::Distance2Sub,
+        }));
+        noise.set_frequency(Some(expr.frequency.value() as _));
+        noise.set_fractal_octaves(Some(expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as _));
+        noise.set_fractal_lacunarity(Some(expr.lacunarity.value() as _));
+        noise.set_fractal_gain(Some(expr.gain.value() as _));
+        noise.set_fractal_weighted_strength(Some(expr.weighted_strength.value() as _));
+        noise.set_fractal_ping_pong_strength(Some(expr.ping_pong_strength.value() as _));
+        noise.set_cellular_jitter(Some(expr.cellular_jitter.value() as _));
+
+        Box::new(FastNoise::new(noise, expr.is_3d))
+    }
+
+    /// Builds the noise function of this expression using the default backend.
+    pub fn noise(&self) -> Box<dyn NoiseFn<f64, 3>> {
+        self.noise_with(Backend::default().get())
+    }
+
+    /// Builds the noise function of this expression, where the backend evaluates every seeded
+    /// generator and fractal.
+    pub fn noise_with(&self, backend: &dyn NoiseBackend) -> Box<dyn NoiseFn<f64, 3>> {
+        let _scope = BuildScope::enter();
+
+        match self {
+            Self::Abs(expr) => Box::new(Abs::new(expr.noise_with(backend))),
+            Self::Add([source1, source2]) => Box::new(Add::new(
+                source1.noise_with(backend),
+                source2.noise_with(backend),
+            )),
+            Self::Bake(expr) => Box::new(
+                expr.table
+                    .get_or_init(|| {
+                        Bake::new(
+                            &*expr.source.noise_with(backend),
+                            expr.resolution.value(),
+                            expr.size.value(),
+                        )
+                    })
+                    .clone(),
+            ),
+            Self::BasicMulti(expr) => backend.fractal(expr.params(FractalKind::BasicMulti)),
+            Self::Billow(expr) => backend.fractal(expr.params(FractalKind::Billow)),
+            Self::Blend(expr) => Box::new(Blend::new(
+                expr.sources[0].noise_with(backend),
+                expr.sources[1].noise_with(backend),
+                expr.control.noise_with(backend),
+            )),
+            Self::BlueNoise(seed) => Box::new(BlueNoise::new(seed.value())),
+            Self::Cache(expr) => Box::new(expr.noise_with(backend)),
+            Self::Checkerboard(size) => Box::new(Checkerboard::new(size.value() as _)),
+            Self::Clamp(expr) => Box::new(
+                Clamp::new(expr.source.noise_with(backend))
+                    .set_lower_bound(expr.lower_bound.value().min(expr.upper_bound.value()))
+                    .set_upper_bound(expr.lower_bound.value().max(expr.upper_bound.value())),
+            ),
+            Self::Constant(value) => Box::new(Constant::new(value.value())),
+            Self::ConstantU32(_) => unreachable!(),
+            Self::Curve(expr) => Self::curve(expr, backend),
+            Self::Cylinders(frequency) => {
+                Box::new(Cylinders::new().set_frequency(frequency.value()))
+            }
+            Self::Derivative(expr) => {
+                let derivative = match expr.source_ty {
+                    SourceType::Perlin => Derivative::perlin(expr.seed.value()),
+                    SourceType::Simplex => Derivative::simplex(expr.seed.value()),
+                    _ => return Box::new(Constant::new(0.0)),
+                };
+
+                Box::new(derivative.set_axis(match expr.channel {
+                    Channel::DerivativeX => Some(0),
+                    Channel::DerivativeY => Some(1),
+                    Channel::DerivativeZ => Some(2),
+                    Channel::Slope | Channel::Value => None,
+                }))
+            }
+            Self::Displace(expr) => Box::new(Displace::new(
+                expr.source.noise_with(backend),
+                expr.axes[0].noise_with(backend),
+                expr.axes[1].noise_with(backend),
+                expr.axes[2].noise_with(backend),
+                expr.axes[3].noise_with(backend),
+            )),
+            Self::Exp(expr) => Box::new(Exp::new(expr.noise_with(backend))),
+            Self::Exponent(expr) => Box::new(
+                Exponent::new(expr.source.noise_with(backend)).set_exponent(expr.exponent.value()),
+            ),
+            Self::FastNoise(expr) => Self::fast_noise(expr),
+            Self::Fault(expr) => Box::new(
+                Fault::new(expr.seed.value())
+                    .set_iterations(expr.iterations.value())
+                    .set_extent(expr.extent.value()),
+            ),
+            Self::Fbm(expr) => backend.fractal(expr.params(FractalKind::Fbm)),
+            Self::Heightmap(expr) => {
+                Box::new(Heightmap::new(Arc::clone(&expr.image), expr.size.value()))
+            }
+            Self::Gradient(expr) => Box::new(
+                Gradient::new()
+                    .set_angle(expr.angle.value())
+                    .set_offset(expr.offset.value())
+                    .set_wrap_function(match expr.wrap {
+                        WrapMode::Clamp => wrap_clamp,
+                        WrapMode::Mirror => wrap_mirror,
+                        WrapMode::Repeat => wrap_repeat,
+                    }),
+            ),
+            Self::HybridMulti(expr) => backend.fractal(expr.params(FractalKind::HybridMulti)),
+            Self::Jitter(expr) => Box::new(
+                Jitter::new(expr.source.noise_with(backend))
+                    .set_seed(expr.seed.value())
+                    .set_amount(expr.amount.value()),
+            ),
+            Self::Log(expr) => Box::new(Log::new(expr.noise_with(backend))),
+            Self::Max([source1, source2]) => Box::new(Max::new(
+                source1.noise_with(backend),
+                source2.noise_with(backend),
+            )),
+            Self::Min([source1, source2]) => Box::new(Min::new(
+                source1.noise_with(backend),
+                source2.noise_with(backend),
+            )),
+            Self::Mix(expr) => Box::new(Mix::new(
+                expr.sources[0].noise_with(backend),
+                expr.sources[1].noise_with(backend),
+                expr.factor.noise_with(backend),
+            )),
+            Self::Multiply([source1, source2]) => Box::new(Multiply::new(
+                source1.noise_with(backend),
+                source2.noise_with(backend),
+            )),
+            Self::Negate(expr) => Box::new(Negate::new(expr.noise_with(backend))),
+            Self::OpenSimplex(seed) => backend.source(SourceType::OpenSimplex, seed.value()),
+            Self::OutputRange(expr) => expr.source.noise_with(backend),
+            Self::Perlin(seed) => backend.source(SourceType::Perlin, seed.value()),
+            Self::PerlinSurflet(seed) => backend.source(SourceType::PerlinSurflet, seed.value()),
+            Self::Plateau(expr) => Box::new(
+                Plateau::new(expr.source.noise_with(backend))
+                    .set_level(expr.level.value())
+                    .set_softness(expr.softness.value()),
+            ),
+            Self::Power([source1, source2]) => Box::new(Power::new(
+                source1.noise_with(backend),
+                source2.noise_with(backend),
+            )),
+            Self::PowSignal([base, exponent]) => Box::new(PowSignal::new(
+                base.noise_with(backend),
+                exponent.noise_with(backend),
+            )),
+            Self::Quantize(expr) => Box::new(
+                Quantize::new(expr.source.noise_with(backend))
+                    .set_levels(expr.levels.value())
+                    .set_dither(expr.dither.value())
+                    .set_seed(expr.seed.value()),
+            ),
+            Self::Rebase(expr) => Box::new(
+                Rebase::new(expr.source.noise_with(backend)).set_period(expr.period.value()),
+            ),
+            Self::Repeat(expr) => Box::new(
+                Repeat::new(expr.source.noise_with(backend))
+                    .set_all_periods(
+                        expr.periods[0].value(),
+                        expr.periods[1].value(),
+                        expr.periods[2].value(),
+                    )
+                    .set_mirror(expr.mode == RepeatMode::Mirror),
+            ),
+            Self::Ridge(expr) => Box::new(
+                Ridge::new(expr.source.noise_with(backend))
+                    .set_sharpness(expr.sharpness.value())
+                    .set_offset(expr.offset.value()),
+            ),
+            Self::RidgedMulti(expr) => backend.fractal(expr.params()),
+            Self::RotatePoint(expr) => Box::new(
+                RotatePoint::new(expr.source.noise_with(backend)).set_angles(
+                    expr.axes[0].value(),
+                    expr.axes[1].value(),
+                    expr.axes[2].value(),
+                    expr.axes[3].value(),
+                ),
+            ),
+            Self::ScaleBias(expr) => Box::new(
+                ScaleBias::new(expr.source.noise_with(backend))
+                    .set_bias(expr.bias.value())
+                    .set_scale(expr.scale.value()),
+            ),
+            Self::ScalePoint(expr) => Box::new(
+                ScalePoint::new(expr.source.noise_with(backend)).set_all_scales(
+                    expr.axes[0].value(),
+                    expr.axes[1].value(),
+                    expr.axes[2].value(),
+                    expr.axes[3].value(),
+                ),
+            ),
+            Self::Select(expr) => Box::new(
+                Select::new(
+                    expr.sources[0].noise_with(backend),
+                    expr.sources[1].noise_with(backend),
+                    expr.control.noise_with(backend),
+                )
+                .set_bounds(expr.lower_bound.value(), expr.upper_bound.value())
+                .set_falloff(expr.falloff.value()),
+            ),
+            Self::Simplex(seed) => backend.source(SourceType::Simplex, seed.value()),
+            Self::SuperSimplex(seed) => backend.source(SourceType::SuperSimplex, seed.value()),
+            Self::Terrace(expr) => Self::terrace(expr, backend),
+            Self::Threshold(expr) => Box::new(
+                Threshold::new(expr.source.noise_with(backend))
+                    .set_distance(expr.mode == ThresholdMode::Distance)
+                    .set_off_threshold(expr.off_threshold.value())
+                    .set_on_threshold(expr.on_threshold.value())
+                    .set_scan_steps(expr.scan_steps.value())
+                    .set_smooth(expr.mode == ThresholdMode::Smoothstep),
+            ),
+            Self::TranslatePoint(expr) => Box::new(
+                TranslatePoint::new(expr.source.noise_with(backend)).set_all_translations(
+                    expr.axes[0].value(),
+                    expr.axes[1].value(),
+                    expr.axes[2].value(),
+                    expr.axes[3].value(),
+                ),
+            ),
+            Self::Turbulence(expr) => {
+                backend.turbulence(expr.source.noise_with(backend), expr.params())
+            }
+            Self::Value(seed) => backend.source(SourceType::Value, seed.value()),
+            Self::WhiteNoise(seed) => Box::new(WhiteNoise::new(seed.value())),
+            Self::Worley(expr) => backend.worley(
+                expr.seed.value(),
+                expr.frequency.value(),
+                expr.distance_fn,
+                expr.return_ty,
+            ),
+        }
+    }
+
+    #[allow(unused)]
+    pub fn set_f64(&mut self, name: &str, value: f64) -> &mut Self {
+        match self {
+            Self::Abs(expr) | Self::Exp(expr) | Self::Log(expr) | Self::Negate(expr) => {
+                expr.set_f64(name, value);
+            }
+            Self::Add(exprs)
+            | Self::Max(exprs)
+            | Self::Min(exprs)
+            | Self::Multiply(exprs)
+            | Self::Power(exprs)
+            | Self::PowSignal(exprs) => exprs.iter_mut().for_each(|expr| {
+                expr.set_f64(name, value);
+            }),
+            Self::Bake(expr) => expr.set_f64(name, value),
+            Self::BasicMulti(expr)
+            | Self::Billow(expr)
+            | Self::Fbm(expr)
+            | Self::HybridMulti(expr) => expr.set_f64(name, value),
+            Self::Blend(expr) => expr.set_f64(name, value),
+            Self::Cache(expr) => expr.set_f64(name, value),
+            Self::Clamp(expr) => expr.set_f64(name, value),
+            Self::Constant(expr) | Self::Cylinders(expr) => expr.set_if_named(name, value),
+            Self::Curve(expr) => expr.set_f64(name, value),
+            Self::Displace(expr) => expr.set_f64(name, value),
+            Self::Exponent(expr) => expr.set_f64(name, value),
+            Self::FastNoise(expr) => expr.set_f64(name, value),
+            Self::Fault(expr) => expr.set_f64(name, value),
+            Self::Gradient(expr) => expr.set_f64(name, value),
+            Self::Heightmap(expr) => expr.size.set_if_named(name, value),
+            Self::Jitter(expr) => expr.set_f64(name, value),
+            Self::Mix(expr) => expr.set_f64(name, value),
+            Self::OutputRange(expr) => expr.set_f64(name, value),
+            Self::Plateau(expr) => expr.set_f64(name, value),
+            Self::Quantize(expr) => expr.set_f64(name, value),
+            Self::Rebase(expr) => expr.set_f64(name, value),
+            Self::Repeat(expr) => expr.set_f64(name, value),
+            Self::Ridge(expr) => expr.set_f64(name, value),
+            Self::RidgedMulti(expr) => expr.set_f64(name, value),
+            Self::RotatePoint(expr) | Self::ScalePoint(expr) | Self::TranslatePoint(expr) => {
+                expr.set_f64(name, value)
+            }
+            Self::ScaleBias(expr) => expr.set_f64(name, value),
+            Self::Select(expr) => expr.set_f64(name, value),
+            Self::Terrace(expr) => expr.set_f64(name, value),
+            Self::Threshold(expr) => expr.set_f64(name, value),
+            Self::Turbulence(expr) => expr.set_f64(name, value),
+            Self::Worley(expr) => expr.set_f64(name, value),
+            Self::BlueNoise(_)
+            | Self::Checkerboard(_)
+            | Self::ConstantU32(_)
+            | Self::Derivative(_)
+            | Self::Gradient(_)
+            | Self::OpenSimplex(_)
+            | Self::Perlin(_)
+            | Self::PerlinSurflet(_)
+            | Self::Simplex(_)
+            | Self::SuperSimplex(_)
+            | Self::Value(_)
+            | Self::WhiteNoise(_) => (),
+        }
+
+        self
+    }
+
+    #[allow(unused)]
+    pub fn set_u32(&mut self, name: &str, value: u32) -> &mut Self {
+        match self {
+            Self::Abs(expr) | Self::Exp(expr) | Self::Log(expr) | Self::Negate(expr) => {
+                expr.set_u32(name, value);
+            }
+            Self::Add(exprs)
+            | Self::Max(exprs)
+            | Self::Min(exprs)
+            | Self::Multiply(exprs)
+            | Self::Power(exprs)
+            | Self::PowSignal(exprs) => exprs.iter_mut().for_each(|expr| {
+                expr.set_u32(name, value);
+            }),
+            Self::Bake(expr) => expr.set_u32(name, value),
+            Self::BasicMulti(expr)
+            | Self::Billow(expr)
+            | Self::Fbm(expr)
+            | Self::HybridMulti(expr) => expr.set_u32(name, value),
+            Self::Blend(expr) => expr.set_u32(name, value),
+            Self::Cache(expr) => expr.set_u32(name, value),
+            Self::BlueNoise(expr)
+            | Self::Checkerboard(expr)
+            | Self::ConstantU32(expr)
+            | Self::OpenSimplex(expr)
+            | Self::Perlin(expr)
+            | Self::PerlinSurflet(expr)
+            | Self::Simplex(expr)
+            | Self::SuperSimplex(expr)
+            | Self::Value(expr)
+            | Self::WhiteNoise(expr) => expr.set_if_named(name, value),
+            Self::Clamp(expr) => expr.set_u32(name, value),
+            Self::Curve(expr) => expr.set_u32(name, value),
+            Self::Derivative(expr) => expr.set_u32(name, value),
+            Self::Displace(expr) => expr.set_u32(name, value),
+            Self::Exponent(expr) => expr.set_u32(name, value),
+            Self::FastNoise(expr) => expr.set_u32(name, value),
+            Self::Fault(expr) => expr.set_u32(name, value),
+            Self::Jitter(expr) => expr.set_u32(name, value),
+            Self::Mix(expr) => expr.set_u32(name, value),
+            Self::OutputRange(expr) => expr.set_u32(name, value),
+            Self::Plateau(expr) => expr.set_u32(name, value),
+            Self::Quantize(expr) => expr.set_u32(name, value),
+            Self::Rebase(expr) => expr.set_u32(name, value),
+            Self::Repeat(expr) => expr.set_u32(name, value),
+            Self::Ridge(expr) => expr.set_u32(name, value),
+            Self::RidgedMulti(expr) => expr.set_u32(name, value),
+            Self::RotatePoint(expr) | Self::ScalePoint(expr) | Self::TranslatePoint(expr) => {
+                expr.set_u32(name, value)
+            }
+            Self::Select(expr) => expr.set_u32(name, value),
+            Self::ScaleBias(expr) => expr.set_u32(name, value),
+            Self::Terrace(expr) => expr.set_u32(name, value),
+            Self::Threshold(expr) => expr.set_u32(name, value),
+            Self::Turbulence(expr) => expr.set_u32(name, value),
+            Self::Worley(expr) => expr.set_u32(name, value),
+            Self::Constant(_) | Self::Cylinders(_) | Self::Gradient(_) | Self::Heightmap(_) => (),
+        }
+
+        self
+    }
+
+    fn terrace(expr: &TerraceExpr, backend: &dyn NoiseBackend) -> Box<dyn NoiseFn<f64, 3>> {
+        fn invalid_inputs(control_points: &[Variable<f64>]) -> bool {
+            debug_assert!(control_points.len() >= 2);
+
+            let first_input = OrderedFloat(control_points[0].value());
+
+            for input_value in &control_points[1..] {
+                let input_value = OrderedFloat(input_value.value());
+                if input_value != first_input {
+                    return false;
+                }
+            }
+
+            true
+        }
+
+        // Make sure the control points are valid (noise-rs panics!)
+        if expr.control_points.len() < 2 || invalid_inputs(&expr.control_points) {
+            return Box::new(Constant::new(0.0));
+        }
+
+        let mut res = Terrace::new(expr.source.noise_with(backend)).invert_terraces(expr.inverted);
+
+        for control_point in expr.control_points.iter() {
+            res = res.add_control_point(control_point.value());
+        }
+
+        Box::new(res)
+    }
+
+    /// Generates GLSL which declares `float noise_graph(vec3 p)`, sampling this expression as the
+    /// `noise` crate backend does, for use within shaders.
+    ///
+    /// Named decimal variables become `uniform float` declarations; named integer variables decide
+    /// seeds and loop counts, so they are written as literals instead. The header comment breaks
+    /// down the [`Expr::cost`] of the expression, as does that of WGSL and Rust.
+    pub fn to_glsl(&self) -> Result<String, CodegenError> {
+        codegen::glsl(self)
+    }
+
+    /// Generates WGSL which declares `fn noise_graph(p: vec3<f32>) -> f32`, sampling this
+    /// expression like [`Expr::to_glsl`], followed by a `sample_grid` compute shader which fills a
+    /// storage buffer with a grid of samples.
+    ///
+    /// Named decimal variables become the fields of a `Params` uniform struct (group 0, binding 0)
+    /// so they may be changed without generating the shader again.
+    pub fn to_wgsl(&self) -> Result<String, CodegenError> {
+        codegen::wgsl(self, false).map(|(wgsl, _)| wgsl)
+    }
+
+    /// Generates WGSL like [`Expr::to_wgsl`], along with the values of the fields of the `Params`
+    /// struct in the order they are declared, which fill its uniform buffer as the editor has them.
+    ///
+    /// The values are empty when the expression has no named decimal variables, in which case the
+    /// shader declares no `Params` struct.
+    pub fn to_wgsl_with_params(&self) -> Result<(String, Vec<f32>), CodegenError> {
+        codegen::wgsl(self, false)
+    }
+
+    /// Generates WGSL like [`Expr::to_wgsl_with_params`], except that every decimal setting,
+    /// named or not, becomes a field of `Params`, so the shader is the same for expressions which
+    /// differ only by their decimal settings and one compiled pipeline renders them all.
+    pub fn to_wgsl_with_all_params(&self) -> Result<(String, Vec<f32>), CodegenError> {
+        codegen::wgsl(self, true)
+    }
+
+    /// Generates Rust which declares `fn noise_graph() -> impl NoiseFn<f64, 3>`, building this
+    /// expression with the `noise` crate as the default backend does.
+    ///
+    /// Every setting is written as a literal, unless [`Expr::to_rust_with`] writes seeds and octave
+    /// counts as parameters. Nodes which the `noise` crate does not have are built from
+    /// [`noise_fns`](crate::noise_fns), so the generated code may depend on this crate.
+    pub fn to_rust(&self) -> Result<String, CodegenError> {
+        codegen::rust(self, RustParameters::Literals)
+    }
+
+    /// Generates Rust like [`Expr::to_rust`], writing seeds and the octave counts of fractals as
+    /// given, so one function may build the noise of many world seeds.
+    ///
+    /// Parameters replace the first seed and octave count written; the others are offset from
+    /// them, so the values of this expression are built when the parameters are those values.
+    pub fn to_rust_with(&self, parameters: RustParameters) -> Result<String, CodegenError> {
+        codegen::rust(self, parameters)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JitterExpr {
+    pub source: Box<Expr>,
+
+    pub seed: Variable<u32>,
+    pub amount: Variable<f64>,
+}
+
+impl JitterExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.amount.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.seed.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MixExpr {
+    pub sources: [Box<Expr>; 2],
+    pub factor: Box<Expr>,
+}
+
+impl MixExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+        self.factor.set_f64(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+        self.factor.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OpType {
+    Add,
+    Divide,
+    Multiply,
+    Subtract,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OutputRangeExpr {
+    pub source: Box<Expr>,
+
+    /// The lower and upper bound of the values of the source, inclusive.
+    pub range: [f64; 2],
+}
+
+impl OutputRangeExpr {
+    /// Returns `true` if a value of the source is within the declared range.
+    pub fn contains(&self, value: f64) -> bool {
+        (self.range[0]..=self.range[1]).contains(&value)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PlateauExpr {
+    pub source: Box<Expr>,
+
+    pub level: Variable<f64>,
+    pub softness: Variable<f64>,
+}
+
+impl PlateauExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.level.set_if_named(name, value);
+        self.softness.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct QuantizeExpr {
+    pub source: Box<Expr>,
+
+    pub levels: Variable<u32>,
+    pub dither: Variable<f64>,
+
+    #[serde(default = "QuantizeExpr::default_seed")]
+    pub seed: Variable<u32>,
+}
+
+impl QuantizeExpr {
+    fn default_seed() -> Variable<u32> {
+        Variable::Anonymous(Quantize::<Constant>::DEFAULT_SEED)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.dither.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.levels.set_if_named(name, value);
+        self.seed.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RebaseExpr {
+    pub source: Box<Expr>,
+
+    pub period: Variable<f64>,
+}
+
+impl RebaseExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.period.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RepeatExpr {
+    pub source: Box<Expr>,
+
+    pub mode: RepeatMode,
+    pub periods: [Variable<f64>; 3],
+}
+
+impl RepeatExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.periods
+            .iter_mut()
+            .for_each(|period| period.set_if_named(name, value));
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RepeatMode {
+    Mirror,
+    Wrap,
+}
+
+impl RepeatMode {
+    pub const ALL: [Self; 2] = [Self::Mirror, Self::Wrap];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mirror => "Mirror",
+            Self::Wrap => "Wrap",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReturnType {
+    Distance,
+    Value,
+}
+
+impl ReturnType {
+    pub const ALL: [Self; 2] = [Self::Distance, Self::Value];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Distance => "Distance",
+            Self::Value => "Value",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RidgeExpr {
+    pub source: Box<Expr>,
+
+    pub sharpness: Variable<f64>,
+    pub offset: Variable<f64>,
+}
+
+impl RidgeExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.sharpness.set_if_named(name, value);
+        self.offset.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RigidFractalExpr {
+    pub source_ty: SourceType,
+    pub seed: Variable<u32>,
+    pub octaves: Variable<u32>,
+    pub frequency: Variable<f64>,
+    pub lacunarity: Variable<f64>,
+    pub persistence: Variable<f64>,
+    pub attenuation: Variable<f64>,
+
+    /// The weight of one more octave after the whole ones.
+    #[serde(default = "FractalExpr::default_octave_remainder")]
+    pub octave_remainder: Variable<f64>,
+}
+
+impl RigidFractalExpr {
+    fn params(&self) -> FractalParams {
+        FractalParams {
+            kind: FractalKind::RidgedMulti,
+            source_ty: self.source_ty,
+            seed: self.seed.value(),
+            octaves: self.octaves.value(),
+            frequency: self.frequency.value(),
+            lacunarity: self.lacunarity.value(),
+            persistence: self.persistence.value(),
+            attenuation: self.attenuation.value(),
+            octave_remainder: self.octave_remainder.value().clamp(0.0, 1.0),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.frequency.set_if_named(name, value);
+        self.lacunarity.set_if_named(name, value);
+        self.persistence.set_if_named(name, value);
+        self.attenuation.set_if_named(name, value);
+        self.octave_remainder.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+        self.octaves.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ScaleBiasExpr {
+    pub source: Box<Expr>,
+
+    pub scale: Variable<f64>,
+    pub bias: Variable<f64>,
+}
+
+impl ScaleBiasExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.scale.set_if_named(name, value);
+        self.bias.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SelectExpr {
+    pub sources: [Box<Expr>; 2],
+    pub control: Box<Expr>,
+
+    pub lower_bound: Variable<f64>,
+    pub upper_bound: Variable<f64>,
+    pub falloff: Variable<f64>,
+}
+
+impl SelectExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+        self.control.set_f64(name, value);
+        self.lower_bound.set_if_named(name, value);
+        self.upper_bound.set_if_named(name, value);
+        self.falloff.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+        self.control.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SourceType {
+    OpenSimplex,
+    #[default]
+    Perlin,
+    PerlinSurflet,
+    Simplex,
+    SuperSimplex,
+    Value,
+    Worley,
+}
+
+impl SourceType {
+    pub const ALL: [Self; 7] = [
+        Self::OpenSimplex,
+        Self::Perlin,
+        Self::PerlinSurflet,
+        Self::Simplex,
+        Self::SuperSimplex,
+        Self::Value,
+        Self::Worley,
+    ];
+
+    /// The estimated operations of one three-dimensional sample, relative to an addition.
+    fn cost(self) -> usize {
+        match self {
+            Self::OpenSimplex => 110,
+            Self::Perlin => 60,
+            Self::PerlinSurflet => 70,
+            Self::Simplex => 70,
+            Self::SuperSimplex => 140,
+            Self::Value => 40,
+            Self::Worley => 400,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::OpenSimplex => "OpenSimplex",
+            Self::Perlin => "Perlin",
+            Self::PerlinSurflet => "PerlinSurflet",
+            Self::Simplex => "Simplex",
+            Self::SuperSimplex => "SuperSimplex",
+            Self::Value => "Value",
+            Self::Worley => "Worley",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TerraceExpr {
+    pub source: Box<Expr>,
+
+    pub inverted: bool,
+    pub control_points: Vec<Variable<f64>>,
+}
+
+impl TerraceExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.control_points
+            .iter_mut()
+            .for_each(|control_point| control_point.set_if_named(name, value));
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ThresholdExpr {
+    pub source: Box<Expr>,
+
+    pub mode: ThresholdMode,
+    pub off_threshold: Variable<f64>,
+    pub on_threshold: Variable<f64>,
+
+    /// The most samples taken along the X axis while resolving the hysteresis band.
+    #[serde(default = "ThresholdExpr::default_scan_steps")]
+    pub scan_steps: Variable<u32>,
+}
+
+impl ThresholdExpr {
+    fn default_scan_steps() -> Variable<u32> {
+        Variable::Anonymous(Threshold::<Constant>::DEFAULT_SCAN_STEPS)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.off_threshold.set_if_named(name, value);
+        self.on_threshold.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.scan_steps.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ThresholdMode {
+    /// The signed distance to the boundary between the thresholds, instead of a mask.
+    Distance,
+
+    Hysteresis,
+    Smoothstep,
+}
+
+impl ThresholdMode {
+    pub const ALL: [Self; 3] = [Self::Distance, Self::Hysteresis, Self::Smoothstep];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Distance => "Distance",
+            Self::Hysteresis => "Hysteresis",
+            Self::Smoothstep => "Smoothstep",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TransformExpr {
+    pub source: Box<Expr>,
+
+    pub axes: [Variable<f64>; 4],
+}
+
+impl TransformExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.axes
+            .iter_mut()
+            .for_each(|axis| axis.set_if_named(name, value));
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TurbulenceExpr {
+    pub source: Box<Expr>,
+
+    pub source_ty: SourceType,
+    pub seed: Variable<u32>,
+    pub frequency: Variable<f64>,
+    pub power: Variable<f64>,
+    pub roughness: Variable<u32>,
+
+    /// Per-axis overrides of `frequency`.
+    #[serde(default)]
+    pub axis_frequencies: Option<[Variable<f64>; 3]>,
+
+    /// Per-axis overrides of `power`.
+    #[serde(default)]
+    pub axis_powers: Option<[Variable<f64>; 3]>,
+}
+
+impl TurbulenceExpr {
+    fn params(&self) -> TurbulenceParams {
+        fn axis_values(vars: &Option<[Variable<f64>; 3]>) -> Option<[f64; 3]> {
+            vars.as_ref()
+                .map(|vars| vars.each_ref().map(|var| var.value()))
+        }
+
+        TurbulenceParams {
+            source_ty: self.source_ty,
+            seed: self.seed.value(),
+            frequency: self.frequency.value(),
+            power: self.power.value(),
+            roughness: self.roughness.value(),
+            axis_frequencies: axis_values(&self.axis_frequencies),
+            axis_powers: axis_values(&self.axis_powers),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.frequency.set_if_named(name, value);
+        self.power.set_if_named(name, value);
+        self.axis_frequencies
+            .iter_mut()
+            .chain(self.axis_powers.iter_mut())
+            .flatten()
+            .for_each(|var| var.set_if_named(name, value));
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.seed.set_if_named(name, value);
+        self.roughness.set_if_named(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Variable<T> {
+    #[serde(rename = "Value")]
+    Anonymous(T),
+
+    #[serde(rename = "Variable")]
+    Named(String, T),
+
+    Operation([Box<Self>; 2], OpType),
+}
+
+impl<T> Variable<T> {
+    fn set_if_named(&mut self, name: &str, value: T)
+    where
+        T: Copy,
+    {
+        match self {
+            Self::Anonymous(_) => (),
+            Self::Named(named, valued) => {
+                if named == name {
+                    *valued = value;
+                }
+            }
+            Self::Operation(vars, _) => {
+                vars.iter_mut()
+                    .for_each(|var| var.set_if_named(name, value));
+            }
+        }
+    }
+}
+
+impl Variable<f64> {
+    pub fn value(&self) -> f64 {
+        match self {
+            Self::Anonymous(value) | Self::Named(_, value) => *value,
+            Self::Operation(vars, op) => {
+                let (lhs, rhs) = (vars[0].value(), vars[1].value());
+                match op {
+                    OpType::Add => lhs + rhs,
+                    OpType::Divide => {
+                        if rhs != 0.0 {
+                            lhs / rhs
+                        } else {
+                            0.0
+                        }
+                    }
+                    OpType::Multiply => lhs * rhs,
+                    OpType::Subtract => lhs - rhs,
+                }
+            }
+        }
+    }
+}
+
+impl Variable<u32> {
+    pub fn value(&self) -> u32 {
+        match self {
+            Self::Anonymous(value) | Self::Named(_, value) => *value,
+            Self::Operation(vars, op) => {
+                let (lhs, rhs) = (vars[0].value(), vars[1].value());
+                match op {
+                    OpType::Add => lhs.checked_add(rhs),
+                    OpType::Divide => lhs.checked_div(rhs),
+                    OpType::Multiply => lhs.checked_mul(rhs),
+                    OpType::Subtract => lhs.checked_sub(rhs),
+                }
+                .unwrap_or_default()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WrapMode {
+    Clamp,
+    Mirror,
+    Repeat,
+}
+
+impl WrapMode {
+    pub const ALL: [Self; 3] = [Self::Clamp, Self::Mirror, Self::Repeat];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Clamp => "Clamp",
+            Self::Mirror => "Mirror",
+            Self::Repeat => "Repeat",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorleyExpr {
+    pub seed: Variable<u32>,
+    pub frequency: Variable<f64>,
+    pub distance_fn: DistanceFunction,
+    pub return_ty: ReturnType,
+}
+
+impl WorleyExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.frequency.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+    }
+}
@@ -0,0 +1,121 @@
+//! Loads the expressions which `noise_gui` exports and samples them, without any of the
+//! dependencies of the editor, so that games can evaluate designed graphs at runtime.
+//!
+//! ```no_run
+//! let src = std::fs::read_to_string("terrain.ron").unwrap();
+//! let mut graph = noise_graph::Graph::from_ron(&src).unwrap();
+//! let value = graph.sample(0.0, 0.0, 0.0);
+//!
+//! graph.set_f64("my-var", 42.0);
+//! let updated_value = graph.sample(0.0, 0.0, 0.0);
+//! ```
+
+pub mod backend;
+
+#[cfg(feature = "bevy")]
+pub mod bevy;
+
+pub mod codegen;
+pub mod expr;
+pub mod noise_fns;
+
+pub use self::{
+    backend::*,
+    codegen::{CodegenError, RustParameters},
+    expr::*,
+};
+
+use {noise::NoiseFn, ron::error::SpannedError, std::str::FromStr};
+
+/// An exported expression along with the noise function which samples it.
+///
+/// A graph is neither `Send` nor `Sync`, as cached sources are shared within its noise function by
+/// reference counting. Threads which sample the same expression should each build a graph from a
+/// clone of [`expr`](Self::expr).
+pub struct Graph {
+    backend: Backend,
+    expr: Expr,
+    noise: Box<dyn NoiseFn<f64, 3>>,
+}
+
+impl Graph {
+    /// Reads an expression exported by right-clicking a node in the editor.
+    pub fn from_ron(s: &str) -> Result<Self, SpannedError> {
+        ron::from_str(s).map(Self::new)
+    }
+
+    pub fn new(expr: Expr) -> Self {
+        Self::with_backend(expr, Backend::default())
+    }
+
+    /// Uses the backend of the project the expression was exported from, which the same seeds
+    /// differ between.
+    pub fn with_backend(expr: Expr, backend: Backend) -> Self {
+        let noise = expr.noise_with(backend.get());
+
+        Self {
+            backend,
+            expr,
+            noise,
+        }
+    }
+
+    /// Returns the position of a sample of a chunk along one axis, for chunks of `size` samples
+    /// which are `chunk_len` units wide and share their first and last samples with the adjacent
+    /// chunks.
+    ///
+    /// The position is computed from the index of the sample in the whole world, so that both
+    /// chunks of a shared border sample exactly the same position. Adding the offset of a sample
+    /// to the origin of its chunk instead rounds differently in each chunk.
+    pub fn chunk_position(chunk: i64, idx: usize, size: usize, chunk_len: f64) -> f64 {
+        let intervals = size.saturating_sub(1).max(1);
+
+        (chunk * intervals as i64 + idx as i64) as f64 * (chunk_len / intervals as f64)
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// Returns the value at a point, which is identical to the value shown by the editor.
+    pub fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.noise.get([x, y, z])
+    }
+
+    /// Samples a chunk of `size` by `size` values in rows, where the value at `(x, y)` is sampled
+    /// at the [chunk positions](Self::chunk_position) of `x` and `y`.
+    pub fn sample_chunk(&self, chunk: [i64; 2], size: usize, chunk_len: f64) -> Vec<f64> {
+        let positions = |chunk| {
+            (0..size)
+                .map(move |idx| Self::chunk_position(chunk, idx, size, chunk_len))
+                .collect::<Vec<_>>()
+        };
+        let (xs, ys) = (positions(chunk[0]), positions(chunk[1]));
+
+        ys.iter()
+            .flat_map(|&y| xs.iter().map(move |&x| self.sample(x, y, 0.0)))
+            .collect()
+    }
+
+    /// Replaces the value of every decimal constant with the given name.
+    pub fn set_f64(&mut self, name: &str, value: f64) -> &mut Self {
+        self.expr.set_f64(name, value);
+        self.noise = self.expr.noise_with(self.backend.get());
+        self
+    }
+
+    /// Replaces the value of every integer constant with the given name.
+    pub fn set_u32(&mut self, name: &str, value: u32) -> &mut Self {
+        self.expr.set_u32(name, value);
+        self.noise = self.expr.noise_with(self.backend.get());
+        self
+    }
+}
+
+impl FromStr for Graph {
+    type Err = SpannedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_ron(s)
+    }
+}
@@ -0,0 +1,1514 @@
+use {
+    super::{backend::FractalKind, expr::MAX_FRACTAL_OCTAVES},
+    fastnoise_lite::FastNoiseLite,
+    noise::{
+        core::simplex::simplex_3d,
+        permutationtable::{NoiseHasher, PermutationTable},
+        Cache, Fbm, MultiFractal, NoiseFn, RidgedMulti, Seedable,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        array::from_fn,
+        fmt::{Debug, Formatter},
+        rc::Rc,
+        sync::Arc,
+    },
+};
+
+/// Hashes an integer cell coordinate into a pseudo-random value which is stable across platforms.
+///
+/// The `salt` parameter allows a single cell to produce multiple uncorrelated values.
+fn hash(seed: u32, cell: [i64; 3], salt: u32) -> u32 {
+    let mut res = seed.wrapping_mul(0x9e37_79b9) ^ salt.wrapping_mul(0x85eb_ca6b);
+
+    for (coord, prime) in cell.iter().zip([0x27d4_eb2d_u64, 0x1656_67b1, 0xc2b2_ae35]) {
+        let coord = (*coord as u64).wrapping_mul(prime);
+        res ^= (coord ^ (coord >> 32)) as u32;
+        res = res.rotate_left(13).wrapping_mul(0x5bd1_e995);
+    }
+
+    // Final avalanche (murmur3 fmix32)
+    res ^= res >> 16;
+    res = res.wrapping_mul(0x85eb_ca6b);
+    res ^= res >> 13;
+    res = res.wrapping_mul(0xc2b2_ae35);
+    res ^ (res >> 16)
+}
+
+/// Hashes an integer cell coordinate into the range [-1, 1].
+fn hash_signed(seed: u32, cell: [i64; 3], salt: u32) -> f64 {
+    hash(seed, cell, salt) as f64 / u32::MAX as f64 * 2.0 - 1.0
+}
+
+fn floor_cell(point: [f64; 3]) -> [i64; 3] {
+    point.map(|coord| coord.floor() as i64)
+}
+
+/// Noise function that randomly displaces the input value before returning the output value from
+/// the source function, using a separate frequency and power for each axis.
+///
+/// This matches `Turbulence` when every axis uses the same frequency and power.
+pub struct AxisTurbulence<Source, F>
+where
+    F: Default + Seedable,
+{
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// The scale of the displacement along each axis.
+    pub powers: [f64; 3],
+
+    distort_functions: [Fbm<F>; 3],
+}
+
+impl<Source, F> AxisTurbulence<Source, F>
+where
+    F: Default + Seedable,
+{
+    pub fn new(
+        source: Source,
+        seed: u32,
+        frequencies: [f64; 3],
+        powers: [f64; 3],
+        roughness: usize,
+    ) -> Self {
+        Self {
+            source,
+            powers,
+            distort_functions: from_fn(|axis| {
+                Fbm::default()
+                    .set_seed(seed.wrapping_add(axis as _))
+                    .set_octaves(roughness)
+                    .set_frequency(frequencies[axis])
+            }),
+        }
+    }
+}
+
+impl<Source, F> NoiseFn<f64, 3> for AxisTurbulence<Source, F>
+where
+    Source: NoiseFn<f64, 3>,
+    F: Default + Seedable + NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        // The same offsets as `Turbulence` so that the output matches for uniform axes
+        const OFFSETS: [[f64; 3]; 3] = [
+            [12414.0, 65124.0, 31337.0],
+            [26519.0, 18128.0, 60943.0],
+            [53820.0, 11213.0, 44845.0],
+        ];
+
+        self.source.get(from_fn(|axis| {
+            let offset_point = from_fn(|idx| point[idx] + OFFSETS[axis][idx] / 65536.0);
+
+            point[axis] + self.distort_functions[axis].get(offset_point) * self.powers[axis]
+        }))
+    }
+}
+
+/// Noise function that outputs the value of a lookup table of samples taken from a source
+/// function, bilinearly interpolated between samples.
+///
+/// The table covers a square of `size` units along the x and y axes starting at the origin and
+/// repeats outside of it; the z coordinate is ignored. Sampling the table is much cheaper than an
+/// expensive source function, at the cost of detail finer than `size / resolution` units.
+#[derive(Clone)]
+pub struct Bake {
+    /// Number of samples along each side of the table.
+    pub resolution: u32,
+
+    /// Width and height of the area covered by the table.
+    pub size: f64,
+
+    samples: Arc<[f64]>,
+}
+
+impl Bake {
+    pub const DEFAULT_RESOLUTION: u32 = 256;
+    pub const DEFAULT_SIZE: f64 = 4.0;
+    pub const MAX_RESOLUTION: u32 = 4096;
+
+    /// Evaluates the source function at the center of each table cell.
+    pub fn new<Source>(source: &Source, resolution: u32, size: f64) -> Self
+    where
+        Source: NoiseFn<f64, 3> + ?Sized,
+    {
+        let resolution = resolution.clamp(1, Self::MAX_RESOLUTION);
+        let step = size / resolution as f64;
+        let samples = (0..resolution)
+            .flat_map(|y| (0..resolution).map(move |x| (x, y)))
+            .map(|(x, y)| source.get([(x as f64 + 0.5) * step, (y as f64 + 0.5) * step, 0.0]))
+            .collect();
+
+        Self {
+            resolution,
+            size,
+            samples,
+        }
+    }
+}
+
+impl NoiseFn<f64, 3> for Bake {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        if self.size <= 0.0 {
+            return 0.0;
+        }
+
+        // Sample positions are at the cell centers, so the table is offset by half a cell
+        let cell = |coord: f64| coord / self.size * self.resolution as f64 - 0.5;
+
+        bilinear(
+            [self.resolution; 2],
+            [cell(point[0]), cell(point[1])],
+            |idx| self.samples[idx],
+        )
+    }
+}
+
+/// Returns a grid of `size` samples in rows, which repeats, bilinearly interpolated at a position
+/// measured in samples.
+fn bilinear(size: [u32; 2], point: [f64; 2], sample: impl Fn(usize) -> f64) -> f64 {
+    let [width, height] = size.map(i64::from);
+    let sample = |x: i64, y: i64| {
+        let x = x.rem_euclid(width) as usize;
+        let y = y.rem_euclid(height) as usize;
+
+        sample(y * width as usize + x)
+    };
+
+    let [x, y] = point;
+    let (cell_x, cell_y) = (x.floor(), y.floor());
+    let (fract_x, fract_y) = (x - cell_x, y - cell_y);
+    let (cell_x, cell_y) = (cell_x as i64, cell_y as i64);
+
+    let top = sample(cell_x, cell_y) * (1.0 - fract_x) + sample(cell_x + 1, cell_y) * fract_x;
+    let bottom =
+        sample(cell_x, cell_y + 1) * (1.0 - fract_x) + sample(cell_x + 1, cell_y + 1) * fract_x;
+
+    top * (1.0 - fract_y) + bottom * fract_y
+}
+
+/// Noise function that outputs the brightness of a grayscale image, such as a heightmap painted in
+/// another program, from -1 for black to 1 for white, bilinearly interpolated between pixels.
+///
+/// The image covers `size` units along the x axis, and its height in proportion along the y axis,
+/// starting at the origin and repeats outside of it; the z coordinate is ignored. The first row of
+/// the image is at the lowest y.
+#[derive(Clone)]
+pub struct Heightmap {
+    /// Width of the area covered by the image.
+    pub size: f64,
+
+    image: Arc<HeightmapImage>,
+}
+
+impl Heightmap {
+    pub const DEFAULT_SIZE: f64 = 4.0;
+
+    pub fn new(image: Arc<HeightmapImage>, size: f64) -> Self {
+        Self { size, image }
+    }
+}
+
+impl NoiseFn<f64, 3> for Heightmap {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let HeightmapImage {
+            width,
+            height,
+            samples,
+        } = &*self.image;
+
+        if self.size <= 0.0 || samples.is_empty() {
+            return 0.0;
+        }
+
+        // Pixels are sampled at their centers, as baked tables are
+        let pixel = |coord: f64| coord / self.size * *width as f64 - 0.5;
+
+        bilinear(
+            [*width, *height],
+            [pixel(point[0]), pixel(point[1])],
+            |idx| samples[idx] as f64 / u16::MAX as f64 * 2.0 - 1.0,
+        )
+    }
+}
+
+/// The pixels of a grayscale image in rows, from 0 for black to `u16::MAX` for white.
+#[derive(Clone, Default, Deserialize, PartialEq, Serialize)]
+pub struct HeightmapImage {
+    pub width: u32,
+    pub height: u32,
+    pub samples: Vec<u16>,
+}
+
+// The pixels are left out so that expressions print by their parameters only
+impl Debug for HeightmapImage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HeightmapImage")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Noise function that outputs one component, or the magnitude, of the analytic gradient of Perlin
+/// or Simplex noise with the same seed.
+///
+/// Unlike finite differences, analytic gradients do not depend on a step size and so do not
+/// introduce artifacts at any sampling resolution.
+pub struct Derivative {
+    /// The gradient component to output, or `None` for the magnitude of the gradient.
+    pub axis: Option<usize>,
+
+    gradient_fn: fn([f64; 3], &PermutationTable) -> [f64; 3],
+    hasher: PermutationTable,
+}
+
+impl Derivative {
+    pub fn perlin(seed: u32) -> Self {
+        Self {
+            axis: None,
+            gradient_fn: perlin_gradient_3d,
+            hasher: PermutationTable::new(seed),
+        }
+    }
+
+    pub fn set_axis(self, axis: Option<usize>) -> Self {
+        Self { axis, ..self }
+    }
+
+    pub fn simplex(seed: u32) -> Self {
+        Self {
+            axis: None,
+            gradient_fn: |point, hasher| simplex_3d(point, hasher).1,
+            hasher: PermutationTable::new(seed),
+        }
+    }
+}
+
+impl NoiseFn<f64, 3> for Derivative {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let gradient = (self.gradient_fn)(point, &self.hasher);
+
+        match self.axis {
+            Some(axis) => gradient[axis],
+            None => gradient
+                .iter()
+                .map(|value| value * value)
+                .sum::<f64>()
+                .sqrt(),
+        }
+    }
+}
+
+/// Returns the gradient of `noise::core::perlin::perlin_3d` at a point, ignoring the final clamp
+/// of the value to [-1, 1].
+fn perlin_gradient_3d(point: [f64; 3], hasher: &PermutationTable) -> [f64; 3] {
+    // Matches the scale factor (2 / sqrt(3)) and gradients of `perlin_3d`
+    const SCALE_FACTOR: f64 = 1.154_700_538_379_251_5;
+    const GRADIENTS: [[f64; 3]; 16] = [
+        [1.0, 1.0, 0.0],
+        [-1.0, 1.0, 0.0],
+        [1.0, -1.0, 0.0],
+        [-1.0, -1.0, 0.0],
+        [1.0, 0.0, 1.0],
+        [-1.0, 0.0, 1.0],
+        [1.0, 0.0, -1.0],
+        [-1.0, 0.0, -1.0],
+        [0.0, 1.0, 1.0],
+        [0.0, -1.0, 1.0],
+        [0.0, 1.0, -1.0],
+        [0.0, -1.0, -1.0],
+        [1.0, 1.0, 0.0],
+        [-1.0, 1.0, 0.0],
+        [0.0, -1.0, 1.0],
+        [0.0, -1.0, -1.0],
+    ];
+
+    let floored = point.map(f64::floor);
+    let corner = floored.map(|coord| coord as isize);
+    let distance = from_fn::<_, 3, _>(|axis| point[axis] - floored[axis]);
+
+    // The value (dot product) and gradient vector of each corner, indexed by its offset bits
+    let corners = from_fn::<_, 8, _>(|idx| {
+        let offset = [idx & 1, (idx >> 1) & 1, (idx >> 2) & 1];
+        let gradient = GRADIENTS[hasher.hash(&from_fn::<_, 3, _>(|axis| {
+            corner[axis] + offset[axis] as isize
+        })) & 0b1111];
+        let value = (0..3)
+            .map(|axis| gradient[axis] * (distance[axis] - offset[axis] as f64))
+            .sum::<f64>();
+
+        (value, gradient)
+    });
+
+    let [a, b, c] = distance.map(|t| t * t * t * (t * (t * 6.0 - 15.0) + 10.0));
+    let [da, db, dc] = distance.map(|t| 30.0 * t * t * (t - 1.0) * (t - 1.0));
+
+    // The interpolation coefficients of `perlin_3d`, for the values and for their gradients
+    let coefficients = |corner: &dyn Fn(usize) -> f64| {
+        let [g000, g100, g010, g110, g001, g101, g011, g111] = from_fn(corner);
+
+        [
+            g000,
+            g100 - g000,
+            g010 - g000,
+            g001 - g000,
+            g000 + g110 - g100 - g010,
+            g000 + g101 - g100 - g001,
+            g000 + g011 - g010 - g001,
+            g100 + g010 + g001 + g111 - g000 - g110 - g101 - g011,
+        ]
+    };
+    let interpolate = |k: [f64; 8]| {
+        k[0] + k[1] * a
+            + k[2] * b
+            + k[3] * c
+            + k[4] * a * b
+            + k[5] * a * c
+            + k[6] * b * c
+            + k[7] * a * b * c
+    };
+
+    let k = coefficients(&|idx| corners[idx].0);
+    let chain = [
+        da * (k[1] + k[4] * b + k[5] * c + k[7] * b * c),
+        db * (k[2] + k[4] * a + k[6] * c + k[7] * a * c),
+        dc * (k[3] + k[5] * a + k[6] * b + k[7] * a * b),
+    ];
+
+    from_fn(|axis| {
+        let gradient = interpolate(coefficients(&|idx| corners[idx].1[axis]));
+
+        (gradient + chain[axis]) * SCALE_FACTOR
+    })
+}
+
+/// Noise function that outputs the signed exponential of the output value from the source function.
+///
+/// Computes `sign(x) * (e^|x| - 1)`, which is the inverse of `Log` and keeps the sign of the input.
+pub struct Exp<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+}
+
+impl<Source> Exp<Source> {
+    pub fn new(source: Source) -> Self {
+        Self { source }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Exp<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let value = self.source.get(point);
+
+        value.abs().exp_m1().copysign(value)
+    }
+}
+
+/// Noise function that outputs FastNoise Lite, which engines often generate noise with at runtime.
+///
+/// Engines sampling 2D noise ignore the Z coordinate, which FastNoise Lite does not evaluate the
+/// same as 3D noise sliced at zero, so either may be sampled.
+pub struct FastNoise {
+    pub is_3d: bool,
+    noise: FastNoiseLite,
+}
+
+impl FastNoise {
+    pub fn new(noise: FastNoiseLite, is_3d: bool) -> Self {
+        Self { is_3d, noise }
+    }
+}
+
+impl NoiseFn<f64, 3> for FastNoise {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        if self.is_3d {
+            self.noise.get_noise_3d(point[0], point[1], point[2]) as f64
+        } else {
+            self.noise.get_noise_2d(point[0], point[1]) as f64
+        }
+    }
+}
+
+/// Noise function that outputs terrain built by the fault formation algorithm.
+///
+/// Each iteration splits space along a pseudo-random plane, raising one side and lowering the
+/// other; the displacement of each fault decreases linearly over the iterations.
+///
+/// The planes pass within `extent` units of the origin, so terrain is only formed inside that
+/// radius: further out every plane is crossed in the same order along any ray, and the output
+/// turns into spokes radiating from the origin.
+pub struct Fault {
+    seed: u32,
+    extent: f64,
+    faults: Vec<([f64; 3], f64)>,
+}
+
+impl Fault {
+    pub const DEFAULT_SEED: u32 = 0;
+    pub const DEFAULT_ITERATIONS: u32 = 64;
+    pub const MAX_ITERATIONS: u32 = 1024;
+
+    /// Covers the preview at its default scale, which spans two units either side of the origin.
+    pub const DEFAULT_EXTENT: f64 = 4.0;
+
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            extent: Self::DEFAULT_EXTENT,
+            faults: vec![],
+        }
+        .set_iterations(Self::DEFAULT_ITERATIONS)
+    }
+
+    pub fn set_extent(self, extent: f64) -> Self {
+        Self { extent, ..self }
+    }
+
+    pub fn set_iterations(self, iterations: u32) -> Self {
+        let seed = self.seed;
+        let faults = (0..iterations.clamp(1, Self::MAX_ITERATIONS) as i64)
+            .map(|iteration| {
+                let cell = [iteration, 0, 0];
+                let normal = [0, 1, 2].map(|axis| hash_signed(seed, cell, axis));
+                let len = normal.iter().map(|coord| coord * coord).sum::<f64>().sqrt();
+                let normal = if len > f64::EPSILON {
+                    normal.map(|coord| coord / len)
+                } else {
+                    [1.0, 0.0, 0.0]
+                };
+
+                (normal, hash_signed(seed, cell, 3))
+            })
+            .collect();
+
+        Self { faults, ..self }
+    }
+}
+
+impl Default for Fault {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+impl NoiseFn<f64, 3> for Fault {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let count = self.faults.len() as f64;
+        let mut res = 0.0;
+        let mut total = 0.0;
+
+        for (iteration, (normal, offset)) in self.faults.iter().enumerate() {
+            let displacement = 1.0 - iteration as f64 / count;
+            let distance = normal[0] * point[0] + normal[1] * point[1] + normal[2] * point[2];
+
+            if distance > offset * self.extent {
+                res += displacement;
+            } else {
+                res -= displacement;
+            }
+
+            total += displacement;
+        }
+
+        res / total
+    }
+}
+
+/// Noise function that sums octaves of a source like the fractals of the `noise` crate, followed by
+/// a fraction of one more octave.
+///
+/// Only the last octave is weighted by the remainder, and the scale of the sum moves between that
+/// of `octaves` and `octaves + 1` octaves with it, so the output changes smoothly from one octave
+/// count to the next within a single evaluation of the fractal.
+pub struct FractionalFractal<T> {
+    kind: FractalKind,
+    seed: u32,
+    octaves: usize,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    attenuation: f64,
+    remainder: f64,
+    sources: Vec<T>,
+}
+
+impl<T> FractionalFractal<T>
+where
+    T: Default + Seedable,
+{
+    pub const DEFAULT_OCTAVES: usize = Fbm::<T>::DEFAULT_OCTAVE_COUNT;
+    pub const DEFAULT_FREQUENCY: f64 = Fbm::<T>::DEFAULT_FREQUENCY;
+    pub const DEFAULT_LACUNARITY: f64 = Fbm::<T>::DEFAULT_LACUNARITY;
+    pub const DEFAULT_PERSISTENCE: f64 = Fbm::<T>::DEFAULT_PERSISTENCE;
+    pub const DEFAULT_ATTENUATION: f64 = RidgedMulti::<T>::DEFAULT_ATTENUATION;
+    pub const DEFAULT_REMAINDER: f64 = 0.0;
+
+    pub fn new(kind: FractalKind, seed: u32) -> Self {
+        Self {
+            kind,
+            seed,
+            octaves: Self::DEFAULT_OCTAVES,
+            frequency: Self::DEFAULT_FREQUENCY,
+            lacunarity: Self::DEFAULT_LACUNARITY,
+            persistence: Self::DEFAULT_PERSISTENCE,
+            attenuation: Self::DEFAULT_ATTENUATION,
+            remainder: Self::DEFAULT_REMAINDER,
+            sources: vec![],
+        }
+        .build_sources()
+    }
+
+    /// Only used by ridged fractals.
+    pub fn set_attenuation(self, attenuation: f64) -> Self {
+        Self {
+            attenuation,
+            ..self
+        }
+    }
+
+    /// Sets the weight of the octave after the last whole one, within [0, 1].
+    pub fn set_remainder(self, remainder: f64) -> Self {
+        Self {
+            remainder: remainder.clamp(0.0, 1.0),
+            ..self
+        }
+        .build_sources()
+    }
+
+    /// Seeds a source for each whole octave, and one more for the remainder.
+    fn build_sources(self) -> Self {
+        // Hybrid and ridged fractals of the `noise` crate keep the sources of seed 0 when they have
+        // the default octave count, which is matched so that the whole octaves are the same
+        let seed = match self.kind {
+            FractalKind::HybridMulti | FractalKind::RidgedMulti
+                if self.octaves == Self::DEFAULT_OCTAVES =>
+            {
+                0
+            }
+            _ => self.seed,
+        };
+        let count = self.octaves + (self.remainder > 0.0) as usize;
+        let sources = (0..count as u32)
+            .map(|octave| T::default().set_seed(seed.wrapping_add(octave)))
+            .collect();
+
+        Self { sources, ..self }
+    }
+}
+
+impl<T> MultiFractal for FractionalFractal<T>
+where
+    T: Default + Seedable,
+{
+    fn set_octaves(self, octaves: usize) -> Self {
+        Self {
+            octaves: octaves.clamp(1, MAX_FRACTAL_OCTAVES as usize),
+            ..self
+        }
+        .build_sources()
+    }
+
+    fn set_frequency(self, frequency: f64) -> Self {
+        Self { frequency, ..self }
+    }
+
+    fn set_lacunarity(self, lacunarity: f64) -> Self {
+        Self { lacunarity, ..self }
+    }
+
+    fn set_persistence(self, persistence: f64) -> Self {
+        Self {
+            persistence,
+            ..self
+        }
+    }
+}
+
+impl<T> NoiseFn<f64, 3> for FractionalFractal<T>
+where
+    T: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let mut point = point.map(|coord| coord * self.frequency);
+        let mut res = 0.0;
+        let mut weight = 1.0;
+
+        for (octave, source) in self.sources.iter().enumerate() {
+            let signal = source.get(point);
+            let amplitude = self.persistence.powi(octave as _);
+            let factor = if octave < self.octaves {
+                1.0
+            } else {
+                self.remainder
+            };
+
+            match self.kind {
+                FractalKind::BasicMulti if octave == 0 => res = signal,
+                FractalKind::BasicMulti => res += signal * amplitude * res * factor,
+                FractalKind::Billow => res += (signal.abs() * 2.0 - 1.0) * amplitude * factor,
+                FractalKind::Fbm => res += signal * amplitude * factor,
+                FractalKind::HybridMulti if octave == 0 => {
+                    res = signal * self.persistence;
+                    weight = res;
+                }
+                FractalKind::HybridMulti => {
+                    weight = weight.max(1.0);
+                    let signal = signal * amplitude;
+                    res += weight * signal * factor;
+                    weight *= signal;
+                }
+                FractalKind::RidgedMulti => {
+                    let signal = 1.0 - signal.abs();
+                    let signal = signal * signal * weight;
+                    weight = (signal / self.attenuation).clamp(0.0, 1.0);
+                    res += signal * amplitude * factor;
+                }
+            }
+
+            point = point.map(|coord| coord * self.lacunarity);
+        }
+
+        let octaves = self.octaves as i32;
+        let remainder = if self.sources.len() > self.octaves {
+            self.remainder
+        } else {
+            0.0
+        };
+
+        match self.kind {
+            FractalKind::BasicMulti => res * 0.5,
+            FractalKind::Billow | FractalKind::Fbm => {
+                let amplitude = self.persistence.powi(octaves);
+                res / (1.0 - amplitude + amplitude * (1.0 - self.persistence) * remainder)
+            }
+            FractalKind::HybridMulti => res * 3.0,
+            FractalKind::RidgedMulti => {
+                let scale = 2.0 - 0.5f64.powi(octaves - 1) + 0.5f64.powi(octaves) * remainder;
+                res.abs() * (2.0 / scale) - 1.0
+            }
+        }
+    }
+}
+
+/// Function used by `Gradient` to map the ramp position onto the output range.
+pub type WrapFunction = fn(f64) -> f64;
+
+/// Clamps the ramp to [-1, 1].
+pub fn wrap_clamp(value: f64) -> f64 {
+    value.clamp(-1.0, 1.0)
+}
+
+/// Reflects the ramp back and forth between -1 and 1.
+pub fn wrap_mirror(value: f64) -> f64 {
+    let value = (value + 1.0).rem_euclid(4.0);
+
+    if value > 2.0 {
+        3.0 - value
+    } else {
+        value - 1.0
+    }
+}
+
+/// Restarts the ramp at -1 each time it reaches 1.
+pub fn wrap_repeat(value: f64) -> f64 {
+    (value + 1.0).rem_euclid(2.0) - 1.0
+}
+
+/// Noise function that outputs a linear ramp along a direction in the XY plane.
+///
+/// The ramp passes through -1 and 1 one unit either side of the origin (shifted by the offset) and
+/// is mapped onto [-1, 1] using the wrap function.
+pub struct Gradient {
+    /// Direction of the ramp, in degrees counter-clockwise from the X axis.
+    pub angle: f64,
+
+    /// Offset added to the ramp position.
+    pub offset: f64,
+
+    wrap_fn: WrapFunction,
+}
+
+impl Gradient {
+    pub const DEFAULT_ANGLE: f64 = 0.0;
+    pub const DEFAULT_OFFSET: f64 = 0.0;
+
+    pub fn new() -> Self {
+        Self {
+            angle: Self::DEFAULT_ANGLE,
+            offset: Self::DEFAULT_OFFSET,
+            wrap_fn: wrap_clamp,
+        }
+    }
+
+    pub fn set_angle(self, angle: f64) -> Self {
+        Self { angle, ..self }
+    }
+
+    pub fn set_offset(self, offset: f64) -> Self {
+        Self { offset, ..self }
+    }
+
+    pub fn set_wrap_function(self, wrap_fn: WrapFunction) -> Self {
+        Self { wrap_fn, ..self }
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoiseFn<f64, 3> for Gradient {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+
+        (self.wrap_fn)(point[0] * cos + point[1] * sin + self.offset)
+    }
+}
+
+/// Noise function that offsets the input coordinates by a pseudo-random amount which is constant
+/// within each integer cell.
+///
+/// This is useful for breaking up the regular grid of patterns such as `Checkerboard`.
+pub struct Jitter<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// Seed used to hash each cell.
+    pub seed: u32,
+
+    /// Maximum distance (per axis) the input coordinates may be offset.
+    pub amount: f64,
+}
+
+impl<Source> Jitter<Source> {
+    pub const DEFAULT_SEED: u32 = 0;
+    pub const DEFAULT_AMOUNT: f64 = 0.5;
+
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            seed: Self::DEFAULT_SEED,
+            amount: Self::DEFAULT_AMOUNT,
+        }
+    }
+
+    pub fn set_amount(self, amount: f64) -> Self {
+        Self { amount, ..self }
+    }
+
+    pub fn set_seed(self, seed: u32) -> Self {
+        Self { seed, ..self }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Jitter<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let cell = floor_cell(point);
+        let mut axis = 0;
+        let point = point.map(|coord| {
+            let offset = hash_signed(self.seed, cell, axis) * self.amount;
+            axis += 1;
+            coord + offset
+        });
+
+        self.source.get(point)
+    }
+}
+
+/// Noise function that outputs a high-pass filtered white noise, which approximates blue noise.
+///
+/// Each cell value is the difference between the white noise of the cell and the average of its
+/// six face neighbours, which removes most of the low frequency content.
+pub struct BlueNoise {
+    seed: u32,
+}
+
+impl BlueNoise {
+    pub const DEFAULT_SEED: u32 = 0;
+
+    pub fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+}
+
+impl Default for BlueNoise {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+impl NoiseFn<f64, 3> for BlueNoise {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let [x, y, z] = floor_cell(point);
+        let neighbours = [
+            [x - 1, y, z],
+            [x + 1, y, z],
+            [x, y - 1, z],
+            [x, y + 1, z],
+            [x, y, z - 1],
+            [x, y, z + 1],
+        ]
+        .iter()
+        .map(|&cell| hash_signed(self.seed, cell, 0))
+        .sum::<f64>()
+            / 6.0;
+
+        // The difference lies within [-2, 2]
+        (hash_signed(self.seed, [x, y, z], 0) - neighbours) * 0.5
+    }
+}
+
+/// Noise function that outputs the signed logarithm of the output value from the source function.
+///
+/// Computes `sign(x) * ln(1 + |x|)`, which is defined for every input and keeps the sign of the
+/// input.
+pub struct Log<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+}
+
+impl<Source> Log<Source> {
+    pub fn new(source: Source) -> Self {
+        Self { source }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Log<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let value = self.source.get(point);
+
+        value.abs().ln_1p().copysign(value)
+    }
+}
+
+/// Noise function that linearly interpolates between the output values of two source functions.
+///
+/// Unlike `Blend` the factor is used as-is: a factor of 0 outputs the first source and a factor of
+/// 1 outputs the second source.
+pub struct Mix<Source1, Source2, Factor> {
+    /// Source function that outputs a value when the factor is 0.
+    pub source1: Source1,
+
+    /// Source function that outputs a value when the factor is 1.
+    pub source2: Source2,
+
+    /// Function that outputs the interpolation factor.
+    pub factor: Factor,
+}
+
+impl<Source1, Source2, Factor> Mix<Source1, Source2, Factor> {
+    pub fn new(source1: Source1, source2: Source2, factor: Factor) -> Self {
+        Self {
+            source1,
+            source2,
+            factor,
+        }
+    }
+}
+
+impl<Source1, Source2, Factor> NoiseFn<f64, 3> for Mix<Source1, Source2, Factor>
+where
+    Source1: NoiseFn<f64, 3>,
+    Source2: NoiseFn<f64, 3>,
+    Factor: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let lower = self.source1.get(point);
+        let upper = self.source2.get(point);
+
+        lower + (upper - lower) * self.factor.get(point)
+    }
+}
+
+/// Noise function that outputs the output value of the source function unchanged, asserting in
+/// debug builds that it is within the range the source is expected to output.
+pub struct OutputRange<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// The lower and upper bound of the output values of the source, inclusive.
+    pub range: [f64; 2],
+}
+
+impl<Source> OutputRange<Source> {
+    pub fn new(source: Source, lower_bound: f64, upper_bound: f64) -> Self {
+        Self {
+            source,
+            range: [lower_bound, upper_bound],
+        }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for OutputRange<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let value = self.source.get(point);
+
+        debug_assert!(
+            (self.range[0]..=self.range[1]).contains(&value),
+            "{value} at {point:?} is outside of the expected range [{}, {}]",
+            self.range[0],
+            self.range[1]
+        );
+
+        value
+    }
+}
+
+/// Noise function that flattens the output value of the source function above a level, with a
+/// smooth transition into the flat plateau.
+///
+/// The transition uses a polynomial smooth minimum spanning `softness` on either side of the
+/// level; a softness of zero clips sharply.
+pub struct Plateau<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// Output value of the plateau.
+    pub level: f64,
+
+    /// Width of the transition into the plateau.
+    pub softness: f64,
+}
+
+impl<Source> Plateau<Source> {
+    pub const DEFAULT_LEVEL: f64 = 0.5;
+    pub const DEFAULT_SOFTNESS: f64 = 0.1;
+
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            level: Self::DEFAULT_LEVEL,
+            softness: Self::DEFAULT_SOFTNESS,
+        }
+    }
+
+    pub fn set_level(self, level: f64) -> Self {
+        Self { level, ..self }
+    }
+
+    pub fn set_softness(self, softness: f64) -> Self {
+        Self { softness, ..self }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Plateau<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let value = self.source.get(point);
+
+        if self.softness <= 0.0 {
+            return value.min(self.level);
+        }
+
+        let blend = (0.5 + 0.5 * (self.level - value) / self.softness).clamp(0.0, 1.0);
+
+        self.level + (value - self.level) * blend - self.softness * blend * (1.0 - blend)
+    }
+}
+
+/// Noise function that raises the output value of the base function to the power of the output
+/// value of the exponent function.
+///
+/// Unlike `Power`, negative bases are raised by their magnitude and keep their sign, so the result
+/// is never NaN for finite inputs.
+pub struct PowSignal<Base, Exponent> {
+    /// Function that outputs the base.
+    pub base: Base,
+
+    /// Function that outputs the exponent.
+    pub exponent: Exponent,
+}
+
+impl<Base, Exponent> PowSignal<Base, Exponent> {
+    pub fn new(base: Base, exponent: Exponent) -> Self {
+        Self { base, exponent }
+    }
+}
+
+impl<Base, Exponent> NoiseFn<f64, 3> for PowSignal<Base, Exponent>
+where
+    Base: NoiseFn<f64, 3>,
+    Exponent: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let base = self.base.get(point);
+        let res = base.abs().powf(self.exponent.get(point)).copysign(base);
+
+        // Zero raised to a negative exponent
+        if res.is_finite() {
+            res
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Noise function that snaps the output value of the source function to a number of evenly spaced
+/// levels within [-1, 1].
+///
+/// An optional dither offsets each sample by a pseudo-random fraction of a level before rounding,
+/// which breaks up the banding between levels. The offset is the same throughout each cell of a
+/// fine lattice, so it does not depend on the exact coordinates sampled.
+pub struct Quantize<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// Number of discrete output levels.
+    pub levels: u32,
+
+    /// Amount of dithering, in levels, applied before rounding.
+    pub dither: f64,
+
+    /// Seed of the dither offsets.
+    pub seed: u32,
+}
+
+impl<Source> Quantize<Source> {
+    pub const DEFAULT_LEVELS: u32 = 4;
+    pub const DEFAULT_DITHER: f64 = 0.0;
+    pub const DEFAULT_SEED: u32 = 0;
+
+    /// Number of dither cells per unit, which is finer than a pixel of the preview at all but
+    /// the closest zoom.
+    const DITHER_FREQUENCY: f64 = 1024.0;
+
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            levels: Self::DEFAULT_LEVELS,
+            dither: Self::DEFAULT_DITHER,
+            seed: Self::DEFAULT_SEED,
+        }
+    }
+
+    pub fn set_dither(self, dither: f64) -> Self {
+        Self { dither, ..self }
+    }
+
+    pub fn set_levels(self, levels: u32) -> Self {
+        Self { levels, ..self }
+    }
+
+    pub fn set_seed(self, seed: u32) -> Self {
+        Self { seed, ..self }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Quantize<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        if self.levels < 2 {
+            return 0.0;
+        }
+
+        let steps = (self.levels - 1) as f64;
+        let mut level = (self.source.get(point) + 1.0) * 0.5 * steps;
+
+        if self.dither != 0.0 {
+            let cell = floor_cell(point.map(|coord| coord * Self::DITHER_FREQUENCY));
+            level += hash_signed(self.seed, cell, 0) * 0.5 * self.dither;
+        }
+
+        level.round().clamp(0.0, steps) / steps * 2.0 - 1.0
+    }
+}
+
+/// Noise function that moves the input coordinates of the source function into
+/// `[-period / 2, period / 2]` before sampling it.
+///
+/// Sources which repeat with the period are sampled at the same values near the origin, where the
+/// coordinates keep all of their fractional precision once they are rebased. Perlin, Value and
+/// Worley noise repeat every 256 units, divided by their frequency; other sources show a seam at
+/// each multiple of the period.
+pub struct Rebase<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// Period coordinates are wrapped with; a period of zero (or less) leaves them unchanged.
+    pub period: f64,
+}
+
+impl<Source> Rebase<Source> {
+    pub const DEFAULT_PERIOD: f64 = 256.0;
+
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            period: Self::DEFAULT_PERIOD,
+        }
+    }
+
+    pub fn set_period(self, period: f64) -> Self {
+        Self { period, ..self }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Rebase<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        if self.period <= 0.0 {
+            return self.source.get(point);
+        }
+
+        self.source
+            .get(point.map(|coord| coord - (coord / self.period).round() * self.period))
+    }
+}
+
+/// Noise function that repeats the input coordinates of the source function with a fixed period
+/// along each axis.
+///
+/// When mirroring is enabled every other period is reflected, so the repeated motif stays
+/// continuous across the seams.
+pub struct Repeat<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// Period of each axis; axes with a period of zero (or less) are not repeated.
+    pub periods: [f64; 3],
+
+    /// Reflect every other period instead of wrapping around.
+    pub mirror: bool,
+}
+
+impl<Source> Repeat<Source> {
+    pub const DEFAULT_PERIOD: f64 = 1.0;
+
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            periods: [Self::DEFAULT_PERIOD; 3],
+            mirror: false,
+        }
+    }
+
+    pub fn set_all_periods(self, x_period: f64, y_period: f64, z_period: f64) -> Self {
+        Self {
+            periods: [x_period, y_period, z_period],
+            ..self
+        }
+    }
+
+    pub fn set_mirror(self, mirror: bool) -> Self {
+        Self { mirror, ..self }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Repeat<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, mut point: [f64; 3]) -> f64 {
+        for (coord, &period) in point.iter_mut().zip(self.periods.iter()) {
+            if period <= 0.0 {
+                continue;
+            }
+
+            *coord = if self.mirror {
+                let coord = coord.rem_euclid(2.0 * period);
+                if coord > period {
+                    2.0 * period - coord
+                } else {
+                    coord
+                }
+            } else {
+                coord.rem_euclid(period)
+            };
+        }
+
+        self.source.get(point)
+    }
+}
+
+/// Noise function that turns the output value of the source function into ridges, which peak
+/// where the source crosses `offset`.
+///
+/// The output is `2 * (1 - |value - offset|)^sharpness - 1`, with the base clamped to [0, 1], so
+/// larger sharpness values give narrower crests and wider valleys.
+pub struct Ridge<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// Exponent applied to the ridge profile.
+    pub sharpness: f64,
+
+    /// Source value at which the ridges peak.
+    pub offset: f64,
+}
+
+impl<Source> Ridge<Source> {
+    pub const DEFAULT_SHARPNESS: f64 = 2.0;
+    pub const DEFAULT_OFFSET: f64 = 0.0;
+
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            sharpness: Self::DEFAULT_SHARPNESS,
+            offset: Self::DEFAULT_OFFSET,
+        }
+    }
+
+    pub fn set_offset(self, offset: f64) -> Self {
+        Self { offset, ..self }
+    }
+
+    pub fn set_sharpness(self, sharpness: f64) -> Self {
+        Self { sharpness, ..self }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Ridge<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let ridge = (1.0 - (self.source.get(point) - self.offset).abs()).clamp(0.0, 1.0);
+
+        ridge.powf(self.sharpness.max(0.0)) * 2.0 - 1.0
+    }
+}
+
+/// Noise function that rounds the input coordinates and the output value of the source function to
+/// `f32`, as if the source function was sampled by single precision code.
+pub struct RoundF32<Source> {
+    /// Source function that outputs a value.
+    pub source: Source,
+}
+
+impl<Source> RoundF32<Source> {
+    pub fn new(source: Source) -> Self {
+        Self { source }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for RoundF32<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, point: [f64; 3]) -> f64 {
+        self.source.get(point.map(|coord| coord as f32 as f64)) as f32 as f64
+    }
+}
+
+/// Noise function that outputs the value of a cache shared with other noise functions, so that a
+/// source which feeds several of them is sampled once for each point.
+pub struct SharedCache {
+    pub cache: Rc<Cache<Box<dyn NoiseFn<f64, 3>>>>,
+}
+
+impl SharedCache {
+    pub fn new(cache: Rc<Cache<Box<dyn NoiseFn<f64, 3>>>>) -> Self {
+        Self { cache }
+    }
+}
+
+impl NoiseFn<f64, 3> for SharedCache {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        self.cache.get(point)
+    }
+}
+
+/// Noise function that outputs a binary mask (-1 or 1) of the source function using separate off
+/// and on thresholds.
+///
+/// Values between the thresholds keep the state of the nearest preceding sample along the X axis
+/// which left the band, so noisy values near a single cut-off do not produce speckles. In smooth
+/// mode the band is instead blended with a smoothstep.
+///
+/// The hysteresis scan only looks towards negative X, so the mask depends on direction: a region
+/// of the band takes the state of its left edge, and mirroring the source does not mirror the
+/// mask. Each point in the band evaluates the source up to `scan_steps` more times, after which
+/// the midpoint between the thresholds decides.
+///
+/// In distance mode the output is instead an approximation of the signed distance to the boundary
+/// midway between the thresholds, positive on the side which is switched on and clamped to one
+/// unit either side, so that masks may fall off smoothly or be outlined downstream.
+pub struct Threshold<Source> {
+    /// Output the signed distance to the boundary instead of a mask.
+    pub distance: bool,
+
+    /// Source function that outputs a value.
+    pub source: Source,
+
+    /// Values at or below this threshold switch the output off.
+    pub off_threshold: f64,
+
+    /// Values at or above this threshold switch the output on.
+    pub on_threshold: f64,
+
+    /// The most samples taken along the X axis while resolving the hysteresis band.
+    pub scan_steps: u32,
+
+    /// Blend between the thresholds with a smoothstep instead of applying hysteresis.
+    pub smooth: bool,
+}
+
+impl<Source> Threshold<Source> {
+    pub const DEFAULT_OFF_THRESHOLD: f64 = -0.1;
+    pub const DEFAULT_ON_THRESHOLD: f64 = 0.1;
+    pub const DEFAULT_SCAN_STEPS: u32 = 64;
+
+    /// Distance between the samples taken either side of a point to find the gradient of the
+    /// source, which the distance to the boundary is estimated from.
+    const GRADIENT_STEP: f64 = 1.0 / 64.0;
+
+    /// The smallest gradient the distance is estimated from, so flat areas are far instead of
+    /// infinitely far from the boundary.
+    const MIN_GRADIENT: f64 = 1e-6;
+
+    /// Distance between the samples taken along the X axis while resolving the hysteresis band.
+    const SCAN_STEP: f64 = 1.0 / 32.0;
+
+    pub fn new(source: Source) -> Self {
+        Self {
+            distance: false,
+            source,
+            off_threshold: Self::DEFAULT_OFF_THRESHOLD,
+            on_threshold: Self::DEFAULT_ON_THRESHOLD,
+            scan_steps: Self::DEFAULT_SCAN_STEPS,
+            smooth: false,
+        }
+    }
+
+    pub fn set_distance(self, distance: bool) -> Self {
+        Self { distance, ..self }
+    }
+
+    pub fn set_off_threshold(self, off_threshold: f64) -> Self {
+        Self {
+            off_threshold,
+            ..self
+        }
+    }
+
+    pub fn set_on_threshold(self, on_threshold: f64) -> Self {
+        Self {
+            on_threshold,
+            ..self
+        }
+    }
+
+    pub fn set_scan_steps(self, scan_steps: u32) -> Self {
+        Self { scan_steps, ..self }
+    }
+
+    pub fn set_smooth(self, smooth: bool) -> Self {
+        Self { smooth, ..self }
+    }
+}
+
+impl<Source> NoiseFn<f64, 3> for Threshold<Source>
+where
+    Source: NoiseFn<f64, 3>,
+{
+    fn get(&self, mut point: [f64; 3]) -> f64 {
+        let off_threshold = self.off_threshold.min(self.on_threshold);
+        let on_threshold = self.off_threshold.max(self.on_threshold);
+        let value = self.source.get(point);
+
+        if self.distance {
+            // The source is treated as locally linear: the distance to the boundary is how far the
+            // value is from it divided by how fast the value changes
+            let gradient = (0..3)
+                .map(|axis| {
+                    let mut lhs = point;
+                    let mut rhs = point;
+                    lhs[axis] -= Self::GRADIENT_STEP;
+                    rhs[axis] += Self::GRADIENT_STEP;
+
+                    (self.source.get(rhs) - self.source.get(lhs)) / (2.0 * Self::GRADIENT_STEP)
+                })
+                .map(|slope| slope * slope)
+                .sum::<f64>()
+                .sqrt()
+                .max(Self::MIN_GRADIENT);
+            let boundary = (off_threshold + on_threshold) / 2.0;
+
+            return ((value - boundary) / gradient).clamp(-1.0, 1.0);
+        }
+
+        if self.smooth {
+            if on_threshold - off_threshold <= f64::EPSILON {
+                return if value >= on_threshold { 1.0 } else { -1.0 };
+            }
+
+            let t = ((value - off_threshold) / (on_threshold - off_threshold)).clamp(0.0, 1.0);
+
+            return t * t * (3.0 - 2.0 * t) * 2.0 - 1.0;
+        }
+
+        if value >= on_threshold {
+            return 1.0;
+        } else if value <= off_threshold {
+            return -1.0;
+        }
+
+        for _ in 0..self.scan_steps {
+            point[0] -= Self::SCAN_STEP;
+
+            let value = self.source.get(point);
+            if value >= on_threshold {
+                return 1.0;
+            } else if value <= off_threshold {
+                return -1.0;
+            }
+        }
+
+        // The band is wider than the scan; fall back to the midpoint
+        if value * 2.0 >= off_threshold + on_threshold {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+}
+
+/// Noise function that outputs an uncorrelated pseudo-random value for each integer cell.
+///
+/// Unlike `Value` noise the cell values are not interpolated, making this useful for masks and
+/// dithering.
+pub struct WhiteNoise {
+    seed: u32,
+}
+
+impl WhiteNoise {
+    pub const DEFAULT_SEED: u32 = 0;
+
+    pub fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+}
+
+impl Default for WhiteNoise {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+impl NoiseFn<f64, 3> for WhiteNoise {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        hash_signed(self.seed, floor_cell(point), 0)
+    }
+}
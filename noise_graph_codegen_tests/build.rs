@@ -0,0 +1,51 @@
+use {
+    noise_graph::Graph,
+    std::{
+        env::var,
+        fmt::Write,
+        fs::{read_dir, read_to_string, write},
+        path::Path,
+    },
+};
+
+/// Generates a module of Rust for each graph in `graphs/`, along with `GRAPHS`, which lists the
+/// expression of each graph with the function generated from it.
+fn main() {
+    println!("cargo:rerun-if-changed=graphs");
+
+    let mut paths = read_dir("graphs")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut modules = String::new();
+    let mut graphs = String::new();
+
+    for path in paths {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let src = read_to_string(&path).unwrap();
+        let graph = Graph::from_ron(&src)
+            .unwrap_or_else(|err| panic!("{} is not an expression: {err}", path.display()));
+        let rust = graph
+            .expr()
+            .to_rust()
+            .unwrap_or_else(|err| panic!("unable to generate Rust for {name}: {err:?}"));
+
+        writeln!(modules, "pub mod {name} {{\n{rust}}}\n").unwrap();
+        writeln!(
+            graphs,
+            "    (\"{name}\", include_str!({:?}), || Box::new({name}::noise_graph())),",
+            Path::new(&var("CARGO_MANIFEST_DIR").unwrap()).join(&path)
+        )
+        .unwrap();
+    }
+
+    write(
+        Path::new(&var("OUT_DIR").unwrap()).join("graphs.rs"),
+        format!(
+            "{modules}pub const GRAPHS: &[(&str, &str, fn() -> Box<dyn noise::NoiseFn<f64, 3>>)] = &[\n{graphs}];\n"
+        ),
+    )
+    .unwrap();
+}
@@ -0,0 +1,59 @@
+//! The Rust which `noise_graph` generates for each graph in `graphs/`, compiled so that the tests
+//! may check that it samples exactly the same values as `noise_graph::Graph`.
+
+include!(concat!(env!("OUT_DIR"), "/graphs.rs"));
+
+#[cfg(test)]
+mod tests {
+    use {super::GRAPHS, noise::NoiseFn, noise_graph::Graph};
+
+    /// The number of samples along each side of the sampled square.
+    const SIZE: usize = 64;
+
+    /// The length of each side of the sampled square, which starts at the origin.
+    const LEN: f64 = 4.0;
+
+    #[test]
+    fn generated_code_has_cost_estimate() {
+        for &(name, src, _) in GRAPHS {
+            let graph = Graph::from_ron(src).unwrap();
+            let expr = graph.expr();
+            let cost = expr.cost().comment("//");
+
+            assert!(
+                expr.to_rust().unwrap().contains(&cost),
+                "{name} generates Rust without its cost estimate"
+            );
+
+            // Graphs with nodes which have no shader implementation generate no shaders
+            for (language, src) in [("GLSL", expr.to_glsl()), ("WGSL", expr.to_wgsl())] {
+                if let Ok(src) = src {
+                    assert!(
+                        src.contains(&cost),
+                        "{name} generates {language} without its cost estimate"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generated_rust_matches_graph() {
+        for &(name, src, noise_graph) in GRAPHS {
+            let graph = Graph::from_ron(src).unwrap();
+            let expected = graph.sample_chunk([0, 0], SIZE, LEN);
+            let noise = noise_graph();
+
+            for (idx, expected) in expected.into_iter().enumerate() {
+                let x = Graph::chunk_position(0, idx % SIZE, SIZE, LEN);
+                let y = Graph::chunk_position(0, idx / SIZE, SIZE, LEN);
+                let actual = noise.get([x, y, 0.0]);
+
+                assert!(
+                    actual == expected || actual.is_nan() && expected.is_nan(),
+                    "{name} samples {actual} at ({x}, {y}) instead of {expected}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/* C ABI for sampling noise graphs exported by noise_gui.
+ *
+ * A NoiseGraph must not be shared across threads: use and free it only on the thread which
+ * loaded it, and load a graph for each thread which samples. Functions given NULL pointers do
+ * nothing, or return NaN. */
+
+#ifndef NOISE_GRAPH_H
+#define NOISE_GRAPH_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct NoiseGraph NoiseGraph;
+
+/* Loads the text of an exported .ron expression, returning NULL if it is not valid. The text must
+ * be RON as exported by noise_gui: JSON is not accepted. */
+NoiseGraph *noise_graph_load(const char *src);
+
+/* Frees a graph returned by noise_graph_load; NULL is ignored. */
+void noise_graph_free(NoiseGraph *graph);
+
+/* Returns the value of a graph at a point, or NaN if graph is NULL. */
+double noise_graph_sample(const NoiseGraph *graph, double x, double y, double z);
+
+/* Samples len points, read as x, y, z triples from points, into values; nothing is sampled if
+ * any pointer is NULL. */
+void noise_graph_sample_many(const NoiseGraph *graph, const double *points, double *values,
+                             size_t len);
+
+/* Replaces the value of every decimal constant with the given name. */
+void noise_graph_set_f64(NoiseGraph *graph, const char *name, double value);
+
+/* Replaces the value of every integer constant with the given name. */
+void noise_graph_set_u32(NoiseGraph *graph, const char *name, unsigned int value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,146 @@
+//! C ABI over `noise_graph`, so that engines such as Unity (through P/Invoke), Godot
+//! (through GDExtension) and C++ programs can sample exported graphs without a Rust toolchain.
+//!
+//! See `include/noise_graph.h` for the declarations.
+//!
+//! Graphs share cached sources without synchronization, so a graph must only be used, and freed,
+//! on the thread which loaded it; load a graph for each thread which samples.
+
+use {
+    noise_graph::Graph,
+    std::{
+        ffi::{c_char, c_double, c_uint, CStr},
+        ptr::null_mut,
+        slice::{from_raw_parts, from_raw_parts_mut},
+    },
+};
+
+/// Loads an expression exported by right-clicking a node in the editor, returning null if the
+/// text is not a valid expression.
+///
+/// The editor exports expressions as RON, which is the only format read here: JSON is not
+/// accepted.
+///
+/// The graph must be freed with `noise_graph_free`, and must not be used by other threads.
+///
+/// # Safety
+///
+/// `src` must be a null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn noise_graph_load(src: *const c_char) -> *mut Graph {
+    if src.is_null() {
+        return null_mut();
+    }
+
+    CStr::from_ptr(src)
+        .to_str()
+        .ok()
+        .and_then(|src| Graph::from_ron(src).ok())
+        .map_or(null_mut(), |graph| Box::into_raw(Box::new(graph)))
+}
+
+/// Frees a graph returned by `noise_graph_load`; null is ignored.
+///
+/// # Safety
+///
+/// `graph` must be null or returned by `noise_graph_load` on this thread, and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn noise_graph_free(graph: *mut Graph) {
+    if !graph.is_null() {
+        drop(Box::from_raw(graph));
+    }
+}
+
+/// Returns the value of a graph at a point, which is identical to the value shown by the editor,
+/// or NaN if `graph` is null.
+///
+/// # Safety
+///
+/// `graph` must be null or returned by `noise_graph_load` on this thread, and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn noise_graph_sample(
+    graph: *const Graph,
+    x: c_double,
+    y: c_double,
+    z: c_double,
+) -> c_double {
+    graph
+        .as_ref()
+        .map_or(c_double::NAN, |graph| graph.sample(x, y, z))
+}
+
+/// Samples a graph at `len` points, reading `x, y, z` triples from `points` and writing one value
+/// to `values` for each, which avoids a call per sample. Nothing is sampled if any pointer is
+/// null.
+///
+/// # Safety
+///
+/// `graph` must be null or returned by `noise_graph_load` on this thread, and not freed; unless
+/// they are null, `points` must hold `len * 3` values and `values` must have room for `len`.
+#[no_mangle]
+pub unsafe extern "C" fn noise_graph_sample_many(
+    graph: *const Graph,
+    points: *const c_double,
+    values: *mut c_double,
+    len: usize,
+) {
+    // Slices may not be made from null pointers, even empty ones
+    if len == 0 || points.is_null() || values.is_null() {
+        return;
+    }
+
+    let Some(graph) = graph.as_ref() else {
+        return;
+    };
+
+    let points = from_raw_parts(points, len * 3);
+    let values = from_raw_parts_mut(values, len);
+
+    for (value, point) in values.iter_mut().zip(points.chunks_exact(3)) {
+        *value = graph.sample(point[0], point[1], point[2]);
+    }
+}
+
+/// Replaces the value of every decimal constant with the given name; nothing is replaced if either
+/// pointer is null.
+///
+/// # Safety
+///
+/// `graph` must be null or returned by `noise_graph_load` on this thread, and not freed; `name`
+/// must be null or a null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn noise_graph_set_f64(
+    graph: *mut Graph,
+    name: *const c_char,
+    value: c_double,
+) {
+    if graph.is_null() || name.is_null() {
+        return;
+    }
+
+    if let Ok(name) = CStr::from_ptr(name).to_str() {
+        (*graph).set_f64(name, value);
+    }
+}
+
+/// Replaces the value of every integer constant with the given name; nothing is replaced if either
+/// pointer is null.
+///
+/// # Safety
+///
+/// `graph` must be null or returned by `noise_graph_load` on this thread, and not freed; `name`
+/// must be null or a null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn noise_graph_set_u32(
+    graph: *mut Graph,
+    name: *const c_char,
+    value: c_uint,
+) {
+    if graph.is_null() || name.is_null() {
+        return;
+    }
+
+    if let Ok(name) = CStr::from_ptr(name).to_str() {
+        (*graph).set_u32(name, value);
+    }
+}
@@ -0,0 +1,163 @@
+//! Python bindings over `noise_graph`, so that graphs designed in the editor can be sampled into
+//! numpy arrays and exported as images from scripts and notebooks.
+//!
+//! Built with `maturin develop --release` from this directory.
+
+use {
+    noise_graph::Graph,
+    numpy::{PyArray1, PyArray2, PyReadonlyArray2},
+    png::{BitDepth, ColorType, Encoder},
+    pyo3::{
+        exceptions::{PyIOError, PyValueError},
+        prelude::*,
+    },
+    std::{
+        fs::{read_to_string, File},
+        io::BufWriter,
+        path::PathBuf,
+    },
+};
+
+/// The range of values which maps to black and white, the same as the previews of the editor.
+const VALUE_RANGE: [f64; 2] = [-1.0, 1.0];
+
+/// An exported expression which may be sampled.
+#[pyclass(name = "Graph", unsendable)]
+struct PyGraph(Graph);
+
+#[pymethods]
+impl PyGraph {
+    /// Reads an expression exported by right-clicking a node in the editor.
+    #[staticmethod]
+    fn from_ron(src: &str) -> PyResult<Self> {
+        Graph::from_ron(src)
+            .map(Self)
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Reads an exported `.ron` file.
+    #[staticmethod]
+    fn load(path: PathBuf) -> PyResult<Self> {
+        let src = read_to_string(path).map_err(|err| PyIOError::new_err(err.to_string()))?;
+
+        Self::from_ron(&src)
+    }
+
+    /// Returns the value at a point.
+    #[pyo3(signature = (x, y, z = 0.0))]
+    fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.0.sample(x, y, z)
+    }
+
+    /// Samples a square area into a `size` by `size` array, at the same positions as an image
+    /// exported from the editor with the same offset and scale.
+    #[pyo3(signature = (size, x = 0.0, y = 0.0, scale = 1.0))]
+    fn sample_grid<'py>(
+        &self,
+        py: Python<'py>,
+        size: usize,
+        x: f64,
+        y: f64,
+        scale: f64,
+    ) -> &'py PyArray2<f64> {
+        let rows = self.grid(size, x, y, scale);
+
+        PyArray2::from_vec2(py, &rows).unwrap()
+    }
+
+    /// Samples an array of shape `(n, 3)` holding one point per row.
+    fn sample_points<'py>(
+        &self,
+        py: Python<'py>,
+        points: PyReadonlyArray2<'py, f64>,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let points = points.as_array();
+        if points.ncols() != 3 {
+            return Err(PyValueError::new_err("points must have shape (n, 3)"));
+        }
+
+        let values = points
+            .rows()
+            .into_iter()
+            .map(|point| self.0.sample(point[0], point[1], point[2]))
+            .collect::<Vec<_>>();
+
+        Ok(PyArray1::from_vec(py, values))
+    }
+
+    /// Writes a grayscale PNG of a square area, where -1 and 1 map to black and white.
+    #[pyo3(signature = (path, size, x = 0.0, y = 0.0, scale = 1.0, bit_depth = 16))]
+    fn save_png(
+        &self,
+        path: PathBuf,
+        size: usize,
+        x: f64,
+        y: f64,
+        scale: f64,
+        bit_depth: u8,
+    ) -> PyResult<()> {
+        let (bit_depth, max) = match bit_depth {
+            8 => (BitDepth::Eight, u8::MAX as f64),
+            16 => (BitDepth::Sixteen, u16::MAX as f64),
+            _ => return Err(PyValueError::new_err("bit_depth must be 8 or 16")),
+        };
+
+        let [min_value, max_value] = VALUE_RANGE;
+        let mut pixels = Vec::with_capacity(size * size * 2);
+        for value in self.grid(size, x, y, scale).into_iter().flatten() {
+            let value = ((value - min_value) / (max_value - min_value)).clamp(0.0, 1.0) * max;
+
+            match bit_depth {
+                BitDepth::Eight => pixels.push(value.round() as u8),
+                _ => pixels.extend_from_slice(&(value.round() as u16).to_be_bytes()),
+            }
+        }
+
+        let file = File::create(path).map_err(|err| PyIOError::new_err(err.to_string()))?;
+        let mut encoder = Encoder::new(BufWriter::new(file), size as _, size as _);
+        encoder.set_color(ColorType::Grayscale);
+        encoder.set_depth(bit_depth);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(|err| PyIOError::new_err(err.to_string()))
+    }
+
+    /// Replaces the value of every decimal constant with the given name.
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.0.set_f64(name, value);
+    }
+
+    /// Replaces the value of every integer constant with the given name.
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.0.set_u32(name, value);
+    }
+}
+
+impl PyGraph {
+    /// Samples rows of a square area; like the editor, rows run along the first coordinate.
+    fn grid(&self, size: usize, x: f64, y: f64, scale: f64) -> Vec<Vec<f64>> {
+        let step = 1.0 / size as f64;
+        let half_step = step / 2.0;
+
+        (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| {
+                        self.0.sample(
+                            (row as f64 * step + half_step + y) * scale,
+                            (col as f64 * step + half_step + x) * scale,
+                            0.0,
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "noise_graph")]
+fn module(_py: Python<'_>, module: &PyModule) -> PyResult<()> {
+    module.add_class::<PyGraph>()
+}
@@ -0,0 +1,355 @@
+use {
+    super::{app::NodeExprs, job::Job, node::NoiseNode, thread::Threads},
+    egui::{
+        Button, Color32, ColorImage, Context, DragValue, Grid, Id, ProgressBar, TextureHandle, Ui,
+        Window,
+    },
+    egui_snarl::Snarl,
+    noise::NoiseFn,
+    noise_graph::{Backend, Expr},
+    std::sync::Arc,
+};
+
+/// The window rendered at one sampling rate.
+struct Rendering {
+    /// The number of samples along each side of a pixel.
+    rate: usize,
+
+    texture: TextureHandle,
+    values: Vec<f64>,
+}
+
+/// Collapsible window which renders the area of a node preview with one, four and sixteen samples
+/// per pixel and marks the pixels which change between them, which is where detail finer than a
+/// pixel aliases and a baked heightmap would shimmer as it is sampled at runtime.
+pub struct AliasingCheck {
+    pub node_idx: Option<usize>,
+
+    /// The texture of the differences between the fewest and the most samples per pixel, where
+    /// flagged pixels are red.
+    difference: Option<TextureHandle>,
+
+    /// The fraction of pixels which changed by more than the threshold.
+    flagged: f64,
+
+    /// The renderings which are being sampled, as their size and the values of each sampling
+    /// rate, which replace the shown renderings once they finish.
+    job: Option<Job<(usize, Vec<Vec<f64>>)>>,
+
+    max_difference: f64,
+    mean_difference: f64,
+    renderings: Vec<Rendering>,
+
+    /// The number of pixels along each side of the window.
+    size: usize,
+
+    /// How much a pixel may change before it is flagged, as a fraction of the value range.
+    threshold: f64,
+
+    version: Option<(usize, usize, Backend)>,
+}
+
+impl AliasingCheck {
+    const DEFAULT_SIZE: usize = 128;
+    const DEFAULT_THRESHOLD: f64 = 0.05;
+
+    /// The fraction of pixels which may be flagged before the node is reported as aliasing.
+    const FLAGGED_TOLERANCE: f64 = 0.01;
+
+    const MAX_SIZE: usize = 256;
+
+    /// The number of samples along each side of a pixel of each rendering.
+    const RATES: [usize; 3] = [1, 2, 4];
+
+    /// Starts sampling the renderings, a row of pixels of one sampling rate at a time, on a thread
+    /// of its own.
+    fn analyze(&mut self, ctx: &Context, expr: Expr, backend: Backend, [x, y, scale]: [f64; 3]) {
+        let size = self.size;
+        let step = 1.0 / size as f64;
+
+        self.job = Some(Job::new(
+            ctx,
+            Self::RATES.len() * size,
+            move || {
+                (
+                    expr.noise_with(backend.get()),
+                    vec![Vec::with_capacity(size * size); Self::RATES.len()],
+                )
+            },
+            move |(noise, renderings), step_idx| {
+                let (rate_idx, row) = (step_idx / size, step_idx % size);
+                let rate = Self::RATES[rate_idx];
+
+                // Each pixel is the mean of a grid of samples spread evenly across it
+                let sub_step = step / rate as f64;
+                renderings[rate_idx].extend((0..size).map(|col| {
+                    let mut sum = 0.0;
+
+                    for sub_row in 0..rate {
+                        let eval_x =
+                            ((row * rate + sub_row) as f64 * sub_step + sub_step / 2.0 + y) * scale;
+
+                        for sub_col in 0..rate {
+                            let eval_y =
+                                ((col * rate + sub_col) as f64 * sub_step + sub_step / 2.0 + x)
+                                    * scale;
+
+                            sum += noise.get([eval_x, eval_y, 0.0]);
+                        }
+                    }
+
+                    sum / (rate * rate) as f64
+                }));
+            },
+            move |(_, renderings)| (size, renderings),
+        ));
+    }
+
+    /// Shows the renderings sampled by `analyze` and the differences between them.
+    fn finish(&mut self, ctx: &Context, size: usize, renderings: Vec<Vec<f64>>) {
+        self.renderings = Self::RATES
+            .into_iter()
+            .zip(renderings)
+            .map(|(rate, values)| {
+                let pixels = values
+                    .iter()
+                    .copied()
+                    .map(Threads::value_to_gray)
+                    .collect::<Vec<_>>();
+
+                Rendering {
+                    rate,
+                    texture: ctx.load_texture(
+                        format!("aliasing{rate}"),
+                        ColorImage::from_gray([size, size], &pixels),
+                        Default::default(),
+                    ),
+                    values,
+                }
+            })
+            .collect();
+
+        let [min, max] = Threads::VALUE_RANGE;
+        let (Some(first), Some(last)) = (self.renderings.first(), self.renderings.last()) else {
+            return;
+        };
+        let differences = first
+            .values
+            .iter()
+            .zip(&last.values)
+            .map(|(lhs, rhs)| (lhs - rhs).abs() / (max - min))
+            .collect::<Vec<_>>();
+        let threshold = self.threshold;
+        let is_flagged = |difference: f64| difference.is_nan() || difference > threshold;
+
+        self.flagged = differences
+            .iter()
+            .filter(|&&difference| is_flagged(difference))
+            .count() as f64
+            / differences.len() as f64;
+        self.max_difference = differences.iter().copied().fold(0.0, f64::max);
+        self.mean_difference = differences.iter().sum::<f64>() / differences.len() as f64;
+
+        let pixels = differences
+            .iter()
+            .map(|&difference| {
+                if is_flagged(difference) {
+                    Color32::RED
+                } else {
+                    Color32::from_gray((difference / threshold * 128.0) as u8)
+                }
+            })
+            .collect();
+        self.difference = Some(ctx.load_texture(
+            "aliasing_difference",
+            ColorImage {
+                size: [size, size],
+                pixels,
+            },
+            Default::default(),
+        ));
+    }
+
+    fn clear(&mut self) {
+        self.difference = None;
+        self.job = None;
+        self.renderings.clear();
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        node_exprs: &NodeExprs,
+        snarl: &Snarl<NoiseNode>,
+        backend: Backend,
+    ) {
+        let Some(node_idx) = self.node_idx else {
+            return;
+        };
+
+        let Some((version, expr)) = node_exprs
+            .read()
+            .unwrap()
+            .get(&node_idx)
+            .map(|(version, expr)| (*version, Arc::clone(expr)))
+        else {
+            self.node_idx = None;
+            self.clear();
+
+            return;
+        };
+
+        // Results are only valid for the expression they were sampled from
+        if self.version != Some((node_idx, version, backend)) {
+            self.clear();
+            self.version = Some((node_idx, version, backend));
+        }
+
+        if let Some((size, renderings)) = self.job.as_mut().and_then(|job| job.poll(ctx)) {
+            self.job = None;
+            self.finish(ctx, size, renderings);
+        }
+
+        let mut open = true;
+
+        Window::new(format!("Aliasing #{node_idx}"))
+            .id(Id::new("aliasing"))
+            .open(&mut open)
+            .vscroll(true)
+            .hscroll(true)
+            .show(ctx, |ui| {
+                self.show_settings(ui);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.job.is_none(), Button::new("Analyze"))
+                        .on_hover_text(
+                            "Render the area of the node preview at each sampling rate and \
+                            compare the renderings",
+                        )
+                        .clicked()
+                    {
+                        let area = snarl
+                            .node_indices()
+                            .find(|&(idx, _)| idx == node_idx)
+                            .and_then(|(_, node)| node.image())
+                            .map_or([0.0, 0.0, 1.0], |image| [image.x, image.y, image.scale]);
+                        self.analyze(ctx, expr.as_ref().clone(), backend, area);
+                    }
+
+                    if let Some(job) = &self.job {
+                        ui.add(ProgressBar::new(job.progress()).show_percentage());
+
+                        if ui.button("Cancel").clicked() {
+                            self.job = None;
+                        }
+                    }
+                });
+
+                if self.difference.is_some() {
+                    ui.separator();
+                    self.show_results(ui);
+                }
+            });
+
+        if !open {
+            self.node_idx = None;
+            self.clear();
+        }
+    }
+
+    fn show_results(&self, ui: &mut Ui) {
+        Grid::new("aliasing_renderings").show(ui, |ui| {
+            for rendering in &self.renderings {
+                ui.strong(format!("{0}×{0} Samples", rendering.rate));
+            }
+
+            ui.strong("Difference").on_hover_text(
+                "How much each pixel changes between the fewest and the most samples, where red \
+                pixels changed by more than the threshold",
+            );
+            ui.end_row();
+
+            for rendering in &self.renderings {
+                ui.image((rendering.texture.id(), rendering.texture.size_vec2()));
+            }
+
+            if let Some(difference) = &self.difference {
+                ui.image((difference.id(), difference.size_vec2()));
+            }
+
+            ui.end_row();
+        });
+
+        ui.label(format!(
+            "Flagged {:.1}% of pixels; mean change {:.2}%, largest change {:.1}%",
+            self.flagged * 100.0,
+            self.mean_difference * 100.0,
+            self.max_difference * 100.0
+        ));
+
+        if self.flagged > Self::FLAGGED_TOLERANCE {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                "⚠ This node has detail finer than a pixel, which aliases: a heightmap baked at \
+                this resolution will shimmer as it is sampled. Lower the frequency or the number \
+                of octaves, or bake at a higher resolution",
+            );
+        } else {
+            ui.label("✔ No aliasing at this resolution");
+        }
+    }
+
+    fn show_settings(&mut self, ui: &mut Ui) {
+        Grid::new("aliasing_settings").show(ui, |ui| {
+            ui.label("Resolution");
+            ui.add(
+                DragValue::new(&mut self.size)
+                    .clamp_range(2..=Self::MAX_SIZE)
+                    .suffix(" pixels"),
+            )
+            .on_hover_text(
+                "The number of pixels along each side of the area of the node preview, which \
+                should match the resolution it is baked at",
+            );
+            ui.end_row();
+
+            ui.label("Threshold");
+            ui.add(
+                DragValue::new(&mut self.threshold)
+                    .clamp_range(0.001..=1.0)
+                    .speed(0.001)
+                    .custom_formatter(|value, _| format!("{:.1}%", value * 100.0))
+                    .custom_parser(|text| {
+                        text.trim_end_matches('%')
+                            .trim()
+                            .parse::<f64>()
+                            .ok()
+                            .map(|value| value / 100.0)
+                    }),
+            )
+            .on_hover_text(
+                "How much a pixel may change between sampling rates, as a fraction of the value \
+                range, before it is flagged",
+            );
+            ui.end_row();
+        });
+    }
+}
+
+impl Default for AliasingCheck {
+    fn default() -> Self {
+        Self {
+            node_idx: None,
+            difference: None,
+            flagged: 0.0,
+            job: None,
+            max_difference: 0.0,
+            mean_difference: 0.0,
+            renderings: vec![],
+            size: Self::DEFAULT_SIZE,
+            threshold: Self::DEFAULT_THRESHOLD,
+            version: None,
+        }
+    }
+}
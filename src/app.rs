@@ -1,89 +1,427 @@
 use {
     super::{
-        expr::Expr,
-        node::{Image, NoiseNode},
+        aliasing::AliasingCheck,
+        bookmarks::{Bookmark, Bookmarks},
+        character::CharacterInspector,
+        chunks::ChunkChecker,
+        explore::Explorer,
+        favorites::Favorites,
+        filter::CanvasFilter,
+        layers::LayerStack,
+        lint::Linter,
+        node::{Image, NodeIds, NoiseNode},
+        notes::Notes,
+        outline::Outline,
         rand::shuffled_u8,
-        thread::{ImageInfo, Threads},
-        view::Viewer,
+        saved_graph::SavedGraph,
+        sphere::SpherePreview,
+        stability::StabilityAnalysis,
+        terrain::TerrainPreview,
+        thread::{ImageInfo, Priority, Threads},
+        tree::ExprTree,
+        undo::UndoHistory,
+        view::{Inspector, Viewer, ViewerState},
+        wizard::Wizard,
     },
     eframe::{get_value, set_value, CreationContext, Frame, Storage, APP_KEY},
     egui::{
-        github_link_file, warn_if_debug_build, Align, CentralPanel, Color32, ColorImage, Context,
-        Id, Layout,
+        github_link_file, vec2, warn_if_debug_build, Align, CentralPanel, Color32, ColorImage,
+        Context, Grid, Id, Layout, TopBottomPanel, Vec2,
     },
     egui_snarl::{ui::SnarlStyle, OutPinId, Snarl},
     log::debug,
+    noise_graph::{Backend, Expr},
+    ron::ser::to_string,
     std::{
         cell::RefCell,
         collections::{HashMap, HashSet},
+        mem::{size_of, swap, take},
         sync::{Arc, RwLock},
+        time::Duration,
     },
 };
 
 #[cfg(not(target_arch = "wasm32"))]
 use {
-    egui::{menu, widgets, TopBottomPanel, ViewportCommand},
-    log::warn,
-    rfd::FileDialog,
-    ron::{
-        de::from_reader,
-        ser::{to_writer_pretty, PrettyConfig},
+    super::{
+        asset, canonical,
+        collab::Collab,
+        crash::{self, CrashCopy},
+        profiler::Profiler,
+        report::Report,
+        verify::Snapshot,
+        watch::FileWatcher,
     },
-    serde::Serialize,
+    egui::{menu, widgets, Button, DragValue, ViewportCommand},
+    log::warn,
+    rfd::{FileDialog, MessageDialog, MessageLevel},
+    ron::de::from_str,
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
     std::{
-        fs::OpenOptions,
+        borrow::{Borrow, Cow},
+        fs::{read_to_string, remove_file, OpenOptions},
+        io::Write,
         path::{Path, PathBuf},
     },
 };
 
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+use super::gpu::GpuPreview;
+
+/// The contents of a project file, where `S` is the graph.
+///
+/// Projects are saved as a [`ProjectFile`], in which wires refer to nodes by identifier.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+pub struct Project<S> {
+    pub backend: Backend,
+    pub bookmarks: Vec<Bookmark>,
+
+    /// The directory the project is saved to, which the paths of assets within it are saved
+    /// relative to.
+    pub dir: Option<PathBuf>,
+
+    pub node_ids: NodeIds,
+    pub notes: String,
+    pub snarl: S,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<'de> Deserialize<'de> for Project<Snarl<NoiseNode>> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ProjectFile {
+            backend,
+            bookmarks,
+            graph,
+            notes,
+        } = ProjectFile::deserialize(deserializer)?;
+        let (snarl, node_ids) = graph.load(backend, &NodeIds::default());
+
+        Ok(Self {
+            backend,
+            bookmarks: bookmarks.into_owned(),
+            dir: None,
+            node_ids,
+            notes: notes.into_owned(),
+            snarl,
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S> Serialize for Project<S>
+where
+    S: Borrow<Snarl<NoiseNode>>,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: Serializer,
+    {
+        let mut graph = SavedGraph::new(self.snarl.borrow(), &self.node_ids);
+        if let Some(dir) = &self.dir {
+            asset::save_in(&mut graph, dir);
+        }
+
+        ProjectFile {
+            backend: self.backend,
+            bookmarks: Cow::Borrowed(&self.bookmarks),
+            graph,
+            notes: Cow::Borrowed(&self.notes),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// A project as saved.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Deserialize, Serialize)]
+struct ProjectFile<'a> {
+    #[serde(default)]
+    backend: Backend,
+
+    #[serde(default)]
+    bookmarks: Cow<'a, [Bookmark]>,
+
+    graph: SavedGraph,
+
+    #[serde(default)]
+    notes: Cow<'a, str>,
+}
+
+/// The contents of a project file saved before wires referred to nodes by identifier, when the
+/// identifiers were kept alongside the graph of egui-snarl.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Deserialize)]
+struct LegacyProject {
+    #[serde(default)]
+    backend: Backend,
+
+    #[serde(default)]
+    bookmarks: Vec<Bookmark>,
+
+    #[serde(default)]
+    node_ids: NodeIds,
+
+    #[serde(default)]
+    notes: String,
+    snarl: Snarl<NoiseNode>,
+}
+
 pub type NodeExprs = Arc<RwLock<HashMap<usize, (usize, Arc<Expr>)>>>;
 
+/// What a preview was last rendered from; edits which leave it unchanged, such as moving the
+/// preview of an upstream node, do not render the preview again.
+#[derive(PartialEq)]
+struct RenderedImage {
+    backend: Backend,
+    density: usize,
+
+    /// The expression of the node as text, because expressions cannot be compared.
+    expr: String,
+
+    scale: f64,
+    tileable: bool,
+    x: f64,
+    y: f64,
+}
+
 pub struct App {
+    aliasing_check: AliasingCheck,
+
+    /// When the graph was last kept for the next session, in seconds of input time.
+    autosave_time: Option<f64>,
+
+    /// Set by `save`, which has no input time, until the next frame records the time.
+    autosaved: bool,
+
+    /// The backend which evaluates the generators of the project.
+    backend: Backend,
+
+    bookmarks: Bookmarks,
+
+    /// Dims the nodes of the canvas which are not being looked for.
+    canvas_filter: CanvasFilter,
+
+    character_inspector: CharacterInspector,
+    chunk_checker: ChunkChecker,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    collab: Collab,
+
+    /// The copy of the project which is saved if the app panics.
+    #[cfg(not(target_arch = "wasm32"))]
+    crash_copy: CrashCopy,
+
+    /// The number of pixels rendered along each side of a point of the previews.
+    density: usize,
+
+    explorer: Explorer,
+    expr_tree: ExprTree,
+    favorites: Favorites,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    file_watcher: FileWatcher,
+
+    /// Renders previews on the GPU, if there is one; the threads render those it cannot.
+    #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+    gpu_preview: Option<GpuPreview>,
+
+    layer_stack: LayerStack,
+
+    /// Shows the graph as a stack of layers instead of a canvas of nodes.
+    layers_view: bool,
+
+    linter: Linter,
+
+    /// The highest preview density rendered, which limits the cost of previews on dense displays.
+    max_density: usize,
+
     node_exprs: NodeExprs,
+    node_ids: NodeIds,
+
+    /// The nodes of the canvas which are copied and duplicated.
+    node_selection: HashSet<usize>,
+
+    notes: Notes,
+    outline: Outline,
 
     #[cfg(not(target_arch = "wasm32"))]
     path: Option<PathBuf>,
 
+    /// The node whose waiting sub-images were moved ahead of the others, which follows the
+    /// selection.
+    prioritized_node_idx: Option<usize>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    profiler: Profiler,
+
+    read_only: bool,
+
+    /// When the previews waiting in the render queue were first requested, until it is empty.
+    render_start_time: Option<f64>,
+
+    /// How many seconds the render queue took to empty the last time it did.
+    render_time: Option<f64>,
+
+    rendered_images: HashMap<usize, RenderedImage>,
+
     snarl: Snarl<NoiseNode>,
+    sphere_preview: SpherePreview,
+    stability_analysis: StabilityAnalysis,
+    terrain_preview: TerrainPreview,
     threads: Threads,
-    removed_node_indices: HashSet<usize>,
-    updated_node_indices: HashSet<usize>,
+
+    /// Set once touch input is seen, which enlarges controls and pins.
+    touch: bool,
+
+    undo_history: UndoHistory,
     version: usize,
+
+    /// The state which the viewers of the graph edit, such as the selected node.
+    viewer_state: ViewerState,
+
+    wizard: Wizard,
 }
 
 impl App {
     #[cfg(not(target_arch = "wasm32"))]
     pub const EXTENSION: &'static str = "ron";
 
-    const IMAGE_COUNT: usize = Threads::IMAGE_COORDS as usize * Threads::IMAGE_COORDS as usize;
-    const IMAGE_SIZE: [usize; 2] = [
-        Threads::IMAGE_SIZE * Threads::IMAGE_COORDS as usize,
-        Threads::IMAGE_SIZE * Threads::IMAGE_COORDS as usize,
-    ];
+    const BACKEND_KEY: &'static str = "backend";
+    const BOOKMARKS_KEY: &'static str = "bookmarks";
+    const FAVORITES_KEY: &'static str = "favorites";
+    const LAYERS_VIEW_KEY: &'static str = "layers_view";
+    const MAX_DENSITY_KEY: &'static str = "max_density";
+    const NODE_IDS_KEY: &'static str = "node_ids";
+    const NOTES_KEY: &'static str = "notes";
+
+    const TOUCH_BUTTON_PADDING: Vec2 = vec2(8.0, 6.0);
+    const TOUCH_INTERACT_HEIGHT: f32 = 28.0;
+    const TOUCH_PIN_SIZE: f32 = 16.0;
+
+    const DEFAULT_MAX_DENSITY: usize = 2;
+    const MAX_DENSITY: usize = 4;
+
+    /// The number of sub-images requested for each preview: every sub-image coarse, then full.
+    const REQUEST_COUNT: usize =
+        2 * Threads::IMAGE_COORDS as usize * Threads::IMAGE_COORDS as usize;
 
     pub fn new(#[allow(unused_variables)] cc: &CreationContext<'_>) -> Self {
-        let snarl: Snarl<NoiseNode> = if let Some(storage) = cc.storage {
-            get_value(storage, APP_KEY).unwrap_or_default()
-        } else {
-            Default::default()
-        };
+        let backend = cc
+            .storage
+            .and_then(|storage| get_value(storage, Self::BACKEND_KEY))
+            .unwrap_or_default();
+
+        // Graphs kept by older versions are followed by the identifiers of their nodes
+        let (snarl, mut node_ids, notes): (Snarl<NoiseNode>, NodeIds, String) =
+            if let Some(storage) = cc.storage {
+                let (snarl, node_ids) = get_value::<SavedGraph>(storage, APP_KEY)
+                    .map(|graph| graph.load(backend, &NodeIds::default()))
+                    .unwrap_or_else(|| {
+                        (
+                            get_value(storage, APP_KEY).unwrap_or_default(),
+                            get_value(storage, Self::NODE_IDS_KEY).unwrap_or_default(),
+                        )
+                    });
+
+                (
+                    snarl,
+                    node_ids,
+                    get_value(storage, Self::NOTES_KEY).unwrap_or_default(),
+                )
+            } else {
+                Default::default()
+            };
+        let bookmarks = cc
+            .storage
+            .and_then(|storage| get_value(storage, Self::BOOKMARKS_KEY))
+            .unwrap_or_default();
+        let max_density = cc
+            .storage
+            .and_then(|storage| get_value(storage, Self::MAX_DENSITY_KEY))
+            .unwrap_or(Self::DEFAULT_MAX_DENSITY)
+            .clamp(1, Self::MAX_DENSITY);
+        let favorites = cc
+            .storage
+            .and_then(|storage| get_value(storage, Self::FAVORITES_KEY))
+            .unwrap_or_default();
+        let layers_view = cc
+            .storage
+            .and_then(|storage| get_value(storage, Self::LAYERS_VIEW_KEY))
+            .unwrap_or_default();
+        node_ids.update(&snarl);
 
         let node_exprs = Default::default();
         let threads = Threads::new(&node_exprs);
-        let removed_node_indices = Default::default();
-        let updated_node_indices = Self::all_image_node_indices(&snarl).collect();
+        let undo_history = UndoHistory::new(&snarl);
+        let viewer_state = ViewerState {
+            master_seed: Self::master_seed(&snarl),
+            updated_node_indices: Self::all_image_node_indices(&snarl).collect(),
+            ..Default::default()
+        };
 
         Self {
+            aliasing_check: Default::default(),
+            autosave_time: None,
+            autosaved: false,
+            backend,
+            bookmarks: Bookmarks::new(bookmarks),
+            character_inspector: Default::default(),
+            canvas_filter: Default::default(),
+            chunk_checker: Default::default(),
+
+            #[cfg(not(target_arch = "wasm32"))]
+            collab: Default::default(),
+
+            #[cfg(not(target_arch = "wasm32"))]
+            crash_copy: Default::default(),
+
+            density: 1,
+            explorer: Default::default(),
+            expr_tree: Default::default(),
+            favorites: Favorites::new(favorites),
+
+            #[cfg(not(target_arch = "wasm32"))]
+            file_watcher: Default::default(),
+
+            #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+            gpu_preview: GpuPreview::new(threads.sender()),
+
+            layer_stack: Default::default(),
+            layers_view,
+            linter: Default::default(),
+            max_density,
             node_exprs,
+            node_ids,
+            node_selection: Default::default(),
+            notes: Notes::new(notes),
+            outline: Default::default(),
 
             #[cfg(not(target_arch = "wasm32"))]
             path: None,
 
+            prioritized_node_idx: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            profiler: Default::default(),
+
+            read_only: false,
+            render_start_time: None,
+            render_time: None,
+            rendered_images: Default::default(),
             snarl,
+            sphere_preview: Default::default(),
+            stability_analysis: Default::default(),
+            terrain_preview: Default::default(),
             threads,
-            removed_node_indices,
-            updated_node_indices,
+            touch: false,
+            undo_history,
             version: 0,
+            viewer_state,
+            wizard: Default::default(),
         }
     }
 
@@ -98,37 +436,321 @@ impl App {
         FileDialog::new().add_filter("Noise Project", &[Self::EXTENSION])
     }
 
+    /// Returns the preview density for the display, rounded up to whole pixels and capped.
+    fn display_density(&self, ctx: &Context) -> usize {
+        (ctx.pixels_per_point().ceil() as usize).clamp(1, self.max_density)
+    }
+
     fn has_changes(&self) -> bool {
-        !self.removed_node_indices.is_empty() || !self.updated_node_indices.is_empty()
+        !self.viewer_state.removed_node_indices.is_empty()
+            || !self.viewer_state.updated_node_indices.is_empty()
+    }
+
+    /// Nodes which inherit the master seed store a copy of it, so projects do not save it separately.
+    fn master_seed(snarl: &Snarl<NoiseNode>) -> u32 {
+        snarl
+            .node_indices()
+            .find_map(|(_, node)| node.master_seed().flatten())
+            .unwrap_or_default()
+    }
+
+    fn image_size(density: usize) -> [usize; 2] {
+        let len = Threads::IMAGE_SIZE * Threads::IMAGE_COORDS as usize * density;
+
+        [len, len]
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Project<Snarl<NoiseNode>>> {
+        let path = path.as_ref();
+        let src = read_to_string(path).inspect_err(|_| warn!("Unable to open file"))?;
+
+        // Projects saved before notes were added contain only the graph
+        let mut project = from_str(&src)
+            .or_else(|_| {
+                from_str(&src).map(
+                    |LegacyProject {
+                         backend,
+                         bookmarks,
+                         node_ids,
+                         notes,
+                         snarl,
+                     }| Project {
+                        backend,
+                        bookmarks,
+                        dir: None,
+                        node_ids,
+                        notes,
+                        snarl,
+                    },
+                )
+            })
+            .or_else(|_| {
+                from_str(&src).map(|snarl| Project {
+                    backend: Default::default(),
+                    bookmarks: vec![],
+                    dir: None,
+                    node_ids: Default::default(),
+                    notes: String::new(),
+                    snarl,
+                })
+            })
+            .inspect_err(|_: &ron::error::SpannedError| warn!("Unable to read file"))?;
+
+        if let Some(dir) = path.parent() {
+            asset::open_in(&mut project.snarl, dir);
+        }
+
+        Ok(project)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_project(&mut self, path: PathBuf) {
+        self.expr_tree.clear();
+        self.viewer_state.selected_node_idx = None;
+        self.outline.focused_node_idx = None;
+
+        let project = Self::open(&path).unwrap_or_default();
+        self.backend = project.backend;
+        self.bookmarks.clear();
+        self.bookmarks.bookmarks = project.bookmarks;
+        self.node_ids = project.node_ids;
+        self.node_selection.clear();
+        self.notes.text = project.notes;
+        self.snarl = project.snarl;
+        self.viewer_state.master_seed = Self::master_seed(&self.snarl);
+        self.profiler.clear();
+
+        // Seeds shipped in games must keep producing the same noise, so changes are pointed out
+        let outdated_count = self
+            .snarl
+            .node_indices()
+            .filter(|(_, node)| node.is_algorithm_outdated())
+            .count();
+        if outdated_count > 0 {
+            MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title("Noise algorithms have changed")
+                .set_description(&format!(
+                    "{outdated_count} node(s) of this project were created with older versions \
+                    of their noise algorithms, so their seeds may no longer produce the same \
+                    noise.\n\nThe Lint window lists them."
+                ))
+                .show();
+        }
+
+        self.viewer_state.seed_history.clear();
+        self.file_watcher.watch(&path);
+        self.path = Some(path);
+        self.undo_history = UndoHistory::new(&self.snarl);
+        self.viewer_state.updated_node_indices =
+            Self::all_image_node_indices(&self.snarl).collect();
+    }
+
+    /// Moves the waiting sub-images of the selected node ahead of the others, and those of the
+    /// previously selected node back.
+    fn prioritize_selected_node(&mut self) {
+        let selected_node_idx = self.viewer_state.selected_node_idx;
+        if self.prioritized_node_idx == selected_node_idx {
+            return;
+        }
+
+        let prioritize = |node_idx, priority| {
+            self.threads.prioritize(node_idx, priority);
+
+            #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+            if let Some(gpu_preview) = &self.gpu_preview {
+                gpu_preview.prioritize(node_idx, priority);
+            }
+        };
+
+        if let Some(node_idx) = self.prioritized_node_idx {
+            if self.node_exprs.read().unwrap().contains_key(&node_idx) {
+                prioritize(
+                    node_idx,
+                    Self::priority(node_idx, selected_node_idx, &self.snarl),
+                );
+            }
+        }
+
+        if let Some(node_idx) = selected_node_idx {
+            prioritize(node_idx, Priority::Selected);
+        }
+
+        self.prioritized_node_idx = selected_node_idx;
+    }
+
+    fn priority(
+        node_idx: usize,
+        selected_node_idx: Option<usize>,
+        snarl: &Snarl<NoiseNode>,
+    ) -> Priority {
+        if selected_node_idx == Some(node_idx) {
+            Priority::Selected
+        } else if snarl
+            .out_pin(OutPinId {
+                node: node_idx,
+                output: 0,
+            })
+            .remotes
+            .is_empty()
+        {
+            Priority::Output
+        } else {
+            Priority::Background
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn project(&self) -> Project<&Snarl<NoiseNode>> {
+        Project {
+            backend: self.backend,
+            bookmarks: self.bookmarks.bookmarks.clone(),
+            dir: None,
+            node_ids: self.node_ids.clone(),
+            notes: self.notes.text.clone(),
+            snarl: &self.snarl,
+        }
+    }
+
+    /// Returns the project as saved to a path, with the paths of the assets within its directory
+    /// relative to it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn project_in(&self, path: &Path) -> Project<&Snarl<NoiseNode>> {
+        Project {
+            dir: path.parent().map(Path::to_owned),
+            ..self.project()
+        }
     }
 
+    /// Disables editing, other than the values of named constants, and opens a project if given.
     #[cfg(not(target_arch = "wasm32"))]
-    fn open(path: impl AsRef<Path>) -> anyhow::Result<Snarl<NoiseNode>> {
-        Ok(
-            from_reader(OpenOptions::new().read(true).open(path).map_err(|err| {
-                warn!("Unable to open file");
-                err
-            })?)
-            .map_err(|err: ron::error::SpannedError| {
-                warn!("Unable to read file");
-                err
-            })?,
-        )
+    pub fn read_only(mut self, path: Option<PathBuf>) -> Self {
+        self.read_only = true;
+
+        if let Some(path) = path {
+            self.open_project(path);
+        }
+
+        self
+    }
+
+    /// Opens the project saved when the previous session crashed, if it was kept.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn recover(mut self) -> Self {
+        if let Some(path) = crash::recovered_project() {
+            self.open_project(path.clone());
+
+            // The dump is removed, so the project must be saved somewhere else
+            self.file_watcher.clear();
+            self.path = None;
+            remove_file(path).unwrap_or_default();
+        }
+
+        self
+    }
+
+    fn redo(&mut self) {
+        if let Some(snarl) = self.undo_history.redo() {
+            self.replace_snarl(snarl);
+        }
     }
 
     fn remove_nodes(&mut self) {
         let mut node_exprs = self.node_exprs.write().unwrap();
 
-        for node_idx in self.removed_node_indices.drain() {
+        for node_idx in self.viewer_state.removed_node_indices.drain() {
             node_exprs.remove(&node_idx);
+            self.rendered_images.remove(&node_idx);
+            self.threads.cancel(node_idx);
+
+            #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+            if let Some(gpu_preview) = &self.gpu_preview {
+                gpu_preview.cancel(node_idx);
+            }
+
+            if let Some(node_id) = self.node_ids.id(node_idx) {
+                self.bookmarks.remove(node_id);
+            }
+
+            if self.bookmarks.focused_node_idx == Some(node_idx) {
+                self.bookmarks.focused_node_idx = None;
+            }
+
+            self.node_ids.remove(node_idx);
+            self.node_selection.remove(&node_idx);
+
+            if self.viewer_state.selected_node_idx == Some(node_idx) {
+                self.expr_tree.clear();
+                self.viewer_state.selected_node_idx = None;
+            }
+
+            if self.outline.focused_node_idx == Some(node_idx) {
+                self.outline.focused_node_idx = None;
+            }
+
+            self.viewer_state.seed_history.remove(node_idx);
 
             // Just in case (never happens!)
-            self.updated_node_indices.remove(&node_idx);
+            self.viewer_state.updated_node_indices.remove(&node_idx);
         }
     }
 
+    /// Replaces the graph, rendering again only the nodes which changed; the images of the others
+    /// are kept.
+    fn replace_snarl(&mut self, mut snarl: Snarl<NoiseNode>) {
+        let old_node_indices = self
+            .snarl
+            .node_indices()
+            .map(|(node_idx, _)| node_idx)
+            .collect::<HashSet<_>>();
+        let node_indices = snarl
+            .node_indices()
+            .map(|(node_idx, _)| node_idx)
+            .collect::<Vec<_>>();
+
+        for &node_idx in &node_indices {
+            let is_unchanged = old_node_indices.contains(&node_idx)
+                && to_string(self.snarl.get_node(node_idx)).ok()
+                    == to_string(snarl.get_node(node_idx)).ok();
+
+            if is_unchanged {
+                if let (Some(old_image), Some(image)) = (
+                    self.snarl.get_node_mut(node_idx).image_mut(),
+                    snarl.get_node_mut(node_idx).image_mut(),
+                ) {
+                    swap(old_image, image);
+                }
+            } else {
+                self.viewer_state.updated_node_indices.insert(node_idx);
+            }
+        }
+
+        self.viewer_state.removed_node_indices.extend(
+            old_node_indices
+                .into_iter()
+                .filter(|node_idx| !node_indices.contains(node_idx)),
+        );
+
+        self.snarl = snarl;
+        self.viewer_state.master_seed = Self::master_seed(&self.snarl);
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub fn save_as<T>(path: impl AsRef<Path>, value: &T) -> anyhow::Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        Self::save_commented_as(path, None, value)
+    }
+
+    /// Saves a value preceded by a comment, such as the hash of the graph an expression is from.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_commented_as<T>(
+        path: impl AsRef<Path>,
+        comment: Option<&str>,
+        value: &T,
+    ) -> anyhow::Result<()>
     where
         T: ?Sized + Serialize,
     {
@@ -138,23 +760,149 @@ impl App {
             path.set_extension(Self::EXTENSION);
         }
 
-        let file = OpenOptions::new()
+        let mut file = OpenOptions::new()
             .write(true)
             .create(true)
             .truncate(true)
             .open(path)
-            .map_err(|err| {
-                warn!("Unable to create file");
-                err
-            })?;
-        to_writer_pretty(file, value, PrettyConfig::default()).map_err(|err| {
-            warn!("Unable to write file");
-            err
-        })?;
+            .inspect_err(|_| warn!("Unable to create file"))?;
+
+        for line in comment.iter().flat_map(|comment| comment.lines()) {
+            writeln!(file, "// {line}")?;
+        }
+
+        canonical::to_writer(file, value).inspect_err(|_| warn!("Unable to write file"))?;
 
         Ok(())
     }
 
+    /// Shows the render queue, how long previews took to render, the memory of their textures and
+    /// when the graph was last autosaved, along the bottom of the window.
+    fn show_status_bar(&mut self, ctx: &Context) {
+        #[allow(unused_mut)]
+        let mut status = self.threads.status();
+        let time = ctx.input(|input| input.time);
+
+        #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+        if let Some(gpu_preview) = &self.gpu_preview {
+            status.add(gpu_preview.status());
+        }
+
+        if status.is_empty() {
+            if let Some(render_start_time) = self.render_start_time.take() {
+                self.render_time = Some(time - render_start_time);
+            }
+        }
+
+        if take(&mut self.autosaved) {
+            self.autosave_time = Some(time);
+        }
+
+        let texture_len = self
+            .snarl
+            .node_indices()
+            .filter_map(|(_, node)| node.image())
+            .flat_map(|image| [&image.texture, &image.overlay_texture])
+            .flatten()
+            .map(|texture| texture.size()[0] * texture.size()[1] * size_of::<Color32>())
+            .sum::<usize>();
+
+        TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if status.is_empty() {
+                    ui.weak("Previews up to date");
+                } else {
+                    ui.spinner();
+                    ui.label(format!(
+                        "Rendering {} preview{}",
+                        status.nodes.max(1),
+                        if status.nodes > 1 { "s" } else { "" }
+                    ))
+                    .on_hover_ui(|ui| {
+                        Grid::new("render_queue").show(ui, |ui| {
+                            for priority in Priority::ALL.into_iter().rev() {
+                                ui.label(priority.name());
+                                ui.label(format!(
+                                    "{} sub-images waiting",
+                                    status.sub_images[priority as usize]
+                                ));
+                                ui.end_row();
+                            }
+
+                            ui.label("Workers");
+                            ui.label(format!("{} sub-images rendering", status.rendering));
+                            ui.end_row();
+                        });
+                    });
+                }
+
+                if let Some(render_time) = self.render_time {
+                    ui.separator();
+                    ui.label(format!("Last render {render_time:.2} s"))
+                        .on_hover_text(
+                            "The time from requesting previews until the render queue was empty",
+                        );
+                }
+
+                ui.separator();
+                ui.label(format!(
+                    "Textures {:.1} MiB",
+                    texture_len as f64 / (1024.0 * 1024.0)
+                ))
+                .on_hover_text("The memory of the preview textures, at the current density");
+
+                ui.separator();
+
+                if self.read_only {
+                    ui.weak("Autosave off")
+                        .on_hover_text("Graphs opened read-only are not kept between sessions");
+                } else if let Some(autosave_time) = self.autosave_time {
+                    ui.label(format!(
+                        "Autosaved {:.0} s ago",
+                        (time - autosave_time).max(0.0)
+                    ))
+                    .on_hover_text("The graph is kept for the next session every 30 seconds");
+                } else {
+                    ui.weak("Not autosaved yet")
+                        .on_hover_text("The graph is kept for the next session every 30 seconds");
+                }
+            });
+        });
+
+        // Sub-images are shown as they arrive, which is not otherwise a reason to repaint
+        if !status.is_empty() {
+            ctx.request_repaint();
+        } else if self.autosave_time.is_some() {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+    }
+
+    /// Replaces the graph with the project of a collaboration session, rendering again only the
+    /// nodes which changed.
+    ///
+    /// Edits cannot be undone past a sync, which would otherwise revert the edits of others.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn sync_project(&mut self, project: Project<Snarl<NoiseNode>>) {
+        if project.backend != self.backend {
+            self.backend = project.backend;
+            self.viewer_state
+                .updated_node_indices
+                .extend(Self::all_image_node_indices(&project.snarl));
+        }
+
+        self.bookmarks.bookmarks = project.bookmarks;
+        self.node_ids = project.node_ids;
+        self.notes.text = project.notes;
+        self.replace_snarl(project.snarl);
+        self.undo_history = UndoHistory::new(&self.snarl);
+    }
+
+    fn undo(&mut self) {
+        if let Some(snarl) = self.undo_history.undo(&self.snarl) {
+            self.replace_snarl(snarl);
+        }
+    }
+
     fn update_images(&mut self) {
         thread_local! {
             static NODE_INDICES: RefCell<Option<HashSet<usize>>> = RefCell::new(Some(Default::default()));
@@ -166,7 +914,7 @@ impl App {
             node_indices.insert(node_idx);
         }
 
-        for (node_idx, image_version, coord, image) in self.threads.try_recv_iter() {
+        for (node_idx, image_version, coord, coarse, image) in self.threads.try_recv_iter() {
             // We have to check to make sure snarl *still* contains this index because it may have
             // been removed by the time the thread has responded to the image request
             if !node_indices.contains(&node_idx) {
@@ -174,6 +922,10 @@ impl App {
             }
 
             if let Some(Image {
+                density,
+                display,
+                pixels,
+                refined_coords,
                 texture: Some(texture),
                 version,
                 ..
@@ -185,9 +937,32 @@ impl App {
                     continue;
                 }
 
+                // Workers may finish a coarse sub-image after the full one of the same coordinate
+                if coarse {
+                    if refined_coords.contains(&coord) {
+                        continue;
+                    }
+                } else {
+                    refined_coords.insert(coord);
+                }
+
+                // Textures are positioned using [x, y] and the sub-image rows are laid out along y
+                let [x, y] = Threads::coord_to_row_col(coord, *density);
+                let size = Threads::IMAGE_SIZE * *density;
+                let [width, _] = Self::image_size(*density);
+                for (image_y, image_row) in image.chunks_exact(size).enumerate() {
+                    let start = (y + image_y) * width + x;
+                    pixels[start..start + size].copy_from_slice(image_row);
+                }
+
+                // The sampled pixels are kept as they are for overlays, only the texture is adjusted
+                let image = image
+                    .into_iter()
+                    .map(|gray| display.apply(gray))
+                    .collect::<Vec<_>>();
                 texture.set_partial(
-                    Threads::coord_to_row_col(coord),
-                    ColorImage::from_gray([Threads::IMAGE_SIZE, Threads::IMAGE_SIZE], &image),
+                    [x, y],
+                    ColorImage::from_gray([size, size], &image),
                     Default::default(),
                 );
             }
@@ -203,11 +978,20 @@ impl App {
             static TEMP_NODE_INDICES: RefCell<Option<Vec<usize>>> = RefCell::new(Some(Default::default()));
         }
 
+        // Previews whose shaders did not compile are rendered again, by the threads this time
+        #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+        if let Some(gpu_preview) = &self.gpu_preview {
+            for node_idx in gpu_preview.try_recv_failed() {
+                self.rendered_images.remove(&node_idx);
+                self.viewer_state.updated_node_indices.insert(node_idx);
+            }
+        }
+
         let mut child_node_indices = CHILD_NODE_INDICES.take().unwrap();
         let mut temp_node_indices = TEMP_NODE_INDICES.take().unwrap();
 
         // Before we process the user-updated nodes, we must propagate updates to child nodes
-        for node_idx in self.updated_node_indices.iter().copied() {
+        for node_idx in self.viewer_state.updated_node_indices.iter().copied() {
             temp_node_indices.push(node_idx);
             while let Some(node_idx) = temp_node_indices.pop() {
                 for node_idx in self
@@ -226,31 +1010,68 @@ impl App {
             }
         }
 
-        self.updated_node_indices.extend(child_node_indices.drain());
+        self.viewer_state
+            .updated_node_indices
+            .extend(child_node_indices.drain());
         CHILD_NODE_INDICES.set(Some(child_node_indices));
         TEMP_NODE_INDICES.set(Some(temp_node_indices));
 
+        // Only previews whose expression or area changed are rendered again, because updates reach
+        // every node downstream of an edit even when the edit does not change their expressions
+        let mut exprs = HashMap::with_capacity(self.viewer_state.updated_node_indices.len());
+        self.viewer_state.updated_node_indices.retain(|&node_idx| {
+            let node = self.snarl.get_node(node_idx);
+            let Some(image) = node.image() else {
+                return false;
+            };
+
+            let expr = node.expr(node_idx, &self.snarl);
+            let rendered_image = RenderedImage {
+                backend: self.backend,
+                density: self.density,
+                expr: to_string(&expr).unwrap_or_default(),
+                scale: image.scale,
+                tileable: image.tileable,
+                x: image.x,
+                y: image.y,
+            };
+            let is_rendered = image.texture.is_some()
+                && image.density == self.density
+                && self.rendered_images.get(&node_idx) == Some(&rendered_image);
+
+            if !is_rendered {
+                exprs.insert(node_idx, expr);
+                self.rendered_images.insert(node_idx, rendered_image);
+            }
+
+            !is_rendered
+        });
+
         // First we update the version of all updated images
         self.version = self.version.wrapping_add(1);
-        for node_idx in self.updated_node_indices.iter().copied() {
+        for node_idx in self.viewer_state.updated_node_indices.iter().copied() {
             let node = self.snarl.get_node_mut(node_idx);
             if let Some(image) = node.image_mut() {
-                // Ensure all image nodes contain a valid texture
-                if image.texture.is_none() {
+                // Ensure all image nodes contain a valid texture for the current density
+                if image.texture.is_none() || image.density != self.density {
                     debug!("Creating image for #{node_idx}");
 
+                    let size = Self::image_size(self.density);
+                    image.density = self.density;
                     image.texture = Some(ctx.load_texture(
                         format!("image{node_idx}"),
-                        ColorImage::new(Self::IMAGE_SIZE, Color32::TRANSPARENT),
+                        ColorImage::new(size, Color32::TRANSPARENT),
                         Default::default(),
                     ));
+                    image.pixels = vec![0; size[0] * size[1]];
                 }
 
+                image.refined_coords.clear();
                 image.version = self.version;
             }
         }
 
-        type Request = (usize, usize, ImageInfo);
+        type Request = (usize, usize, ImageInfo, Priority);
 
         thread_local! {
             static REQUESTS: RefCell<Option<Vec<Request>>> = RefCell::new(Some(Default::default()));
@@ -259,28 +1080,58 @@ impl App {
         let mut requests = REQUESTS.take().unwrap();
 
         // Next we update the expressions of all updated images and request new images
-        for node_idx in self.updated_node_indices.drain() {
+        for node_idx in self.viewer_state.updated_node_indices.drain() {
             let node = self.snarl.get_node(node_idx);
             if let Some(image) = node.image() {
                 debug!("Updating image for #{node_idx}");
 
-                self.node_exprs.write().unwrap().insert(
-                    node_idx,
-                    (image.version, Arc::new(node.expr(node_idx, &self.snarl))),
-                );
+                let expr = Arc::new(exprs.remove(&node_idx).unwrap());
+                self.node_exprs
+                    .write()
+                    .unwrap()
+                    .insert(node_idx, (image.version, Arc::clone(&expr)));
+
+                // Sub-images of the previous version would only be skipped once taken by a worker
+                self.threads.cancel(node_idx);
+
+                let priority =
+                    Self::priority(node_idx, self.viewer_state.selected_node_idx, &self.snarl);
+
+                #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+                if let Some(gpu_preview) = &self.gpu_preview {
+                    gpu_preview.cancel(node_idx);
+
+                    if gpu_preview.render(node_idx, &expr, image, priority) {
+                        self.render_start_time
+                            .get_or_insert(ctx.input(|input| input.time));
+
+                        continue;
+                    }
+                }
 
                 // We request coordinate chunks from the threads using pre-shuffled data so that
-                // all the responses come back in a static-like pattern and not row by row
-                for coord in shuffled_u8(image.version).iter().copied() {
+                // all the responses come back in a static-like pattern and not row by row; every
+                // chunk is first requested coarse, which is quick enough to follow slider drags,
+                // and then refined to full resolution
+                let coords = shuffled_u8(image.version);
+                for (coarse, coord) in [true, false]
+                    .into_iter()
+                    .flat_map(|coarse| coords.iter().map(move |&coord| (coarse, coord)))
+                {
                     requests.push((
                         node_idx,
                         image.version,
                         ImageInfo {
+                            backend: self.backend,
+                            coarse,
                             coord,
+                            density: image.density,
                             scale: image.scale,
+                            tileable: image.tileable,
                             x: image.x,
                             y: image.y,
                         },
+                        priority,
                     ));
                 }
             }
@@ -288,15 +1139,21 @@ impl App {
 
         // All requests (which can be for multiple images) are sent in interleaved order so that
         // frequent requests don't always hit one image and cause the others to appear paused
-        let image_count = requests.len() / Self::IMAGE_COUNT;
-        for request_idx in 0..Self::IMAGE_COUNT {
+        let image_count = requests.len() / Self::REQUEST_COUNT;
+        for request_idx in 0..Self::REQUEST_COUNT {
             for image_idx in 0..image_count {
-                let (node_idx, image_version, image_info) =
-                    requests[image_idx * Self::IMAGE_COUNT + request_idx];
-                self.threads.send(node_idx, image_version, image_info);
+                let (node_idx, image_version, image_info, priority) =
+                    requests[image_idx * Self::REQUEST_COUNT + request_idx];
+                self.threads
+                    .send(node_idx, image_version, image_info, priority);
             }
         }
 
+        if !requests.is_empty() {
+            self.render_start_time
+                .get_or_insert(ctx.input(|input| input.time));
+        }
+
         requests.clear();
         REQUESTS.set(Some(requests));
     }
@@ -304,22 +1161,74 @@ impl App {
 
 impl eframe::App for App {
     fn save(&mut self, storage: &mut dyn Storage) {
-        set_value(storage, APP_KEY, &self.snarl);
+        // The graph being edited is kept for the next session instead of the one being viewed
+        if self.read_only {
+            return;
+        }
+
+        set_value(
+            storage,
+            APP_KEY,
+            &SavedGraph::new(&self.snarl, &self.node_ids),
+        );
+        set_value(storage, Self::BACKEND_KEY, &self.backend);
+        set_value(storage, Self::BOOKMARKS_KEY, &self.bookmarks.bookmarks);
+        set_value(storage, Self::FAVORITES_KEY, &self.favorites.node_names);
+        set_value(storage, Self::LAYERS_VIEW_KEY, &self.layers_view);
+        set_value(storage, Self::MAX_DENSITY_KEY, &self.max_density);
+        set_value(storage, Self::NOTES_KEY, &self.notes.text);
+
+        self.autosaved = true;
     }
 
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+        // Panics of other threads are reported here, as dialogs must be shown from this thread
+        #[cfg(not(target_arch = "wasm32"))]
+        crash::report();
+
         #[cfg(target_arch = "wasm32")]
         self.threads.update();
 
         self.update_images();
 
+        // Previews are rendered again when moved to a display of another density
+        let density = self.display_density(ctx);
+        if density != self.density {
+            self.density = density;
+            self.viewer_state
+                .updated_node_indices
+                .extend(Self::all_image_node_indices(&self.snarl));
+        }
+
+        if !self.touch && ctx.input(|input| input.any_touches()) {
+            self.touch = true;
+
+            let mut style = (*ctx.style()).clone();
+            style.spacing.button_padding = Self::TOUCH_BUTTON_PADDING;
+            style.spacing.interact_size.y = Self::TOUCH_INTERACT_HEIGHT;
+            ctx.set_style(style);
+        }
+
         #[cfg(not(target_arch = "wasm32"))]
         TopBottomPanel::top("top_panel").show(ctx, |ui| {
             menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
-                    if ui.button("New").clicked() {
+                    if !self.read_only && ui.button("New").clicked() {
+                        self.backend = Default::default();
+                        self.bookmarks.clear();
+                        self.expr_tree.clear();
+                        self.viewer_state.selected_node_idx = None;
+                        self.file_watcher.clear();
+                        self.outline.focused_node_idx = None;
+                        self.viewer_state.master_seed = 0;
+                        self.node_ids.clear();
+                        self.node_selection.clear();
+                        self.notes.text.clear();
                         self.path = None;
+                        self.profiler.clear();
+                        self.viewer_state.seed_history.clear();
                         self.snarl = Snarl::new();
+                        self.undo_history = UndoHistory::new(&self.snarl);
 
                         ui.close_menu();
                     }
@@ -328,32 +1237,148 @@ impl eframe::App for App {
 
                     if ui.button("Open File...").clicked() {
                         if let Some(path) = Self::file_dialog().pick_file() {
-                            self.snarl = Self::open(&path).unwrap_or_default();
-                            self.path = Some(path);
-                            self.updated_node_indices =
-                                Self::all_image_node_indices(&self.snarl).collect();
+                            self.open_project(path);
                         }
 
                         ui.close_menu();
                     }
 
-                    if let Some(path) = &self.path {
-                        if ui.button("Save").clicked() {
-                            Self::save_as(path, &self.snarl).unwrap_or_default();
+                    if !self.read_only {
+                        if let Some(path) = &self.path {
+                            if ui.button("Save").clicked() {
+                                Self::save_as(path, &self.project_in(path)).unwrap_or_default();
+                                self.file_watcher.watch(path);
+
+                                ui.close_menu();
+                            }
+                        } else {
+                            ui.horizontal(|ui| {
+                                ui.add_space(2.0);
+                                ui.label("Save");
+                            });
+                        }
+
+                        if ui.button("Save As...").clicked() {
+                            if let Some(mut path) = Self::file_dialog().save_file() {
+                                if path.extension().is_none() {
+                                    path.set_extension(Self::EXTENSION);
+                                }
+
+                                Self::save_as(&path, &self.project_in(&path)).unwrap_or_default();
+                                self.file_watcher.watch(&path);
+                                self.path = Some(path);
+                            }
 
                             ui.close_menu();
                         }
-                    } else {
-                        ui.horizontal(|ui| {
-                            ui.add_space(2.0);
-                            ui.label("Save");
+
+                        if let Some(path) = self.path.clone() {
+                            if ui
+                                .button("Collect Assets")
+                                .on_hover_text(
+                                    "Copy the linked files of this project into an assets folder \
+                                    next to it and save it, so that the project may be shipped \
+                                    along with the folder",
+                                )
+                                .clicked()
+                            {
+                                let dir = path.parent().unwrap_or(Path::new(""));
+                                match asset::collect(&mut self.snarl, dir) {
+                                    Ok(_) => {
+                                        Self::save_as(&path, &self.project_in(&path))
+                                            .unwrap_or_default();
+                                        self.file_watcher.watch(&path);
+                                    }
+                                    Err(err) => {
+                                        MessageDialog::new()
+                                            .set_level(MessageLevel::Error)
+                                            .set_title("Unable to collect assets")
+                                            .set_description(&err.to_string())
+                                            .show();
+                                    }
+                                }
+
+                                ui.close_menu();
+                            }
+                        }
+
+                        ui.separator();
+
+                        ui.menu_button("Noise Backend", |ui| {
+                            for backend in Backend::ALL {
+                                if ui
+                                    .radio_value(&mut self.backend, backend, backend.name())
+                                    .on_hover_text(
+                                        "Evaluates the generators and fractals of this project; \
+                                        seeds give different noise with each backend",
+                                    )
+                                    .changed()
+                                {
+                                    self.viewer_state
+                                        .updated_node_indices
+                                        .extend(Self::all_image_node_indices(&self.snarl));
+                                }
+                            }
                         });
                     }
 
-                    if ui.button("Save As...").clicked() {
-                        if let Some(path) = Self::file_dialog().save_file() {
-                            Self::save_as(&path, &self.snarl).unwrap_or_default();
-                            self.path = Some(path);
+                    ui.separator();
+
+                    if ui
+                        .button("Check Snapshot...")
+                        .on_hover_text(
+                            "Check that the expression of a snapshot saved with Verify Export \
+                            still samples the same values",
+                        )
+                        .clicked()
+                    {
+                        if let Some(path) = Self::file_dialog().pick_file() {
+                            match Snapshot::open(path) {
+                                Ok(snapshot) => Viewer::verify_export(&snapshot),
+                                Err(err) => {
+                                    MessageDialog::new()
+                                        .set_level(MessageLevel::Error)
+                                        .set_title("Unable to open snapshot")
+                                        .set_description(&format!("{err:#}"))
+                                        .show();
+                                }
+                            }
+                        }
+
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Export Report...").clicked() {
+                        if let Some(mut path) = FileDialog::new()
+                            .add_filter("HTML Report", &["html"])
+                            .save_file()
+                        {
+                            if path.extension().is_none() {
+                                path.set_extension("html");
+                            }
+
+                            let title = self
+                                .path
+                                .as_ref()
+                                .and_then(|path| path.file_stem())
+                                .map_or_else(
+                                    || "Untitled".to_owned(),
+                                    |file_stem| file_stem.to_string_lossy().into_owned(),
+                                );
+
+                            Report::new(
+                                &title,
+                                &self.notes.text,
+                                &self.snarl,
+                                &self.node_ids,
+                                &mut self.viewer_state.viewer(
+                                    self.backend,
+                                    self.read_only,
+                                    self.touch,
+                                ),
+                            )
+                            .save(path)
+                            .unwrap_or_default();
                         }
 
                         ui.close_menu();
@@ -365,25 +1390,139 @@ impl eframe::App for App {
                         ctx.send_viewport_cmd(ViewportCommand::Close);
                     }
                 });
+                ui.menu_button("Edit", |ui| {
+                    if ui
+                        .add_enabled(
+                            self.undo_history.can_undo(),
+                            Button::new("Undo")
+                                .shortcut_text(ctx.format_shortcut(&UndoHistory::UNDO)),
+                        )
+                        .clicked()
+                    {
+                        self.undo();
+                        ui.close_menu();
+                    }
+
+                    if ui
+                        .add_enabled(
+                            self.undo_history.can_redo(),
+                            Button::new("Redo")
+                                .shortcut_text(ctx.format_shortcut(&UndoHistory::REDO)),
+                        )
+                        .clicked()
+                    {
+                        self.redo();
+                        ui.close_menu();
+                    }
+                });
+                ui.menu_button("View", |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Graph");
+                        ui.selectable_value(&mut self.layers_view, false, "Canvas");
+                        ui.selectable_value(&mut self.layers_view, true, "Layers")
+                            .on_hover_text(
+                                "Edit the graph as a stack of layers, from a generator at the \
+                                bottom to the modifiers applied to it",
+                            );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Max Preview Density");
+                        ui.add(
+                            DragValue::new(&mut self.max_density)
+                                .clamp_range(1..=Self::MAX_DENSITY)
+                                .suffix("×"),
+                        )
+                        .on_hover_text(
+                            "Previews are rendered at the pixel density of the display, up to \
+                            this limit; lower values render faster",
+                        );
+                    });
+                });
                 ui.add_space(16.0);
 
                 widgets::global_dark_light_mode_buttons(ui);
+
+                if self.read_only {
+                    ui.add_space(16.0);
+                    ui.weak("Read-only")
+                        .on_hover_text("Only the values of named constants may be changed");
+                }
             });
         });
 
-        CentralPanel::default().show(ctx, |ui| {
-            self.snarl.show(
-                &mut Viewer {
-                    removed_node_indices: &mut self.removed_node_indices,
-                    updated_node_indices: &mut self.updated_node_indices,
-                },
-                &SnarlStyle {
-                    collapsible: true,
-                    ..Default::default()
-                },
-                Id::new("snarl"),
-                ui,
+        // Nodes are added to the canvas, which the layer stack replaces
+        if !self.layers_view {
+            self.favorites.show(
+                ctx,
+                &mut self.snarl,
+                &mut self
+                    .viewer_state
+                    .viewer(self.backend, self.read_only, self.touch),
             );
+        }
+
+        self.show_status_bar(ctx);
+
+        // Changes made by other programs are offered to be reloaded instead of being overwritten
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.file_watcher.show(ctx) {
+            if let Some(path) = self.path.clone() {
+                self.open_project(path);
+            }
+        }
+
+        self.viewer_state.hovered_node_idx = None;
+
+        let highlighted_node_idx = self
+            .expr_tree
+            .highlighted_node_idx
+            .or(self.linter.highlighted_node_idx)
+            .or(self.outline.highlighted_node_idx)
+            .or(self.bookmarks.highlighted_node_idx);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let highlighted_node_idx = highlighted_node_idx.or(self.profiler.highlighted_node_idx);
+
+        // Positions are recorded as the canvas is shown
+        self.viewer_state.wire_layout.clear();
+
+        CentralPanel::default().show(ctx, |ui| {
+            let mut viewer = Viewer {
+                filter: (!self.layers_view && self.canvas_filter.is_active())
+                    .then_some(&self.canvas_filter),
+                highlighted_node_idx: highlighted_node_idx
+                    .or(self.outline.focused_node_idx)
+                    .or(self.bookmarks.focused_node_idx),
+                node_selection: Some(&mut self.node_selection),
+                ..self
+                    .viewer_state
+                    .viewer(self.backend, self.read_only, self.touch)
+            };
+
+            if self.layers_view {
+                self.layer_stack.show(ui, &mut self.snarl, &mut viewer);
+            } else {
+                self.snarl.show(
+                    &mut viewer,
+                    &SnarlStyle {
+                        collapsible: true,
+                        pin_size: self.touch.then_some(Self::TOUCH_PIN_SIZE),
+                        ..Default::default()
+                    },
+                    Id::new("snarl"),
+                    ui,
+                );
+                viewer.splice_dropped_node(ui.ctx(), &mut self.snarl);
+                viewer.copy_paste(ui.ctx(), &mut self.snarl);
+
+                // The filter is laid over the top right corner of the canvas
+                let filter_rect = ui.max_rect().shrink(8.0);
+                ui.allocate_ui_at_rect(filter_rect, |ui| {
+                    ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+                        self.canvas_filter.show(ui);
+                    });
+                });
+            }
             ui.with_layout(Layout::bottom_up(Align::LEFT), |ui| {
                 ui.add(github_link_file!(
                     "https://github.com/attackgoat/noise_gui/blob/master/",
@@ -400,9 +1539,129 @@ impl eframe::App for App {
             });
         });
 
+        self.outline.show(
+            ctx,
+            &mut self.snarl,
+            &mut self
+                .viewer_state
+                .viewer(self.backend, self.read_only, self.touch),
+        );
+        self.linter.show(
+            ctx,
+            &mut self.snarl,
+            &mut self
+                .viewer_state
+                .viewer(self.backend, self.read_only, self.touch),
+        );
+        self.bookmarks.show(
+            ctx,
+            &self.snarl,
+            &self.node_ids,
+            &mut self
+                .viewer_state
+                .viewer(self.backend, self.read_only, self.touch),
+        );
+        self.notes.show(ctx);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.collab.show(ctx);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.viewer_state.exports.show(ctx);
+
+        if !self.read_only {
+            self.wizard.show(
+                ctx,
+                &mut self.snarl,
+                &mut self
+                    .viewer_state
+                    .viewer(self.backend, self.read_only, self.touch),
+            );
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.profiler.show(
+            ctx,
+            &self.snarl,
+            &mut self
+                .viewer_state
+                .viewer(self.backend, self.read_only, self.touch),
+        );
+
+        // Inspector windows are opened by the node menus of the viewers shown above
+        if let Some((inspector, node_idx)) = self.viewer_state.inspect.take() {
+            *match inspector {
+                Inspector::Aliasing => &mut self.aliasing_check.node_idx,
+                Inspector::Character => &mut self.character_inspector.node_idx,
+                Inspector::Chunks => &mut self.chunk_checker.node_idx,
+                Inspector::Explore => &mut self.explorer.node_idx,
+                Inspector::Sphere => &mut self.sphere_preview.node_idx,
+                Inspector::Stability => &mut self.stability_analysis.node_idx,
+                Inspector::Terrain => &mut self.terrain_preview.node_idx,
+            } = Some(node_idx);
+        }
+
+        self.expr_tree.show(
+            ctx,
+            &self.node_exprs,
+            &mut self.viewer_state.selected_node_idx,
+            self.viewer_state.hovered_node_idx,
+        );
+        self.explorer.show(ctx, &self.node_exprs, self.backend);
+        self.sphere_preview
+            .show(ctx, &self.node_exprs, self.backend);
+        self.chunk_checker.show(ctx, &self.node_exprs, self.backend);
+        self.character_inspector
+            .show(ctx, &self.node_exprs, self.backend);
+        self.stability_analysis
+            .show(ctx, &self.node_exprs, self.backend);
+        self.terrain_preview
+            .show(ctx, &self.node_exprs, &self.snarl, self.backend);
+        self.aliasing_check
+            .show(ctx, &self.node_exprs, &self.snarl, self.backend);
+
         if self.has_changes() {
+            self.undo_history.record_change();
+        }
+
+        self.undo_history.commit(ctx, &self.snarl);
+
+        // Text fields undo their own edits
+        if !ctx.wants_keyboard_input() {
+            if ctx.input_mut(|input| input.consume_shortcut(&UndoHistory::REDO)) {
+                self.redo();
+            } else if ctx.input_mut(|input| input.consume_shortcut(&UndoHistory::UNDO)) {
+                self.undo();
+            }
+        }
+
+        let has_changes = self.has_changes();
+        if has_changes {
             self.remove_nodes();
             self.update_nodes(ctx);
         }
+
+        self.prioritize_selected_node();
+
+        self.node_ids.update(&self.snarl);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut crash_copy = take(&mut self.crash_copy);
+            crash_copy.update(ctx, has_changes, || self.project());
+            self.crash_copy = crash_copy;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(project) = self.collab.update(
+            ctx,
+            &self.snarl,
+            self.backend,
+            &self.bookmarks.bookmarks,
+            &self.node_ids,
+            &self.notes.text,
+        ) {
+            self.sync_project(project);
+        }
     }
 }
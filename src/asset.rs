@@ -0,0 +1,302 @@
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    log::warn,
+    noise_graph::noise_fns::HeightmapImage,
+    png::{BitDepth, ColorType, Decoder, Transformations},
+    serde::{Deserialize, Serialize},
+    std::{
+        path::{Path, PathBuf},
+        sync::{Arc, OnceLock},
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    super::{node::NoiseNode, saved_graph::SavedGraph},
+    egui_snarl::Snarl,
+    std::{
+        fs::{create_dir_all, read, write},
+        io,
+    },
+};
+
+/// How a project saves the file of an asset.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AssetPolicy {
+    /// Saves the contents of the file in the project, which then opens without the file.
+    Embed,
+
+    /// Saves the path of the file, which is read again each time the project is opened.
+    #[default]
+    Link,
+}
+
+impl AssetPolicy {
+    pub const ALL: [Self; 2] = [Self::Link, Self::Embed];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Embed => "Embed",
+            Self::Link => "Link",
+        }
+    }
+}
+
+/// A file which a node reads, such as the image of a heightmap.
+///
+/// Paths are absolute while the project is open. Projects save the paths of files within their
+/// own directory relative to it, so that a project and its collected assets may be moved together.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(from = "SavedAsset", into = "SavedAsset")]
+pub struct Asset {
+    pub path: PathBuf,
+    pub policy: AssetPolicy,
+
+    /// The contents of the file, which are empty when it could not be read.
+    bytes: Arc<[u8]>,
+
+    /// The image decoded from the contents, shared by clones of this asset so it is decoded once.
+    heightmap: Arc<OnceLock<Arc<HeightmapImage>>>,
+}
+
+impl Asset {
+    /// Reads the file at a path, which is saved as the policy chooses.
+    pub fn new(path: PathBuf, policy: AssetPolicy) -> Self {
+        Self {
+            bytes: Self::read(&path),
+            path,
+            policy,
+            heightmap: Default::default(),
+        }
+    }
+
+    /// Decodes the contents as a grayscale PNG image, of which color images are reduced to their
+    /// luminance. Contents which are not an image decode as an empty image.
+    pub fn heightmap(&self) -> Arc<HeightmapImage> {
+        Arc::clone(self.heightmap.get_or_init(|| {
+            Arc::new(
+                Self::decode_heightmap(&self.bytes)
+                    .inspect_err(|err| {
+                        if !self.bytes.is_empty() {
+                            warn!("Unable to decode {}: {err}", self.path.display());
+                        }
+                    })
+                    .unwrap_or_default(),
+            )
+        }))
+    }
+
+    fn decode_heightmap(bytes: &[u8]) -> Result<HeightmapImage, png::DecodingError> {
+        let mut decoder = Decoder::new(bytes);
+
+        // Palettes and pixels of fewer than eight bits are expanded to eight-bit channels
+        decoder.set_transformations(Transformations::EXPAND);
+
+        let mut reader = decoder.read_info()?;
+        let mut data = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut data)?;
+        data.truncate(frame.buffer_size());
+
+        let channel = |idx: usize| match frame.bit_depth {
+            BitDepth::Sixteen => u16::from_be_bytes([data[idx * 2], data[idx * 2 + 1]]) as f64,
+            _ => data[idx] as f64 * 257.0,
+        };
+        let channels = frame.color_type.samples();
+        let samples = (0..frame.width as usize * frame.height as usize)
+            .map(|pixel| {
+                let idx = pixel * channels;
+
+                match frame.color_type {
+                    ColorType::Rgb | ColorType::Rgba => {
+                        0.2126 * channel(idx)
+                            + 0.7152 * channel(idx + 1)
+                            + 0.0722 * channel(idx + 2)
+                    }
+                    _ => channel(idx),
+                }
+                .round() as u16
+            })
+            .collect();
+
+        Ok(HeightmapImage {
+            width: frame.width,
+            height: frame.height,
+            samples,
+        })
+    }
+
+    /// Returns `true` if the file of a linked asset could not be read.
+    pub fn is_missing(&self) -> bool {
+        self.bytes.is_empty() && !self.path.as_os_str().is_empty()
+    }
+
+    /// Makes a path saved relative to the directory of a project absolute, and reads the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_in(&mut self, dir: &Path) {
+        if self.path.is_relative() && !self.path.as_os_str().is_empty() {
+            self.path = dir.join(&self.path);
+
+            if self.bytes.is_empty() {
+                self.bytes = Self::read(&self.path);
+                self.heightmap = Default::default();
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read(path: &Path) -> Arc<[u8]> {
+        read(path)
+            .inspect_err(|_| warn!("Unable to read {}", path.display()))
+            .unwrap_or_default()
+            .into()
+    }
+
+    // Files are not available on the web, so only embedded assets are
+    #[cfg(target_arch = "wasm32")]
+    fn read(_path: &Path) -> Arc<[u8]> {
+        Default::default()
+    }
+
+    /// Makes the path relative if it is within the directory of a project.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_in(&mut self, dir: &Path) {
+        if let Ok(path) = self.path.strip_prefix(dir) {
+            self.path = path.to_owned();
+        }
+    }
+}
+
+/// An asset as saved, where embedded contents are encoded as base64.
+#[derive(Deserialize, Serialize)]
+struct SavedAsset {
+    path: PathBuf,
+    policy: AssetPolicy,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+}
+
+impl From<Asset> for SavedAsset {
+    fn from(asset: Asset) -> Self {
+        Self {
+            data: (asset.policy == AssetPolicy::Embed).then(|| STANDARD.encode(&asset.bytes)),
+            path: asset.path,
+            policy: asset.policy,
+        }
+    }
+}
+
+impl From<SavedAsset> for Asset {
+    fn from(SavedAsset { path, policy, data }: SavedAsset) -> Self {
+        // Relative paths are only read once the directory of the project is known
+        let bytes = match data {
+            Some(data) => STANDARD
+                .decode(data)
+                .inspect_err(|_| warn!("Unable to decode embedded {}", path.display()))
+                .unwrap_or_default()
+                .into(),
+            None if path.is_absolute() => Self::read(&path),
+            None => Default::default(),
+        };
+
+        Self {
+            path,
+            policy,
+            bytes,
+            heightmap: Default::default(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn asset_node_indices(snarl: &Snarl<NoiseNode>) -> Vec<usize> {
+    snarl
+        .node_indices()
+        .filter(|(_, node)| node.asset().is_some())
+        .map(|(node_idx, _)| node_idx)
+        .collect()
+}
+
+/// Makes the asset paths of a graph opened from a project absolute, reading their files.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open_in(snarl: &mut Snarl<NoiseNode>, dir: &Path) {
+    for node_idx in asset_node_indices(snarl) {
+        snarl
+            .get_node_mut(node_idx)
+            .asset_mut()
+            .unwrap()
+            .open_in(dir);
+    }
+}
+
+/// Makes the asset paths of a graph within the directory it is saved to relative to it.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_in(graph: &mut SavedGraph, dir: &Path) {
+    for node in graph.nodes.values_mut() {
+        if let Some(asset) = node.node.asset_mut() {
+            asset.save_in(dir);
+        }
+    }
+}
+
+/// Copies the files of linked assets into the `assets` folder of the directory of a project, and
+/// links the assets to the copies, so that the project may be shipped along with the folder.
+///
+/// Embedded assets are already saved within the project and are left as they are. Returns the
+/// number of assets which were collected.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn collect(snarl: &mut Snarl<NoiseNode>, dir: &Path) -> io::Result<usize> {
+    let assets_dir = dir.join("assets");
+    let mut count = 0;
+
+    for node_idx in asset_node_indices(snarl) {
+        let asset = snarl.get_node_mut(node_idx).asset_mut().unwrap();
+        if asset.policy != AssetPolicy::Link
+            || asset.bytes.is_empty()
+            || asset.path.starts_with(&assets_dir)
+        {
+            continue;
+        }
+
+        let Some(stem) = asset.path.file_stem() else {
+            continue;
+        };
+        let file_name = |number: usize| {
+            let mut file_name = stem.to_owned();
+
+            if number > 1 {
+                file_name.push(format!("-{number}"));
+            }
+
+            if let Some(extension) = asset.path.extension() {
+                file_name.push(".");
+                file_name.push(extension);
+            }
+
+            file_name
+        };
+
+        create_dir_all(&assets_dir)?;
+
+        // Different files of the same name are numbered, and copies of the same file are shared
+        let mut number = 1;
+        let mut path = assets_dir.join(file_name(number));
+        while let Ok(existing) = read(&path) {
+            if *existing == *asset.bytes {
+                break;
+            }
+
+            number += 1;
+            path = assets_dir.join(file_name(number));
+        }
+
+        if !path.exists() {
+            write(&path, &asset.bytes)?;
+        }
+
+        asset.path = path;
+        count += 1;
+    }
+
+    Ok(count)
+}
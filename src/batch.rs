@@ -0,0 +1,208 @@
+use {
+    super::{
+        app::App,
+        export::{ExportSettings, Provenance},
+        node::{NodeId, NodeIds, NoiseNode},
+    },
+    anyhow::{anyhow, bail, Context},
+    crossbeam_channel::unbounded,
+    egui_snarl::{OutPinId, Snarl},
+    ron::de::from_str,
+    serde::Deserialize,
+    std::{
+        fs::{create_dir_all, read_to_string},
+        num::NonZeroUsize,
+        path::{Path, PathBuf},
+        thread::{available_parallelism, scope},
+        time::Instant,
+    },
+};
+
+/// An image listed by a manifest.
+#[derive(Deserialize)]
+struct Job {
+    /// The node rendered, as shown in reports; when not given the project must have one output.
+    #[serde(default)]
+    node: Option<String>,
+
+    /// The image written, relative to the manifest.
+    output: PathBuf,
+
+    /// The project rendered, relative to the manifest.
+    project: PathBuf,
+
+    /// Settings which replace the export settings saved with the node.
+    #[serde(default)]
+    settings: Option<ExportSettings>,
+}
+
+impl Job {
+    fn render(&self, dir: &Path) -> anyhow::Result<()> {
+        let project = App::open(dir.join(&self.project))
+            .with_context(|| format!("unable to open {}", self.project.display()))?;
+        let node_idx = Self::node_idx(self.node.as_deref(), &project.node_ids, &project.snarl)?;
+        let node = project.snarl.get_node(node_idx);
+        let image = node.image().unwrap();
+        let settings = self.settings.as_ref().unwrap_or(&image.export);
+        let output = dir.join(&self.output);
+
+        if let Some(parent) = output.parent() {
+            create_dir_all(parent)?;
+        }
+
+        // Images are rendered one thread each, as the pool is already shared between the jobs
+        settings.save_png(
+            output,
+            &node.expr(node_idx, &project.snarl),
+            image,
+            &Provenance::new(&project.snarl, project.backend),
+            1,
+            &Default::default(),
+        )
+    }
+
+    fn node_idx(
+        node: Option<&str>,
+        node_ids: &NodeIds,
+        snarl: &Snarl<NoiseNode>,
+    ) -> anyhow::Result<usize> {
+        if let Some(node) = node {
+            let id = node
+                .parse::<NodeId>()
+                .map_err(|_| anyhow!("{node} is not a node identifier"))?;
+            let node_idx = node_ids
+                .node_idx(id)
+                .filter(|&node_idx| snarl.node_indices().any(|(idx, _)| idx == node_idx))
+                .ok_or_else(|| anyhow!("node {node} was not found"))?;
+
+            if !snarl.get_node(node_idx).has_image() {
+                bail!("node {node} does not output noise");
+            }
+
+            Ok(node_idx)
+        } else {
+            let outputs = outputs(snarl);
+
+            match outputs[..] {
+                [node_idx] => Ok(node_idx),
+                _ => bail!(
+                    "the project has {} outputs, so the node must be given",
+                    outputs.len()
+                ),
+            }
+        }
+    }
+}
+
+/// Returns the outputs of a graph, which are the images not used by any other node.
+pub fn outputs(snarl: &Snarl<NoiseNode>) -> Vec<usize> {
+    snarl
+        .node_indices()
+        .filter(|(node_idx, node)| {
+            node.has_image()
+                && snarl
+                    .out_pin(OutPinId {
+                        node: *node_idx,
+                        output: 0,
+                    })
+                    .remotes
+                    .is_empty()
+        })
+        .map(|(node_idx, _)| node_idx)
+        .collect()
+}
+
+/// Renders every image listed by a manifest, sharing one pool of threads between all of the
+/// projects, and returns `false` if any image could not be rendered.
+///
+/// Manifests are a list of jobs, such as:
+///
+/// ```ron
+/// [
+///     (project: "island.ron", output: "out/island.png"),
+///     (
+///         project: "mountains.ron",
+///         node: Some("4f1c26b0a9e3d781"),
+///         output: "out/mountains.png",
+///         settings: Some((bit_depth: Sixteen, color_space: Linear, size: 2048)),
+///     ),
+/// ]
+/// ```
+pub fn render(manifest: impl AsRef<Path>) -> bool {
+    let manifest = manifest.as_ref();
+    let jobs = match read_to_string(manifest)
+        .map_err(anyhow::Error::from)
+        .and_then(|src| from_str::<Vec<Job>>(&src).map_err(anyhow::Error::from))
+    {
+        Ok(jobs) => jobs,
+        Err(err) => {
+            eprintln!("Unable to read manifest {}: {err:#}", manifest.display());
+
+            return false;
+        }
+    };
+
+    let dir = manifest.parent().unwrap_or(Path::new(""));
+    let start = Instant::now();
+    let (job_tx, job_rx) = unbounded();
+    let (result_tx, result_rx) = unbounded();
+
+    for job in jobs.iter().enumerate() {
+        job_tx.send(job).unwrap();
+    }
+
+    drop(job_tx);
+
+    let thread_count = available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or_default()
+        .clamp(1, jobs.len().max(1));
+    let mut failures = vec![];
+
+    scope(|scope| {
+        for _ in 0..thread_count {
+            let (job_rx, result_tx) = (job_rx.clone(), result_tx.clone());
+            scope.spawn(move || {
+                for (job_idx, job) in job_rx {
+                    result_tx.send((job_idx, job.render(dir))).unwrap();
+                }
+            });
+        }
+
+        drop(result_tx);
+
+        // Results are reported as they finish so that long builds show progress
+        for (finished, (job_idx, res)) in result_rx.iter().enumerate() {
+            let output = jobs[job_idx].output.display();
+
+            match res {
+                Ok(()) => eprintln!("[{}/{}] Rendered {output}", finished + 1, jobs.len()),
+                Err(err) => {
+                    eprintln!("[{}/{}] Failed {output}: {err:#}", finished + 1, jobs.len());
+                    failures.push(job_idx);
+                }
+            }
+        }
+    });
+
+    eprintln!(
+        "Rendered {} of {} images in {:.1}s",
+        jobs.len() - failures.len(),
+        jobs.len(),
+        start.elapsed().as_secs_f64()
+    );
+
+    if !failures.is_empty() {
+        failures.sort_unstable();
+
+        eprintln!("Failed:");
+
+        for job_idx in failures {
+            eprintln!("  {}", jobs[job_idx].output.display());
+        }
+
+        return false;
+    }
+
+    true
+}
@@ -0,0 +1,195 @@
+use {
+    super::{
+        node::{NodeId, NodeIds, NoiseNode},
+        view::Viewer,
+    },
+    egui::{Button, Context, Id, Key, Label, Modifiers, RichText, Sense, TextEdit, Window},
+    egui_snarl::{ui::SnarlViewer, Snarl},
+    serde::{Deserialize, Serialize},
+};
+
+/// A named node of the graph which may be jumped to.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub node_id: NodeId,
+}
+
+/// Collapsible window listing named bookmarks of nodes, which focuses a bookmarked node when it
+/// is clicked or when Alt and the number of the bookmark are pressed.
+///
+/// Bookmarks refer to nodes by identifier, so they follow nodes between sessions. They do not save
+/// a location or zoom of the canvas, as egui-snarl does not allow them to be set.
+#[derive(Default)]
+pub struct Bookmarks {
+    pub bookmarks: Vec<Bookmark>,
+
+    /// The bookmarked node last jumped to, highlighted in the graph.
+    pub focused_node_idx: Option<usize>,
+
+    /// The node hovered in the bookmarks, highlighted in the graph.
+    pub highlighted_node_idx: Option<usize>,
+
+    name: String,
+}
+
+impl Bookmarks {
+    const HOTKEYS: [Key; 9] = [
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+    ];
+
+    pub fn clear(&mut self) {
+        self.bookmarks.clear();
+        self.focused_node_idx = None;
+        self.highlighted_node_idx = None;
+    }
+
+    pub fn new(bookmarks: Vec<Bookmark>) -> Self {
+        Self {
+            bookmarks,
+            ..Default::default()
+        }
+    }
+
+    fn jump(&mut self, node_idx: usize, snarl: &Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        self.focused_node_idx = Some(node_idx);
+
+        if snarl.get_node(node_idx).has_image() {
+            *viewer.selected_node_idx = Some(node_idx);
+        }
+    }
+
+    /// Removes the bookmarks of a node which is being removed from the graph.
+    pub fn remove(&mut self, node_id: NodeId) {
+        self.bookmarks
+            .retain(|bookmark| bookmark.node_id != node_id);
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        snarl: &Snarl<NoiseNode>,
+        node_ids: &NodeIds,
+        viewer: &mut Viewer<'_>,
+    ) {
+        self.highlighted_node_idx = None;
+
+        for (key, bookmark) in Self::HOTKEYS.into_iter().zip(&self.bookmarks) {
+            if ctx.input_mut(|input| input.consume_key(Modifiers::ALT, key)) {
+                if let Some(node_idx) = node_ids.node_idx(bookmark.node_id) {
+                    self.jump(node_idx, snarl, viewer);
+                }
+
+                break;
+            }
+        }
+
+        // The list borrows the bookmarks, so clicks are handled once it has been shown
+        let mut jumped_node_idx = None;
+        let mut removed_bookmark_idx = None;
+
+        Window::new("Node Bookmarks")
+            .id(Id::new("bookmarks"))
+            .default_open(false)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                let selected_node_idx = *viewer.selected_node_idx;
+
+                ui.add_visible_ui(!viewer.read_only, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            TextEdit::singleline(&mut self.name)
+                                .desired_width(120.0)
+                                .hint_text("Name"),
+                        );
+
+                        if ui
+                            .add_enabled(selected_node_idx.is_some(), Button::new("Add"))
+                            .on_hover_text("Bookmark the selected node")
+                            .on_disabled_hover_text("Select a node to bookmark it")
+                            .clicked()
+                        {
+                            let node_idx = selected_node_idx.unwrap();
+
+                            if let Some(node_id) = node_ids.id(node_idx) {
+                                let name = if self.name.trim().is_empty() {
+                                    format!(
+                                        "{} #{node_idx}",
+                                        viewer.title(snarl.get_node(node_idx))
+                                    )
+                                } else {
+                                    self.name.trim().to_owned()
+                                };
+
+                                self.bookmarks.push(Bookmark { name, node_id });
+                                self.name.clear();
+                            }
+                        }
+                    });
+
+                    ui.separator();
+                });
+
+                if self.bookmarks.is_empty() {
+                    ui.label("Select a node and click Add to bookmark it");
+
+                    return;
+                }
+
+                for (bookmark_idx, bookmark) in self.bookmarks.iter().enumerate() {
+                    let node_idx = node_ids.node_idx(bookmark.node_id);
+
+                    ui.horizontal(|ui| {
+                        if !viewer.read_only
+                            && ui.small_button("🗑").on_hover_text("Remove").clicked()
+                        {
+                            removed_bookmark_idx = Some(bookmark_idx);
+                        }
+
+                        let mut text = RichText::new(&bookmark.name);
+
+                        if node_idx.is_some()
+                            && (node_idx == *viewer.hovered_node_idx
+                                || node_idx == self.focused_node_idx)
+                        {
+                            text = text
+                                .background_color(ui.visuals().selection.bg_fill)
+                                .color(ui.visuals().selection.stroke.color);
+                        }
+
+                        let res = ui.add(Label::new(text).sense(Sense::click()));
+
+                        if bookmark_idx < Self::HOTKEYS.len() {
+                            ui.weak(format!("Alt+{}", bookmark_idx + 1));
+                        }
+
+                        if let Some(node_idx) = node_idx {
+                            if res.hovered() {
+                                self.highlighted_node_idx = Some(node_idx);
+                            }
+
+                            if res.clicked() {
+                                jumped_node_idx = Some(node_idx);
+                            }
+                        }
+                    });
+                }
+            });
+
+        if let Some(node_idx) = jumped_node_idx {
+            self.jump(node_idx, snarl, viewer);
+        }
+
+        if let Some(bookmark_idx) = removed_bookmark_idx {
+            self.bookmarks.remove(bookmark_idx);
+        }
+    }
+}
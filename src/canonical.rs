@@ -0,0 +1,611 @@
+use {
+    ron::{
+        ser::{to_writer_pretty, PrettyConfig},
+        Error,
+    },
+    serde::{
+        ser::{
+            SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+            SerializeTupleStruct, SerializeTupleVariant,
+        },
+        Serialize, Serializer,
+    },
+    std::{cmp::Ordering, io::Write},
+};
+
+/// Fields holding sequences whose order means nothing once saved: egui-snarl keeps its wires in
+/// a hash set and its nodes in the order they were last clicked.
+const UNORDERED_FIELDS: &[&str] = &["draw_order", "wires"];
+
+/// Writes a value as pretty RON which is the same for the same value, regardless of platform or
+/// of the order in which hash maps and sets happen to be iterated, so saved files may be diffed
+/// and merged.
+///
+/// Map entries are sorted by key and unordered sequences are sorted by value.
+pub fn to_writer<W, T>(writer: W, value: &T) -> ron::Result<()>
+where
+    W: Write,
+    T: ?Sized + Serialize,
+{
+    let mut value = value.serialize(Capture)?;
+    value.sort(None);
+
+    to_writer_pretty(
+        writer,
+        &value,
+        PrettyConfig::default()
+            .new_line("\n".to_owned())
+            .indentor("    ".to_owned()),
+    )
+}
+
+/// A captured value of the serde data model, which keeps the names of structs and variants so
+/// that it serializes exactly like the value it was captured from.
+enum Value {
+    Bool(bool),
+    Bytes(Vec<u8>),
+    Char(char),
+    F32(f32),
+    F64(f64),
+    I64(i64),
+    Map(Vec<(Value, Value)>),
+    NewtypeStruct(&'static str, Box<Value>),
+    NewtypeVariant(&'static str, u32, &'static str, Box<Value>),
+    None,
+    Seq(Vec<Value>),
+    Some(Box<Value>),
+    Str(String),
+    Struct(&'static str, Vec<(&'static str, Value)>),
+    StructVariant(&'static str, u32, &'static str, Vec<(&'static str, Value)>),
+    Tuple(Vec<Value>),
+    TupleStruct(&'static str, Vec<Value>),
+    TupleVariant(&'static str, u32, &'static str, Vec<Value>),
+    U64(u64),
+    Unit,
+    UnitStruct(&'static str),
+    UnitVariant(&'static str, u32, &'static str),
+}
+
+impl Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        fn cmp_all<'a>(
+            mut lhs: impl Iterator<Item = &'a Value>,
+            mut rhs: impl Iterator<Item = &'a Value>,
+        ) -> Ordering {
+            loop {
+                match (lhs.next(), rhs.next()) {
+                    (Some(lhs), Some(rhs)) => match lhs.cmp(rhs) {
+                        Ordering::Equal => continue,
+                        ordering => return ordering,
+                    },
+                    (lhs, rhs) => return lhs.is_some().cmp(&rhs.is_some()),
+                }
+            }
+        }
+
+        match (self, other) {
+            (Self::Bool(lhs), Self::Bool(rhs)) => lhs.cmp(rhs),
+            (Self::Bytes(lhs), Self::Bytes(rhs)) => lhs.cmp(rhs),
+            (Self::Char(lhs), Self::Char(rhs)) => lhs.cmp(rhs),
+            (Self::F32(lhs), Self::F32(rhs)) => lhs.total_cmp(rhs),
+            (Self::F64(lhs), Self::F64(rhs)) => lhs.total_cmp(rhs),
+            (Self::I64(lhs), Self::I64(rhs)) => lhs.cmp(rhs),
+            (Self::Map(lhs), Self::Map(rhs)) => cmp_all(
+                lhs.iter().flat_map(|(key, value)| [key, value]),
+                rhs.iter().flat_map(|(key, value)| [key, value]),
+            ),
+            (Self::NewtypeStruct(_, lhs), Self::NewtypeStruct(_, rhs))
+            | (Self::Some(lhs), Self::Some(rhs)) => lhs.cmp(rhs),
+            (
+                Self::NewtypeVariant(_, lhs_idx, _, lhs),
+                Self::NewtypeVariant(_, rhs_idx, _, rhs),
+            ) => lhs_idx.cmp(rhs_idx).then_with(|| lhs.cmp(rhs)),
+            (Self::Seq(lhs), Self::Seq(rhs))
+            | (Self::Tuple(lhs), Self::Tuple(rhs))
+            | (Self::TupleStruct(_, lhs), Self::TupleStruct(_, rhs)) => {
+                cmp_all(lhs.iter(), rhs.iter())
+            }
+            (Self::Str(lhs), Self::Str(rhs)) => lhs.cmp(rhs),
+            (Self::Struct(_, lhs), Self::Struct(_, rhs)) => cmp_all(
+                lhs.iter().map(|(_, value)| value),
+                rhs.iter().map(|(_, value)| value),
+            ),
+            (Self::StructVariant(_, lhs_idx, _, lhs), Self::StructVariant(_, rhs_idx, _, rhs)) => {
+                lhs_idx.cmp(rhs_idx).then_with(|| {
+                    cmp_all(
+                        lhs.iter().map(|(_, value)| value),
+                        rhs.iter().map(|(_, value)| value),
+                    )
+                })
+            }
+            (Self::TupleVariant(_, lhs_idx, _, lhs), Self::TupleVariant(_, rhs_idx, _, rhs)) => {
+                lhs_idx
+                    .cmp(rhs_idx)
+                    .then_with(|| cmp_all(lhs.iter(), rhs.iter()))
+            }
+            (Self::U64(lhs), Self::U64(rhs)) => lhs.cmp(rhs),
+            (Self::UnitVariant(_, lhs_idx, _), Self::UnitVariant(_, rhs_idx, _)) => {
+                lhs_idx.cmp(rhs_idx)
+            }
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+
+    /// Orders values of different kinds, which only happens in sequences of enums: variants are
+    /// ordered as declared and `None` comes before `Some`.
+    fn rank(&self) -> (u8, u32) {
+        match self {
+            Self::NewtypeVariant(_, variant_idx, ..)
+            | Self::StructVariant(_, variant_idx, ..)
+            | Self::TupleVariant(_, variant_idx, ..)
+            | Self::UnitVariant(_, variant_idx, _) => (1, *variant_idx),
+            Self::None => (0, 0),
+            Self::Some(_) => (0, 1),
+            _ => (2, 0),
+        }
+    }
+
+    /// Sorts the entries of maps and the items of unordered fields, recursively.
+    fn sort(&mut self, field: Option<&str>) {
+        match self {
+            Self::Map(entries) => {
+                for (key, value) in entries.iter_mut() {
+                    key.sort(None);
+                    value.sort(None);
+                }
+
+                entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+            }
+            Self::NewtypeStruct(_, value)
+            | Self::NewtypeVariant(_, _, _, value)
+            | Self::Some(value) => value.sort(field),
+            Self::Seq(values)
+            | Self::Tuple(values)
+            | Self::TupleStruct(_, values)
+            | Self::TupleVariant(_, _, _, values) => {
+                for value in values.iter_mut() {
+                    value.sort(None);
+                }
+
+                if field.is_some_and(|field| UNORDERED_FIELDS.contains(&field)) {
+                    values.sort_by(Self::cmp);
+                }
+            }
+            Self::Struct(_, fields) | Self::StructVariant(_, _, _, fields) => {
+                for (field, value) in fields.iter_mut() {
+                    value.sort(Some(field));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Bool(value) => serializer.serialize_bool(*value),
+            Self::Bytes(value) => serializer.serialize_bytes(value),
+            Self::Char(value) => serializer.serialize_char(*value),
+            Self::F32(value) => serializer.serialize_f32(*value),
+            Self::F64(value) => serializer.serialize_f64(*value),
+            Self::I64(value) => serializer.serialize_i64(*value),
+            Self::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+
+                map.end()
+            }
+            Self::NewtypeStruct(name, value) => serializer.serialize_newtype_struct(name, value),
+            Self::NewtypeVariant(name, variant_idx, variant, value) => {
+                serializer.serialize_newtype_variant(name, *variant_idx, variant, value)
+            }
+            Self::None => serializer.serialize_none(),
+            Self::Seq(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+
+                seq.end()
+            }
+            Self::Some(value) => serializer.serialize_some(value),
+            Self::Str(value) => serializer.serialize_str(value),
+            Self::Struct(name, fields) => {
+                let mut state = serializer.serialize_struct(name, fields.len())?;
+                for (field, value) in fields {
+                    state.serialize_field(field, value)?;
+                }
+
+                state.end()
+            }
+            Self::StructVariant(name, variant_idx, variant, fields) => {
+                let mut state = serializer.serialize_struct_variant(
+                    name,
+                    *variant_idx,
+                    variant,
+                    fields.len(),
+                )?;
+                for (field, value) in fields {
+                    state.serialize_field(field, value)?;
+                }
+
+                state.end()
+            }
+            Self::Tuple(values) => {
+                let mut state = serializer.serialize_tuple(values.len())?;
+                for value in values {
+                    state.serialize_element(value)?;
+                }
+
+                state.end()
+            }
+            Self::TupleStruct(name, values) => {
+                let mut state = serializer.serialize_tuple_struct(name, values.len())?;
+                for value in values {
+                    state.serialize_field(value)?;
+                }
+
+                state.end()
+            }
+            Self::TupleVariant(name, variant_idx, variant, values) => {
+                let mut state = serializer.serialize_tuple_variant(
+                    name,
+                    *variant_idx,
+                    variant,
+                    values.len(),
+                )?;
+                for value in values {
+                    state.serialize_field(value)?;
+                }
+
+                state.end()
+            }
+            Self::U64(value) => serializer.serialize_u64(*value),
+            Self::Unit => serializer.serialize_unit(),
+            Self::UnitStruct(name) => serializer.serialize_unit_struct(name),
+            Self::UnitVariant(name, variant_idx, variant) => {
+                serializer.serialize_unit_variant(name, *variant_idx, variant)
+            }
+        }
+    }
+}
+
+/// Serializer which captures a value instead of writing it.
+struct Capture;
+
+/// A compound value being captured; `key` holds the key of a map entry until its value is given.
+struct Compound {
+    fields: Vec<(&'static str, Value)>,
+    key: Option<Value>,
+    map: Vec<(Value, Value)>,
+    values: Vec<Value>,
+    variant: Option<(&'static str, u32, &'static str)>,
+}
+
+impl Compound {
+    fn new(variant: Option<(&'static str, u32, &'static str)>) -> Self {
+        Self {
+            fields: vec![],
+            key: None,
+            map: vec![],
+            values: vec![],
+            variant,
+        }
+    }
+}
+
+impl Serializer for Capture {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = Compound;
+    type SerializeTuple = Compound;
+    type SerializeTupleStruct = Compound;
+    type SerializeTupleVariant = Compound;
+    type SerializeMap = Compound;
+    type SerializeStruct = Compound;
+    type SerializeStructVariant = Compound;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::I64(v as _))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::I64(v as _))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::I64(v as _))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::U64(v as _))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::U64(v as _))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::U64(v as _))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(Value::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(Value::Some(Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Value, Error> {
+        Ok(Value::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_idx: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::UnitVariant(name, variant_idx, variant))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Value, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(Value::NewtypeStruct(name, Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_idx: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(Value::NewtypeVariant(
+            name,
+            variant_idx,
+            variant,
+            Box::new(value.serialize(self)?),
+        ))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound, Error> {
+        Ok(Compound::new(None))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound, Error> {
+        Ok(Compound::new(None))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> Result<Compound, Error> {
+        Ok(Compound::new(Some((name, 0, ""))))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_idx: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound, Error> {
+        Ok(Compound::new(Some((name, variant_idx, variant))))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound, Error> {
+        Ok(Compound::new(None))
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Compound, Error> {
+        Ok(Compound::new(Some((name, 0, ""))))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_idx: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound, Error> {
+        Ok(Compound::new(Some((name, variant_idx, variant))))
+    }
+}
+
+impl SerializeSeq for Compound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.values.push(value.serialize(Capture)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Seq(self.values))
+    }
+}
+
+impl SerializeTuple for Compound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.values.push(value.serialize(Capture)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Tuple(self.values))
+    }
+}
+
+impl SerializeTupleStruct for Compound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.values.push(value.serialize(Capture)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let (name, ..) = self.variant.unwrap();
+
+        Ok(Value::TupleStruct(name, self.values))
+    }
+}
+
+impl SerializeTupleVariant for Compound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.values.push(value.serialize(Capture)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let (name, variant_idx, variant) = self.variant.unwrap();
+
+        Ok(Value::TupleVariant(name, variant_idx, variant, self.values))
+    }
+}
+
+impl SerializeMap for Compound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(Capture)?);
+
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self.key.take().unwrap();
+        self.map.push((key, value.serialize(Capture)?));
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Map(self.map))
+    }
+}
+
+impl SerializeStruct for Compound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, field: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.fields.push((field, value.serialize(Capture)?));
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let (name, ..) = self.variant.unwrap();
+
+        Ok(Value::Struct(name, self.fields))
+    }
+}
+
+impl SerializeStructVariant for Compound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, field: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.fields.push((field, value.serialize(Capture)?));
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let (name, variant_idx, variant) = self.variant.unwrap();
+
+        Ok(Value::StructVariant(
+            name,
+            variant_idx,
+            variant,
+            self.fields,
+        ))
+    }
+}
@@ -0,0 +1,440 @@
+use {
+    super::app::NodeExprs,
+    egui::{Context, Grid, Id, Ui, Window},
+    noise::NoiseFn,
+    noise_graph::{Backend, Expr},
+    std::{f64::consts::TAU, sync::Arc},
+};
+
+/// Simple descriptors of the output of an expression, sampled over a fixed area, which describe
+/// what the noise looks like rather than how it is made.
+#[derive(Clone, Copy)]
+pub struct Character {
+    /// How much more the values change along one direction than across it, from 0 (the same in
+    /// every direction) to 1 (stripes).
+    pub anisotropy: f64,
+
+    /// The standard deviation of the values.
+    pub contrast: f64,
+
+    /// The typical frequency of the features, in cycles per unit, from the gradients of the
+    /// values relative to their contrast.
+    pub frequency: f64,
+
+    /// The skewness of the curvature, which is positive when the noise has sharp crests and
+    /// rounded valleys.
+    pub ridges: f64,
+
+    /// The skewness of the values, which is positive when peaks rise from broad lowlands and
+    /// negative when valleys cut into broad plateaus.
+    pub skew: f64,
+
+    /// The slope of the power spectrum on a log-log scale, which is steeper for smoother noise.
+    pub slope: f64,
+}
+
+impl Character {
+    /// The length of each side of the sampled area, in units.
+    const SAMPLED_LEN: f64 = 4.0;
+
+    /// The number of samples along each side of the sampled area.
+    const SIZE: usize = 64;
+
+    pub fn new(expr: &Expr, backend: Backend) -> Self {
+        let noise = expr.noise_with(backend.get());
+        let step = Self::SAMPLED_LEN / Self::SIZE as f64;
+        let values = (0..Self::SIZE)
+            .flat_map(|y| (0..Self::SIZE).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let value = noise.get([x as f64 * step, y as f64 * step, 0.0]);
+
+                if value.is_finite() {
+                    value
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let (mean, contrast, skew) = moments(&values);
+
+        // Curvature and gradients of the interior samples
+        let mut curvatures = vec![];
+        let mut gradients = 0.0;
+        for y in 1..Self::SIZE - 1 {
+            for x in 1..Self::SIZE - 1 {
+                let value = |x: usize, y: usize| values[y * Self::SIZE + x];
+
+                curvatures.push(
+                    value(x - 1, y) + value(x + 1, y) + value(x, y - 1) + value(x, y + 1)
+                        - 4.0 * value(x, y),
+                );
+                gradients += ((value(x + 1, y) - value(x - 1, y)) / (2.0 * step)).powi(2);
+            }
+        }
+
+        let frequency = if contrast > f64::EPSILON {
+            (gradients / curvatures.len() as f64).sqrt() / contrast / TAU
+        } else {
+            0.0
+        };
+
+        Self {
+            anisotropy: Self::anisotropy(&values),
+            contrast,
+            frequency,
+            ridges: -moments(&curvatures).2,
+            skew,
+            slope: Self::spectrum_slope(&values, mean),
+        }
+    }
+
+    /// Compares the eigenvalues of the structure tensor of the values, which are equal when the
+    /// gradients point in every direction evenly.
+    fn anisotropy(values: &[f64]) -> f64 {
+        let [mut xx, mut xy, mut yy] = [0.0; 3];
+
+        for y in 0..Self::SIZE - 1 {
+            for x in 0..Self::SIZE - 1 {
+                let value = values[y * Self::SIZE + x];
+                let dx = values[y * Self::SIZE + x + 1] - value;
+                let dy = values[(y + 1) * Self::SIZE + x] - value;
+
+                xx += dx * dx;
+                xy += dx * dy;
+                yy += dy * dy;
+            }
+        }
+
+        let trace = xx + yy;
+        if trace <= f64::EPSILON {
+            return 0.0;
+        }
+
+        ((xx - yy).powi(2) + 4.0 * xy * xy).sqrt() / trace
+    }
+
+    /// How well the character matches each recognized term of a query, from 0 to 1.
+    pub fn score(&self, tags: &[Tag]) -> f64 {
+        if tags.is_empty() {
+            return 0.0;
+        }
+
+        tags.iter().map(|tag| tag.membership(self)).sum::<f64>() / tags.len() as f64
+    }
+
+    fn show(&self, ui: &mut Ui) {
+        Grid::new("character_grid").striped(true).show(ui, |ui| {
+            for (name, description, value) in [
+                (
+                    "Spectrum Slope",
+                    "Steeper (more negative) for smoother noise",
+                    self.slope,
+                ),
+                (
+                    "Frequency",
+                    "The typical number of features per unit",
+                    self.frequency,
+                ),
+                (
+                    "Contrast",
+                    "The standard deviation of the values",
+                    self.contrast,
+                ),
+                (
+                    "Skew",
+                    "Positive when peaks rise from lowlands, negative when valleys cut into \
+                    plateaus",
+                    self.skew,
+                ),
+                (
+                    "Ridges",
+                    "Positive when crests are sharper than valleys",
+                    self.ridges,
+                ),
+                (
+                    "Anisotropy",
+                    "0 when the noise looks the same in every direction, 1 for stripes",
+                    self.anisotropy,
+                ),
+            ] {
+                ui.label(name).on_hover_text(description);
+                ui.monospace(format!("{value:.2}"));
+                ui.end_row();
+            }
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Character:");
+
+            for tag in self.tags() {
+                ui.strong(tag.name());
+            }
+        });
+    }
+
+    /// Returns the log-log slope of the power spectrum of the rows and columns of the values.
+    fn spectrum_slope(values: &[f64], mean: f64) -> f64 {
+        let mut power = vec![0.0; Self::SIZE / 2 + 1];
+
+        // A Hann window keeps the edges of the area from adding power to every frequency
+        let window = (0..Self::SIZE)
+            .map(|idx| 0.5 - 0.5 * (TAU * idx as f64 / Self::SIZE as f64).cos())
+            .collect::<Vec<_>>();
+
+        for line in 0..Self::SIZE {
+            for is_row in [true, false] {
+                let value_idx = |idx| {
+                    if is_row {
+                        line * Self::SIZE + idx
+                    } else {
+                        idx * Self::SIZE + line
+                    }
+                };
+
+                for (k, power) in power.iter_mut().enumerate().skip(1) {
+                    let (mut re, mut im) = (0.0, 0.0);
+
+                    for (idx, window) in window.iter().enumerate() {
+                        let value = (values[value_idx(idx)] - mean) * window;
+                        let angle = TAU * (k * idx) as f64 / Self::SIZE as f64;
+
+                        re += value * angle.cos();
+                        im -= value * angle.sin();
+                    }
+
+                    *power += re * re + im * im;
+                }
+            }
+        }
+
+        // Least squares fit of ln(power) against ln(k)
+        let points = power
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, power)| **power > f64::EPSILON)
+            .map(|(k, power)| ((k as f64).ln(), power.ln()))
+            .collect::<Vec<_>>();
+        let count = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+        let (covariance, variance) = points.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+            (
+                cov + (x - mean_x) * (y - mean_y),
+                var + (x - mean_x).powi(2),
+            )
+        });
+
+        if variance > 0.0 {
+            covariance / variance
+        } else {
+            0.0
+        }
+    }
+
+    /// The terms which describe the character, strongest first.
+    pub fn tags(&self) -> Vec<Tag> {
+        let mut res = Tag::ALL
+            .into_iter()
+            .filter(|tag| tag.membership(self) >= 0.5)
+            .collect::<Vec<_>>();
+        res.sort_by(|a, b| b.membership(self).total_cmp(&a.membership(self)));
+
+        res
+    }
+}
+
+/// Collapsible window which describes the character of a node.
+#[derive(Default)]
+pub struct CharacterInspector {
+    pub node_idx: Option<usize>,
+
+    character: Option<Character>,
+    version: Option<(usize, usize, Backend)>,
+}
+
+impl CharacterInspector {
+    pub fn show(&mut self, ctx: &Context, node_exprs: &NodeExprs, backend: Backend) {
+        let Some(node_idx) = self.node_idx else {
+            return;
+        };
+
+        let Some((version, expr)) = node_exprs
+            .read()
+            .unwrap()
+            .get(&node_idx)
+            .map(|(version, expr)| (*version, Arc::clone(expr)))
+        else {
+            self.node_idx = None;
+            self.character = None;
+
+            return;
+        };
+
+        // The descriptors are cheap enough to follow edits of the node
+        if self.version != Some((node_idx, version, backend)) {
+            self.character = Some(Character::new(&expr, backend));
+            self.version = Some((node_idx, version, backend));
+        }
+
+        let mut open = true;
+
+        Window::new(format!("Character #{node_idx}"))
+            .id(Id::new("character"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.weak(format!(
+                    "Sampled from (0, 0) to ({len}, {len})",
+                    len = Character::SAMPLED_LEN
+                ));
+
+                if let Some(character) = &self.character {
+                    character.show(ui);
+                }
+            });
+
+        if !open {
+            self.node_idx = None;
+            self.character = None;
+        }
+    }
+}
+
+/// A term of a search for noise of a certain character.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Tag {
+    Directional,
+    HighContrast,
+    HighFrequency,
+    Isotropic,
+    LowContrast,
+    LowFrequency,
+    Peaks,
+    Plateaus,
+    Ridged,
+    Rough,
+    Smooth,
+}
+
+impl Tag {
+    pub const ALL: [Self; 11] = [
+        Self::Directional,
+        Self::HighContrast,
+        Self::HighFrequency,
+        Self::Isotropic,
+        Self::LowContrast,
+        Self::LowFrequency,
+        Self::Peaks,
+        Self::Plateaus,
+        Self::Ridged,
+        Self::Rough,
+        Self::Smooth,
+    ];
+
+    /// How well a character matches the term, from 0 to 1.
+    fn membership(self, character: &Character) -> f64 {
+        // Rises from 0 at `from` to 1 at `to`, which may be lower than `from`
+        let ramp = |value: f64, from: f64, to: f64| ((value - from) / (to - from)).clamp(0.0, 1.0);
+
+        match self {
+            Self::Directional => ramp(character.anisotropy, 0.3, 0.6),
+            Self::HighContrast => ramp(character.contrast, 0.35, 0.6),
+            Self::HighFrequency => ramp(character.frequency, 0.8, 2.0),
+            Self::Isotropic => ramp(character.anisotropy, 0.35, 0.15),
+            Self::LowContrast => ramp(character.contrast, 0.3, 0.1),
+            Self::LowFrequency => ramp(character.frequency, 0.8, 0.3),
+            Self::Peaks => ramp(character.skew, 0.2, 1.0),
+            Self::Plateaus => ramp(character.skew, -0.2, -1.0),
+            Self::Ridged => ramp(character.ridges, 0.3, 1.5),
+            Self::Rough => ramp(character.slope, -3.0, -1.5),
+            Self::Smooth => ramp(character.slope, -2.5, -5.0),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Directional => "directional",
+            Self::HighContrast => "high-contrast",
+            Self::HighFrequency => "high-frequency",
+            Self::Isotropic => "isotropic",
+            Self::LowContrast => "low-contrast",
+            Self::LowFrequency => "low-frequency",
+            Self::Peaks => "peaks",
+            Self::Plateaus => "plateaus",
+            Self::Ridged => "ridged",
+            Self::Rough => "rough",
+            Self::Smooth => "smooth",
+        }
+    }
+
+    /// Splits a query such as "ridged, high frequency, low-contrast" into the terms it names and
+    /// the words which were not recognized.
+    pub fn parse(query: &str) -> (Vec<Self>, Vec<String>) {
+        let mut tags = vec![];
+        let mut unknown = vec![];
+        let mut words = query
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase);
+
+        while let Some(mut word) = words.next() {
+            // "high frequency" is the same term as "high-frequency"
+            if matches!(word.as_str(), "high" | "low") {
+                if let Some(next_word) = words.next() {
+                    word = format!("{word}-{next_word}");
+                }
+            }
+
+            match Self::ALL
+                .into_iter()
+                .find(|tag| tag.name() == word || tag.synonyms().contains(&word.as_str()))
+            {
+                Some(tag) if !tags.contains(&tag) => tags.push(tag),
+                Some(_) => (),
+                None => unknown.push(word),
+            }
+        }
+
+        (tags, unknown)
+    }
+
+    fn synonyms(self) -> &'static [&'static str] {
+        match self {
+            Self::Directional => &["anisotropic", "stretched", "stripes", "striped"],
+            Self::HighContrast => &["contrasty", "strong"],
+            Self::HighFrequency => &["busy", "fine", "high-freq", "small"],
+            Self::Isotropic => &["uniform"],
+            Self::LowContrast => &["faint", "flat", "subtle"],
+            Self::LowFrequency => &["broad", "coarse", "large", "low-freq"],
+            Self::Peaks => &["peaky", "spiky"],
+            Self::Plateaus => &["mesas", "plateau"],
+            Self::Ridged => &["ridges", "ridge", "sharp"],
+            Self::Rough => &["detailed", "noisy", "grainy"],
+            Self::Smooth => &["soft", "blurry"],
+        }
+    }
+}
+
+/// Returns the mean, standard deviation and skewness of the values.
+fn moments(values: &[f64]) -> (f64, f64, f64) {
+    let len = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / len;
+    let std_dev = (values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / len)
+        .sqrt();
+    let skew = if std_dev > f64::EPSILON {
+        values
+            .iter()
+            .map(|value| ((value - mean) / std_dev).powi(3))
+            .sum::<f64>()
+            / len
+    } else {
+        0.0
+    };
+
+    (mean, std_dev, skew)
+}
@@ -0,0 +1,358 @@
+use {
+    super::{app::NodeExprs, job::Job},
+    egui::{Button, Context, DragValue, Grid, Id, ProgressBar, Ui, Window},
+    noise_graph::{Backend, Expr, Graph},
+    std::sync::Arc,
+};
+
+/// How the positions of the samples of a chunk are computed.
+#[derive(Clone, Copy)]
+enum Scheme {
+    /// The offset of a sample is added to the origin of its chunk, as runtime code often does.
+    ChunkOrigin,
+
+    /// The position is computed from the index of the sample in the whole world, as
+    /// `Graph::sample_chunk` does.
+    GlobalIndex,
+}
+
+impl Scheme {
+    const ALL: [Self; 2] = [Self::GlobalIndex, Self::ChunkOrigin];
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::ChunkOrigin => "chunk * chunk_len + idx * (chunk_len / (size - 1))",
+            Self::GlobalIndex => "(chunk * (size - 1) + idx) * (chunk_len / (size - 1))",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::ChunkOrigin => "Chunk Origin",
+            Self::GlobalIndex => "Global Index (exported)",
+        }
+    }
+
+    fn position(self, chunk: i64, idx: usize, size: usize, chunk_len: f64) -> f64 {
+        match self {
+            Self::ChunkOrigin => {
+                chunk as f64 * chunk_len + idx as f64 * (chunk_len / (size - 1) as f64)
+            }
+            Self::GlobalIndex => Graph::chunk_position(chunk, idx, size, chunk_len),
+        }
+    }
+}
+
+/// The borders of the checked chunks which did not match, for one scheme.
+struct Report {
+    borders: usize,
+    mismatched_borders: usize,
+    mismatched_samples: usize,
+
+    /// The largest difference between the positions both chunks of a border sample.
+    max_position_error: f64,
+
+    max_value_error: f64,
+    scheme: Scheme,
+}
+
+impl Report {
+    fn new(scheme: Scheme) -> Self {
+        Self {
+            borders: 0,
+            mismatched_borders: 0,
+            mismatched_samples: 0,
+            max_position_error: 0.0,
+            max_value_error: 0.0,
+            scheme,
+        }
+    }
+
+    /// Compares the samples of the border between a chunk and the next chunk along an axis.
+    ///
+    /// Only the samples of the border are taken: the last line of samples of the chunk and the
+    /// first line of the next, which share their positions along the border.
+    fn check_border(
+        &mut self,
+        graph: &Graph,
+        chunk: [i64; 2],
+        axis: usize,
+        size: usize,
+        chunk_len: f64,
+    ) {
+        let scheme = self.scheme;
+        let mut next_chunk = chunk;
+        next_chunk[axis] += 1;
+
+        // The last samples of a chunk share positions with the first samples of the next
+        let (last, first) = (
+            scheme.position(chunk[axis], size - 1, size, chunk_len),
+            scheme.position(next_chunk[axis], 0, size, chunk_len),
+        );
+        self.max_position_error = self.max_position_error.max((last - first).abs());
+
+        let other_axis = 1 - axis;
+        let mismatched_samples = (0..size)
+            .map(|idx| {
+                let along = scheme.position(chunk[other_axis], idx, size, chunk_len);
+                let sample = |across| match axis {
+                    0 => graph.sample(across, along, 0.0),
+                    _ => graph.sample(along, across, 0.0),
+                };
+
+                (sample(last), sample(first))
+            })
+            .filter(|(last, first)| last != first && !(last.is_nan() && first.is_nan()))
+            .inspect(|(last, first)| {
+                self.max_value_error = self.max_value_error.max((last - first).abs());
+            })
+            .count();
+
+        self.borders += 1;
+        self.mismatched_samples += mismatched_samples;
+
+        if mismatched_samples > 0 {
+            self.mismatched_borders += 1;
+        }
+    }
+}
+
+/// Collapsible window which samples a grid of adjacent chunks of a node and checks that the
+/// samples of shared borders are identical, which catches rounding in the math of the sampling
+/// positions.
+pub struct ChunkChecker {
+    pub node_idx: Option<usize>,
+
+    chunk_len: f64,
+
+    /// The number of chunks along each side of the checked grid.
+    chunks: usize,
+
+    first_chunk: [i64; 2],
+
+    /// The check which is running, whose reports replace the shown reports once it finishes.
+    job: Option<Job<Vec<Report>>>,
+
+    reports: Vec<Report>,
+
+    /// The number of samples along each side of a chunk, including both borders.
+    size: usize,
+
+    version: Option<(usize, usize, Backend)>,
+}
+
+impl ChunkChecker {
+    const DEFAULT_CHUNK_LEN: f64 = 1.0;
+    const DEFAULT_CHUNKS: usize = 4;
+    const DEFAULT_SIZE: usize = 33;
+
+    const MAX_CHUNKS: usize = 16;
+    const MAX_SIZE: usize = 1_025;
+
+    /// Starts checking every border of the grid of chunks with each scheme, on a thread of its
+    /// own, as large grids take a while.
+    fn check(&mut self, ctx: &Context, expr: Expr, backend: Backend) {
+        let (chunks, first_chunk, size, chunk_len) =
+            (self.chunks, self.first_chunk, self.size, self.chunk_len);
+
+        // Each chunk shares a border with the chunks after it along X and along Y
+        let borders = (0..chunks as i64)
+            .flat_map(|y| (0..chunks as i64).map(move |x| [first_chunk[0] + x, first_chunk[1] + y]))
+            .flat_map(|chunk| {
+                [0, 1].into_iter().filter_map(move |axis| {
+                    (chunk[axis] - first_chunk[axis] + 1 < chunks as i64).then_some((chunk, axis))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        self.job = Some(Job::new(
+            ctx,
+            Scheme::ALL.len() * borders.len(),
+            move || {
+                (
+                    Graph::with_backend(expr, backend),
+                    Scheme::ALL.into_iter().map(Report::new).collect::<Vec<_>>(),
+                )
+            },
+            move |(graph, reports), step_idx| {
+                let (chunk, axis) = borders[step_idx % borders.len()];
+                reports[step_idx / borders.len()].check_border(graph, chunk, axis, size, chunk_len);
+            },
+            |(_, reports)| reports,
+        ));
+    }
+
+    fn clear(&mut self) {
+        self.job = None;
+        self.reports.clear();
+    }
+
+    pub fn show(&mut self, ctx: &Context, node_exprs: &NodeExprs, backend: Backend) {
+        let Some(node_idx) = self.node_idx else {
+            return;
+        };
+
+        let Some((version, expr)) = node_exprs
+            .read()
+            .unwrap()
+            .get(&node_idx)
+            .map(|(version, expr)| (*version, Arc::clone(expr)))
+        else {
+            self.node_idx = None;
+            self.clear();
+
+            return;
+        };
+
+        // Results are only valid for the expression they were checked with
+        if self.version != Some((node_idx, version, backend)) {
+            self.clear();
+            self.version = Some((node_idx, version, backend));
+        }
+
+        if let Some(reports) = self.job.as_mut().and_then(|job| job.poll(ctx)) {
+            self.job = None;
+            self.reports = reports;
+        }
+
+        let mut open = true;
+
+        Window::new(format!("Chunk Borders #{node_idx}"))
+            .id(Id::new("chunk_borders"))
+            .open(&mut open)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                self.show_settings(ui);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.job.is_none(), Button::new("Check"))
+                        .on_hover_text(
+                            "Sample the borders of each chunk and compare shared borders",
+                        )
+                        .clicked()
+                    {
+                        self.check(ctx, expr.as_ref().clone(), backend);
+                    }
+
+                    if let Some(job) = &self.job {
+                        ui.add(ProgressBar::new(job.progress()).show_percentage());
+
+                        if ui.button("Cancel").clicked() {
+                            self.job = None;
+                        }
+                    }
+                });
+
+                if !self.reports.is_empty() {
+                    ui.separator();
+                    self.show_reports(ui);
+                }
+            });
+
+        if !open {
+            self.node_idx = None;
+            self.clear();
+        }
+    }
+
+    fn show_reports(&self, ui: &mut Ui) {
+        Grid::new("chunk_borders_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Scheme");
+                ui.strong("Mismatched Borders");
+                ui.strong("Samples");
+                ui.strong("Value Error")
+                    .on_hover_text("The largest difference between the values of a shared sample");
+                ui.strong("Position Error").on_hover_text(
+                    "The largest difference between the positions of a shared sample",
+                );
+                ui.end_row();
+
+                for report in &self.reports {
+                    ui.label(report.scheme.name())
+                        .on_hover_text(report.scheme.description());
+
+                    if report.mismatched_borders > 0 {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            format!("⚠ {} of {}", report.mismatched_borders, report.borders),
+                        );
+                    } else {
+                        ui.label(format!("✔ 0 of {}", report.borders));
+                    }
+
+                    ui.label(report.mismatched_samples.to_string());
+                    ui.label(format!("{:e}", report.max_value_error));
+                    ui.label(format!("{:e}", report.max_position_error));
+                    ui.end_row();
+                }
+            });
+
+        if self
+            .reports
+            .iter()
+            .any(|report| report.mismatched_borders > 0)
+        {
+            ui.label(
+                "Sample positions which are computed from the index of the sample in the whole \
+                world, like Graph::sample_chunk, are identical on both sides of a border",
+            );
+        }
+    }
+
+    fn show_settings(&mut self, ui: &mut Ui) {
+        Grid::new("chunk_borders_settings").show(ui, |ui| {
+            ui.label("Chunk Size");
+            ui.add(
+                DragValue::new(&mut self.size)
+                    .clamp_range(2..=Self::MAX_SIZE)
+                    .suffix(" samples"),
+            )
+            .on_hover_text("The samples along each side of a chunk, including both borders");
+            ui.end_row();
+
+            ui.label("Chunk Length");
+            ui.add(
+                DragValue::new(&mut self.chunk_len)
+                    .clamp_range(f64::EPSILON..=f64::MAX)
+                    .speed(0.01),
+            );
+            ui.end_row();
+
+            ui.label("First Chunk");
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(&mut self.first_chunk[0]).prefix("x: "));
+                ui.add(DragValue::new(&mut self.first_chunk[1]).prefix("y: "));
+            })
+            .response
+            .on_hover_text("Chunks far from the origin show more rounding");
+            ui.end_row();
+
+            let chunks = self.chunks;
+            ui.label("Chunks");
+            ui.add(
+                DragValue::new(&mut self.chunks)
+                    .clamp_range(2..=Self::MAX_CHUNKS)
+                    .suffix(format!(" × {chunks}")),
+            );
+            ui.end_row();
+        });
+    }
+}
+
+impl Default for ChunkChecker {
+    fn default() -> Self {
+        Self {
+            node_idx: None,
+            chunk_len: Self::DEFAULT_CHUNK_LEN,
+            chunks: Self::DEFAULT_CHUNKS,
+            first_chunk: [0; 2],
+            job: None,
+            reports: vec![],
+            size: Self::DEFAULT_SIZE,
+            version: None,
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use {
+    super::{node::NoiseNode, splice::WireLayout, view::Viewer},
+    egui::{vec2, Context, Pos2, Vec2},
+    egui_snarl::{ui::SnarlViewer, InPinId, OutPinId, Snarl},
+    ron::{de::from_str, ser::to_string},
+    serde::{Deserialize, Serialize},
+    std::collections::{HashMap, HashSet},
+};
+
+/// A copy of a node which refers to no other node.
+#[derive(Deserialize, Serialize)]
+struct CopiedNode {
+    node: NoiseNode,
+
+    /// The index of the node in the graph it was copied from, which the wires refer to.
+    node_idx: usize,
+
+    /// The position of the node from the top left corner of the copied nodes.
+    offset: Vec2,
+}
+
+/// Nodes copied from the canvas with the wires between them, which are kept on the clipboard as
+/// text so that they may be pasted into other projects.
+///
+/// Inputs connected to nodes which were not copied keep the values of those nodes instead.
+#[derive(Deserialize, Serialize)]
+pub struct CopiedNodes {
+    nodes: Vec<CopiedNode>,
+
+    /// The top left corner of the copied nodes in the graph they were copied from.
+    origin: Pos2,
+
+    wires: Vec<(OutPinId, InPinId)>,
+}
+
+impl CopiedNodes {
+    /// How far duplicated nodes are placed from the nodes they are copies of.
+    pub const DUPLICATE_OFFSET: Vec2 = vec2(32.0, 32.0);
+
+    pub fn from_text(text: &str) -> Option<Self> {
+        from_str(text).ok()
+    }
+
+    pub fn new(
+        ctx: &Context,
+        node_indices: &HashSet<usize>,
+        snarl: &Snarl<NoiseNode>,
+        wire_layout: &WireLayout,
+    ) -> Self {
+        let mut node_indices = node_indices.iter().copied().collect::<Vec<_>>();
+        node_indices.sort_unstable();
+
+        let positions = node_indices
+            .iter()
+            .map(|&node_idx| wire_layout.header_pos(ctx, node_idx).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let origin = positions
+            .iter()
+            .copied()
+            .reduce(|lhs, rhs| lhs.min(rhs))
+            .unwrap_or_default();
+
+        let nodes = node_indices
+            .iter()
+            .zip(positions)
+            .map(|(&node_idx, pos)| {
+                let mut node = snarl.get_node(node_idx).clone();
+                node.detach(snarl);
+
+                CopiedNode {
+                    node,
+                    node_idx,
+                    offset: pos - origin,
+                }
+            })
+            .collect();
+        let wires = node_indices
+            .iter()
+            .flat_map(|&node_idx| {
+                let from = OutPinId {
+                    node: node_idx,
+                    output: 0,
+                };
+
+                snarl
+                    .out_pin(from)
+                    .remotes
+                    .into_iter()
+                    .filter(|to| node_indices.contains(&to.node))
+                    .map(move |to| (from, to))
+            })
+            .collect();
+
+        Self {
+            nodes,
+            origin,
+            wires,
+        }
+    }
+
+    pub fn origin(&self) -> Pos2 {
+        self.origin
+    }
+
+    /// Inserts the nodes with their top left corner at `pos` and connects the wires between them,
+    /// returning the indices of the new nodes.
+    pub fn paste(
+        self,
+        pos: Pos2,
+        snarl: &mut Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) -> HashSet<usize> {
+        let mut node_indices = HashMap::with_capacity(self.nodes.len());
+
+        for CopiedNode {
+            mut node,
+            node_idx,
+            offset,
+        } in self.nodes
+        {
+            // Nodes which inherit the master seed take the master seed of this graph
+            if let Some(Some(master_seed)) = node.master_seed_mut() {
+                *master_seed = *viewer.master_seed;
+            }
+
+            node_indices.insert(node_idx, snarl.insert_node(pos + offset, node));
+        }
+
+        for (from, to) in self.wires {
+            let (Some(&from_node_idx), Some(&to_node_idx)) =
+                (node_indices.get(&from.node), node_indices.get(&to.node))
+            else {
+                continue;
+            };
+
+            viewer.connect(
+                &snarl.out_pin(OutPinId {
+                    node: from_node_idx,
+                    ..from
+                }),
+                &snarl.in_pin(InPinId {
+                    node: to_node_idx,
+                    ..to
+                }),
+                snarl,
+            );
+        }
+
+        viewer
+            .updated_node_indices
+            .extend(node_indices.values().copied());
+
+        node_indices.into_values().collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        to_string(self).unwrap_or_default()
+    }
+}
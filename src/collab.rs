@@ -0,0 +1,565 @@
+use {
+    super::{
+        app::Project,
+        bookmarks::Bookmark,
+        node::{NodeId, NodeIds, NoiseNode},
+        saved_graph::{SavedGraph, SavedNode, SavedWire},
+    },
+    crossbeam_channel::{bounded, unbounded, Receiver, Sender, TryRecvError},
+    egui::{Button, Context, Id, TextEdit, Window},
+    egui_snarl::Snarl,
+    log::warn,
+    noise_graph::Backend,
+    ron::{from_str, to_string},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::{hash_map::RandomState, BTreeMap},
+        hash::BuildHasher,
+        io::{BufRead, BufReader, Write},
+        net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs},
+        sync::{Arc, Mutex},
+        thread::spawn,
+        time::{Duration, Instant},
+    },
+};
+
+/// A part of the project which instances edit independently of each other.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+enum Key {
+    Backend,
+    Bookmarks,
+    Node(NodeId),
+    Notes,
+
+    /// The wire connected to an input, which is connected to one output at most.
+    Wire {
+        to: NodeId,
+        input: usize,
+    },
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+enum Value {
+    Backend(Backend),
+    Bookmarks(Vec<Bookmark>),
+    Node(Box<SavedNode>),
+    Notes(String),
+    Wire { from: NodeId, output: usize },
+}
+
+/// The value of a part of the project after an edit, or `None` once removed, stamped with the
+/// Lamport clock of the edit and the instance which made it.
+#[derive(Clone, Deserialize, Serialize)]
+struct Edit {
+    clock: u64,
+    key: Key,
+    peer: u64,
+    value: Option<Value>,
+}
+
+/// A line sent between instances through the relay.
+#[derive(Deserialize, Serialize)]
+enum Message {
+    /// Sent by an instance which has just connected, so that the others send it their edits.
+    Join,
+
+    /// Edits of the project; instances answer `Join` with the latest edit of every part of the
+    /// project, including the parts which were removed.
+    Edits(Vec<Edit>),
+}
+
+struct Connection {
+    /// Set until the edits of the session have been received, or nobody answered in time.
+    joined_at: Option<Instant>,
+
+    lines: Receiver<String>,
+    stream: TcpStream,
+
+    /// Lines waiting to be written to the relay, which a thread of its own writes so that a slow
+    /// relay does not block the UI.
+    writer: Sender<String>,
+}
+
+/// Collapsible window which connects to a relay shared with other instances, keeping the project
+/// the same in all of them.
+///
+/// The project is split into parts: each node, each wire, the backend, the bookmarks and the
+/// notes. An edit sends the parts which changed, each stamped with a Lamport clock, and instances
+/// keep the latest edit of every part, breaking ties by instance. Edits of different parts are
+/// merged, so two people may edit different nodes at the same moment; when they edit the same
+/// node, the later edit replaces the earlier one. Removed parts are remembered, so that a late
+/// edit does not bring them back.
+pub struct Collab {
+    address: String,
+
+    /// The Lamport clock, which is ahead of every edit sent or received.
+    clock: u64,
+
+    connection: Option<Connection>,
+
+    /// The result of connecting to the relay, which a thread of its own resolves and connects to
+    /// so that a slow or unreachable relay does not block the UI.
+    connecting: Option<Receiver<Result<TcpStream, String>>>,
+
+    /// The latest edit of every part of the project, with its value as text, which local edits
+    /// are found by.
+    edits: BTreeMap<Key, (Edit, Option<String>)>,
+
+    peer: u64,
+    sent_at: Option<Instant>,
+    status: Option<String>,
+}
+
+impl Collab {
+    /// How often local edits are sent, which limits the traffic of dragging values.
+    const SEND_INTERVAL: Duration = Duration::from_millis(100);
+
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+    const JOIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+    fn connect(&mut self, ctx: &Context) {
+        let address = self.address.clone();
+        let (tx, rx) = bounded(1);
+        let ctx = ctx.clone();
+        spawn(move || {
+            let stream = address
+                .to_socket_addrs()
+                .ok()
+                .and_then(|mut addrs| addrs.next())
+                .ok_or_else(|| "Unknown address".to_owned())
+                .and_then(|addr| {
+                    TcpStream::connect_timeout(&addr, Self::CONNECT_TIMEOUT)
+                        .map_err(|err| err.to_string())
+                });
+
+            // Nobody receives the stream once connecting has been cancelled, which closes it
+            if tx.send(stream).is_ok() {
+                ctx.request_repaint();
+            }
+        });
+
+        self.connecting = Some(rx);
+        self.status = None;
+    }
+
+    /// Joins the session of the relay once the thread of `connect` has finished.
+    fn connected(&mut self, ctx: &Context, stream: Result<TcpStream, String>) {
+        let (stream, reader, writer) = match stream.and_then(|stream| {
+            let reader = stream.try_clone().map_err(|err| err.to_string())?;
+            let writer = stream.try_clone().map_err(|err| err.to_string())?;
+
+            Ok((stream, reader, writer))
+        }) {
+            Ok(streams) => streams,
+            Err(err) => {
+                warn!("Unable to connect to relay");
+                self.status = Some(format!("Unable to connect: {err}"));
+
+                return;
+            }
+        };
+
+        stream.set_nodelay(true).unwrap_or_default();
+
+        let (tx, rx) = unbounded();
+        let ctx = ctx.clone();
+        spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+
+                if tx.send(line).is_err() {
+                    break;
+                }
+
+                ctx.request_repaint();
+            }
+        });
+
+        self.connection = Some(Connection {
+            joined_at: Some(Instant::now()),
+            lines: rx,
+            stream,
+            writer: write_lines(writer),
+        });
+        self.edits.clear();
+        self.status = None;
+        self.send(Message::Join);
+    }
+
+    fn disconnect(&mut self, status: Option<&str>) {
+        if let Some(connection) = self.connection.take() {
+            connection
+                .stream
+                .shutdown(Shutdown::Both)
+                .unwrap_or_default();
+        }
+
+        self.status = status.map(str::to_owned);
+    }
+
+    /// Keeps an edit if it is later than the kept edit of the same part, returning `true` if so.
+    fn merge(&mut self, edit: Edit) -> bool {
+        self.clock = self.clock.max(edit.clock);
+
+        if let Some((kept, _)) = self.edits.get(&edit.key) {
+            if (kept.clock, kept.peer) >= (edit.clock, edit.peer) {
+                return false;
+            }
+        }
+
+        let text = edit.value.as_ref().and_then(|value| to_string(value).ok());
+        self.edits.insert(edit.key, (edit, text));
+
+        true
+    }
+
+    /// The project made of the kept edits, whose nodes keep their graph indices in `node_ids`.
+    fn project(&self, node_ids: &NodeIds) -> Project<Snarl<NoiseNode>> {
+        let mut backend = Backend::default();
+        let mut bookmarks = vec![];
+        let mut graph = SavedGraph::default();
+        let mut notes = String::new();
+
+        for (key, (edit, _)) in &self.edits {
+            match (*key, edit.value.clone()) {
+                (_, Some(Value::Backend(value))) => backend = value,
+                (_, Some(Value::Bookmarks(value))) => bookmarks = value,
+                (Key::Node(id), Some(Value::Node(node))) => {
+                    graph.nodes.insert(id, *node);
+                }
+                (_, Some(Value::Notes(value))) => notes = value,
+                (Key::Wire { to, input }, Some(Value::Wire { from, output })) => {
+                    graph.wires.push(SavedWire {
+                        from,
+                        input,
+                        output,
+                        to,
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        let (snarl, node_ids) = graph.load(backend, node_ids);
+
+        Project {
+            backend,
+            bookmarks,
+            dir: None,
+            node_ids,
+            notes,
+            snarl,
+        }
+    }
+
+    fn send(&mut self, message: Message) {
+        let Some(connection) = &self.connection else {
+            return;
+        };
+
+        if to_string(&message)
+            .ok()
+            .and_then(|line| connection.writer.send(line).ok())
+            .is_none()
+        {
+            warn!("Unable to send collaboration message");
+            self.disconnect(Some("Disconnected from relay"));
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        if let Some(connecting) = &self.connecting {
+            let stream = match connecting.try_recv() {
+                Ok(stream) => Some(stream),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => Some(Err("Unable to connect".to_owned())),
+            };
+
+            if let Some(stream) = stream {
+                self.connecting = None;
+                self.connected(ctx, stream);
+            }
+        }
+
+        Window::new("Collaborate")
+            .id(Id::new("collab"))
+            .default_open(false)
+            .show(ctx, |ui| {
+                if self.connection.is_some() {
+                    ui.label(format!("Connected to {}", self.address));
+
+                    if ui.button("Disconnect").clicked() {
+                        self.disconnect(None);
+                    }
+                } else if self.connecting.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(format!("Connecting to {}…", self.address));
+                    });
+
+                    if ui.button("Cancel").clicked() {
+                        self.connecting = None;
+                    }
+                } else {
+                    ui.horizontal(|ui| {
+                        ui.label("Relay");
+                        ui.add(TextEdit::singleline(&mut self.address).hint_text("host:port"))
+                            .on_hover_text(
+                                "The address of `noise_gui --relay ADDRESS`, run where every \
+                                instance can reach it",
+                            );
+                    });
+
+                    if ui
+                        .add_enabled(!self.address.trim().is_empty(), Button::new("Connect"))
+                        .on_hover_text("Replaces the open project with the project of the session")
+                        .clicked()
+                    {
+                        self.connect(ctx);
+                    }
+                }
+
+                if let Some(status) = &self.status {
+                    ui.weak(status);
+                }
+            });
+    }
+
+    /// Sends local edits and returns the project of the session if another instance changed it.
+    pub fn update(
+        &mut self,
+        ctx: &Context,
+        snarl: &Snarl<NoiseNode>,
+        backend: Backend,
+        bookmarks: &[Bookmark],
+        node_ids: &NodeIds,
+        notes: &str,
+    ) -> Option<Project<Snarl<NoiseNode>>> {
+        let connection = self.connection.as_mut()?;
+        let is_joined = connection.joined_at.is_none();
+        let mut is_joining = false;
+        let mut received = vec![];
+
+        loop {
+            match connection.lines.try_recv() {
+                Ok(line) => match from_str::<Message>(&line) {
+                    Ok(Message::Join) => is_joining = true,
+                    Ok(Message::Edits(edits)) => {
+                        connection.joined_at = None;
+                        received.extend(edits);
+                    }
+                    Err(_) => warn!("Unable to read collaboration message"),
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.disconnect(Some("Disconnected from relay"));
+
+                    return None;
+                }
+            }
+        }
+
+        // Nobody answered, so this instance starts the session with its own project
+        if let Some(joined_at) = connection.joined_at {
+            let elapsed = joined_at.elapsed();
+            if elapsed < Self::JOIN_TIMEOUT {
+                ctx.request_repaint_after(Self::JOIN_TIMEOUT - elapsed);
+
+                return None;
+            }
+
+            connection.joined_at = None;
+        }
+
+        let is_due = match self.sent_at.map(|sent_at| sent_at.elapsed()) {
+            Some(elapsed) if elapsed < Self::SEND_INTERVAL => {
+                ctx.request_repaint_after(Self::SEND_INTERVAL - elapsed);
+
+                false
+            }
+            _ => true,
+        };
+
+        // Local edits are sent before received edits replace the project, which would lose them;
+        // an instance which has just joined replaces its project with the project of the session
+        if (is_due || !received.is_empty()) && (is_joined || received.is_empty()) {
+            let edits = self.local_edits(snarl, backend, bookmarks, node_ids, notes);
+            if !edits.is_empty() {
+                self.sent_at = Some(Instant::now());
+                self.send(Message::Edits(edits));
+            }
+        }
+
+        // Instances which are still joining have no edits of the session to send yet
+        if is_joining && is_joined {
+            let edits = self.edits.values().map(|(edit, _)| edit.clone()).collect();
+            self.send(Message::Edits(edits));
+        }
+
+        let mut is_changed = false;
+        for edit in received {
+            is_changed |= self.merge(edit);
+        }
+
+        is_changed.then(|| self.project(node_ids))
+    }
+
+    /// Finds the parts of the project which changed since the kept edits, keeping the edits of
+    /// those parts.
+    fn local_edits(
+        &mut self,
+        snarl: &Snarl<NoiseNode>,
+        backend: Backend,
+        bookmarks: &[Bookmark],
+        node_ids: &NodeIds,
+        notes: &str,
+    ) -> Vec<Edit> {
+        // Connected inputs are saved without the values of the nodes they are connected to, so
+        // that editing a constant does not also edit every node it is connected to
+        let graph = SavedGraph::without_input_values(snarl, node_ids);
+        let mut values = BTreeMap::new();
+        values.insert(Key::Backend, Value::Backend(backend));
+        values.insert(Key::Bookmarks, Value::Bookmarks(bookmarks.to_vec()));
+        values.insert(Key::Notes, Value::Notes(notes.to_owned()));
+
+        for (id, node) in graph.nodes {
+            values.insert(Key::Node(id), Value::Node(Box::new(node)));
+        }
+
+        for wire in graph.wires {
+            values.insert(
+                Key::Wire {
+                    to: wire.to,
+                    input: wire.input,
+                },
+                Value::Wire {
+                    from: wire.from,
+                    output: wire.output,
+                },
+            );
+        }
+
+        let clock = self.clock + 1;
+        let mut edits = self
+            .edits
+            .iter()
+            .filter(|(key, (_, text))| text.is_some() && !values.contains_key(key))
+            .map(|(&key, _)| Edit {
+                clock,
+                key,
+                peer: self.peer,
+                value: None,
+            })
+            .collect::<Vec<_>>();
+
+        for (key, value) in values {
+            let text = to_string(&value).ok();
+            if self.edits.get(&key).map(|(_, kept)| kept) != Some(&text) {
+                edits.push(Edit {
+                    clock,
+                    key,
+                    peer: self.peer,
+                    value: Some(value),
+                });
+            }
+        }
+
+        for edit in &edits {
+            self.merge(edit.clone());
+        }
+
+        edits
+    }
+}
+
+impl Default for Collab {
+    fn default() -> Self {
+        Self {
+            address: String::new(),
+            clock: 0,
+            connection: None,
+            connecting: None,
+            edits: Default::default(),
+            peer: RandomState::new().hash_one(Instant::now()),
+            sent_at: None,
+            status: None,
+        }
+    }
+}
+
+/// Passes every line sent by one connection on to all of the others, until the process is
+/// stopped, and returns `false` if the address could not be listened on.
+pub fn relay(address: &str) -> bool {
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Unable to listen on {address}: {err}");
+
+            return false;
+        }
+    };
+
+    eprintln!("Relaying on {address}");
+
+    let writers = Arc::new(Mutex::new(Vec::<(usize, Sender<String>)>::new()));
+
+    for (stream_idx, stream) in listener.incoming().enumerate() {
+        let Ok((stream, writer)) =
+            stream.and_then(|stream| stream.try_clone().map(|writer| (stream, writer)))
+        else {
+            continue;
+        };
+
+        let peer_addr = stream
+            .peer_addr()
+            .map_or_else(|_| "Unknown".to_owned(), |addr| addr.to_string());
+        eprintln!("{peer_addr} connected");
+
+        writers
+            .lock()
+            .unwrap()
+            .push((stream_idx, write_lines(writer)));
+
+        let writers = Arc::clone(&writers);
+        spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+
+                // Connections which can no longer be written to are dropped
+                writers.lock().unwrap().retain(|(other_idx, other)| {
+                    *other_idx == stream_idx || other.send(line.clone()).is_ok()
+                });
+            }
+
+            writers
+                .lock()
+                .unwrap()
+                .retain(|(other_idx, _)| *other_idx != stream_idx);
+            eprintln!("{peer_addr} disconnected");
+        });
+    }
+
+    true
+}
+
+/// Writes lines to a stream from a thread of its own, so that sending a line never blocks. Once
+/// the stream can no longer be written to it is shut down, and sending fails.
+fn write_lines(mut stream: TcpStream) -> Sender<String> {
+    let (tx, rx) = unbounded::<String>();
+
+    spawn(move || {
+        for line in rx {
+            if writeln!(stream, "{line}").is_err() {
+                stream.shutdown(Shutdown::Both).unwrap_or_default();
+
+                break;
+            }
+        }
+    });
+
+    tx
+}
@@ -0,0 +1,201 @@
+use {
+    egui::Context,
+    log::warn,
+    rfd::{MessageButtons, MessageDialog, MessageLevel},
+    ron::ser::{to_string_pretty, PrettyConfig},
+    serde::Serialize,
+    std::{
+        backtrace::Backtrace,
+        env::temp_dir,
+        fs::{metadata, read_dir, remove_file, write},
+        panic::{set_hook, take_hook},
+        path::PathBuf,
+        process::id,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Mutex,
+        },
+        time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// A panic which saved the open project, waiting to be reported from the main thread.
+struct Crash {
+    backtrace: String,
+    message: String,
+    path: PathBuf,
+}
+
+/// Keeps a copy of the open project to be saved if the app panics.
+///
+/// Serializing the project takes longer the larger the graph is, so while it is being edited, such
+/// as while a value is dragged, the copy is only updated once the edits pause or once per
+/// interval.
+#[derive(Default)]
+pub struct CrashCopy {
+    /// When the project was first edited after the copy was last updated.
+    changed_at: Option<Instant>,
+
+    /// When the project was last edited.
+    edited_at: Option<Instant>,
+}
+
+impl CrashCopy {
+    /// The time without edits after which the copy is updated.
+    const DEBOUNCE: Duration = Duration::from_millis(500);
+
+    /// The longest time the copy is left behind the project while it is edited.
+    const MAX_DELAY: Duration = Duration::from_secs(5);
+
+    /// Updates the copy of the project if it is due, where `has_changes` is whether the project
+    /// was edited this frame.
+    pub fn update<T>(&mut self, ctx: &Context, has_changes: bool, project: impl FnOnce() -> T)
+    where
+        T: Serialize,
+    {
+        let now = Instant::now();
+
+        if has_changes {
+            self.changed_at.get_or_insert(now);
+            self.edited_at = Some(now);
+        }
+
+        let (Some(changed_at), Some(edited_at)) = (self.changed_at, self.edited_at) else {
+            return;
+        };
+
+        if now - edited_at >= Self::DEBOUNCE || now - changed_at >= Self::MAX_DELAY {
+            save_project(&project());
+            self.changed_at = None;
+        } else {
+            ctx.request_repaint_after(Self::DEBOUNCE - (now - edited_at));
+        }
+    }
+}
+
+/// Set once a panic has saved the open project, so that it is reported without locking.
+static IS_CRASHED: AtomicBool = AtomicBool::new(false);
+
+/// The panic which saved the open project, until it is reported.
+static CRASH: Mutex<Option<Crash>> = Mutex::new(None);
+
+/// The most recent copy of the open project, which is written out if the app panics.
+static PROJECT: Mutex<Option<String>> = Mutex::new(None);
+
+/// The number of lines of the backtrace shown in the crash dialog.
+const BACKTRACE_LINES: usize = 24;
+
+/// The start of the names of dump files, which end with the time and process of the crash.
+const DUMP_PREFIX: &str = "noise_gui_crash_";
+
+/// Returns a dump path of its own for this crash, so that sessions which crash at the same time
+/// or one after another do not overwrite each other's projects.
+fn dump_path() -> PathBuf {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    temp_dir().join(format!("{DUMP_PREFIX}{secs}_{}.ron", id()))
+}
+
+/// Installs a panic hook which writes the open project to a dump file, to be reported by
+/// [`report`].
+///
+/// The panicking thread may be any thread, and dialogs may only be shown from the main thread, so
+/// the hook only records the crash.
+pub fn install() {
+    let default_hook = take_hook();
+
+    set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        // Only the first panic is saved, as later ones often follow from it
+        if IS_CRASHED.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // The lock may be held by the thread which panicked, in which case nothing is saved
+        let Some(project) = PROJECT.try_lock().ok().and_then(|project| project.clone()) else {
+            return;
+        };
+
+        let path = dump_path();
+        if write(&path, project).is_err() {
+            return;
+        }
+
+        let backtrace = Backtrace::force_capture().to_string();
+        let backtrace = backtrace
+            .lines()
+            .take(BACKTRACE_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Ok(mut crash) = CRASH.try_lock() {
+            *crash = Some(Crash {
+                backtrace,
+                message: info.to_string(),
+                path,
+            });
+            IS_CRASHED.store(true, Ordering::Relaxed);
+        }
+    }));
+}
+
+/// Returns the project saved by the most recent crash, if it was kept to be opened.
+pub fn recovered_project() -> Option<PathBuf> {
+    read_dir(temp_dir())
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension().is_some_and(|extension| extension == "ron")
+                && path
+                    .file_name()
+                    .is_some_and(|file_name| file_name.to_string_lossy().starts_with(DUMP_PREFIX))
+        })
+        .max_by_key(|path| metadata(path).and_then(|metadata| metadata.modified()).ok())
+}
+
+/// Asks whether the project saved by a panic should be opened the next time the app starts; this
+/// must be called from the main thread.
+pub fn report() {
+    if !IS_CRASHED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let Some(Crash {
+        backtrace,
+        message,
+        path,
+    }) = CRASH.lock().ok().and_then(|mut crash| crash.take())
+    else {
+        return;
+    };
+
+    IS_CRASHED.store(false, Ordering::Relaxed);
+
+    let reopen = MessageDialog::new()
+        .set_level(MessageLevel::Error)
+        .set_title("Noise Gen has crashed")
+        .set_description(&format!(
+            "The project was saved to {}.\n\nOpen it the next time Noise Gen starts?\n\n\
+            {message}\n\n{backtrace}",
+            path.display()
+        ))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+
+    if !reopen {
+        remove_file(path).unwrap_or_default();
+    }
+}
+
+fn save_project<T>(project: &T)
+where
+    T: Serialize,
+{
+    match to_string_pretty(project, PrettyConfig::default()) {
+        Ok(project) => *PROJECT.lock().unwrap() = Some(project),
+        Err(_) => warn!("Unable to copy project"),
+    }
+}
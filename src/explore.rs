@@ -0,0 +1,524 @@
+use {
+    super::{app::NodeExprs, grid, readout, thread::Threads},
+    crossbeam_channel::{unbounded, Receiver, Sender},
+    egui::{
+        pos2, vec2, Color32, ColorImage, Context, DragValue, Id, Pos2, Rect, Sense, TextureHandle,
+        Ui, Vec2, Window,
+    },
+    noise_graph::{Backend, Expr, SinglePrecision},
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    iter::repeat_with,
+    num::NonZeroUsize,
+    thread::{available_parallelism, spawn, JoinHandle},
+};
+
+/// The zoom level and column and row of a tile; tiles of a level are `2^level` units wide.
+type TileKey = (i32, i64, i64);
+
+/// A tile request: the generation of the view, the tile, what to sample it from and whether it
+/// shows drift.
+type TileRequest = (usize, TileKey, Backend, Arc<Expr>, bool);
+
+/// A rendered tile: the generation of the view, the tile, its grayscale pixels and its largest
+/// drift.
+type TileResponse = (usize, TileKey, Vec<u8>, f64);
+
+struct Tile {
+    /// The largest difference between double and single precision values of the tile.
+    max_drift: f64,
+
+    texture: TextureHandle,
+
+    /// The frame the tile was last drawn, which decides the tiles evicted from the cache.
+    used: u64,
+}
+
+/// Collapsible window which shows a node as an infinite world which may be panned and zoomed,
+/// streamed in tiles so that only newly uncovered areas are rendered.
+pub struct Explorer {
+    pub node_idx: Option<usize>,
+
+    /// The world position at the center of the view.
+    center: [f64; 2],
+
+    /// Shows the difference between double and single precision evaluation instead of values.
+    drift: bool,
+
+    frame: u64,
+
+    /// Increased whenever the tiles are cleared, so that stale responses are ignored.
+    generation: usize,
+
+    /// Whether world-space grid lines, axis labels and a ruler are drawn over the view.
+    grid: bool,
+
+    pending: HashSet<TileKey>,
+
+    /// The number of pixels each world unit is drawn across.
+    pixels_per_unit: f64,
+
+    tiles: HashMap<TileKey, Tile>,
+    version: Option<(usize, usize, Backend)>,
+
+    /// The largest drift of the tiles drawn last frame.
+    visible_max_drift: Option<f64>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    workers: Vec<JoinHandle<()>>,
+
+    rx: Receiver<TileResponse>,
+    tx: Sender<Option<TileRequest>>,
+    thread_rx: Receiver<Option<TileRequest>>,
+    thread_tx: Sender<TileResponse>,
+}
+
+impl Explorer {
+    const DEFAULT_PIXELS_PER_UNIT: f64 = 256.0;
+
+    /// The drift drawn as black and as white, on a logarithmic scale.
+    const DRIFT_RANGE: [f64; 2] = [1e-7, 1e-1];
+
+    /// The number of tiles kept in the cache, about 64 MB of textures.
+    const MAX_TILES: usize = 1_024;
+
+    /// The number of tiles requested but not yet rendered; the nearest missing tiles are
+    /// requested first, so tiles which were panned past are not rendered in vain.
+    const MAX_PENDING: usize = 64;
+
+    /// The number of coarser levels searched for a tile to stretch over one not yet rendered.
+    const MAX_FALLBACK_LEVELS: i32 = 4;
+
+    const MAX_PIXELS_PER_UNIT: f64 = 1e6;
+    const MIN_PIXELS_PER_UNIT: f64 = 1e-3;
+
+    #[cfg(target_arch = "wasm32")]
+    const REQUESTS_PER_FRAME: usize = 4;
+
+    /// The number of pixels along each side of a tile.
+    const TILE_SIZE: usize = 128;
+
+    fn clear(&mut self) {
+        self.clear_tiles();
+        self.version = None;
+    }
+
+    fn clear_tiles(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.pending.clear();
+        self.tiles.clear();
+        self.visible_max_drift = None;
+    }
+
+    fn drift_to_gray(drift: f64) -> u8 {
+        let [min, max] = Self::DRIFT_RANGE.map(f64::log10);
+
+        (((drift.log10() - min) / (max - min)).clamp(0.0, 1.0) * 255.0) as u8
+    }
+
+    /// Returns the level whose tiles are drawn between 0.7 and 1.4 times their size.
+    fn level(&self) -> i32 {
+        (Self::TILE_SIZE as f64 / self.pixels_per_unit)
+            .log2()
+            .round() as i32
+    }
+
+    fn render_tile(
+        (level, col, row): TileKey,
+        backend: Backend,
+        expr: &Expr,
+        drift: bool,
+    ) -> (Vec<u8>, f64) {
+        let noise = expr.noise_with(backend.get());
+        let single_noise = drift.then(|| expr.noise_with(&SinglePrecision(backend.get())));
+        let tile_len = 2f64.powi(level);
+        let step = tile_len / Self::TILE_SIZE as f64;
+        let [u, v] = [col as f64 * tile_len, row as f64 * tile_len];
+        let mut pixels = vec![0; Self::TILE_SIZE * Self::TILE_SIZE];
+        let mut max_drift = 0f64;
+
+        // Like the previews, rows run along the first coordinate
+        for (y, pixel_row) in pixels.chunks_exact_mut(Self::TILE_SIZE).enumerate() {
+            let eval_x = v + (y as f64 + 0.5) * step;
+            for (x, pixel) in pixel_row.iter_mut().enumerate() {
+                let eval_y = u + (x as f64 + 0.5) * step;
+                let point = [eval_x, eval_y, 0.0];
+                let value = noise.get(point);
+
+                *pixel = if let Some(single_noise) = &single_noise {
+                    let drift = (value - single_noise.get(point)).abs();
+                    max_drift = max_drift.max(drift);
+
+                    Self::drift_to_gray(drift)
+                } else {
+                    Threads::value_to_gray(value)
+                };
+            }
+        }
+
+        (pixels, max_drift)
+    }
+
+    fn request(&mut self, request: TileRequest) {
+        self.pending.insert(request.1);
+
+        // Threads are only started once something is explored
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.workers.is_empty() {
+            self.workers = repeat_with(|| {
+                let (rx, tx) = (self.thread_rx.clone(), self.thread_tx.clone());
+                spawn(move || Self::thread_worker(rx, tx))
+            })
+            .take(
+                available_parallelism()
+                    .map(NonZeroUsize::get)
+                    .unwrap_or_default()
+                    .max(1),
+            )
+            .collect();
+        }
+
+        self.tx.send(Some(request)).unwrap();
+    }
+
+    fn screen_to_world(&self, rect: Rect, pos: Pos2) -> [f64; 2] {
+        let offset = pos - rect.center();
+
+        [
+            self.center[0] + offset.x as f64 / self.pixels_per_unit,
+            self.center[1] + offset.y as f64 / self.pixels_per_unit,
+        ]
+    }
+
+    pub fn show(&mut self, ctx: &Context, node_exprs: &NodeExprs, backend: Backend) {
+        let Some(node_idx) = self.node_idx else {
+            return;
+        };
+
+        let Some((version, expr)) = node_exprs
+            .read()
+            .unwrap()
+            .get(&node_idx)
+            .map(|(version, expr)| (*version, Arc::clone(expr)))
+        else {
+            self.node_idx = None;
+            self.clear();
+
+            return;
+        };
+
+        if self.version != Some((node_idx, version, backend)) {
+            self.clear();
+            self.version = Some((node_idx, version, backend));
+        }
+
+        self.update_tiles(ctx);
+
+        let mut open = true;
+
+        Window::new(format!("Explore #{node_idx}"))
+            .id(Id::new("explore"))
+            .open(&mut open)
+            .default_size([512.0, 512.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.show_view(ui, backend, &expr);
+            });
+
+        if !open {
+            self.node_idx = None;
+            self.clear();
+        }
+    }
+
+    fn show_view(&mut self, ui: &mut Ui, backend: Backend, expr: &Arc<Expr>) {
+        ui.horizontal(|ui| {
+            let drift = self.drift;
+            ui.selectable_value(&mut self.drift, false, "Value");
+            ui.selectable_value(&mut self.drift, true, "f32 Drift")
+                .on_hover_text(format!(
+                    "Shows the difference between evaluating the graph in double and in single \
+                    precision, as GPUs and many engines do; black is {:e} or less and white is \
+                    {:e} or more",
+                    Self::DRIFT_RANGE[0],
+                    Self::DRIFT_RANGE[1],
+                ));
+
+            if self.drift != drift {
+                self.clear_tiles();
+            }
+
+            if let Some(max_drift) = self.visible_max_drift.filter(|_| self.drift) {
+                ui.label(format!("Max: {max_drift:.2e}"))
+                    .on_hover_text("The largest drift of the visible area");
+            }
+        });
+
+        ui.horizontal(|ui| {
+            // Like the previews, the first coordinate runs down the view
+            let speed = 4.0 / self.pixels_per_unit;
+            ui.add(
+                DragValue::new(&mut self.center[1])
+                    .prefix("X: ")
+                    .speed(speed),
+            );
+            ui.add(
+                DragValue::new(&mut self.center[0])
+                    .prefix("Y: ")
+                    .speed(speed),
+            )
+            .on_hover_text("Large coordinates show more drift");
+
+            if ui.button("Reset View").clicked() {
+                self.center = [0.0; 2];
+                self.pixels_per_unit = Self::DEFAULT_PIXELS_PER_UNIT;
+            }
+
+            ui.label(format!(
+                "Zoom: {:.3}×",
+                self.pixels_per_unit / Self::DEFAULT_PIXELS_PER_UNIT
+            ))
+            .on_hover_text("Drag to pan and scroll to zoom");
+            ui.checkbox(&mut self.grid, "Grid")
+                .on_hover_text("Draws world-space grid lines, axis labels and a ruler");
+            ui.weak(format!("{} tiles cached", self.tiles.len()));
+        });
+
+        let (rect, res) = ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
+        if res.dragged() {
+            let delta = res.drag_delta();
+            self.center[0] -= delta.x as f64 / self.pixels_per_unit;
+            self.center[1] -= delta.y as f64 / self.pixels_per_unit;
+        }
+
+        if let Some(pos) = res.hover_pos() {
+            // Zoom around the cursor, so that the position below it stays put
+            let zoom = ui.input(|input| input.zoom_delta() * (input.scroll_delta.y / 200.0).exp());
+            if zoom != 1.0 {
+                let before = self.screen_to_world(rect, pos);
+                self.pixels_per_unit = (self.pixels_per_unit * zoom as f64)
+                    .clamp(Self::MIN_PIXELS_PER_UNIT, Self::MAX_PIXELS_PER_UNIT);
+                let after = self.screen_to_world(rect, pos);
+                self.center[0] += before[0] - after[0];
+                self.center[1] += before[1] - after[1];
+            }
+
+            // Like the previews, the first coordinate runs down the view
+            let [u, v] = self.screen_to_world(rect, pos);
+            readout::show(
+                ui,
+                Id::new("explore_readout"),
+                backend,
+                expr,
+                [v, u],
+                res.clicked(),
+            );
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, Color32::BLACK);
+
+        let level = self.level();
+        let tile_len = 2f64.powi(level);
+        let [min_u, min_v] = self.screen_to_world(rect, rect.min);
+        let [max_u, max_v] = self.screen_to_world(rect, rect.max);
+        let cols = (min_u / tile_len).floor() as i64..=(max_u / tile_len).floor() as i64;
+        let rows = (min_v / tile_len).floor() as i64..=(max_v / tile_len).floor() as i64;
+        let mut missing = vec![];
+        let mut visible_max_drift = None::<f64>;
+
+        for row in rows {
+            for col in cols.clone() {
+                let key = (level, col, row);
+                let tile_rect = self.tile_rect(rect, key);
+
+                if let Some(tile) = self.tiles.get_mut(&key) {
+                    tile.used = self.frame;
+                    visible_max_drift = Some(
+                        visible_max_drift.map_or(tile.max_drift, |max| max.max(tile.max_drift)),
+                    );
+                    painter.image(
+                        tile.texture.id(),
+                        tile_rect,
+                        Rect::from_min_max(Pos2::ZERO, pos2(1.0, 1.0)),
+                        Color32::WHITE,
+                    );
+
+                    continue;
+                }
+
+                // Coarser tiles are stretched over the area until this one arrives
+                for fallback in 1..=Self::MAX_FALLBACK_LEVELS {
+                    let scale = 1 << fallback;
+                    let parent_key = (
+                        level + fallback,
+                        col.div_euclid(scale),
+                        row.div_euclid(scale),
+                    );
+                    if let Some(tile) = self.tiles.get_mut(&parent_key) {
+                        let uv_min =
+                            vec2(col.rem_euclid(scale) as f32, row.rem_euclid(scale) as f32)
+                                / scale as f32;
+                        tile.used = self.frame;
+                        painter.image(
+                            tile.texture.id(),
+                            tile_rect,
+                            Rect::from_min_size(uv_min.to_pos2(), Vec2::splat(1.0 / scale as f32)),
+                            Color32::WHITE,
+                        );
+
+                        break;
+                    }
+                }
+
+                if !self.pending.contains(&key) {
+                    let distance = tile_rect.center().distance_sq(rect.center());
+                    missing.push((distance, key));
+                }
+            }
+        }
+
+        self.visible_max_drift = visible_max_drift;
+
+        if self.grid {
+            grid::paint(&painter, rect, [min_u, min_v], [max_u, max_v], 1.0);
+        }
+
+        if self.pending.is_empty() && missing.is_empty() {
+            return;
+        }
+
+        missing.sort_unstable_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs));
+        for (_, key) in missing
+            .into_iter()
+            .take(Self::MAX_PENDING.saturating_sub(self.pending.len()))
+        {
+            self.request((self.generation, key, backend, Arc::clone(expr), self.drift));
+        }
+
+        // Rendered tiles are received on the next frames
+        ui.ctx().request_repaint();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn thread_worker(rx: Receiver<Option<TileRequest>>, tx: Sender<TileResponse>) {
+        while let Some((generation, key, backend, expr, drift)) = rx.recv().unwrap() {
+            let (pixels, max_drift) = Self::render_tile(key, backend, &expr, drift);
+            tx.send((generation, key, pixels, max_drift)).unwrap();
+        }
+    }
+
+    fn tile_rect(&self, rect: Rect, (level, col, row): TileKey) -> Rect {
+        let tile_len = 2f64.powi(level);
+        let min = [col as f64 * tile_len, row as f64 * tile_len];
+        let to_screen = |[u, v]: [f64; 2]| {
+            rect.center()
+                + vec2(
+                    ((u - self.center[0]) * self.pixels_per_unit) as f32,
+                    ((v - self.center[1]) * self.pixels_per_unit) as f32,
+                )
+        };
+
+        Rect::from_min_max(
+            to_screen(min),
+            to_screen([min[0] + tile_len, min[1] + tile_len]),
+        )
+    }
+
+    fn update_tiles(&mut self, ctx: &Context) {
+        self.frame += 1;
+
+        // On web a few tiles are rendered each frame instead
+        #[cfg(target_arch = "wasm32")]
+        for (generation, key, backend, expr, drift) in self
+            .thread_rx
+            .try_iter()
+            .flatten()
+            .take(Self::REQUESTS_PER_FRAME)
+        {
+            let (pixels, max_drift) = Self::render_tile(key, backend, &expr, drift);
+            self.thread_tx
+                .send((generation, key, pixels, max_drift))
+                .unwrap();
+        }
+
+        for (generation, key, pixels, max_drift) in self.rx.try_iter() {
+            if generation != self.generation {
+                continue;
+            }
+
+            self.pending.remove(&key);
+            self.tiles.insert(
+                key,
+                Tile {
+                    max_drift,
+                    texture: ctx.load_texture(
+                        format!("explore{key:?}"),
+                        ColorImage::from_gray([Self::TILE_SIZE; 2], &pixels),
+                        Default::default(),
+                    ),
+                    used: self.frame,
+                },
+            );
+        }
+
+        // The least recently drawn tiles are evicted
+        while self.tiles.len() > Self::MAX_TILES {
+            let key = self
+                .tiles
+                .iter()
+                .min_by_key(|(_, tile)| tile.used)
+                .map(|(key, _)| *key)
+                .unwrap();
+            self.tiles.remove(&key);
+        }
+    }
+}
+
+impl Default for Explorer {
+    fn default() -> Self {
+        let (tx, thread_rx) = unbounded();
+        let (thread_tx, rx) = unbounded();
+
+        Self {
+            node_idx: None,
+            center: [0.0; 2],
+            drift: false,
+            frame: 0,
+            generation: 0,
+            grid: false,
+            pending: Default::default(),
+            pixels_per_unit: Self::DEFAULT_PIXELS_PER_UNIT,
+            tiles: Default::default(),
+            version: None,
+            visible_max_drift: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            workers: vec![],
+
+            rx,
+            tx,
+            thread_rx,
+            thread_tx,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Explorer {
+    fn drop(&mut self) {
+        for _ in 0..self.workers.len() {
+            self.tx.send(None).unwrap();
+        }
+
+        for worker in self.workers.drain(..) {
+            worker.join().unwrap();
+        }
+    }
+}
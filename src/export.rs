@@ -0,0 +1,1496 @@
+use {
+    super::{
+        node::{Image, NoiseNode},
+        thread::Threads,
+    },
+    crossbeam_channel::{unbounded, Receiver},
+    egui_snarl::{OutPinId, Snarl},
+    noise::NoiseFn,
+    noise_graph::{noise_fns::BlueNoise, Backend, Expr},
+    png::{
+        AdaptiveFilterType, ColorType, Compression, Encoder, EncodingError, ScaledFloat,
+        SrgbRenderingIntent,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        io::{self, Write},
+        mem::size_of,
+        ops::Range,
+        sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+        thread::scope,
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    anyhow::bail,
+    egui::{Button, Context, ProgressBar, Window},
+    log::warn,
+    noise::permutationtable::{NoiseHasher, PermutationTable},
+    noise_graph::{SourceType, MAX_FRACTAL_OCTAVES},
+    png::{Decoder, Transformations},
+    std::{
+        f64::consts::FRAC_1_SQRT_2,
+        fs::{remove_file, write, File},
+        io::{BufReader, BufWriter},
+        num::NonZeroUsize,
+        path::{Path, PathBuf},
+        sync::Arc,
+        thread::{available_parallelism, spawn, JoinHandle},
+        time::Duration,
+    },
+    sysinfo::{System, SystemExt},
+};
+
+#[cfg(target_arch = "wasm32")]
+use js_sys::{Array, Uint8Array};
+
+/// The number of bits used to store each pixel of an exported image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+impl BitDepth {
+    pub const ALL: [Self; 2] = [Self::Eight, Self::Sixteen];
+
+    fn bits(self) -> u8 {
+        match self {
+            Self::Eight => 8,
+            Self::Sixteen => 16,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Eight => "8-bit",
+            Self::Sixteen => "16-bit",
+        }
+    }
+}
+
+/// How values are encoded into the pixels of an exported image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ColorSpace {
+    /// Values are written as they are, which suits data such as heightmaps and masks.
+    #[default]
+    Linear,
+
+    /// Values are treated as linear light and encoded with the sRGB transfer function, which suits
+    /// textures which are shown as color.
+    Srgb,
+}
+
+impl ColorSpace {
+    pub const ALL: [Self; 2] = [Self::Linear, Self::Srgb];
+
+    /// Encodes a normalized value in `[0, 1]`.
+    pub fn encode(self, value: f64) -> f64 {
+        match self {
+            Self::Linear => value,
+            Self::Srgb if value <= 0.0031308 => value * 12.92,
+            Self::Srgb => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::Srgb => "sRGB",
+        }
+    }
+}
+
+/// The pattern added to values before they are rounded to 8 bits, which hides banding in smooth
+/// gradients.
+///
+/// Patterns are repeated using the position of each pixel in the whole image, so that tiles of a
+/// larger image are dithered the same as the image would be.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Dither {
+    #[default]
+    None,
+
+    /// An 8 x 8 Bayer matrix, which is regular and compresses well.
+    Ordered,
+
+    /// High-pass filtered white noise, which has no visible pattern.
+    BlueNoise,
+}
+
+impl Dither {
+    pub const ALL: [Self; 3] = [Self::None, Self::Ordered, Self::BlueNoise];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Ordered => "Ordered",
+            Self::BlueNoise => "Blue Noise",
+        }
+    }
+
+    /// Returns the offset of a pixel in `[-0.5, 0.5]`, as a fraction of one 8-bit step.
+    fn offset(self, x: usize, y: usize) -> f64 {
+        match self {
+            Self::None => 0.0,
+            Self::Ordered => {
+                // Interleaving the bits of x ^ y and y in reverse order gives the Bayer matrix
+                let xor = x ^ y;
+                let mut threshold = 0;
+                for bit in 0..3 {
+                    threshold = threshold << 2 | ((xor >> bit) & 1) << 1 | (y >> bit) & 1;
+                }
+
+                (threshold as f64 + 0.5) / 64.0 - 0.5
+            }
+            Self::BlueNoise => BlueNoise::default().get([x as f64, y as f64, 0.0]) * 0.5,
+        }
+    }
+}
+
+/// A square area of noise, in the units of the `x`, `y` and `scale` of an image: it covers world
+/// coordinates from `x * scale` to `(x + 1) * scale` horizontally, and likewise from `y`
+/// vertically.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportRegion {
+    pub scale: f64,
+    pub x: f64,
+    pub y: f64,
+}
+
+impl ExportRegion {
+    /// Returns the world coordinates of the corners with the least and the greatest coordinates.
+    pub fn bounds(&self) -> [[f64; 2]; 2] {
+        [
+            [self.x * self.scale, self.y * self.scale],
+            [(self.x + 1.0) * self.scale, (self.y + 1.0) * self.scale],
+        ]
+    }
+
+    /// Returns the square with a corner at `anchor` which reaches `pos` along the axis it is
+    /// furthest along, in world coordinates, or `None` if the square has no area.
+    pub fn from_corners(anchor: [f64; 2], pos: [f64; 2]) -> Option<Self> {
+        let delta = [pos[0] - anchor[0], pos[1] - anchor[1]];
+        let scale = delta[0].abs().max(delta[1].abs());
+
+        if scale <= 0.0 || !scale.is_finite() {
+            return None;
+        }
+
+        let [x, y] = [0, 1].map(|axis| {
+            if delta[axis] < 0.0 {
+                anchor[axis] / scale - 1.0
+            } else {
+                anchor[axis] / scale
+            }
+        });
+
+        Some(Self { scale, x, y })
+    }
+}
+
+/// How dragging on the preview of an image changes its export region.
+#[derive(Clone, Copy, Debug)]
+pub enum RegionDrag {
+    /// Moves the region, keeping the offset of the pointer from its least corner.
+    Move([f64; 2]),
+
+    /// Resizes the region about a fixed corner.
+    Resize([f64; 2]),
+}
+
+/// How the samples taken within the pixels of a supersampled image are combined.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SampleFilter {
+    /// Samples within a pixel are weighted equally.
+    #[default]
+    Box,
+
+    /// Samples are weighted by their distance from the center of the pixel, including samples
+    /// within half of each neighbouring pixel, which is smoother.
+    Gaussian,
+}
+
+impl SampleFilter {
+    pub const ALL: [Self; 2] = [Self::Box, Self::Gaussian];
+
+    /// The standard deviation of the Gaussian filter, in pixels.
+    const SIGMA: f64 = 0.5;
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Box => "Box",
+            Self::Gaussian => "Gaussian",
+        }
+    }
+
+    /// Returns the distance from the center of a pixel to the edge of the square its samples are
+    /// taken from, in pixels.
+    fn radius(self) -> f64 {
+        match self {
+            Self::Box => 0.5,
+            Self::Gaussian => 1.0,
+        }
+    }
+
+    /// Returns the weight of a sample at an offset from the center of its pixel, in pixels, before
+    /// the weights of a pixel are normalized.
+    fn weight(self, [x, y]: [f64; 2]) -> f64 {
+        match self {
+            Self::Box => 1.0,
+            Self::Gaussian => (-(x * x + y * y) / (2.0 * Self::SIGMA * Self::SIGMA)).exp(),
+        }
+    }
+}
+
+/// The number of samples taken along each side of a pixel of an exported image, which smooths
+/// hard edges such as the edges of Select masks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Supersampling {
+    #[default]
+    None,
+    Two,
+    Four,
+}
+
+impl Supersampling {
+    pub const ALL: [Self; 3] = [Self::None, Self::Two, Self::Four];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Two => "2×2",
+            Self::Four => "4×4",
+        }
+    }
+
+    fn rate(self) -> usize {
+        match self {
+            Self::None => 1,
+            Self::Two => 2,
+            Self::Four => 4,
+        }
+    }
+}
+
+/// The settings used when the image of a node is exported, saved with the node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportSettings {
+    pub bit_depth: BitDepth,
+    pub color_space: ColorSpace,
+
+    /// Dithering used by 8-bit exports; 16-bit exports have steps too small to band.
+    #[serde(default)]
+    pub dither: Dither,
+
+    /// How the samples of supersampled pixels are combined.
+    #[serde(default)]
+    pub filter: SampleFilter,
+
+    /// The area exported instead of the area shown by the image, which is selected by dragging on
+    /// the preview.
+    #[serde(default)]
+    pub region: Option<ExportRegion>,
+
+    /// Whether the metadata of the image is also written to a JSON file beside it.
+    #[serde(default)]
+    pub sidecar: bool,
+
+    /// The number of pixels along each side of the exported image.
+    pub size: usize,
+
+    #[serde(default)]
+    pub supersampling: Supersampling,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            bit_depth: Default::default(),
+            color_space: Default::default(),
+            dither: Default::default(),
+            filter: Default::default(),
+            region: None,
+            sidecar: false,
+            size: Self::DEFAULT_SIZE,
+            supersampling: Default::default(),
+        }
+    }
+}
+
+impl ExportSettings {
+    pub const DEFAULT_SIZE: usize = 512;
+    pub const MAX_SIZE: usize = 16_384;
+
+    /// The number of rows of pixels rendered and written at a time.
+    const BAND_ROWS: usize = 256;
+
+    /// The number of rows of pixels rendered by a thread at a time.
+    const TILE_ROWS: usize = 16;
+
+    /// The number of bytes of compressed pixels the encoder writes to each chunk of the file.
+    const CHUNK_LEN: usize = 64 * 1024;
+
+    /// Roughly the number of bytes of the state of the compressor, which is mostly its dictionary
+    /// and hash tables.
+    const COMPRESSOR_LEN: usize = 320 * 1024;
+
+    /// The capacity of the buffer files are written through.
+    #[cfg(not(target_arch = "wasm32"))]
+    const WRITE_BUFFER_LEN: usize = 8 * 1024;
+
+    /// Sizes offered next to the size of the image, in pixels.
+    pub const PRESET_SIZES: [usize; 4] = [512, 1024, 2048, 4096];
+}
+
+impl ExportSettings {
+    /// Returns the area of noise exported from an image: the selected region, if any, or the area
+    /// the image shows.
+    pub fn exported_region(&self, image: &Image) -> ExportRegion {
+        self.region.unwrap_or(ExportRegion {
+            scale: image.scale,
+            x: image.x,
+            y: image.y,
+        })
+    }
+
+    /// Returns the most bytes an export writes, which is the size of its pixels; pixels are
+    /// compressed, so files are usually much smaller.
+    pub fn file_len(&self) -> usize {
+        (self.size * self.bytes_per_pixel() + 1) * self.size
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.bit_depth.bits() as usize / 8
+    }
+
+    /// Returns the peak number of bytes of memory used while exporting.
+    ///
+    /// Rows are rendered and written a band at a time, so this does not grow with the height of
+    /// the image: while a band is written it is held as samples and as pixels, and the encoder
+    /// holds two rows for filtering, a chunk of the file and the state of the compressor.
+    /// Browsers also hold the whole file until it is downloaded.
+    pub fn memory_len(&self) -> usize {
+        let rows = Self::BAND_ROWS.min(self.size);
+        let row_len = self.size * self.bytes_per_pixel();
+        let band_len = self.size * rows * size_of::<f64>() + row_len * rows;
+        let encoder_len = 2 * row_len + Self::CHUNK_LEN + Self::COMPRESSOR_LEN;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let res = band_len + encoder_len + Self::WRITE_BUFFER_LEN;
+
+        #[cfg(target_arch = "wasm32")]
+        let res = band_len + encoder_len + self.file_len();
+
+        res
+    }
+
+    /// Returns a writer of a grayscale PNG which is tagged with the color space the pixels were
+    /// encoded with and has the given text entries.
+    fn png_writer<W>(
+        &self,
+        writer: W,
+        text: Vec<(&'static str, String)>,
+    ) -> Result<png::Writer<W>, EncodingError>
+    where
+        W: Write,
+    {
+        let mut encoder = Encoder::new(writer, self.size as u32, self.size as u32);
+        encoder.set_color(ColorType::Grayscale);
+        encoder.set_depth(match self.bit_depth {
+            BitDepth::Eight => png::BitDepth::Eight,
+            BitDepth::Sixteen => png::BitDepth::Sixteen,
+        });
+        encoder.set_compression(Compression::Default);
+        encoder.set_adaptive_filter(AdaptiveFilterType::Adaptive);
+
+        // Readers which understand these chunks decode the pixels without guessing
+        match self.color_space {
+            ColorSpace::Linear => encoder.set_source_gamma(ScaledFloat::new(1.0)),
+            ColorSpace::Srgb => encoder.set_source_srgb(SrgbRenderingIntent::Perceptual),
+        }
+
+        for (keyword, text) in text {
+            encoder.add_itxt_chunk(keyword.to_owned(), text)?;
+        }
+
+        encoder.write_header()
+    }
+
+    /// Returns an image as the parts of a PNG file, sampled on the calling thread as browsers
+    /// cannot start threads; unlike `save_png`, no metadata is written.
+    ///
+    /// Each band of rows is handed to the browser as it is written, so the file is not held in the
+    /// memory of the application.
+    #[cfg(target_arch = "wasm32")]
+    pub fn encode_png(&self, expr: &Expr, backend: Backend, image: &Image) -> Array {
+        let mut parts = BlobParts::default();
+        let mut png = self.png_writer(&mut parts, vec![]).unwrap();
+        let mut stream = png.stream_writer_with_size(Self::CHUNK_LEN).unwrap();
+
+        for y in (0..self.size).step_by(Self::BAND_ROWS) {
+            let rows = Self::BAND_ROWS.min(self.size - y);
+            let values = self.sample(expr, backend, image, y..y + rows, 1, &Default::default());
+            stream.write_all(&self.quantize(y, &values)).unwrap();
+        }
+
+        stream.finish().unwrap();
+        png.finish().unwrap();
+
+        parts.0
+    }
+
+    /// Returns every pixel of an image as `save_png` writes them, for comparing with images which
+    /// were exported before.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render(
+        &self,
+        expr: &Expr,
+        backend: Backend,
+        image: &Image,
+        thread_count: usize,
+    ) -> Pixels {
+        let mut data = Vec::with_capacity(self.size * self.size * self.bytes_per_pixel());
+
+        for y in (0..self.size).step_by(Self::BAND_ROWS) {
+            let rows = Self::BAND_ROWS.min(self.size - y);
+            let values = self.sample(
+                expr,
+                backend,
+                image,
+                y..y + rows,
+                thread_count,
+                &Default::default(),
+            );
+            data.extend(self.quantize(y, &values));
+        }
+
+        Pixels {
+            bit_depth: self.bit_depth,
+            data,
+            size: self.size,
+        }
+    }
+
+    /// Returns the pixels of rows of an image as big-endian integers of the export bit depth,
+    /// where `y` is the first row.
+    fn quantize(&self, y: usize, values: &[f64]) -> Vec<u8> {
+        let max = match self.bit_depth {
+            BitDepth::Eight => u8::MAX as f64,
+            BitDepth::Sixteen => u16::MAX as f64,
+        };
+        let mut pixels = Vec::with_capacity(values.len() * self.bytes_per_pixel());
+
+        for (idx, &value) in values.iter().enumerate() {
+            let value = self.color_space.encode(value) * max;
+
+            match self.bit_depth {
+                BitDepth::Eight => {
+                    let offset = self.dither.offset(idx % self.size, y + idx / self.size);
+                    pixels.push((value + offset).round().clamp(0.0, max) as u8);
+                }
+                BitDepth::Sixteen => {
+                    pixels.extend_from_slice(&(value.round() as u16).to_be_bytes());
+                }
+            }
+        }
+
+        pixels
+    }
+
+    /// Samples noise over a range of rows of the exported region of an image, returning values in
+    /// `[0, 1]` by row.
+    ///
+    /// Rows are split into tiles which are rendered by any of the threads, in any order; every
+    /// tile is written to its own part of the image so the result does not depend on either. One
+    /// thread renders on the calling thread. Rendered rows are counted by `progress`, and once it
+    /// is cancelled the remaining tiles are skipped.
+    fn sample(
+        &self,
+        expr: &Expr,
+        backend: Backend,
+        image: &Image,
+        rows: Range<usize>,
+        thread_count: usize,
+        progress: &ExportProgress,
+    ) -> Vec<f64> {
+        let [min, max] = Threads::VALUE_RANGE;
+        let region = self.exported_region(image);
+        let step = 1.0 / self.size as f64;
+        let half_step = step / 2.0;
+        let offsets = self.sample_offsets();
+        let mut values = vec![0.0; self.size * rows.len()];
+
+        let (tx, rx) = unbounded();
+        for tile in values.chunks_mut(self.size * Self::TILE_ROWS).enumerate() {
+            tx.send(tile).unwrap();
+        }
+
+        drop(tx);
+
+        let render = |rx: Receiver<(usize, &mut [f64])>| {
+            let noise = expr.noise_with(backend.get());
+
+            for (tile_idx, tile) in rx {
+                if progress.cancelled.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                for (idx, value) in tile.iter_mut().enumerate() {
+                    let x = idx % self.size;
+                    let y = rows.start + tile_idx * Self::TILE_ROWS + idx / self.size;
+
+                    // Sample positions match those used by the image threads, so exports look
+                    // like previews unless they are supersampled
+                    *value = offsets
+                        .iter()
+                        .map(|&([offset_x, offset_y], weight)| {
+                            let point = [
+                                (y as f64 * step + half_step + offset_y * step + region.y)
+                                    * region.scale,
+                                (x as f64 * step + half_step + offset_x * step + region.x)
+                                    * region.scale,
+                            ];
+                            let sample = if image.tileable {
+                                Threads::sample_tileable(
+                                    &*noise,
+                                    point,
+                                    [region.y * region.scale, region.x * region.scale],
+                                    region.scale,
+                                )
+                            } else {
+                                noise.get([point[0], point[1], 0.0])
+                            };
+
+                            ((sample - min) / (max - min)).clamp(0.0, 1.0) * weight
+                        })
+                        .sum();
+                }
+
+                progress
+                    .rows
+                    .fetch_add(tile.len() / self.size, Ordering::Relaxed);
+            }
+        };
+
+        if thread_count <= 1 {
+            render(rx);
+        } else {
+            scope(|scope| {
+                for _ in 0..thread_count {
+                    let rx = rx.clone();
+                    scope.spawn(|| render(rx));
+                }
+            });
+
+            drop(rx);
+        }
+
+        values
+    }
+
+    /// Returns the offset of each sample of a pixel from its center, in pixels, with the weight of
+    /// the sample; the weights sum to one.
+    fn sample_offsets(&self) -> Vec<([f64; 2], f64)> {
+        let rate = self.supersampling.rate();
+
+        if rate == 1 {
+            return vec![([0.0, 0.0], 1.0)];
+        }
+
+        let radius = self.filter.radius();
+        let count = (2.0 * radius * rate as f64).round() as usize;
+        let offset = |idx: usize| (idx as f64 + 0.5) / rate as f64 - radius;
+        let mut offsets = (0..count)
+            .flat_map(|row| (0..count).map(move |col| [offset(col), offset(row)]))
+            .map(|offset| (offset, self.filter.weight(offset)))
+            .collect::<Vec<_>>();
+        let total_weight = offsets.iter().map(|(_, weight)| weight).sum::<f64>();
+
+        for (_, weight) in &mut offsets {
+            *weight /= total_weight;
+        }
+
+        offsets
+    }
+
+    /// Describes how pixels are sampled, for metadata.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn supersampling_text(&self) -> String {
+        match self.supersampling {
+            Supersampling::None => Supersampling::None.name().to_owned(),
+            supersampling => format!("{} {}", supersampling.name(), self.filter.name()),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Returns the settings and provenance of an image as JSON.
+    fn metadata_json(&self, image: &Image, provenance: &Provenance) -> String {
+        let parameters = provenance
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("\n    {}: {value}", json_string(name)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let [min, max] = Threads::VALUE_RANGE;
+        let region = self.exported_region(image);
+
+        let fields = [
+            ("graph_hash", json_string(&provenance.graph_hash)),
+            ("backend", json_string(provenance.backend.name())),
+            ("resolution", format!("[{0}, {0}]", self.size)),
+            (
+                "region",
+                format!(
+                    "{{ \"x\": {}, \"y\": {}, \"scale\": {} }}",
+                    json_f64(region.x),
+                    json_f64(region.y),
+                    json_f64(region.scale)
+                ),
+            ),
+            (
+                "value_range",
+                format!("[{}, {}]", json_f64(min), json_f64(max)),
+            ),
+            ("bit_depth", self.bit_depth.bits().to_string()),
+            ("color_space", json_string(self.color_space.name())),
+            ("dither", json_string(self.dither.name())),
+            ("supersampling", json_string(&self.supersampling_text())),
+            ("tileable", image.tileable.to_string()),
+            ("parameters", format!("{{{parameters}\n  }}")),
+        ]
+        .map(|(name, value)| format!("  \"{name}\": {value}"))
+        .join(",\n");
+
+        format!("{{\n{fields}\n}}\n")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Returns the settings and provenance of an image as PNG text entries.
+    fn metadata_text(&self, image: &Image, provenance: &Provenance) -> Vec<(&'static str, String)> {
+        let [min, max] = Threads::VALUE_RANGE;
+        let region = self.exported_region(image);
+        let parameters = provenance
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("{name} = {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        vec![
+            ("Graph Hash", provenance.graph_hash.clone()),
+            ("Noise Backend", provenance.backend.name().to_owned()),
+            ("Resolution", format!("{0}x{0}", self.size)),
+            (
+                "Region",
+                format!("x {}, y {}, scale {}", region.x, region.y, region.scale),
+            ),
+            (
+                "Value Range",
+                format!("[{min:?}, {max:?}] maps to black and white"),
+            ),
+            ("Bit Depth", self.bit_depth.bits().to_string()),
+            ("Color Space", self.color_space.name().to_owned()),
+            ("Dither", self.dither.name().to_owned()),
+            ("Supersampling", self.supersampling_text()),
+            ("Tileable", image.tileable.to_string()),
+            ("Parameters", parameters),
+        ]
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Renders an expression over the area shown by an image and writes it as a PNG, using up to
+    /// the given number of threads; files are identical whatever the number used.
+    ///
+    /// The export settings and the provenance of the image are written as text entries of the
+    /// PNG and, if enabled, to a JSON file of the same name. The rows written are counted by
+    /// `progress`, which may cancel the export between bands; cancelled exports leave no file.
+    pub fn save_png(
+        &self,
+        path: impl AsRef<Path>,
+        expr: &Expr,
+        image: &Image,
+        provenance: &Provenance,
+        thread_count: usize,
+        progress: &ExportProgress,
+    ) -> anyhow::Result<()> {
+        let path = path.as_ref();
+
+        if self.sidecar {
+            write(
+                path.with_extension("json"),
+                self.metadata_json(image, provenance),
+            )
+            .inspect_err(|_| warn!("Unable to write metadata"))?;
+        }
+
+        let file = File::create(path).inspect_err(|_| warn!("Unable to create image"))?;
+        let mut file = BufWriter::with_capacity(Self::WRITE_BUFFER_LEN, file);
+        let mut png = self.png_writer(&mut file, self.metadata_text(image, provenance))?;
+        let mut stream = png.stream_writer_with_size(Self::CHUNK_LEN)?;
+
+        // Rows are compressed as they are written, so the file does not depend on the threads
+        for y in (0..self.size).step_by(Self::BAND_ROWS) {
+            let rows = Self::BAND_ROWS.min(self.size - y);
+            let values = self.sample(
+                expr,
+                provenance.backend,
+                image,
+                y..y + rows,
+                thread_count,
+                progress,
+            );
+
+            // Threads stop sampling once cancelled, so the band is not finished
+            if progress.cancelled.load(Ordering::Relaxed) {
+                drop(stream);
+                drop(png);
+                drop(file);
+                remove_file(path).unwrap_or_default();
+
+                if self.sidecar {
+                    remove_file(path.with_extension("json")).unwrap_or_default();
+                }
+
+                bail!("export cancelled");
+            }
+
+            stream.write_all(&self.quantize(y, &values))?;
+        }
+
+        stream
+            .finish()
+            .and_then(|_| png.finish())
+            .inspect_err(|_| warn!("Unable to write image"))?;
+        file.flush()
+            .inspect_err(|_| warn!("Unable to write image"))?;
+
+        Ok(())
+    }
+}
+
+/// The progress of an export, shared with the thread writing it.
+#[derive(Default)]
+pub struct ExportProgress {
+    /// Set to stop the export before its next band of rows.
+    pub cancelled: AtomicBool,
+
+    /// The number of rows written.
+    pub rows: AtomicUsize,
+}
+
+/// An export writing on a thread of its own.
+#[cfg(not(target_arch = "wasm32"))]
+struct ExportJob {
+    path: PathBuf,
+    progress: Arc<ExportProgress>,
+    size: usize,
+    thread: JoinHandle<anyhow::Result<()>>,
+}
+
+/// Exports of images, which are written by threads of their own so that the interface stays
+/// responsive, with their progress shown in a window until they finish.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+pub struct Exports {
+    jobs: Vec<ExportJob>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Exports {
+    /// How often the window is redrawn while exports are written.
+    const REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Starts writing a PNG as [`ExportSettings::save_png`] does.
+    pub fn save_png(&mut self, path: PathBuf, expr: Expr, image: Image, provenance: Provenance) {
+        let progress = Arc::<ExportProgress>::default();
+        let size = image.export.size;
+        let thread = {
+            let (path, progress) = (path.clone(), Arc::clone(&progress));
+
+            spawn(move || {
+                image.export.save_png(
+                    path,
+                    &expr,
+                    &image,
+                    &provenance,
+                    available_parallelism().map_or(1, NonZeroUsize::get),
+                    &progress,
+                )
+            })
+        };
+
+        self.jobs.push(ExportJob {
+            path,
+            progress,
+            size,
+            thread,
+        });
+    }
+
+    /// Shows the progress of the exports being written, which may be cancelled.
+    pub fn show(&mut self, ctx: &Context) {
+        let mut job_idx = 0;
+        while job_idx < self.jobs.len() {
+            if self.jobs[job_idx].thread.is_finished() {
+                let job = self.jobs.remove(job_idx);
+                if let Err(err) = job.thread.join().unwrap() {
+                    warn!("Unable to export {}: {err}", job.path.display());
+                }
+            } else {
+                job_idx += 1;
+            }
+        }
+
+        if self.jobs.is_empty() {
+            return;
+        }
+
+        ctx.request_repaint_after(Self::REPAINT_INTERVAL);
+
+        Window::new("Exports")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                for job in &self.jobs {
+                    let file_name = job
+                        .path
+                        .file_name()
+                        .map(|file_name| file_name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let is_cancelled = job.progress.cancelled.load(Ordering::Relaxed);
+                    let rows = job.progress.rows.load(Ordering::Relaxed);
+
+                    ui.horizontal(|ui| {
+                        ui.label(file_name);
+
+                        if ui
+                            .add_enabled(!is_cancelled, Button::new("Cancel"))
+                            .clicked()
+                        {
+                            job.progress.cancelled.store(true, Ordering::Relaxed);
+                        }
+                    });
+                    ui.add(
+                        ProgressBar::new(rows as f32 / job.size.max(1) as f32)
+                            .show_percentage()
+                            .animate(!is_cancelled),
+                    );
+                }
+            });
+    }
+}
+
+/// Exports which are still being written when the application closes are cancelled, so that no
+/// partial files are left behind.
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Exports {
+    fn drop(&mut self) {
+        for job in &self.jobs {
+            job.progress.cancelled.store(true, Ordering::Relaxed);
+        }
+
+        for job in self.jobs.drain(..) {
+            job.thread.join().unwrap().unwrap_or_default();
+        }
+    }
+}
+
+/// A file written as the parts of a browser blob, which the browser holds instead of the memory
+/// of the application.
+#[cfg(target_arch = "wasm32")]
+struct BlobParts(Array);
+
+#[cfg(target_arch = "wasm32")]
+impl Default for BlobParts {
+    fn default() -> Self {
+        Self(Array::new())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Write for BlobParts {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.push(&Uint8Array::from(buf));
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The pixels of a grayscale image, as exported.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct Pixels {
+    pub bit_depth: BitDepth,
+
+    /// Big-endian integers of the bit depth, by row.
+    data: Vec<u8>,
+
+    /// The number of pixels along each side of the image.
+    pub size: usize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Pixels {
+    /// Returns the largest value of a pixel, which is white.
+    pub fn max(&self) -> u16 {
+        match self.bit_depth {
+            BitDepth::Eight => u8::MAX as u16,
+            BitDepth::Sixteen => u16::MAX,
+        }
+    }
+
+    /// Reads a square grayscale image, such as those written by `ExportSettings::save_png`.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut decoder = Decoder::new(BufReader::new(File::open(path)?));
+        decoder.set_transformations(Transformations::IDENTITY);
+
+        let mut reader = decoder.read_info()?;
+        let info = reader.info();
+        let size = info.width as usize;
+
+        if info.height != info.width {
+            bail!("the image is not square");
+        }
+
+        if info.color_type != ColorType::Grayscale {
+            bail!("the image is not grayscale");
+        }
+
+        let bit_depth = match info.bit_depth {
+            png::BitDepth::Eight => BitDepth::Eight,
+            png::BitDepth::Sixteen => BitDepth::Sixteen,
+            bit_depth => bail!("the image has {}-bit pixels", bit_depth as u8),
+        };
+
+        // Sixteen-bit pixels are decoded as big-endian integers, as they are stored
+        let mut data = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut data)?;
+        data.truncate(frame.buffer_size());
+
+        Ok(Self {
+            bit_depth,
+            data,
+            size,
+        })
+    }
+
+    /// Returns the value of a pixel, by row.
+    pub fn pixel(&self, idx: usize) -> u16 {
+        match self.bit_depth {
+            BitDepth::Eight => self.data[idx] as u16,
+            BitDepth::Sixteen => u16::from_be_bytes([self.data[idx * 2], self.data[idx * 2 + 1]]),
+        }
+    }
+}
+
+/// Where an exported image came from: the graph, its backend and the values of its named constants.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct Provenance {
+    /// The backend which evaluated the generators, as the same seeds differ between backends.
+    backend: Backend,
+
+    graph_hash: String,
+
+    /// The name and value of each named constant, sorted by name.
+    parameters: Vec<(String, String)>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Provenance {
+    pub fn new(snarl: &Snarl<NoiseNode>, backend: Backend) -> Self {
+        let mut parameters = snarl
+            .node_indices()
+            .filter_map(|(_, node)| match node {
+                NoiseNode::F64(node) => Some((&node.name, json_f64(node.value))),
+                NoiseNode::U32(node) => Some((&node.name, node.value.to_string())),
+                _ => None,
+            })
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, value)| (name.clone(), value))
+            .collect::<Vec<_>>();
+        parameters.sort();
+
+        Self {
+            backend,
+            graph_hash: graph_hash(snarl),
+            parameters,
+        }
+    }
+}
+
+/// Returns a hash of the nodes, parameters and connections of a graph, as 16 hexadecimal digits.
+///
+/// Moving nodes around does not change the hash, and it is the same on every platform, so that an
+/// exported file can be traced back to the graph it was exported from.
+pub fn graph_hash(snarl: &Snarl<NoiseNode>) -> String {
+    // FNV-1a, which unlike the hashers of the standard library is specified
+    fn hash(mut state: u64, data: &[u8]) -> u64 {
+        for &byte in data {
+            state = (state ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+
+        state
+    }
+
+    let mut node_indices = snarl
+        .node_indices()
+        .map(|(node_idx, _)| node_idx)
+        .collect::<Vec<_>>();
+    node_indices.sort_unstable();
+
+    let mut state = 0xcbf2_9ce4_8422_2325;
+    for node_idx in node_indices {
+        let node = ron::to_string(snarl.get_node(node_idx)).unwrap_or_default();
+        state = hash(state, &(node_idx as u64).to_le_bytes());
+        state = hash(state, node.as_bytes());
+
+        let mut remotes = snarl
+            .out_pin(OutPinId {
+                node: node_idx,
+                output: 0,
+            })
+            .remotes;
+        remotes.sort_unstable_by_key(|remote| (remote.node, remote.input));
+
+        for remote in remotes {
+            state = hash(state, &(remote.node as u64).to_le_bytes());
+            state = hash(state, &(remote.input as u64).to_le_bytes());
+        }
+    }
+
+    format!("{state:016x}")
+}
+
+/// Glyphs of the hexadecimal digits, three pixels wide and five tall, as rows of three bits from
+/// the top.
+const HEX_GLYPHS: [[u8; 5]; 16] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b010, 0b101, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b110, 0b101, 0b110],
+    [0b011, 0b100, 0b100, 0b100, 0b011],
+    [0b110, 0b101, 0b101, 0b101, 0b110],
+    [0b111, 0b100, 0b110, 0b100, 0b111],
+    [0b111, 0b100, 0b110, 0b100, 0b100],
+];
+
+/// The number of pixels along each side of a shared preview, which keeps the file small enough to
+/// paste into a chat.
+pub const SHARE_SIZE: usize = 512;
+
+/// Returns the area shown by an image as a small 8-bit PNG, with the hash of its graph stamped in
+/// white on black in the bottom right corner, so that whoever it is shared with can tell which
+/// version of the graph it shows.
+pub fn share_png(
+    expr: &Expr,
+    backend: Backend,
+    image: &Image,
+    graph_hash: &str,
+    thread_count: usize,
+) -> Vec<u8> {
+    const GLYPH_SCALE: usize = 2;
+    const MARGIN: usize = 4;
+
+    let settings = ExportSettings {
+        region: None,
+        size: SHARE_SIZE,
+        ..Default::default()
+    };
+    let values = settings.sample(
+        expr,
+        backend,
+        image,
+        0..SHARE_SIZE,
+        thread_count,
+        &Default::default(),
+    );
+    let mut pixels = settings.quantize(0, &values);
+
+    let digits = graph_hash
+        .chars()
+        .filter_map(|char| char.to_digit(16))
+        .collect::<Vec<_>>();
+    let advance = 4 * GLYPH_SCALE;
+    let width = (digits.len() * advance).saturating_sub(GLYPH_SCALE) + 2 * MARGIN;
+    let height = 5 * GLYPH_SCALE + 2 * MARGIN;
+    let [left, top] = [width, height].map(|len| SHARE_SIZE.saturating_sub(len));
+
+    for y in top..SHARE_SIZE {
+        pixels[y * SHARE_SIZE + left..(y + 1) * SHARE_SIZE].fill(0);
+    }
+
+    for (digit_idx, digit) in digits.into_iter().enumerate() {
+        for (row, bits) in HEX_GLYPHS[digit as usize].into_iter().enumerate() {
+            for col in 0..3 {
+                if bits >> (2 - col) & 1 == 0 {
+                    continue;
+                }
+
+                let x = left + MARGIN + digit_idx * advance + col * GLYPH_SCALE;
+                let y = top + MARGIN + row * GLYPH_SCALE;
+
+                for y in y..(y + GLYPH_SCALE).min(SHARE_SIZE) {
+                    for x in x..(x + GLYPH_SCALE).min(SHARE_SIZE) {
+                        pixels[y * SHARE_SIZE + x] = u8::MAX;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut png = vec![];
+    let mut writer = settings.png_writer(&mut png, vec![]).unwrap();
+    writer.write_image_data(&pixels).unwrap();
+    writer.finish().unwrap();
+
+    png
+}
+
+/// Returns the gradients which the `noise` crate picks between with the hash of a lattice point
+/// when sampling a source in 3D, which is none for sources that use the hash itself.
+#[cfg(not(target_arch = "wasm32"))]
+fn permutation_gradients(source_ty: SourceType) -> Vec<[f64; 3]> {
+    // The same literals as the `noise` crate, so that the values are identical to the bit
+    const DIAG: f64 = FRAC_1_SQRT_2;
+    const DIAG2: f64 = 0.577_350_269_189_625_8;
+
+    match source_ty {
+        // Picked by the lowest four bits of the hash, and not normalized
+        SourceType::Perlin => vec![
+            [1.0, 1.0, 0.0],
+            [-1.0, 1.0, 0.0],
+            [1.0, -1.0, 0.0],
+            [-1.0, -1.0, 0.0],
+            [1.0, 0.0, 1.0],
+            [-1.0, 0.0, 1.0],
+            [1.0, 0.0, -1.0],
+            [-1.0, 0.0, -1.0],
+            [0.0, 1.0, 1.0],
+            [0.0, -1.0, 1.0],
+            [0.0, 1.0, -1.0],
+            [0.0, -1.0, -1.0],
+            [1.0, 1.0, 0.0],
+            [-1.0, 1.0, 0.0],
+            [0.0, -1.0, 1.0],
+            [0.0, -1.0, -1.0],
+        ],
+
+        // Picked by the hash modulo 32: the 12 edges twice, then the 8 corners
+        SourceType::OpenSimplex
+        | SourceType::PerlinSurflet
+        | SourceType::Simplex
+        | SourceType::SuperSimplex => {
+            let edges = [
+                [DIAG, DIAG, 0.0],
+                [-DIAG, DIAG, 0.0],
+                [DIAG, -DIAG, 0.0],
+                [-DIAG, -DIAG, 0.0],
+                [DIAG, 0.0, DIAG],
+                [-DIAG, 0.0, DIAG],
+                [DIAG, 0.0, -DIAG],
+                [-DIAG, 0.0, -DIAG],
+                [0.0, DIAG, DIAG],
+                [0.0, -DIAG, DIAG],
+                [0.0, DIAG, -DIAG],
+                [0.0, -DIAG, -DIAG],
+            ];
+            let corners = (0..8)
+                .map(|corner| [1, 2, 4].map(|bit| if corner & bit == 0 { DIAG2 } else { -DIAG2 }));
+
+            edges.into_iter().chain(edges).chain(corners).collect()
+        }
+
+        SourceType::Value | SourceType::Worley => vec![],
+    }
+}
+
+/// Returns the source type of the generator of an expression and the seed of each permutation
+/// table the `noise` crate builds for it, with what the table is used for.
+///
+/// Only the generator itself is included, not the expressions it is built from.
+#[cfg(not(target_arch = "wasm32"))]
+fn permutation_seeds(expr: &Expr) -> Option<(SourceType, Vec<(String, u32)>)> {
+    // Octaves are seeded one after another from the seed of the fractal
+    fn octave_seeds(seed: u32, octaves: u32, label: &str) -> Vec<(String, u32)> {
+        (0..octaves.clamp(1, MAX_FRACTAL_OCTAVES))
+            .map(|octave| (format!("{label}{}", octave + 1), seed.wrapping_add(octave)))
+            .collect()
+    }
+
+    // Fractals and turbulence of Super Simplex are built from Open Simplex
+    fn fractal_source_ty(source_ty: SourceType) -> SourceType {
+        match source_ty {
+            SourceType::SuperSimplex => SourceType::OpenSimplex,
+            source_ty => source_ty,
+        }
+    }
+
+    let (source_ty, seed) = match expr {
+        Expr::BasicMulti(expr) | Expr::Billow(expr) | Expr::Fbm(expr) | Expr::HybridMulti(expr) => {
+            return Some((
+                fractal_source_ty(expr.source_ty),
+                octave_seeds(expr.seed.value(), expr.octaves.value(), "Octave "),
+            ));
+        }
+        Expr::RidgedMulti(expr) => {
+            return Some((
+                fractal_source_ty(expr.source_ty),
+                octave_seeds(expr.seed.value(), expr.octaves.value(), "Octave "),
+            ));
+        }
+        Expr::Turbulence(expr) => {
+            // Each axis is displaced by a fractal seeded one after the other
+            let seeds = ["X", "Y", "Z"]
+                .into_iter()
+                .zip(0..)
+                .flat_map(|(axis, offset)| {
+                    octave_seeds(
+                        expr.seed.value().wrapping_add(offset),
+                        expr.roughness.value(),
+                        &format!("{axis} Displacement Octave "),
+                    )
+                })
+                .collect();
+
+            return Some((fractal_source_ty(expr.source_ty), seeds));
+        }
+        Expr::OpenSimplex(seed) => (SourceType::OpenSimplex, seed.value()),
+        Expr::Perlin(seed) => (SourceType::Perlin, seed.value()),
+        Expr::PerlinSurflet(seed) => (SourceType::PerlinSurflet, seed.value()),
+        Expr::Simplex(seed) => (SourceType::Simplex, seed.value()),
+        Expr::SuperSimplex(seed) => (SourceType::SuperSimplex, seed.value()),
+        Expr::Value(seed) => (SourceType::Value, seed.value()),
+        Expr::Worley(expr) => (SourceType::Worley, expr.seed.value()),
+        _ => return None,
+    };
+
+    Some((source_ty, vec![("Source".to_owned(), seed)]))
+}
+
+/// Writes the permutation tables and gradients which the `noise` crate derives from the seeds of
+/// the generator of an expression as JSON, so that a runtime implementation of the same
+/// algorithms can use exactly the values of the preview.
+///
+/// Returns `false` without writing anything if the expression has no permutation tables.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_permutation_tables(
+    path: impl AsRef<Path>,
+    expr: &Expr,
+    provenance: &Provenance,
+) -> io::Result<bool> {
+    let Some((source_ty, seeds)) = permutation_seeds(expr) else {
+        return Ok(false);
+    };
+
+    let tables = seeds
+        .iter()
+        .map(|(label, seed)| {
+            let table = PermutationTable::new(*seed);
+            let values = (0..256)
+                .map(|idx| table.hash(&[idx]).to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!(
+                "    {{ \"use\": {}, \"seed\": {seed}, \"values\": [{values}] }}",
+                json_string(label)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    let gradients = permutation_gradients(source_ty)
+        .iter()
+        .map(|gradient| format!("    [{}]", gradient.map(json_f64).join(", ")))
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    let fields = [
+        ("graph_hash", json_string(&provenance.graph_hash)),
+        ("backend", json_string(provenance.backend.name())),
+        ("source", json_string(&format!("{source_ty:?}"))),
+        (
+            "hash",
+            json_string(
+                "The lattice point (x, y, z) hashes to values[values[values[x & 255] ^ (y & 255)] \
+                ^ (z & 255)]",
+            ),
+        ),
+        ("gradients", format!("[\n{gradients}\n  ]")),
+        ("tables", format!("[\n{tables}\n  ]")),
+    ]
+    .map(|(name, value)| format!("  \"{name}\": {value}"))
+    .join(",\n");
+
+    write(path, format!("{{\n{fields}\n}}\n"))
+        .inspect_err(|_| warn!("Unable to write permutation tables"))?;
+
+    Ok(true)
+}
+
+/// Formats a value as a JSON number, or null when it is not finite.
+#[cfg(not(target_arch = "wasm32"))]
+fn json_f64(value: f64) -> String {
+    if value.is_finite() {
+        format!("{value:?}")
+    } else {
+        "null".to_owned()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+
+    for char in value.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            char if char.is_control() => json.push_str(&format!("\\u{:04x}", char as u32)),
+            char => json.push(char),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+/// Returns the available memory of the system, where it is known.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn available_memory_len() -> Option<usize> {
+    if !System::IS_SUPPORTED {
+        return None;
+    }
+
+    let mut system = System::new();
+    system.refresh_memory();
+
+    usize::try_from(system.available_memory()).ok()
+}
+
+/// Offers a file to the user as a download, which browsers save to their downloads folder or ask
+/// where to save.
+#[cfg(target_arch = "wasm32")]
+pub fn download(name: &str, mime_ty: &str, data: &[u8]) -> Result<(), wasm_bindgen::JsValue> {
+    download_parts(name, mime_ty, &Array::of1(&Uint8Array::from(data)))
+}
+
+/// Offers a file given as the parts of a blob as a download, like [`download`].
+#[cfg(target_arch = "wasm32")]
+pub fn download_parts(
+    name: &str,
+    mime_ty: &str,
+    parts: &Array,
+) -> Result<(), wasm_bindgen::JsValue> {
+    use {
+        wasm_bindgen::JsCast,
+        web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url},
+    };
+
+    let mut options = BlobPropertyBag::new();
+    options.type_(mime_ty);
+
+    let blob = Blob::new_with_u8_array_sequence_and_options(parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let anchor = document
+        .create_element("a")?
+        .dyn_into::<HtmlAnchorElement>()?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    Url::revoke_object_url(&url)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use {
+        super::*,
+        noise_graph::{FractalExpr, Variable},
+        std::{
+            env::temp_dir,
+            fs::{read, remove_file},
+            process::id,
+        },
+    };
+
+    /// Exports the same image with different numbers of threads, including more threads than
+    /// there are tiles in a band, and checks that every file is the same; the sizes leave a short
+    /// last tile and, for the larger image, a short last band.
+    #[test]
+    fn save_png_is_identical_for_any_thread_count() {
+        let expr = Expr::Fbm(FractalExpr {
+            source_ty: SourceType::Perlin,
+            seed: Variable::Anonymous(1),
+            octaves: Variable::Anonymous(2),
+            frequency: Variable::Anonymous(1.0),
+            lacunarity: Variable::Anonymous(2.0),
+            persistence: Variable::Anonymous(0.5),
+            octave_remainder: Variable::Anonymous(0.0),
+        });
+        let provenance = Provenance::new(&Snarl::new(), Backend::default());
+        let settings = [
+            ExportSettings {
+                dither: Dither::BlueNoise,
+                size: 72,
+                supersampling: Supersampling::Two,
+                ..Default::default()
+            },
+            ExportSettings {
+                bit_depth: BitDepth::Sixteen,
+                size: 260,
+                ..Default::default()
+            },
+        ];
+
+        for (settings_idx, settings) in settings.into_iter().enumerate() {
+            for tileable in [false, true] {
+                let image = Image {
+                    tileable,
+                    ..Default::default()
+                };
+                let files = [1, 2, 3, 32].map(|thread_count| {
+                    let path = temp_dir().join(format!(
+                        "noise_gui_test_{}_{settings_idx}_{tileable}_{thread_count}.png",
+                        id()
+                    ));
+                    settings
+                        .save_png(
+                            &path,
+                            &expr,
+                            &image,
+                            &provenance,
+                            thread_count,
+                            &Default::default(),
+                        )
+                        .unwrap();
+                    let file = read(&path).unwrap();
+                    remove_file(&path).unwrap();
+
+                    file
+                });
+
+                for file in &files[1..] {
+                    assert!(*file == files[0], "{settings:?} differs between threads");
+                }
+            }
+        }
+    }
+}
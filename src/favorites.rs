@@ -0,0 +1,327 @@
+use {
+    super::{
+        node::{ConstantOpNode, NoiseNode, TransformNode},
+        view::Viewer,
+    },
+    egui::{menu, Context, Pos2, TopBottomPanel, Vec2},
+    egui_snarl::Snarl,
+    noise_graph::OpType,
+};
+
+/// A node offered by the graph menu, by menu and name.
+pub type MenuNode = (&'static str, &'static str, fn() -> NoiseNode);
+
+/// Toolbar strip of pinned node types, each of which adds a node at the center of the canvas with
+/// a single click.
+///
+/// Favorites are stored as the menu and name of each node, like `"Generators/Perlin"`, so they
+/// are kept between sessions.
+#[derive(Default)]
+pub struct Favorites {
+    pub node_names: Vec<String>,
+
+    /// The number of nodes added from the toolbar, which staggers nodes added in a row so that
+    /// they do not cover each other.
+    added: usize,
+}
+
+impl Favorites {
+    const CASCADE_OFFSET: f32 = 24.0;
+
+    const CASCADE_LEN: usize = 8;
+
+    pub const NODES: [MenuNode; 60] = [
+        ("Combiners", "Add", || NoiseNode::Add(Default::default())),
+        ("Combiners", "Min", || NoiseNode::Min(Default::default())),
+        ("Combiners", "Max", || NoiseNode::Max(Default::default())),
+        ("Combiners", "Multiply", || {
+            NoiseNode::Multiply(Default::default())
+        }),
+        (
+            "Combiners",
+            "Power",
+            || NoiseNode::Power(Default::default()),
+        ),
+        ("Combiners", "Pow Signal", || {
+            NoiseNode::PowSignal(Default::default())
+        }),
+        ("Generators", "Blue Noise", || {
+            NoiseNode::BlueNoise(Default::default())
+        }),
+        ("Generators", "Checkerboard", || {
+            NoiseNode::Checkerboard(Default::default())
+        }),
+        ("Generators", "Cylinders", || {
+            NoiseNode::Cylinders(Default::default())
+        }),
+        ("Generators", "FastNoise Lite", || {
+            NoiseNode::FastNoise(Default::default())
+        }),
+        ("Generators", "Fault", || {
+            NoiseNode::Fault(Default::default())
+        }),
+        ("Generators", "Gradient", || {
+            NoiseNode::Gradient(Default::default())
+        }),
+        ("Generators", "Heightmap", || {
+            NoiseNode::Heightmap(Default::default())
+        }),
+        ("Generators", "Open Simplex", || {
+            NoiseNode::OpenSimplex(Default::default())
+        }),
+        ("Generators", "Perlin", || {
+            NoiseNode::Perlin(Default::default())
+        }),
+        ("Generators", "Perlin Surflet", || {
+            NoiseNode::PerlinSurflet(Default::default())
+        }),
+        ("Generators", "Simplex", || {
+            NoiseNode::Simplex(Default::default())
+        }),
+        ("Generators", "Super Simplex", || {
+            NoiseNode::SuperSimplex(Default::default())
+        }),
+        ("Generators", "Value", || {
+            NoiseNode::Value(Default::default())
+        }),
+        ("Generators", "White Noise", || {
+            NoiseNode::WhiteNoise(Default::default())
+        }),
+        ("Generators", "Worley", || {
+            NoiseNode::Worley(Default::default())
+        }),
+        ("Fractals", "Basic Multi", || {
+            NoiseNode::BasicMulti(Default::default())
+        }),
+        ("Fractals", "Hybrid Multi", || {
+            NoiseNode::HybridMulti(Default::default())
+        }),
+        ("Fractals", "Rigid Multi", || {
+            NoiseNode::RigidMulti(Default::default())
+        }),
+        ("Fractals", "Billow", || {
+            NoiseNode::Billow(Default::default())
+        }),
+        ("Fractals", "fBm", || NoiseNode::Fbm(Default::default())),
+        ("Modifiers", "Abs", || NoiseNode::Abs(Default::default())),
+        ("Modifiers", "Bake", || NoiseNode::Bake(Default::default())),
+        (
+            "Modifiers",
+            "Cache",
+            || NoiseNode::Cache(Default::default()),
+        ),
+        (
+            "Modifiers",
+            "Clamp",
+            || NoiseNode::Clamp(Default::default()),
+        ),
+        (
+            "Modifiers",
+            "Curve",
+            || NoiseNode::Curve(Default::default()),
+        ),
+        ("Modifiers", "Exp", || NoiseNode::Exp(Default::default())),
+        ("Modifiers", "Exponent", || {
+            NoiseNode::Exponent(Default::default())
+        }),
+        ("Modifiers", "Log", || NoiseNode::Log(Default::default())),
+        ("Modifiers", "Negate", || {
+            NoiseNode::Negate(Default::default())
+        }),
+        ("Modifiers", "Plateau", || {
+            NoiseNode::Plateau(Default::default())
+        }),
+        ("Modifiers", "Quantize", || {
+            NoiseNode::Quantize(Default::default())
+        }),
+        (
+            "Modifiers",
+            "Ridge",
+            || NoiseNode::Ridge(Default::default()),
+        ),
+        ("Modifiers", "Scale + Bias", || {
+            NoiseNode::ScaleBias(Default::default())
+        }),
+        ("Modifiers", "Terrace", || {
+            NoiseNode::Terrace(Default::default())
+        }),
+        (
+            "Selectors",
+            "Blend",
+            || NoiseNode::Blend(Default::default()),
+        ),
+        ("Selectors", "Masked Blend", || {
+            NoiseNode::MaskedBlend(Default::default())
+        }),
+        ("Selectors", "Mix", || NoiseNode::Mix(Default::default())),
+        ("Selectors", "Select", || {
+            NoiseNode::Select(Default::default())
+        }),
+        ("Selectors", "Threshold", || {
+            NoiseNode::Threshold(Default::default())
+        }),
+        ("Transformers", "Displace", || {
+            NoiseNode::Displace(Default::default())
+        }),
+        ("Transformers", "Jitter", || {
+            NoiseNode::Jitter(Default::default())
+        }),
+        ("Transformers", "Rebase", || {
+            NoiseNode::Rebase(Default::default())
+        }),
+        ("Transformers", "Repeat", || {
+            NoiseNode::Repeat(Default::default())
+        }),
+        ("Transformers", "Rotate Point", || {
+            NoiseNode::RotatePoint(TransformNode::zero())
+        }),
+        ("Transformers", "Scale Point", || {
+            NoiseNode::ScalePoint(TransformNode::one())
+        }),
+        ("Transformers", "Translate Point", || {
+            NoiseNode::TranslatePoint(TransformNode::zero())
+        }),
+        ("Transformers", "Turbulence", || {
+            NoiseNode::Turbulence(Default::default())
+        }),
+        ("Constants", "Control Point", || {
+            NoiseNode::ControlPoint(Default::default())
+        }),
+        (
+            "Constants",
+            "Decimal",
+            || NoiseNode::F64(Default::default()),
+        ),
+        (
+            "Constants",
+            "Integer",
+            || NoiseNode::U32(Default::default()),
+        ),
+        ("Operations", "Add", || {
+            NoiseNode::Operation(ConstantOpNode::new(OpType::Add, ()))
+        }),
+        ("Operations", "Divide", || {
+            NoiseNode::Operation(ConstantOpNode::new(OpType::Divide, ()))
+        }),
+        ("Operations", "Multiply", || {
+            NoiseNode::Operation(ConstantOpNode::new(OpType::Multiply, ()))
+        }),
+        ("Operations", "Subtract", || {
+            NoiseNode::Operation(ConstantOpNode::new(OpType::Subtract, ()))
+        }),
+    ];
+
+    fn add(
+        &mut self,
+        node: NoiseNode,
+        center: Pos2,
+        snarl: &mut Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) {
+        let pos =
+            center + Vec2::splat((self.added % Self::CASCADE_LEN) as f32 * Self::CASCADE_OFFSET);
+        let has_image = node.has_image();
+        let node_idx = snarl.insert_node(pos, node);
+
+        if has_image {
+            viewer.updated_node_indices.insert(node_idx);
+        }
+
+        self.added += 1;
+    }
+
+    fn key((menu, name, _): &MenuNode) -> String {
+        format!("{menu}/{name}")
+    }
+
+    pub fn new(node_names: Vec<String>) -> Self {
+        Self {
+            node_names,
+            ..Default::default()
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, snarl: &mut Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        // Clicks borrow the favorites, so they are handled once the toolbar has been shown
+        let mut added_node = None;
+        let mut toggled_key = None;
+
+        TopBottomPanel::top("favorites").show(ctx, |ui| {
+            menu::bar(ui, |ui| {
+                ui.add_enabled_ui(!viewer.read_only, |ui| {
+                    ui.menu_button("☆", |ui| {
+                        let mut last_menu = None;
+
+                        for node in &Self::NODES {
+                            let (menu, name, _) = *node;
+
+                            if last_menu != Some(menu) {
+                                if last_menu.is_some() {
+                                    ui.separator();
+                                }
+
+                                ui.weak(menu);
+                                last_menu = Some(menu);
+                            }
+
+                            let key = Self::key(node);
+                            let mut pinned = self.node_names.contains(&key);
+
+                            if ui.checkbox(&mut pinned, name).changed() {
+                                toggled_key = Some(key);
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text("Pin node types to the toolbar");
+
+                    if self.node_names.is_empty() {
+                        ui.weak("Pin frequently used nodes here");
+                    }
+
+                    for key in &self.node_names {
+                        let Some(node) = Self::NODES.iter().find(|node| Self::key(node) == *key)
+                        else {
+                            continue;
+                        };
+
+                        let (menu, name, new_node) = *node;
+                        let response = ui.button(name).on_hover_text(format!(
+                            "Add a {name} node ({menu}) to the center of the canvas"
+                        ));
+
+                        if response.clicked() {
+                            added_node = Some(new_node);
+                        }
+
+                        response.context_menu(|ui| {
+                            if ui.button("Unpin").clicked() {
+                                toggled_key = Some(key.clone());
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                });
+            });
+        });
+
+        if let Some(key) = toggled_key {
+            if let Some(idx) = self
+                .node_names
+                .iter()
+                .position(|node_name| *node_name == key)
+            {
+                self.node_names.remove(idx);
+            } else {
+                self.node_names.push(key);
+            }
+        }
+
+        if let Some(node) = added_node {
+            // The canvas does not expose how far it has been panned, so nodes are added at the
+            // center of the area left for it, measured from the origin of the graph
+            let center = (ctx.available_rect().size() / 2.0).to_pos2();
+            self.add(node(), center, snarl, viewer);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use {
+    super::node::{NodeCategory, NoiseNode},
+    egui::{Button, ComboBox, TextEdit, Ui},
+};
+
+/// Dims the nodes of the canvas which are not of a chosen category or do not match a search, so
+/// that the others stand out in dense graphs.
+#[derive(Default)]
+pub struct CanvasFilter {
+    category: Option<NodeCategory>,
+
+    /// Matched against the titles of nodes and the names of constants, ignoring case.
+    search: String,
+}
+
+impl CanvasFilter {
+    pub fn is_active(&self) -> bool {
+        self.category.is_some() || !self.search.trim().is_empty()
+    }
+
+    /// Returns `true` if a node, which has the given title, is not dimmed.
+    pub fn matches(&self, node: &NoiseNode, title: &str) -> bool {
+        if self
+            .category
+            .is_some_and(|category| category != node.category())
+        {
+            return false;
+        }
+
+        let search = self.search.trim().to_lowercase();
+        let name = match node {
+            NoiseNode::F64(node) => Some(&node.name),
+            NoiseNode::U32(node) => Some(&node.name),
+            _ => None,
+        };
+
+        search.is_empty()
+            || title.to_lowercase().contains(&search)
+            || name.is_some_and(|name| name.to_lowercase().contains(&search))
+    }
+
+    /// Shows the filter within a right-to-left layout, with the clear button furthest right.
+    pub fn show(&mut self, ui: &mut Ui) {
+        if ui
+            .add_enabled(self.is_active(), Button::new("✖").small())
+            .on_hover_text("Clear the filter")
+            .clicked()
+        {
+            *self = Default::default();
+        }
+
+        ui.add(
+            TextEdit::singleline(&mut self.search)
+                .hint_text("Search nodes")
+                .desired_width(120.0),
+        );
+
+        ComboBox::from_id_source("canvas_filter_category")
+            .selected_text(self.category.map_or("All Nodes", NodeCategory::name))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.category, None, "All Nodes");
+
+                for category in NodeCategory::ALL {
+                    ui.selectable_value(&mut self.category, Some(category), category.name());
+                }
+            })
+            .response
+            .on_hover_text("Dim the nodes of other categories");
+    }
+}
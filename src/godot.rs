@@ -0,0 +1,235 @@
+use {
+    log::warn,
+    noise_graph::{
+        CellularDistanceFunction, CellularReturnType, DistanceFunction, Expr, FastFractalType,
+        FastNoiseType, FastRotationType, FractalExpr, ReturnType, SourceType,
+    },
+    std::{fmt::Write, fs::write, io, path::Path},
+};
+
+/// The properties of a Godot 4 `FastNoiseLite` resource, along with what could not be carried
+/// over from the expression.
+pub struct GodotNoise {
+    properties: Vec<(&'static str, String)>,
+
+    /// Differences between the resource and the expression, which are shown after exporting.
+    pub warnings: Vec<String>,
+}
+
+impl GodotNoise {
+    /// Maps the generator of an expression onto a `FastNoiseLite` resource, or returns why it
+    /// cannot be.
+    ///
+    /// Only FastNoise Lite nodes are reproduced exactly; the generators and fractals of the
+    /// `noise` crate are mapped onto their closest FastNoise Lite settings, with a warning.
+    pub fn new(expr: &Expr) -> Result<Self, String> {
+        let mut res = Self {
+            properties: vec![],
+            warnings: vec![],
+        };
+
+        match expr {
+            Expr::FastNoise(expr) => {
+                res.noise_type(match expr.noise_ty {
+                    FastNoiseType::Cellular => 2,
+                    FastNoiseType::OpenSimplex2 => 0,
+                    FastNoiseType::OpenSimplex2S => 1,
+                    FastNoiseType::Perlin => 3,
+                    FastNoiseType::Value => 5,
+                    FastNoiseType::ValueCubic => 4,
+                });
+                res.int("seed", expr.seed.value() as i32 as _);
+                res.float("frequency", expr.frequency.value());
+                res.int(
+                    "fractal_type",
+                    match expr.fractal_ty {
+                        FastFractalType::None => 0,
+                        FastFractalType::Fbm => 1,
+                        FastFractalType::Ridged => 2,
+                        FastFractalType::PingPong => 3,
+                    },
+                );
+                res.int("fractal_octaves", expr.octaves.value() as _);
+                res.float("fractal_lacunarity", expr.lacunarity.value());
+                res.float("fractal_gain", expr.gain.value());
+                res.float("fractal_weighted_strength", expr.weighted_strength.value());
+                res.float(
+                    "fractal_ping_pong_strength",
+                    expr.ping_pong_strength.value(),
+                );
+                res.int(
+                    "cellular_distance_function",
+                    match expr.cellular_distance_fn {
+                        CellularDistanceFunction::Euclidean => 0,
+                        CellularDistanceFunction::EuclideanSq => 1,
+                        CellularDistanceFunction::Manhattan => 2,
+                        CellularDistanceFunction::Hybrid => 3,
+                    },
+                );
+                res.float("cellular_jitter", expr.cellular_jitter.value());
+                res.int(
+                    "cellular_return_type",
+                    match expr.cellular_return_ty {
+                        CellularReturnType::CellValue => 0,
+                        CellularReturnType::Distance => 1,
+                        CellularReturnType::Distance2 => 2,
+                        CellularReturnType::Distance2Add => 3,
+                        CellularReturnType::Distance2Sub => 4,
+                        CellularReturnType::Distance2Mul => 5,
+                        CellularReturnType::Distance2Div => 6,
+                    },
+                );
+
+                if expr.rotation_ty != FastRotationType::None {
+                    res.warnings.push(format!(
+                        "Godot does not expose the {:?} rotation, so 3D noise is not rotated",
+                        expr.rotation_ty
+                    ));
+                }
+
+                if expr.is_3d {
+                    res.warnings.push(
+                        "The node samples 3D noise, which Godot only does through get_noise_3d; \
+                        noise textures sample 2D noise"
+                            .to_owned(),
+                    );
+                }
+            }
+            Expr::BasicMulti(expr) => res.fractal(expr, Some("Basic Multi")),
+            Expr::Billow(expr) => res.fractal(expr, Some("Billow")),
+            Expr::Fbm(expr) => res.fractal(expr, None),
+            Expr::HybridMulti(expr) => res.fractal(expr, Some("Hybrid Multi")),
+            Expr::RidgedMulti(expr) => {
+                res.source_type(expr.source_ty);
+                res.int("seed", expr.seed.value() as i32 as _);
+                res.float("frequency", expr.frequency.value());
+                res.int("fractal_type", 2);
+                res.int("fractal_octaves", expr.octaves.value() as _);
+                res.float("fractal_lacunarity", expr.lacunarity.value());
+                res.float("fractal_gain", expr.persistence.value());
+                res.warnings
+                    .push("Ridged fractals in Godot have no attenuation".to_owned());
+            }
+            Expr::OpenSimplex(seed) => res.generator(SourceType::OpenSimplex, seed.value()),
+
+            // Godot has nowhere to keep the annotation, which does not change the values
+            Expr::OutputRange(expr) => return Self::new(&expr.source),
+
+            Expr::Perlin(seed) => res.generator(SourceType::Perlin, seed.value()),
+            Expr::PerlinSurflet(seed) => res.generator(SourceType::PerlinSurflet, seed.value()),
+            Expr::Simplex(seed) => res.generator(SourceType::Simplex, seed.value()),
+            Expr::SuperSimplex(seed) => res.generator(SourceType::SuperSimplex, seed.value()),
+            Expr::Value(seed) => res.generator(SourceType::Value, seed.value()),
+            Expr::Worley(expr) => {
+                res.noise_type(2);
+                res.int("seed", expr.seed.value() as i32 as _);
+                res.float("frequency", expr.frequency.value());
+                res.int(
+                    "cellular_distance_function",
+                    match expr.distance_fn {
+                        DistanceFunction::Chebyshev | DistanceFunction::Euclidean => 0,
+                        DistanceFunction::EuclideanSquared => 1,
+                        DistanceFunction::Manhattan => 2,
+                    },
+                );
+                res.int(
+                    "cellular_return_type",
+                    match expr.return_ty {
+                        ReturnType::Distance => 1,
+                        ReturnType::Value => 0,
+                    },
+                );
+                if expr.distance_fn == DistanceFunction::Chebyshev {
+                    res.warnings
+                        .push("Godot has no Chebyshev distance, so Euclidean is used".to_owned());
+                }
+
+                res.warnings.push(
+                    "Worley noise is approximated by cellular noise, which places its points \
+                    differently"
+                        .to_owned(),
+                );
+            }
+            _ => {
+                return Err(
+                    "Only generators and fractals can be represented by FastNoiseLite; combine \
+                    the exported resources of each generator in Godot instead"
+                        .to_owned(),
+                )
+            }
+        }
+
+        Ok(res)
+    }
+
+    fn float(&mut self, name: &'static str, value: f64) {
+        self.properties.push((name, format!("{value:?}")));
+    }
+
+    /// Exports a fractal as fBm, naming the fractal when it is not one.
+    fn fractal(&mut self, expr: &FractalExpr, unsupported_name: Option<&str>) {
+        self.source_type(expr.source_ty);
+        self.int("seed", expr.seed.value() as i32 as _);
+        self.float("frequency", expr.frequency.value());
+        self.int("fractal_type", 1);
+        self.int("fractal_octaves", expr.octaves.value() as _);
+        self.float("fractal_lacunarity", expr.lacunarity.value());
+        self.float("fractal_gain", expr.persistence.value());
+
+        if let Some(name) = unsupported_name {
+            self.warnings.push(format!(
+                "Godot has no {name} fractal, so it is exported as fBm"
+            ));
+        }
+    }
+
+    fn generator(&mut self, source_ty: SourceType, seed: u32) {
+        self.source_type(source_ty);
+        self.int("seed", seed as i32 as _);
+        self.float("frequency", 1.0);
+        self.int("fractal_type", 0);
+    }
+
+    fn int(&mut self, name: &'static str, value: i64) {
+        self.properties.push((name, value.to_string()));
+    }
+
+    fn noise_type(&mut self, noise_ty: i64) {
+        self.int("noise_type", noise_ty);
+    }
+
+    /// Maps a source of the `noise` crate onto the closest noise type of FastNoise Lite, which
+    /// produces similar noise from different values.
+    fn source_type(&mut self, source_ty: SourceType) {
+        let (noise_ty, name) = match source_ty {
+            SourceType::OpenSimplex | SourceType::Simplex => (0, "Simplex"),
+            SourceType::Perlin | SourceType::PerlinSurflet => (3, "Perlin"),
+            SourceType::SuperSimplex => (1, "Simplex Smooth"),
+            SourceType::Value => (5, "Value"),
+            SourceType::Worley => (2, "Cellular"),
+        };
+
+        self.noise_type(noise_ty);
+        self.warnings.push(format!(
+            "{source_ty:?} noise is approximated by {name} noise, so the same seed produces a \
+            different pattern"
+        ));
+    }
+
+    /// Writes the resource as a `.tres` file, with the warnings as comments.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut tres = "[gd_resource type=\"FastNoiseLite\" format=3]\n\n".to_owned();
+
+        tres.push_str("; Exported by noise_gui\n");
+        for warning in &self.warnings {
+            writeln!(tres, "; Warning: {warning}").unwrap();
+        }
+
+        tres.push_str("\n[resource]\n");
+        for (name, value) in &self.properties {
+            writeln!(tres, "{name} = {value}").unwrap();
+        }
+
+        write(path, tres).inspect_err(|_| warn!("Unable to write Godot resource"))
+    }
+}
@@ -0,0 +1,232 @@
+use {
+    super::{
+        app::App,
+        batch,
+        export::{ExportSettings, Pixels, Provenance},
+        node::NodeId,
+    },
+    anyhow::{bail, Context},
+    std::{
+        collections::HashSet,
+        fs::{create_dir_all, read_dir},
+        num::NonZeroUsize,
+        path::PathBuf,
+        thread::available_parallelism,
+        time::Instant,
+    },
+};
+
+/// The arguments of the `verify` subcommand.
+pub struct Options {
+    /// The directory of golden images, named by node identifier.
+    golden: PathBuf,
+
+    project: PathBuf,
+
+    /// The largest difference allowed between a pixel and its golden, as a fraction of white;
+    /// when not given, pixels may differ by one step of their bit depth.
+    tolerance: Option<f64>,
+
+    /// Whether the goldens are written instead of compared.
+    update: bool,
+}
+
+impl Options {
+    pub const USAGE: &'static str =
+        "Usage: noise_gui verify PROJECT --golden DIR [--tolerance FRACTION] [--update]";
+
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Option<Self> {
+        let mut golden = None;
+        let mut project = None;
+        let mut tolerance = None;
+        let mut update = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--golden" => golden = Some(PathBuf::from(args.next()?)),
+                "--tolerance" => {
+                    let tolerance_arg = args.next()?.parse::<f64>().ok();
+                    tolerance = Some(tolerance_arg.filter(|value| (0.0..=1.0).contains(value))?);
+                }
+                "--update" => update = true,
+                _ if project.is_none() && !arg.starts_with('-') => project = Some(arg.into()),
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            golden: golden?,
+            project: project?,
+            tolerance,
+            update,
+        })
+    }
+}
+
+/// Compares the pixels an output renders with its golden, returning a description of the
+/// differences if none of them are larger than the tolerance.
+fn compare(golden: &Pixels, pixels: &Pixels, tolerance: Option<f64>) -> anyhow::Result<String> {
+    if golden.size != pixels.size || golden.bit_depth != pixels.bit_depth {
+        bail!(
+            "the golden is {0}×{0} {1} but the node exports {2}×{2} {3}",
+            golden.size,
+            golden.bit_depth.name(),
+            pixels.size,
+            pixels.bit_depth.name()
+        );
+    }
+
+    // Fractions of white are compared as whole steps so that rounding does not reject a step
+    let max_step = tolerance.map_or(1, |tolerance| {
+        (tolerance * pixels.max() as f64 + 1e-9).floor() as u16
+    });
+    let pixel_count = pixels.size * pixels.size;
+    let mut max_diff = 0;
+    let mut mismatched_pixels = 0;
+
+    for idx in 0..pixel_count {
+        let diff = golden.pixel(idx).abs_diff(pixels.pixel(idx));
+        max_diff = max_diff.max(diff);
+
+        if diff > max_step {
+            mismatched_pixels += 1;
+        }
+    }
+
+    let max_diff_fraction = max_diff as f64 / pixels.max() as f64;
+
+    if mismatched_pixels > 0 {
+        bail!(
+            "{mismatched_pixels} of {pixel_count} pixels differ by more than {max_step} steps, \
+            by up to {max_diff} ({max_diff_fraction:.4})"
+        );
+    }
+
+    Ok(if max_diff == 0 {
+        "identical".to_owned()
+    } else {
+        format!("within tolerance, by up to {max_diff} steps ({max_diff_fraction:.4})")
+    })
+}
+
+/// Renders every output of a project and compares it with its golden, printing the result of each,
+/// and returns `false` if any output differs by more than the tolerance or could not be compared.
+///
+/// Goldens are named by the identifier of their node, such as `4f1c26b0a9e3d781.png`, and are
+/// rendered with the export settings saved with the node. When updating, the goldens are written
+/// instead.
+pub fn verify(options: &Options) -> bool {
+    let project = match App::open(&options.project) {
+        Ok(project) => project,
+        Err(err) => {
+            eprintln!("Unable to open {}: {err:#}", options.project.display());
+
+            return false;
+        }
+    };
+
+    let outputs = batch::outputs(&project.snarl);
+    if outputs.is_empty() {
+        eprintln!("{} has no outputs", options.project.display());
+
+        return false;
+    }
+
+    if options.update {
+        if let Err(err) = create_dir_all(&options.golden) {
+            eprintln!("Unable to create {}: {err}", options.golden.display());
+
+            return false;
+        }
+    }
+
+    let start = Instant::now();
+    let thread_count = available_parallelism().map(NonZeroUsize::get).unwrap_or(1);
+    let provenance = Provenance::new(&project.snarl, project.backend);
+    let mut failures = 0;
+    let mut ids = HashSet::new();
+
+    for &node_idx in &outputs {
+        // Graph indices change as nodes are removed, so goldens are found by identifier instead
+        let Some(id) = project.node_ids.id(node_idx) else {
+            eprintln!("Output {node_idx} has no identifier; save the project to assign one");
+            failures += 1;
+            continue;
+        };
+
+        ids.insert(id);
+
+        let node = project.snarl.get_node(node_idx);
+        let image = node.image().unwrap();
+        let expr = node.expr(node_idx, &project.snarl);
+        let path = options.golden.join(format!("{id}.png"));
+        let settings = ExportSettings {
+            sidecar: false,
+            ..image.export.clone()
+        };
+
+        let res = if options.update {
+            settings
+                .save_png(
+                    &path,
+                    &expr,
+                    image,
+                    &provenance,
+                    thread_count,
+                    &Default::default(),
+                )
+                .map(|_| "updated".to_owned())
+        } else {
+            Pixels::open(&path)
+                .with_context(|| format!("unable to read golden {}", path.display()))
+                .and_then(|golden| {
+                    let pixels = settings.render(&expr, project.backend, image, thread_count);
+
+                    compare(&golden, &pixels, options.tolerance)
+                })
+        };
+
+        match res {
+            Ok(description) => println!("{id}: {description}"),
+            Err(err) => {
+                eprintln!("{id}: {err:#}");
+                failures += 1;
+            }
+        }
+    }
+
+    // Goldens of removed outputs are reported but do not fail, as the directory may be shared
+    if let Ok(entries) = read_dir(&options.golden) {
+        for path in entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+        {
+            let id = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".png"))
+                .and_then(|id| id.parse::<NodeId>().ok());
+
+            if id.is_some_and(|id| !ids.contains(&id)) {
+                eprintln!(
+                    "Warning: {} is not the golden of any output",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    eprintln!(
+        "{} {} of {} outputs in {:.1}s",
+        if options.update {
+            "Updated"
+        } else {
+            "Verified"
+        },
+        outputs.len() - failures,
+        outputs.len(),
+        start.elapsed().as_secs_f64()
+    );
+
+    failures == 0
+}
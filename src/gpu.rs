@@ -0,0 +1,586 @@
+use {
+    super::{
+        node::Image,
+        thread::{Priority, QueueStatus, SubImage, Threads},
+    },
+    crossbeam_channel::{unbounded, Receiver, Sender, TryRecvError},
+    futures_lite::future::block_on,
+    log::{info, warn},
+    noise_graph::Expr,
+    std::{
+        collections::{HashMap, HashSet, VecDeque},
+        mem::take,
+        sync::{Arc, Condvar, Mutex},
+        thread::{spawn, JoinHandle},
+        time::Duration,
+    },
+    wgpu::{
+        BindGroupDescriptor, BindGroupEntry, Buffer, BufferAsyncError, BufferDescriptor,
+        BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipeline,
+        ComputePipelineDescriptor, Device, DeviceDescriptor, ErrorFilter, Instance, Maintain,
+        MapMode, Queue, RequestAdapterOptions, ShaderModuleDescriptor, ShaderSource,
+    },
+};
+
+/// The WGSL which did not compile, shared with the GPU thread.
+type FailedWgsl = Arc<Mutex<HashSet<Arc<str>>>>;
+
+/// The queue of passes shared with the GPU thread, which waits on the condition variable for more.
+type SharedQueue = Arc<(Mutex<PassQueue>, Condvar)>;
+
+/// Renders previews with the `sample_grid` compute shader of the WGSL of their expressions, on a
+/// thread of its own so that the interface never waits for the GPU.
+///
+/// Every decimal setting of a preview is a uniform, so pipelines are compiled once for each shape
+/// of graph and dragging a value only uploads new uniforms. Like the sub-images of the threads,
+/// each preview is first rendered coarse and then in full, most important first, and the results
+/// are sent as sub-images to the same channel as those of the threads.
+///
+/// Shaders sample in single precision with the algorithms of the `noise` crate backend, so the
+/// previews match the CPU previews closely but not exactly. Previews which cannot be rendered on the
+/// GPU are rendered by the threads instead.
+pub struct GpuPreview {
+    failed: FailedWgsl,
+
+    /// The nodes whose shaders did not compile, which must be rendered by the threads.
+    failed_rx: Receiver<usize>,
+
+    queue: SharedQueue,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl GpuPreview {
+    /// Returns a renderer for the default adapter which sends its sub-images to `tx`, or `None` if
+    /// there is no adapter.
+    pub fn new(tx: Sender<SubImage>) -> Option<Self> {
+        let instance = Instance::default();
+        let Some(adapter) = block_on(instance.request_adapter(&RequestAdapterOptions::default()))
+        else {
+            info!("No GPU adapter; previews are rendered on the CPU");
+
+            return None;
+        };
+
+        let (device, queue) =
+            match block_on(adapter.request_device(&DeviceDescriptor::default(), None)) {
+                Ok(res) => res,
+                Err(err) => {
+                    warn!("Unable to open GPU device: {err}");
+
+                    return None;
+                }
+            };
+
+        info!("Rendering previews on {}", adapter.get_info().name);
+
+        let failed = FailedWgsl::default();
+        let (failed_tx, failed_rx) = unbounded();
+        let pass_queue = SharedQueue::default();
+        let worker = Worker {
+            device,
+            failed: Arc::clone(&failed),
+            failed_tx,
+            passes: vec![],
+            pipelines: Default::default(),
+            queue,
+            tx,
+        };
+        let worker = {
+            let pass_queue = Arc::clone(&pass_queue);
+            spawn(move || worker.run(&pass_queue))
+        };
+
+        Some(Self {
+            failed,
+            failed_rx,
+            queue: pass_queue,
+            worker: Some(worker),
+        })
+    }
+
+    /// Removes the waiting passes of a node, such as a node which was removed or edited.
+    pub fn cancel(&self, node_idx: usize) {
+        let mut queue = self.queue.0.lock().unwrap();
+
+        for requests in &mut queue.requests {
+            requests.retain(|request| request.node_idx != node_idx);
+        }
+    }
+
+    /// Moves the waiting passes of a node to another priority, keeping their order.
+    pub fn prioritize(&self, node_idx: usize, priority: Priority) {
+        let mut queue = self.queue.0.lock().unwrap();
+        let mut moved = VecDeque::new();
+
+        for (requests_priority, requests) in Priority::ALL.into_iter().zip(&mut queue.requests) {
+            if requests_priority != priority {
+                requests.retain(|request| {
+                    let is_moved = request.node_idx == node_idx;
+                    if is_moved {
+                        moved.push_back(request.clone());
+                    }
+
+                    !is_moved
+                });
+            }
+        }
+
+        queue.requests[priority as usize].append(&mut moved);
+    }
+
+    /// Queues the coarse and full passes of the current version of a preview, returning `false` if
+    /// the expression has no shader implementation or the preview must be sampled on the CPU.
+    pub fn render(&self, node_idx: usize, expr: &Expr, image: &Image, priority: Priority) -> bool {
+        // Shaders cannot blend the edges of tileable previews
+        if image.tileable {
+            return false;
+        }
+
+        let Ok((wgsl, params)) = expr.to_wgsl_with_all_params() else {
+            return false;
+        };
+
+        if self.failed.lock().unwrap().contains(wgsl.as_str()) {
+            return false;
+        }
+
+        // Uniform buffers are sized in multiples of sixteen bytes
+        let mut params = params
+            .into_iter()
+            .flat_map(f32::to_le_bytes)
+            .collect::<Vec<_>>();
+        params.resize(params.len().next_multiple_of(16), 0);
+
+        let request = PassRequest {
+            coarse: true,
+            density: image.density,
+            node_idx,
+            params: params.into(),
+            scale: image.scale,
+            version: image.version,
+            wgsl: wgsl.into(),
+            x: image.x,
+            y: image.y,
+        };
+        let (queue, requests_available) = &*self.queue;
+        let requests = &mut queue.lock().unwrap().requests[priority as usize];
+
+        // Coarse passes go ahead of the full passes already waiting, so that every edited preview
+        // shows before any is refined
+        let coarse_idx = requests
+            .iter()
+            .position(|request| !request.coarse)
+            .unwrap_or(requests.len());
+        requests.insert(
+            coarse_idx,
+            PassRequest {
+                coarse: true,
+                ..request.clone()
+            },
+        );
+        requests.push_back(PassRequest {
+            coarse: false,
+            ..request
+        });
+        requests_available.notify_one();
+
+        true
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        let queue = self.queue.0.lock().unwrap();
+        let mut node_indices = HashSet::new();
+        let mut res = QueueStatus::default();
+
+        for (sub_images, requests) in res.sub_images.iter_mut().zip(&queue.requests) {
+            *sub_images = requests.len() * Worker::SUB_IMAGE_COUNT;
+            node_indices.extend(requests.iter().map(|request| request.node_idx));
+        }
+
+        res.nodes = node_indices.len();
+        res.rendering = queue.rendering * Worker::SUB_IMAGE_COUNT;
+
+        res
+    }
+
+    /// Returns the nodes whose shaders did not compile since the last call, which must be sampled
+    /// on the CPU.
+    pub fn try_recv_failed(&self) -> impl Iterator<Item = usize> + '_ {
+        self.failed_rx.try_iter()
+    }
+}
+
+impl Drop for GpuPreview {
+    fn drop(&mut self) {
+        let (queue, requests_available) = &*self.queue;
+        queue.lock().unwrap().closed = true;
+        requests_available.notify_all();
+
+        if let Some(worker) = self.worker.take() {
+            worker.join().unwrap();
+        }
+    }
+}
+
+/// A pass submitted to the GPU whose samples are being copied back.
+struct Pass {
+    /// Receives the result of mapping the readback buffer, once the GPU has finished the pass.
+    mapped: Receiver<Result<(), BufferAsyncError>>,
+
+    readback: Buffer,
+    request: PassRequest,
+}
+
+/// Passes waiting for the GPU thread, in the order they are rendered within each priority.
+#[derive(Default)]
+struct PassQueue {
+    /// Set when the GPU thread should return instead of waiting for more passes.
+    closed: bool,
+
+    /// The number of passes submitted which are not read back yet.
+    rendering: usize,
+
+    requests: [VecDeque<PassRequest>; Priority::ALL.len()],
+}
+
+impl PassQueue {
+    fn pop(&mut self) -> Option<PassRequest> {
+        let res = self.requests.iter_mut().rev().find_map(VecDeque::pop_front);
+        if res.is_some() {
+            self.rendering += 1;
+        }
+
+        res
+    }
+}
+
+/// A pass over the whole preview of a version of a node waiting to be rendered.
+#[derive(Clone)]
+struct PassRequest {
+    /// Whether the pass samples the center of each sub-image only, like the coarse sub-images of
+    /// the threads.
+    coarse: bool,
+
+    density: usize,
+    node_idx: usize,
+
+    /// The `Params` uniform, padded.
+    params: Arc<[u8]>,
+
+    scale: f64,
+    version: usize,
+    wgsl: Arc<str>,
+    x: f64,
+    y: f64,
+}
+
+/// The GPU thread, which owns the device.
+struct Worker {
+    device: Device,
+    failed: FailedWgsl,
+    failed_tx: Sender<usize>,
+
+    /// The passes submitted which are not read back yet, oldest first.
+    passes: Vec<Pass>,
+
+    /// The pipeline compiled from each shader, which depends only on the shape of the graph.
+    pipelines: HashMap<Arc<str>, ComputePipeline>,
+
+    queue: Queue,
+    tx: Sender<SubImage>,
+}
+
+impl Worker {
+    /// The number of pipelines kept before the cache is cleared, as each edit of the shape of a
+    /// graph compiles new ones.
+    const MAX_PIPELINES: usize = 64;
+
+    /// The number of passes submitted before the thread waits for the oldest to be read back.
+    const MAX_PASSES: usize = 4;
+
+    /// How long the thread waits for more passes between polls of the GPU.
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+    /// The number of sub-images of a pass.
+    const SUB_IMAGE_COUNT: usize = Threads::IMAGE_COORDS as usize * Threads::IMAGE_COORDS as usize;
+
+    /// The number of invocations along each side of a workgroup of `sample_grid`.
+    const WORKGROUP_SIZE: u32 = 8;
+
+    fn buffer(&self, usage: BufferUsages, contents: &[u8]) -> Buffer {
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: contents.len() as u64,
+            usage: usage | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.queue.write_buffer(&buffer, 0, contents);
+
+        buffer
+    }
+
+    /// Compiles a shader into its pipeline, unless it is compiled already, returning `false` if it
+    /// does not compile.
+    fn compile(&mut self, wgsl: &Arc<str>) -> bool {
+        if self.pipelines.contains_key(wgsl) {
+            return true;
+        }
+
+        if self.pipelines.len() >= Self::MAX_PIPELINES {
+            self.pipelines.clear();
+        }
+
+        // Validation errors would otherwise panic, so they are caught and the node is left to the
+        // CPU
+        self.device.push_error_scope(ErrorFilter::Validation);
+
+        let module = self.device.create_shader_module(ShaderModuleDescriptor {
+            label: None,
+            source: ShaderSource::Wgsl(wgsl.as_ref().into()),
+        });
+        let pipeline = self
+            .device
+            .create_compute_pipeline(&ComputePipelineDescriptor {
+                label: None,
+                layout: None,
+                module: &module,
+                entry_point: "sample_grid",
+            });
+
+        if let Some(err) = block_on(self.device.pop_error_scope()) {
+            warn!("Unable to compile a preview shader: {err}");
+
+            return false;
+        }
+
+        self.pipelines.insert(Arc::clone(wgsl), pipeline);
+
+        true
+    }
+
+    /// Sends the sub-images of the passes whose readback buffers are mapped, returning how many
+    /// passes finished.
+    fn read_back(&mut self) -> usize {
+        let mut finished = 0;
+
+        for pass in take(&mut self.passes) {
+            match pass.mapped.try_recv() {
+                Err(TryRecvError::Empty) => self.passes.push(pass),
+                Ok(Ok(())) => {
+                    self.send(pass);
+                    finished += 1;
+                }
+                _ => finished += 1,
+            }
+        }
+
+        finished
+    }
+
+    fn run(mut self, queue: &SharedQueue) {
+        let (queue, requests_available) = &**queue;
+
+        loop {
+            // Take the most important pass, unless enough passes are on the GPU already
+            let request = {
+                let mut queue = queue.lock().unwrap();
+
+                loop {
+                    if queue.closed {
+                        return;
+                    }
+
+                    if self.passes.len() < Self::MAX_PASSES {
+                        if let Some(request) = queue.pop() {
+                            break Some(request);
+                        }
+                    }
+
+                    if !self.passes.is_empty() {
+                        break None;
+                    }
+
+                    queue = requests_available.wait(queue).unwrap();
+                }
+            };
+            let is_taken = request.is_some();
+            let is_submitted = request.is_some_and(|request| self.submit(request));
+
+            // Buffers are mapped asynchronously: the GPU is polled without blocking, so that more
+            // important passes are submitted while earlier ones finish
+            self.device.poll(Maintain::Poll);
+            let finished = self.read_back() + usize::from(is_taken && !is_submitted);
+
+            let mut queue = queue.lock().unwrap();
+            queue.rendering -= finished;
+
+            if !is_taken && finished == 0 {
+                drop(
+                    requests_available
+                        .wait_timeout(queue, Self::POLL_INTERVAL)
+                        .unwrap(),
+                );
+            }
+        }
+    }
+
+    /// Splits the samples of a pass into sub-images for the preview.
+    fn send(
+        &self,
+        Pass {
+            readback, request, ..
+        }: Pass,
+    ) {
+        let values = readback
+            .slice(..)
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|value| {
+                Threads::value_to_gray(f32::from_le_bytes(value.try_into().unwrap()) as f64)
+            })
+            .collect::<Vec<_>>();
+        readback.unmap();
+
+        let sub_image_size = Threads::IMAGE_SIZE * request.density;
+        let size = sub_image_size * Threads::IMAGE_COORDS as usize;
+
+        // Samples are laid out like the pixels of the preview: columns run along the first
+        // coordinate of the buffer
+        for row in 0..Threads::IMAGE_COORDS {
+            for col in 0..Threads::IMAGE_COORDS {
+                let coord = row * Threads::IMAGE_COORDS + col;
+                let image = if request.coarse {
+                    let value =
+                        values[col as usize * Threads::IMAGE_COORDS as usize + row as usize];
+
+                    vec![value; sub_image_size * sub_image_size]
+                } else {
+                    let [row, col] = Threads::coord_to_row_col(coord, request.density);
+
+                    (0..sub_image_size)
+                        .flat_map(|image_x| {
+                            let start = (col + image_x) * size + row;
+                            values[start..start + sub_image_size].iter().copied()
+                        })
+                        .collect()
+                };
+
+                // The channel closes before this thread when the application exits
+                self.tx
+                    .send((
+                        request.node_idx,
+                        request.version,
+                        coord,
+                        request.coarse,
+                        image,
+                    ))
+                    .unwrap_or_default();
+            }
+        }
+    }
+
+    /// Submits a pass to the GPU, returning `false` if its shader does not compile.
+    fn submit(&mut self, request: PassRequest) -> bool {
+        if !self.compile(&request.wgsl) {
+            self.failed
+                .lock()
+                .unwrap()
+                .insert(Arc::clone(&request.wgsl));
+            self.failed_tx.send(request.node_idx).unwrap_or_default();
+
+            return false;
+        }
+
+        // Coarse passes sample the center of each sub-image, as the threads do
+        let size = if request.coarse {
+            Threads::IMAGE_COORDS as usize
+        } else {
+            Threads::IMAGE_SIZE * Threads::IMAGE_COORDS as usize * request.density
+        };
+        let step = 1.0 / size as f64;
+
+        // The `Grid` uniform, placed so that samples are taken at the centers of the pixels like
+        // the threads do
+        let grid = [
+            ((step / 2.0 + request.y) * request.scale) as f32,
+            ((step / 2.0 + request.x) * request.scale) as f32,
+            (step * request.scale) as f32,
+        ]
+        .into_iter()
+        .flat_map(f32::to_le_bytes)
+        .chain((size as u32).to_le_bytes())
+        .collect::<Vec<_>>();
+
+        let values_len = (size * size * 4) as u64;
+        let values = self.device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: values_len,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: values_len,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let grid = self.buffer(BufferUsages::UNIFORM, &grid);
+        let params = (!request.params.is_empty())
+            .then(|| self.buffer(BufferUsages::UNIFORM, &request.params));
+
+        let pipeline = &self.pipelines[&request.wgsl];
+        let mut entries = vec![
+            BindGroupEntry {
+                binding: 1,
+                resource: grid.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: values.as_entire_binding(),
+            },
+        ];
+
+        if let Some(params) = &params {
+            entries.insert(
+                0,
+                BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+            );
+        }
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor::default());
+
+        {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+            let workgroups = (size as u32).div_ceil(Self::WORKGROUP_SIZE);
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(workgroups, workgroups, 1);
+        }
+
+        encoder.copy_buffer_to_buffer(&values, 0, &readback, 0, values_len);
+        self.queue.submit([encoder.finish()]);
+
+        let (tx, mapped) = unbounded();
+        readback
+            .slice(..)
+            .map_async(MapMode::Read, move |res| tx.send(res).unwrap_or_default());
+        self.passes.push(Pass {
+            mapped,
+            readback,
+            request,
+        });
+
+        true
+    }
+}
@@ -0,0 +1,148 @@
+use egui::{pos2, Align2, Color32, FontId, Painter, Pos2, Rect, Stroke};
+
+const AXIS_COLOR: Color32 = Color32::from_rgba_premultiplied(72, 120, 200, 200);
+const LABEL_BACKGROUND: Color32 = Color32::from_black_alpha(160);
+const LABEL_COLOR: Color32 = Color32::from_rgb(176, 208, 255);
+const LINE_COLOR: Color32 = Color32::from_rgba_premultiplied(40, 64, 96, 96);
+
+/// The fewest points between grid lines at a scale of one, which keeps their labels apart.
+const MIN_LINE_SPACING: f32 = 40.0;
+
+/// Formats a coordinate with as many decimals as the step between grid lines needs.
+fn format_coord(coord: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).clamp(0.0, 9.0) as usize;
+
+    format!("{coord:.decimals$}")
+}
+
+fn label(painter: &Painter, pos: Pos2, align: Align2, text: String, font_id: FontId) {
+    let galley = painter.layout_no_wrap(text, font_id, LABEL_COLOR);
+    let rect = align.anchor_rect(Rect::from_min_size(pos, galley.size()));
+    painter.rect_filled(rect.expand(1.0), 2.0, LABEL_BACKGROUND);
+    painter.galley(rect.min, galley);
+}
+
+/// Returns the smallest of 1, 2 or 5 times a power of ten which is at least `min_step`.
+fn nice_step(min_step: f64) -> f64 {
+    let magnitude = 10f64.powf(min_step.log10().floor());
+
+    [1.0, 2.0, 5.0]
+        .into_iter()
+        .map(|multiple| multiple * magnitude)
+        .find(|&step| step >= min_step)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Draws grid lines, axis labels and a ruler over `rect`, which shows the world area from `min` to
+/// `max`; like the previews, the second coordinate runs across and the first runs down, so `min`
+/// and `max` are given as `[across, down]`.
+///
+/// Grid lines are spaced by 1, 2 or 5 times a power of ten and are labelled along the top and left
+/// edges, with the lines through the origin drawn brighter. The ruler in the bottom right corner
+/// is about a quarter of the width.
+pub fn paint(painter: &Painter, rect: Rect, min: [f64; 2], max: [f64; 2], scale: f32) {
+    let units_per_point = (max[0] - min[0]) / rect.width() as f64;
+    if !units_per_point.is_finite() || units_per_point <= 0.0 {
+        return;
+    }
+
+    let painter = painter.with_clip_rect(rect.intersect(painter.clip_rect()));
+    let font_id = FontId::proportional(9.0 * scale);
+    let margin = 2.0 * scale;
+    let step = nice_step(units_per_point * (MIN_LINE_SPACING * scale) as f64);
+    let to_screen = |axis: usize, coord: f64| {
+        rect.min[axis] + ((coord - min[axis]) / (max[axis] - min[axis])) as f32 * rect.size()[axis]
+    };
+
+    for axis in 0..2 {
+        let first = (min[axis] / step).ceil() as i64;
+        let last = (max[axis] / step).floor() as i64;
+
+        for line in first..=last {
+            let coord = line as f64 * step;
+            let pos = to_screen(axis, coord);
+            let stroke = Stroke::new(scale, if line == 0 { AXIS_COLOR } else { LINE_COLOR });
+
+            if axis == 0 {
+                painter.vline(pos, rect.y_range(), stroke);
+            } else {
+                painter.hline(rect.x_range(), pos, stroke);
+            }
+        }
+
+        // Labels are drawn after the lines so that no line crosses them
+        for line in first..=last {
+            let coord = line as f64 * step;
+            let pos = to_screen(axis, coord);
+            let text = format_coord(coord, step);
+
+            if axis == 0 {
+                label(
+                    &painter,
+                    pos2(pos + margin, rect.top() + margin),
+                    Align2::LEFT_TOP,
+                    text,
+                    font_id.clone(),
+                );
+            } else {
+                label(
+                    &painter,
+                    pos2(rect.left() + margin, pos - margin),
+                    Align2::LEFT_BOTTOM,
+                    text,
+                    font_id.clone(),
+                );
+            }
+        }
+    }
+
+    // The coordinates across are the second coordinate of the noise function
+    label(
+        &painter,
+        pos2(rect.right() - margin, rect.top() + margin),
+        Align2::RIGHT_TOP,
+        "Y".to_owned(),
+        font_id.clone(),
+    );
+    label(
+        &painter,
+        pos2(rect.left() + margin, rect.bottom() - margin),
+        Align2::LEFT_BOTTOM,
+        "X".to_owned(),
+        font_id.clone(),
+    );
+
+    let ruler_len = nice_step(units_per_point * rect.width() as f64 / 4.0);
+    let ruler_width = (ruler_len / units_per_point) as f32;
+    let ruler_right = rect.right() - 2.0 * margin;
+    let ruler_bottom = rect.bottom() - 2.0 * margin;
+    let ruler_top = ruler_bottom - 4.0 * scale;
+    let ruler_stroke = Stroke::new(scale, LABEL_COLOR);
+
+    painter.rect_filled(
+        Rect::from_min_max(
+            pos2(ruler_right - ruler_width, ruler_top),
+            pos2(ruler_right, ruler_bottom),
+        )
+        .expand(margin),
+        2.0,
+        LABEL_BACKGROUND,
+    );
+    painter.hline(
+        ruler_right - ruler_width..=ruler_right,
+        ruler_bottom,
+        ruler_stroke,
+    );
+
+    for x in [ruler_right - ruler_width, ruler_right] {
+        painter.vline(x, ruler_top..=ruler_bottom, ruler_stroke);
+    }
+
+    label(
+        &painter,
+        pos2(ruler_right - ruler_width / 2.0, ruler_top - 2.0 * margin),
+        Align2::CENTER_BOTTOM,
+        format_coord(ruler_len, ruler_len),
+        font_id,
+    );
+}
@@ -0,0 +1,187 @@
+use {
+    egui::Context,
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    crossbeam_channel::{bounded, Receiver},
+    std::{sync::atomic::AtomicBool, thread::spawn},
+};
+
+#[cfg(target_arch = "wasm32")]
+use js_sys::Date;
+
+/// A computation split into steps, which runs on a thread of its own so that the interface stays
+/// responsive, and which stops after its current step once dropped.
+///
+/// On the web, where there are no threads, steps run for a few milliseconds of each frame instead.
+pub struct Job<T> {
+    /// The number of steps which have run.
+    finished_steps: Arc<AtomicUsize>,
+
+    steps: usize,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    cancelled: Arc<AtomicBool>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    rx: Receiver<T>,
+
+    /// Runs the next step, returning the result once every step has run.
+    #[cfg(target_arch = "wasm32")]
+    next_step: Box<dyn FnMut() -> Option<T>>,
+}
+
+impl<T: Send + 'static> Job<T> {
+    /// The milliseconds of each frame which web builds spend running steps.
+    #[cfg(target_arch = "wasm32")]
+    const FRAME_BUDGET_MS: f64 = 8.0;
+
+    /// How often windows are redrawn while a job runs, which shows its progress.
+    const REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Starts a job which creates its state with `init`, calls `step` with the index of each of
+    /// `steps` steps in order, and returns the result of `finish`.
+    ///
+    /// The state is created where the steps run, so it need not be sent between threads.
+    pub fn new<S: 'static>(
+        ctx: &Context,
+        steps: usize,
+        init: impl FnOnce() -> S + Send + 'static,
+        step: impl FnMut(&mut S, usize) + Send + 'static,
+        finish: impl FnOnce(S) -> T + Send + 'static,
+    ) -> Self {
+        let finished_steps = Arc::<AtomicUsize>::default();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let cancelled = Arc::<AtomicBool>::default();
+            let (tx, rx) = bounded(1);
+
+            {
+                let cancelled = Arc::clone(&cancelled);
+                let ctx = ctx.clone();
+                let finished_steps = Arc::clone(&finished_steps);
+
+                spawn(move || {
+                    let mut next_step = Self::next_step(finished_steps, steps, init, step, finish);
+
+                    while !cancelled.load(Ordering::Relaxed) {
+                        if let Some(res) = next_step() {
+                            if tx.send(res).is_ok() {
+                                ctx.request_repaint();
+                            }
+
+                            break;
+                        }
+                    }
+                });
+            }
+
+            Self {
+                finished_steps,
+                steps,
+                cancelled,
+                rx,
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            ctx.request_repaint();
+
+            Self {
+                next_step: Box::new(Self::next_step(
+                    Arc::clone(&finished_steps),
+                    steps,
+                    init,
+                    step,
+                    finish,
+                )),
+                finished_steps,
+                steps,
+            }
+        }
+    }
+
+    /// Returns a function which runs the next step, creating the state before the first step, and
+    /// returns the result once every step has run.
+    fn next_step<S>(
+        finished_steps: Arc<AtomicUsize>,
+        steps: usize,
+        init: impl FnOnce() -> S,
+        mut step: impl FnMut(&mut S, usize),
+        finish: impl FnOnce(S) -> T,
+    ) -> impl FnMut() -> Option<T> {
+        let mut init = Some(init);
+        let mut finish = Some(finish);
+        let mut state = None;
+
+        move || {
+            let step_idx = finished_steps.load(Ordering::Relaxed);
+            let mut current = state.take().unwrap_or_else(|| init.take().unwrap()());
+
+            if step_idx < steps {
+                step(&mut current, step_idx);
+                state = Some(current);
+                finished_steps.store(step_idx + 1, Ordering::Relaxed);
+
+                None
+            } else {
+                Some(finish.take().unwrap()(current))
+            }
+        }
+    }
+
+    /// Returns the result once every step has run, and until then requests the frames which show
+    /// the progress of the job.
+    pub fn poll(&mut self, ctx: &Context) -> Option<T> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let res = self.rx.try_recv().ok();
+
+        #[cfg(target_arch = "wasm32")]
+        let res = {
+            let start = Date::now();
+
+            loop {
+                if let Some(res) = (self.next_step)() {
+                    break Some(res);
+                }
+
+                if Date::now() - start >= Self::FRAME_BUDGET_MS {
+                    break None;
+                }
+            }
+        };
+
+        if res.is_none() {
+            // Web builds run the next steps on the next frame, as soon as it may be drawn
+            if cfg!(target_arch = "wasm32") {
+                ctx.request_repaint();
+            } else {
+                ctx.request_repaint_after(Self::REPAINT_INTERVAL);
+            }
+        }
+
+        res
+    }
+
+    /// Returns the fraction of the steps which have run.
+    pub fn progress(&self) -> f32 {
+        self.finished_steps.load(Ordering::Relaxed) as f32 / self.steps.max(1) as f32
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T> Drop for Job<T> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,422 @@
+use {
+    super::{
+        node::{NoiseNode, TransformNode},
+        view::Viewer,
+    },
+    egui::{pos2, Button, ComboBox, ScrollArea, Ui},
+    egui_snarl::{ui::SnarlViewer, InPinId, OutPinId, Snarl},
+};
+
+/// A change to the stack, applied after the stack has been shown because it borrows the graph.
+enum Action {
+    /// Adds a layer on top of the stack.
+    Add(fn() -> NoiseNode),
+
+    /// Connects a generator to the bottom layer, which has no source.
+    AddGenerator(usize, fn() -> NoiseNode),
+
+    /// Removes a layer, which connects the layer below it to the layer above it.
+    Remove(usize),
+
+    /// Swaps a modifier layer with the modifier layer below it.
+    Swap { lower: usize, upper: usize },
+}
+
+/// A node offered by the menus of the stack, by name.
+type MenuNode = (&'static str, fn() -> NoiseNode);
+
+/// Presents the graph leading to an output node as a stack of layers, like the layers of an image
+/// editor: a generator at the bottom and the modifiers applied to it above.
+///
+/// Layers are the nodes of the graph, so changes made here are changes to the graph and switching
+/// back to the canvas shows them.
+#[derive(Default)]
+pub struct LayerStack {
+    /// The node at the top of the stack.
+    pub node_idx: Option<usize>,
+}
+
+impl LayerStack {
+    /// The distance between layers added here, which are laid out in a row from the origin
+    /// because the graph does not expose the positions of existing nodes.
+    const COLUMN_WIDTH: f32 = 200.0;
+
+    const GENERATORS: [MenuNode; 11] = [
+        ("Billow", || NoiseNode::Billow(Default::default())),
+        ("Checkerboard", || {
+            NoiseNode::Checkerboard(Default::default())
+        }),
+        ("fBm", || NoiseNode::Fbm(Default::default())),
+        (
+            "Open Simplex",
+            || NoiseNode::OpenSimplex(Default::default()),
+        ),
+        ("Perlin", || NoiseNode::Perlin(Default::default())),
+        ("Rigid Multi", || NoiseNode::RigidMulti(Default::default())),
+        ("Simplex", || NoiseNode::Simplex(Default::default())),
+        ("Super Simplex", || {
+            NoiseNode::SuperSimplex(Default::default())
+        }),
+        ("Value", || NoiseNode::Value(Default::default())),
+        ("White Noise", || NoiseNode::WhiteNoise(Default::default())),
+        ("Worley", || NoiseNode::Worley(Default::default())),
+    ];
+
+    const MODIFIERS: [MenuNode; 14] = [
+        ("Abs", || NoiseNode::Abs(Default::default())),
+        ("Clamp", || NoiseNode::Clamp(Default::default())),
+        ("Exponent", || NoiseNode::Exponent(Default::default())),
+        ("Negate", || NoiseNode::Negate(Default::default())),
+        ("Plateau", || NoiseNode::Plateau(Default::default())),
+        ("Quantize", || NoiseNode::Quantize(Default::default())),
+        ("Repeat", || NoiseNode::Repeat(Default::default())),
+        ("Ridge", || NoiseNode::Ridge(Default::default())),
+        ("Rotate Point", || {
+            NoiseNode::RotatePoint(TransformNode::zero())
+        }),
+        ("Scale + Bias", || NoiseNode::ScaleBias(Default::default())),
+        (
+            "Scale Point",
+            || NoiseNode::ScalePoint(TransformNode::one()),
+        ),
+        ("Threshold", || NoiseNode::Threshold(Default::default())),
+        ("Translate Point", || {
+            NoiseNode::TranslatePoint(TransformNode::zero())
+        }),
+        ("Turbulence", || NoiseNode::Turbulence(Default::default())),
+    ];
+
+    fn apply(
+        &mut self,
+        action: Action,
+        layers: &[usize],
+        snarl: &mut Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) {
+        match action {
+            Action::Add(node) => {
+                let node_idx = Self::insert(layers.len(), node(), snarl, viewer);
+
+                if let Some(&top_node_idx) = layers.first() {
+                    Self::connect(top_node_idx, node_idx, snarl, viewer);
+                }
+
+                self.node_idx = Some(node_idx);
+            }
+            Action::AddGenerator(bottom_node_idx, node) => {
+                let node_idx = Self::insert(0, node(), snarl, viewer);
+                Self::connect(node_idx, bottom_node_idx, snarl, viewer);
+            }
+            Action::Remove(node_idx) => {
+                if self.node_idx == Some(node_idx) {
+                    self.node_idx = Self::source(node_idx, snarl).flatten();
+                }
+
+                let inputs = (0..viewer.inputs(snarl.get_node(node_idx)))
+                    .map(|input| {
+                        snarl.in_pin(InPinId {
+                            node: node_idx,
+                            input,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let outputs = [snarl.out_pin(OutPinId {
+                    node: node_idx,
+                    output: 0,
+                })];
+
+                viewer.remove_node(node_idx, &inputs, &outputs, snarl);
+            }
+            Action::Swap { lower, upper } => {
+                let source = Self::source(lower, snarl).flatten();
+                let outputs = snarl
+                    .out_pin(OutPinId {
+                        node: upper,
+                        output: 0,
+                    })
+                    .remotes;
+
+                // The wire between the layers is replaced first so that no step forms a cycle
+                if let Some(source) = source {
+                    Self::connect(source, upper, snarl, viewer);
+                } else {
+                    viewer.drop_inputs(
+                        &snarl.in_pin(InPinId {
+                            node: upper,
+                            input: 0,
+                        }),
+                        snarl,
+                    );
+                }
+
+                Self::connect(upper, lower, snarl, viewer);
+
+                for output in outputs {
+                    viewer.connect(
+                        &snarl.out_pin(OutPinId {
+                            node: lower,
+                            output: 0,
+                        }),
+                        &snarl.in_pin(output),
+                        snarl,
+                    );
+                }
+
+                if self.node_idx == Some(upper) {
+                    self.node_idx = Some(lower);
+                }
+            }
+        }
+    }
+
+    /// Connects the output of a node to the source of a modifier.
+    fn connect(
+        from_node_idx: usize,
+        to_node_idx: usize,
+        snarl: &mut Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) {
+        let from = snarl.out_pin(OutPinId {
+            node: from_node_idx,
+            output: 0,
+        });
+        let to = snarl.in_pin(InPinId {
+            node: to_node_idx,
+            input: 0,
+        });
+
+        viewer.connect(&from, &to, snarl);
+    }
+
+    fn insert(
+        column: usize,
+        node: NoiseNode,
+        snarl: &mut Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) -> usize {
+        let node_idx = snarl.insert_node(pos2(column as f32 * Self::COLUMN_WIDTH, 0.0), node);
+        viewer.updated_node_indices.insert(node_idx);
+
+        node_idx
+    }
+
+    /// The nodes of the stack, from the top down, following the source of each modifier.
+    fn layers(node_idx: usize, snarl: &Snarl<NoiseNode>) -> Vec<usize> {
+        let mut res = vec![node_idx];
+
+        while let Some(Some(node_idx)) = Self::source(*res.last().unwrap(), snarl) {
+            // Sources are image nodes, which are not part of a stack if they have no image
+            if !snarl.get_node(node_idx).has_image() {
+                break;
+            }
+
+            res.push(node_idx);
+        }
+
+        res
+    }
+
+    /// The image nodes which do not output to any other node, each of which tops a stack.
+    fn output_node_indices(snarl: &Snarl<NoiseNode>) -> Vec<usize> {
+        snarl
+            .node_indices()
+            .filter(|(node_idx, node)| {
+                node.has_image()
+                    && snarl
+                        .out_pin(OutPinId {
+                            node: *node_idx,
+                            output: 0,
+                        })
+                        .remotes
+                        .is_empty()
+            })
+            .map(|(node_idx, _)| node_idx)
+            .collect()
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, snarl: &mut Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        let output_node_indices = Self::output_node_indices(snarl);
+
+        // Stacks are chosen by their top node, which changes when the graph is edited elsewhere
+        if !self
+            .node_idx
+            .is_some_and(|node_idx| output_node_indices.contains(&node_idx))
+        {
+            self.node_idx = output_node_indices.first().copied();
+        }
+
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Stack");
+            ComboBox::from_id_source("layer_stack")
+                .selected_text(
+                    self.node_idx
+                        .map(|node_idx| {
+                            format!("{} #{node_idx}", viewer.title(snarl.get_node(node_idx)))
+                        })
+                        .unwrap_or_default(),
+                )
+                .show_ui(ui, |ui| {
+                    for &node_idx in &output_node_indices {
+                        let text =
+                            format!("{} #{node_idx}", viewer.title(snarl.get_node(node_idx)));
+                        ui.selectable_value(&mut self.node_idx, Some(node_idx), text);
+                    }
+                })
+                .response
+                .on_hover_text("Each node which does not output to another node tops a stack");
+
+            ui.add_enabled_ui(!viewer.read_only, |ui| {
+                ui.menu_button(
+                    if self.node_idx.is_some() {
+                        "Add Layer"
+                    } else {
+                        "Add Generator"
+                    },
+                    |ui| {
+                        let nodes = if self.node_idx.is_some() {
+                            Self::MODIFIERS.as_slice()
+                        } else {
+                            Self::GENERATORS.as_slice()
+                        };
+
+                        for &(name, node) in nodes {
+                            if ui.button(name).clicked() {
+                                action = Some(Action::Add(node));
+                                ui.close_menu();
+                            }
+                        }
+                    },
+                );
+            });
+        });
+
+        let layers = self
+            .node_idx
+            .map(|node_idx| Self::layers(node_idx, snarl))
+            .unwrap_or_default();
+
+        if layers.is_empty() {
+            ui.label("The graph has no image nodes; add a generator to start a stack");
+        }
+
+        ui.separator();
+
+        ScrollArea::vertical().show(ui, |ui| {
+            for (idx, &node_idx) in layers.iter().enumerate() {
+                let lower = layers.get(idx + 1).copied();
+                let upper = idx.checked_sub(1).map(|idx| layers[idx]);
+
+                // Only the source of a modifier is a layer; its other inputs are its parameters
+                let source = Self::source(node_idx, snarl);
+
+                ui.group(|ui| {
+                    ui.horizontal(|ui| {
+                        viewer.show_output(
+                            &snarl.out_pin(OutPinId {
+                                node: node_idx,
+                                output: 0,
+                            }),
+                            ui,
+                            1.0,
+                            snarl,
+                        );
+
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                viewer.show_header(node_idx, &[], &[], ui, 1.0, snarl);
+
+                                ui.add_enabled_ui(!viewer.read_only, |ui| {
+                                    if ui
+                                        .add_enabled(upper.is_some(), Button::new("⏶").small())
+                                        .on_hover_text("Move up")
+                                        .clicked()
+                                    {
+                                        action = upper.map(|upper| Action::Swap {
+                                            lower: node_idx,
+                                            upper,
+                                        });
+                                    }
+
+                                    // The bottom layer of a stack is its generator, which stays put
+                                    if ui
+                                        .add_enabled(
+                                            lower.is_some_and(|lower| {
+                                                Self::source(lower, snarl).is_some()
+                                            }),
+                                            Button::new("⏷").small(),
+                                        )
+                                        .on_hover_text("Move down")
+                                        .clicked()
+                                    {
+                                        action = lower.map(|lower| Action::Swap {
+                                            lower,
+                                            upper: node_idx,
+                                        });
+                                    }
+
+                                    if ui
+                                        .add(Button::new("✖").small())
+                                        .on_hover_text("Remove")
+                                        .clicked()
+                                    {
+                                        action = Some(Action::Remove(node_idx));
+                                    }
+                                });
+                            });
+
+                            let first_input = usize::from(source.is_some());
+                            for input in first_input..viewer.inputs(snarl.get_node(node_idx)) {
+                                ui.horizontal(|ui| {
+                                    viewer.show_input(
+                                        &snarl.in_pin(InPinId {
+                                            node: node_idx,
+                                            input,
+                                        }),
+                                        ui,
+                                        1.0,
+                                        snarl,
+                                    );
+                                });
+                            }
+
+                            // A modifier at the bottom of the stack is waiting for a generator
+                            if source == Some(None) {
+                                ui.add_enabled_ui(!viewer.read_only, |ui| {
+                                    ui.menu_button("Add Generator", |ui| {
+                                        for &(name, node) in &Self::GENERATORS {
+                                            if ui.button(name).clicked() {
+                                                action = Some(Action::AddGenerator(node_idx, node));
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    });
+                                });
+                            }
+                        });
+                    });
+                });
+            }
+        });
+
+        if let Some(action) = action {
+            self.apply(action, &layers, snarl, viewer);
+        }
+    }
+
+    /// The node connected to the source of a modifier, or `None` for nodes which are not
+    /// modifiers.
+    fn source(node_idx: usize, snarl: &Snarl<NoiseNode>) -> Option<Option<usize>> {
+        snarl.get_node(node_idx).has_source().then(|| {
+            snarl
+                .in_pin(InPinId {
+                    node: node_idx,
+                    input: 0,
+                })
+                .remotes
+                .first()
+                .map(|remote| remote.node)
+        })
+    }
+}
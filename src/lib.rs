@@ -1,3 +1 @@
-mod expr;
-
-pub use self::expr::*;
+pub use noise_graph::*;
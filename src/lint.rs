@@ -0,0 +1,405 @@
+use {
+    super::{
+        node::{AlgorithmVersion, ClampNode, NodeValue::Value, NoiseNode, ScaleBiasNode},
+        view::Viewer,
+    },
+    egui::{Button, Context, Id, Label, RichText, Sense, Ui, Window},
+    egui_snarl::{ui::SnarlViewer, InPinId, OutPinId, Snarl},
+    std::{collections::HashSet, mem::swap},
+};
+
+/// A one-click change which resolves a warning.
+#[derive(Clone, Copy)]
+enum Fix {
+    /// Records that the output of a node with an outdated algorithm has been checked.
+    AcceptAlgorithm(usize),
+
+    /// Gives a node a seed which no other seeded node uses.
+    Reseed(usize),
+
+    /// Sorts the control points of a Terrace node.
+    SortControlPoints(usize),
+
+    /// Swaps the lower and upper bounds of a Clamp node, along with the nodes connected to them.
+    SwapClampBounds(usize),
+
+    /// Moves a Clamp node after the Scale + Bias node it feeds, with bounds scaled to match.
+    SwapClampScaleBias { clamp: usize, scale_bias: usize },
+}
+
+struct Warning {
+    node_idx: usize,
+    message: String,
+    fix: Option<Fix>,
+}
+
+/// Collapsible window which checks the graph for common mistakes and offers fixes for them.
+#[derive(Default)]
+pub struct Linter {
+    /// The node hovered in the linter, highlighted in the graph.
+    pub highlighted_node_idx: Option<usize>,
+}
+
+impl Linter {
+    fn apply(fix: Fix, snarl: &mut Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        match fix {
+            Fix::AcceptAlgorithm(node_idx) => {
+                if let Some(algorithm) = snarl.get_node_mut(node_idx).algorithm_mut() {
+                    *algorithm = AlgorithmVersion::CURRENT;
+                }
+            }
+            Fix::Reseed(node_idx) => {
+                let used_seeds = snarl
+                    .node_indices()
+                    .filter_map(|(_, node)| node.seed_value(snarl))
+                    .collect::<HashSet<_>>();
+                let Some(Value(mut seed)) = snarl.get_node(node_idx).seed() else {
+                    return;
+                };
+
+                loop {
+                    seed = seed.wrapping_add(1);
+                    *snarl.get_node_mut(node_idx).seed_mut().unwrap() = Value(seed);
+
+                    if !used_seeds.contains(&snarl.get_node(node_idx).seed_value(snarl).unwrap()) {
+                        break;
+                    }
+                }
+
+                viewer.updated_node_indices.insert(node_idx);
+            }
+            Fix::SortControlPoints(node_idx) => {
+                NoiseNode::sort_control_points(node_idx, snarl);
+                viewer.updated_node_indices.insert(node_idx);
+            }
+            Fix::SwapClampBounds(clamp) => {
+                let sources = [1, 2].map(|input| Self::input(clamp, input, snarl));
+
+                // Disconnected bounds keep the values of their nodes, so only values are swapped
+                for (input, source) in [1, 2].into_iter().zip(sources) {
+                    if let Some(source) = source {
+                        viewer.disconnect(
+                            &snarl.out_pin(OutPinId {
+                                node: source,
+                                output: 0,
+                            }),
+                            &snarl.in_pin(InPinId { node: clamp, input }),
+                            snarl,
+                        );
+                    }
+                }
+
+                let node = snarl.get_node_mut(clamp).as_clamp_mut().unwrap();
+                swap(&mut node.lower_bound, &mut node.upper_bound);
+
+                for (input, source) in [2, 1].into_iter().zip(sources) {
+                    if let Some(source) = source {
+                        Self::connect(source, clamp, input, snarl, viewer);
+                    }
+                }
+
+                viewer.updated_node_indices.insert(clamp);
+            }
+            Fix::SwapClampScaleBias { clamp, scale_bias } => {
+                let (
+                    NoiseNode::Clamp(ClampNode {
+                        lower_bound: Value(lower_bound),
+                        upper_bound: Value(upper_bound),
+                        ..
+                    }),
+                    NoiseNode::ScaleBias(ScaleBiasNode {
+                        scale: Value(scale),
+                        bias: Value(bias),
+                        ..
+                    }),
+                ) = (snarl.get_node(clamp), snarl.get_node(scale_bias))
+                else {
+                    return;
+                };
+
+                let lower_bound = lower_bound * scale + bias;
+                let upper_bound = upper_bound * scale + bias;
+                let source = Self::input(clamp, 0, snarl);
+                let outputs = Self::outputs(scale_bias, snarl);
+
+                // Connecting a pin replaces its existing connection
+                if let Some(source) = source {
+                    Self::connect(source, scale_bias, 0, snarl, viewer);
+                } else {
+                    viewer.disconnect(
+                        &snarl.out_pin(OutPinId {
+                            node: clamp,
+                            output: 0,
+                        }),
+                        &snarl.in_pin(InPinId {
+                            node: scale_bias,
+                            input: 0,
+                        }),
+                        snarl,
+                    );
+                }
+
+                Self::connect(scale_bias, clamp, 0, snarl, viewer);
+
+                for output in outputs {
+                    Self::connect(clamp, output.node, output.input, snarl, viewer);
+                }
+
+                let node = snarl.get_node_mut(clamp).as_clamp_mut().unwrap();
+                node.lower_bound = Value(lower_bound.min(upper_bound));
+                node.upper_bound = Value(lower_bound.max(upper_bound));
+
+                viewer.updated_node_indices.insert(clamp);
+                viewer.updated_node_indices.insert(scale_bias);
+            }
+        }
+    }
+
+    fn check(snarl: &Snarl<NoiseNode>, viewer: &mut Viewer<'_>) -> Vec<Warning> {
+        let mut warnings = vec![];
+        let mut node_indices = snarl
+            .node_indices()
+            .map(|(node_idx, _)| node_idx)
+            .collect::<Vec<_>>();
+        node_indices.sort_unstable();
+
+        for &node_idx in &node_indices {
+            Self::check_seeds(node_idx, snarl, viewer, &mut warnings);
+
+            let node = snarl.get_node(node_idx);
+            if node.is_algorithm_outdated() {
+                warnings.push(Warning {
+                    node_idx,
+                    message: format!(
+                        "{} #{node_idx} was created with noise algorithm {}, but its output \
+                        changed in {}, so its seed may no longer produce the same noise",
+                        viewer.title(node),
+                        node.algorithm().unwrap(),
+                        node.algorithm_changed().unwrap(),
+                    ),
+                    fix: Some(Fix::AcceptAlgorithm(node_idx)),
+                });
+            }
+
+            match snarl.get_node(node_idx) {
+                NoiseNode::Clamp(node) if node.has_inverted_bounds(snarl) => {
+                    warnings.push(Warning {
+                        node_idx,
+                        message: format!(
+                            "Clamp #{node_idx} has a lower bound greater than its upper bound, so \
+                            its bounds are swapped when evaluated"
+                        ),
+                        fix: Some(Fix::SwapClampBounds(node_idx)),
+                    });
+                }
+                NoiseNode::ScaleBias(ScaleBiasNode {
+                    scale: Value(scale),
+                    bias: Value(_),
+                    ..
+                }) if scale.abs() > 1.0 => {
+                    let Some(clamp) = Self::input(node_idx, 0, snarl) else {
+                        continue;
+                    };
+
+                    if let NoiseNode::Clamp(ClampNode {
+                        lower_bound: Value(_),
+                        upper_bound: Value(_),
+                        ..
+                    }) = snarl.get_node(clamp)
+                    {
+                        // The clamp can only be moved when nothing else depends on its output
+                        let fix = (Self::outputs(clamp, snarl).len() == 1).then_some(
+                            Fix::SwapClampScaleBias {
+                                clamp,
+                                scale_bias: node_idx,
+                            },
+                        );
+
+                        warnings.push(Warning {
+                            node_idx,
+                            message: format!(
+                                "Scale + Bias #{node_idx} expands the range of Clamp #{clamp}, so \
+                                the clamped output no longer reaches the bounds which were set"
+                            ),
+                            fix,
+                        });
+                    }
+                }
+                NoiseNode::Terrace(node) => {
+                    let is_sorted =
+                        |values: &[f64]| values.windows(2).all(|pair| pair[0] <= pair[1]);
+                    let node_values = node
+                        .control_point_node_indices
+                        .iter()
+                        .flatten()
+                        .map(|&node_idx| snarl.get_node(node_idx).eval_f64(snarl))
+                        .collect::<Vec<_>>();
+
+                    if !is_sorted(&node_values) || !is_sorted(&node.inline_control_points) {
+                        warnings.push(Warning {
+                            node_idx,
+                            message: format!(
+                                "Terrace #{node_idx} has control points which are not in \
+                                ascending order"
+                            ),
+                            fix: Some(Fix::SortControlPoints(node_idx)),
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        warnings
+    }
+
+    /// Checks for seeded noise inputs which share a seed, and so output correlated values.
+    fn check_seeds(
+        node_idx: usize,
+        snarl: &Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+        warnings: &mut Vec<Warning>,
+    ) {
+        let node = snarl.get_node(node_idx);
+
+        // Seeded nodes with noise inputs, such as Turbulence, are compared with their inputs
+        let mut seeded_node_indices = (0..viewer.inputs(node))
+            .filter_map(|input| Self::input(node_idx, input, snarl))
+            .filter(|&node_idx| snarl.get_node(node_idx).has_image())
+            .collect::<Vec<_>>();
+
+        if !seeded_node_indices.is_empty() {
+            seeded_node_indices.push(node_idx);
+        }
+
+        seeded_node_indices.retain(|&node_idx| snarl.get_node(node_idx).seed().is_some());
+        seeded_node_indices.sort_unstable();
+        seeded_node_indices.dedup();
+
+        for (idx, &lhs) in seeded_node_indices.iter().enumerate() {
+            for &rhs in &seeded_node_indices[idx + 1..] {
+                let seed = snarl.get_node(lhs).seed_value(snarl);
+
+                if seed != snarl.get_node(rhs).seed_value(snarl) {
+                    continue;
+                }
+
+                // Seeds connected to another node are left alone, because it may be shared
+                let fix = [rhs, lhs]
+                    .into_iter()
+                    .find(|&node_idx| matches!(snarl.get_node(node_idx).seed(), Some(Value(_))))
+                    .map(Fix::Reseed);
+
+                warnings.push(Warning {
+                    node_idx,
+                    message: format!(
+                        "{} #{lhs} and {} #{rhs} both use seed {} and are combined by #{node_idx}, \
+                        so their noise is correlated",
+                        viewer.title(snarl.get_node(lhs)),
+                        viewer.title(snarl.get_node(rhs)),
+                        seed.unwrap(),
+                    ),
+                    fix,
+                });
+            }
+        }
+    }
+
+    fn connect(
+        from_node_idx: usize,
+        to_node_idx: usize,
+        input: usize,
+        snarl: &mut Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) {
+        let from = snarl.out_pin(OutPinId {
+            node: from_node_idx,
+            output: 0,
+        });
+        let to = snarl.in_pin(InPinId {
+            node: to_node_idx,
+            input,
+        });
+
+        viewer.connect(&from, &to, snarl);
+    }
+
+    fn input(node_idx: usize, input: usize, snarl: &Snarl<NoiseNode>) -> Option<usize> {
+        snarl
+            .in_pin(InPinId {
+                node: node_idx,
+                input,
+            })
+            .remotes
+            .first()
+            .map(|remote| remote.node)
+    }
+
+    fn outputs(node_idx: usize, snarl: &Snarl<NoiseNode>) -> Vec<InPinId> {
+        snarl
+            .out_pin(OutPinId {
+                node: node_idx,
+                output: 0,
+            })
+            .remotes
+    }
+
+    pub fn show(&mut self, ctx: &Context, snarl: &mut Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        self.highlighted_node_idx = None;
+
+        // Fixes are applied after the warnings have been shown because they borrow the graph
+        let mut applied_fix = None;
+
+        Window::new("Lint")
+            .id(Id::new("lint"))
+            .default_open(false)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                let warnings = Self::check(snarl, viewer);
+
+                if warnings.is_empty() {
+                    ui.label("No problems found");
+
+                    return;
+                }
+
+                for warning in warnings {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(
+                                warning.fix.is_some() && !viewer.read_only,
+                                Button::new("Fix").small(),
+                            )
+                            .on_disabled_hover_text("This must be fixed by hand")
+                            .clicked()
+                        {
+                            applied_fix = warning.fix;
+                        }
+
+                        self.show_warning(ui, &warning, viewer);
+                    });
+                }
+            });
+
+        if let Some(fix) = applied_fix {
+            Self::apply(fix, snarl, viewer);
+        }
+    }
+
+    fn show_warning(&mut self, ui: &mut Ui, warning: &Warning, viewer: &mut Viewer<'_>) {
+        let mut text = RichText::new(format!("⚠ {}", warning.message));
+
+        if *viewer.hovered_node_idx == Some(warning.node_idx) {
+            text = text
+                .background_color(ui.visuals().selection.bg_fill)
+                .color(ui.visuals().selection.stroke.color);
+        }
+
+        if ui
+            .add(Label::new(text).wrap(true).sense(Sense::hover()))
+            .hovered()
+        {
+            self.highlighted_node_idx = Some(warning.node_idx);
+        }
+    }
+}
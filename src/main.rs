@@ -5,12 +5,79 @@ mod rand {
     include!(concat!(env!("OUT_DIR"), "/rand.rs"));
 }
 
+mod aliasing;
 mod app;
-mod expr;
+mod asset;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod batch;
+
+mod bookmarks;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod canonical;
+
+mod character;
+mod chunks;
+mod clipboard;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod collab;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod crash;
+
+mod explore;
+mod export;
+mod favorites;
+mod filter;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod godot;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod golden;
+
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+mod gpu;
+
+mod grid;
+mod job;
+mod layers;
+
+mod lint;
 mod node;
+mod notes;
+mod outline;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod profiler;
+
+mod readout;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod report;
+
+mod saved_graph;
+mod seed;
+mod sphere;
+mod splice;
+mod stability;
+mod terrain;
 mod thread;
+mod tree;
+mod undo;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod verify;
+
 mod view;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod watch;
+
+mod wizard;
+
 use self::app::App;
 
 // When compiling natively:
@@ -18,17 +85,88 @@ use self::app::App;
 fn main() -> eframe::Result<()> {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
+    let mut args = std::env::args().skip(1);
+    let arg = args.next();
+
+    // `--render MANIFEST` renders the images listed by a manifest without opening a window
+    if arg.as_deref() == Some("--render") {
+        let Some(manifest) = args.next() else {
+            eprintln!("Usage: noise_gui --render MANIFEST");
+            std::process::exit(2);
+        };
+
+        std::process::exit(if batch::render(manifest) { 0 } else { 1 });
+    }
+
+    // `--relay ADDRESS` passes edits between instances which collaborate on a project
+    if arg.as_deref() == Some("--relay") {
+        let Some(address) = args.next() else {
+            eprintln!("Usage: noise_gui --relay ADDRESS");
+            std::process::exit(2);
+        };
+
+        std::process::exit(if collab::relay(&address) { 0 } else { 1 });
+    }
+
+    // `--check-snapshots SNAPSHOT...` checks that exported expressions still sample their snapshots
+    if arg.as_deref() == Some("--check-snapshots") {
+        let snapshots = args.collect::<Vec<_>>();
+        if snapshots.is_empty() {
+            eprintln!("Usage: noise_gui --check-snapshots SNAPSHOT...");
+            std::process::exit(2);
+        }
+
+        std::process::exit(if verify::check_snapshots(snapshots) {
+            0
+        } else {
+            1
+        });
+    }
+
+    // `verify PROJECT --golden DIR` renders the outputs of a project and compares them with goldens
+    if arg.as_deref() == Some("verify") {
+        let Some(options) = golden::Options::parse(&mut args) else {
+            eprintln!("{}", golden::Options::USAGE);
+            std::process::exit(2);
+        };
+
+        std::process::exit(if golden::verify(&options) { 0 } else { 1 });
+    }
+
+    // Panics save the open project so that it may be opened again on the next launch
+    crash::install();
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([400.0, 300.0])
             .with_min_inner_size([300.0, 220.0]),
         ..Default::default()
     };
-    eframe::run_native(
-        "Noise Gen",
-        native_options,
-        Box::new(|cc| Box::new(App::new(cc))),
-    )
+
+    // `--viewer [PROJECT]` opens a project read-only, for handing graphs off for review
+    let viewer = arg
+        .filter(|arg| arg == "--viewer")
+        .map(|_| args.next().map(std::path::PathBuf::from));
+
+    // The window runs on this thread, so a panic of it is reported once it has unwound
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        eframe::run_native(
+            "Noise Gen",
+            native_options,
+            Box::new(move |cc| {
+                let app = App::new(cc).recover();
+
+                Box::new(match viewer {
+                    Some(path) => app.read_only(path),
+                    None => app,
+                })
+            }),
+        )
+    }));
+
+    crash::report();
+
+    res.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
 }
 
 // When compiling to web using trunk:
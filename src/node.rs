@@ -1,17 +1,36 @@
 use {
-    super::expr::{
-        BlendExpr, ClampExpr, ControlPointExpr, CurveExpr, DisplaceExpr, DistanceFunction,
-        ExponentExpr, Expr, FractalExpr, OpType, ReturnType, RigidFractalExpr, ScaleBiasExpr,
-        SelectExpr, SourceType, TerraceExpr, TransformExpr, TurbulenceExpr, Variable, WorleyExpr,
+    super::{
+        asset::Asset,
+        export::{ExportSettings, RegionDrag},
     },
-    egui::TextureHandle,
+    egui::{ColorImage, TextureHandle},
     egui_snarl::{InPinId, OutPinId, Snarl},
     noise::{
-        BasicMulti as Fractal, Cylinders, Perlin as AnySeedable, RidgedMulti as RigidFractal,
-        Turbulence, Worley,
+        BasicMulti as Fractal, Cylinders, NoiseFn, Perlin as AnySeedable,
+        RidgedMulti as RigidFractal, Turbulence, Worley,
+    },
+    noise_graph::{
+        noise_fns::{
+            Bake, Fault, Gradient, Heightmap, Jitter, Plateau, Quantize, Rebase, Repeat, Ridge,
+            Threshold,
+        },
+        Axis, Backend, BakeExpr, BlendExpr, CacheExpr, CellularDistanceFunction,
+        CellularReturnType, Channel, ClampExpr, ControlPointExpr, CurveExpr, DerivativeExpr,
+        DisplaceExpr, DistanceFunction, ExponentExpr, Expr, FastFractalType, FastNoiseExpr,
+        FastNoiseType, FastRotationType, FaultExpr, FractalExpr, GradientExpr, HeightmapExpr,
+        JitterExpr, MixExpr, OpType, OutputRangeExpr, PlateauExpr, QuantizeExpr, RebaseExpr,
+        RepeatExpr, RepeatMode, ReturnType, RidgeExpr, RigidFractalExpr, ScaleBiasExpr, SelectExpr,
+        SourceType, TerraceExpr, ThresholdExpr, ThresholdMode, TransformExpr, TurbulenceExpr,
+        Variable, WorleyExpr, WrapMode,
     },
     serde::{Deserialize, Serialize},
-    std::{cell::RefCell, collections::HashSet},
+    std::{
+        cell::RefCell,
+        collections::{hash_map::RandomState, BTreeMap, HashMap, HashSet},
+        hash::BuildHasher,
+        ops::RangeInclusive,
+        sync::atomic::{AtomicU64, Ordering},
+    },
 };
 
 fn constant(value: f64) -> Box<Expr> {
@@ -64,6 +83,48 @@ where
     remotes.first().map(|remote| f(remote.node))
 }
 
+/// Returns the seed of a node, which is combined with the master seed as `master ^ offset` when the
+/// node inherits it.
+fn seed_var(
+    seed: NodeValue<u32>,
+    master_seed: Option<u32>,
+    snarl: &Snarl<NoiseNode>,
+) -> Variable<u32> {
+    match master_seed {
+        Some(master_seed) => Variable::Anonymous(master_seed ^ seed.eval(snarl)),
+        None => seed.var(snarl),
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BakeNode {
+    pub image: Image,
+
+    pub resolution: NodeValue<u32>,
+    pub size: NodeValue<f64>,
+}
+
+impl BakeNode {
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> BakeExpr {
+        BakeExpr {
+            source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
+            resolution: self.resolution.var(snarl),
+            size: self.size.var(snarl),
+            table: Default::default(),
+        }
+    }
+}
+
+impl Default for BakeNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            resolution: NodeValue::Value(Bake::DEFAULT_RESOLUTION),
+            size: NodeValue::Value(Bake::DEFAULT_SIZE),
+        }
+    }
+}
+
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct BlendNode {
     pub image: Image,
@@ -87,13 +148,59 @@ pub struct CheckerboardNode {
     pub image: Image,
 
     pub size: NodeValue<u32>,
+
+    #[serde(default)]
+    pub offset: [NodeValue<f64>; 2],
+
+    /// Rotation of the pattern in degrees.
+    #[serde(default)]
+    pub rotation: NodeValue<f64>,
+}
+
+impl CheckerboardNode {
+    /// The same value as `Checkerboard::DEFAULT_SIZE`, which is private.
+    pub const DEFAULT_SIZE: u32 = 0;
+
+    /// Offset and rotation are applied by wrapping the checkerboard in point transforms, which are
+    /// left out when unused so that plain checkerboards export as before.
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> Expr {
+        let mut expr = Expr::Checkerboard(self.size.var(snarl));
+
+        if self.offset != [NodeValue::Value(0.0); 2] {
+            expr = Expr::TranslatePoint(TransformExpr {
+                source: Box::new(expr),
+                axes: [
+                    self.offset[0].var(snarl),
+                    self.offset[1].var(snarl),
+                    Variable::Anonymous(0.0),
+                    Variable::Anonymous(0.0),
+                ],
+            });
+        }
+
+        if self.rotation != NodeValue::Value(0.0) {
+            expr = Expr::RotatePoint(TransformExpr {
+                source: Box::new(expr),
+                axes: [
+                    Variable::Anonymous(0.0),
+                    Variable::Anonymous(0.0),
+                    self.rotation.var(snarl),
+                    Variable::Anonymous(0.0),
+                ],
+            });
+        }
+
+        expr
+    }
 }
 
 impl Default for CheckerboardNode {
     fn default() -> Self {
         Self {
             image: Default::default(),
-            size: NodeValue::Value(0), // TODO: Checkerboard::DEFAULT_SIZE is private!
+            size: NodeValue::Value(Self::DEFAULT_SIZE),
+            offset: Default::default(),
+            rotation: Default::default(),
         }
     }
 }
@@ -107,6 +214,12 @@ pub struct ClampNode {
 }
 
 impl ClampNode {
+    /// The percentiles of the sampled input used as the lower and upper bounds.
+    const OBSERVED_PERCENTILES: [f64; 2] = [0.01, 0.99];
+
+    /// The number of samples taken along each side of the preview window.
+    const OBSERVED_RESOLUTION: usize = 64;
+
     fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> ClampExpr {
         ClampExpr {
             source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
@@ -114,6 +227,47 @@ impl ClampNode {
             upper_bound: self.upper_bound.var(snarl),
         }
     }
+
+    pub fn has_inverted_bounds(&self, snarl: &Snarl<NoiseNode>) -> bool {
+        self.lower_bound.eval(snarl) > self.upper_bound.eval(snarl)
+    }
+
+    /// Samples the input over the current preview window and returns the observed lower and upper
+    /// percentiles, or `None` if the input is not connected.
+    pub fn observed_bounds(
+        &self,
+        node_idx: usize,
+        snarl: &Snarl<NoiseNode>,
+        backend: Backend,
+    ) -> Option<[f64; 2]> {
+        let noise = in_pin_expr(snarl, node_idx, 0)?.noise_with(backend.get());
+        let step = 1.0 / Self::OBSERVED_RESOLUTION as f64;
+        let half_step = step / 2.0;
+
+        // Sample positions match those used by the image threads
+        let mut samples = (0..Self::OBSERVED_RESOLUTION)
+            .flat_map(|row| (0..Self::OBSERVED_RESOLUTION).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let eval_x = (col as f64 * step + half_step + self.image.y) * self.image.scale;
+                let eval_y = (row as f64 * step + half_step + self.image.x) * self.image.scale;
+
+                noise.get([eval_x, eval_y, 0.0])
+            })
+            .filter(|sample| sample.is_finite())
+            .collect::<Vec<_>>();
+
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort_by(f64::total_cmp);
+
+        Some(
+            Self::OBSERVED_PERCENTILES.map(|percentile| {
+                samples[((samples.len() - 1) as f64 * percentile).round() as usize]
+            }),
+        )
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize)]
@@ -199,6 +353,14 @@ pub struct CurveNode {
     pub image: Image,
 
     pub control_point_node_indices: Vec<Option<usize>>,
+
+    /// Input and output value pairs which are stored in the node instead of as graph nodes.
+    #[serde(default)]
+    pub inline_control_points: Vec<(f64, f64)>,
+
+    /// The minimum number of control point pins shown, including empty pins.
+    #[serde(default)]
+    pub pin_count: usize,
 }
 
 impl CurveNode {
@@ -221,6 +383,14 @@ impl CurveNode {
                             .unwrap()
                     })
                 })
+                .chain(
+                    self.inline_control_points
+                        .iter()
+                        .map(|&(input, output)| ControlPointExpr {
+                            input_value: Variable::Anonymous(input),
+                            output_value: Variable::Anonymous(output),
+                        }),
+                )
                 .collect(),
         }
     }
@@ -231,6 +401,59 @@ pub struct CylindersNode {
     pub image: Image,
 
     pub frequency: NodeValue<f64>,
+
+    /// The axis which the cylinders are centered on.
+    #[serde(default)]
+    pub axis: Axis,
+
+    /// Multipliers of the frequency along each axis, which make the cylinders elliptical.
+    #[serde(default = "CylindersNode::default_axis_frequencies")]
+    pub axis_frequencies: [NodeValue<f64>; 3],
+}
+
+impl CylindersNode {
+    fn default_axis_frequencies() -> [NodeValue<f64>; 3] {
+        [NodeValue::Value(1.0); 3]
+    }
+
+    /// Axis and per-axis frequencies are applied by wrapping the cylinders in point transforms,
+    /// which are left out when unused so that plain cylinders export as before.
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> Expr {
+        let mut expr = Expr::Cylinders(self.frequency.var(snarl));
+
+        // Cylinders are centered on the Z axis, so points are rotated onto it from other axes
+        let angles = match self.axis {
+            Axis::X => Some([0.0, 90.0, 0.0]),
+            Axis::Y => Some([90.0, 0.0, 0.0]),
+            Axis::Z => None,
+        };
+
+        if let Some([x, y, z]) = angles {
+            expr = Expr::RotatePoint(TransformExpr {
+                source: Box::new(expr),
+                axes: [
+                    Variable::Anonymous(x),
+                    Variable::Anonymous(y),
+                    Variable::Anonymous(z),
+                    Variable::Anonymous(0.0),
+                ],
+            });
+        }
+
+        if self.axis_frequencies != Self::default_axis_frequencies() {
+            expr = Expr::ScalePoint(TransformExpr {
+                source: Box::new(expr),
+                axes: [
+                    self.axis_frequencies[0].var(snarl),
+                    self.axis_frequencies[1].var(snarl),
+                    self.axis_frequencies[2].var(snarl),
+                    Variable::Anonymous(1.0),
+                ],
+            });
+        }
+
+        expr
+    }
 }
 
 impl Default for CylindersNode {
@@ -238,6 +461,8 @@ impl Default for CylindersNode {
         Self {
             image: Default::default(),
             frequency: NodeValue::Value(Cylinders::DEFAULT_FREQUENCY),
+            axis: Default::default(),
+            axis_frequencies: Self::default_axis_frequencies(),
         }
     }
 }
@@ -285,28 +510,209 @@ impl Default for ExponentNode {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FastNoiseNode {
+    pub image: Image,
+
+    #[serde(default = "AlgorithmVersion::first")]
+    pub algorithm: AlgorithmVersion,
+
+    pub noise_ty: FastNoiseType,
+    pub fractal_ty: FastFractalType,
+    pub rotation_ty: FastRotationType,
+    pub cellular_distance_fn: CellularDistanceFunction,
+    pub cellular_return_ty: CellularReturnType,
+    pub is_3d: bool,
+    pub seed: NodeValue<u32>,
+
+    #[serde(default)]
+    pub master_seed: Option<u32>,
+    pub octaves: NodeValue<u32>,
+    pub frequency: NodeValue<f64>,
+    pub lacunarity: NodeValue<f64>,
+    pub gain: NodeValue<f64>,
+    pub weighted_strength: NodeValue<f64>,
+    pub ping_pong_strength: NodeValue<f64>,
+    pub cellular_jitter: NodeValue<f64>,
+}
+
+impl FastNoiseNode {
+    /// The defaults of FastNoise Lite, except for the frequency: its default of 0.01 suits pixel
+    /// coordinates, which previews show as flat.
+    pub const DEFAULT_SEED: u32 = 1337;
+    pub const DEFAULT_OCTAVES: u32 = 3;
+    pub const DEFAULT_FREQUENCY: f64 = 1.0;
+    pub const DEFAULT_LACUNARITY: f64 = 2.0;
+    pub const DEFAULT_GAIN: f64 = 0.5;
+    pub const DEFAULT_WEIGHTED_STRENGTH: f64 = 0.0;
+    pub const DEFAULT_PING_PONG_STRENGTH: f64 = 2.0;
+    pub const DEFAULT_CELLULAR_JITTER: f64 = 1.0;
+
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> FastNoiseExpr {
+        FastNoiseExpr {
+            noise_ty: self.noise_ty,
+            fractal_ty: self.fractal_ty,
+            rotation_ty: self.rotation_ty,
+            cellular_distance_fn: self.cellular_distance_fn,
+            cellular_return_ty: self.cellular_return_ty,
+            is_3d: self.is_3d,
+            seed: seed_var(self.seed, self.master_seed, snarl),
+            octaves: self.octaves.var(snarl),
+            frequency: self.frequency.var(snarl),
+            lacunarity: self.lacunarity.var(snarl),
+            gain: self.gain.var(snarl),
+            weighted_strength: self.weighted_strength.var(snarl),
+            ping_pong_strength: self.ping_pong_strength.var(snarl),
+            cellular_jitter: self.cellular_jitter.var(snarl),
+        }
+    }
+
+    /// Returns the decimal value of an input pin, which are all those after the seed and octaves.
+    pub fn f64_value(&self, input: usize) -> Option<NodeValue<f64>> {
+        match input {
+            2 => Some(self.frequency),
+            3 => Some(self.lacunarity),
+            4 => Some(self.gain),
+            5 => Some(self.weighted_strength),
+            6 => Some(self.ping_pong_strength),
+            7 => Some(self.cellular_jitter),
+            _ => None,
+        }
+    }
+
+    pub fn f64_value_mut(&mut self, input: usize) -> Option<&mut NodeValue<f64>> {
+        match input {
+            2 => Some(&mut self.frequency),
+            3 => Some(&mut self.lacunarity),
+            4 => Some(&mut self.gain),
+            5 => Some(&mut self.weighted_strength),
+            6 => Some(&mut self.ping_pong_strength),
+            7 => Some(&mut self.cellular_jitter),
+            _ => None,
+        }
+    }
+
+    pub fn u32_value(&self, input: usize) -> Option<NodeValue<u32>> {
+        match input {
+            0 => Some(self.seed),
+            1 => Some(self.octaves),
+            _ => None,
+        }
+    }
+
+    pub fn u32_value_mut(&mut self, input: usize) -> Option<&mut NodeValue<u32>> {
+        match input {
+            0 => Some(&mut self.seed),
+            1 => Some(&mut self.octaves),
+            _ => None,
+        }
+    }
+}
+
+impl Default for FastNoiseNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            algorithm: Default::default(),
+            noise_ty: Default::default(),
+            fractal_ty: Default::default(),
+            rotation_ty: Default::default(),
+            cellular_distance_fn: Default::default(),
+            cellular_return_ty: Default::default(),
+            is_3d: false,
+            seed: NodeValue::Value(Self::DEFAULT_SEED),
+            master_seed: None,
+            octaves: NodeValue::Value(Self::DEFAULT_OCTAVES),
+            frequency: NodeValue::Value(Self::DEFAULT_FREQUENCY),
+            lacunarity: NodeValue::Value(Self::DEFAULT_LACUNARITY),
+            gain: NodeValue::Value(Self::DEFAULT_GAIN),
+            weighted_strength: NodeValue::Value(Self::DEFAULT_WEIGHTED_STRENGTH),
+            ping_pong_strength: NodeValue::Value(Self::DEFAULT_PING_PONG_STRENGTH),
+            cellular_jitter: NodeValue::Value(Self::DEFAULT_CELLULAR_JITTER),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FaultNode {
+    pub image: Image,
+
+    #[serde(default = "AlgorithmVersion::first")]
+    pub algorithm: AlgorithmVersion,
+
+    pub seed: NodeValue<u32>,
+
+    #[serde(default)]
+    pub master_seed: Option<u32>,
+    pub iterations: NodeValue<u32>,
+
+    /// The distance from the origin within which the fault planes pass.
+    #[serde(default = "FaultNode::default_extent")]
+    pub extent: NodeValue<f64>,
+}
+
+impl FaultNode {
+    /// Projects saved before the extent was added keep their planes within one unit.
+    fn default_extent() -> NodeValue<f64> {
+        NodeValue::Value(1.0)
+    }
+
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> FaultExpr {
+        FaultExpr {
+            seed: seed_var(self.seed, self.master_seed, snarl),
+            iterations: self.iterations.var(snarl),
+            extent: self.extent.var(snarl),
+        }
+    }
+}
+
+impl Default for FaultNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            algorithm: Default::default(),
+            seed: NodeValue::Value(Fault::DEFAULT_SEED),
+            master_seed: None,
+            iterations: NodeValue::Value(Fault::DEFAULT_ITERATIONS),
+            extent: NodeValue::Value(Fault::DEFAULT_EXTENT),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct FractalNode {
     pub image: Image,
 
+    #[serde(default = "AlgorithmVersion::first")]
+    pub algorithm: AlgorithmVersion,
+
     pub source_ty: SourceType,
     pub seed: NodeValue<u32>,
+
+    #[serde(default)]
+    pub master_seed: Option<u32>,
     pub octaves: NodeValue<u32>,
     pub frequency: NodeValue<f64>,
     pub lacunarity: NodeValue<f64>,
     pub persistence: NodeValue<f64>,
+
+    /// The weight of one more octave after the whole ones, so that the octave count may change
+    /// smoothly.
+    #[serde(default)]
+    pub octave_remainder: NodeValue<f64>,
 }
 
 impl FractalNode {
-    fn expr(&self, snarl: &Snarl<NoiseNode>) -> FractalExpr {
-        FractalExpr {
+    fn expr(&self, variant: fn(FractalExpr) -> Expr, snarl: &Snarl<NoiseNode>) -> Expr {
+        variant(FractalExpr {
             source_ty: self.source_ty,
-            seed: self.seed.var(snarl),
+            seed: seed_var(self.seed, self.master_seed, snarl),
             octaves: self.octaves.var(snarl),
             frequency: self.frequency.var(snarl),
             lacunarity: self.lacunarity.var(snarl),
             persistence: self.persistence.var(snarl),
-        }
+            octave_remainder: self.octave_remainder.var(snarl),
+        })
     }
 }
 
@@ -314,12 +720,15 @@ impl Default for FractalNode {
     fn default() -> Self {
         Self {
             image: Default::default(),
+            algorithm: Default::default(),
             source_ty: Default::default(),
             seed: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_SEED),
+            master_seed: None,
             octaves: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_OCTAVES as _),
             frequency: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_FREQUENCY),
             lacunarity: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_LACUNARITY),
             persistence: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_PERSISTENCE),
+            octave_remainder: Default::default(),
         }
     }
 }
@@ -328,16 +737,167 @@ impl Default for FractalNode {
 pub struct GeneratorNode {
     pub image: Image,
 
+    #[serde(default = "AlgorithmVersion::first")]
+    pub algorithm: AlgorithmVersion,
+
     pub seed: NodeValue<u32>,
+
+    /// The frequency of each axis, which stretches the noise along the axes with lower
+    /// frequencies, or `None` to sample the noise as it is.
+    #[serde(default)]
+    pub axis_frequencies: Option<[f64; 3]>,
+
+    /// The output of Perlin and Simplex nodes, which may be an analytic derivative.
+    #[serde(default)]
+    pub channel: Channel,
+
+    #[serde(default)]
+    pub master_seed: Option<u32>,
+}
+
+impl GeneratorNode {
+    /// Returns the expression of a Perlin or Simplex node, depending on its channel.
+    fn expr(&self, source_ty: SourceType, snarl: &Snarl<NoiseNode>) -> Expr {
+        let seed = seed_var(self.seed, self.master_seed, snarl);
+
+        match (self.channel, source_ty) {
+            (Channel::Value, SourceType::Perlin) => Expr::Perlin(seed),
+            (Channel::Value, _) => Expr::Simplex(seed),
+            (channel, source_ty) => Expr::Derivative(DerivativeExpr {
+                source_ty,
+                seed,
+                channel,
+            }),
+        }
+    }
+
+    /// Scales the point the generator samples by the frequency of each axis, if there are any.
+    fn scale(&self, expr: Expr) -> Expr {
+        match self.axis_frequencies {
+            Some([x, y, z]) => Expr::ScalePoint(TransformExpr {
+                source: Box::new(expr),
+                axes: [x, y, z, 1.0].map(Variable::Anonymous),
+            }),
+            None => expr,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GradientNode {
+    pub image: Image,
+
+    pub wrap: WrapMode,
+    pub angle: NodeValue<f64>,
+    pub offset: NodeValue<f64>,
+}
+
+impl GradientNode {
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> GradientExpr {
+        GradientExpr {
+            angle: self.angle.var(snarl),
+            offset: self.offset.var(snarl),
+            wrap: self.wrap,
+        }
+    }
+}
+
+impl Default for GradientNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            wrap: WrapMode::Clamp,
+            angle: NodeValue::Value(Gradient::DEFAULT_ANGLE),
+            offset: NodeValue::Value(Gradient::DEFAULT_OFFSET),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HeightmapNode {
+    pub image: Image,
+
+    /// The grayscale image file, which is linked or embedded as the asset chooses.
+    pub asset: Asset,
+
+    pub size: NodeValue<f64>,
+}
+
+impl HeightmapNode {
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> HeightmapExpr {
+        HeightmapExpr {
+            image: self.asset.heightmap(),
+            size: self.size.var(snarl),
+        }
+    }
+}
+
+impl Default for HeightmapNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            asset: Default::default(),
+            size: NodeValue::Value(Heightmap::DEFAULT_SIZE),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Image {
+    /// The number of texture pixels along each side of a point, which matches the display density
+    /// the texture was rendered for.
+    #[serde(skip)]
+    pub density: usize,
+
+    #[serde(default)]
+    pub display: PreviewDisplay,
+
+    #[serde(default)]
+    pub export: ExportSettings,
+
+    /// Whether world-space grid lines, axis labels and a ruler are drawn over the image.
+    #[serde(default)]
+    pub grid: bool,
+
+    /// Whether a bar showing how values map to gray levels is shown under the image.
+    #[serde(default)]
+    pub legend: bool,
+
+    /// The lower and upper bound the values of the node are expected to be within, which is kept
+    /// in exported expressions.
+    #[serde(default)]
+    pub output_range: Option<[f64; 2]>,
+
+    /// Texture drawn over the image, such as the selection bands of a Select node.
+    #[serde(skip)]
+    pub overlay_texture: Option<TextureHandle>,
+
+    /// Grayscale copy of the texture, used to build overlays from the sampled values.
+    #[serde(skip)]
+    pub pixels: Vec<u8>,
+
+    /// The coordinates of the sub-images of this version rendered at full resolution, which coarse
+    /// sub-images arriving late must not replace.
+    #[serde(skip)]
+    pub refined_coords: HashSet<u8>,
+
+    /// The change to the export region made by the drag in progress on the preview.
+    #[serde(skip)]
+    pub region_drag: Option<RegionDrag>,
+
     pub scale: f64,
 
+    /// Whether dragging on the preview selects the export region.
+    #[serde(skip)]
+    pub selecting_region: bool,
+
     #[serde(skip)]
     pub texture: Option<TextureHandle>,
 
+    /// Whether the image is sampled so that it tiles seamlessly, in the preview and in exports.
+    #[serde(default)]
+    pub tileable: bool,
+
     #[serde(skip)]
     pub version: usize,
 
@@ -348,8 +908,20 @@ pub struct Image {
 impl Default for Image {
     fn default() -> Self {
         Self {
+            density: 1,
+            display: Default::default(),
+            export: Default::default(),
+            grid: false,
+            legend: false,
+            output_range: None,
+            overlay_texture: None,
+            pixels: vec![],
+            refined_coords: Default::default(),
+            region_drag: None,
             scale: 4.0,
+            selecting_region: false,
             texture: None,
+            tileable: false,
             version: 0,
             x: 0.0,
             y: 0.0,
@@ -357,6 +929,300 @@ impl Default for Image {
     }
 }
 
+impl Image {
+    /// Uploads the sampled pixels to the texture again, which shows changes of the display
+    /// settings without sampling the node again.
+    pub fn redisplay(&mut self) {
+        let Some(texture) = &mut self.texture else {
+            return;
+        };
+
+        let size = texture.size();
+        if self.pixels.len() != size[0] * size[1] {
+            return;
+        }
+
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|&gray| self.display.apply(gray))
+            .collect::<Vec<_>>();
+        texture.set(ColorImage::from_gray(size, &pixels), Default::default());
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct JitterNode {
+    pub image: Image,
+
+    #[serde(default = "AlgorithmVersion::first")]
+    pub algorithm: AlgorithmVersion,
+
+    pub seed: NodeValue<u32>,
+    pub amount: NodeValue<f64>,
+}
+
+impl JitterNode {
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> JitterExpr {
+        JitterExpr {
+            source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
+            seed: self.seed.var(snarl),
+            amount: self.amount.var(snarl),
+        }
+    }
+}
+
+impl Default for JitterNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            algorithm: Default::default(),
+            seed: NodeValue::Value(Jitter::<AnySeedable>::DEFAULT_SEED),
+            amount: NodeValue::Value(Jitter::<AnySeedable>::DEFAULT_AMOUNT),
+        }
+    }
+}
+
+/// Blends two sources where a mask rises above a threshold, which is a Select node with no upper
+/// bound: the pattern most terrain graphs use to place one layer over another.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MaskedBlendNode {
+    pub image: Image,
+
+    pub threshold: NodeValue<f64>,
+
+    /// The distance from the threshold over which the sources are blended.
+    pub feather: NodeValue<f64>,
+}
+
+impl MaskedBlendNode {
+    /// The upper bound of the equivalent Select node, which is above any mask while remaining a
+    /// number single precision code can represent.
+    pub const UPPER_BOUND: f64 = 1e30;
+
+    /// Returns the bounds of the equivalent Select node, as `SelectNode::bounds` does.
+    pub fn bounds(&self, snarl: &Snarl<NoiseNode>) -> [f64; 3] {
+        [
+            self.threshold.eval(snarl),
+            Self::UPPER_BOUND,
+            self.feather.eval(snarl),
+        ]
+    }
+
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> SelectExpr {
+        SelectExpr {
+            sources: (0..2)
+                .map(|input| in_pin_expr_or_const(snarl, node_idx, input, 0.0))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            control: in_pin_expr_or_const(snarl, node_idx, 2, 0.0),
+            lower_bound: self.threshold.var(snarl),
+            upper_bound: Variable::Anonymous(Self::UPPER_BOUND),
+            falloff: self.feather.var(snarl),
+        }
+    }
+}
+
+impl Default for MaskedBlendNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            threshold: NodeValue::Value(0.0),
+            feather: NodeValue::Value(0.1),
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct MixNode {
+    pub image: Image,
+}
+
+impl MixNode {
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> MixExpr {
+        MixExpr {
+            sources: (0..2)
+                .map(|input| in_pin_expr_or_const(snarl, node_idx, input, 0.0))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            factor: in_pin_expr_or_const(snarl, node_idx, 2, 0.5),
+        }
+    }
+}
+
+/// The version of the algorithms of seeded nodes, recorded in each node so that projects which
+/// ship world seeds can tell when upgrading `noise` or changing a noise function of this crate
+/// alters their output.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct AlgorithmVersion(pub u32);
+
+impl AlgorithmVersion {
+    /// The algorithms of `noise` 0.8, which projects saved before versions were recorded used.
+    pub const FIRST: Self = Self(1);
+
+    /// Bump this when a change alters the output of any kind of seeded node, and list the kinds
+    /// which changed in `NoiseNode::algorithm_changed`.
+    pub const CURRENT: Self = Self(1);
+
+    fn first() -> Self {
+        Self::FIRST
+    }
+}
+
+impl Default for AlgorithmVersion {
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+impl std::fmt::Display for AlgorithmVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// The kinds of node, as they are grouped by the menu of the graph.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NodeCategory {
+    Combiner,
+    Constant,
+    Fractal,
+    Generator,
+    Modifier,
+    Selector,
+    Transformer,
+}
+
+impl NodeCategory {
+    pub const ALL: [Self; 7] = [
+        Self::Combiner,
+        Self::Constant,
+        Self::Fractal,
+        Self::Generator,
+        Self::Modifier,
+        Self::Selector,
+        Self::Transformer,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Combiner => "Combiner",
+            Self::Constant => "Constant",
+            Self::Fractal => "Fractal",
+            Self::Generator => "Generator",
+            Self::Modifier => "Modifier",
+            Self::Selector => "Selector",
+            Self::Transformer => "Transformer",
+        }
+    }
+}
+
+/// Identifier of a node which, unlike its graph index, is never reused and does not change when
+/// nodes are imported from another project.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct NodeId(u64);
+
+impl NodeId {
+    fn new() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        Self(RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed)))
+    }
+}
+
+impl std::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl std::str::FromStr for NodeId {
+    type Err = std::num::ParseIntError;
+
+    /// Parses the hexadecimal form shown to users.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u64::from_str_radix(s, 16).map(Self)
+    }
+}
+
+/// The identifiers of the nodes of a graph, by graph index.
+///
+/// Saved graphs refer to nodes by identifier, so that references to nodes may be matched up after
+/// the graph indices have changed.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<usize, NodeId>", into = "BTreeMap<usize, NodeId>")]
+pub struct NodeIds {
+    ids: HashMap<usize, NodeId>,
+    node_indices: HashMap<NodeId, usize>,
+}
+
+impl NodeIds {
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        self.node_indices.clear();
+    }
+
+    pub fn id(&self, node_idx: usize) -> Option<NodeId> {
+        self.ids.get(&node_idx).copied()
+    }
+
+    /// Returns the graph index of the node with an identifier.
+    pub fn node_idx(&self, id: NodeId) -> Option<usize> {
+        self.node_indices.get(&id).copied()
+    }
+
+    fn insert(&mut self, node_idx: usize, id: NodeId) {
+        if let Some(old_id) = self.ids.insert(node_idx, id) {
+            self.node_indices.remove(&old_id);
+        }
+
+        self.node_indices.insert(id, node_idx);
+    }
+
+    pub fn remove(&mut self, node_idx: usize) {
+        if let Some(id) = self.ids.remove(&node_idx) {
+            self.node_indices.remove(&id);
+        }
+    }
+
+    /// Gives identifiers to nodes which do not have one yet.
+    pub fn update(&mut self, snarl: &Snarl<NoiseNode>) {
+        for (node_idx, _) in snarl.node_indices() {
+            if !self.ids.contains_key(&node_idx) {
+                let mut id = NodeId::new();
+                while self.node_indices.contains_key(&id) {
+                    id = NodeId::new();
+                }
+
+                self.insert(node_idx, id);
+            }
+        }
+    }
+}
+
+impl From<BTreeMap<usize, NodeId>> for NodeIds {
+    fn from(ids: BTreeMap<usize, NodeId>) -> Self {
+        let mut res = Self::default();
+
+        for (node_idx, id) in ids {
+            // Duplicate identifiers are replaced by the next update
+            if !res.node_indices.contains_key(&id) {
+                res.insert(node_idx, id);
+            }
+        }
+
+        res
+    }
+}
+
+impl From<NodeIds> for BTreeMap<usize, NodeId> {
+    fn from(node_ids: NodeIds) -> Self {
+        node_ids.ids.into_iter().collect()
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum NodeValue<T> {
     Node(usize),
@@ -386,6 +1252,10 @@ impl<T> NodeValue<T> {
 }
 
 impl NodeValue<f64> {
+    fn detach(&mut self, snarl: &Snarl<NoiseNode>) {
+        *self = Self::Value(self.eval(snarl));
+    }
+
     fn eval(self, snarl: &Snarl<NoiseNode>) -> f64 {
         match self {
             Self::Node(node_idx) => snarl.get_node(node_idx).eval_f64(snarl),
@@ -406,6 +1276,10 @@ impl NodeValue<f64> {
 }
 
 impl NodeValue<u32> {
+    fn detach(&mut self, snarl: &Snarl<NoiseNode>) {
+        *self = Self::Value(self.eval(snarl));
+    }
+
     fn eval(self, snarl: &Snarl<NoiseNode>) -> u32 {
         match self {
             Self::Node(node_idx) => snarl.get_node(node_idx).eval_u32(snarl),
@@ -446,22 +1320,34 @@ where
 pub enum NoiseNode {
     Abs(UnaryNode),
     Add(CombinerNode),
+    Bake(BakeNode),
     BasicMulti(FractalNode),
     Billow(FractalNode),
     Blend(BlendNode),
+    BlueNoise(GeneratorNode),
+    Cache(UnaryNode),
     Clamp(ClampNode),
     Checkerboard(CheckerboardNode),
     ControlPoint(ControlPointNode),
     Curve(CurveNode),
     Cylinders(CylindersNode),
     Displace(DisplaceNode),
+    Exp(UnaryNode),
     Exponent(ExponentNode),
     F64(ConstantNode<f64>),
     F64Operation(ConstantOpNode<f64>),
+    FastNoise(FastNoiseNode),
+    Fault(FaultNode),
     Fbm(FractalNode),
+    Gradient(GradientNode),
+    Heightmap(HeightmapNode),
     HybridMulti(FractalNode),
+    Jitter(JitterNode),
+    Log(UnaryNode),
+    MaskedBlend(MaskedBlendNode),
     Max(CombinerNode),
     Min(CombinerNode),
+    Mix(MixNode),
     Multiply(CombinerNode),
     Negate(UnaryNode),
     OpenSimplex(GeneratorNode),
@@ -469,6 +1355,12 @@ pub enum NoiseNode {
     Perlin(GeneratorNode),
     PerlinSurflet(GeneratorNode),
     Power(CombinerNode),
+    Plateau(PlateauNode),
+    PowSignal(CombinerNode),
+    Quantize(QuantizeNode),
+    Rebase(RebaseNode),
+    Repeat(RepeatNode),
+    Ridge(RidgeNode),
     RigidMulti(RigidFractalNode),
     RotatePoint(TransformNode),
     ScaleBias(ScaleBiasNode),
@@ -477,15 +1369,87 @@ pub enum NoiseNode {
     Simplex(GeneratorNode),
     SuperSimplex(GeneratorNode),
     Terrace(TerraceNode),
+    Threshold(ThresholdNode),
     TranslatePoint(TransformNode),
     Turbulence(TurbulenceNode),
     U32(ConstantNode<u32>),
     U32Operation(ConstantOpNode<u32>),
     Value(GeneratorNode),
+    WhiteNoise(GeneratorNode),
     Worley(WorleyNode),
 }
 
 impl NoiseNode {
+    /// Returns the version of the noise algorithm a seeded node was created with.
+    pub fn algorithm(&self) -> Option<AlgorithmVersion> {
+        match self {
+            Self::BasicMulti(FractalNode { algorithm, .. })
+            | Self::Billow(FractalNode { algorithm, .. })
+            | Self::BlueNoise(GeneratorNode { algorithm, .. })
+            | Self::FastNoise(FastNoiseNode { algorithm, .. })
+            | Self::Fault(FaultNode { algorithm, .. })
+            | Self::Fbm(FractalNode { algorithm, .. })
+            | Self::HybridMulti(FractalNode { algorithm, .. })
+            | Self::Jitter(JitterNode { algorithm, .. })
+            | Self::OpenSimplex(GeneratorNode { algorithm, .. })
+            | Self::Perlin(GeneratorNode { algorithm, .. })
+            | Self::PerlinSurflet(GeneratorNode { algorithm, .. })
+            | Self::RigidMulti(RigidFractalNode { algorithm, .. })
+            | Self::Simplex(GeneratorNode { algorithm, .. })
+            | Self::SuperSimplex(GeneratorNode { algorithm, .. })
+            | Self::Turbulence(TurbulenceNode { algorithm, .. })
+            | Self::Value(GeneratorNode { algorithm, .. })
+            | Self::WhiteNoise(GeneratorNode { algorithm, .. })
+            | Self::Worley(WorleyNode { algorithm, .. }) => Some(*algorithm),
+            _ => None,
+        }
+    }
+
+    /// Returns the latest version which changed the output of a kind of seeded node.
+    pub fn algorithm_changed(&self) -> Option<AlgorithmVersion> {
+        // When a version changes the output of some kinds of node they are listed here, such as
+        // `Self::Perlin(_) | Self::Fbm(_) => Some(AlgorithmVersion(2))`
+        self.algorithm().map(|_| AlgorithmVersion::FIRST)
+    }
+
+    pub fn algorithm_mut(&mut self) -> Option<&mut AlgorithmVersion> {
+        match self {
+            Self::BasicMulti(FractalNode { algorithm, .. })
+            | Self::Billow(FractalNode { algorithm, .. })
+            | Self::BlueNoise(GeneratorNode { algorithm, .. })
+            | Self::FastNoise(FastNoiseNode { algorithm, .. })
+            | Self::Fault(FaultNode { algorithm, .. })
+            | Self::Fbm(FractalNode { algorithm, .. })
+            | Self::HybridMulti(FractalNode { algorithm, .. })
+            | Self::Jitter(JitterNode { algorithm, .. })
+            | Self::OpenSimplex(GeneratorNode { algorithm, .. })
+            | Self::Perlin(GeneratorNode { algorithm, .. })
+            | Self::PerlinSurflet(GeneratorNode { algorithm, .. })
+            | Self::RigidMulti(RigidFractalNode { algorithm, .. })
+            | Self::Simplex(GeneratorNode { algorithm, .. })
+            | Self::SuperSimplex(GeneratorNode { algorithm, .. })
+            | Self::Turbulence(TurbulenceNode { algorithm, .. })
+            | Self::Value(GeneratorNode { algorithm, .. })
+            | Self::WhiteNoise(GeneratorNode { algorithm, .. })
+            | Self::Worley(WorleyNode { algorithm, .. }) => Some(algorithm),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if a seeded node was created before its algorithm last changed, in which
+    /// case its seed may no longer produce the output it did.
+    pub fn is_algorithm_outdated(&self) -> bool {
+        self.algorithm() < self.algorithm_changed()
+    }
+
+    pub fn as_bake_mut(&mut self) -> Option<&mut BakeNode> {
+        if let Self::Bake(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
     pub fn as_checkerboard_mut(&mut self) -> Option<&mut CheckerboardNode> {
         if let Self::Checkerboard(node) = self {
             Some(node)
@@ -590,6 +1554,22 @@ impl NoiseNode {
         }
     }
 
+    pub fn as_fast_noise_mut(&mut self) -> Option<&mut FastNoiseNode> {
+        if let Self::FastNoise(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_fault_mut(&mut self) -> Option<&mut FaultNode> {
+        if let Self::Fault(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
     pub fn as_fractal_mut(&mut self) -> Option<&mut FractalNode> {
         if let Self::BasicMulti(node)
         | Self::Billow(node)
@@ -603,12 +1583,14 @@ impl NoiseNode {
     }
 
     pub fn as_generator_mut(&mut self) -> Option<&mut GeneratorNode> {
-        if let Self::OpenSimplex(node)
+        if let Self::BlueNoise(node)
+        | Self::OpenSimplex(node)
         | Self::Perlin(node)
         | Self::PerlinSurflet(node)
         | Self::Simplex(node)
         | Self::SuperSimplex(node)
-        | Self::Value(node) = self
+        | Self::Value(node)
+        | Self::WhiteNoise(node) = self
         {
             Some(node)
         } else {
@@ -616,6 +1598,78 @@ impl NoiseNode {
         }
     }
 
+    pub fn as_gradient_mut(&mut self) -> Option<&mut GradientNode> {
+        if let Self::Gradient(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_heightmap_mut(&mut self) -> Option<&mut HeightmapNode> {
+        if let Self::Heightmap(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_jitter_mut(&mut self) -> Option<&mut JitterNode> {
+        if let Self::Jitter(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_masked_blend_mut(&mut self) -> Option<&mut MaskedBlendNode> {
+        if let Self::MaskedBlend(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_plateau_mut(&mut self) -> Option<&mut PlateauNode> {
+        if let Self::Plateau(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_quantize_mut(&mut self) -> Option<&mut QuantizeNode> {
+        if let Self::Quantize(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_rebase_mut(&mut self) -> Option<&mut RebaseNode> {
+        if let Self::Rebase(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_repeat_mut(&mut self) -> Option<&mut RepeatNode> {
+        if let Self::Repeat(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_ridge_mut(&mut self) -> Option<&mut RidgeNode> {
+        if let Self::Ridge(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
     pub fn as_rigid_fractal_mut(&mut self) -> Option<&mut RigidFractalNode> {
         if let Self::RigidMulti(node) = self {
             Some(node)
@@ -648,6 +1702,14 @@ impl NoiseNode {
         }
     }
 
+    pub fn as_threshold_mut(&mut self) -> Option<&mut ThresholdNode> {
+        if let Self::Threshold(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
     pub fn as_transform_mut(&mut self) -> Option<&mut TransformNode> {
         if let Self::RotatePoint(node) | Self::ScalePoint(node) | Self::TranslatePoint(node) = self
         {
@@ -657,6 +1719,14 @@ impl NoiseNode {
         }
     }
 
+    pub fn as_turbulence(&self) -> Option<&TurbulenceNode> {
+        if let Self::Turbulence(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
     pub fn as_turbulence_mut(&mut self) -> Option<&mut TurbulenceNode> {
         if let Self::Turbulence(node) = self {
             Some(node)
@@ -673,6 +1743,283 @@ impl NoiseNode {
         }
     }
 
+    /// Returns the file a node reads, if it has one.
+    pub fn asset(&self) -> Option<&Asset> {
+        match self {
+            Self::Heightmap(node) => Some(&node.asset),
+            _ => None,
+        }
+    }
+
+    pub fn asset_mut(&mut self) -> Option<&mut Asset> {
+        match self {
+            Self::Heightmap(node) => Some(&mut node.asset),
+            _ => None,
+        }
+    }
+
+    pub fn category(&self) -> NodeCategory {
+        match self {
+            Self::Add(_)
+            | Self::Max(_)
+            | Self::Min(_)
+            | Self::Multiply(_)
+            | Self::Power(_)
+            | Self::PowSignal(_) => NodeCategory::Combiner,
+            Self::ControlPoint(_)
+            | Self::F64(_)
+            | Self::F64Operation(_)
+            | Self::Operation(_)
+            | Self::U32(_)
+            | Self::U32Operation(_) => NodeCategory::Constant,
+            Self::BasicMulti(_)
+            | Self::Billow(_)
+            | Self::Fbm(_)
+            | Self::HybridMulti(_)
+            | Self::RigidMulti(_) => NodeCategory::Fractal,
+            Self::BlueNoise(_)
+            | Self::Checkerboard(_)
+            | Self::Cylinders(_)
+            | Self::FastNoise(_)
+            | Self::Fault(_)
+            | Self::Gradient(_)
+            | Self::Heightmap(_)
+            | Self::OpenSimplex(_)
+            | Self::Perlin(_)
+            | Self::PerlinSurflet(_)
+            | Self::Simplex(_)
+            | Self::SuperSimplex(_)
+            | Self::Value(_)
+            | Self::WhiteNoise(_)
+            | Self::Worley(_) => NodeCategory::Generator,
+            Self::Abs(_)
+            | Self::Bake(_)
+            | Self::Cache(_)
+            | Self::Clamp(_)
+            | Self::Curve(_)
+            | Self::Exp(_)
+            | Self::Exponent(_)
+            | Self::Log(_)
+            | Self::Negate(_)
+            | Self::Plateau(_)
+            | Self::Quantize(_)
+            | Self::Ridge(_)
+            | Self::ScaleBias(_)
+            | Self::Terrace(_) => NodeCategory::Modifier,
+            Self::Blend(_)
+            | Self::MaskedBlend(_)
+            | Self::Mix(_)
+            | Self::Select(_)
+            | Self::Threshold(_) => NodeCategory::Selector,
+            Self::Displace(_)
+            | Self::Jitter(_)
+            | Self::Rebase(_)
+            | Self::Repeat(_)
+            | Self::RotatePoint(_)
+            | Self::ScalePoint(_)
+            | Self::TranslatePoint(_)
+            | Self::Turbulence(_) => NodeCategory::Transformer,
+        }
+    }
+
+    pub fn control_point_node_indices(&self) -> Option<&[Option<usize>]> {
+        match self {
+            Self::Curve(CurveNode {
+                control_point_node_indices,
+                ..
+            })
+            | Self::Terrace(TerraceNode {
+                control_point_node_indices,
+                ..
+            }) => Some(control_point_node_indices),
+            _ => None,
+        }
+    }
+
+    /// Replaces the inputs which are connected to other nodes with the values of those nodes and
+    /// forgets connected control points, so that a copy of the node refers to no other node.
+    pub fn detach(&mut self, snarl: &Snarl<Self>) {
+        match self {
+            Self::Abs(_)
+            | Self::Add(_)
+            | Self::Blend(_)
+            | Self::Cache(_)
+            | Self::Displace(_)
+            | Self::Exp(_)
+            | Self::F64(_)
+            | Self::Log(_)
+            | Self::Max(_)
+            | Self::Min(_)
+            | Self::Mix(_)
+            | Self::Multiply(_)
+            | Self::Negate(_)
+            | Self::Power(_)
+            | Self::PowSignal(_)
+            | Self::U32(_) => (),
+            Self::Bake(node) => {
+                node.resolution.detach(snarl);
+                node.size.detach(snarl);
+            }
+            Self::BasicMulti(node)
+            | Self::Billow(node)
+            | Self::Fbm(node)
+            | Self::HybridMulti(node) => {
+                node.seed.detach(snarl);
+                node.octaves.detach(snarl);
+                node.frequency.detach(snarl);
+                node.lacunarity.detach(snarl);
+                node.persistence.detach(snarl);
+                node.octave_remainder.detach(snarl);
+            }
+            Self::BlueNoise(node)
+            | Self::OpenSimplex(node)
+            | Self::Perlin(node)
+            | Self::PerlinSurflet(node)
+            | Self::Simplex(node)
+            | Self::SuperSimplex(node)
+            | Self::Value(node)
+            | Self::WhiteNoise(node) => node.seed.detach(snarl),
+            Self::Heightmap(node) => node.size.detach(snarl),
+            Self::Checkerboard(node) => {
+                node.size.detach(snarl);
+
+                for offset in &mut node.offset {
+                    offset.detach(snarl);
+                }
+
+                node.rotation.detach(snarl);
+            }
+            Self::Clamp(node) => {
+                node.lower_bound.detach(snarl);
+                node.upper_bound.detach(snarl);
+            }
+            Self::ControlPoint(node) => {
+                node.input.detach(snarl);
+                node.output.detach(snarl);
+            }
+            Self::Curve(CurveNode {
+                control_point_node_indices,
+                ..
+            })
+            | Self::Terrace(TerraceNode {
+                control_point_node_indices,
+                ..
+            }) => control_point_node_indices.fill(None),
+            Self::Cylinders(node) => {
+                node.frequency.detach(snarl);
+
+                for frequency in &mut node.axis_frequencies {
+                    frequency.detach(snarl);
+                }
+            }
+            Self::Exponent(node) => node.exponent.detach(snarl),
+            Self::F64Operation(node) => {
+                for input in &mut node.inputs {
+                    input.detach(snarl);
+                }
+            }
+            Self::FastNoise(node) => {
+                node.seed.detach(snarl);
+                node.octaves.detach(snarl);
+                node.frequency.detach(snarl);
+                node.lacunarity.detach(snarl);
+                node.gain.detach(snarl);
+                node.weighted_strength.detach(snarl);
+                node.ping_pong_strength.detach(snarl);
+                node.cellular_jitter.detach(snarl);
+            }
+            Self::Fault(node) => {
+                node.seed.detach(snarl);
+                node.iterations.detach(snarl);
+                node.extent.detach(snarl);
+            }
+            Self::Gradient(node) => {
+                node.angle.detach(snarl);
+                node.offset.detach(snarl);
+            }
+            Self::Jitter(node) => {
+                node.seed.detach(snarl);
+                node.amount.detach(snarl);
+            }
+            Self::MaskedBlend(node) => {
+                node.threshold.detach(snarl);
+                node.feather.detach(snarl);
+            }
+            Self::Operation(node) => node.inputs = [NodeValue::Value(()); 2],
+            Self::Plateau(node) => {
+                node.level.detach(snarl);
+                node.softness.detach(snarl);
+            }
+            Self::Quantize(node) => {
+                node.levels.detach(snarl);
+                node.dither.detach(snarl);
+                node.seed.detach(snarl);
+            }
+            Self::Rebase(node) => node.period.detach(snarl),
+            Self::Repeat(node) => {
+                for period in &mut node.periods {
+                    period.detach(snarl);
+                }
+            }
+            Self::Ridge(node) => {
+                node.sharpness.detach(snarl);
+                node.offset.detach(snarl);
+            }
+            Self::RigidMulti(node) => {
+                node.seed.detach(snarl);
+                node.octaves.detach(snarl);
+                node.frequency.detach(snarl);
+                node.lacunarity.detach(snarl);
+                node.persistence.detach(snarl);
+                node.attenuation.detach(snarl);
+                node.octave_remainder.detach(snarl);
+            }
+            Self::RotatePoint(node) | Self::ScalePoint(node) | Self::TranslatePoint(node) => {
+                for axis in &mut node.axes {
+                    axis.detach(snarl);
+                }
+            }
+            Self::ScaleBias(node) => {
+                node.scale.detach(snarl);
+                node.bias.detach(snarl);
+            }
+            Self::Select(node) => {
+                node.lower_bound.detach(snarl);
+                node.upper_bound.detach(snarl);
+                node.falloff.detach(snarl);
+            }
+            Self::Threshold(node) => {
+                node.off_threshold.detach(snarl);
+                node.on_threshold.detach(snarl);
+                node.scan_steps.detach(snarl);
+            }
+            Self::Turbulence(node) => {
+                node.seed.detach(snarl);
+                node.frequency.detach(snarl);
+                node.power.detach(snarl);
+                node.roughness.detach(snarl);
+
+                for axis_value in node
+                    .axis_frequencies
+                    .iter_mut()
+                    .chain(&mut node.axis_powers)
+                    .flatten()
+                {
+                    axis_value.detach(snarl);
+                }
+            }
+            Self::U32Operation(node) => {
+                for input in &mut node.inputs {
+                    input.detach(snarl);
+                }
+            }
+            Self::Worley(node) => {
+                node.seed.detach(snarl);
+                node.frequency.detach(snarl);
+            }
+        }
+    }
+
     pub fn eval_f64(&self, snarl: &Snarl<Self>) -> f64 {
         match self {
             Self::F64(node) => node.value,
@@ -713,45 +2060,98 @@ impl NoiseNode {
     }
 
     pub fn expr(&self, node_idx: usize, snarl: &Snarl<Self>) -> Expr {
-        match self {
+        let expr = match self {
             Self::Abs(node) => Expr::Abs(node.expr(node_idx, snarl)),
             Self::Add(node) => Expr::Add(node.expr(node_idx, snarl, 0.0)),
-            Self::BasicMulti(node) => Expr::BasicMulti(node.expr(snarl)),
-            Self::Billow(node) => Expr::Billow(node.expr(snarl)),
+            Self::Bake(node) => Expr::Bake(node.expr(node_idx, snarl)),
+            Self::BasicMulti(node) => node.expr(Expr::BasicMulti, snarl),
+            Self::Billow(node) => node.expr(Expr::Billow, snarl),
             Self::Blend(node) => Expr::Blend(node.expr(node_idx, snarl)),
-            Self::Checkerboard(node) => Expr::Checkerboard(node.size.var(snarl)),
+            Self::BlueNoise(node) => node.scale(Expr::BlueNoise(seed_var(
+                node.seed,
+                node.master_seed,
+                snarl,
+            ))),
+            Self::Cache(node) => Expr::Cache(CacheExpr {
+                id: node_idx,
+                source: node.expr(node_idx, snarl),
+            }),
+            Self::Checkerboard(node) => node.expr(snarl),
             Self::Clamp(node) => Expr::Clamp(node.expr(node_idx, snarl)),
             Self::Curve(node) => Expr::Curve(node.expr(node_idx, snarl)),
-            Self::Cylinders(node) => Expr::Cylinders(node.frequency.var(snarl)),
+            Self::Cylinders(node) => node.expr(snarl),
             Self::Displace(node) => Expr::Displace(node.expr(node_idx, snarl)),
+            Self::Exp(node) => Expr::Exp(node.expr(node_idx, snarl)),
             Self::Exponent(node) => Expr::Exponent(node.expr(node_idx, snarl)),
             Self::F64(node) => Expr::Constant(Variable::Named(node.name.clone(), node.value)),
             Self::F64Operation(node) => Expr::Constant(node.var(snarl)),
-            Self::Fbm(node) => Expr::Fbm(node.expr(snarl)),
-            Self::HybridMulti(node) => Expr::HybridMulti(node.expr(snarl)),
+            Self::FastNoise(node) => Expr::FastNoise(node.expr(snarl)),
+            Self::Fault(node) => Expr::Fault(node.expr(snarl)),
+            Self::Fbm(node) => node.expr(Expr::Fbm, snarl),
+            Self::Gradient(node) => Expr::Gradient(node.expr(snarl)),
+            Self::Heightmap(node) => Expr::Heightmap(node.expr(snarl)),
+            Self::HybridMulti(node) => node.expr(Expr::HybridMulti, snarl),
+            Self::Jitter(node) => Expr::Jitter(node.expr(node_idx, snarl)),
+            Self::Log(node) => Expr::Log(node.expr(node_idx, snarl)),
+            Self::MaskedBlend(node) => Expr::Select(node.expr(node_idx, snarl)),
             Self::Max(node) => Expr::Max(node.expr(node_idx, snarl, 1.0)),
             Self::Min(node) => Expr::Min(node.expr(node_idx, snarl, -1.0)),
+            Self::Mix(node) => Expr::Mix(node.expr(node_idx, snarl)),
             Self::Multiply(node) => Expr::Multiply(node.expr(node_idx, snarl, 1.0)),
             Self::Negate(node) => Expr::Negate(node.expr(node_idx, snarl)),
-            Self::OpenSimplex(node) => Expr::OpenSimplex(node.seed.var(snarl)),
-            Self::Perlin(node) => Expr::Perlin(node.seed.var(snarl)),
-            Self::PerlinSurflet(node) => Expr::PerlinSurflet(node.seed.var(snarl)),
+            Self::OpenSimplex(node) => node.scale(Expr::OpenSimplex(seed_var(
+                node.seed,
+                node.master_seed,
+                snarl,
+            ))),
+            Self::Perlin(node) => node.scale(node.expr(SourceType::Perlin, snarl)),
+            Self::PerlinSurflet(node) => node.scale(Expr::PerlinSurflet(seed_var(
+                node.seed,
+                node.master_seed,
+                snarl,
+            ))),
             Self::Power(node) => Expr::Power(node.expr(node_idx, snarl, 1.0)),
-            Self::RigidMulti(node) => Expr::RidgedMulti(node.expr(snarl)),
+            Self::Plateau(node) => Expr::Plateau(node.expr(node_idx, snarl)),
+            Self::PowSignal(node) => Expr::PowSignal(node.expr(node_idx, snarl, 1.0)),
+            Self::Quantize(node) => Expr::Quantize(node.expr(node_idx, snarl)),
+            Self::Rebase(node) => Expr::Rebase(node.expr(node_idx, snarl)),
+            Self::Repeat(node) => Expr::Repeat(node.expr(node_idx, snarl)),
+            Self::Ridge(node) => Expr::Ridge(node.expr(node_idx, snarl)),
+            Self::RigidMulti(node) => node.expr(snarl),
             Self::RotatePoint(node) => Expr::RotatePoint(node.expr(node_idx, snarl)),
             Self::ScaleBias(node) => Expr::ScaleBias(node.expr(node_idx, snarl)),
             Self::ScalePoint(node) => Expr::ScalePoint(node.expr(node_idx, snarl)),
             Self::Select(node) => Expr::Select(node.expr(node_idx, snarl)),
-            Self::Simplex(node) => Expr::Simplex(node.seed.var(snarl)),
-            Self::SuperSimplex(node) => Expr::SuperSimplex(node.seed.var(snarl)),
+            Self::Simplex(node) => node.scale(node.expr(SourceType::Simplex, snarl)),
+            Self::SuperSimplex(node) => node.scale(Expr::SuperSimplex(seed_var(
+                node.seed,
+                node.master_seed,
+                snarl,
+            ))),
             Self::Terrace(node) => Expr::Terrace(node.expr(node_idx, snarl)),
+            Self::Threshold(node) => Expr::Threshold(node.expr(node_idx, snarl)),
             Self::TranslatePoint(node) => Expr::TranslatePoint(node.expr(node_idx, snarl)),
             Self::Turbulence(node) => Expr::Turbulence(node.expr(node_idx, snarl)),
-            Self::Value(node) => Expr::Value(node.seed.var(snarl)),
+            Self::Value(node) => {
+                node.scale(Expr::Value(seed_var(node.seed, node.master_seed, snarl)))
+            }
+            Self::WhiteNoise(node) => node.scale(Expr::WhiteNoise(seed_var(
+                node.seed,
+                node.master_seed,
+                snarl,
+            ))),
             Self::Worley(node) => Expr::Worley(node.expr(snarl)),
             Self::ControlPoint(_) | Self::Operation(_) | Self::U32(_) | Self::U32Operation(_) => {
                 unreachable!()
             }
+        };
+
+        match self.image().and_then(|image| image.output_range) {
+            Some(range) => Expr::OutputRange(OutputRangeExpr {
+                source: Box::new(expr),
+                range,
+            }),
+            None => expr,
         }
     }
 
@@ -759,29 +2159,112 @@ impl NoiseNode {
         self.image().is_some()
     }
 
+    /// Returns `true` for image nodes which modify the image connected to their first input.
+    pub fn has_source(&self) -> bool {
+        match self {
+            Self::Abs(_)
+            | Self::Add(_)
+            | Self::Bake(_)
+            | Self::Blend(_)
+            | Self::Cache(_)
+            | Self::Clamp(_)
+            | Self::Curve(_)
+            | Self::Displace(_)
+            | Self::Exp(_)
+            | Self::Exponent(_)
+            | Self::Jitter(_)
+            | Self::Log(_)
+            | Self::MaskedBlend(_)
+            | Self::Max(_)
+            | Self::Min(_)
+            | Self::Mix(_)
+            | Self::Multiply(_)
+            | Self::Negate(_)
+            | Self::Plateau(_)
+            | Self::Power(_)
+            | Self::PowSignal(_)
+            | Self::Quantize(_)
+            | Self::Rebase(_)
+            | Self::Repeat(_)
+            | Self::Ridge(_)
+            | Self::RotatePoint(_)
+            | Self::ScaleBias(_)
+            | Self::ScalePoint(_)
+            | Self::Select(_)
+            | Self::Terrace(_)
+            | Self::Threshold(_)
+            | Self::TranslatePoint(_)
+            | Self::Turbulence(_) => true,
+            Self::BasicMulti(_)
+            | Self::Billow(_)
+            | Self::BlueNoise(_)
+            | Self::Checkerboard(_)
+            | Self::ControlPoint(_)
+            | Self::Cylinders(_)
+            | Self::F64(_)
+            | Self::F64Operation(_)
+            | Self::FastNoise(_)
+            | Self::Fault(_)
+            | Self::Fbm(_)
+            | Self::Gradient(_)
+            | Self::Heightmap(_)
+            | Self::HybridMulti(_)
+            | Self::OpenSimplex(_)
+            | Self::Operation(_)
+            | Self::Perlin(_)
+            | Self::PerlinSurflet(_)
+            | Self::RigidMulti(_)
+            | Self::Simplex(_)
+            | Self::SuperSimplex(_)
+            | Self::U32(_)
+            | Self::U32Operation(_)
+            | Self::Value(_)
+            | Self::WhiteNoise(_)
+            | Self::Worley(_) => false,
+        }
+    }
+
     pub fn image(&self) -> Option<&Image> {
         match self {
             Self::Abs(UnaryNode { image, .. })
             | Self::Add(CombinerNode { image, .. })
+            | Self::Bake(BakeNode { image, .. })
             | Self::BasicMulti(FractalNode { image, .. })
             | Self::Billow(FractalNode { image, .. })
             | Self::Blend(BlendNode { image, .. })
+            | Self::BlueNoise(GeneratorNode { image, .. })
+            | Self::Cache(UnaryNode { image, .. })
             | Self::Checkerboard(CheckerboardNode { image, .. })
             | Self::Clamp(ClampNode { image, .. })
             | Self::Curve(CurveNode { image, .. })
             | Self::Cylinders(CylindersNode { image, .. })
             | Self::Displace(DisplaceNode { image, .. })
+            | Self::Exp(UnaryNode { image, .. })
             | Self::Exponent(ExponentNode { image, .. })
+            | Self::FastNoise(FastNoiseNode { image, .. })
+            | Self::Fault(FaultNode { image, .. })
             | Self::Fbm(FractalNode { image, .. })
+            | Self::Gradient(GradientNode { image, .. })
+            | Self::Heightmap(HeightmapNode { image, .. })
             | Self::HybridMulti(FractalNode { image, .. })
+            | Self::Jitter(JitterNode { image, .. })
+            | Self::Log(UnaryNode { image, .. })
+            | Self::MaskedBlend(MaskedBlendNode { image, .. })
             | Self::Max(CombinerNode { image, .. })
             | Self::Min(CombinerNode { image, .. })
+            | Self::Mix(MixNode { image, .. })
             | Self::Multiply(CombinerNode { image, .. })
             | Self::Negate(UnaryNode { image, .. })
             | Self::OpenSimplex(GeneratorNode { image, .. })
             | Self::Perlin(GeneratorNode { image, .. })
             | Self::PerlinSurflet(GeneratorNode { image, .. })
             | Self::Power(CombinerNode { image, .. })
+            | Self::Plateau(PlateauNode { image, .. })
+            | Self::PowSignal(CombinerNode { image, .. })
+            | Self::Quantize(QuantizeNode { image, .. })
+            | Self::Rebase(RebaseNode { image, .. })
+            | Self::Repeat(RepeatNode { image, .. })
+            | Self::Ridge(RidgeNode { image, .. })
             | Self::RigidMulti(RigidFractalNode { image, .. })
             | Self::RotatePoint(TransformNode { image, .. })
             | Self::ScaleBias(ScaleBiasNode { image, .. })
@@ -790,9 +2273,11 @@ impl NoiseNode {
             | Self::Simplex(GeneratorNode { image, .. })
             | Self::SuperSimplex(GeneratorNode { image, .. })
             | Self::Terrace(TerraceNode { image, .. })
+            | Self::Threshold(ThresholdNode { image, .. })
             | Self::TranslatePoint(TransformNode { image, .. })
             | Self::Turbulence(TurbulenceNode { image, .. })
             | Self::Value(GeneratorNode { image, .. })
+            | Self::WhiteNoise(GeneratorNode { image, .. })
             | Self::Worley(WorleyNode { image, .. }) => Some(image),
             Self::ControlPoint(_)
             | Self::F64(_)
@@ -807,25 +2292,43 @@ impl NoiseNode {
         match self {
             Self::Abs(UnaryNode { image, .. })
             | Self::Add(CombinerNode { image, .. })
+            | Self::Bake(BakeNode { image, .. })
             | Self::BasicMulti(FractalNode { image, .. })
             | Self::Billow(FractalNode { image, .. })
             | Self::Blend(BlendNode { image, .. })
+            | Self::BlueNoise(GeneratorNode { image, .. })
+            | Self::Cache(UnaryNode { image, .. })
             | Self::Checkerboard(CheckerboardNode { image, .. })
             | Self::Clamp(ClampNode { image, .. })
             | Self::Curve(CurveNode { image, .. })
             | Self::Cylinders(CylindersNode { image, .. })
             | Self::Displace(DisplaceNode { image, .. })
+            | Self::Exp(UnaryNode { image, .. })
             | Self::Exponent(ExponentNode { image, .. })
+            | Self::FastNoise(FastNoiseNode { image, .. })
+            | Self::Fault(FaultNode { image, .. })
             | Self::Fbm(FractalNode { image, .. })
+            | Self::Gradient(GradientNode { image, .. })
+            | Self::Heightmap(HeightmapNode { image, .. })
             | Self::HybridMulti(FractalNode { image, .. })
+            | Self::Jitter(JitterNode { image, .. })
+            | Self::Log(UnaryNode { image, .. })
+            | Self::MaskedBlend(MaskedBlendNode { image, .. })
             | Self::Max(CombinerNode { image, .. })
             | Self::Min(CombinerNode { image, .. })
+            | Self::Mix(MixNode { image, .. })
             | Self::Multiply(CombinerNode { image, .. })
             | Self::Negate(UnaryNode { image, .. })
             | Self::OpenSimplex(GeneratorNode { image, .. })
             | Self::Perlin(GeneratorNode { image, .. })
             | Self::PerlinSurflet(GeneratorNode { image, .. })
             | Self::Power(CombinerNode { image, .. })
+            | Self::Plateau(PlateauNode { image, .. })
+            | Self::PowSignal(CombinerNode { image, .. })
+            | Self::Quantize(QuantizeNode { image, .. })
+            | Self::Rebase(RebaseNode { image, .. })
+            | Self::Repeat(RepeatNode { image, .. })
+            | Self::Ridge(RidgeNode { image, .. })
             | Self::RigidMulti(RigidFractalNode { image, .. })
             | Self::RotatePoint(TransformNode { image, .. })
             | Self::ScaleBias(ScaleBiasNode { image, .. })
@@ -834,9 +2337,11 @@ impl NoiseNode {
             | Self::Simplex(GeneratorNode { image, .. })
             | Self::SuperSimplex(GeneratorNode { image, .. })
             | Self::Terrace(TerraceNode { image, .. })
+            | Self::Threshold(ThresholdNode { image, .. })
             | Self::TranslatePoint(TransformNode { image, .. })
             | Self::Turbulence(TurbulenceNode { image, .. })
             | Self::Value(GeneratorNode { image, .. })
+            | Self::WhiteNoise(GeneratorNode { image, .. })
             | Self::Worley(WorleyNode { image, .. }) => Some(image),
             Self::ControlPoint(_)
             | Self::F64(_)
@@ -847,6 +2352,67 @@ impl NoiseNode {
         }
     }
 
+    /// Returns the project master seed stored by a node which inherits it, or `None` for nodes
+    /// which have no seed to inherit into.
+    pub fn master_seed(&self) -> Option<Option<u32>> {
+        match self {
+            Self::BasicMulti(FractalNode { master_seed, .. })
+            | Self::Billow(FractalNode { master_seed, .. })
+            | Self::BlueNoise(GeneratorNode { master_seed, .. })
+            | Self::FastNoise(FastNoiseNode { master_seed, .. })
+            | Self::Fault(FaultNode { master_seed, .. })
+            | Self::Fbm(FractalNode { master_seed, .. })
+            | Self::HybridMulti(FractalNode { master_seed, .. })
+            | Self::OpenSimplex(GeneratorNode { master_seed, .. })
+            | Self::Perlin(GeneratorNode { master_seed, .. })
+            | Self::PerlinSurflet(GeneratorNode { master_seed, .. })
+            | Self::RigidMulti(RigidFractalNode { master_seed, .. })
+            | Self::Simplex(GeneratorNode { master_seed, .. })
+            | Self::SuperSimplex(GeneratorNode { master_seed, .. })
+            | Self::Turbulence(TurbulenceNode { master_seed, .. })
+            | Self::Value(GeneratorNode { master_seed, .. })
+            | Self::WhiteNoise(GeneratorNode { master_seed, .. })
+            | Self::Worley(WorleyNode { master_seed, .. }) => Some(*master_seed),
+            _ => None,
+        }
+    }
+
+    pub fn master_seed_mut(&mut self) -> Option<&mut Option<u32>> {
+        match self {
+            Self::BasicMulti(FractalNode { master_seed, .. })
+            | Self::Billow(FractalNode { master_seed, .. })
+            | Self::BlueNoise(GeneratorNode { master_seed, .. })
+            | Self::FastNoise(FastNoiseNode { master_seed, .. })
+            | Self::Fault(FaultNode { master_seed, .. })
+            | Self::Fbm(FractalNode { master_seed, .. })
+            | Self::HybridMulti(FractalNode { master_seed, .. })
+            | Self::OpenSimplex(GeneratorNode { master_seed, .. })
+            | Self::Perlin(GeneratorNode { master_seed, .. })
+            | Self::PerlinSurflet(GeneratorNode { master_seed, .. })
+            | Self::RigidMulti(RigidFractalNode { master_seed, .. })
+            | Self::Simplex(GeneratorNode { master_seed, .. })
+            | Self::SuperSimplex(GeneratorNode { master_seed, .. })
+            | Self::Turbulence(TurbulenceNode { master_seed, .. })
+            | Self::Value(GeneratorNode { master_seed, .. })
+            | Self::WhiteNoise(GeneratorNode { master_seed, .. })
+            | Self::Worley(WorleyNode { master_seed, .. }) => Some(master_seed),
+            _ => None,
+        }
+    }
+
+    pub fn move_control_point(node_idx: usize, from: usize, to: usize, snarl: &mut Snarl<Self>) {
+        if let Some(mut control_point_node_indices) = snarl
+            .get_node(node_idx)
+            .control_point_node_indices()
+            .map(<[_]>::to_vec)
+        {
+            let control_point_node_idx = control_point_node_indices.remove(from);
+            control_point_node_indices.insert(to, control_point_node_idx);
+
+            Self::set_control_point_node_indices(node_idx, control_point_node_indices, snarl);
+        }
+    }
+
     pub fn propagate_f64_from_tuple_op(node_idx: usize, snarl: &mut Snarl<Self>) {
         thread_local! {
             static CHILD_NODE_INDICES: RefCell<Option<HashSet<usize>>> = RefCell::new(Some(Default::default()));
@@ -1080,32 +2646,445 @@ impl NoiseNode {
         CHILD_NODE_INDICES.set(Some(child_node_indices));
         NODE_INDICES.set(Some(node_indices));
     }
+
+    pub fn remove_empty_control_points(node_idx: usize, snarl: &mut Snarl<Self>) {
+        if let Some(control_point_node_indices) = snarl
+            .get_node(node_idx)
+            .control_point_node_indices()
+            .map(|control_point_node_indices| {
+                control_point_node_indices
+                    .iter()
+                    .copied()
+                    .filter(Option::is_some)
+                    .collect()
+            })
+        {
+            Self::set_control_point_node_indices(node_idx, control_point_node_indices, snarl);
+
+            match snarl.get_node_mut(node_idx) {
+                Self::Curve(CurveNode { pin_count, .. })
+                | Self::Terrace(TerraceNode { pin_count, .. }) => *pin_count = 0,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    pub fn seed(&self) -> Option<NodeValue<u32>> {
+        match self {
+            Self::BasicMulti(FractalNode { seed, .. })
+            | Self::Billow(FractalNode { seed, .. })
+            | Self::BlueNoise(GeneratorNode { seed, .. })
+            | Self::FastNoise(FastNoiseNode { seed, .. })
+            | Self::Fault(FaultNode { seed, .. })
+            | Self::Fbm(FractalNode { seed, .. })
+            | Self::HybridMulti(FractalNode { seed, .. })
+            | Self::Jitter(JitterNode { seed, .. })
+            | Self::OpenSimplex(GeneratorNode { seed, .. })
+            | Self::Perlin(GeneratorNode { seed, .. })
+            | Self::PerlinSurflet(GeneratorNode { seed, .. })
+            | Self::Quantize(QuantizeNode { seed, .. })
+            | Self::RigidMulti(RigidFractalNode { seed, .. })
+            | Self::Simplex(GeneratorNode { seed, .. })
+            | Self::SuperSimplex(GeneratorNode { seed, .. })
+            | Self::Turbulence(TurbulenceNode { seed, .. })
+            | Self::Value(GeneratorNode { seed, .. })
+            | Self::WhiteNoise(GeneratorNode { seed, .. })
+            | Self::Worley(WorleyNode { seed, .. }) => Some(*seed),
+            _ => None,
+        }
+    }
+
+    pub fn seed_mut(&mut self) -> Option<&mut NodeValue<u32>> {
+        match self {
+            Self::BasicMulti(FractalNode { seed, .. })
+            | Self::Billow(FractalNode { seed, .. })
+            | Self::BlueNoise(GeneratorNode { seed, .. })
+            | Self::FastNoise(FastNoiseNode { seed, .. })
+            | Self::Fault(FaultNode { seed, .. })
+            | Self::Fbm(FractalNode { seed, .. })
+            | Self::HybridMulti(FractalNode { seed, .. })
+            | Self::Jitter(JitterNode { seed, .. })
+            | Self::OpenSimplex(GeneratorNode { seed, .. })
+            | Self::Perlin(GeneratorNode { seed, .. })
+            | Self::PerlinSurflet(GeneratorNode { seed, .. })
+            | Self::Quantize(QuantizeNode { seed, .. })
+            | Self::RigidMulti(RigidFractalNode { seed, .. })
+            | Self::Simplex(GeneratorNode { seed, .. })
+            | Self::SuperSimplex(GeneratorNode { seed, .. })
+            | Self::Turbulence(TurbulenceNode { seed, .. })
+            | Self::Value(GeneratorNode { seed, .. })
+            | Self::WhiteNoise(GeneratorNode { seed, .. })
+            | Self::Worley(WorleyNode { seed, .. }) => Some(seed),
+            _ => None,
+        }
+    }
+
+    /// Returns the seed used by the noise function of a node, including any master seed.
+    pub fn seed_value(&self, snarl: &Snarl<Self>) -> Option<u32> {
+        let seed = self.seed()?.eval(snarl);
+
+        Some(match self.master_seed().flatten() {
+            Some(master_seed) => master_seed ^ seed,
+            None => seed,
+        })
+    }
+
+    /// Stores new control point node indices for a Curve or Terrace node and re-wires the control
+    /// point pins (which follow the source pin) to match.
+    fn set_control_point_node_indices(
+        node_idx: usize,
+        control_point_node_indices: Vec<Option<usize>>,
+        snarl: &mut Snarl<Self>,
+    ) {
+        let pin_count = match snarl.get_node_mut(node_idx) {
+            Self::Curve(node) => {
+                let pin_count = node.control_point_node_indices.len();
+                node.control_point_node_indices = control_point_node_indices.clone();
+
+                pin_count
+            }
+            Self::Terrace(node) => {
+                let pin_count = node.control_point_node_indices.len();
+                node.control_point_node_indices = control_point_node_indices.clone();
+
+                pin_count
+            }
+            _ => unreachable!(),
+        }
+        .max(control_point_node_indices.len());
+
+        for control_point_idx in 0..pin_count {
+            let pin = InPinId {
+                node: node_idx,
+                input: control_point_idx + 1,
+            };
+
+            snarl.drop_inputs(pin);
+
+            if let Some(control_point_node_idx) = control_point_node_indices
+                .get(control_point_idx)
+                .copied()
+                .flatten()
+            {
+                snarl.connect(
+                    OutPinId {
+                        node: control_point_node_idx,
+                        output: 0,
+                    },
+                    pin,
+                );
+            }
+        }
+    }
+
+    /// Enables or disables the per-axis overrides of a Turbulence node; enabled overrides start at
+    /// the current frequency and power and disabled overrides have their pins disconnected.
+    pub fn set_turbulence_per_axis(node_idx: usize, per_axis: bool, snarl: &mut Snarl<Self>) {
+        let node = snarl.get_node(node_idx).as_turbulence().unwrap();
+        let frequency = node.frequency.eval(snarl);
+        let power = node.power.eval(snarl);
+
+        let node = snarl.get_node_mut(node_idx).as_turbulence_mut().unwrap();
+        if per_axis {
+            node.axis_frequencies = Some([NodeValue::Value(frequency); 3]);
+            node.axis_powers = Some([NodeValue::Value(power); 3]);
+        } else {
+            node.axis_frequencies = None;
+            node.axis_powers = None;
+
+            for input in 5..=10 {
+                snarl.drop_inputs(InPinId {
+                    node: node_idx,
+                    input,
+                });
+            }
+        }
+    }
+
+    /// Sorts the control points of a Curve or Terrace node by input value, placing any empty pins
+    /// last. Inline control points are sorted separately.
+    pub fn sort_control_points(node_idx: usize, snarl: &mut Snarl<Self>) {
+        match snarl.get_node_mut(node_idx) {
+            Self::Curve(node) => node
+                .inline_control_points
+                .sort_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs)),
+            Self::Terrace(node) => node.inline_control_points.sort_by(f64::total_cmp),
+            _ => (),
+        }
+
+        let node = snarl.get_node(node_idx);
+        if let Some(control_point_node_indices) = node.control_point_node_indices() {
+            let mut control_points = control_point_node_indices
+                .iter()
+                .copied()
+                .map(|control_point_node_idx| {
+                    let input_value = control_point_node_idx.map(|control_point_node_idx| {
+                        let control_point_node = snarl.get_node(control_point_node_idx);
+                        match node {
+                            Self::Curve(_) => control_point_node
+                                .as_control_point()
+                                .unwrap()
+                                .input
+                                .eval(snarl),
+                            _ => control_point_node.eval_f64(snarl),
+                        }
+                    });
+
+                    (input_value, control_point_node_idx)
+                })
+                .collect::<Vec<_>>();
+
+            control_points.sort_by(|(lhs, _), (rhs, _)| match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => lhs.total_cmp(rhs),
+                (lhs, rhs) => rhs.is_some().cmp(&lhs.is_some()),
+            });
+
+            Self::set_control_point_node_indices(
+                node_idx,
+                control_points
+                    .into_iter()
+                    .map(|(_, control_point_node_idx)| control_point_node_idx)
+                    .collect(),
+                snarl,
+            );
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PlateauNode {
+    pub image: Image,
+
+    pub level: NodeValue<f64>,
+    pub softness: NodeValue<f64>,
+}
+
+impl PlateauNode {
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> PlateauExpr {
+        PlateauExpr {
+            source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
+            level: self.level.var(snarl),
+            softness: self.softness.var(snarl),
+        }
+    }
+}
+
+impl Default for PlateauNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            level: NodeValue::Value(Plateau::<AnySeedable>::DEFAULT_LEVEL),
+            softness: NodeValue::Value(Plateau::<AnySeedable>::DEFAULT_SOFTNESS),
+        }
+    }
+}
+
+/// Adjusts how the preview of a node is shown, so that dark or flat outputs are readable at a
+/// glance. The values of the node, and everything exported from it, are not changed.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PreviewDisplay {
+    /// Scales gray levels away from middle gray.
+    pub contrast: f32,
+
+    /// Brightens gray levels above one and darkens them below one.
+    pub gamma: f32,
+
+    pub invert: bool,
+}
+
+impl PreviewDisplay {
+    pub const CONTRAST_RANGE: RangeInclusive<f32> = 0.1..=10.0;
+    pub const GAMMA_RANGE: RangeInclusive<f32> = 0.1..=10.0;
+
+    pub fn apply(&self, gray: u8) -> u8 {
+        if self.is_default() {
+            return gray;
+        }
+
+        let mut value = ((gray as f32 / 255.0 - 0.5) * self.contrast + 0.5).clamp(0.0, 1.0);
+        value = value.powf(1.0 / self.gamma);
+
+        if self.invert {
+            value = 1.0 - value;
+        }
+
+        (value * 255.0).round() as u8
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for PreviewDisplay {
+    fn default() -> Self {
+        Self {
+            contrast: 1.0,
+            gamma: 1.0,
+            invert: false,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct QuantizeNode {
+    pub image: Image,
+
+    pub levels: NodeValue<u32>,
+    pub dither: NodeValue<f64>,
+
+    #[serde(default = "QuantizeNode::default_seed")]
+    pub seed: NodeValue<u32>,
+}
+
+impl QuantizeNode {
+    fn default_seed() -> NodeValue<u32> {
+        NodeValue::Value(Quantize::<AnySeedable>::DEFAULT_SEED)
+    }
+
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> QuantizeExpr {
+        QuantizeExpr {
+            source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
+            levels: self.levels.var(snarl),
+            dither: self.dither.var(snarl),
+            seed: self.seed.var(snarl),
+        }
+    }
+}
+
+impl Default for QuantizeNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            levels: NodeValue::Value(Quantize::<AnySeedable>::DEFAULT_LEVELS),
+            dither: NodeValue::Value(Quantize::<AnySeedable>::DEFAULT_DITHER),
+            seed: Self::default_seed(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RebaseNode {
+    pub image: Image,
+
+    pub period: NodeValue<f64>,
+}
+
+impl RebaseNode {
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> RebaseExpr {
+        RebaseExpr {
+            source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
+            period: self.period.var(snarl),
+        }
+    }
+}
+
+impl Default for RebaseNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            period: NodeValue::Value(Rebase::<AnySeedable>::DEFAULT_PERIOD),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RepeatNode {
+    pub image: Image,
+
+    pub mode: RepeatMode,
+    pub periods: [NodeValue<f64>; 3],
+}
+
+impl RepeatNode {
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> RepeatExpr {
+        RepeatExpr {
+            source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
+            mode: self.mode,
+            periods: self
+                .periods
+                .iter()
+                .map(|period| period.var(snarl))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        }
+    }
+}
+
+impl Default for RepeatNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            mode: RepeatMode::Wrap,
+            periods: [NodeValue::Value(Repeat::<AnySeedable>::DEFAULT_PERIOD); 3],
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RidgeNode {
+    pub image: Image,
+
+    pub sharpness: NodeValue<f64>,
+    pub offset: NodeValue<f64>,
+}
+
+impl RidgeNode {
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> RidgeExpr {
+        RidgeExpr {
+            source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
+            sharpness: self.sharpness.var(snarl),
+            offset: self.offset.var(snarl),
+        }
+    }
+}
+
+impl Default for RidgeNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            sharpness: NodeValue::Value(Ridge::<AnySeedable>::DEFAULT_SHARPNESS),
+            offset: NodeValue::Value(Ridge::<AnySeedable>::DEFAULT_OFFSET),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RigidFractalNode {
     pub image: Image,
 
+    #[serde(default = "AlgorithmVersion::first")]
+    pub algorithm: AlgorithmVersion,
+
     pub source_ty: SourceType,
     pub seed: NodeValue<u32>,
+
+    #[serde(default)]
+    pub master_seed: Option<u32>,
     pub octaves: NodeValue<u32>,
     pub frequency: NodeValue<f64>,
     pub lacunarity: NodeValue<f64>,
     pub persistence: NodeValue<f64>,
     pub attenuation: NodeValue<f64>,
+
+    /// The weight of one more octave after the whole ones, like fractal nodes.
+    #[serde(default)]
+    pub octave_remainder: NodeValue<f64>,
 }
 
 impl RigidFractalNode {
-    fn expr(&self, snarl: &Snarl<NoiseNode>) -> RigidFractalExpr {
-        RigidFractalExpr {
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> Expr {
+        Expr::RidgedMulti(RigidFractalExpr {
             source_ty: self.source_ty,
-            seed: self.seed.var(snarl),
+            seed: seed_var(self.seed, self.master_seed, snarl),
             octaves: self.octaves.var(snarl),
             frequency: self.frequency.var(snarl),
             lacunarity: self.lacunarity.var(snarl),
             persistence: self.persistence.var(snarl),
             attenuation: self.attenuation.var(snarl),
-        }
+            octave_remainder: self.octave_remainder.var(snarl),
+        })
     }
 }
 
@@ -1113,13 +3092,16 @@ impl Default for RigidFractalNode {
     fn default() -> Self {
         Self {
             image: Default::default(),
+            algorithm: Default::default(),
             source_ty: Default::default(),
             seed: NodeValue::Value(RigidFractal::<AnySeedable>::DEFAULT_SEED),
+            master_seed: None,
             octaves: NodeValue::Value(RigidFractal::<AnySeedable>::DEFAULT_OCTAVE_COUNT as _),
             frequency: NodeValue::Value(RigidFractal::<AnySeedable>::DEFAULT_FREQUENCY),
             lacunarity: NodeValue::Value(RigidFractal::<AnySeedable>::DEFAULT_LACUNARITY),
             persistence: NodeValue::Value(RigidFractal::<AnySeedable>::DEFAULT_PERSISTENCE),
             attenuation: NodeValue::Value(RigidFractal::<AnySeedable>::DEFAULT_ATTENUATION),
+            octave_remainder: Default::default(),
         }
     }
 }
@@ -1133,6 +3115,12 @@ pub struct ScaleBiasNode {
 }
 
 impl ScaleBiasNode {
+    /// Common mappings as a label, description and the scale and bias which produce them.
+    pub const PRESETS: [(&'static str, &'static str, [f64; 2]); 2] = [
+        ("0‥1", "Normalize [-1, 1] to [0, 1]", [0.5, 0.5]),
+        ("±1", "Expand [0, 1] to [-1, 1]", [2.0, -1.0]),
+    ];
+
     fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> ScaleBiasExpr {
         ScaleBiasExpr {
             source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
@@ -1140,6 +3128,14 @@ impl ScaleBiasNode {
             bias: self.bias.var(snarl),
         }
     }
+
+    /// Returns the range of output values for input values in [-1, 1].
+    pub fn output_range(&self, snarl: &Snarl<NoiseNode>) -> [f64; 2] {
+        let (scale, bias) = (self.scale.eval(snarl), self.bias.eval(snarl));
+        let (lower, upper) = (bias - scale, bias + scale);
+
+        [lower.min(upper), lower.max(upper)]
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -1152,6 +3148,19 @@ pub struct SelectNode {
 }
 
 impl SelectNode {
+    /// Returns the lower bound, upper bound and falloff, with the falloff limited the same way
+    /// the noise function does.
+    pub fn bounds(&self, snarl: &Snarl<NoiseNode>) -> [f64; 3] {
+        let lower_bound = self.lower_bound.eval(snarl);
+        let upper_bound = self.upper_bound.eval(snarl);
+        let falloff = self
+            .falloff
+            .eval(snarl)
+            .min((upper_bound - lower_bound) * 0.5);
+
+        [lower_bound, upper_bound, falloff]
+    }
+
     fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> SelectExpr {
         SelectExpr {
             sources: (0..2)
@@ -1178,18 +3187,20 @@ impl Default for SelectNode {
     }
 }
 
-impl Default for SourceType {
-    fn default() -> Self {
-        Self::Perlin
-    }
-}
-
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct TerraceNode {
     pub image: Image,
 
     pub inverted: bool,
     pub control_point_node_indices: Vec<Option<usize>>,
+
+    /// Values which are stored in the node instead of as graph nodes.
+    #[serde(default)]
+    pub inline_control_points: Vec<f64>,
+
+    /// The minimum number of control point pins shown, including empty pins.
+    #[serde(default)]
+    pub pin_count: usize,
 }
 
 impl TerraceNode {
@@ -1208,11 +3219,58 @@ impl TerraceNode {
                         _ => unreachable!(),
                     })
                 })
+                .chain(
+                    self.inline_control_points
+                        .iter()
+                        .copied()
+                        .map(Variable::Anonymous),
+                )
                 .collect(),
         }
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ThresholdNode {
+    pub image: Image,
+
+    pub mode: ThresholdMode,
+    pub off_threshold: NodeValue<f64>,
+    pub on_threshold: NodeValue<f64>,
+
+    /// The most samples taken along the X axis while resolving the hysteresis band.
+    #[serde(default = "ThresholdNode::default_scan_steps")]
+    pub scan_steps: NodeValue<u32>,
+}
+
+impl ThresholdNode {
+    fn default_scan_steps() -> NodeValue<u32> {
+        NodeValue::Value(Threshold::<AnySeedable>::DEFAULT_SCAN_STEPS)
+    }
+
+    fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> ThresholdExpr {
+        ThresholdExpr {
+            source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
+            mode: self.mode,
+            off_threshold: self.off_threshold.var(snarl),
+            on_threshold: self.on_threshold.var(snarl),
+            scan_steps: self.scan_steps.var(snarl),
+        }
+    }
+}
+
+impl Default for ThresholdNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            mode: ThresholdMode::Hysteresis,
+            off_threshold: NodeValue::Value(Threshold::<AnySeedable>::DEFAULT_OFF_THRESHOLD),
+            on_threshold: NodeValue::Value(Threshold::<AnySeedable>::DEFAULT_ON_THRESHOLD),
+            scan_steps: Self::default_scan_steps(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TransformNode {
     pub image: Image,
@@ -1254,22 +3312,64 @@ impl TransformNode {
 pub struct TurbulenceNode {
     pub image: Image,
 
+    #[serde(default = "AlgorithmVersion::first")]
+    pub algorithm: AlgorithmVersion,
+
     pub source_ty: SourceType,
     pub seed: NodeValue<u32>,
+
+    #[serde(default)]
+    pub master_seed: Option<u32>,
     pub frequency: NodeValue<f64>,
     pub power: NodeValue<f64>,
     pub roughness: NodeValue<u32>,
+
+    /// Per-axis frequency overrides, shown as pins following roughness.
+    #[serde(default)]
+    pub axis_frequencies: Option<[NodeValue<f64>; 3]>,
+
+    /// Per-axis power overrides, shown as pins following the per-axis frequencies.
+    #[serde(default)]
+    pub axis_powers: Option<[NodeValue<f64>; 3]>,
 }
 
 impl TurbulenceNode {
+    pub fn axis_value(&self, input: usize) -> Option<NodeValue<f64>> {
+        match input {
+            5..=7 => self.axis_frequencies.map(|values| values[input - 5]),
+            8..=10 => self.axis_powers.map(|values| values[input - 8]),
+            _ => None,
+        }
+    }
+
+    pub fn axis_value_mut(&mut self, input: usize) -> Option<&mut NodeValue<f64>> {
+        match input {
+            5..=7 => self
+                .axis_frequencies
+                .as_mut()
+                .map(|values| &mut values[input - 5]),
+            8..=10 => self
+                .axis_powers
+                .as_mut()
+                .map(|values| &mut values[input - 8]),
+            _ => None,
+        }
+    }
+
     fn expr(&self, node_idx: usize, snarl: &Snarl<NoiseNode>) -> TurbulenceExpr {
         TurbulenceExpr {
             source: in_pin_expr_or_const(snarl, node_idx, 0, 0.0),
             source_ty: self.source_ty,
-            seed: self.seed.var(snarl),
+            seed: seed_var(self.seed, self.master_seed, snarl),
             frequency: self.frequency.var(snarl),
             power: self.power.var(snarl),
             roughness: self.roughness.var(snarl),
+            axis_frequencies: self
+                .axis_frequencies
+                .map(|values| values.map(|value| value.var(snarl))),
+            axis_powers: self
+                .axis_powers
+                .map(|values| values.map(|value| value.var(snarl))),
         }
     }
 }
@@ -1278,13 +3378,17 @@ impl Default for TurbulenceNode {
     fn default() -> Self {
         Self {
             image: Default::default(),
+            algorithm: Default::default(),
             source_ty: Default::default(),
             seed: NodeValue::Value(Turbulence::<AnySeedable, AnySeedable>::DEFAULT_SEED),
+            master_seed: None,
             frequency: NodeValue::Value(Turbulence::<AnySeedable, AnySeedable>::DEFAULT_FREQUENCY),
             power: NodeValue::Value(Turbulence::<AnySeedable, AnySeedable>::DEFAULT_POWER),
             roughness: NodeValue::Value(
                 Turbulence::<AnySeedable, AnySeedable>::DEFAULT_ROUGHNESS as _,
             ),
+            axis_frequencies: None,
+            axis_powers: None,
         }
     }
 }
@@ -1304,7 +3408,13 @@ impl UnaryNode {
 pub struct WorleyNode {
     pub image: Image,
 
+    #[serde(default = "AlgorithmVersion::first")]
+    pub algorithm: AlgorithmVersion,
+
     pub seed: NodeValue<u32>,
+
+    #[serde(default)]
+    pub master_seed: Option<u32>,
     pub frequency: NodeValue<f64>,
     pub distance_fn: DistanceFunction,
     pub return_ty: ReturnType,
@@ -1313,7 +3423,7 @@ pub struct WorleyNode {
 impl WorleyNode {
     fn expr(&self, snarl: &Snarl<NoiseNode>) -> WorleyExpr {
         WorleyExpr {
-            seed: self.seed.var(snarl),
+            seed: seed_var(self.seed, self.master_seed, snarl),
             frequency: self.frequency.var(snarl),
             distance_fn: self.distance_fn,
             return_ty: self.return_ty,
@@ -1325,7 +3435,9 @@ impl Default for WorleyNode {
     fn default() -> Self {
         Self {
             image: Default::default(),
+            algorithm: Default::default(),
             seed: NodeValue::Value(Worley::DEFAULT_SEED),
+            master_seed: None,
             frequency: NodeValue::Value(Worley::DEFAULT_FREQUENCY),
             distance_fn: DistanceFunction::Euclidean,
             return_ty: ReturnType::Value,
@@ -0,0 +1,71 @@
+use egui::{Context, Id, RichText, TextEdit, Ui, Window};
+
+/// Collapsible window containing the notes of the project, which are written as plain text with
+/// a few markdown-style prefixes: `#` and `##` for headings and `-` or `*` for list items.
+#[derive(Default)]
+pub struct Notes {
+    pub text: String,
+    preview: bool,
+}
+
+impl Notes {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            preview: false,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        Window::new("Notes")
+            .id(Id::new("notes"))
+            .default_open(false)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.preview, false, "Edit");
+                    ui.selectable_value(&mut self.preview, true, "Preview");
+                });
+
+                ui.separator();
+
+                if self.preview {
+                    if self.text.trim().is_empty() {
+                        ui.weak("No notes");
+                    } else {
+                        Self::show_preview(ui, &self.text);
+                    }
+                } else {
+                    ui.add(
+                        TextEdit::multiline(&mut self.text)
+                            .desired_rows(12)
+                            .desired_width(f32::INFINITY)
+                            .hint_text(
+                                "Intent, required engine settings, expected output ranges...",
+                            ),
+                    );
+                }
+            });
+    }
+
+    fn show_preview(ui: &mut Ui, text: &str) {
+        for line in text.lines() {
+            let line = line.trim_end();
+
+            if let Some(heading) = line.strip_prefix("## ") {
+                ui.label(RichText::new(heading).strong());
+            } else if let Some(heading) = line.strip_prefix("# ") {
+                ui.heading(heading);
+            } else if let Some(item) = line.strip_prefix("- ").or(line.strip_prefix("* ")) {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("•");
+                    ui.label(item);
+                });
+            } else if line.is_empty() {
+                ui.add_space(ui.spacing().item_spacing.y * 2.0);
+            } else {
+                ui.label(line);
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use {
+    super::{node::NoiseNode, view::Viewer},
+    egui::{collapsing_header::CollapsingState, Context, Id, Label, RichText, Sense, Ui, Window},
+    egui_snarl::{ui::SnarlViewer, InPinId, OutPinId, Snarl},
+};
+
+/// Collapsible window which lists the nodes of the graph hierarchically, starting from the nodes
+/// which do not output to any other node.
+#[derive(Default)]
+pub struct Outline {
+    /// The node clicked in the outline, highlighted in the graph.
+    pub focused_node_idx: Option<usize>,
+
+    /// The node hovered in the outline, highlighted in the graph.
+    pub highlighted_node_idx: Option<usize>,
+
+    dragged_control_point: Option<(usize, usize)>,
+}
+
+impl Outline {
+    fn focus(&mut self, node_idx: usize, snarl: &Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        if self.focused_node_idx == Some(node_idx) {
+            self.focused_node_idx = None;
+        } else {
+            self.focused_node_idx = Some(node_idx);
+
+            if snarl.get_node(node_idx).has_image() {
+                *viewer.selected_node_idx = Some(node_idx);
+            }
+        }
+    }
+
+    fn node_text(
+        &self,
+        ui: &Ui,
+        node_idx: usize,
+        snarl: &Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) -> RichText {
+        let text = RichText::new(format!(
+            "{} #{node_idx}",
+            viewer.title(snarl.get_node(node_idx))
+        ));
+
+        if *viewer.hovered_node_idx == Some(node_idx) || self.focused_node_idx == Some(node_idx) {
+            text.background_color(ui.visuals().selection.bg_fill)
+                .color(ui.visuals().selection.stroke.color)
+        } else {
+            text
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context, snarl: &mut Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        self.highlighted_node_idx = None;
+
+        // Control points are moved after the outline has been shown because it borrows the graph
+        let mut moved_control_point = None;
+
+        Window::new("Outline")
+            .id(Id::new("outline"))
+            .default_open(false)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                let root_node_indices = snarl
+                    .node_indices()
+                    .map(|(node_idx, _)| node_idx)
+                    .filter(|&node_idx| {
+                        snarl
+                            .out_pin(OutPinId {
+                                node: node_idx,
+                                output: 0,
+                            })
+                            .remotes
+                            .is_empty()
+                    })
+                    .collect::<Vec<_>>();
+
+                if root_node_indices.is_empty() {
+                    ui.label("Right-click the graph to add nodes");
+                }
+
+                let mut path = vec![];
+                for node_idx in root_node_indices {
+                    self.show_node(
+                        ui,
+                        node_idx,
+                        snarl,
+                        viewer,
+                        &mut path,
+                        &mut moved_control_point,
+                    );
+                }
+            });
+
+        if ctx.input(|input| input.pointer.any_released()) {
+            self.dragged_control_point = None;
+        }
+
+        if let Some((node_idx, from, to)) = moved_control_point.filter(|_| !viewer.read_only) {
+            NoiseNode::move_control_point(node_idx, from, to, snarl);
+            viewer.updated_node_indices.insert(node_idx);
+        }
+    }
+
+    fn show_control_points(
+        &mut self,
+        ui: &mut Ui,
+        node_idx: usize,
+        control_point_node_indices: &[Option<usize>],
+        snarl: &Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+        moved_control_point: &mut Option<(usize, usize, usize)>,
+    ) {
+        for (control_point_idx, control_point_node_idx) in
+            control_point_node_indices.iter().copied().enumerate()
+        {
+            let text = if let Some(control_point_node_idx) = control_point_node_idx {
+                self.node_text(ui, control_point_node_idx, snarl, viewer)
+            } else {
+                RichText::new("Empty").weak()
+            };
+
+            let is_drop_target = matches!(
+                self.dragged_control_point,
+                Some((dragged_node_idx, dragged_control_point_idx))
+                    if dragged_node_idx == node_idx && dragged_control_point_idx != control_point_idx
+            );
+
+            let res = ui
+                .add(Label::new(text).sense(Sense::click_and_drag()))
+                .on_hover_text("Drag to reorder");
+
+            if res.drag_started() {
+                self.dragged_control_point = Some((node_idx, control_point_idx));
+            }
+
+            if is_drop_target && ui.rect_contains_pointer(res.rect) {
+                ui.painter().hline(
+                    res.rect.x_range(),
+                    res.rect.bottom(),
+                    ui.visuals().selection.stroke,
+                );
+
+                if ui.input(|input| input.pointer.any_released()) {
+                    let (_, from) = self.dragged_control_point.unwrap();
+                    *moved_control_point = Some((node_idx, from, control_point_idx));
+                }
+            }
+
+            if let Some(control_point_node_idx) = control_point_node_idx {
+                if res.clicked() {
+                    self.focus(control_point_node_idx, snarl, viewer);
+                }
+
+                if res.hovered() {
+                    self.highlighted_node_idx = Some(control_point_node_idx);
+                }
+            }
+        }
+    }
+
+    fn show_node(
+        &mut self,
+        ui: &mut Ui,
+        node_idx: usize,
+        snarl: &Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+        path: &mut Vec<usize>,
+        moved_control_point: &mut Option<(usize, usize, usize)>,
+    ) {
+        let node = snarl.get_node(node_idx);
+        let text = self.node_text(ui, node_idx, snarl, viewer);
+
+        // Curve and Terrace nodes list control points separately so that they may be reordered
+        let (source_inputs, control_point_node_indices) = match node.control_point_node_indices() {
+            Some(control_point_node_indices) => (1, control_point_node_indices),
+            None => (viewer.inputs(node), [].as_slice()),
+        };
+
+        let input_node_indices = (0..source_inputs)
+            .filter_map(|input| {
+                snarl
+                    .in_pin(InPinId {
+                        node: node_idx,
+                        input,
+                    })
+                    .remotes
+                    .first()
+                    .map(|remote| remote.node)
+            })
+            .collect::<Vec<_>>();
+
+        let res = if input_node_indices.is_empty() && control_point_node_indices.is_empty() {
+            ui.add(Label::new(text).sense(Sense::click()))
+        } else if path.contains(&node_idx) {
+            ui.label(text.italics()).on_hover_text("Cycle")
+        } else {
+            path.push(node_idx);
+
+            let id = Id::new(("outline", path.as_slice()));
+            let (_, res, _) = CollapsingState::load_with_default_open(ui.ctx(), id, true)
+                .show_header(ui, |ui| ui.add(Label::new(text).sense(Sense::click())))
+                .body(|ui| {
+                    for input_node_idx in input_node_indices {
+                        self.show_node(
+                            ui,
+                            input_node_idx,
+                            snarl,
+                            viewer,
+                            path,
+                            moved_control_point,
+                        );
+                    }
+
+                    if !control_point_node_indices.is_empty() {
+                        ui.separator();
+                        self.show_control_points(
+                            ui,
+                            node_idx,
+                            control_point_node_indices,
+                            snarl,
+                            viewer,
+                            moved_control_point,
+                        );
+                    }
+                });
+
+            path.pop();
+
+            res.inner
+        };
+
+        if res.clicked() {
+            self.focus(node_idx, snarl, viewer);
+        }
+
+        if res.hovered() {
+            self.highlighted_node_idx = Some(node_idx);
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use {
+    super::{node::NoiseNode, view::Viewer},
+    egui::{CollapsingHeader, Context, DragValue, Grid, Id, Label, RichText, Sense, Ui, Window},
+    egui_snarl::{ui::SnarlViewer, InPinId, OutPinId, Snarl},
+    noise_graph::Backend,
+    std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        hint::black_box,
+        time::Instant,
+    },
+};
+
+/// The measured cost of a node, in microseconds per sample.
+struct Measurement {
+    /// The cost of this node without the noise inputs it evaluates.
+    self_cost: f64,
+
+    /// The fraction of the budget this node may use by itself: each output shares the budget
+    /// equally between the nodes it evaluates, and nodes used by several outputs get the least.
+    share: f64,
+
+    /// The number of nodes evaluated per sample, including this node and repeated inputs.
+    subtree_len: usize,
+
+    total_cost: f64,
+}
+
+/// Collapsible window which times the evaluation of each noise node over its preview and flags
+/// the nodes and subtrees which exceed their share of a per-sample budget.
+pub struct Profiler {
+    /// The budget of each output, in microseconds per sample.
+    budget: f64,
+
+    /// The node hovered in the profiler, highlighted in the graph.
+    pub highlighted_node_idx: Option<usize>,
+
+    measurements: HashMap<usize, Measurement>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self {
+            budget: Self::DEFAULT_BUDGET,
+            highlighted_node_idx: None,
+            measurements: Default::default(),
+        }
+    }
+}
+
+impl Profiler {
+    const DEFAULT_BUDGET: f64 = 2.0;
+
+    /// The number of samples along each side of a preview which are timed.
+    const RESOLUTION: usize = 64;
+
+    /// The number of times samples are timed; the fastest run is used to reduce noise.
+    const RUNS: usize = 3;
+
+    pub fn clear(&mut self) {
+        self.measurements.clear();
+        self.highlighted_node_idx = None;
+    }
+
+    /// Returns the noise nodes connected to the inputs of a node, once per connected input.
+    fn noise_inputs(
+        node_idx: usize,
+        snarl: &Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) -> Vec<usize> {
+        (0..viewer.inputs(snarl.get_node(node_idx)))
+            .flat_map(|input| {
+                snarl
+                    .in_pin(InPinId {
+                        node: node_idx,
+                        input,
+                    })
+                    .remotes
+                    .into_iter()
+                    .map(|remote| remote.node)
+            })
+            .filter(|&node_idx| snarl.get_node(node_idx).has_image())
+            .collect()
+    }
+
+    fn measure(&mut self, snarl: &Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        self.measurements.clear();
+
+        let node_indices = snarl
+            .node_indices()
+            .filter_map(|(node_idx, node)| node.has_image().then_some(node_idx))
+            .collect::<Vec<_>>();
+        let inputs = node_indices
+            .iter()
+            .map(|&node_idx| (node_idx, Self::noise_inputs(node_idx, snarl, viewer)))
+            .collect::<HashMap<_, _>>();
+
+        let mut subtree_lens = HashMap::new();
+        for &node_idx in &node_indices {
+            Self::subtree_len(node_idx, &inputs, &mut subtree_lens);
+        }
+
+        // Each output divides the budget between every node it evaluates
+        let mut shares = HashMap::<usize, f64>::new();
+        for &node_idx in &node_indices {
+            let is_output = snarl
+                .out_pin(OutPinId {
+                    node: node_idx,
+                    output: 0,
+                })
+                .remotes
+                .is_empty();
+
+            if is_output {
+                let share = 1.0 / subtree_lens[&node_idx] as f64;
+                let mut stack = vec![node_idx];
+
+                while let Some(node_idx) = stack.pop() {
+                    let node_share = shares.entry(node_idx).or_insert(share);
+                    *node_share = node_share.min(share);
+                    stack.extend(inputs[&node_idx].iter().copied());
+                }
+            }
+        }
+
+        let total_costs = node_indices
+            .iter()
+            .map(|&node_idx| (node_idx, Self::sample_cost(node_idx, snarl, viewer.backend)))
+            .collect::<HashMap<_, _>>();
+
+        for &node_idx in &node_indices {
+            let total_cost = total_costs[&node_idx];
+            let input_cost = inputs[&node_idx]
+                .iter()
+                .map(|node_idx| total_costs[node_idx])
+                .sum::<f64>();
+
+            self.measurements.insert(
+                node_idx,
+                Measurement {
+                    self_cost: (total_cost - input_cost).max(0.0),
+                    share: shares.get(&node_idx).copied().unwrap_or(1.0),
+                    subtree_len: subtree_lens[&node_idx],
+                    total_cost,
+                },
+            );
+        }
+    }
+
+    /// Times the evaluation of a node over its preview window and returns the microseconds per
+    /// sample, not including the time taken to build the noise functions.
+    fn sample_cost(node_idx: usize, snarl: &Snarl<NoiseNode>, backend: Backend) -> f64 {
+        let node = snarl.get_node(node_idx);
+        let Some(image) = node.image() else {
+            return 0.0;
+        };
+
+        let noise = node.expr(node_idx, snarl).noise_with(backend.get());
+        let step = 1.0 / Self::RESOLUTION as f64;
+        let half_step = step / 2.0;
+
+        // Sample positions match those used by the image threads
+        let points = (0..Self::RESOLUTION)
+            .flat_map(|row| (0..Self::RESOLUTION).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                [
+                    (col as f64 * step + half_step + image.y) * image.scale,
+                    (row as f64 * step + half_step + image.x) * image.scale,
+                    0.0,
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let seconds = (0..Self::RUNS)
+            .map(|_| {
+                let start = Instant::now();
+
+                for point in &points {
+                    black_box(noise.get(*point));
+                }
+
+                start.elapsed().as_secs_f64()
+            })
+            .fold(f64::INFINITY, f64::min);
+
+        seconds * 1_000_000.0 / points.len() as f64
+    }
+
+    pub fn show(&mut self, ctx: &Context, snarl: &Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        self.highlighted_node_idx = None;
+
+        Window::new("Profiler")
+            .id(Id::new("profiler"))
+            .default_open(false)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Budget");
+                    ui.add(
+                        DragValue::new(&mut self.budget)
+                            .clamp_range(0.01..=1_000.0)
+                            .speed(0.01)
+                            .suffix(" µs/sample"),
+                    )
+                    .on_hover_text("The cost allowed for each output, shared by its nodes");
+
+                    if ui.button("Measure").clicked() {
+                        self.measure(snarl, viewer);
+                    }
+                });
+
+                // Nodes may have been removed since they were measured
+                let node_indices = snarl
+                    .node_indices()
+                    .map(|(node_idx, _)| node_idx)
+                    .filter(|node_idx| self.measurements.contains_key(node_idx))
+                    .collect::<HashSet<_>>();
+
+                if node_indices.is_empty() {
+                    ui.label("Click Measure to time each node over its preview");
+
+                    return;
+                }
+
+                ui.separator();
+                self.show_nodes(ui, &node_indices, snarl, viewer);
+
+                CollapsingHeader::new("By Node Type")
+                    .id_source("profiler_types")
+                    .show(ui, |ui| {
+                        self.show_node_types(ui, &node_indices, snarl, viewer);
+                    });
+            });
+    }
+
+    fn show_node_types(
+        &self,
+        ui: &mut Ui,
+        node_indices: &HashSet<usize>,
+        snarl: &Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) {
+        // Sorted by title so that the rows do not move around between frames
+        let mut node_types = BTreeMap::<String, (usize, f64, usize)>::new();
+        for &node_idx in node_indices {
+            let measurement = &self.measurements[&node_idx];
+            let (count, self_cost, over_budget) = node_types
+                .entry(viewer.title(snarl.get_node(node_idx)))
+                .or_default();
+
+            *count += 1;
+            *self_cost += measurement.self_cost;
+
+            if measurement.self_cost > measurement.share * self.budget {
+                *over_budget += 1;
+            }
+        }
+
+        Grid::new("profiler_types_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Type");
+                ui.strong("Count");
+                ui.strong("Mean Self");
+                ui.strong("Over Budget");
+                ui.end_row();
+
+                for (title, (count, self_cost, over_budget)) in node_types {
+                    ui.label(title);
+                    ui.label(count.to_string());
+                    ui.label(format!("{:.3} µs", self_cost / count as f64));
+
+                    if over_budget > 0 {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {over_budget}"));
+                    } else {
+                        ui.label("0");
+                    }
+
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_nodes(
+        &mut self,
+        ui: &mut Ui,
+        node_indices: &HashSet<usize>,
+        snarl: &Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) {
+        let mut node_indices = node_indices.iter().copied().collect::<Vec<_>>();
+        node_indices.sort_unstable_by(|lhs, rhs| {
+            self.measurements[rhs]
+                .total_cost
+                .total_cmp(&self.measurements[lhs].total_cost)
+                .then(lhs.cmp(rhs))
+        });
+
+        Grid::new("profiler_grid").striped(true).show(ui, |ui| {
+            ui.strong("Node");
+            ui.strong("Self")
+                .on_hover_text("The cost of the node without its noise inputs");
+            ui.strong("Total")
+                .on_hover_text("The cost of the node and its noise inputs");
+            ui.strong("Budget")
+                .on_hover_text("The share of the budget for the node and for its subtree");
+            ui.end_row();
+
+            for node_idx in node_indices {
+                let measurement = &self.measurements[&node_idx];
+                let self_budget = measurement.share * self.budget;
+                let subtree_budget = self_budget * measurement.subtree_len as f64;
+                let is_self_over = measurement.self_cost > self_budget;
+                let is_subtree_over = measurement.total_cost > subtree_budget;
+
+                let mut text = RichText::new(format!(
+                    "{} #{node_idx}",
+                    viewer.title(snarl.get_node(node_idx))
+                ));
+
+                if *viewer.hovered_node_idx == Some(node_idx) {
+                    text = text
+                        .background_color(ui.visuals().selection.bg_fill)
+                        .color(ui.visuals().selection.stroke.color);
+                }
+
+                let res = ui.add(Label::new(text).sense(Sense::hover()));
+
+                if res.hovered() {
+                    self.highlighted_node_idx = Some(node_idx);
+                }
+
+                Self::show_cost(
+                    ui,
+                    measurement.self_cost,
+                    is_self_over.then_some("This node exceeds its share of the budget"),
+                );
+                Self::show_cost(
+                    ui,
+                    measurement.total_cost,
+                    is_subtree_over.then_some("This subtree exceeds its share of the budget"),
+                );
+                ui.weak(format!("{self_budget:.3} / {subtree_budget:.3} µs"));
+                ui.end_row();
+            }
+        });
+    }
+
+    /// Shows a cost, flagged with the given hover text when it is over budget.
+    fn show_cost(ui: &mut Ui, cost: f64, over_budget: Option<&str>) {
+        let text = format!("{cost:.3} µs");
+
+        if let Some(hover_text) = over_budget {
+            ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {text}"))
+                .on_hover_text(hover_text);
+        } else {
+            ui.label(text);
+        }
+    }
+
+    /// Returns the number of nodes evaluated by a node, counting inputs once per connection.
+    fn subtree_len(
+        node_idx: usize,
+        inputs: &HashMap<usize, Vec<usize>>,
+        subtree_lens: &mut HashMap<usize, usize>,
+    ) -> usize {
+        if let Some(&len) = subtree_lens.get(&node_idx) {
+            return len;
+        }
+
+        let len = 1 + inputs[&node_idx]
+            .iter()
+            .map(|&node_idx| Self::subtree_len(node_idx, inputs, subtree_lens))
+            .sum::<usize>();
+        subtree_lens.insert(node_idx, len);
+
+        len
+    }
+}
@@ -0,0 +1,25 @@
+use {
+    egui::{show_tooltip_at_pointer, Id, Ui},
+    noise::NoiseFn,
+    noise_graph::{Backend, Expr},
+};
+
+/// Shows the point under the pointer and the value of an expression there in a tooltip, copying
+/// both to the clipboard when `clicked`.
+///
+/// The expression is evaluated at the point on its own, so the value is exact instead of the gray
+/// level of a preview texture.
+pub fn show(ui: &Ui, id: Id, backend: Backend, expr: &Expr, point: [f64; 2], clicked: bool) {
+    let [x, y] = point;
+    let value = expr.noise_with(backend.get()).get([x, y, 0.0]);
+
+    show_tooltip_at_pointer(ui.ctx(), id, |ui| {
+        ui.label(format!("Point: [{x:.4}, {y:.4}]"));
+        ui.label(format!("Value: {value:.6}"));
+        ui.weak("Click to copy");
+    });
+
+    if clicked {
+        ui.output_mut(|output| output.copied_text = format!("[{x:?}, {y:?}, 0.0]: {value:?}"));
+    }
+}
@@ -0,0 +1,342 @@
+use {
+    super::{
+        node::{Image, NodeIds, NoiseNode},
+        view::Viewer,
+    },
+    base64::{engine::general_purpose::STANDARD, Engine},
+    egui_snarl::{ui::SnarlViewer, InPinId, OutPinId, Snarl},
+    log::warn,
+    std::{fmt::Write, fs::write, path::Path},
+};
+
+/// Encodes the pixels of an image as an 8-bit grayscale bitmap, which browsers display directly.
+fn bitmap(image: &Image) -> Option<Vec<u8>> {
+    let [width, height] = image.texture.as_ref()?.size();
+
+    if image.pixels.len() != width * height {
+        return None;
+    }
+
+    const HEADER_LEN: usize = 14 + 40 + 256 * 4;
+
+    let row_len = width.next_multiple_of(4);
+    let file_len = HEADER_LEN + row_len * height;
+    let mut data = Vec::with_capacity(file_len);
+
+    // File header
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&(file_len as u32).to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&(HEADER_LEN as u32).to_le_bytes());
+
+    // Info header: a negative height stores rows from the top down
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&(width as i32).to_le_bytes());
+    data.extend_from_slice(&(-(height as i32)).to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&8u16.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&((row_len * height) as u32).to_le_bytes());
+    data.extend_from_slice(&2835u32.to_le_bytes());
+    data.extend_from_slice(&2835u32.to_le_bytes());
+    data.extend_from_slice(&256u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+
+    // Grayscale palette
+    for gray in 0..=255u8 {
+        data.extend_from_slice(&[gray, gray, gray, 0]);
+    }
+
+    for row in image.pixels.chunks_exact(width) {
+        data.extend_from_slice(row);
+        data.resize(data.len() + row_len - width, 0);
+    }
+
+    Some(data)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Splits serialized fields or items at the commas which are not nested in any brackets.
+fn split_top_level(src: &str) -> Vec<&str> {
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (idx, char) in src.char_indices() {
+        if quoted {
+            match char {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => quoted = false,
+                _ => escaped = false,
+            }
+
+            continue;
+        }
+
+        match char {
+            '"' => quoted = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(src[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => (),
+        }
+    }
+
+    items.push(src[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Formats a serialized parameter value, showing node values as plain values and connected
+/// values as the node they come from.
+fn format_value(value: &str) -> String {
+    if let Some(inner) = value
+        .strip_prefix("Value(")
+        .and_then(|value| value.strip_suffix(')'))
+    {
+        inner.to_owned()
+    } else if let Some(inner) = value
+        .strip_prefix("Node(")
+        .and_then(|value| value.strip_suffix(')'))
+    {
+        format!("#{inner}")
+    } else if let Some(inner) = value
+        .strip_prefix('(')
+        .and_then(|value| value.strip_suffix(')'))
+    {
+        format!(
+            "({})",
+            split_top_level(inner)
+                .into_iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    } else {
+        value.to_owned()
+    }
+}
+
+/// HTML document describing a project: notes, output images, named variables and the parameters
+/// of every node, for design documentation and reviews.
+pub struct Report {
+    html: String,
+}
+
+impl Report {
+    pub fn new(
+        title: &str,
+        notes: &str,
+        snarl: &Snarl<NoiseNode>,
+        node_ids: &NodeIds,
+        viewer: &mut Viewer<'_>,
+    ) -> Self {
+        let mut html = String::new();
+
+        let title = escape(title);
+        writeln!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+            <style>\n\
+            body {{ font-family: sans-serif; margin: 2em; }}\n\
+            img {{ image-rendering: pixelated; border: 1px solid #888; }}\n\
+            table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+            td, th {{ border: 1px solid #ccc; padding: 2px 8px; text-align: left; }}\n\
+            .node {{ break-inside: avoid; display: flex; gap: 1em; }}\n\
+            .output {{ display: inline-block; margin-right: 1em; text-align: center; }}\n\
+            </style>\n</head>\n<body>\n<h1>{title}</h1>"
+        )
+        .unwrap();
+
+        if !notes.trim().is_empty() {
+            writeln!(
+                html,
+                "<h2>Notes</h2>\n<p style=\"white-space: pre-wrap\">{}</p>",
+                escape(notes.trim())
+            )
+            .unwrap();
+        }
+
+        let mut node_indices = snarl
+            .node_indices()
+            .map(|(node_idx, _)| node_idx)
+            .collect::<Vec<_>>();
+        node_indices.sort_unstable();
+
+        // Nodes link to their section by identifier, which stays the same as the graph is edited
+        let anchor = |node_idx: usize| {
+            node_ids
+                .id(node_idx)
+                .map_or_else(|| format!("node-{node_idx}"), |id| format!("node-{id}"))
+        };
+        let node_name = |node_idx: usize, viewer: &mut Viewer<'_>| {
+            format!(
+                "<a href=\"#{}\">#{node_idx} {}</a>",
+                anchor(node_idx),
+                escape(&viewer.title(snarl.get_node(node_idx)))
+            )
+        };
+        let output_node_indices = |node_idx: usize| {
+            snarl
+                .out_pin(OutPinId {
+                    node: node_idx,
+                    output: 0,
+                })
+                .remotes
+                .iter()
+                .map(|remote| remote.node)
+                .collect::<Vec<_>>()
+        };
+
+        // Outputs are the images which are not used by any other node
+        html.push_str("<h2>Output</h2>\n");
+
+        for &node_idx in &node_indices {
+            let node = snarl.get_node(node_idx);
+
+            if node.has_image() && output_node_indices(node_idx).is_empty() {
+                writeln!(
+                    html,
+                    "<div class=\"output\">{}<br>{}</div>",
+                    Self::image(node.image(), 256),
+                    node_name(node_idx, viewer)
+                )
+                .unwrap();
+            }
+        }
+
+        html.push_str("<h2>Named Variables</h2>\n");
+        html.push_str("<table>\n<tr><th>Name</th><th>Value</th><th>Used By</th></tr>\n");
+
+        for &node_idx in &node_indices {
+            let (name, value) = match snarl.get_node(node_idx) {
+                NoiseNode::F64(node) => (&node.name, node.value.to_string()),
+                NoiseNode::U32(node) => (&node.name, node.value.to_string()),
+                _ => continue,
+            };
+
+            let used_by = output_node_indices(node_idx)
+                .into_iter()
+                .map(|node_idx| node_name(node_idx, viewer))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            writeln!(
+                html,
+                "<tr><td>{}</td><td>{value}</td><td>{used_by}</td></tr>",
+                escape(name)
+            )
+            .unwrap();
+        }
+
+        html.push_str("</table>\n<h2>Nodes</h2>\n");
+
+        for &node_idx in &node_indices {
+            let node = snarl.get_node(node_idx);
+
+            writeln!(
+                html,
+                "<h3 id=\"{}\">#{node_idx} {}</h3>\n<div class=\"node\">",
+                anchor(node_idx),
+                escape(&viewer.title(node))
+            )
+            .unwrap();
+
+            if node.has_image() {
+                html.push_str(&Self::image(node.image(), 128));
+            }
+
+            html.push_str("<table>\n<tr><th>Parameter</th><th>Value</th></tr>\n");
+
+            let input_names = (0..viewer.inputs(node))
+                .filter_map(|input| {
+                    snarl
+                        .in_pin(InPinId {
+                            node: node_idx,
+                            input,
+                        })
+                        .remotes
+                        .first()
+                        .map(|remote| node_name(remote.node, viewer))
+                })
+                .collect::<Vec<_>>();
+
+            if !input_names.is_empty() {
+                writeln!(
+                    html,
+                    "<tr><td>inputs</td><td>{}</td></tr>",
+                    input_names.join(", ")
+                )
+                .unwrap();
+            }
+
+            for (name, value) in Self::parameters(node) {
+                writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    escape(&name),
+                    escape(&value)
+                )
+                .unwrap();
+            }
+
+            html.push_str("</table>\n</div>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+
+        Self { html }
+    }
+
+    fn image(image: Option<&Image>, size: usize) -> String {
+        match image.and_then(bitmap) {
+            Some(data) => format!(
+                "<img width=\"{size}\" height=\"{size}\" src=\"data:image/bmp;base64,{}\">",
+                STANDARD.encode(&data)
+            ),
+            None => format!("<div style=\"width: {size}px\">(Not rendered)</div>"),
+        }
+    }
+
+    /// Returns the serialized fields of a node, other than its image settings.
+    fn parameters(node: &NoiseNode) -> Vec<(String, String)> {
+        let Ok(src) = ron::to_string(node) else {
+            return vec![];
+        };
+
+        // Nodes are serialized as a variant containing a struct, such as "Perlin((seed: ..))"
+        let Some(fields) = src
+            .find('(')
+            .map(|start| src[start + 1..src.len() - 1].trim())
+            .and_then(|src| src.strip_prefix('('))
+            .and_then(|src| src.strip_suffix(')'))
+        else {
+            return vec![];
+        };
+
+        split_top_level(fields)
+            .into_iter()
+            .filter_map(|field| field.split_once(':'))
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .filter(|(name, _)| *name != "image")
+            .map(|(name, value)| (name.to_owned(), format_value(value)))
+            .collect()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        write(path, &self.html).inspect_err(|_| warn!("Unable to write report"))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use {
+    super::{
+        node::{NodeId, NodeIds, NoiseNode},
+        view::Viewer,
+    },
+    egui::Pos2,
+    egui_snarl::{ui::SnarlViewer, InPinId, OutPinId, Snarl},
+    log::warn,
+    noise_graph::Backend,
+    ron::{de::from_str, ser::to_string},
+    serde::{Deserialize, Serialize},
+    std::collections::{BTreeMap, HashMap, HashSet},
+};
+
+/// A node as egui-snarl saves it.
+#[derive(Deserialize, Serialize)]
+struct SnarlNode<T> {
+    value: T,
+    pos: Pos2,
+    open: bool,
+}
+
+/// A wire as egui-snarl saves it.
+#[derive(Deserialize, Serialize)]
+struct SnarlWire {
+    out_pin: OutPinId,
+    in_pin: InPinId,
+}
+
+/// A graph as egui-snarl saves it, which is the only way to reach the positions and open states
+/// of its nodes.
+#[derive(Deserialize, Serialize)]
+struct SnarlData<T> {
+    nodes: BTreeMap<usize, SnarlNode<T>>,
+    draw_order: Vec<usize>,
+    wires: Vec<SnarlWire>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SavedNode {
+    pub node: NoiseNode,
+    pub open: bool,
+    pub pos: Pos2,
+}
+
+/// A wire from the output of a node to an input of another.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SavedWire {
+    pub from: NodeId,
+    pub input: usize,
+    pub output: usize,
+    pub to: NodeId,
+}
+
+/// A graph as saved, in which wires refer to nodes by identifier instead of by graph index, so
+/// that saved graphs do not depend on the indices egui-snarl happened to give their nodes.
+///
+/// Nodes are saved detached from each other: the inputs and control points which refer to other
+/// nodes are connected again from the wires when the graph is loaded.
+#[derive(Default, Deserialize, Serialize)]
+pub struct SavedGraph {
+    /// The nodes from the back to the front of the canvas.
+    pub draw_order: Vec<NodeId>,
+
+    pub nodes: BTreeMap<NodeId, SavedNode>,
+    pub wires: Vec<SavedWire>,
+}
+
+impl SavedGraph {
+    /// Loads the graph, returning it with the identifiers of its nodes.
+    ///
+    /// Nodes which have an identifier in `node_ids`, the identifiers of a graph this one replaces,
+    /// keep their graph index there.
+    pub fn load(self, backend: Backend, node_ids: &NodeIds) -> (Snarl<NoiseNode>, NodeIds) {
+        let mut node_indices = HashMap::with_capacity(self.nodes.len());
+        let mut used_node_indices = HashSet::with_capacity(self.nodes.len());
+
+        for &id in self.nodes.keys() {
+            if let Some(node_idx) = node_ids.node_idx(id) {
+                used_node_indices.insert(node_idx);
+                node_indices.insert(id, node_idx);
+            }
+        }
+
+        let mut free_node_indices = (0..).filter(|node_idx| !used_node_indices.contains(node_idx));
+        for &id in self.nodes.keys() {
+            node_indices
+                .entry(id)
+                .or_insert_with(|| free_node_indices.next().unwrap());
+        }
+
+        let nodes = self
+            .nodes
+            .into_iter()
+            .map(|(id, SavedNode { node, open, pos })| {
+                (
+                    node_indices[&id],
+                    SnarlNode {
+                        value: node,
+                        pos,
+                        open,
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        // Nodes missing from the draw order would not be drawn at all
+        let mut drawn = HashSet::with_capacity(nodes.len());
+        let mut draw_order = self
+            .draw_order
+            .iter()
+            .filter_map(|id| node_indices.get(id).copied())
+            .filter(|&node_idx| drawn.insert(node_idx))
+            .collect::<Vec<_>>();
+        draw_order.extend(nodes.keys().filter(|node_idx| !drawn.contains(node_idx)));
+
+        let data = SnarlData {
+            nodes,
+            draw_order,
+            wires: vec![],
+        };
+        let mut snarl = to_string(&data)
+            .ok()
+            .and_then(|data| from_str::<Snarl<NoiseNode>>(&data).ok())
+            .unwrap_or_else(|| {
+                warn!("Unable to load graph");
+                Snarl::new()
+            });
+
+        Viewer::hidden(backend, 0, |viewer| {
+            for wire in self.wires {
+                let (Some(&from), Some(&to)) =
+                    (node_indices.get(&wire.from), node_indices.get(&wire.to))
+                else {
+                    warn!(
+                        "Wire from {} to {} refers to a missing node",
+                        wire.from, wire.to
+                    );
+                    continue;
+                };
+
+                viewer.connect(
+                    &snarl.out_pin(OutPinId {
+                        node: from,
+                        output: wire.output,
+                    }),
+                    &snarl.in_pin(InPinId {
+                        node: to,
+                        input: wire.input,
+                    }),
+                    &mut snarl,
+                );
+            }
+        });
+
+        let node_ids = NodeIds::from(
+            node_indices
+                .into_iter()
+                .map(|(id, node_idx)| (node_idx, id))
+                .collect::<BTreeMap<_, _>>(),
+        );
+
+        (snarl, node_ids)
+    }
+
+    /// Saves a graph, giving identifiers to the nodes which do not have one yet.
+    pub fn new(snarl: &Snarl<NoiseNode>, node_ids: &NodeIds) -> Self {
+        Self::save(snarl, node_ids, false)
+    }
+
+    fn save(snarl: &Snarl<NoiseNode>, node_ids: &NodeIds, zero_constants: bool) -> Self {
+        let mut node_ids = node_ids.clone();
+        node_ids.update(snarl);
+
+        let src = to_string(snarl).ok();
+        let Some(data) = src
+            .as_deref()
+            .and_then(|src| from_str::<SnarlData<NoiseNode>>(src).ok())
+        else {
+            warn!("Unable to save graph");
+
+            return Self::default();
+        };
+
+        // Nodes are detached from a copy of the graph whose constants are zero
+        let zeroed = zero_constants
+            .then(|| src.as_deref().and_then(|src| from_str(src).ok()))
+            .flatten()
+            .map(|mut zeroed: Snarl<NoiseNode>| {
+                let node_indices = zeroed
+                    .node_indices()
+                    .map(|(node_idx, _)| node_idx)
+                    .collect::<Vec<_>>();
+
+                for node_idx in node_indices {
+                    match zeroed.get_node_mut(node_idx) {
+                        NoiseNode::F64(node) => node.value = 0.0,
+                        NoiseNode::U32(node) => node.value = 0,
+                        _ => (),
+                    }
+                }
+
+                zeroed
+            });
+        let constants = zeroed.as_ref().unwrap_or(snarl);
+
+        let id = |node_idx| node_ids.id(node_idx).unwrap();
+
+        Self {
+            draw_order: data.draw_order.into_iter().map(id).collect(),
+            nodes: data
+                .nodes
+                .into_iter()
+                .map(|(node_idx, mut node)| {
+                    node.value.detach(constants);
+
+                    (
+                        id(node_idx),
+                        SavedNode {
+                            node: node.value,
+                            open: node.open,
+                            pos: node.pos,
+                        },
+                    )
+                })
+                .collect(),
+            wires: data
+                .wires
+                .into_iter()
+                .map(|SnarlWire { out_pin, in_pin }| SavedWire {
+                    from: id(out_pin.node),
+                    input: in_pin.input,
+                    output: out_pin.output,
+                    to: id(in_pin.node),
+                })
+                .collect(),
+        }
+    }
+
+    /// Saves a graph as [`new`](Self::new) does, except that inputs connected to other nodes take
+    /// the values they would have if every constant were zero, so that a saved node stays the same
+    /// when the nodes it is connected to are edited.
+    pub fn without_input_values(snarl: &Snarl<NoiseNode>, node_ids: &NodeIds) -> Self {
+        Self::save(snarl, node_ids, true)
+    }
+}
@@ -0,0 +1,132 @@
+use {
+    super::node::Image,
+    egui::{Button, Color32, ColorImage, Context, TextureHandle, Ui},
+    std::{
+        collections::{hash_map::RandomState, HashMap, VecDeque},
+        hash::BuildHasher,
+    },
+};
+
+/// A seed which was replaced, with a thumbnail of the image it produced.
+struct Entry {
+    seed: u32,
+    thumbnail: Option<TextureHandle>,
+}
+
+/// Recently replaced seeds of each node (or of the master seed), most recent first.
+#[derive(Default)]
+pub struct SeedHistory {
+    entries: HashMap<Option<usize>, VecDeque<Entry>>,
+}
+
+impl SeedHistory {
+    /// The number of seeds kept for each node.
+    const LEN: usize = 8;
+
+    /// The number of image pixels along each side of a thumbnail pixel.
+    const THUMBNAIL_STEP: usize = 4;
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Records a seed which is about to be replaced, where `node_idx` is `None` for the master seed.
+    pub fn push(
+        &mut self,
+        ctx: &Context,
+        node_idx: Option<usize>,
+        seed: u32,
+        image: Option<&Image>,
+    ) {
+        let entries = self.entries.entry(node_idx).or_default();
+
+        // Seeds are only listed once, at their most recent position
+        entries.retain(|entry| entry.seed != seed);
+        entries.truncate(Self::LEN - 1);
+        entries.push_front(Entry {
+            seed,
+            thumbnail: image.and_then(Self::thumbnail).map(|thumbnail| {
+                ctx.load_texture(
+                    format!("seed{node_idx:?}-{seed}"),
+                    thumbnail,
+                    Default::default(),
+                )
+            }),
+        });
+    }
+
+    pub fn random_seed() -> u32 {
+        RandomState::new().hash_one(()) as u32
+    }
+
+    pub fn remove(&mut self, node_idx: usize) {
+        self.entries.remove(&Some(node_idx));
+    }
+
+    /// Lists the seeds of a node and returns the one which was clicked, if any.
+    pub fn show(&self, ui: &mut Ui, node_idx: Option<usize>) -> Option<u32> {
+        let Some(entries) = self
+            .entries
+            .get(&node_idx)
+            .filter(|entries| !entries.is_empty())
+        else {
+            ui.weak("No previous seeds");
+
+            return None;
+        };
+
+        let mut clicked = None;
+
+        for entry in entries {
+            let text = entry.seed.to_string();
+            let button = match &entry.thumbnail {
+                Some(thumbnail) => {
+                    Button::image_and_text((thumbnail.id(), thumbnail.size_vec2()), text)
+                }
+                None => Button::new(text),
+            };
+
+            if ui.add(button).on_hover_text("Restore this seed").clicked() {
+                clicked = Some(entry.seed);
+            }
+        }
+
+        clicked
+    }
+
+    fn thumbnail(image: &Image) -> Option<ColorImage> {
+        let size = image.texture.as_ref()?.size();
+
+        if image.pixels.len() != size[0] * size[1] {
+            return None;
+        }
+
+        // Thumbnails are the same size whatever the density the image was rendered at
+        let step = Self::THUMBNAIL_STEP * image.density;
+        let thumbnail_size = size.map(|len| len / step);
+        let samples = (step * step) as u32;
+
+        // Each thumbnail pixel is the average of a block of image pixels
+        let pixels = (0..thumbnail_size[1])
+            .flat_map(|y| (0..thumbnail_size[0]).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let sum = (0..step)
+                    .flat_map(|block_y| (0..step).map(move |block_x| (block_x, block_y)))
+                    .map(|(block_x, block_y)| {
+                        let image_x = x * step + block_x;
+                        let image_y = y * step + block_y;
+
+                        image.pixels[image_y * size[0] + image_x] as u32
+                    })
+                    .sum::<u32>();
+
+                Color32::from_gray((sum / samples) as u8)
+            })
+            .collect();
+
+        Some(ColorImage {
+            size: thumbnail_size,
+            pixels,
+        })
+    }
+}
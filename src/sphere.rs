@@ -0,0 +1,401 @@
+use {
+    super::{app::NodeExprs, thread::Threads},
+    crossbeam_channel::{unbounded, Receiver, Sender},
+    egui::{
+        epaint::Vertex, pos2, vec2, Color32, ColorImage, Context, DragValue, Id, Mesh, Rect, Sense,
+        Shape, TextureHandle, TextureId, Ui, Vec2, Window,
+    },
+    noise::NoiseFn,
+    noise_graph::{Backend, Expr},
+    std::{
+        f32::consts::{FRAC_PI_2, PI, TAU},
+        sync::Arc,
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    iter::repeat_with,
+    num::NonZeroUsize,
+    thread::{available_parallelism, spawn, JoinHandle},
+};
+
+/// A band request: the generation of the texture, the first row of the band, what to sample it
+/// from and the radius of the sphere.
+type BandRequest = (usize, usize, Backend, Arc<Expr>, f64);
+
+/// A rendered band: the generation of the texture, the first row of the band and its grayscale
+/// pixels.
+type BandResponse = (usize, usize, Vec<u8>);
+
+/// Collapsible window which shows a node on the surface of a sphere, which may be dragged to turn
+/// it, so that the seams and polar distortion of planets are seen.
+///
+/// The surface is sampled once into a texture of latitude and longitude, which is wrapped around
+/// a mesh; turning the sphere does not sample the node again.
+pub struct SpherePreview {
+    pub node_idx: Option<usize>,
+
+    /// Increased whenever the texture is cleared, so that stale responses are ignored.
+    generation: usize,
+
+    /// The number of bands requested but not yet received.
+    pending: usize,
+
+    /// How far the sphere is tilted toward the viewer, in radians.
+    pitch: f32,
+
+    /// The distance from the origin the surface is sampled at.
+    radius: f64,
+
+    /// Whether the sphere is lit from the top left, which shows its shape.
+    shading: bool,
+
+    /// Whether the sphere turns on its own while it is not dragged.
+    spin: bool,
+
+    texture: Option<TextureHandle>,
+    version: Option<(usize, usize, Backend, u64)>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    workers: Vec<JoinHandle<()>>,
+
+    /// How far the sphere is turned about its poles, in radians.
+    yaw: f32,
+
+    rx: Receiver<BandResponse>,
+    tx: Sender<Option<BandRequest>>,
+    thread_rx: Receiver<Option<BandRequest>>,
+    thread_tx: Sender<BandResponse>,
+}
+
+impl SpherePreview {
+    /// The number of texture rows sampled by each request.
+    const BAND_ROWS: usize = 16;
+
+    /// The number of rings of the mesh, from pole to pole.
+    const LATITUDE_SEGMENTS: usize = 48;
+
+    /// The direction light comes from, as depth toward the viewer, across and up.
+    const LIGHT: [f32; 3] = [0.79, -0.35, 0.5];
+
+    /// The number of segments of the mesh around the poles.
+    const LONGITUDE_SEGMENTS: usize = 96;
+
+    const RADIUS_RANGE: std::ops::RangeInclusive<f64> = 0.01..=1e6;
+
+    #[cfg(target_arch = "wasm32")]
+    const REQUESTS_PER_FRAME: usize = 2;
+
+    /// The radians turned each second while spinning.
+    const SPIN_SPEED: f32 = 0.3;
+
+    /// The number of texture pixels around the equator and from pole to pole.
+    const TEXTURE_SIZE: [usize; 2] = [512, 256];
+
+    fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.pending = 0;
+        self.texture = None;
+        self.version = None;
+    }
+
+    /// Returns the mesh of the near side of the sphere, centered in `rect`.
+    fn mesh(&self, rect: Rect, texture_id: TextureId) -> Mesh {
+        let center = rect.center();
+        let radius = rect.width().min(rect.height()) / 2.0 - 4.0;
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let row_len = Self::LONGITUDE_SEGMENTS + 1;
+        let mut depths = Vec::with_capacity(row_len * (Self::LATITUDE_SEGMENTS + 1));
+        let mut mesh = Mesh::with_texture(texture_id);
+
+        for lat_idx in 0..=Self::LATITUDE_SEGMENTS {
+            let v = lat_idx as f32 / Self::LATITUDE_SEGMENTS as f32;
+
+            for lon_idx in 0..=Self::LONGITUDE_SEGMENTS {
+                let u = lon_idx as f32 / Self::LONGITUDE_SEGMENTS as f32;
+                let [x, y, z] = Self::surface_point(u, v);
+
+                // Turned about the poles, then tilted toward the viewer
+                let [x, y] = [x * cos_yaw - y * sin_yaw, x * sin_yaw + y * cos_yaw];
+                let [depth, z] = [x * cos_pitch - z * sin_pitch, x * sin_pitch + z * cos_pitch];
+
+                let brightness = if self.shading {
+                    let [light_depth, light_y, light_z] = Self::LIGHT;
+                    let lambert = depth * light_depth + y * light_y + z * light_z;
+
+                    0.25 + 0.75 * lambert.max(0.0)
+                } else {
+                    1.0
+                };
+
+                mesh.vertices.push(Vertex {
+                    pos: center + vec2(y, -z) * radius,
+                    uv: pos2(u, v),
+                    color: Color32::from_gray((brightness * 255.0) as u8),
+                });
+                depths.push(depth);
+            }
+        }
+
+        // There is no depth buffer, so only the near side of the sphere is drawn
+        for lat_idx in 0..Self::LATITUDE_SEGMENTS {
+            for lon_idx in 0..Self::LONGITUDE_SEGMENTS {
+                let idx = lat_idx * row_len + lon_idx;
+                let quad = [idx, idx + 1, idx + row_len, idx + row_len + 1];
+
+                if quad.iter().map(|&idx| depths[idx]).sum::<f32>() > 0.0 {
+                    let [top_left, top_right, bottom_left, bottom_right] =
+                        quad.map(|idx| idx as u32);
+                    mesh.add_triangle(top_left, top_right, bottom_left);
+                    mesh.add_triangle(top_right, bottom_right, bottom_left);
+                }
+            }
+        }
+
+        mesh
+    }
+
+    fn render_band(row: usize, backend: Backend, expr: &Expr, radius: f64) -> Vec<u8> {
+        let noise = expr.noise_with(backend.get());
+        let [width, height] = Self::TEXTURE_SIZE;
+        let mut pixels = Vec::with_capacity(width * Self::BAND_ROWS);
+
+        for y in row..row + Self::BAND_ROWS {
+            let v = (y as f64 + 0.5) / height as f64;
+
+            for x in 0..width {
+                let u = (x as f64 + 0.5) / width as f64;
+                let [lat, lon] = [
+                    (0.5 - v) * std::f64::consts::PI,
+                    (u - 0.5) * std::f64::consts::TAU,
+                ];
+                let point = [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+                    .map(|coord| coord * radius);
+
+                pixels.push(Threads::value_to_gray(noise.get(point)));
+            }
+        }
+
+        pixels
+    }
+
+    fn request(&mut self, request: BandRequest) {
+        self.pending += 1;
+
+        // Threads are only started once a sphere is previewed
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.workers.is_empty() {
+            self.workers = repeat_with(|| {
+                let (rx, tx) = (self.thread_rx.clone(), self.thread_tx.clone());
+                spawn(move || Self::thread_worker(rx, tx))
+            })
+            .take(
+                available_parallelism()
+                    .map(NonZeroUsize::get)
+                    .unwrap_or_default()
+                    .max(1),
+            )
+            .collect();
+        }
+
+        self.tx.send(Some(request)).unwrap();
+    }
+
+    pub fn show(&mut self, ctx: &Context, node_exprs: &NodeExprs, backend: Backend) {
+        let Some(node_idx) = self.node_idx else {
+            return;
+        };
+
+        let Some((version, expr)) = node_exprs
+            .read()
+            .unwrap()
+            .get(&node_idx)
+            .map(|(version, expr)| (*version, Arc::clone(expr)))
+        else {
+            self.node_idx = None;
+            self.clear();
+
+            return;
+        };
+
+        let version = Some((node_idx, version, backend, self.radius.to_bits()));
+        if self.version != version {
+            self.clear();
+            self.version = version;
+            self.texture = Some(ctx.load_texture(
+                "sphere",
+                ColorImage::new(Self::TEXTURE_SIZE, Color32::BLACK),
+                Default::default(),
+            ));
+
+            for row in (0..Self::TEXTURE_SIZE[1]).step_by(Self::BAND_ROWS) {
+                self.request((
+                    self.generation,
+                    row,
+                    backend,
+                    Arc::clone(&expr),
+                    self.radius,
+                ));
+            }
+        }
+
+        self.update_texture();
+
+        let mut open = true;
+
+        Window::new(format!("Sphere #{node_idx}"))
+            .id(Id::new("sphere"))
+            .open(&mut open)
+            .default_size([384.0, 384.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.show_view(ui);
+            });
+
+        if !open {
+            self.node_idx = None;
+            self.clear();
+        }
+    }
+
+    fn show_view(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Radius");
+            ui.add(
+                DragValue::new(&mut self.radius)
+                    .clamp_range(Self::RADIUS_RANGE)
+                    .speed(0.01),
+            )
+            .on_hover_text(
+                "The distance from the origin the surface is sampled at; larger spheres show more \
+                features",
+            );
+            ui.checkbox(&mut self.spin, "Spin");
+            ui.checkbox(&mut self.shading, "Shading");
+
+            if ui.button("Reset View").clicked() {
+                self.pitch = 0.0;
+                self.yaw = 0.0;
+            }
+
+            if self.pending > 0 {
+                ui.spinner();
+            }
+        });
+
+        let (rect, res) = ui.allocate_exact_size(ui.available_size(), Sense::drag());
+        let radius = rect.width().min(rect.height()) / 2.0;
+
+        if res.dragged() {
+            // The surface under the pointer follows it
+            let Vec2 { x, y } = res.drag_delta() / radius.max(1.0);
+            self.yaw = (self.yaw + x) % TAU;
+            self.pitch = (self.pitch - y).clamp(-FRAC_PI_2, FRAC_PI_2);
+        } else if self.spin {
+            let dt = ui.input(|input| input.stable_dt).min(0.1);
+            self.yaw = (self.yaw + dt * Self::SPIN_SPEED) % TAU;
+            ui.ctx().request_repaint();
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, Color32::BLACK);
+
+        if let Some(texture) = &self.texture {
+            painter.add(Shape::mesh(self.mesh(rect, texture.id())));
+        }
+
+        if self.pending > 0 {
+            // Rendered bands are received on the next frames
+            ui.ctx().request_repaint();
+        }
+    }
+
+    /// Returns the point of the unit sphere at a position of the texture, where `u` runs around
+    /// the equator and `v` from the north pole to the south pole.
+    fn surface_point(u: f32, v: f32) -> [f32; 3] {
+        let [lat, lon] = [(0.5 - v) * PI, (u - 0.5) * TAU];
+
+        [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn thread_worker(rx: Receiver<Option<BandRequest>>, tx: Sender<BandResponse>) {
+        while let Some((generation, row, backend, expr, radius)) = rx.recv().unwrap() {
+            let pixels = Self::render_band(row, backend, &expr, radius);
+            tx.send((generation, row, pixels)).unwrap();
+        }
+    }
+
+    fn update_texture(&mut self) {
+        // On web a few bands are rendered each frame instead
+        #[cfg(target_arch = "wasm32")]
+        for (generation, row, backend, expr, radius) in self
+            .thread_rx
+            .try_iter()
+            .flatten()
+            .take(Self::REQUESTS_PER_FRAME)
+        {
+            let pixels = Self::render_band(row, backend, &expr, radius);
+            self.thread_tx.send((generation, row, pixels)).unwrap();
+        }
+
+        for (generation, row, pixels) in self.rx.try_iter() {
+            if generation != self.generation {
+                continue;
+            }
+
+            self.pending -= 1;
+
+            if let Some(texture) = &mut self.texture {
+                texture.set_partial(
+                    [0, row],
+                    ColorImage::from_gray([Self::TEXTURE_SIZE[0], Self::BAND_ROWS], &pixels),
+                    Default::default(),
+                );
+            }
+        }
+    }
+}
+
+impl Default for SpherePreview {
+    fn default() -> Self {
+        let (tx, thread_rx) = unbounded();
+        let (thread_tx, rx) = unbounded();
+
+        Self {
+            node_idx: None,
+            generation: 0,
+            pending: 0,
+            pitch: 0.0,
+            radius: 1.0,
+            shading: true,
+            spin: true,
+            texture: None,
+            version: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            workers: vec![],
+
+            yaw: 0.0,
+            rx,
+            tx,
+            thread_rx,
+            thread_tx,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for SpherePreview {
+    fn drop(&mut self) {
+        for _ in 0..self.workers.len() {
+            self.tx.send(None).unwrap();
+        }
+
+        for worker in self.workers.drain(..) {
+            worker.join().unwrap();
+        }
+    }
+}
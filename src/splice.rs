@@ -0,0 +1,171 @@
+use {
+    super::node::NoiseNode,
+    egui::{vec2, Context, Pos2, Rect},
+    egui_snarl::{InPinId, OutPinId, Snarl},
+    std::collections::HashMap,
+};
+
+/// The screen positions of the nodes and pins shown on the canvas during the current frame, which
+/// locate the wire a node is dropped onto while Shift is held and where nodes are placed.
+#[derive(Default)]
+pub struct WireLayout {
+    /// The node whose header was pressed, which is being dragged until the pointer is released.
+    dragged_node_idx: Option<usize>,
+
+    headers: HashMap<usize, Rect>,
+    inputs: HashMap<InPinId, Pos2>,
+    outputs: HashMap<usize, Pos2>,
+
+    /// How far the canvas was last zoomed in, which is kept when the layout is cleared.
+    scale: Option<f32>,
+}
+
+impl WireLayout {
+    /// The space left between a node and a node added downstream of it, in points.
+    const DOWNSTREAM_GAP: f32 = 48.0;
+
+    /// How far from a wire a dropped node may be, in points.
+    const HIT_DISTANCE: f32 = 8.0;
+
+    /// The number of segments each wire is divided into when it is hit-tested.
+    const WIRE_SEGMENTS: usize = 16;
+
+    pub fn clear(&mut self) {
+        self.headers.clear();
+        self.inputs.clear();
+        self.outputs.clear();
+    }
+
+    /// Returns the graph position of a node added downstream of another, which is placed to the
+    /// right of the header of the other with its top aligned.
+    pub fn downstream_pos(&self, ctx: &Context, node_idx: usize) -> Option<Pos2> {
+        let header = self.headers.get(&node_idx)?;
+
+        Some(self.to_graph(ctx, header.right_top()) + vec2(Self::DOWNSTREAM_GAP, 0.0))
+    }
+
+    /// Returns the node dropped onto a wire this frame with Shift held, and the wire, if the node
+    /// may be spliced into it.
+    pub fn dropped_node(
+        &mut self,
+        ctx: &Context,
+        snarl: &Snarl<NoiseNode>,
+    ) -> Option<(usize, OutPinId, InPinId)> {
+        let (pressed, released, shift, pointer_pos) = ctx.input(|input| {
+            (
+                input.pointer.primary_pressed(),
+                input.pointer.primary_released(),
+                input.modifiers.shift,
+                input.pointer.interact_pos(),
+            )
+        });
+
+        if pressed {
+            self.dragged_node_idx = pointer_pos.and_then(|pointer_pos| {
+                self.headers
+                    .iter()
+                    .find(|(_, header)| header.contains(pointer_pos))
+                    .map(|(&node_idx, _)| node_idx)
+            });
+        }
+
+        if !released {
+            return None;
+        }
+
+        let node_idx = self.dragged_node_idx.take()?;
+
+        if !shift || !Self::is_unconnected_modifier(node_idx, snarl) {
+            return None;
+        }
+
+        let header = self.headers.get(&node_idx)?;
+
+        self.inputs
+            .iter()
+            .filter(|(to, _)| to.node != node_idx)
+            .flat_map(|(&to, &to_pos)| {
+                snarl
+                    .in_pin(to)
+                    .remotes
+                    .into_iter()
+                    .map(move |from| (from, to, to_pos))
+            })
+            .filter(|(from, _, _)| from.node != node_idx && snarl.get_node(from.node).has_image())
+            .filter_map(|(from, to, to_pos)| {
+                let from_pos = *self.outputs.get(&from.node)?;
+                let distance = Self::wire_points(from_pos, to_pos)
+                    .map(|point| header.distance_to_pos(point))
+                    .fold(f32::INFINITY, f32::min);
+
+                (distance <= Self::HIT_DISTANCE).then_some((distance, from, to))
+            })
+            .min_by(|(lhs, ..), (rhs, ..)| lhs.total_cmp(rhs))
+            .map(|(_, from, to)| (node_idx, from, to))
+    }
+
+    /// Returns the graph position of the header of a node, which is about where the node is.
+    pub fn header_pos(&self, ctx: &Context, node_idx: usize) -> Option<Pos2> {
+        let header = self.headers.get(&node_idx)?;
+
+        Some(self.to_graph(ctx, header.min))
+    }
+
+    /// Nodes may only be spliced into a wire while neither their source nor their output is
+    /// connected.
+    fn is_unconnected_modifier(node_idx: usize, snarl: &Snarl<NoiseNode>) -> bool {
+        snarl.get_node(node_idx).has_source()
+            && snarl
+                .in_pin(InPinId {
+                    node: node_idx,
+                    input: 0,
+                })
+                .remotes
+                .is_empty()
+            && snarl
+                .out_pin(OutPinId {
+                    node: node_idx,
+                    output: 0,
+                })
+                .remotes
+                .is_empty()
+    }
+
+    pub fn record_header(&mut self, node_idx: usize, rect: Rect, scale: f32) {
+        self.headers.insert(node_idx, rect);
+        self.scale = Some(scale);
+    }
+
+    pub fn record_input(&mut self, pin: InPinId, pos: Pos2) {
+        self.inputs.insert(pin, pos);
+    }
+
+    pub fn record_output(&mut self, node_idx: usize, pos: Pos2) {
+        self.outputs.insert(node_idx, pos);
+    }
+
+    /// Converts a position on screen to a position in the graph.
+    ///
+    /// The canvas does not expose how far it has been panned or where it is, so positions are
+    /// measured from the corner of the area left for the canvas, as the favorites toolbar does.
+    pub fn to_graph(&self, ctx: &Context, pos: Pos2) -> Pos2 {
+        ((pos - ctx.available_rect().min) / self.scale.unwrap_or(1.0)).to_pos2()
+    }
+
+    /// Points along a wire, which leaves its output and enters its input horizontally.
+    fn wire_points(from: Pos2, to: Pos2) -> impl Iterator<Item = Pos2> {
+        let bend = ((to.x - from.x).abs() / 2.0).max(Self::HIT_DISTANCE * 4.0);
+        let control_points = [from, from + vec2(bend, 0.0), to - vec2(bend, 0.0), to];
+
+        (0..=Self::WIRE_SEGMENTS).map(move |idx| {
+            let t = idx as f32 / Self::WIRE_SEGMENTS as f32;
+            let s = 1.0 - t;
+
+            (control_points[0].to_vec2() * s * s * s
+                + control_points[1].to_vec2() * 3.0 * s * s * t
+                + control_points[2].to_vec2() * 3.0 * s * t * t
+                + control_points[3].to_vec2() * t * t * t)
+                .to_pos2()
+        })
+    }
+}
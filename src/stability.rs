@@ -0,0 +1,439 @@
+use {
+    super::{app::NodeExprs, job::Job},
+    egui::{Button, Checkbox, Context, DragValue, Grid, Id, ProgressBar, Ui, Window},
+    noise::{Constant, NoiseFn},
+    noise_graph::{noise_fns::Rebase, Backend, Expr, RebaseExpr, SinglePrecision, Variable},
+    std::sync::Arc,
+};
+
+/// Statistics of the differences between neighbouring samples of a window.
+#[derive(Clone, Copy)]
+struct Stats {
+    /// The fraction of neighbours with identical values, which grows as positions round together.
+    flat: f64,
+
+    max_step: f64,
+    mean_step: f64,
+    non_finite: usize,
+}
+
+impl Stats {
+    fn new(values: &[f64], size: usize) -> Self {
+        let mut res = Self {
+            flat: 0.0,
+            max_step: 0.0,
+            mean_step: 0.0,
+            non_finite: values.iter().filter(|value| !value.is_finite()).count(),
+        };
+        let mut neighbours = 0;
+
+        for y in 0..size {
+            for x in 0..size {
+                let value = values[y * size + x];
+
+                for (next_x, next_y) in [(x + 1, y), (x, y + 1)] {
+                    if next_x == size || next_y == size {
+                        continue;
+                    }
+
+                    let step = (values[next_y * size + next_x] - value).abs();
+                    if !step.is_finite() {
+                        continue;
+                    }
+
+                    if step == 0.0 {
+                        res.flat += 1.0;
+                    }
+
+                    res.max_step = res.max_step.max(step);
+                    res.mean_step += step;
+                    neighbours += 1;
+                }
+            }
+        }
+
+        if neighbours > 0 {
+            res.flat /= neighbours as f64;
+            res.mean_step /= neighbours as f64;
+        }
+
+        res
+    }
+
+    /// Describes how the samples differ from the samples of the window at the origin.
+    fn artifacts(&self, baseline: &Self) -> Vec<&'static str> {
+        let mut res = vec![];
+
+        if self.non_finite > 0 {
+            res.push("values are not finite");
+        }
+
+        if self.flat > baseline.flat + StabilityAnalysis::FLAT_TOLERANCE {
+            res.push("neighbours round to the same value, which shows as flat steps");
+        }
+
+        if self.max_step > baseline.max_step * StabilityAnalysis::STEP_TOLERANCE {
+            res.push("neighbours jump further apart than at the origin");
+        }
+
+        let roughness = self.mean_step / baseline.mean_step;
+        if baseline.mean_step > 0.0
+            && !(1.0 / StabilityAnalysis::STEP_TOLERANCE..=StabilityAnalysis::STEP_TOLERANCE)
+                .contains(&roughness)
+        {
+            res.push("the noise is much smoother or rougher than at the origin");
+        }
+
+        res
+    }
+}
+
+/// The statistics of the window at the origin and at each offset.
+struct Analysis {
+    baseline: Stats,
+    rows: Vec<Row>,
+}
+
+/// The samples of the window at one offset.
+struct Row {
+    offset: f64,
+    rebased: Option<Stats>,
+    stats: Stats,
+
+    /// The distance between the offset and the next coordinate the sampled precision can
+    /// represent.
+    ulp: f64,
+}
+
+/// Collapsible window which samples a node at offsets far from the origin and compares the
+/// samples with the same window at the origin, which shows where coordinates lose the precision
+/// gradient noise needs.
+pub struct StabilityAnalysis {
+    pub node_idx: Option<usize>,
+
+    analysis: Option<Analysis>,
+
+    /// The analysis which is running, which replaces the shown analysis once it finishes.
+    job: Option<Job<Analysis>>,
+
+    offsets: Vec<f64>,
+
+    /// Also samples the node wrapped in a rebase expression with this period.
+    rebase_period: Option<f64>,
+
+    /// Rounds generators to `f32`, as shaders and game engines often sample them.
+    single_precision: bool,
+
+    /// The number of samples along each side of the window.
+    size: usize,
+
+    version: Option<(usize, usize, Backend)>,
+    window_len: f64,
+}
+
+impl StabilityAnalysis {
+    const DEFAULT_OFFSETS: [f64; 6] = [1e3, 1e5, 1e7, 1e9, 1e12, 1e15];
+    const DEFAULT_SIZE: usize = 64;
+    const DEFAULT_WINDOW_LEN: f64 = 1.0;
+
+    /// How much larger the fraction of flat neighbours may be than at the origin.
+    const FLAT_TOLERANCE: f64 = 0.01;
+
+    const MAX_SIZE: usize = 256;
+
+    /// How many times larger (or smaller) the steps between neighbours may be than at the origin.
+    const STEP_TOLERANCE: f64 = 2.0;
+
+    /// Starts sampling the window at the origin and at each offset, on a thread of its own.
+    fn analyze(&mut self, ctx: &Context, expr: Expr, backend: Backend) {
+        let (single_precision, size, window_len) =
+            (self.single_precision, self.size, self.window_len);
+        let sample = move |expr: &Expr, offset: f64| {
+            let noise = if single_precision {
+                expr.noise_with(&SinglePrecision(backend.get()))
+            } else {
+                expr.noise_with(backend.get())
+            };
+            let step = window_len / size as f64;
+            let values = (0..size)
+                .flat_map(|y| (0..size).map(move |x| (x, y)))
+                .map(|(x, y)| noise.get([offset + x as f64 * step, offset + y as f64 * step, 0.0]))
+                .collect::<Vec<_>>();
+
+            Stats::new(&values, size)
+        };
+        let rebased_expr = self.rebase_period.map(|period| {
+            Expr::Rebase(RebaseExpr {
+                source: Box::new(expr.clone()),
+                period: Variable::Anonymous(period),
+            })
+        });
+        let offsets = self.offsets.clone();
+
+        // The window at the origin is the first step, followed by a step for each offset
+        self.job = Some(Job::new(
+            ctx,
+            1 + offsets.len(),
+            || (None, vec![]),
+            move |(baseline, rows): &mut (Option<Stats>, Vec<Row>), step_idx| {
+                if step_idx == 0 {
+                    *baseline = Some(sample(&expr, 0.0));
+
+                    return;
+                }
+
+                let offset = offsets[step_idx - 1];
+
+                rows.push(Row {
+                    offset,
+                    rebased: rebased_expr
+                        .as_ref()
+                        .map(|rebased_expr| sample(rebased_expr, offset)),
+                    stats: sample(&expr, offset),
+                    ulp: if single_precision {
+                        let offset = offset.abs() as f32;
+
+                        (f32::from_bits(offset.to_bits() + 1) - offset) as f64
+                    } else {
+                        f64::from_bits(offset.abs().to_bits() + 1) - offset.abs()
+                    },
+                });
+            },
+            |(baseline, rows)| Analysis {
+                baseline: baseline.unwrap(),
+                rows,
+            },
+        ));
+    }
+
+    fn clear(&mut self) {
+        self.analysis = None;
+        self.job = None;
+    }
+
+    pub fn show(&mut self, ctx: &Context, node_exprs: &NodeExprs, backend: Backend) {
+        let Some(node_idx) = self.node_idx else {
+            return;
+        };
+
+        let Some((version, expr)) = node_exprs
+            .read()
+            .unwrap()
+            .get(&node_idx)
+            .map(|(version, expr)| (*version, Arc::clone(expr)))
+        else {
+            self.node_idx = None;
+            self.clear();
+
+            return;
+        };
+
+        // Results are only valid for the expression they were sampled from
+        if self.version != Some((node_idx, version, backend)) {
+            self.clear();
+            self.version = Some((node_idx, version, backend));
+        }
+
+        if let Some(analysis) = self.job.as_mut().and_then(|job| job.poll(ctx)) {
+            self.analysis = Some(analysis);
+            self.job = None;
+        }
+
+        let mut open = true;
+
+        Window::new(format!("Large Coordinates #{node_idx}"))
+            .id(Id::new("large_coordinates"))
+            .open(&mut open)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                self.show_settings(ui);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.job.is_none(), Button::new("Analyze"))
+                        .on_hover_text(
+                            "Sample the node at each offset and compare it with the same window \
+                            at the origin",
+                        )
+                        .clicked()
+                    {
+                        self.analyze(ctx, expr.as_ref().clone(), backend);
+                    }
+
+                    if let Some(job) = &self.job {
+                        ui.add(ProgressBar::new(job.progress()).show_percentage());
+
+                        if ui.button("Cancel").clicked() {
+                            self.job = None;
+                        }
+                    }
+                });
+
+                if let Some(analysis) = &self.analysis {
+                    ui.separator();
+                    self.show_rows(ui, analysis);
+                }
+            });
+
+        if !open {
+            self.node_idx = None;
+            self.clear();
+        }
+    }
+
+    fn show_rows(&self, ui: &mut Ui, Analysis { baseline, rows }: &Analysis) {
+        let step = self.window_len / self.size as f64;
+
+        Grid::new("large_coordinates_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Offset");
+                ui.strong("Coordinate Precision").on_hover_text(
+                    "The distance between representable coordinates, relative to the distance \
+                    between samples",
+                );
+                ui.strong("Roughness").on_hover_text(
+                    "The mean difference between neighbours, relative to the origin",
+                );
+                ui.strong("Flat");
+                ui.strong("Result");
+
+                if self.rebase_period.is_some() {
+                    ui.strong("Rebased");
+                }
+
+                ui.end_row();
+
+                for row in rows {
+                    ui.label(format!("{:e}", row.offset));
+                    ui.label(format!("{:.1e}", row.ulp / step));
+                    ui.label(format!("{:.2}×", row.stats.mean_step / baseline.mean_step));
+                    ui.label(format!("{:.1}%", row.stats.flat * 100.0));
+                    Self::verdict(ui, &row.stats, baseline);
+
+                    if let Some(rebased) = &row.rebased {
+                        Self::verdict(ui, rebased, baseline);
+                    }
+
+                    ui.end_row();
+                }
+            });
+
+        if rows.iter().any(|row| {
+            row.rebased
+                .is_some_and(|rebased| rebased.artifacts(baseline).is_empty())
+                && !row.stats.artifacts(baseline).is_empty()
+        }) {
+            ui.label(
+                "Rebasing keeps the coordinates of generators near the origin, which is exact \
+                for sources that repeat with the period: Perlin, Value and Worley noise repeat \
+                every 256 units, divided by their frequency",
+            );
+        }
+    }
+
+    fn show_settings(&mut self, ui: &mut Ui) {
+        Grid::new("large_coordinates_settings").show(ui, |ui| {
+            ui.label("Window Size");
+            ui.add(
+                DragValue::new(&mut self.size)
+                    .clamp_range(2..=Self::MAX_SIZE)
+                    .suffix(" samples"),
+            );
+            ui.end_row();
+
+            ui.label("Window Length");
+            ui.add(
+                DragValue::new(&mut self.window_len)
+                    .clamp_range(f64::EPSILON..=f64::MAX)
+                    .speed(0.01),
+            );
+            ui.end_row();
+
+            ui.label("Offsets");
+            ui.vertical(|ui| {
+                let mut removed_idx = None;
+
+                for (idx, offset) in self.offsets.iter_mut().enumerate() {
+                    let speed = offset.abs().max(1.0) * 0.01;
+
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            DragValue::new(offset)
+                                .speed(speed)
+                                .custom_formatter(|value, _| format!("{value:e}")),
+                        );
+
+                        if ui.small_button("✖").clicked() {
+                            removed_idx = Some(idx);
+                        }
+                    });
+                }
+
+                if let Some(idx) = removed_idx {
+                    self.offsets.remove(idx);
+                }
+
+                if ui.small_button("Add Offset").clicked() {
+                    let offset = self.offsets.last().map_or(1e3, |offset| offset * 10.0);
+                    self.offsets.push(offset);
+                }
+            })
+            .response
+            .on_hover_text("Both coordinates of the window start at each offset");
+            ui.end_row();
+
+            ui.label("Precision");
+            ui.add(Checkbox::new(&mut self.single_precision, "Single"))
+                .on_hover_text("Round the generators to f32, as shaders and game engines do");
+            ui.end_row();
+
+            let mut rebase = self.rebase_period.is_some();
+            ui.label("Rebase");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut rebase, "")
+                    .on_hover_text("Also sample the node wrapped in a rebase node");
+
+                if let Some(period) = &mut self.rebase_period {
+                    ui.add(
+                        DragValue::new(period)
+                            .clamp_range(0.0..=f64::MAX)
+                            .prefix("period: "),
+                    );
+                }
+            });
+            ui.end_row();
+
+            if rebase != self.rebase_period.is_some() {
+                self.rebase_period = rebase.then_some(Rebase::<Constant>::DEFAULT_PERIOD);
+            }
+        });
+    }
+
+    fn verdict(ui: &mut Ui, stats: &Stats, baseline: &Stats) {
+        let artifacts = stats.artifacts(baseline);
+
+        if artifacts.is_empty() {
+            ui.label("✔ Stable");
+        } else {
+            ui.colored_label(ui.visuals().error_fg_color, "⚠ Artifacts")
+                .on_hover_text(artifacts.join("\n"));
+        }
+    }
+}
+
+impl Default for StabilityAnalysis {
+    fn default() -> Self {
+        Self {
+            node_idx: None,
+            analysis: None,
+            job: None,
+            offsets: Self::DEFAULT_OFFSETS.to_vec(),
+            rebase_period: None,
+            single_precision: false,
+            size: Self::DEFAULT_SIZE,
+            version: None,
+            window_len: Self::DEFAULT_WINDOW_LEN,
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use {
+    super::{app::NodeExprs, node::NoiseNode},
+    crossbeam_channel::{unbounded, Receiver, Sender},
+    egui::{
+        epaint::Vertex, vec2, Color32, ComboBox, Context, DragValue, Id, Mesh, Rect, Sense, Shape,
+        Ui, Window,
+    },
+    egui_snarl::Snarl,
+    noise::NoiseFn,
+    noise_graph::{Backend, Expr},
+    std::{f32::consts::FRAC_PI_2, sync::Arc},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    iter::repeat_with,
+    num::NonZeroUsize,
+    thread::{available_parallelism, spawn, JoinHandle},
+};
+
+/// A band request: the generation of the heightfield, the first row of the band, what to sample
+/// it from, the number of samples along each side and the area of the node preview.
+type BandRequest = (usize, usize, Backend, Arc<Expr>, usize, [f64; 3]);
+
+/// A sampled band: the generation of the heightfield, the first row of the band and its values.
+type BandResponse = (usize, usize, Vec<f32>);
+
+/// Collapsible window which shows the values of a node as the heights of a lit terrain, which may
+/// be orbited by dragging and zoomed by scrolling, so that the relief of fractals is seen.
+///
+/// The terrain covers the area of the node preview, which it matches when seen from above.
+pub struct TerrainPreview {
+    pub node_idx: Option<usize>,
+
+    /// The distance of the camera from the center of the terrain, whose sides are two units long.
+    distance: f32,
+
+    /// Increased whenever the heightfield is cleared, so that stale responses are ignored.
+    generation: usize,
+
+    /// The height of a value of one, relative to the sides of the terrain.
+    height_scale: f32,
+
+    /// The sampled values, row by row.
+    heights: Vec<f32>,
+
+    /// The number of bands requested but not yet received.
+    pending: usize,
+
+    /// How far the camera is raised above the horizon, in radians.
+    pitch: f32,
+
+    /// The number of samples along each side of the terrain.
+    resolution: usize,
+
+    version: Option<(usize, usize, Backend, usize, [u64; 3])>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    workers: Vec<JoinHandle<()>>,
+
+    /// How far the camera is turned about the center of the terrain, in radians.
+    yaw: f32,
+
+    rx: Receiver<BandResponse>,
+    tx: Sender<Option<BandRequest>>,
+    thread_rx: Receiver<Option<BandRequest>>,
+    thread_tx: Sender<BandResponse>,
+}
+
+impl TerrainPreview {
+    /// The number of rows sampled by each request.
+    const BAND_ROWS: usize = 8;
+
+    const DEFAULT_DISTANCE: f32 = 3.0;
+    const DEFAULT_PITCH: f32 = 0.6;
+
+    const DISTANCE_RANGE: [f32; 2] = [1.0, 20.0];
+
+    /// The direction light comes from, which is above and to the side of the terrain.
+    const LIGHT: [f32; 3] = [-0.48, 0.8, -0.36];
+
+    /// Triangles nearer to the camera than this are not drawn, as they would be projected
+    /// without bounds.
+    const NEAR: f32 = 0.05;
+
+    #[cfg(target_arch = "wasm32")]
+    const REQUESTS_PER_FRAME: usize = 2;
+
+    const RESOLUTIONS: [usize; 3] = [64, 128, 256];
+
+    fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.heights.clear();
+        self.pending = 0;
+        self.version = None;
+    }
+
+    /// Returns the mesh of the terrain as seen by the camera, centered in `rect`.
+    ///
+    /// There is no depth buffer, so triangles are sorted to be drawn from the farthest to the
+    /// nearest.
+    fn mesh(&self, rect: Rect) -> Mesh {
+        let size = self.resolution;
+        let step = 2.0 / (size - 1) as f32;
+        let focal = rect.width().min(rect.height());
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let height = |row: usize, col: usize| {
+            self.heights
+                .get(row * size + col)
+                .copied()
+                .unwrap_or_default()
+                * self.height_scale
+        };
+
+        let mut depths = Vec::with_capacity(size * size);
+        let mut mesh = Mesh::default();
+
+        for row in 0..size {
+            for col in 0..size {
+                // Columns run across the terrain and rows toward the viewer, as in the preview
+                let [x, y, z] = [
+                    col as f32 * step - 1.0,
+                    height(row, col),
+                    row as f32 * step - 1.0,
+                ];
+
+                let normal = [
+                    height(row, col.saturating_sub(1)) - height(row, (col + 1).min(size - 1)),
+                    2.0 * step,
+                    height(row.saturating_sub(1), col) - height((row + 1).min(size - 1), col),
+                ];
+                let len = normal.iter().map(|coord| coord * coord).sum::<f32>().sqrt();
+                let lambert = normal
+                    .iter()
+                    .zip(Self::LIGHT)
+                    .map(|(coord, light)| coord * light)
+                    .sum::<f32>()
+                    / len;
+                let brightness = 0.2 + 0.8 * lambert.max(0.0);
+
+                // Turned about the center, then seen from above the horizon
+                let [x, z] = [x * cos_yaw - z * sin_yaw, x * sin_yaw + z * cos_yaw];
+                let depth = self.distance - (y * sin_pitch + z * cos_pitch);
+                let up = y * cos_pitch - z * sin_pitch;
+
+                mesh.vertices.push(Vertex {
+                    pos: rect.center() + vec2(x, -up) * focal / depth.max(Self::NEAR),
+                    uv: Default::default(),
+                    color: Color32::from_gray((brightness * 255.0) as u8),
+                });
+                depths.push(depth);
+            }
+        }
+
+        let mut triangles = Vec::with_capacity(2 * (size - 1) * (size - 1));
+        for row in 0..size - 1 {
+            for col in 0..size - 1 {
+                let idx = row * size + col;
+                let [top_left, top_right, bottom_left, bottom_right] =
+                    [idx, idx + 1, idx + size, idx + size + 1];
+
+                triangles.push([top_left, top_right, bottom_left]);
+                triangles.push([top_right, bottom_right, bottom_left]);
+            }
+        }
+
+        let triangle_depth = |triangle: &[usize; 3]| triangle.iter().map(|&idx| depths[idx]).sum();
+        triangles.retain(|triangle| triangle.iter().all(|&idx| depths[idx] > Self::NEAR));
+        triangles.sort_by(|lhs, rhs| f32::total_cmp(&triangle_depth(rhs), &triangle_depth(lhs)));
+
+        for [a, b, c] in triangles {
+            mesh.add_triangle(a as u32, b as u32, c as u32);
+        }
+
+        mesh
+    }
+
+    fn render_band(
+        row: usize,
+        backend: Backend,
+        expr: &Expr,
+        size: usize,
+        [x, y, scale]: [f64; 3],
+    ) -> Vec<f32> {
+        let noise = expr.noise_with(backend.get());
+        let step = 1.0 / (size - 1) as f64;
+        let mut values = Vec::with_capacity(size * Self::BAND_ROWS);
+
+        for row in row..(row + Self::BAND_ROWS).min(size) {
+            let eval_x = (row as f64 * step + y) * scale;
+
+            for col in 0..size {
+                let eval_y = (col as f64 * step + x) * scale;
+
+                values.push(noise.get([eval_x, eval_y, 0.0]) as f32);
+            }
+        }
+
+        values
+    }
+
+    fn request(&mut self, request: BandRequest) {
+        self.pending += 1;
+
+        // Threads are only started once a terrain is previewed
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.workers.is_empty() {
+            self.workers = repeat_with(|| {
+                let (rx, tx) = (self.thread_rx.clone(), self.thread_tx.clone());
+                spawn(move || Self::thread_worker(rx, tx))
+            })
+            .take(
+                available_parallelism()
+                    .map(NonZeroUsize::get)
+                    .unwrap_or_default()
+                    .max(1),
+            )
+            .collect();
+        }
+
+        self.tx.send(Some(request)).unwrap();
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        node_exprs: &NodeExprs,
+        snarl: &Snarl<NoiseNode>,
+        backend: Backend,
+    ) {
+        let Some(node_idx) = self.node_idx else {
+            return;
+        };
+
+        let Some((version, expr)) = node_exprs
+            .read()
+            .unwrap()
+            .get(&node_idx)
+            .map(|(version, expr)| (*version, Arc::clone(expr)))
+        else {
+            self.node_idx = None;
+            self.clear();
+
+            return;
+        };
+
+        let area = snarl
+            .node_indices()
+            .find(|&(idx, _)| idx == node_idx)
+            .and_then(|(_, node)| node.image())
+            .map_or([0.0, 0.0, 1.0], |image| [image.x, image.y, image.scale]);
+
+        let version = Some((
+            node_idx,
+            version,
+            backend,
+            self.resolution,
+            area.map(f64::to_bits),
+        ));
+        if self.version != version {
+            self.clear();
+            self.version = version;
+            self.heights = vec![0.0; self.resolution * self.resolution];
+
+            for row in (0..self.resolution).step_by(Self::BAND_ROWS) {
+                self.request((
+                    self.generation,
+                    row,
+                    backend,
+                    Arc::clone(&expr),
+                    self.resolution,
+                    area,
+                ));
+            }
+        }
+
+        self.update_heights();
+
+        let mut open = true;
+
+        Window::new(format!("Terrain #{node_idx}"))
+            .id(Id::new("terrain"))
+            .open(&mut open)
+            .default_size([480.0, 384.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.show_view(ui);
+            });
+
+        if !open {
+            self.node_idx = None;
+            self.clear();
+        }
+    }
+
+    fn show_view(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Height");
+            ui.add(
+                DragValue::new(&mut self.height_scale)
+                    .clamp_range(0.0..=4.0)
+                    .speed(0.005),
+            )
+            .on_hover_text("The height of a value of one, relative to the width of the terrain");
+
+            ComboBox::from_id_source("terrain_resolution")
+                .selected_text(format!("{0}×{0}", self.resolution))
+                .show_ui(ui, |ui| {
+                    for resolution in Self::RESOLUTIONS {
+                        ui.selectable_value(
+                            &mut self.resolution,
+                            resolution,
+                            format!("{resolution}×{resolution}"),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text("The number of samples along each side of the terrain");
+
+            if ui.button("Reset View").clicked() {
+                self.distance = Self::DEFAULT_DISTANCE;
+                self.pitch = Self::DEFAULT_PITCH;
+                self.yaw = 0.0;
+            }
+
+            if self.pending > 0 {
+                ui.spinner();
+            }
+        });
+
+        let (rect, res) = ui.allocate_exact_size(ui.available_size(), Sense::drag());
+
+        if res.dragged() {
+            let delta = res.drag_delta() / rect.width().min(rect.height()).max(1.0) * 4.0;
+            self.yaw -= delta.x;
+            self.pitch = (self.pitch + delta.y).clamp(0.05, FRAC_PI_2);
+        }
+
+        if res.hovered() {
+            let scroll = ui.input(|input| input.scroll_delta.y);
+            let [min, max] = Self::DISTANCE_RANGE;
+            self.distance = (self.distance * (-scroll * 0.002).exp()).clamp(min, max);
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, Color32::from_gray(24));
+
+        if !self.heights.is_empty() {
+            painter.add(Shape::mesh(self.mesh(rect)));
+        }
+
+        if self.pending > 0 {
+            // Sampled bands are received on the next frames
+            ui.ctx().request_repaint();
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn thread_worker(rx: Receiver<Option<BandRequest>>, tx: Sender<BandResponse>) {
+        while let Some((generation, row, backend, expr, size, area)) = rx.recv().unwrap() {
+            let values = Self::render_band(row, backend, &expr, size, area);
+            tx.send((generation, row, values)).unwrap();
+        }
+    }
+
+    fn update_heights(&mut self) {
+        // On web a few bands are sampled each frame instead
+        #[cfg(target_arch = "wasm32")]
+        for (generation, row, backend, expr, size, area) in self
+            .thread_rx
+            .try_iter()
+            .flatten()
+            .take(Self::REQUESTS_PER_FRAME)
+        {
+            let values = Self::render_band(row, backend, &expr, size, area);
+            self.thread_tx.send((generation, row, values)).unwrap();
+        }
+
+        for (generation, row, values) in self.rx.try_iter() {
+            if generation != self.generation {
+                continue;
+            }
+
+            self.pending -= 1;
+
+            let start = row * self.resolution;
+            self.heights[start..start + values.len()].copy_from_slice(&values);
+        }
+    }
+}
+
+impl Default for TerrainPreview {
+    fn default() -> Self {
+        let (tx, thread_rx) = unbounded();
+        let (thread_tx, rx) = unbounded();
+
+        Self {
+            node_idx: None,
+            distance: Self::DEFAULT_DISTANCE,
+            generation: 0,
+            height_scale: 0.25,
+            heights: vec![],
+            pending: 0,
+            pitch: Self::DEFAULT_PITCH,
+            resolution: 128,
+            version: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            workers: vec![],
+
+            yaw: 0.0,
+            rx,
+            tx,
+            thread_rx,
+            thread_tx,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for TerrainPreview {
+    fn drop(&mut self) {
+        for _ in 0..self.workers.len() {
+            self.tx.send(None).unwrap();
+        }
+
+        for worker in self.workers.drain(..) {
+            worker.join().unwrap();
+        }
+    }
+}
@@ -1,38 +1,161 @@
 use {
-    super::{app::NodeExprs, expr::Expr},
+    super::app::NodeExprs,
     crossbeam_channel::{unbounded, Receiver, Sender},
+    noise::NoiseFn,
+    noise_graph::{Backend, Expr},
     std::{
-        collections::HashMap,
-        sync::{Arc, RwLock},
+        collections::{HashMap, HashSet, VecDeque},
+        sync::{Arc, Condvar, Mutex, RwLock},
     },
 };
 
+#[cfg(target_arch = "wasm32")]
+use js_sys::Date;
+
 #[cfg(not(target_arch = "wasm32"))]
-use std::{
-    iter::repeat_with,
-    num::NonZeroUsize,
-    thread::{available_parallelism, spawn, JoinHandle},
+use {
+    rayon::prelude::*,
+    std::{
+        iter::repeat_with,
+        num::NonZeroUsize,
+        thread::{available_parallelism, spawn, JoinHandle},
+    },
 };
 
 type NodeExprsCache = HashMap<usize, (usize, Arc<Expr>)>;
 
+/// The queue of requests shared with the workers, which wait on the condition variable for more.
+type SharedQueue = Arc<(Mutex<Queue>, Condvar)>;
+
+/// A rendered sub-image: the node index, image version, coordinate, whether it is coarse and
+/// grayscale pixels.
+pub type SubImage = (usize, usize, u8, bool, Vec<u8>);
+
 #[derive(Clone, Copy)]
 pub struct ImageInfo {
+    pub backend: Backend,
+
+    /// Whether the sub-image is filled with one sample from its center, which is an eighth of the
+    /// resolution of the preview and is rendered before the full sub-images so that edits show at
+    /// once.
+    pub coarse: bool,
+
     pub coord: u8,
+
+    /// The number of pixels rendered along each side of a point of the preview.
+    pub density: usize,
+
     pub scale: f64,
+
+    /// Whether the image is sampled so that it tiles seamlessly.
+    pub tileable: bool,
+
     pub x: f64,
     pub y: f64,
 }
 
+/// How soon the sub-images of a preview are rendered, relative to the other previews waiting.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Priority {
+    /// Thumbnails of the nodes between the generators and the outputs of the graph.
+    Background,
+
+    /// Nodes whose output is not connected, which are the results of the graph.
+    Output,
+
+    /// The node selected in the expression tree.
+    Selected,
+}
+
+impl Priority {
+    pub const ALL: [Self; 3] = [Self::Background, Self::Output, Self::Selected];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Background => "Background",
+            Self::Output => "Output",
+            Self::Selected => "Selected",
+        }
+    }
+}
+
+/// Requests waiting for a worker, in the order they are rendered within each priority.
+#[derive(Default)]
+struct Queue {
+    /// Set when the workers should return instead of waiting for more requests.
+    closed: bool,
+
+    /// The number of requests taken by workers which are still being rendered.
+    rendering: usize,
+
+    requests: [VecDeque<Request>; Priority::ALL.len()],
+}
+
+impl Queue {
+    fn pop(&mut self) -> Option<Request> {
+        let res = self.requests.iter_mut().rev().find_map(VecDeque::pop_front);
+        if res.is_some() {
+            self.rendering += 1;
+        }
+
+        res
+    }
+}
+
+/// The render queue as shown in the status bar.
+#[derive(Clone, Copy, Default)]
+pub struct QueueStatus {
+    /// The number of previews which have sub-images waiting.
+    pub nodes: usize,
+
+    /// The number of sub-images taken by workers which are still being rendered.
+    pub rendering: usize,
+
+    /// The number of sub-images waiting, by priority.
+    pub sub_images: [usize; Priority::ALL.len()],
+}
+
+impl QueueStatus {
+    /// Adds the requests of another queue, such as the passes waiting for the GPU.
+    #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+    pub fn add(&mut self, other: Self) {
+        self.nodes += other.nodes;
+        self.rendering += other.rendering;
+
+        for (sub_images, other) in self.sub_images.iter_mut().zip(other.sub_images) {
+            *sub_images += other;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes == 0 && self.rendering == 0
+    }
+}
+
+/// A sub-image of a version of a node waiting to be rendered.
+#[derive(Clone, Copy)]
+struct Request {
+    image_info: ImageInfo,
+    node_idx: usize,
+    version: usize,
+}
+
+/// Renders the sub-images of previews, most important first: requests wait in a queue which
+/// replaces the requests of a node when it is sent again, so edits never wait behind stale work.
 pub struct Threads {
+    queue: SharedQueue,
+
     #[cfg(target_arch = "wasm32")]
     worker: Box<dyn Fn()>,
 
     #[cfg(not(target_arch = "wasm32"))]
     workers: Vec<JoinHandle<()>>,
 
-    rx: Receiver<(usize, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])>,
-    tx: Sender<Option<(usize, usize, ImageInfo)>>,
+    rx: Receiver<SubImage>,
+
+    /// Kept so that other renderers may send sub-images alongside the workers.
+    #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+    tx: Sender<SubImage>,
 }
 
 impl Threads {
@@ -42,31 +165,45 @@ impl Threads {
     /// threads to send and receive the location of a sub-image easily.
     pub const IMAGE_COORDS: u8 = 16;
 
-    /// The number of pixels along any one side of a sub-image.
+    /// The number of points along any one side of a sub-image, which contains this many pixels
+    /// per side at a density of one.
     pub const IMAGE_SIZE: usize = 8;
 
+    /// The number of pixel rows in each band of a sub-image, which are rendered in parallel on
+    /// native builds so that dense previews of large graphs do not wait on a single thread.
+    const BAND_ROWS: usize = 8;
+
+    /// The milliseconds of each frame which web builds spend rendering sub-images, so that the
+    /// interface stays responsive however slow the graph is to sample.
     #[cfg(target_arch = "wasm32")]
-    const REQUESTS_PER_FRAME: usize = 64;
+    const FRAME_BUDGET_MS: f64 = 8.0;
+
+    /// The fraction of a tileable area, along each side, over which samples near the far edges are
+    /// blended with the samples of the near edges.
+    const TILE_BLEND: f64 = 0.25;
+
+    /// The range of values which images map from black to white; values outside are clamped.
+    pub const VALUE_RANGE: [f64; 2] = [-1.0, 1.0];
 
     pub fn new(node_exprs: &NodeExprs) -> Self {
-        let (tx, thread_rx) = unbounded();
+        let queue = SharedQueue::default();
         let (thread_tx, rx) = unbounded();
 
         #[cfg(target_arch = "wasm32")]
         let worker = {
             let node_exprs = Arc::clone(node_exprs);
-            let (tx, rx) = (thread_tx.clone(), thread_rx.clone());
+            let queue = Arc::clone(&queue);
 
             Box::new(move || {
-                Self::web_worker(&node_exprs, &rx, &tx);
+                Self::web_worker(&node_exprs, &queue, &thread_tx);
             })
         };
 
         #[cfg(not(target_arch = "wasm32"))]
         let workers = repeat_with(|| {
             let node_exprs = Arc::clone(node_exprs);
-            let (tx, rx) = (thread_tx.clone(), thread_rx.clone());
-            spawn(|| Self::thread_worker(node_exprs, rx, tx))
+            let (queue, tx) = (Arc::clone(&queue), thread_tx.clone());
+            spawn(|| Self::thread_worker(node_exprs, queue, tx))
         })
         .take(
             available_parallelism()
@@ -77,6 +214,8 @@ impl Threads {
         .collect();
 
         Self {
+            queue,
+
             #[cfg(target_arch = "wasm32")]
             worker,
 
@@ -84,25 +223,74 @@ impl Threads {
             workers,
 
             rx,
-            tx,
+
+            #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+            tx: thread_tx,
         }
     }
 
-    pub fn coord_to_row_col(coord: u8) -> [usize; 2] {
-        let row = (coord / Self::IMAGE_COORDS) as usize * Self::IMAGE_SIZE;
-        let col = (coord % Self::IMAGE_COORDS) as usize * Self::IMAGE_SIZE;
+    pub fn gray_to_value(gray: u8) -> f64 {
+        let [min, max] = Self::VALUE_RANGE;
+
+        min + gray as f64 / 255.0 * (max - min)
+    }
+
+    pub fn coord_to_row_col(coord: u8, density: usize) -> [usize; 2] {
+        let row = (coord / Self::IMAGE_COORDS) as usize * Self::IMAGE_SIZE * density;
+        let col = (coord % Self::IMAGE_COORDS) as usize * Self::IMAGE_SIZE * density;
 
         [row, col]
     }
 
+    /// Removes the waiting requests of a node, such as a node which was removed or edited.
+    pub fn cancel(&self, node_idx: usize) {
+        let mut queue = self.queue.0.lock().unwrap();
+
+        for requests in &mut queue.requests {
+            requests.retain(|request| request.node_idx != node_idx);
+        }
+    }
+
+    /// Moves the waiting requests of a node to another priority, keeping their order.
+    pub fn prioritize(&self, node_idx: usize, priority: Priority) {
+        let mut queue = self.queue.0.lock().unwrap();
+        let mut moved = VecDeque::new();
+
+        for (requests_priority, requests) in Priority::ALL.into_iter().zip(&mut queue.requests) {
+            if requests_priority != priority {
+                requests.retain(|&request| {
+                    let is_moved = request.node_idx == node_idx;
+                    if is_moved {
+                        moved.push_back(request);
+                    }
+
+                    !is_moved
+                });
+            }
+        }
+
+        queue.requests[priority as usize].append(&mut moved);
+    }
+
     fn process_request(
         node_exprs: &Arc<RwLock<NodeExprsCache>>,
-        node_idx: usize,
-        version: usize,
-        image_info: ImageInfo,
-        tx: &Sender<(usize, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])>,
+        Request {
+            image_info,
+            node_idx,
+            version,
+        }: Request,
+        tx: &Sender<SubImage>,
     ) -> bool {
-        let ImageInfo { coord, scale, x, y } = image_info;
+        let ImageInfo {
+            backend,
+            coarse,
+            coord,
+            density,
+            scale,
+            tileable,
+            x,
+            y,
+        } = image_info;
 
         // Double-check that the expression is still the current version (it may have been
         // updated by the time we receive this request)
@@ -113,21 +301,51 @@ impl Threads {
             .filter(|(current_version, _)| *current_version == version)
             .map(|(_, expr)| Arc::clone(expr))
         {
-            let [row, col] = Self::coord_to_row_col(coord);
-            let step = 1.0 / (Self::IMAGE_SIZE * 16) as f64;
+            let [row, col] = Self::coord_to_row_col(coord, density);
+            let size = Self::IMAGE_SIZE * density;
+            let step = 1.0 / (size * Self::IMAGE_COORDS as usize) as f64;
             let half_step = step / 2.0;
-            let mut image = [0u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE];
-
-            for image_y in 0..Self::IMAGE_SIZE {
-                let eval_y = ((row + image_y) as f64 * step + half_step + x) * scale;
-                for image_x in 0..Self::IMAGE_SIZE {
-                    let eval_x = ((col + image_x) as f64 * step + half_step + y) * scale;
-                    let sample = (expr.noise().get([eval_x, eval_y, 0.0]) + 1.0) / 2.0;
-                    image[image_x * Self::IMAGE_SIZE + image_y] = (sample * 255.0) as u8;
+            let mut image = vec![0u8; size * size];
+
+            let sample = |noise: &dyn NoiseFn<f64, 3>, eval_x: f64, eval_y: f64| {
+                if tileable {
+                    Self::sample_tileable(noise, [eval_x, eval_y], [y * scale, x * scale], scale)
+                } else {
+                    noise.get([eval_x, eval_y, 0.0])
                 }
+            };
+
+            if coarse {
+                let half_size = size as f64 / 2.0;
+                let eval_y = ((row as f64 + half_size) * step + x) * scale;
+                let eval_x = ((col as f64 + half_size) * step + y) * scale;
+                let noise = expr.noise_with(backend.get());
+                image.fill(Self::value_to_gray(sample(&*noise, eval_x, eval_y)));
+            } else {
+                // Web builds have no threads, so their bands are rendered one after another
+                #[cfg(not(target_arch = "wasm32"))]
+                let bands = image.par_chunks_mut(Self::BAND_ROWS * size);
+
+                #[cfg(target_arch = "wasm32")]
+                let bands = image.chunks_mut(Self::BAND_ROWS * size);
+
+                bands.enumerate().for_each(|(band_idx, band)| {
+                    // Building the noise function creates the permutation tables of every
+                    // generator, which costs more than sampling it, so it is built once per band
+                    let noise = expr.noise_with(backend.get());
+
+                    for (band_x, pixels) in band.chunks_mut(size).enumerate() {
+                        let image_x = band_idx * Self::BAND_ROWS + band_x;
+                        let eval_x = ((col + image_x) as f64 * step + half_step + y) * scale;
+                        for (image_y, pixel) in pixels.iter_mut().enumerate() {
+                            let eval_y = ((row + image_y) as f64 * step + half_step + x) * scale;
+                            *pixel = Self::value_to_gray(sample(&*noise, eval_x, eval_y));
+                        }
+                    }
+                });
             }
 
-            tx.send((node_idx, version, coord, image)).unwrap();
+            tx.send((node_idx, version, coord, coarse, image)).unwrap();
 
             true
         } else {
@@ -135,26 +353,111 @@ impl Threads {
         }
     }
 
-    pub fn send(&self, node: usize, version: usize, image_info: ImageInfo) {
-        self.tx.send(Some((node, version, image_info))).unwrap();
+    pub fn send(&self, node_idx: usize, version: usize, image_info: ImageInfo, priority: Priority) {
+        let (queue, requests_available) = &*self.queue;
+
+        queue.lock().unwrap().requests[priority as usize].push_back(Request {
+            image_info,
+            node_idx,
+            version,
+        });
+        requests_available.notify_one();
+    }
+
+    /// Returns a sender of sub-images which are received with those of the workers.
+    #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+    pub fn sender(&self) -> Sender<SubImage> {
+        self.tx.clone()
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        let queue = self.queue.0.lock().unwrap();
+        let mut node_indices = HashSet::new();
+        let mut res = QueueStatus::default();
+
+        for (sub_images, requests) in res.sub_images.iter_mut().zip(&queue.requests) {
+            *sub_images = requests.len();
+            node_indices.extend(requests.iter().map(|request| request.node_idx));
+        }
+
+        res.nodes = node_indices.len();
+        res.rendering = queue.rendering;
+
+        res
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    fn thread_worker(
-        node_exprs: NodeExprs,
-        rx: Receiver<Option<(usize, usize, ImageInfo)>>,
-        tx: Sender<(usize, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])>,
-    ) {
-        // Receive the next versioned node request from the main thread
-        while let Some((node_idx, version, image_info)) = rx.recv().unwrap() {
-            Self::process_request(&node_exprs, node_idx, version, image_info, &tx);
+    fn thread_worker(node_exprs: NodeExprs, queue: SharedQueue, tx: Sender<SubImage>) {
+        let (queue, requests_available) = &*queue;
+
+        loop {
+            // Wait for the most important request from the main thread
+            let request = {
+                let mut queue = queue.lock().unwrap();
+
+                loop {
+                    if queue.closed {
+                        return;
+                    }
+
+                    if let Some(request) = queue.pop() {
+                        break request;
+                    }
+
+                    queue = requests_available.wait(queue).unwrap();
+                }
+            };
+
+            Self::process_request(&node_exprs, request, &tx);
+            queue.lock().unwrap().rendering -= 1;
         }
     }
 
-    pub fn try_recv_iter(
-        &self,
-    ) -> impl Iterator<Item = (usize, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])> + '_
-    {
+    pub fn value_to_gray(value: f64) -> u8 {
+        let [min, max] = Self::VALUE_RANGE;
+
+        ((value - min) / (max - min) * 255.0) as u8
+    }
+
+    /// Samples noise at a point of a square area which starts at `origin` and has sides of `len`,
+    /// such that the area tiles seamlessly.
+    ///
+    /// Noise cannot be sampled on a torus, which needs four dimensions, so samples near the far
+    /// edges are instead blended with the samples one side back, which continue the near edges.
+    /// The blend is smooth, so the slope of the noise is also continuous across the seams.
+    pub fn sample_tileable(
+        noise: &dyn NoiseFn<f64, 3>,
+        [x, y]: [f64; 2],
+        origin: [f64; 2],
+        len: f64,
+    ) -> f64 {
+        let weight = |coord: f64, origin: f64| {
+            let t = (((coord - origin) / len - 1.0) / Self::TILE_BLEND + 1.0).clamp(0.0, 1.0);
+
+            t * t * (3.0 - 2.0 * t)
+        };
+        let [weight_x, weight_y] = [weight(x, origin[0]), weight(y, origin[1])];
+
+        // Most samples are away from the far edges, which need only one sample
+        let row = |y: f64| {
+            let sample = noise.get([x, y, 0.0]);
+
+            if weight_x > 0.0 {
+                sample + (noise.get([x - len, y, 0.0]) - sample) * weight_x
+            } else {
+                sample
+            }
+        };
+        let sample = row(y);
+
+        if weight_y > 0.0 {
+            sample + (row(y - len) - sample) * weight_y
+        } else {
+            sample
+        }
+    }
+
+    pub fn try_recv_iter(&self) -> impl Iterator<Item = SubImage> + '_ {
         self.rx.try_iter()
     }
 
@@ -164,23 +467,19 @@ impl Threads {
     }
 
     #[cfg(target_arch = "wasm32")]
-    fn web_worker(
-        node_exprs: &NodeExprs,
-        rx: &Receiver<Option<(usize, usize, ImageInfo)>>,
-        tx: &Sender<(usize, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])>,
-    ) {
-        // On web we only process a small number of requests, always checking to only count
-        // requests which are actually processed (and not stale ones)
-        let mut processed = 0;
-
-        // Receive the next versioned node request
-        while let Some((node_idx, version, image_info)) = rx.try_recv().ok().flatten() {
-            if Self::process_request(&node_exprs, node_idx, version, image_info, &tx) {
-                processed += 1;
-
-                if processed == Self::REQUESTS_PER_FRAME {
-                    return;
-                }
+    fn web_worker(node_exprs: &NodeExprs, queue: &SharedQueue, tx: &Sender<SubImage>) {
+        // On web we only process requests for part of each frame, rendering at least one so that
+        // slow graphs still progress; stale requests are skipped without rendering
+        let start = Date::now();
+
+        // Take the most important versioned node request
+        let pop = || queue.0.lock().unwrap().pop();
+        while let Some(request) = pop() {
+            let is_processed = Self::process_request(node_exprs, request, tx);
+            queue.0.lock().unwrap().rendering -= 1;
+
+            if is_processed && Date::now() - start >= Self::FRAME_BUDGET_MS {
+                return;
             }
         }
     }
@@ -189,9 +488,9 @@ impl Threads {
 #[cfg(not(target_arch = "wasm32"))]
 impl Drop for Threads {
     fn drop(&mut self) {
-        for _ in 0..self.workers.len() {
-            self.tx.send(None).unwrap();
-        }
+        let (queue, requests_available) = &*self.queue;
+        queue.lock().unwrap().closed = true;
+        requests_available.notify_all();
 
         for worker in self.workers.drain(..) {
             worker.join().unwrap();
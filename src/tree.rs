@@ -0,0 +1,264 @@
+use {
+    super::app::NodeExprs,
+    egui::{CollapsingHeader, Context, Id, RichText, Ui, Window},
+    noise_graph::ExprCost,
+    std::collections::HashMap,
+};
+
+/// One value of the `Debug` representation of an `Expr`.
+struct Item {
+    children: Vec<Item>,
+    fields: bool,
+    inline: Option<String>,
+    label: String,
+    node_idx: Option<usize>,
+}
+
+impl Item {
+    fn parse(
+        src: &str,
+        pos: &mut usize,
+        prefix: &str,
+        node_indices: &HashMap<String, usize>,
+    ) -> Self {
+        let start = *pos;
+        let mut quoted = false;
+        let mut escaped = false;
+
+        // The head is the type, variant or literal which precedes any nested values
+        *pos = src[start..]
+            .char_indices()
+            .find(|&(_, char)| {
+                if quoted {
+                    match char {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => quoted = false,
+                        _ => escaped = false,
+                    }
+
+                    false
+                } else {
+                    quoted = char == '"';
+
+                    matches!(char, '(' | '[' | '{' | ',' | ')' | ']' | '}')
+                }
+            })
+            .map_or(src.len(), |(idx, _)| start + idx);
+
+        let label = format!("{prefix}{}", src[start..*pos].trim_end());
+        let mut children = vec![];
+        let mut fields = false;
+
+        if let Some(open) = src[*pos..]
+            .chars()
+            .next()
+            .filter(|char| matches!(char, '(' | '[' | '{'))
+        {
+            let close = match open {
+                '(' => ')',
+                '[' => ']',
+                _ => '}',
+            };
+
+            fields = open == '{';
+            *pos += 1;
+
+            loop {
+                *pos += src[*pos..]
+                    .find(|char: char| char != ',' && !char.is_whitespace())
+                    .unwrap_or(src.len() - *pos);
+
+                if *pos == src.len() {
+                    break;
+                }
+
+                if src[*pos..].starts_with(close) {
+                    *pos += 1;
+                    break;
+                }
+
+                // Struct fields are written as "name: value"
+                let field = if open == '{' {
+                    let name_end = *pos + src[*pos..].find(':').unwrap_or(0);
+                    let name = format!("{}: ", &src[*pos..name_end]);
+                    *pos = name_end + 1;
+                    *pos += src[*pos..].len() - src[*pos..].trim_start().len();
+
+                    name
+                } else {
+                    String::new()
+                };
+
+                children.push(Self::parse(src, pos, &field, node_indices));
+            }
+        }
+
+        // Unwrap the list or struct of a variant such as "Add([..])" or "Fbm(FractalExpr { .. })"
+        if children.len() == 1 && (children[0].label.is_empty() || children[0].fields) {
+            children = children.pop().unwrap().children;
+        }
+
+        // Values which do not contain any node are shown on a single line
+        let source = &src[start..*pos];
+        let node_idx = node_indices.get(source).copied();
+        let inline = (node_idx.is_none() && children.iter().all(|child| child.inline.is_some()))
+            .then(|| format!("{prefix}{source}"));
+
+        Self {
+            children,
+            fields,
+            inline,
+            label,
+            node_idx,
+        }
+    }
+
+    fn show(&self, ui: &mut Ui, hovered_node_idx: Option<usize>) -> Option<usize> {
+        let mut text = if let Some(inline) = &self.inline {
+            RichText::new(inline)
+        } else if let Some(node_idx) = self.node_idx {
+            RichText::new(format!("{} #{node_idx}", self.label))
+        } else {
+            RichText::new(&self.label)
+        };
+
+        if self.node_idx.is_some() && self.node_idx == hovered_node_idx {
+            text = text
+                .background_color(ui.visuals().selection.bg_fill)
+                .color(ui.visuals().selection.stroke.color);
+        }
+
+        if self.inline.is_some() {
+            ui.label(text);
+
+            return None;
+        }
+
+        let mut highlighted_node_idx = None;
+        let res = CollapsingHeader::new(text)
+            .id_source(ui.next_auto_id())
+            .default_open(true)
+            .show(ui, |ui| {
+                for child in &self.children {
+                    highlighted_node_idx =
+                        child.show(ui, hovered_node_idx).or(highlighted_node_idx);
+                }
+            });
+
+        highlighted_node_idx.or(self.node_idx.filter(|_| res.header_response.hovered()))
+    }
+}
+
+/// Collapsible window which shows the expression that would be exported for the selected node.
+#[derive(Default)]
+pub struct ExprTree {
+    /// The node hovered in the tree, highlighted in the graph.
+    pub highlighted_node_idx: Option<usize>,
+
+    cost: Option<ExprCost>,
+    root: Option<Item>,
+    version: Option<(usize, usize)>,
+}
+
+impl ExprTree {
+    /// Shows the expression of the selected node, which is deselected when the window is closed.
+    /// The node hovered in the graph is highlighted in the tree.
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        node_exprs: &NodeExprs,
+        selected_node_idx: &mut Option<usize>,
+        hovered_node_idx: Option<usize>,
+    ) {
+        self.highlighted_node_idx = None;
+
+        let Some(node_idx) = *selected_node_idx else {
+            return;
+        };
+
+        {
+            let node_exprs = node_exprs.read().unwrap();
+            let Some((version, expr)) = node_exprs.get(&node_idx) else {
+                return;
+            };
+
+            if self.version != Some((node_idx, *version)) {
+                // Sub-expressions are matched to the nodes which generate them by their text
+                let node_indices = node_exprs
+                    .iter()
+                    .map(|(node_idx, (_, expr))| (format!("{expr:?}"), *node_idx))
+                    .collect();
+
+                self.cost = Some(expr.cost());
+                self.root = Some(Item::parse(&format!("{expr:?}"), &mut 0, "", &node_indices));
+                self.version = Some((node_idx, *version));
+            }
+        }
+
+        let mut open = true;
+
+        Window::new(format!("Expression #{node_idx}"))
+            .id(Id::new("expr_tree"))
+            .open(&mut open)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                if let Some(cost) = &self.cost {
+                    Self::show_cost(ui, cost);
+                    ui.separator();
+                }
+
+                if let Some(root) = &self.root {
+                    self.highlighted_node_idx = root.show(ui, hovered_node_idx);
+                }
+            });
+
+        if !open {
+            self.clear();
+            *selected_node_idx = None;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cost = None;
+        self.root = None;
+        self.version = None;
+    }
+
+    fn show_cost(ui: &mut Ui, cost: &ExprCost) {
+        fn show_item(ui: &mut Ui, cost: &ExprCost) {
+            let text = format!("{}: ~{}", cost.label, cost.total());
+
+            if cost.children.is_empty() {
+                ui.label(text);
+            } else {
+                CollapsingHeader::new(format!("{text} ({} own)", cost.ops))
+                    .id_source(ui.next_auto_id())
+                    .show(ui, |ui| {
+                        for child in &cost.children {
+                            show_item(ui, child);
+                        }
+                    });
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!("Estimated cost: ~{} ops/sample", cost.total()))
+                .on_hover_text(
+                    "A rough count of the operations evaluated for each sample, weighted relative \
+                    to an addition",
+                );
+
+            if ui
+                .small_button("Copy")
+                .on_hover_text("Copy the breakdown as code comments")
+                .clicked()
+            {
+                ui.output_mut(|output| output.copied_text = cost.comment("//"));
+            }
+        });
+
+        CollapsingHeader::new("Cost Breakdown")
+            .id_source("expr_tree_cost")
+            .show(ui, |ui| show_item(ui, cost));
+    }
+}
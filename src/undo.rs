@@ -0,0 +1,105 @@
+use {
+    super::node::NoiseNode,
+    egui::{Context, Key, KeyboardShortcut, Modifiers},
+    egui_snarl::Snarl,
+    ron::{de::from_str, ser::to_string},
+    std::{collections::VecDeque, mem::replace},
+};
+
+/// Snapshots of the graph taken as it is edited, which are restored to undo and redo edits.
+///
+/// Snapshots are the serialized graph, so the values of nodes, their wires and their positions are
+/// all restored. Edits which last several frames, such as dragging a value or typing a name, are
+/// kept as one snapshot once the pointer is released and no text is being edited.
+pub struct UndoHistory {
+    /// Set when the graph may have changed since the current snapshot was taken.
+    changed: bool,
+
+    redo: Vec<String>,
+
+    /// The graph as it was after the most recent edit.
+    snapshot: String,
+
+    undo: VecDeque<String>,
+}
+
+impl UndoHistory {
+    /// The number of edits which may be undone.
+    const LEN: usize = 100;
+
+    pub const REDO: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+    pub const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || self.changed
+    }
+
+    /// Takes a snapshot if the graph changed and the edit is finished; graphs which serialize the
+    /// same as the current snapshot, such as after previews are rendered again, are not kept.
+    pub fn commit(&mut self, ctx: &Context, snarl: &Snarl<NoiseNode>) {
+        let is_editing = ctx.input(|input| input.pointer.any_down()) || ctx.wants_keyboard_input();
+
+        if self.changed && !is_editing {
+            self.take_snapshot(snarl);
+        }
+    }
+
+    pub fn new(snarl: &Snarl<NoiseNode>) -> Self {
+        Self {
+            changed: false,
+            redo: Default::default(),
+            snapshot: to_string(snarl).unwrap_or_default(),
+            undo: Default::default(),
+        }
+    }
+
+    /// Records that nodes or wires of the graph were changed this frame.
+    pub fn record_change(&mut self) {
+        self.changed = true;
+    }
+
+    /// Returns the graph as it was after the last undone edit, if any.
+    pub fn redo(&mut self) -> Option<Snarl<NoiseNode>> {
+        let snapshot = self.redo.pop()?;
+        let snarl = from_str(&snapshot).ok()?;
+        self.undo.push_back(replace(&mut self.snapshot, snapshot));
+
+        Some(snarl)
+    }
+
+    fn take_snapshot(&mut self, snarl: &Snarl<NoiseNode>) {
+        self.changed = false;
+
+        let Ok(snapshot) = to_string(snarl) else {
+            return;
+        };
+
+        if snapshot != self.snapshot {
+            self.undo.push_back(replace(&mut self.snapshot, snapshot));
+            self.redo.clear();
+
+            if self.undo.len() > Self::LEN {
+                self.undo.pop_front();
+            }
+        }
+    }
+
+    /// Returns the graph as it was before the last edit, if any. The current graph is kept first,
+    /// so unfinished edits are undone as well.
+    pub fn undo(&mut self, snarl: &Snarl<NoiseNode>) -> Option<Snarl<NoiseNode>> {
+        if self.changed {
+            self.take_snapshot(snarl);
+        }
+
+        let snapshot = self.undo.pop_back()?;
+        let snarl = from_str(&snapshot).ok()?;
+        self.redo.push(replace(&mut self.snapshot, snapshot));
+
+        Some(snarl)
+    }
+}
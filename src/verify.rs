@@ -0,0 +1,143 @@
+use {
+    super::{app::App, canonical},
+    anyhow::Context,
+    noise::NoiseFn,
+    noise_graph::{Backend, Expr, Graph},
+    ron::de::from_str,
+    serde::{Deserialize, Serialize},
+    std::{fs::read_to_string, path::Path},
+};
+
+/// How closely an exported expression reproduces the values it was checked against.
+pub struct Comparison {
+    pub max_error: f64,
+    pub mismatched_samples: usize,
+    pub samples: usize,
+}
+
+impl Comparison {
+    fn new(expected: &[f64], actual: &[f64]) -> Self {
+        let mut res = Self {
+            max_error: 0.0,
+            mismatched_samples: 0,
+            samples: expected.len(),
+        };
+
+        for (expected, actual) in expected.iter().zip(actual) {
+            if expected != actual && !(expected.is_nan() && actual.is_nan()) {
+                res.max_error = res.max_error.max((expected - actual).abs());
+                res.mismatched_samples += 1;
+            }
+        }
+
+        res
+    }
+
+    pub fn description(&self) -> String {
+        if self.is_identical() {
+            format!("All {} samples are identical", self.samples)
+        } else {
+            format!(
+                "{} of {} samples differ, by up to {:e}",
+                self.mismatched_samples, self.samples, self.max_error
+            )
+        }
+    }
+
+    pub fn is_identical(&self) -> bool {
+        self.mismatched_samples == 0
+    }
+}
+
+/// A grid of values sampled by the editor from an expression, which the exported expression must
+/// reproduce exactly; snapshots saved by one build may be checked by later builds, which catches
+/// changes to the generated output.
+#[derive(Deserialize, Serialize)]
+pub struct Snapshot {
+    backend: Backend,
+    expr: Expr,
+
+    /// The length of each side of the sampled square, which starts at the origin.
+    len: f64,
+
+    /// The number of samples along each side of the square, in rows.
+    size: usize,
+
+    values: Vec<f64>,
+}
+
+impl Snapshot {
+    const DEFAULT_LEN: f64 = 4.0;
+    const DEFAULT_SIZE: usize = 64;
+
+    /// Samples an expression the way node previews do.
+    pub fn new(expr: &Expr, backend: Backend) -> Self {
+        let noise = expr.noise_with(backend.get());
+        let positions = (0..Self::DEFAULT_SIZE)
+            .map(|idx| Graph::chunk_position(0, idx, Self::DEFAULT_SIZE, Self::DEFAULT_LEN))
+            .collect::<Vec<_>>();
+        let values = positions
+            .iter()
+            .flat_map(|&y| positions.iter().map(move |&x| [x, y, 0.0]))
+            .map(|point| noise.get(point))
+            .collect();
+
+        Self {
+            backend,
+            expr: expr.clone(),
+            len: Self::DEFAULT_LEN,
+            size: Self::DEFAULT_SIZE,
+            values,
+        }
+    }
+
+    /// Writes the expression as `Export File...` does, reads it back as games do and compares the
+    /// values it samples with the snapshot.
+    pub fn compare(&self) -> anyhow::Result<Comparison> {
+        let mut src = vec![];
+        canonical::to_writer(&mut src, &self.expr).context("unable to write expression")?;
+
+        let src = String::from_utf8(src)?;
+        let expr = from_str(&src).context("unable to read exported expression")?;
+        let values =
+            Graph::with_backend(expr, self.backend).sample_chunk([0, 0], self.size, self.len);
+
+        Ok(Comparison::new(&self.values, &values))
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let src =
+            read_to_string(path).with_context(|| format!("unable to read {}", path.display()))?;
+
+        from_str(&src).with_context(|| format!("{} is not a snapshot", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        App::save_as(path, self)
+    }
+}
+
+/// Checks that each snapshot is still reproduced by its exported expression, printing the result
+/// of each, and returns `true` if all of them are.
+pub fn check_snapshots(paths: impl IntoIterator<Item = String>) -> bool {
+    let mut res = true;
+
+    for path in paths {
+        match Snapshot::open(&path).and_then(|snapshot| snapshot.compare()) {
+            Ok(comparison) if comparison.is_identical() => {
+                println!("{path}: {}", comparison.description());
+            }
+            Ok(comparison) => {
+                eprintln!("{path}: {}", comparison.description());
+                res = false;
+            }
+            Err(err) => {
+                eprintln!("{path}: {err:#}");
+                res = false;
+            }
+        }
+    }
+
+    res
+}
@@ -1,31 +1,66 @@
 use {
     super::{
-        expr::{DistanceFunction, OpType, ReturnType, SourceType, MAX_FRACTAL_OCTAVES},
+        asset::{Asset, AssetPolicy},
+        clipboard::CopiedNodes,
+        export::{
+            graph_hash, share_png, BitDepth, ColorSpace, Dither, ExportRegion, ExportSettings,
+            RegionDrag, SampleFilter, Supersampling,
+        },
+        favorites::Favorites,
+        filter::CanvasFilter,
+        grid,
         node::{
-            CheckerboardNode, ClampNode, ConstantOpNode, ControlPointNode, CylindersNode,
-            ExponentNode, FractalNode, GeneratorNode,
+            AlgorithmVersion, BakeNode, CheckerboardNode, ClampNode, ConstantOpNode,
+            ControlPointNode, CurveNode, CylindersNode, ExponentNode, FastNoiseNode, FaultNode,
+            FractalNode, GeneratorNode, GradientNode, HeightmapNode, Image, JitterNode,
+            MaskedBlendNode, NodeCategory,
             NodeValue::{Node, Value},
-            NoiseNode, RigidFractalNode, ScaleBiasNode, SelectNode, TransformNode, TurbulenceNode,
-            WorleyNode,
+            NoiseNode, PlateauNode, PreviewDisplay, QuantizeNode, RebaseNode, RidgeNode,
+            RigidFractalNode, ScaleBiasNode, SelectNode, TerraceNode, ThresholdNode, TransformNode,
+            TurbulenceNode, WorleyNode,
         },
+        readout,
+        seed::SeedHistory,
+        splice::WireLayout,
+        thread::Threads,
     },
     egui::{
-        epaint::PathShape, vec2, Align, Color32, ComboBox, DragValue, Layout, Pos2, Shape, Stroke,
-        Style, TextEdit, Ui, Vec2,
+        epaint::PathShape, pos2, vec2, Align, Align2, Area, Button, Color32, ColorImage, ComboBox,
+        Context, DragValue, Event, FontId, Frame, Grid, Id, Key, KeyboardShortcut, Label, Layout,
+        Mesh, Modifiers, Order, Pos2, Rect, RichText, Sense, Shape, Stroke, Style, TextEdit,
+        TextureHandle, Ui, Vec2,
     },
     egui_snarl::{
         ui::{PinInfo, SnarlViewer},
-        InPin, OutPin, OutPinId, Snarl,
+        InPin, InPinId, OutPin, OutPinId, Snarl,
     },
     log::debug,
-    std::{cell::RefCell, collections::HashSet},
+    noise_graph::{
+        Axis, Backend, CellularDistanceFunction, CellularReturnType, Channel, DistanceFunction,
+        FastFractalType, FastNoiseType, FastRotationType, OpType, RepeatMode, ReturnType,
+        SourceType, ThresholdMode, WrapMode, MAX_FRACTAL_OCTAVES,
+    },
+    std::{cell::RefCell, collections::HashSet, hash::Hash, time::Duration},
 };
 
-#[cfg(debug_assertions)]
-use {egui::RichText, egui_snarl::InPinId};
-
 #[cfg(not(target_arch = "wasm32"))]
-use super::app::App;
+use {
+    super::{
+        app::App,
+        export::{available_memory_len, save_permutation_tables, Exports, Provenance},
+        godot::GodotNoise,
+        verify::Snapshot,
+    },
+    noise_graph::{CodegenError, Expr, RustParameters},
+    rfd::{FileDialog, MessageDialog, MessageLevel},
+    std::{fs::write, num::NonZeroUsize, thread::available_parallelism},
+};
+
+#[cfg(target_arch = "wasm32")]
+use {
+    super::export::{download, download_parts},
+    log::warn,
+};
 
 #[cfg(debug_assertions)]
 fn in_pin_remote_node<T>(snarl: &Snarl<T>, pin_id: InPinId) -> Option<usize> {
@@ -36,46 +71,298 @@ fn in_pin_remote_node<T>(snarl: &Snarl<T>, pin_id: InPinId) -> Option<usize> {
         .map(|remote| remote.node)
 }
 
+/// A window which inspects one node, opened from the node menu.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Inspector {
+    Aliasing,
+    Character,
+    Chunks,
+    Explore,
+    Sphere,
+    Stability,
+    Terrain,
+}
+
+/// The state which viewers of the graph edit, kept between frames.
+#[derive(Default)]
+pub struct ViewerState {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub exports: Exports,
+
+    pub hovered_node_idx: Option<usize>,
+    pub inspect: Option<(Inspector, usize)>,
+    pub master_seed: u32,
+    pub removed_node_indices: HashSet<usize>,
+    pub seed_history: SeedHistory,
+    pub selected_node_idx: Option<usize>,
+    pub updated_node_indices: HashSet<usize>,
+    pub wire_layout: WireLayout,
+}
+
+impl ViewerState {
+    /// Returns a viewer which neither filters, highlights nor selects the nodes of the canvas.
+    pub fn viewer(&mut self, backend: Backend, read_only: bool, touch: bool) -> Viewer<'_> {
+        Viewer {
+            backend,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            exports: &mut self.exports,
+
+            filter: None,
+            highlighted_node_idx: None,
+            hovered_node_idx: &mut self.hovered_node_idx,
+            inspect: &mut self.inspect,
+            master_seed: &mut self.master_seed,
+            node_selection: None,
+            read_only,
+            removed_node_indices: &mut self.removed_node_indices,
+            seed_history: &mut self.seed_history,
+            selected_node_idx: &mut self.selected_node_idx,
+            touch,
+            updated_node_indices: &mut self.updated_node_indices,
+            wire_layout: &mut self.wire_layout,
+        }
+    }
+}
+
 pub struct Viewer<'a> {
+    /// The backend of the project, which previews sampled outside of the image threads use.
+    pub backend: Backend,
+
+    /// Exports of images which are being written.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub exports: &'a mut Exports,
+
+    /// Dims the nodes of the canvas which do not match, when it is filtered.
+    pub filter: Option<&'a CanvasFilter>,
+
+    pub highlighted_node_idx: Option<usize>,
+    pub hovered_node_idx: &'a mut Option<usize>,
+
+    /// The window which inspects a node, which the app opens once the graph has been shown.
+    pub inspect: &'a mut Option<(Inspector, usize)>,
+
+    pub master_seed: &'a mut u32,
+
+    /// The nodes which are copied and duplicated, which are added or removed by clicking their
+    /// headers with Ctrl held; only the canvas selects nodes.
+    pub node_selection: Option<&'a mut HashSet<usize>>,
+
+    /// Disables editing the graph, other than the values of named constants.
+    pub read_only: bool,
+
     pub removed_node_indices: &'a mut HashSet<usize>,
+    pub seed_history: &'a mut SeedHistory,
+    pub selected_node_idx: &'a mut Option<usize>,
+
+    /// Adds step buttons to values, which are hard to drag precisely on touch screens.
+    pub touch: bool,
+
     pub updated_node_indices: &'a mut HashSet<usize>,
+
+    /// The positions of nodes and pins on the canvas, which nodes dropped onto wires are spliced
+    /// into and which new nodes are placed by.
+    pub wire_layout: &'a mut WireLayout,
 }
 
 impl<'a> Viewer<'a> {
     const AXES: [&'static str; 4] = ["X", "Y", "Z", "W"];
 
+    const DUPLICATE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::D);
+
+    /// The seconds a touch is held still on the header of a node before its menu opens, which is
+    /// longer than a click may last.
+    const LONG_PRESS_SECS: f64 = 0.7;
+
+    /// The amount decimal values change by when their step buttons are pressed.
+    const TOUCH_STEP: f64 = 0.1;
+
+    /// Opens the node menu when a touch is held still on the header of a node, as touch screens
+    /// have no secondary click to open it with. Any following click closes the menu, after the
+    /// item it clicked, if any, has run.
+    fn long_press_menu(
+        &mut self,
+        node_idx: usize,
+        inputs: &[InPin],
+        outputs: &[OutPin],
+        ui: &mut Ui,
+        scale: f32,
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        let id = Id::new(("long_press_menu", node_idx));
+        let header = ui.max_rect();
+        let (held_pos, held_secs) = ui.input(|input| {
+            let pointer = &input.pointer;
+            let held_secs = pointer
+                .press_start_time()
+                .filter(|_| pointer.primary_down() && !pointer.is_decidedly_dragging())
+                .filter(|_| {
+                    pointer
+                        .press_origin()
+                        .is_some_and(|pos| header.contains(pos))
+                })
+                .map(|time| input.time - time);
+
+            (pointer.press_origin(), held_secs)
+        });
+
+        match held_secs {
+            Some(secs) if secs >= Self::LONG_PRESS_SECS => {
+                ui.memory_mut(|memory| memory.data.insert_temp(id, held_pos.unwrap()));
+            }
+
+            // A held touch sends no events, so the frame it is held long enough is requested
+            Some(secs) => ui
+                .ctx()
+                .request_repaint_after(Duration::from_secs_f64(Self::LONG_PRESS_SECS - secs)),
+            None => (),
+        }
+
+        let Some(pos) = ui.memory(|memory| memory.data.get_temp::<Pos2>(id)) else {
+            return;
+        };
+
+        Area::new(id)
+            .order(Order::Foreground)
+            .fixed_pos(pos)
+            .show(ui.ctx(), |ui| {
+                Frame::menu(ui.style()).show(ui, |ui| {
+                    self.node_menu(node_idx, inputs, outputs, ui, scale, snarl);
+                });
+            });
+
+        if held_secs.is_none() && ui.input(|input| input.pointer.any_click()) {
+            ui.memory_mut(|memory| memory.data.remove::<Pos2>(id));
+        }
+    }
+
+    /// Shows the algorithm version of a seeded node, which may be clicked to accept the current
+    /// version once the output of an outdated node has been checked.
+    /// Adds a node to the right of another, with its first input connected to the output of the
+    /// other.
+    fn add_downstream(
+        &mut self,
+        ctx: &Context,
+        node_idx: usize,
+        node: NoiseNode,
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        let pos = self
+            .wire_layout
+            .downstream_pos(ctx, node_idx)
+            .unwrap_or_default();
+        let downstream_node_idx = snarl.insert_node(pos, node);
+
+        debug!("Adding #{downstream_node_idx} downstream of #{node_idx}");
+
+        self.connect(
+            &snarl.out_pin(OutPinId {
+                node: node_idx,
+                output: 0,
+            }),
+            &snarl.in_pin(InPinId {
+                node: downstream_node_idx,
+                input: 0,
+            }),
+            snarl,
+        );
+        self.updated_node_indices.insert(downstream_node_idx);
+    }
+
+    fn algorithm_badge(ui: &mut Ui, node: &mut NoiseNode) {
+        let Some(algorithm) = node.algorithm() else {
+            return;
+        };
+
+        if !node.is_algorithm_outdated() {
+            ui.weak(algorithm.to_string())
+                .on_hover_text(format!("Created with noise algorithm {algorithm}"));
+
+            return;
+        }
+
+        let text = RichText::new(format!("⚠ {algorithm}")).color(ui.visuals().warn_fg_color);
+        if ui
+            .add(Button::new(text).small())
+            .on_hover_text(format!(
+                "Created with noise algorithm {algorithm}, but the output of this kind of node \
+                changed in {}, so its seed may no longer produce the same noise.\n\nClick once \
+                the output has been checked to record {}.",
+                node.algorithm_changed().unwrap(),
+                AlgorithmVersion::CURRENT,
+            ))
+            .clicked()
+        {
+            *node.algorithm_mut().unwrap() = AlgorithmVersion::CURRENT;
+        }
+    }
+
+    /// Shows the category of a node, which is the graph menu it is added from, as a small badge.
+    fn category_badge(ui: &mut Ui, category: NodeCategory) {
+        let (text, color) = match category {
+            NodeCategory::Combiner => ("CMB", Color32::from_rgb(230, 160, 80)),
+            NodeCategory::Constant => ("CST", Color32::from_rgb(160, 160, 160)),
+            NodeCategory::Fractal => ("FRC", Color32::from_rgb(180, 130, 230)),
+            NodeCategory::Generator => ("GEN", Color32::from_rgb(110, 190, 110)),
+            NodeCategory::Modifier => ("MOD", Color32::from_rgb(100, 170, 230)),
+            NodeCategory::Selector => ("SEL", Color32::from_rgb(230, 200, 90)),
+            NodeCategory::Transformer => ("TRN", Color32::from_rgb(220, 110, 140)),
+        };
+
+        ui.label(RichText::new(text).small().strong().color(color))
+            .on_hover_text(category.name());
+    }
+
     fn control_point_pin_info(is_input: bool, filled: bool) -> PinInfo {
         let fill = Color32::from_rgb(132, 80, 24);
 
         Self::scalar_pin_info(is_input, filled, fill)
     }
 
-    // TODO: Make generic (see other combo box functions)
-    fn distance_fn_combo_box(
-        &mut self,
+    /// Covers what a node has drawn within a UI with the background of the canvas, if the filter
+    /// of the canvas does not match the node. Dimmed nodes may still be edited.
+    fn dim_filtered(&mut self, ui: &Ui, node_idx: usize, snarl: &Snarl<NoiseNode>) {
+        let Some(filter) = self.filter else {
+            return;
+        };
+
+        let node = snarl.get_node(node_idx);
+        if filter.matches(node, &self.title(node)) {
+            return;
+        }
+
+        ui.painter().rect_filled(
+            ui.min_rect().expand(2.0),
+            0.0,
+            ui.visuals().panel_fill.gamma_multiply(0.75),
+        );
+    }
+
+    /// Shows a combo box which chooses between the values of an enum, returning whether the value
+    /// changed.
+    fn enum_combo_box<T>(
         ui: &mut Ui,
-        distance_fn: &mut DistanceFunction,
-        node_idx: usize,
-    ) {
-        ComboBox::from_id_source(0)
-            .selected_text(format!("{distance_fn:?}"))
+        id: impl Hash,
+        value: &mut T,
+        all: &[T],
+        name: fn(T) -> &'static str,
+    ) -> bool
+    where
+        T: Copy + PartialEq,
+    {
+        let mut changed = false;
+
+        ComboBox::from_id_source(id)
+            .selected_text(name(*value))
             .show_ui(ui, |ui| {
                 ui.style_mut().wrap = Some(false);
                 ui.set_min_width(60.0);
-                for value in [
-                    DistanceFunction::Chebyshev,
-                    DistanceFunction::Euclidean,
-                    DistanceFunction::EuclideanSquared,
-                    DistanceFunction::Manhattan,
-                ] {
-                    if ui
-                        .selectable_value(distance_fn, value, format!("{value:?}"))
-                        .changed()
-                    {
-                        self.updated_node_indices.insert(node_idx);
-                    }
+                for &item in all {
+                    changed |= ui.selectable_value(value, item, name(item)).changed();
                 }
             });
+
+        changed
     }
 
     fn drag_value_f64(&mut self, ui: &mut Ui, scale: f32, value: &mut f64, node_idx: usize) {
@@ -83,6 +370,13 @@ impl<'a> Viewer<'a> {
             Layout::right_to_left(Align::Min).with_cross_align(Align::Center),
             |ui| {
                 ui.set_height(16.0 * scale);
+
+                // Added from the right, so the step buttons are either side of the value
+                if self.touch && ui.small_button("+").clicked() {
+                    *value += Self::TOUCH_STEP;
+                    self.updated_node_indices.insert(node_idx);
+                }
+
                 if ui
                     .add(
                         DragValue::new(value)
@@ -94,6 +388,11 @@ impl<'a> Viewer<'a> {
                 {
                     self.updated_node_indices.insert(node_idx);
                 }
+
+                if self.touch && ui.small_button("−").clicked() {
+                    *value -= Self::TOUCH_STEP;
+                    self.updated_node_indices.insert(node_idx);
+                }
             },
         );
     }
@@ -103,12 +402,53 @@ impl<'a> Viewer<'a> {
             Layout::right_to_left(Align::Min).with_cross_align(Align::Center),
             |ui| {
                 ui.set_height(16.0 * scale);
+
+                if self.touch && ui.small_button("+").clicked() {
+                    *value = (*value + 1).min(MAX_FRACTAL_OCTAVES);
+                    self.updated_node_indices.insert(node_idx);
+                }
+
                 if ui
                     .add(DragValue::new(value).clamp_range(1..=MAX_FRACTAL_OCTAVES))
                     .changed()
                 {
                     self.updated_node_indices.insert(node_idx);
                 }
+
+                if self.touch && ui.small_button("−").clicked() {
+                    *value = value.saturating_sub(1).max(1);
+                    self.updated_node_indices.insert(node_idx);
+                }
+            },
+        );
+    }
+
+    fn drag_value_seed(
+        &mut self,
+        ui: &mut Ui,
+        scale: f32,
+        value: &mut u32,
+        image: &Image,
+        node_idx: usize,
+    ) {
+        ui.with_layout(
+            Layout::right_to_left(Align::Min).with_cross_align(Align::Center),
+            |ui| {
+                ui.set_height(16.0 * scale);
+                if ui
+                    .small_button("🎲")
+                    .on_hover_text("Re-roll seed")
+                    .clicked()
+                {
+                    self.seed_history
+                        .push(ui.ctx(), Some(node_idx), *value, Some(image));
+                    *value = SeedHistory::random_seed();
+                    self.updated_node_indices.insert(node_idx);
+                }
+
+                if ui.add(DragValue::new(value)).changed() {
+                    self.updated_node_indices.insert(node_idx);
+                }
             },
         );
     }
@@ -118,19 +458,362 @@ impl<'a> Viewer<'a> {
             Layout::right_to_left(Align::Min).with_cross_align(Align::Center),
             |ui| {
                 ui.set_height(16.0 * scale);
+
+                if self.touch && ui.small_button("+").clicked() {
+                    *value = value.saturating_add(1);
+                    self.updated_node_indices.insert(node_idx);
+                }
+
                 if ui.add(DragValue::new(value)).changed() {
                     self.updated_node_indices.insert(node_idx);
                 }
+
+                if self.touch && ui.small_button("−").clicked() {
+                    *value = value.saturating_sub(1);
+                    self.updated_node_indices.insert(node_idx);
+                }
             },
         );
     }
 
+    /// Copies, pastes or duplicates the selected nodes, or the node under the pointer when none are
+    /// selected. Pasted and duplicated nodes are selected instead.
+    pub fn copy_paste(&mut self, ctx: &Context, snarl: &mut Snarl<NoiseNode>) {
+        // Text fields copy and paste their own text
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let Some(node_selection) = self.node_selection.as_deref_mut() else {
+            return;
+        };
+
+        let (copy, duplicate, escape, paste) = ctx.input_mut(|input| {
+            (
+                input.events.contains(&Event::Copy),
+                input.consume_shortcut(&Self::DUPLICATE),
+                input.key_pressed(Key::Escape),
+                input.events.iter().find_map(|event| match event {
+                    Event::Paste(text) => CopiedNodes::from_text(text),
+                    _ => None,
+                }),
+            )
+        });
+
+        if escape {
+            node_selection.clear();
+        }
+
+        let node_indices = if node_selection.is_empty() {
+            self.hovered_node_idx.iter().copied().collect()
+        } else {
+            node_selection.clone()
+        };
+
+        if copy && !node_indices.is_empty() {
+            let copied_nodes = CopiedNodes::new(ctx, &node_indices, snarl, self.wire_layout);
+            ctx.output_mut(|output| output.copied_text = copied_nodes.to_text());
+        }
+
+        if self.read_only {
+            return;
+        }
+
+        let pasted = if let Some(copied_nodes) = paste {
+            // Nodes are pasted at the pointer, or in the middle of the canvas
+            let pos = ctx
+                .pointer_hover_pos()
+                .unwrap_or_else(|| ctx.available_rect().center());
+            let pos = self.wire_layout.to_graph(ctx, pos);
+
+            Some(copied_nodes.paste(pos, snarl, self))
+        } else if duplicate && !node_indices.is_empty() {
+            let copied_nodes = CopiedNodes::new(ctx, &node_indices, snarl, self.wire_layout);
+            let pos = copied_nodes.origin() + CopiedNodes::DUPLICATE_OFFSET;
+
+            Some(copied_nodes.paste(pos, snarl, self))
+        } else {
+            None
+        };
+
+        if let (Some(pasted), Some(node_selection)) = (pasted, self.node_selection.as_deref_mut()) {
+            debug!("Pasting {} node(s)", pasted.len());
+
+            *node_selection = pasted;
+        }
+    }
+
+    /// Copies the generated code of a node to the clipboard, or explains why it has none.
+    ///
+    /// The code starts with the hash of the graph, like exported files, so that it can be traced
+    /// back to the graph it was generated from.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn copy_code(
+        ui: &mut Ui,
+        language: &str,
+        src: Result<String, CodegenError>,
+        snarl: &Snarl<NoiseNode>,
+    ) {
+        match src {
+            Ok(src) => ui.output_mut(|output| {
+                output.copied_text = format!("// Graph hash: {}\n{src}", graph_hash(snarl))
+            }),
+            Err(err) => {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title(&format!("Unable to generate {language}"))
+                    .set_description(&err.to_string())
+                    .show();
+            }
+        }
+    }
+
+    /// Saves the `.tres` of a node, or explains why it has none, and lists what Godot will not
+    /// reproduce.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_godot_resource(expr: &Expr) {
+        let noise = match GodotNoise::new(expr) {
+            Ok(noise) => noise,
+            Err(err) => {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title("Unable to export Godot resource")
+                    .set_description(&err)
+                    .show();
+
+                return;
+            }
+        };
+
+        let Some(mut path) = FileDialog::new()
+            .add_filter("Godot Resource", &["tres"])
+            .save_file()
+        else {
+            return;
+        };
+
+        if path.extension().is_none() {
+            path.set_extension("tres");
+        }
+
+        if noise.save(path).is_ok() && !noise.warnings.is_empty() {
+            MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title("Godot resource differs from the graph")
+                .set_description(&noise.warnings.join("\n"))
+                .show();
+        }
+    }
+
+    /// Shows whether the exported expression of a snapshot reproduces its values.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn verify_export(snapshot: &Snapshot) {
+        let (level, title, description) = match snapshot.compare() {
+            Ok(comparison) if comparison.is_identical() => (
+                MessageLevel::Info,
+                "Export matches the editor",
+                comparison.description(),
+            ),
+            Ok(comparison) => (
+                MessageLevel::Warning,
+                "Export differs from the editor",
+                comparison.description(),
+            ),
+            Err(err) => (
+                MessageLevel::Error,
+                "Unable to verify export",
+                format!("{err:#}"),
+            ),
+        };
+
+        MessageDialog::new()
+            .set_level(level)
+            .set_title(title)
+            .set_description(&description)
+            .show();
+    }
+
+    fn export_image_menu(&mut self, ui: &mut Ui, node_idx: usize, snarl: &mut Snarl<NoiseNode>) {
+        let image = snarl.get_node_mut(node_idx).image_mut().unwrap();
+        let settings = &mut image.export;
+
+        ui.horizontal(|ui| {
+            ui.label("Size");
+            ui.add(
+                DragValue::new(&mut settings.size)
+                    .clamp_range(1..=ExportSettings::MAX_SIZE)
+                    .suffix(" px"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            for size in ExportSettings::PRESET_SIZES {
+                ui.selectable_value(&mut settings.size, size, size.to_string());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.toggle_value(&mut image.selecting_region, "Select Region")
+                .on_hover_text(
+                    "Drag a square on the preview to export instead of the area it shows; drag \
+                    the corners of the square to resize it or its inside to move it",
+                );
+
+            if ui
+                .add_enabled(settings.region.is_some(), Button::new("Reset Region"))
+                .on_hover_text("Export the area shown by the preview")
+                .clicked()
+            {
+                settings.region = None;
+            }
+        });
+
+        if let Some(region) = &settings.region {
+            ui.weak(Self::region_text(region));
+        }
+
+        ui.horizontal(|ui| {
+            for bit_depth in BitDepth::ALL {
+                ui.radio_value(&mut settings.bit_depth, bit_depth, bit_depth.name());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            for color_space in ColorSpace::ALL {
+                ui.radio_value(&mut settings.color_space, color_space, color_space.name());
+            }
+        })
+        .response
+        .on_hover_text(
+            "Linear writes values as they are, for data such as heightmaps; sRGB treats values as \
+            linear light and encodes them for display, for color textures",
+        );
+
+        ui.add_enabled_ui(settings.bit_depth == BitDepth::Eight, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Dither");
+
+                for dither in Dither::ALL {
+                    ui.radio_value(&mut settings.dither, dither, dither.name());
+                }
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Supersampling");
+
+            for supersampling in Supersampling::ALL {
+                ui.radio_value(
+                    &mut settings.supersampling,
+                    supersampling,
+                    supersampling.name(),
+                );
+            }
+        })
+        .response
+        .on_hover_text(
+            "Sample each pixel several times, which smooths hard edges such as those of Select \
+            masks; exports take longer and no longer match the preview exactly",
+        );
+
+        ui.add_enabled_ui(settings.supersampling != Supersampling::None, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Filter");
+
+                for filter in SampleFilter::ALL {
+                    ui.radio_value(&mut settings.filter, filter, filter.name());
+                }
+            })
+            .response
+            .on_hover_text(
+                "Box weighs the samples of a pixel equally; Gaussian weighs them by distance and \
+                includes samples of neighbouring pixels, which is smoother",
+            );
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.checkbox(&mut settings.sidecar, "Write JSON Metadata")
+            .on_hover_text("Also write the metadata stored in the image to a .json file beside it");
+
+        ui.separator();
+
+        // Exports are written a band of rows at a time, so only the file grows with the image
+        let mib = |len: usize| len as f64 / (1024.0 * 1024.0);
+        let memory_len = settings.memory_len();
+        ui.weak(format!(
+            "Up to {:.1} MiB file, {:.1} MiB of memory",
+            mib(settings.file_len()),
+            mib(memory_len)
+        ));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if available_memory_len().is_some_and(|available_len| memory_len > available_len) {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "⚠ More memory is needed than is available",
+            );
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button("Save PNG...").clicked() {
+            if let Some(mut path) = FileDialog::new()
+                .add_filter("PNG Image", &["png"])
+                .save_file()
+            {
+                if path.extension().is_none() {
+                    path.set_extension("png");
+                }
+
+                let node = snarl.get_node(node_idx);
+                self.exports.save_png(
+                    path,
+                    node.expr(node_idx, snarl),
+                    node.image().unwrap().clone(),
+                    Provenance::new(snarl, self.backend),
+                );
+            }
+
+            ui.close_menu();
+        }
+
+        // Browsers cannot start threads, so the image is rendered before the download starts
+        #[cfg(target_arch = "wasm32")]
+        if ui.button("Download PNG").clicked() {
+            let node = snarl.get_node(node_idx);
+            let image = node.image().unwrap();
+            let png = image
+                .export
+                .encode_png(&node.expr(node_idx, snarl), self.backend, image);
+
+            if let Err(err) = download_parts("noise.png", "image/png", &png) {
+                warn!("Unable to download image: {err:?}");
+            }
+
+            ui.close_menu();
+        }
+    }
+
     fn f64_pin_info(is_input: bool, filled: bool) -> PinInfo {
         let fill = Color32::from_rgb(128, 64, 192);
 
         Self::scalar_pin_info(is_input, filled, fill)
     }
 
+    /// Calls `f` with a viewer of a graph which is not shown on the canvas, so that the graph is
+    /// edited by the same rules as the canvas.
+    pub fn hidden<R>(
+        backend: Backend,
+        master_seed: u32,
+        f: impl FnOnce(&mut Viewer<'_>) -> R,
+    ) -> R {
+        let mut state = ViewerState {
+            master_seed,
+            ..Default::default()
+        };
+
+        f(&mut state.viewer(backend, false, false))
+    }
+
     fn image_pin_info(is_input: bool, filled: bool) -> PinInfo {
         PinInfo::default()
             .with_fill(Color32::from_gray(192))
@@ -177,98 +860,1191 @@ impl<'a> Viewer<'a> {
             )))
     }
 
-    fn operation_pin_info(is_input: bool, filled: bool) -> PinInfo {
-        let fill = Color32::from_gray(127);
+    fn inline_control_points_menu(
+        &mut self,
+        ui: &mut Ui,
+        node_idx: usize,
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        fn drag_value(ui: &mut Ui, value: &mut f64) -> bool {
+            ui.add(
+                DragValue::new(value)
+                    .min_decimals(2)
+                    .max_decimals(2)
+                    .speed(0.01),
+            )
+            .changed()
+        }
 
-        Self::scalar_pin_info(is_input, filled, fill)
-    }
+        let mut changed = false;
+        let mut removed_idx = None;
 
-    // TODO: Make generic (see other combo box functions)
-    fn return_ty_combo_box(&mut self, ui: &mut Ui, return_ty: &mut ReturnType, node_idx: usize) {
-        ComboBox::from_id_source(1)
-            .selected_text(format!("{return_ty:?}"))
-            .show_ui(ui, |ui| {
-                ui.style_mut().wrap = Some(false);
-                ui.set_min_width(60.0);
-                for value in [ReturnType::Distance, ReturnType::Value] {
-                    if ui
-                        .selectable_value(return_ty, value, format!("{value:?}"))
-                        .changed()
-                    {
-                        self.updated_node_indices.insert(node_idx);
-                    }
+        match snarl.get_node_mut(node_idx) {
+            NoiseNode::Curve(node) => {
+                for (idx, (input, output)) in node.inline_control_points.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label("In");
+                        changed |= drag_value(ui, input);
+                        ui.label("Out");
+                        changed |= drag_value(ui, output);
+
+                        if ui.small_button("🗙").clicked() {
+                            removed_idx = Some(idx);
+                        }
+                    });
                 }
-            });
+
+                if let Some(idx) = removed_idx {
+                    node.inline_control_points.remove(idx);
+                }
+
+                if ui.button("Add").clicked() {
+                    let input = node
+                        .inline_control_points
+                        .last()
+                        .map_or(-1.0, |&(input, _)| input + 0.25);
+                    node.inline_control_points.push((input, input));
+                    changed = true;
+                }
+            }
+            NoiseNode::Terrace(node) => {
+                for (idx, value) in node.inline_control_points.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        changed |= drag_value(ui, value);
+
+                        if ui.small_button("🗙").clicked() {
+                            removed_idx = Some(idx);
+                        }
+                    });
+                }
+
+                if let Some(idx) = removed_idx {
+                    node.inline_control_points.remove(idx);
+                }
+
+                if ui.button("Add").clicked() {
+                    let value = node
+                        .inline_control_points
+                        .last()
+                        .map_or(-1.0, |value| value + 0.25);
+                    node.inline_control_points.push(value);
+                    changed = true;
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        if changed || removed_idx.is_some() {
+            self.updated_node_indices.insert(node_idx);
+        }
     }
 
-    // TODO: Make generic (see other combo box functions)
-    fn source_ty_combo_box(&mut self, ui: &mut Ui, source: &mut SourceType, node_idx: usize) {
-        ComboBox::from_id_source(0)
-            .selected_text(format!("{source:?}"))
-            .show_ui(ui, |ui| {
-                ui.style_mut().wrap = Some(false);
-                ui.set_min_width(60.0);
-                for value in [
-                    SourceType::OpenSimplex,
-                    SourceType::Perlin,
-                    SourceType::PerlinSurflet,
-                    SourceType::Simplex,
-                    SourceType::SuperSimplex,
-                    SourceType::Value,
-                    SourceType::Worley,
-                ] {
-                    if ui
-                        .selectable_value(source, value, format!("{value:?}"))
-                        .changed()
-                    {
-                        self.updated_node_indices.insert(node_idx);
+    fn operation_pin_info(is_input: bool, filled: bool) -> PinInfo {
+        let fill = Color32::from_gray(127);
+
+        Self::scalar_pin_info(is_input, filled, fill)
+    }
+
+    /// Shows the label of an input of a list of `len` inputs, which is reordered by dragging the
+    /// label of a connected input onto another input of the list, as the outline reorders control
+    /// points. Returns the indices the input moved from and to once it is dropped.
+    fn reorder_label(
+        ui: &mut Ui,
+        text: &str,
+        node_idx: usize,
+        idx: usize,
+        len: usize,
+        is_draggable: bool,
+    ) -> Option<(usize, usize)> {
+        let id = Id::new("dragged_input");
+        let dragged = ui.memory(|memory| memory.data.get_temp::<(usize, usize)>(id));
+
+        let res = if is_draggable {
+            ui.add(Label::new(text).sense(Sense::click_and_drag()))
+                .on_hover_text("Drag onto another input to reorder")
+        } else {
+            ui.label(text)
+        };
+
+        if res.drag_started() {
+            ui.memory_mut(|memory| memory.data.insert_temp(id, (node_idx, idx)));
+        }
+
+        let (dragged_node_idx, from) = dragged?;
+        let mut moved = None;
+
+        // The whole row of the input is a drop target
+        let row = Rect::from_x_y_ranges(ui.max_rect().x_range(), res.rect.y_range());
+        if dragged_node_idx == node_idx && from != idx && idx < len && ui.rect_contains_pointer(row)
+        {
+            ui.painter()
+                .hline(row.x_range(), row.bottom(), ui.visuals().selection.stroke);
+
+            if ui.input(|input| input.pointer.any_released()) {
+                moved = Some((from, idx));
+            }
+        }
+
+        // The drag ends the frame after it is released, once every input has seen the release
+        if ui.input(|input| !input.pointer.any_down() && !input.pointer.any_released()) {
+            ui.memory_mut(|memory| memory.data.remove::<(usize, usize)>(id));
+        }
+
+        moved
+    }
+
+    /// Removes a node, resetting the inputs it was connected to with its last value.
+    ///
+    /// Nodes which pass a source through are removed from the middle of a chain by connecting
+    /// the source to the inputs the node was connected to.
+    pub fn remove_node(
+        &mut self,
+        node_idx: usize,
+        inputs: &[InPin],
+        outputs: &[OutPin],
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        self.removed_node_indices.insert(node_idx);
+
+        if let Some(&source) = snarl
+            .get_node(node_idx)
+            .has_source()
+            .then(|| inputs.first().and_then(|input| input.remotes.first()))
+            .flatten()
+        {
+            for &remote in outputs.iter().flat_map(|output| output.remotes.iter()) {
+                self.connect(&snarl.out_pin(source), &snarl.in_pin(remote), snarl);
+            }
+        }
+
+        for remote in outputs.iter().flat_map(|output| output.remotes.iter()) {
+            self.updated_node_indices.insert(remote.node);
+            match (remote.input, snarl.get_node(remote.node)) {
+                (
+                    0,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_fractal_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (0, NoiseNode::Checkerboard(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_checkerboard_mut()
+                        .unwrap()
+                        .size = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (0, NoiseNode::ControlPoint(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_control_point_mut()
+                        .unwrap()
+                        .input = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (0, NoiseNode::Cylinders(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_cylinders_mut()
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (0, NoiseNode::Heightmap(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_heightmap_mut()
+                        .unwrap()
+                        .size = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (0, NoiseNode::Gradient(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_gradient_mut()
+                        .unwrap()
+                        .angle = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (
+                    0,
+                    NoiseNode::BlueNoise(_)
+                    | NoiseNode::OpenSimplex(_)
+                    | NoiseNode::Perlin(_)
+                    | NoiseNode::PerlinSurflet(_)
+                    | NoiseNode::Simplex(_)
+                    | NoiseNode::SuperSimplex(_)
+                    | NoiseNode::Value(_)
+                    | NoiseNode::WhiteNoise(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_generator_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (0, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (0, NoiseNode::Fault(_)) => {
+                    snarl.get_node_mut(remote.node).as_fault_mut().unwrap().seed =
+                        Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (0, NoiseNode::Worley(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_worley_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (0 | 1, NoiseNode::FastNoise(_)) => {
+                    let value = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    *snarl
+                        .get_node_mut(remote.node)
+                        .as_fast_noise_mut()
+                        .unwrap()
+                        .u32_value_mut(remote.input)
+                        .unwrap() = value;
+                }
+                (0 | 1, NoiseNode::F64Operation(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_const_op_f64_mut()
+                        .unwrap()
+                        .inputs[remote.input] = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (0 | 1, NoiseNode::Operation(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_const_op_tuple_mut()
+                        .unwrap()
+                        .inputs[remote.input] = Default::default();
+                }
+                (0 | 1, NoiseNode::U32Operation(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_const_op_u32_mut()
+                        .unwrap()
+                        .inputs[remote.input] = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (
+                    1,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_fractal_mut()
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (1, NoiseNode::Bake(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_bake_mut()
+                        .unwrap()
+                        .resolution = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (1, NoiseNode::Clamp(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_clamp_mut()
+                        .unwrap()
+                        .lower_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::ControlPoint(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_control_point_mut()
+                        .unwrap()
+                        .output = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::Exponent(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_exponent_mut()
+                        .unwrap()
+                        .exponent = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::Fault(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_fault_mut()
+                        .unwrap()
+                        .iterations = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (1, NoiseNode::Gradient(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_gradient_mut()
+                        .unwrap()
+                        .offset = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::Jitter(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_jitter_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (1, NoiseNode::Quantize(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_quantize_mut()
+                        .unwrap()
+                        .levels = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (1, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (1, NoiseNode::Plateau(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_plateau_mut()
+                        .unwrap()
+                        .level = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::Rebase(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_rebase_mut()
+                        .unwrap()
+                        .period = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::Ridge(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_ridge_mut()
+                        .unwrap()
+                        .sharpness = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::ScaleBias(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_scale_bias_mut()
+                        .unwrap()
+                        .scale = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::Threshold(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_threshold_mut()
+                        .unwrap()
+                        .off_threshold = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1, NoiseNode::Turbulence(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_turbulence_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (1, NoiseNode::Worley(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_worley_mut()
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1..=2, NoiseNode::Checkerboard(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_checkerboard_mut()
+                        .unwrap()
+                        .offset[remote.input - 1] = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (3, NoiseNode::Checkerboard(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_checkerboard_mut()
+                        .unwrap()
+                        .rotation = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1..=3, NoiseNode::Cylinders(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_cylinders_mut()
+                        .unwrap()
+                        .axis_frequencies[remote.input - 1] =
+                        Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (1..=3, NoiseNode::Repeat(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_repeat_mut()
+                        .unwrap()
+                        .periods[remote.input - 1] =
+                        Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (
+                    1..=4,
+                    NoiseNode::RotatePoint(_)
+                    | NoiseNode::ScalePoint(_)
+                    | NoiseNode::TranslatePoint(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_transform_mut()
+                        .unwrap()
+                        .axes[remote.input - 1] = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (
+                    2,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_fractal_mut()
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Bake(_)) => {
+                    snarl.get_node_mut(remote.node).as_bake_mut().unwrap().size =
+                        Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Clamp(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_clamp_mut()
+                        .unwrap()
+                        .upper_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Fault(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_fault_mut()
+                        .unwrap()
+                        .extent = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Jitter(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_jitter_mut()
+                        .unwrap()
+                        .amount = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Quantize(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_quantize_mut()
+                        .unwrap()
+                        .dither = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Plateau(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_plateau_mut()
+                        .unwrap()
+                        .softness = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Ridge(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_ridge_mut()
+                        .unwrap()
+                        .offset = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::ScaleBias(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_scale_bias_mut()
+                        .unwrap()
+                        .bias = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Threshold(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_threshold_mut()
+                        .unwrap()
+                        .on_threshold = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2, NoiseNode::Turbulence(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_turbulence_mut()
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (
+                    3,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_fractal_mut()
+                        .unwrap()
+                        .lacunarity = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (3, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .lacunarity = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (3, NoiseNode::MaskedBlend(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_masked_blend_mut()
+                        .unwrap()
+                        .threshold = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (3, NoiseNode::Select(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_select_mut()
+                        .unwrap()
+                        .lower_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (3, NoiseNode::Quantize(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_quantize_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (3, NoiseNode::Threshold(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_threshold_mut()
+                        .unwrap()
+                        .scan_steps = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (3, NoiseNode::Turbulence(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_turbulence_mut()
+                        .unwrap()
+                        .power = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (
+                    4,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_fractal_mut()
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (4, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (4, NoiseNode::MaskedBlend(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_masked_blend_mut()
+                        .unwrap()
+                        .feather = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (4, NoiseNode::Select(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_select_mut()
+                        .unwrap()
+                        .upper_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (4, NoiseNode::Turbulence(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_turbulence_mut()
+                        .unwrap()
+                        .roughness = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                }
+                (
+                    5,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_fractal_mut()
+                        .unwrap()
+                        .octave_remainder = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (5, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .attenuation = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (5, NoiseNode::Select(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_select_mut()
+                        .unwrap()
+                        .falloff = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (6, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .octave_remainder = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                }
+                (2..=7, NoiseNode::FastNoise(_)) => {
+                    let value = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    *snarl
+                        .get_node_mut(remote.node)
+                        .as_fast_noise_mut()
+                        .unwrap()
+                        .f64_value_mut(remote.input)
+                        .unwrap() = value;
+                }
+                (5..=10, NoiseNode::Turbulence(_)) => {
+                    let value = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    if let Some(axis_value) = snarl
+                        .get_node_mut(remote.node)
+                        .as_turbulence_mut()
+                        .unwrap()
+                        .axis_value_mut(remote.input)
+                    {
+                        *axis_value = value;
+                    }
+                }
+                (control_point_idx, NoiseNode::Curve(_)) if control_point_idx > 0 => {
+                    let node = snarl.get_node_mut(remote.node).as_curve_mut().unwrap();
+                    node.control_point_node_indices[control_point_idx - 1] = None;
+
+                    while let Some(None) = node.control_point_node_indices.last() {
+                        node.control_point_node_indices.pop();
+                    }
+                }
+                (control_point_idx, NoiseNode::Terrace(_)) if control_point_idx > 0 => {
+                    let node = snarl.get_node_mut(remote.node).as_terrace_mut().unwrap();
+                    node.control_point_node_indices[control_point_idx - 1] = None;
+
+                    while let Some(None) = node.control_point_node_indices.last() {
+                        node.control_point_node_indices.pop();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for node_idx in inputs
+            .iter()
+            .flat_map(|input| input.remotes.iter().map(|remote| remote.node))
+            .chain(
+                outputs
+                    .iter()
+                    .flat_map(|output| output.remotes.iter().map(|remote| remote.node)),
+            )
+        {
+            NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+            NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+        }
+
+        snarl.remove_node(node_idx);
+    }
+
+    fn scalar_pin_info(is_input: bool, filled: bool, fill: Color32) -> PinInfo {
+        let (r, g, b, _) = fill.to_tuple();
+
+        PinInfo::default()
+            .with_fill(fill)
+            .with_stroke(Stroke::new(
+                1.5,
+                Color32::from_rgba_unmultiplied(r, g, b, 192),
+            ))
+            .with_shape(egui_snarl::ui::PinShape::Custom(Box::new(
+                move |painter, rect, _fill, stroke| {
+                    const S: f32 = 1.0;
+                    const A: Vec2 = vec2(0.64 * S, 0.07 * S);
+                    const B: Vec2 = vec2(0.64 * S, -0.07 * S);
+                    const C: Vec2 = vec2(0.72 * S, 0.0 * S);
+
+                    let mut pos = rect.min;
+                    let size = rect.size();
+                    pos.y += 0.5 * size.y;
+
+                    if !is_input {
+                        pos.x += 0.6 * size.x;
+                    }
+
+                    let points = vec![pos + A * size, pos + B * size, pos + C * size];
+
+                    painter.add(Shape::Path(PathShape {
+                        points,
+                        closed: true,
+                        fill,
+                        stroke,
+                    }));
+
+                    let radius = 0.5 * size.x;
+
+                    painter.add(if filled {
+                        Shape::circle_filled(pos, radius, fill)
+                    } else {
+                        Shape::circle_stroke(pos, radius, stroke)
+                    });
+                },
+            )))
+    }
+
+    /// Replaces the master seed, keeping the previous one in the seed history along with a
+    /// thumbnail of the first node which does not output to any other node.
+    fn set_master_seed(&mut self, ctx: &Context, master_seed: u32, snarl: &mut Snarl<NoiseNode>) {
+        let image = snarl
+            .node_indices()
+            .find(|&(node_idx, node)| {
+                node.has_image()
+                    && snarl
+                        .out_pin(OutPinId {
+                            node: node_idx,
+                            output: 0,
+                        })
+                        .remotes
+                        .is_empty()
+            })
+            .and_then(|(_, node)| node.image());
+
+        self.seed_history.push(ctx, None, *self.master_seed, image);
+        *self.master_seed = master_seed;
+        self.update_master_seed(snarl);
+    }
+
+    /// Saves a small PNG of the preview of a node, or downloads it on web, named after the hash of
+    /// the graph.
+    fn share_preview(node_idx: usize, snarl: &Snarl<NoiseNode>, backend: Backend) {
+        let node = snarl.get_node(node_idx);
+        let Some(image) = node.image() else {
+            return;
+        };
+
+        let hash = graph_hash(snarl);
+        let name = format!("noise-{hash}.png");
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(mut path) = FileDialog::new()
+                .add_filter("PNG Image", &["png"])
+                .set_file_name(&name)
+                .save_file()
+            else {
+                return;
+            };
+
+            if path.extension().is_none() {
+                path.set_extension("png");
+            }
+
+            let png = share_png(
+                &node.expr(node_idx, snarl),
+                backend,
+                image,
+                &hash,
+                available_parallelism().map_or(1, NonZeroUsize::get),
+            );
+
+            if let Err(err) = write(path, png) {
+                MessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title("Unable to save preview")
+                    .set_description(&err.to_string())
+                    .show();
+            }
+        }
+
+        // Browsers cannot start threads, so the preview is rendered before the download starts
+        #[cfg(target_arch = "wasm32")]
+        {
+            let png = share_png(&node.expr(node_idx, snarl), backend, image, &hash, 1);
+
+            if let Err(err) = download(&name, "image/png", &png) {
+                warn!("Unable to download preview: {err:?}");
+            }
+        }
+    }
+
+    /// Shows a bar of the gray levels used by images, labelled with the values they represent.
+    fn show_legend(ui: &mut Ui, width: f32, scale: f32, display: &PreviewDisplay) {
+        const LEGEND_SEGMENTS: usize = 32;
+        const TICKS: usize = 5;
+
+        let [min, max] = Threads::VALUE_RANGE;
+        let font_id = FontId::proportional(9.0 * scale);
+        let bar_height = 8.0 * scale;
+        let (rect, res) = ui.allocate_exact_size(
+            vec2(width, bar_height + font_id.size + 2.0 * scale),
+            Sense::hover(),
+        );
+        let bar = Rect::from_min_size(rect.min, vec2(width, bar_height));
+
+        // The bar is split into segments so that it follows the display settings of the preview
+        let mut mesh = Mesh::default();
+        for segment in 0..=LEGEND_SEGMENTS {
+            let t = segment as f32 / LEGEND_SEGMENTS as f32;
+            let x = bar.left() + t * bar.width();
+            let color = Color32::from_gray(display.apply((t * 255.0).round() as u8));
+            mesh.colored_vertex(pos2(x, bar.top()), color);
+            mesh.colored_vertex(pos2(x, bar.bottom()), color);
+
+            if segment > 0 {
+                let idx = segment as u32 * 2;
+                mesh.add_triangle(idx - 2, idx - 1, idx);
+                mesh.add_triangle(idx - 1, idx + 1, idx);
+            }
+        }
+        ui.painter().add(mesh);
+
+        let stroke = ui.visuals().widgets.noninteractive.fg_stroke;
+        for tick in 0..TICKS {
+            let t = tick as f32 / (TICKS - 1) as f32;
+            let x = bar.left() + t * bar.width();
+            let align = match tick {
+                0 => Align2::LEFT_TOP,
+                tick if tick == TICKS - 1 => Align2::RIGHT_TOP,
+                _ => Align2::CENTER_TOP,
+            };
+
+            ui.painter()
+                .vline(x, bar.bottom()..=bar.bottom() + 2.0 * scale, stroke);
+            ui.painter().text(
+                pos2(x, bar.bottom() + 2.0 * scale),
+                align,
+                format!("{}", min + t as f64 * (max - min)),
+                font_id.clone(),
+                stroke.color,
+            );
+        }
+
+        let [min_color, max_color] = if display.invert {
+            ["white", "black"]
+        } else {
+            ["black", "white"]
+        };
+
+        res.on_hover_text(format!(
+            "Values from {min} ({min_color}) to {max} ({max_color}); values outside are clamped"
+        ));
+    }
+
+    /// Tints the image of a node which is the control of the selected Select (or Masked Blend)
+    /// node using the selection bounds: the selected range and the falloff bands around each bound.
+    fn show_select_bounds(&self, ui: &Ui, rect: Rect, pin: &OutPin, snarl: &mut Snarl<NoiseNode>) {
+        let Some((select_node_idx, [lower_bound, upper_bound, falloff])) = pin
+            .remotes
+            .iter()
+            .filter(|remote| remote.input == 2)
+            .filter(|remote| {
+                *self.selected_node_idx == Some(remote.node)
+                    || self.highlighted_node_idx == Some(remote.node)
+            })
+            .find_map(|remote| match snarl.get_node(remote.node) {
+                NoiseNode::MaskedBlend(node) => Some((remote.node, node.bounds(snarl))),
+                NoiseNode::Select(node) => Some((remote.node, node.bounds(snarl))),
+                _ => None,
+            })
+        else {
+            return;
+        };
+
+        let Some(image) = snarl.get_node_mut(pin.id.node).image_mut() else {
+            return;
+        };
+
+        let Some(size) = image.texture.as_ref().map(TextureHandle::size) else {
+            return;
+        };
+
+        let selected = Color32::from_rgba_unmultiplied(64, 160, 255, 96);
+        let blended = Color32::from_rgba_unmultiplied(255, 160, 64, 96);
+        let overlay = ColorImage {
+            size,
+            pixels: image
+                .pixels
+                .iter()
+                .map(|&pixel| {
+                    let value = Threads::gray_to_value(pixel);
+
+                    if (value - lower_bound).abs() < falloff
+                        || (value - upper_bound).abs() < falloff
+                    {
+                        blended
+                    } else if (lower_bound..upper_bound).contains(&value) {
+                        selected
+                    } else {
+                        Color32::TRANSPARENT
+                    }
+                })
+                .collect(),
+        };
+
+        if let Some(texture) = &mut image.overlay_texture {
+            texture.set(overlay, Default::default());
+        } else {
+            image.overlay_texture = Some(ui.ctx().load_texture(
+                format!("overlay{}", pin.id.node),
+                overlay,
+                Default::default(),
+            ));
+        }
+
+        let texture = image.overlay_texture.as_ref().unwrap();
+        ui.painter().image(
+            texture.id(),
+            rect,
+            Rect::from_min_max(Pos2::ZERO, pos2(1.0, 1.0)),
+            Color32::WHITE,
+        );
+
+        ui.interact(rect, ui.id().with("select_bounds"), Sense::hover())
+            .on_hover_text(if upper_bound == MaskedBlendNode::UPPER_BOUND {
+                format!(
+                    "Masked Blend #{select_node_idx}: above {lower_bound:.3}, feather {falloff:.3}"
+                )
+            } else {
+                format!(
+                    "Select #{select_node_idx}: {lower_bound:.3} to {upper_bound:.3}, falloff \
+                    {falloff:.3}"
+                )
+            });
+    }
+
+    /// Draws the export region of a node over its preview with a handle at each corner. While the
+    /// region is being selected, dragging a handle resizes it, dragging its inside moves it and
+    /// dragging anywhere else draws a new region.
+    fn show_export_region(ui: &mut Ui, rect: Rect, node_idx: usize, snarl: &mut Snarl<NoiseNode>) {
+        const HANDLE_RADIUS: f32 = 3.0;
+
+        let Some(image) = snarl.get_node_mut(node_idx).image_mut() else {
+            return;
+        };
+
+        // Previews show `x` to `x + 1` across and `y` to `y + 1` down, times the scale
+        let (x, y, scale) = (image.x, image.y, image.scale);
+        let to_world = |pos: Pos2| {
+            [
+                ((pos.x - rect.left()) / rect.width()) as f64 + x,
+                ((pos.y - rect.top()) / rect.height()) as f64 + y,
+            ]
+            .map(|coord| coord * scale)
+        };
+        let to_screen = |[world_x, world_y]: [f64; 2]| {
+            pos2(
+                rect.left() + ((world_x / scale - x) * rect.width() as f64) as f32,
+                rect.top() + ((world_y / scale - y) * rect.height() as f64) as f32,
+            )
+        };
+
+        if image.selecting_region {
+            let response = ui.interact(rect, ui.id().with("export_region"), Sense::drag());
+
+            if let Some(pos) = response.interact_pointer_pos() {
+                let pos = to_world(pos.clamp(rect.min, rect.max));
+
+                if response.drag_started() {
+                    image.region_drag = Some(match image.export.region {
+                        Some(region) => {
+                            let [min, max] = region.bounds();
+                            let corners = [min, [max[0], min[1]], [min[0], max[1]], max];
+                            let pointer = response.interact_pointer_pos().unwrap();
+
+                            if let Some(corner) = corners.iter().position(|&corner| {
+                                to_screen(corner).distance(pointer) <= HANDLE_RADIUS * 2.0
+                            }) {
+                                RegionDrag::Resize(corners[3 - corner])
+                            } else if (min[0]..max[0]).contains(&pos[0])
+                                && (min[1]..max[1]).contains(&pos[1])
+                            {
+                                RegionDrag::Move([pos[0] - min[0], pos[1] - min[1]])
+                            } else {
+                                RegionDrag::Resize(pos)
+                            }
+                        }
+                        None => RegionDrag::Resize(pos),
+                    });
+                }
+
+                match image.region_drag {
+                    Some(RegionDrag::Move(offset)) => {
+                        if let Some(region) = &mut image.export.region {
+                            region.x = (pos[0] - offset[0]) / region.scale;
+                            region.y = (pos[1] - offset[1]) / region.scale;
+                        }
+                    }
+                    Some(RegionDrag::Resize(anchor)) => {
+                        if let Some(region) = ExportRegion::from_corners(anchor, pos) {
+                            image.export.region = Some(region);
+                        }
                     }
+                    None => (),
                 }
-            });
+            }
+
+            if response.drag_released() {
+                image.region_drag = None;
+            }
+        }
+
+        let Some(region) = image.export.region else {
+            return;
+        };
+
+        let [min, max] = region.bounds();
+        let region_rect = Rect::from_min_max(to_screen(min), to_screen(max));
+        let painter = ui.painter().with_clip_rect(rect);
+        let stroke = Stroke::new(1.5, Color32::from_rgb(255, 200, 64));
+
+        painter.rect_stroke(region_rect, 0.0, stroke);
+
+        if image.selecting_region {
+            for corner in [
+                region_rect.left_top(),
+                region_rect.right_top(),
+                region_rect.left_bottom(),
+                region_rect.right_bottom(),
+            ] {
+                painter.rect_filled(
+                    Rect::from_center_size(corner, Vec2::splat(HANDLE_RADIUS * 2.0)),
+                    0.0,
+                    stroke.color,
+                );
+            }
+        }
+
+        ui.weak(Self::region_text(&region));
     }
 
-    fn scalar_pin_info(is_input: bool, filled: bool, fill: Color32) -> PinInfo {
-        let (r, g, b, _) = fill.to_tuple();
+    /// Describes the world coordinates an export region covers; like the previews, the first
+    /// coordinate runs down the region.
+    fn region_text(region: &ExportRegion) -> String {
+        let [min, max] = region.bounds();
 
-        PinInfo::default()
-            .with_fill(fill)
-            .with_stroke(Stroke::new(
-                1.5,
-                Color32::from_rgba_unmultiplied(r, g, b, 192),
-            ))
-            .with_shape(egui_snarl::ui::PinShape::Custom(Box::new(
-                move |painter, rect, _fill, stroke| {
-                    const S: f32 = 1.0;
-                    const A: Vec2 = vec2(0.64 * S, 0.07 * S);
-                    const B: Vec2 = vec2(0.64 * S, -0.07 * S);
-                    const C: Vec2 = vec2(0.72 * S, 0.0 * S);
+        format!(
+            "Export X {:.3} to {:.3}, Y {:.3} to {:.3}",
+            min[1], max[1], min[0], max[0]
+        )
+    }
 
-                    let mut pos = rect.min;
-                    let size = rect.size();
-                    pos.y += 0.5 * size.y;
+    /// Shows the value of a node under the pointer, which is over its preview.
+    fn show_readout(&self, ui: &Ui, rect: Rect, node_idx: usize, snarl: &Snarl<NoiseNode>) {
+        let node = snarl.get_node(node_idx);
+        let Some(image) = node.image().filter(|image| !image.selecting_region) else {
+            return;
+        };
 
-                    if !is_input {
-                        pos.x += 0.6 * size.x;
-                    }
+        let Some(pos) = ui.ctx().pointer_hover_pos() else {
+            return;
+        };
+
+        // Previews show `x` to `x + 1` across and `y` to `y + 1` down, and rows run along the
+        // first coordinate
+        let offset = (pos - rect.min) / rect.size();
+        let point =
+            [offset.y as f64 + image.y, offset.x as f64 + image.x].map(|coord| coord * image.scale);
+
+        readout::show(
+            ui,
+            ui.id().with(("readout", node_idx)),
+            self.backend,
+            &node.expr(node_idx, snarl),
+            point,
+            ui.input(|input| input.pointer.primary_clicked()),
+        );
+    }
 
-                    let points = vec![pos + A * size, pos + B * size, pos + C * size];
+    /// Splices a node dropped onto a wire while Shift is held into the wire.
+    pub fn splice_dropped_node(&mut self, ctx: &Context, snarl: &mut Snarl<NoiseNode>) {
+        if self.read_only {
+            return;
+        }
 
-                    painter.add(Shape::Path(PathShape {
-                        points,
-                        closed: true,
-                        fill,
-                        stroke,
-                    }));
+        let Some((node_idx, from, to)) = self.wire_layout.dropped_node(ctx, snarl) else {
+            return;
+        };
 
-                    let radius = 0.5 * size.x;
+        debug!(
+            "Splicing #{node_idx} between #{} and #{}",
+            from.node, to.node
+        );
 
-                    painter.add(if filled {
-                        Shape::circle_filled(pos, radius, fill)
-                    } else {
-                        Shape::circle_stroke(pos, radius, stroke)
-                    });
-                },
-            )))
+        self.connect(
+            &snarl.out_pin(from),
+            &snarl.in_pin(InPinId {
+                node: node_idx,
+                input: 0,
+            }),
+            snarl,
+        );
+        self.connect(
+            &snarl.out_pin(OutPinId {
+                node: node_idx,
+                output: 0,
+            }),
+            &snarl.in_pin(to),
+            snarl,
+        );
+    }
+
+    /// Swaps the nodes connected to two image inputs of a node.
+    pub fn swap_inputs(
+        &mut self,
+        node_idx: usize,
+        input: usize,
+        other_input: usize,
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        let [source, other_source] = [input, other_input].map(|input| {
+            snarl
+                .in_pin(InPinId {
+                    node: node_idx,
+                    input,
+                })
+                .remotes
+                .first()
+                .copied()
+        });
+
+        for (input, source) in [(input, other_source), (other_input, source)] {
+            let pin = snarl.in_pin(InPinId {
+                node: node_idx,
+                input,
+            });
+
+            // Connecting a pin replaces its existing connection
+            if let Some(source) = source {
+                self.connect(&snarl.out_pin(source), &pin, snarl);
+            } else {
+                self.drop_inputs(&pin, snarl);
+            }
+        }
     }
 
     fn u32_pin_info(is_input: bool, filled: bool) -> PinInfo {
@@ -276,11 +2052,30 @@ impl<'a> Viewer<'a> {
 
         Self::scalar_pin_info(is_input, filled, fill)
     }
+
+    /// Copies the master seed into all nodes which inherit it.
+    fn update_master_seed(&mut self, snarl: &mut Snarl<NoiseNode>) {
+        let node_indices = snarl
+            .node_indices()
+            .filter_map(|(node_idx, node)| {
+                node.master_seed().flatten().is_some().then_some(node_idx)
+            })
+            .collect::<Vec<_>>();
+
+        for node_idx in node_indices {
+            *snarl.get_node_mut(node_idx).master_seed_mut().unwrap() = Some(*self.master_seed);
+            self.updated_node_indices.insert(node_idx);
+        }
+    }
 }
 
 impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
     #[inline]
     fn connect(&mut self, from: &OutPin, to: &InPin, snarl: &mut Snarl<NoiseNode>) {
+        if self.read_only {
+            return;
+        }
+
         // Make sure this connection is not to the same node
         if from.id.node == to.id.node {
             debug!("Not connecting #{} to #{} (Same)", from.id.node, to.id.node);
@@ -335,17 +2130,30 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     (
                         0,
                         NoiseNode::Abs(_)
+                        | NoiseNode::Bake(_)
+                        | NoiseNode::Cache(_)
                         | NoiseNode::Clamp(_)
                         | NoiseNode::ControlPoint(_)
                         | NoiseNode::Curve(_)
                         | NoiseNode::Cylinders(_)
                         | NoiseNode::Displace(_)
+                        | NoiseNode::Exp(_)
                         | NoiseNode::Exponent(_)
+                        | NoiseNode::Gradient(_)
+                        | NoiseNode::Heightmap(_)
+                        | NoiseNode::Jitter(_)
+                        | NoiseNode::Log(_)
                         | NoiseNode::Negate(_)
+                        | NoiseNode::Plateau(_)
+                        | NoiseNode::Quantize(_)
+                        | NoiseNode::Rebase(_)
+                        | NoiseNode::Repeat(_)
+                        | NoiseNode::Ridge(_)
                         | NoiseNode::RotatePoint(_)
                         | NoiseNode::ScaleBias(_)
                         | NoiseNode::ScalePoint(_)
                         | NoiseNode::Terrace(_)
+                        | NoiseNode::Threshold(_)
                         | NoiseNode::TranslatePoint(_)
                         | NoiseNode::Turbulence(_),
                     ) => {
@@ -355,7 +2163,9 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         0,
                         NoiseNode::BasicMulti(_)
                         | NoiseNode::Billow(_)
+                        | NoiseNode::BlueNoise(_)
                         | NoiseNode::Checkerboard(_)
+                        | NoiseNode::Fault(_)
                         | NoiseNode::Fbm(_)
                         | NoiseNode::HybridMulti(_)
                         | NoiseNode::OpenSimplex(_)
@@ -365,6 +2175,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         | NoiseNode::Simplex(_)
                         | NoiseNode::SuperSimplex(_)
                         | NoiseNode::Value(_)
+                        | NoiseNode::WhiteNoise(_)
                         | NoiseNode::Worley(_),
                     ) => {
                         NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
@@ -374,15 +2185,18 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         NoiseNode::Add(_)
                         | NoiseNode::Blend(_)
                         | NoiseNode::F64Operation(_)
+                        | NoiseNode::MaskedBlend(_)
                         | NoiseNode::Min(_)
                         | NoiseNode::Max(_)
+                        | NoiseNode::Mix(_)
                         | NoiseNode::Multiply(_)
                         | NoiseNode::Power(_)
+                        | NoiseNode::PowSignal(_)
                         | NoiseNode::Select(_),
                     ) => {
                         NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
                     }
-                    (0 | 1, NoiseNode::U32Operation(_)) => {
+                    (0 | 1, NoiseNode::FastNoise(_) | NoiseNode::U32Operation(_)) => {
                         NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
                     }
                     (
@@ -390,42 +2204,67 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         NoiseNode::Clamp(_)
                         | NoiseNode::ControlPoint(_)
                         | NoiseNode::Exponent(_)
+                        | NoiseNode::Gradient(_)
+                        | NoiseNode::Plateau(_)
+                        | NoiseNode::Rebase(_)
+                        | NoiseNode::Ridge(_)
                         | NoiseNode::ScaleBias(_)
+                        | NoiseNode::Threshold(_)
                         | NoiseNode::Worley(_),
                     ) => {
                         NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
                     }
                     (
                         1,
-                        NoiseNode::BasicMulti(_)
+                        NoiseNode::Bake(_)
+                        | NoiseNode::BasicMulti(_)
                         | NoiseNode::Billow(_)
+                        | NoiseNode::Fault(_)
                         | NoiseNode::Fbm(_)
                         | NoiseNode::HybridMulti(_)
+                        | NoiseNode::Jitter(_)
+                        | NoiseNode::Quantize(_)
                         | NoiseNode::RigidMulti(_)
                         | NoiseNode::Turbulence(_),
                     ) => {
                         NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
                     }
+                    (1..=3, NoiseNode::Checkerboard(_) | NoiseNode::Cylinders(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
                     (
                         1..=4,
                         NoiseNode::Displace(_)
+                        | NoiseNode::Repeat(_)
                         | NoiseNode::RotatePoint(_)
                         | NoiseNode::ScalePoint(_)
                         | NoiseNode::TranslatePoint(_),
                     ) => {
                         NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
                     }
+                    (2..=7, NoiseNode::FastNoise(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
                     (
                         2,
-                        NoiseNode::BasicMulti(_)
+                        NoiseNode::Bake(_)
+                        | NoiseNode::BasicMulti(_)
                         | NoiseNode::Billow(_)
                         | NoiseNode::Blend(_)
                         | NoiseNode::Clamp(_)
+                        | NoiseNode::Fault(_)
                         | NoiseNode::Fbm(_)
                         | NoiseNode::HybridMulti(_)
+                        | NoiseNode::Jitter(_)
+                        | NoiseNode::MaskedBlend(_)
+                        | NoiseNode::Mix(_)
+                        | NoiseNode::Plateau(_)
+                        | NoiseNode::Quantize(_)
+                        | NoiseNode::Ridge(_)
                         | NoiseNode::RigidMulti(_)
                         | NoiseNode::ScaleBias(_)
                         | NoiseNode::Select(_)
+                        | NoiseNode::Threshold(_)
                         | NoiseNode::Turbulence(_),
                     ) => {
                         NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
@@ -436,12 +2275,16 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         | NoiseNode::Billow(_)
                         | NoiseNode::Fbm(_)
                         | NoiseNode::HybridMulti(_)
+                        | NoiseNode::MaskedBlend(_)
                         | NoiseNode::RigidMulti(_)
                         | NoiseNode::Select(_)
                         | NoiseNode::Turbulence(_),
                     ) => {
                         NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
                     }
+                    (3, NoiseNode::Quantize(_) | NoiseNode::Threshold(_)) => {
+                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
+                    }
 
                     (
                         4,
@@ -449,6 +2292,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         | NoiseNode::Billow(_)
                         | NoiseNode::Fbm(_)
                         | NoiseNode::HybridMulti(_)
+                        | NoiseNode::MaskedBlend(_)
                         | NoiseNode::RigidMulti(_)
                         | NoiseNode::Select(_),
                     ) => {
@@ -457,7 +2301,21 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     (4, NoiseNode::Turbulence(_)) => {
                         NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
                     }
-                    (5, NoiseNode::RigidMulti(_) | NoiseNode::Select(_)) => {
+                    (
+                        5,
+                        NoiseNode::BasicMulti(_)
+                        | NoiseNode::Billow(_)
+                        | NoiseNode::Fbm(_)
+                        | NoiseNode::HybridMulti(_)
+                        | NoiseNode::RigidMulti(_)
+                        | NoiseNode::Select(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (6, NoiseNode::RigidMulti(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (5..=10, NoiseNode::Turbulence(_)) => {
                         NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
                     }
                     (_, NoiseNode::Terrace(_)) => {
@@ -471,27 +2329,45 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 match snarl.get_node(from.id.node) {
                     NoiseNode::Abs(_)
                     | NoiseNode::Add(_)
+                    | NoiseNode::Bake(_)
                     | NoiseNode::BasicMulti(_)
                     | NoiseNode::Billow(_)
                     | NoiseNode::Blend(_)
+                    | NoiseNode::BlueNoise(_)
+                    | NoiseNode::Cache(_)
                     | NoiseNode::Clamp(_)
                     | NoiseNode::Checkerboard(_)
                     | NoiseNode::ControlPoint(_)
                     | NoiseNode::Curve(_)
                     | NoiseNode::Cylinders(_)
                     | NoiseNode::Displace(_)
+                    | NoiseNode::Exp(_)
                     | NoiseNode::Exponent(_)
+                    | NoiseNode::FastNoise(_)
+                    | NoiseNode::Fault(_)
                     | NoiseNode::Fbm(_)
+                    | NoiseNode::Gradient(_)
+                    | NoiseNode::Heightmap(_)
                     | NoiseNode::HybridMulti(_)
+                    | NoiseNode::Jitter(_)
+                    | NoiseNode::Log(_)
+                    | NoiseNode::MaskedBlend(_)
                     | NoiseNode::Max(_)
                     | NoiseNode::Min(_)
+                    | NoiseNode::Mix(_)
                     | NoiseNode::Multiply(_)
                     | NoiseNode::Negate(_)
                     | NoiseNode::OpenSimplex(_)
                     | NoiseNode::Operation(_)
                     | NoiseNode::Perlin(_)
                     | NoiseNode::PerlinSurflet(_)
+                    | NoiseNode::Plateau(_)
                     | NoiseNode::Power(_)
+                    | NoiseNode::PowSignal(_)
+                    | NoiseNode::Quantize(_)
+                    | NoiseNode::Rebase(_)
+                    | NoiseNode::Repeat(_)
+                    | NoiseNode::Ridge(_)
                     | NoiseNode::RigidMulti(_)
                     | NoiseNode::RotatePoint(_)
                     | NoiseNode::ScaleBias(_)
@@ -500,9 +2376,11 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     | NoiseNode::Simplex(_)
                     | NoiseNode::SuperSimplex(_)
                     | NoiseNode::Terrace(_)
+                    | NoiseNode::Threshold(_)
                     | NoiseNode::TranslatePoint(_)
                     | NoiseNode::Turbulence(_)
                     | NoiseNode::Value(_)
+                    | NoiseNode::WhiteNoise(_)
                     | NoiseNode::Worley(_) => (),
                     NoiseNode::F64(_) | NoiseNode::F64Operation(_) => {
                         NoiseNode::propagate_f64_from_tuple_op(to.id.node, snarl)
@@ -520,28 +2398,46 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
+                | NoiseNode::Bake(_)
                 | NoiseNode::BasicMulti(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
                 | NoiseNode::ControlPoint(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::Exp(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FastNoise(_)
+                | NoiseNode::Fault(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Gradient(_)
+                | NoiseNode::Heightmap(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::Jitter(_)
+                | NoiseNode::Log(_)
+                | NoiseNode::MaskedBlend(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
+                | NoiseNode::Plateau(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::PowSignal(_)
+                | NoiseNode::Quantize(_)
+                | NoiseNode::Rebase(_)
+                | NoiseNode::Repeat(_)
+                | NoiseNode::Ridge(_)
                 | NoiseNode::RigidMulti(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
@@ -550,21 +2446,34 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 | NoiseNode::Simplex(_)
                 | NoiseNode::SuperSimplex(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::Threshold(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WhiteNoise(_)
                 | NoiseNode::Worley(_),
                 0,
                 NoiseNode::Abs(_)
+                | NoiseNode::Bake(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Clamp(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::Exp(_)
                 | NoiseNode::Exponent(_)
+                | NoiseNode::Jitter(_)
+                | NoiseNode::Log(_)
                 | NoiseNode::Negate(_)
+                | NoiseNode::Plateau(_)
+                | NoiseNode::Quantize(_)
+                | NoiseNode::Rebase(_)
+                | NoiseNode::Repeat(_)
+                | NoiseNode::Ridge(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
                 | NoiseNode::ScalePoint(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::Threshold(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_),
             ) => {}
@@ -574,6 +2483,12 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 0, NoiseNode::Cylinders(node)) => {
                 node.frequency = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 0, NoiseNode::Gradient(node)) => {
+                node.angle = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 0, NoiseNode::Heightmap(node)) => {
+                node.size = Node(from.id.node);
+            }
             (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 0, NoiseNode::Checkerboard(node)) => {
                 node.size = Node(from.id.node);
             }
@@ -582,6 +2497,8 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 0,
                 NoiseNode::BasicMulti(FractalNode { seed, .. })
                 | NoiseNode::Billow(FractalNode { seed, .. })
+                | NoiseNode::BlueNoise(GeneratorNode { seed, .. })
+                | NoiseNode::Fault(FaultNode { seed, .. })
                 | NoiseNode::Fbm(FractalNode { seed, .. })
                 | NoiseNode::HybridMulti(FractalNode { seed, .. })
                 | NoiseNode::OpenSimplex(GeneratorNode { seed, .. })
@@ -591,10 +2508,14 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 | NoiseNode::Simplex(GeneratorNode { seed, .. })
                 | NoiseNode::SuperSimplex(GeneratorNode { seed, .. })
                 | NoiseNode::Value(GeneratorNode { seed, .. })
+                | NoiseNode::WhiteNoise(GeneratorNode { seed, .. })
                 | NoiseNode::Worley(WorleyNode { seed, .. }),
             ) => {
                 *seed = Node(from.id.node);
             }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 0 | 1, NoiseNode::FastNoise(node)) => {
+                *node.u32_value_mut(to.id.input).unwrap() = Node(from.id.node);
+            }
             (
                 NoiseNode::F64(_) | NoiseNode::F64Operation(_),
                 0 | 1,
@@ -615,28 +2536,46 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
+                | NoiseNode::Bake(_)
                 | NoiseNode::BasicMulti(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
                 | NoiseNode::ControlPoint(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::Exp(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FastNoise(_)
+                | NoiseNode::Fault(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Gradient(_)
+                | NoiseNode::Heightmap(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::Jitter(_)
+                | NoiseNode::Log(_)
+                | NoiseNode::MaskedBlend(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
+                | NoiseNode::Plateau(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::PowSignal(_)
+                | NoiseNode::Quantize(_)
+                | NoiseNode::Rebase(_)
+                | NoiseNode::Repeat(_)
+                | NoiseNode::Ridge(_)
                 | NoiseNode::RigidMulti(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
@@ -645,42 +2584,63 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 | NoiseNode::Simplex(_)
                 | NoiseNode::SuperSimplex(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::Threshold(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WhiteNoise(_)
                 | NoiseNode::Worley(_),
                 0 | 1,
                 NoiseNode::Add(_)
                 | NoiseNode::Min(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Multiply(_)
-                | NoiseNode::Power(_),
+                | NoiseNode::Power(_)
+                | NoiseNode::PowSignal(_),
             ) => {}
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
+                | NoiseNode::Bake(_)
                 | NoiseNode::BasicMulti(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
                 | NoiseNode::ControlPoint(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::Exp(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FastNoise(_)
+                | NoiseNode::Fault(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Gradient(_)
+                | NoiseNode::Heightmap(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::Jitter(_)
+                | NoiseNode::Log(_)
+                | NoiseNode::MaskedBlend(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
+                | NoiseNode::Plateau(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::PowSignal(_)
+                | NoiseNode::Quantize(_)
+                | NoiseNode::Rebase(_)
+                | NoiseNode::Repeat(_)
+                | NoiseNode::Ridge(_)
                 | NoiseNode::RigidMulti(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
@@ -689,12 +2649,17 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 | NoiseNode::Simplex(_)
                 | NoiseNode::SuperSimplex(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::Threshold(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WhiteNoise(_)
                 | NoiseNode::Worley(_),
                 0 | 1,
-                NoiseNode::Blend(_) | NoiseNode::Select(_),
+                NoiseNode::Blend(_)
+                | NoiseNode::MaskedBlend(_)
+                | NoiseNode::Mix(_)
+                | NoiseNode::Select(_),
             ) => {}
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Clamp(node)) => {
                 node.lower_bound = Node(from.id.node);
@@ -716,40 +2681,85 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Exponent(node)) => {
                 node.exponent = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Gradient(node)) => {
+                node.offset = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Plateau(node)) => {
+                node.level = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Rebase(node)) => {
+                node.period = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Ridge(node)) => {
+                node.sharpness = Node(from.id.node);
+            }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::ScaleBias(node)) => {
                 node.scale = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Threshold(node)) => {
+                node.off_threshold = Node(from.id.node);
+            }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Worley(node)) => {
                 node.frequency = Node(from.id.node);
             }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::Bake(node)) => {
+                node.resolution = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::Fault(node)) => {
+                node.iterations = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::Jitter(node)) => {
+                node.seed = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::Quantize(node)) => {
+                node.levels = Node(from.id.node);
+            }
             (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::Turbulence(node)) => {
                 node.seed = Node(from.id.node);
             }
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
+                | NoiseNode::Bake(_)
                 | NoiseNode::BasicMulti(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
                 | NoiseNode::ControlPoint(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::Exp(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FastNoise(_)
+                | NoiseNode::Fault(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Gradient(_)
+                | NoiseNode::Heightmap(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::Jitter(_)
+                | NoiseNode::Log(_)
+                | NoiseNode::MaskedBlend(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
+                | NoiseNode::Plateau(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::PowSignal(_)
+                | NoiseNode::Quantize(_)
+                | NoiseNode::Rebase(_)
+                | NoiseNode::Repeat(_)
+                | NoiseNode::Ridge(_)
                 | NoiseNode::RigidMulti(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
@@ -758,13 +2768,31 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 | NoiseNode::Simplex(_)
                 | NoiseNode::SuperSimplex(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::Threshold(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WhiteNoise(_)
                 | NoiseNode::Worley(_),
                 1..=4,
                 NoiseNode::Displace(_),
             ) => {}
+            (
+                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
+                1..=2,
+                NoiseNode::Checkerboard(node),
+            ) => {
+                node.offset[to.id.input - 1] = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 3, NoiseNode::Checkerboard(node)) => {
+                node.rotation = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1..=3, NoiseNode::Cylinders(node)) => {
+                node.axis_frequencies[to.id.input - 1] = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1..=3, NoiseNode::Repeat(node)) => {
+                node.periods[to.id.input - 1] = Node(from.id.node);
+            }
             (
                 NoiseNode::F64(_) | NoiseNode::F64Operation(_),
                 1..=4,
@@ -777,28 +2805,46 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
+                | NoiseNode::Bake(_)
                 | NoiseNode::BasicMulti(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
                 | NoiseNode::ControlPoint(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::Exp(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FastNoise(_)
+                | NoiseNode::Fault(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Gradient(_)
+                | NoiseNode::Heightmap(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::Jitter(_)
+                | NoiseNode::Log(_)
+                | NoiseNode::MaskedBlend(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
+                | NoiseNode::Plateau(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::PowSignal(_)
+                | NoiseNode::Quantize(_)
+                | NoiseNode::Rebase(_)
+                | NoiseNode::Repeat(_)
+                | NoiseNode::Ridge(_)
                 | NoiseNode::RigidMulti(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
@@ -807,13 +2853,21 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 | NoiseNode::Simplex(_)
                 | NoiseNode::SuperSimplex(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::Threshold(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WhiteNoise(_)
                 | NoiseNode::Worley(_),
                 2,
-                NoiseNode::Blend(_) | NoiseNode::Select(_),
+                NoiseNode::Blend(_)
+                | NoiseNode::MaskedBlend(_)
+                | NoiseNode::Mix(_)
+                | NoiseNode::Select(_),
             ) => {}
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2..=7, NoiseNode::FastNoise(node)) => {
+                *node.f64_value_mut(to.id.input).unwrap() = Node(from.id.node);
+            }
             (
                 NoiseNode::F64(_) | NoiseNode::F64Operation(_),
                 2,
@@ -826,12 +2880,33 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             ) => {
                 *frequency = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Bake(node)) => {
+                node.size = Node(from.id.node);
+            }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Clamp(node)) => {
                 node.upper_bound = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Fault(node)) => {
+                node.extent = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Jitter(node)) => {
+                node.amount = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Quantize(node)) => {
+                node.dither = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Plateau(node)) => {
+                node.softness = Node(from.id.node);
+            }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Ridge(node)) => {
+                node.offset = Node(from.id.node);
+            }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::ScaleBias(node)) => {
                 node.bias = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Threshold(node)) => {
+                node.on_threshold = Node(from.id.node);
+            }
             (
                 NoiseNode::F64(_) | NoiseNode::F64Operation(_),
                 3,
@@ -843,12 +2918,21 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             ) => {
                 *lacunarity = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 3, NoiseNode::MaskedBlend(node)) => {
+                node.threshold = Node(from.id.node);
+            }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 3, NoiseNode::Select(node)) => {
                 node.lower_bound = Node(from.id.node);
             }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 3, NoiseNode::Turbulence(node)) => {
                 node.power = Node(from.id.node);
             }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 3, NoiseNode::Quantize(node)) => {
+                node.seed = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 3, NoiseNode::Threshold(node)) => {
+                node.scan_steps = Node(from.id.node);
+            }
             (
                 NoiseNode::F64(_) | NoiseNode::F64Operation(_),
                 4,
@@ -860,18 +2944,41 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             ) => {
                 *persistence = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 4, NoiseNode::MaskedBlend(node)) => {
+                node.feather = Node(from.id.node);
+            }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 4, NoiseNode::Select(node)) => {
                 node.upper_bound = Node(from.id.node);
             }
             (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 4, NoiseNode::Turbulence(node)) => {
                 node.roughness = Node(from.id.node);
             }
+            (
+                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
+                5..=10,
+                NoiseNode::Turbulence(node),
+            ) if node.axis_value(to.id.input).is_some() => {
+                *node.axis_value_mut(to.id.input).unwrap() = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
+                5,
+                NoiseNode::BasicMulti(node)
+                | NoiseNode::Billow(node)
+                | NoiseNode::Fbm(node)
+                | NoiseNode::HybridMulti(node),
+            ) => {
+                node.octave_remainder = Node(from.id.node);
+            }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 5, NoiseNode::RigidMulti(node)) => {
                 node.attenuation = Node(from.id.node);
             }
             (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 5, NoiseNode::Select(node)) => {
                 node.falloff = Node(from.id.node);
             }
+            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 6, NoiseNode::RigidMulti(node)) => {
+                node.octave_remainder = Node(from.id.node);
+            }
             (NoiseNode::ControlPoint(_), to_input, NoiseNode::Curve(node)) => {
                 let control_point_idx = to_input - 1;
 
@@ -920,37 +3027,146 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
     }
 
     fn disconnect(&mut self, from: &OutPin, to: &InPin, snarl: &mut Snarl<NoiseNode>) {
+        if self.read_only {
+            return;
+        }
+
         snarl.disconnect(from.id, to.id);
         self.updated_node_indices.insert(to.id.node);
     }
 
     fn drop_inputs(&mut self, pin: &InPin, snarl: &mut Snarl<NoiseNode>) {
+        if self.read_only {
+            return;
+        }
+
         snarl.drop_inputs(pin.id);
         self.updated_node_indices.insert(pin.id.node);
     }
 
     fn drop_outputs(&mut self, pin: &OutPin, snarl: &mut Snarl<NoiseNode>) {
+        if self.read_only {
+            return;
+        }
+
         snarl.drop_outputs(pin.id);
         self.updated_node_indices
             .extend(pin.remotes.iter().map(|remote| remote.node));
     }
 
-    fn title(&mut self, _node: &NoiseNode) -> String {
-        unimplemented!()
+    fn title(&mut self, node: &NoiseNode) -> String {
+        match node {
+            NoiseNode::Abs(_) => "Abs",
+            NoiseNode::Add(_) => "Add",
+            NoiseNode::Bake(_) => "Bake",
+            NoiseNode::BasicMulti(_) => "Basic Multi",
+            NoiseNode::Billow(_) => "Billow",
+            NoiseNode::Blend(_) => "Blend",
+            NoiseNode::BlueNoise(_) => "Blue Noise",
+            NoiseNode::Cache(_) => "Cache",
+            NoiseNode::Checkerboard(_) => "Checkerboard",
+            NoiseNode::Clamp(_) => "Clamp",
+            NoiseNode::ControlPoint(_) => "Control Point",
+            NoiseNode::Curve(_) => "Curve",
+            NoiseNode::Cylinders(_) => "Cylinders",
+            NoiseNode::Displace(_) => "Displace",
+            NoiseNode::Exp(_) => "Exp",
+            NoiseNode::Exponent(_) => "Exponent",
+            NoiseNode::F64(_) => "Decimal",
+            NoiseNode::F64Operation(ConstantOpNode { op_ty, .. })
+            | NoiseNode::Operation(ConstantOpNode { op_ty, .. })
+            | NoiseNode::U32Operation(ConstantOpNode { op_ty, .. }) => match op_ty {
+                OpType::Add => "Add",
+                OpType::Divide => "Divide",
+                OpType::Multiply => "Multiply",
+                OpType::Subtract => "Subtract",
+            },
+            NoiseNode::FastNoise(_) => "FastNoise Lite",
+            NoiseNode::Fault(_) => "Fault",
+            NoiseNode::Fbm(_) => "fBm",
+            NoiseNode::Gradient(_) => "Gradient",
+            NoiseNode::Heightmap(_) => "Heightmap",
+            NoiseNode::HybridMulti(_) => "Hybrid Multi",
+            NoiseNode::Jitter(_) => "Jitter",
+            NoiseNode::Log(_) => "Log",
+            NoiseNode::MaskedBlend(_) => "Masked Blend",
+            NoiseNode::Min(_) => "Min",
+            NoiseNode::Max(_) => "Max",
+            NoiseNode::Mix(_) => "Mix",
+            NoiseNode::Multiply(_) => "Multiply",
+            NoiseNode::Negate(_) => "Negate",
+            NoiseNode::OpenSimplex(_) => "Open Simplex",
+            NoiseNode::Perlin(_) => "Perlin",
+            NoiseNode::PerlinSurflet(_) => "Perlin Surflet",
+            NoiseNode::Power(_) => "Power",
+            NoiseNode::Plateau(_) => "Plateau",
+            NoiseNode::PowSignal(_) => "Pow Signal",
+            NoiseNode::Quantize(_) => "Quantize",
+            NoiseNode::Rebase(_) => "Rebase",
+            NoiseNode::Repeat(_) => "Repeat",
+            NoiseNode::Ridge(_) => "Ridge",
+            NoiseNode::RigidMulti(_) => "Rigid Multi",
+            NoiseNode::RotatePoint(_) => "Rotate Point",
+            NoiseNode::ScaleBias(_) => "Scale + Bias",
+            NoiseNode::ScalePoint(_) => "Scale Point",
+            NoiseNode::Select(_) => "Select",
+            NoiseNode::Simplex(_) => "Simplex",
+            NoiseNode::SuperSimplex(_) => "Super Simplex",
+            NoiseNode::Terrace(_) => "Terrace",
+            NoiseNode::Threshold(_) => "Threshold",
+            NoiseNode::TranslatePoint(_) => "Translate Point",
+            NoiseNode::Turbulence(_) => "Turbulence",
+            NoiseNode::U32(_) => "Integer",
+            NoiseNode::Value(_) => "Value",
+            NoiseNode::WhiteNoise(_) => "White Noise",
+            NoiseNode::Worley(_) => "Worley",
+        }
+        .to_owned()
     }
 
     fn show_header(
         &mut self,
         node_idx: usize,
-        _inputs: &[InPin],
-        _outputs: &[OutPin],
+        inputs: &[InPin],
+        outputs: &[OutPin],
         ui: &mut Ui,
         scale: f32,
         snarl: &mut Snarl<NoiseNode>,
     ) {
+        self.wire_layout
+            .record_header(node_idx, ui.max_rect(), scale);
+
+        if self.touch {
+            self.long_press_menu(node_idx, inputs, outputs, ui, scale, snarl);
+        }
+
         #[cfg(debug_assertions)]
         ui.label(RichText::new(format!("#{node_idx}")).color(Color32::DEBUG_COLOR));
 
+        if ui.ui_contains_pointer() {
+            *self.hovered_node_idx = Some(node_idx);
+
+            if let Some(node_selection) = self.node_selection.as_deref_mut() {
+                let toggled =
+                    ui.input(|input| input.modifiers.command && input.pointer.primary_clicked());
+
+                if toggled && !node_selection.remove(&node_idx) {
+                    node_selection.insert(node_idx);
+                }
+            }
+        }
+
+        // Nodes hovered in the expression tree are highlighted
+        if self.highlighted_node_idx == Some(node_idx) {
+            ui.visuals_mut().override_text_color = Some(ui.visuals().selection.stroke.color);
+        }
+
+        // Clamp bounds may be connected to other nodes so they are checked before borrowing the node
+        let inverted_bounds = matches!(
+            snarl.get_node(node_idx),
+            NoiseNode::Clamp(node) if node.has_inverted_bounds(snarl)
+        );
+
         let node = snarl.get_node_mut(node_idx);
 
         ui.set_height(16.0 * scale);
@@ -958,7 +3174,14 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
         ui.with_layout(
             Layout::left_to_right(Align::Min).with_cross_align(Align::Center),
             |ui| {
+                // Only the values of named constants may be changed in read-only mode
+                if self.read_only && !matches!(node, NoiseNode::F64(_) | NoiseNode::U32(_)) {
+                    ui.set_enabled(false);
+                }
+
                 ui.add_space(20.0 * scale);
+                Self::category_badge(ui, node.category());
+
                 match node {
                     NoiseNode::Abs(_) => {
                         ui.label("Abs");
@@ -966,22 +3189,57 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     NoiseNode::Add(_) => {
                         ui.label("Add");
                     }
+                    NoiseNode::Bake(_) => {
+                        ui.label("Bake").on_hover_text(
+                            "Evaluates the source once into a table which repeats every Size units",
+                        );
+                    }
                     NoiseNode::BasicMulti(node) => {
                         ui.label("Basic Multi");
-                        self.source_ty_combo_box(ui, &mut node.source_ty, node_idx);
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.source_ty,
+                            &SourceType::ALL,
+                            SourceType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
                     }
                     NoiseNode::Billow(node) => {
                         ui.label("Billow");
-                        self.source_ty_combo_box(ui, &mut node.source_ty, node_idx);
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.source_ty,
+                            &SourceType::ALL,
+                            SourceType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
                     }
                     NoiseNode::Blend(_) => {
                         ui.label("Blend");
                     }
+                    NoiseNode::BlueNoise(_) => {
+                        ui.label("Blue Noise");
+                    }
+                    NoiseNode::Cache(_) => {
+                        ui.label("Cache");
+                    }
                     NoiseNode::Checkerboard(_) => {
                         ui.label("Checkerboard");
                     }
                     NoiseNode::Clamp(_) => {
                         ui.label("Clamp");
+
+                        if inverted_bounds {
+                            ui.colored_label(ui.visuals().error_fg_color, "⚠ Inverted")
+                                .on_hover_text(
+                                    "The lower bound is greater than the upper bound, so the \
+                                    bounds are swapped when evaluated; the linter can swap them",
+                                );
+                        }
                     }
                     NoiseNode::ControlPoint(_) => {
                         ui.label("Control Point");
@@ -989,22 +3247,35 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     NoiseNode::Curve(node) => {
                         ui.label("Curve");
 
+                        if !node.inline_control_points.is_empty() {
+                            ui.weak(format!("+{} inline", node.inline_control_points.len()));
+                        }
+
                         while let Some(None) = node.control_point_node_indices.last() {
                             node.control_point_node_indices.pop();
                         }
                     }
-                    NoiseNode::Cylinders(_) => {
+                    NoiseNode::Cylinders(node) => {
                         ui.label("Cylinders");
+                        if Self::enum_combo_box(ui, 0, &mut node.axis, &Axis::ALL, Axis::name) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
                     }
                     NoiseNode::Displace(_) => {
                         ui.label("Displace");
                     }
+                    NoiseNode::Exp(_) => {
+                        ui.label("Exp");
+                    }
                     NoiseNode::Exponent(_) => {
                         ui.label("Exponent");
                     }
                     NoiseNode::F64(node) => {
                         ui.label("Decimal");
-                        ui.add(TextEdit::singleline(&mut node.name).desired_width(50.0 * scale));
+                        ui.add_enabled(
+                            !self.read_only,
+                            TextEdit::singleline(&mut node.name).desired_width(50.0 * scale),
+                        );
 
                         if ui
                             .add(
@@ -1028,13 +3299,118 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             OpType::Subtract => "Subtract",
                         });
                     }
+                    NoiseNode::FastNoise(node) => {
+                        ui.label("FastNoise");
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.noise_ty,
+                            &FastNoiseType::ALL,
+                            FastNoiseType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
+
+                        if Self::enum_combo_box(
+                            ui,
+                            1,
+                            &mut node.fractal_ty,
+                            &FastFractalType::ALL,
+                            FastFractalType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
+                    }
+                    NoiseNode::Fault(_) => {
+                        ui.label("Fault");
+                    }
                     NoiseNode::Fbm(node) => {
                         ui.label("fBm");
-                        self.source_ty_combo_box(ui, &mut node.source_ty, node_idx);
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.source_ty,
+                            &SourceType::ALL,
+                            SourceType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
+                    }
+                    NoiseNode::Gradient(node) => {
+                        ui.label("Gradient");
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.wrap,
+                            &WrapMode::ALL,
+                            WrapMode::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
+                    }
+                    NoiseNode::Heightmap(node) => {
+                        ui.label("Heightmap");
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if ui
+                            .add_enabled(!self.read_only, Button::new("Open..."))
+                            .clicked()
+                        {
+                            if let Some(path) = FileDialog::new()
+                                .add_filter("PNG Image", &["png"])
+                                .pick_file()
+                            {
+                                node.asset = Asset::new(path, node.asset.policy);
+                                self.updated_node_indices.insert(node_idx);
+                            }
+                        }
+
+                        Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.asset.policy,
+                            &AssetPolicy::ALL,
+                            AssetPolicy::name,
+                        );
+
+                        if let Some(file_name) = node.asset.path.file_name() {
+                            let file_name = file_name.to_string_lossy();
+
+                            if node.asset.is_missing() {
+                                ui.colored_label(
+                                    ui.visuals().warn_fg_color,
+                                    format!("⚠ {file_name}"),
+                                )
+                                .on_hover_text(format!(
+                                    "{} could not be read",
+                                    node.asset.path.display()
+                                ));
+                            } else {
+                                ui.label(file_name)
+                                    .on_hover_text(node.asset.path.display().to_string());
+                            }
+                        }
                     }
                     NoiseNode::HybridMulti(node) => {
                         ui.label("Hybrid Multi");
-                        self.source_ty_combo_box(ui, &mut node.source_ty, node_idx);
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.source_ty,
+                            &SourceType::ALL,
+                            SourceType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
+                    }
+                    NoiseNode::Jitter(_) => {
+                        ui.label("Jitter");
+                    }
+                    NoiseNode::Log(_) => {
+                        ui.label("Log");
+                    }
+                    NoiseNode::MaskedBlend(_) => {
+                        ui.label("Masked Blend");
                     }
                     NoiseNode::Min(_) => {
                         ui.label("Min");
@@ -1042,6 +3418,9 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     NoiseNode::Max(_) => {
                         ui.label("Max");
                     }
+                    NoiseNode::Mix(_) => {
+                        ui.label("Mix");
+                    }
                     NoiseNode::Multiply(_) => {
                         ui.label("Multiply");
                     }
@@ -1051,24 +3430,96 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     NoiseNode::OpenSimplex(_) => {
                         ui.label("Open Simplex");
                     }
-                    NoiseNode::Perlin(_) => {
+                    NoiseNode::Perlin(node) => {
                         ui.label("Perlin");
+                        let channel = ui.scope(|ui| {
+                            Self::enum_combo_box(
+                                ui,
+                                0,
+                                &mut node.channel,
+                                &Channel::ALL,
+                                Channel::name,
+                            )
+                        });
+                        channel
+                            .response
+                            .on_hover_text("Output the value or its analytic gradient");
+
+                        if channel.inner {
+                            self.updated_node_indices.insert(node_idx);
+                        }
                     }
                     NoiseNode::PerlinSurflet(_) => {
                         ui.label("Perlin Surflet");
                     }
+                    NoiseNode::Plateau(_) => {
+                        ui.label("Plateau");
+                    }
                     NoiseNode::Power(_) => {
                         ui.label("Power");
                     }
+                    NoiseNode::PowSignal(_) => {
+                        ui.label("Pow Signal");
+                    }
+                    NoiseNode::Quantize(_) => {
+                        ui.label("Quantize");
+                    }
+                    NoiseNode::Rebase(_) => {
+                        ui.label("Rebase");
+                    }
+                    NoiseNode::Repeat(node) => {
+                        ui.label("Repeat");
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.mode,
+                            &RepeatMode::ALL,
+                            RepeatMode::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
+                    }
+                    NoiseNode::Ridge(_) => {
+                        ui.label("Ridge");
+                    }
                     NoiseNode::RigidMulti(node) => {
                         ui.label("Rigid Multi");
-                        self.source_ty_combo_box(ui, &mut node.source_ty, node_idx);
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.source_ty,
+                            &SourceType::ALL,
+                            SourceType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
                     }
                     NoiseNode::RotatePoint(_) => {
                         ui.label("Rotate Point");
                     }
-                    NoiseNode::ScaleBias(_) => {
+                    NoiseNode::ScaleBias(node) => {
                         ui.label("Scale + Bias");
+
+                        let has_value = node.scale.as_value_mut().is_some()
+                            || node.bias.as_value_mut().is_some();
+
+                        for (label, description, [scale, bias]) in ScaleBiasNode::PRESETS {
+                            if ui
+                                .add_enabled(has_value, Button::new(label).small())
+                                .on_hover_text(description)
+                                .clicked()
+                            {
+                                if let Some(value) = node.scale.as_value_mut() {
+                                    *value = scale;
+                                }
+
+                                if let Some(value) = node.bias.as_value_mut() {
+                                    *value = bias;
+                                }
+
+                                self.updated_node_indices.insert(node_idx);
+                            }
+                        }
                     }
                     NoiseNode::ScalePoint(_) => {
                         ui.label("Scale Point");
@@ -1076,8 +3527,24 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     NoiseNode::Select(_) => {
                         ui.label("Select");
                     }
-                    NoiseNode::Simplex(_) => {
+                    NoiseNode::Simplex(node) => {
                         ui.label("Simplex");
+                        let channel = ui.scope(|ui| {
+                            Self::enum_combo_box(
+                                ui,
+                                0,
+                                &mut node.channel,
+                                &Channel::ALL,
+                                Channel::name,
+                            )
+                        });
+                        channel
+                            .response
+                            .on_hover_text("Output the value or its analytic gradient");
+
+                        if channel.inner {
+                            self.updated_node_indices.insert(node_idx);
+                        }
                     }
                     NoiseNode::SuperSimplex(_) => {
                         ui.label("Super Simplex");
@@ -1088,20 +3555,47 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             self.updated_node_indices.insert(node_idx);
                         }
 
+                        if !node.inline_control_points.is_empty() {
+                            ui.weak(format!("+{} inline", node.inline_control_points.len()));
+                        }
+
                         while let Some(None) = node.control_point_node_indices.last() {
                             node.control_point_node_indices.pop();
                         }
                     }
+                    NoiseNode::Threshold(node) => {
+                        ui.label("Threshold");
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.mode,
+                            &ThresholdMode::ALL,
+                            ThresholdMode::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
+                    }
                     NoiseNode::TranslatePoint(_) => {
                         ui.label("Translate Point");
                     }
                     NoiseNode::Turbulence(node) => {
                         ui.label("Turbulence");
-                        self.source_ty_combo_box(ui, &mut node.source_ty, node_idx);
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.source_ty,
+                            &SourceType::ALL,
+                            SourceType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
                     }
                     NoiseNode::U32(node) => {
                         ui.label("Integer");
-                        ui.add(TextEdit::singleline(&mut node.name).desired_width(50.0 * scale));
+                        ui.add_enabled(
+                            !self.read_only,
+                            TextEdit::singleline(&mut node.name).desired_width(50.0 * scale),
+                        );
 
                         if ui.add(DragValue::new(&mut node.value)).changed() {
                             self.updated_node_indices.insert(node_idx);
@@ -1110,61 +3604,128 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     NoiseNode::Value(_) => {
                         ui.label("Value");
                     }
+                    NoiseNode::WhiteNoise(_) => {
+                        ui.label("White Noise");
+                    }
                     NoiseNode::Worley(node) => {
                         ui.label("Worley");
-                        self.distance_fn_combo_box(ui, &mut node.distance_fn, node_idx);
-                        self.return_ty_combo_box(ui, &mut node.return_ty, node_idx);
+                        if Self::enum_combo_box(
+                            ui,
+                            0,
+                            &mut node.distance_fn,
+                            &DistanceFunction::ALL,
+                            DistanceFunction::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
+
+                        if Self::enum_combo_box(
+                            ui,
+                            1,
+                            &mut node.return_ty,
+                            &ReturnType::ALL,
+                            ReturnType::name,
+                        ) {
+                            self.updated_node_indices.insert(node_idx);
+                        }
                     }
                 }
+
+                Self::algorithm_badge(ui, node);
             },
         );
+
+        if self
+            .node_selection
+            .as_deref()
+            .is_some_and(|node_selection| node_selection.contains(&node_idx))
+        {
+            ui.painter().rect_stroke(
+                ui.min_rect().expand(4.0),
+                4.0,
+                ui.visuals().selection.stroke,
+            );
+        }
+
+        self.dim_filtered(ui, node_idx, snarl);
     }
 
     fn inputs(&mut self, node: &NoiseNode) -> usize {
         match node {
             NoiseNode::F64(_) | NoiseNode::U32(_) => 0,
             NoiseNode::Abs(_)
-            | NoiseNode::Checkerboard(_)
-            | NoiseNode::Cylinders(_)
+            | NoiseNode::BlueNoise(_)
+            | NoiseNode::Cache(_)
+            | NoiseNode::Exp(_)
+            | NoiseNode::Heightmap(_)
+            | NoiseNode::Log(_)
             | NoiseNode::OpenSimplex(_)
             | NoiseNode::Perlin(_)
             | NoiseNode::PerlinSurflet(_)
             | NoiseNode::Negate(_)
             | NoiseNode::Simplex(_)
             | NoiseNode::SuperSimplex(_)
-            | NoiseNode::Value(_) => 1,
+            | NoiseNode::Value(_)
+            | NoiseNode::WhiteNoise(_) => 1,
             NoiseNode::Add(_)
             | NoiseNode::ControlPoint(_)
             | NoiseNode::Exponent(_)
             | NoiseNode::F64Operation(_)
+            | NoiseNode::Gradient(_)
             | NoiseNode::Min(_)
             | NoiseNode::Max(_)
             | NoiseNode::Multiply(_)
             | NoiseNode::Operation(_)
             | NoiseNode::Power(_)
+            | NoiseNode::PowSignal(_)
+            | NoiseNode::Rebase(_)
             | NoiseNode::U32Operation(_)
             | NoiseNode::Worley(_) => 2,
-            NoiseNode::Blend(_) | NoiseNode::Clamp(_) | NoiseNode::ScaleBias(_) => 3,
+            NoiseNode::Bake(_)
+            | NoiseNode::Blend(_)
+            | NoiseNode::Clamp(_)
+            | NoiseNode::Fault(_)
+            | NoiseNode::Jitter(_)
+            | NoiseNode::Mix(_)
+            | NoiseNode::Plateau(_)
+            | NoiseNode::Ridge(_)
+            | NoiseNode::ScaleBias(_) => 3,
+            NoiseNode::Checkerboard(_)
+            | NoiseNode::Cylinders(_)
+            | NoiseNode::Quantize(_)
+            | NoiseNode::Repeat(_)
+            | NoiseNode::Threshold(_) => 4,
+            NoiseNode::Displace(_)
+            | NoiseNode::MaskedBlend(_)
+            | NoiseNode::RotatePoint(_)
+            | NoiseNode::ScalePoint(_)
+            | NoiseNode::TranslatePoint(_) => 5,
             NoiseNode::BasicMulti(_)
             | NoiseNode::Billow(_)
-            | NoiseNode::Displace(_)
             | NoiseNode::Fbm(_)
             | NoiseNode::HybridMulti(_)
-            | NoiseNode::RotatePoint(_)
-            | NoiseNode::ScalePoint(_)
-            | NoiseNode::TranslatePoint(_)
-            | NoiseNode::Turbulence(_) => 5,
-            NoiseNode::RigidMulti(_) | NoiseNode::Select(_) => 6,
+            | NoiseNode::Select(_) => 6,
+            NoiseNode::RigidMulti(_) => 7,
+            NoiseNode::FastNoise(_) => 8,
+            NoiseNode::Turbulence(node) => {
+                if node.axis_frequencies.is_some() {
+                    11
+                } else {
+                    5
+                }
+            }
             NoiseNode::Curve(node) => {
                 (node.control_point_node_indices.len()
                     + node.control_point_node_indices.iter().all(Option::is_some) as usize)
-                    .max(4)
+                    .max(4usize.saturating_sub(node.inline_control_points.len()))
+                    .max(node.pin_count)
                     + 1
             }
             NoiseNode::Terrace(node) => {
                 (node.control_point_node_indices.len()
                     + node.control_point_node_indices.iter().all(Option::is_some) as usize)
-                    .max(2)
+                    .max(2usize.saturating_sub(node.inline_control_points.len()))
+                    .max(node.pin_count)
                     + 1
             }
         }
@@ -1181,6 +3742,18 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
         scale: f32,
         snarl: &mut Snarl<NoiseNode>,
     ) -> PinInfo {
+        self.wire_layout.record_input(
+            pin.id,
+            pos2(
+                ui.max_rect().left(),
+                ui.cursor().top() + ui.spacing().interact_size.y / 2.0,
+            ),
+        );
+
+        if self.read_only {
+            ui.set_enabled(false);
+        }
+
         // TODO: This comment is inaccurate and the code should be moved to disconnect
         // and drop_inputs/drop_outputs
         // Handle disconnections by resetting node pins to the value of the previous node
@@ -1257,7 +3830,39 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 }
                 (
                     0,
-                    &NoiseNode::OpenSimplex(GeneratorNode {
+                    &NoiseNode::Gradient(GradientNode {
+                        angle: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_gradient_mut()
+                        .unwrap()
+                        .angle = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::Heightmap(HeightmapNode {
+                        size: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_heightmap_mut()
+                        .unwrap()
+                        .size = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::BlueNoise(GeneratorNode {
+                        seed: Node(node_idx),
+                        ..
+                    })
+                    | &NoiseNode::OpenSimplex(GeneratorNode {
                         seed: Node(node_idx),
                         ..
                     })
@@ -1280,6 +3885,10 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     | &NoiseNode::Value(GeneratorNode {
                         seed: Node(node_idx),
                         ..
+                    })
+                    | &NoiseNode::WhiteNoise(GeneratorNode {
+                        seed: Node(node_idx),
+                        ..
                     }),
                 ) => {
                     snarl
@@ -1303,6 +3912,17 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
                 }
+                (
+                    0,
+                    &NoiseNode::Fault(FaultNode {
+                        seed: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl.get_node_mut(pin.id.node).as_fault_mut().unwrap().seed =
+                        Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                }
                 (
                     0,
                     &NoiseNode::Worley(WorleyNode {
@@ -1317,6 +3937,24 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
                 }
+                (0 | 1, NoiseNode::FastNoise(node))
+                    if node
+                        .u32_value(pin.id.input)
+                        .is_some_and(|value| value.is_node_idx()) =>
+                {
+                    let node_idx = node
+                        .u32_value(pin.id.input)
+                        .unwrap()
+                        .as_node_index()
+                        .unwrap();
+                    *snarl
+                        .get_node_mut(pin.id.node)
+                        .as_fast_noise_mut()
+                        .unwrap()
+                        .u32_value_mut(pin.id.input)
+                        .unwrap() = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                }
                 (0 | 1, NoiseNode::F64Operation(node))
                     if node.inputs[pin.id.input].is_node_idx() =>
                 {
@@ -1350,6 +3988,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
                     NoiseNode::propagate_tuple_from_u32_op(pin.id.node, snarl);
                 }
+                (
+                    1,
+                    &NoiseNode::Bake(BakeNode {
+                        resolution: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_bake_mut()
+                        .unwrap()
+                        .resolution = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                }
                 (
                     1,
                     &NoiseNode::BasicMulti(FractalNode {
@@ -1418,6 +4070,62 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .exponent = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    1,
+                    &NoiseNode::Fault(FaultNode {
+                        iterations: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_fault_mut()
+                        .unwrap()
+                        .iterations = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Gradient(GradientNode {
+                        offset: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_gradient_mut()
+                        .unwrap()
+                        .offset = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Jitter(JitterNode {
+                        seed: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_jitter_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Quantize(QuantizeNode {
+                        levels: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_quantize_mut()
+                        .unwrap()
+                        .levels = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                }
                 (
                     1,
                     &NoiseNode::RigidMulti(RigidFractalNode {
@@ -1432,6 +4140,48 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .octaves = Value(snarl.get_node(node_idx).eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
                 }
+                (
+                    1,
+                    &NoiseNode::Plateau(PlateauNode {
+                        level: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_plateau_mut()
+                        .unwrap()
+                        .level = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Rebase(RebaseNode {
+                        period: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_rebase_mut()
+                        .unwrap()
+                        .period = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Ridge(RidgeNode {
+                        sharpness: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_ridge_mut()
+                        .unwrap()
+                        .sharpness = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     1,
                     &NoiseNode::ScaleBias(ScaleBiasNode {
@@ -1446,6 +4196,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .scale = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    1,
+                    &NoiseNode::Threshold(ThresholdNode {
+                        off_threshold: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_threshold_mut()
+                        .unwrap()
+                        .off_threshold = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     1,
                     &NoiseNode::Turbulence(TurbulenceNode {
@@ -1474,6 +4238,57 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (1..=2, NoiseNode::Checkerboard(node))
+                    if node.offset[pin.id.input - 1].is_node_idx() =>
+                {
+                    let node_idx = node.offset[pin.id.input - 1].as_node_index().unwrap();
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_checkerboard_mut()
+                        .unwrap()
+                        .offset[pin.id.input - 1] = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    3,
+                    &NoiseNode::Checkerboard(CheckerboardNode {
+                        rotation: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_checkerboard_mut()
+                        .unwrap()
+                        .rotation = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (1..=3, NoiseNode::Cylinders(node))
+                    if node.axis_frequencies[pin.id.input - 1].is_node_idx() =>
+                {
+                    let node_idx = node.axis_frequencies[pin.id.input - 1]
+                        .as_node_index()
+                        .unwrap();
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_cylinders_mut()
+                        .unwrap()
+                        .axis_frequencies[pin.id.input - 1] =
+                        Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (1..=3, NoiseNode::Repeat(node))
+                    if node.periods[pin.id.input - 1].is_node_idx() =>
+                {
+                    let node_idx = node.periods[pin.id.input - 1].as_node_index().unwrap();
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_repeat_mut()
+                        .unwrap()
+                        .periods[pin.id.input - 1] =
+                        Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     1..=4,
                     NoiseNode::RotatePoint(node)
@@ -1488,6 +4303,17 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .axes[pin.id.input - 1] = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    2,
+                    &NoiseNode::Bake(BakeNode {
+                        size: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl.get_node_mut(pin.id.node).as_bake_mut().unwrap().size =
+                        Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     2,
                     &NoiseNode::BasicMulti(FractalNode {
@@ -1528,6 +4354,48 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .upper_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    2,
+                    &NoiseNode::Fault(FaultNode {
+                        extent: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_fault_mut()
+                        .unwrap()
+                        .extent = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Jitter(JitterNode {
+                        amount: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_jitter_mut()
+                        .unwrap()
+                        .amount = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Quantize(QuantizeNode {
+                        dither: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_quantize_mut()
+                        .unwrap()
+                        .dither = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     2,
                     &NoiseNode::RigidMulti(RigidFractalNode {
@@ -1537,9 +4405,37 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .as_rigid_fractal_mut()
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Plateau(PlateauNode {
+                        softness: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_plateau_mut()
+                        .unwrap()
+                        .softness = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Ridge(RidgeNode {
+                        offset: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_ridge_mut()
                         .unwrap()
-                        .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                        .offset = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
                 (
@@ -1556,6 +4452,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .bias = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    2,
+                    &NoiseNode::Threshold(ThresholdNode {
+                        on_threshold: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_threshold_mut()
+                        .unwrap()
+                        .on_threshold = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     2,
                     &NoiseNode::Turbulence(TurbulenceNode {
@@ -1610,6 +4520,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .lacunarity = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    3,
+                    &NoiseNode::MaskedBlend(MaskedBlendNode {
+                        threshold: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_masked_blend_mut()
+                        .unwrap()
+                        .threshold = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     3,
                     &NoiseNode::Select(SelectNode {
@@ -1624,6 +4548,34 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .lower_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    3,
+                    &NoiseNode::Quantize(QuantizeNode {
+                        seed: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_quantize_mut()
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                }
+                (
+                    3,
+                    &NoiseNode::Threshold(ThresholdNode {
+                        scan_steps: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_threshold_mut()
+                        .unwrap()
+                        .scan_steps = Value(snarl.get_node(node_idx).eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                }
                 (
                     3,
                     &NoiseNode::Turbulence(TurbulenceNode {
@@ -1678,6 +4630,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .persistence = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    4,
+                    &NoiseNode::MaskedBlend(MaskedBlendNode {
+                        feather: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_masked_blend_mut()
+                        .unwrap()
+                        .feather = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     4,
                     &NoiseNode::Select(SelectNode {
@@ -1706,6 +4672,32 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .roughness = Value(snarl.get_node(node_idx).eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
                 }
+                (
+                    5,
+                    &NoiseNode::BasicMulti(FractalNode {
+                        octave_remainder: Node(node_idx),
+                        ..
+                    })
+                    | &NoiseNode::Billow(FractalNode {
+                        octave_remainder: Node(node_idx),
+                        ..
+                    })
+                    | &NoiseNode::Fbm(FractalNode {
+                        octave_remainder: Node(node_idx),
+                        ..
+                    })
+                    | &NoiseNode::HybridMulti(FractalNode {
+                        octave_remainder: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_fractal_mut()
+                        .unwrap()
+                        .octave_remainder = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (
                     5,
                     &NoiseNode::RigidMulti(RigidFractalNode {
@@ -1734,6 +4726,56 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .falloff = Value(snarl.get_node(node_idx).eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
                 }
+                (
+                    6,
+                    &NoiseNode::RigidMulti(RigidFractalNode {
+                        octave_remainder: Node(node_idx),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .as_rigid_fractal_mut()
+                        .unwrap()
+                        .octave_remainder = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (2..=7, NoiseNode::FastNoise(node))
+                    if node
+                        .f64_value(pin.id.input)
+                        .is_some_and(|value| value.is_node_idx()) =>
+                {
+                    let node_idx = node
+                        .f64_value(pin.id.input)
+                        .unwrap()
+                        .as_node_index()
+                        .unwrap();
+                    *snarl
+                        .get_node_mut(pin.id.node)
+                        .as_fast_noise_mut()
+                        .unwrap()
+                        .f64_value_mut(pin.id.input)
+                        .unwrap() = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
+                (5..=10, NoiseNode::Turbulence(node))
+                    if node
+                        .axis_value(pin.id.input)
+                        .is_some_and(|value| value.is_node_idx()) =>
+                {
+                    let node_idx = node
+                        .axis_value(pin.id.input)
+                        .unwrap()
+                        .as_node_index()
+                        .unwrap();
+                    *snarl
+                        .get_node_mut(pin.id.node)
+                        .as_turbulence_mut()
+                        .unwrap()
+                        .axis_value_mut(pin.id.input)
+                        .unwrap() = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
+                }
                 (control_point_idx, NoiseNode::Curve(node)) if control_point_idx > 0 => {
                     let control_point_idx = control_point_idx - 1;
 
@@ -1772,9 +4814,14 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             }
         }
 
+        // Control points and axes are moved after the pin is shown because the node is borrowed
+        // until then
+        let mut moved_axis = None;
+        let mut moved_control_point = None;
+
         ui.set_height(16.0 * scale);
         ui.set_width(128.0 * scale);
-        ui.with_layout(
+        let pin_info = ui.with_layout(
             Layout::left_to_right(Align::Min).with_cross_align(Align::Center),
             |ui| {
                 ui.add_space(20.0 * scale);
@@ -1782,15 +4829,26 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     (
                         0,
                         NoiseNode::Abs(_)
+                        | NoiseNode::Bake(_)
+                        | NoiseNode::Cache(_)
                         | NoiseNode::Clamp(_)
                         | NoiseNode::Curve(_)
                         | NoiseNode::Displace(_)
+                        | NoiseNode::Exp(_)
                         | NoiseNode::Exponent(_)
+                        | NoiseNode::Jitter(_)
+                        | NoiseNode::Log(_)
                         | NoiseNode::Negate(_)
+                        | NoiseNode::Plateau(_)
+                        | NoiseNode::Quantize(_)
+                        | NoiseNode::Rebase(_)
+                        | NoiseNode::Repeat(_)
+                        | NoiseNode::Ridge(_)
                         | NoiseNode::RotatePoint(_)
                         | NoiseNode::ScaleBias(_)
                         | NoiseNode::ScalePoint(_)
                         | NoiseNode::Terrace(_)
+                        | NoiseNode::Threshold(_)
                         | NoiseNode::TranslatePoint(_)
                         | NoiseNode::Turbulence(_),
                     ) => {
@@ -1805,57 +4863,589 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
                     }
                     (
-                        0,
-                        NoiseNode::BasicMulti(FractalNode { seed, .. })
-                        | NoiseNode::Billow(FractalNode { seed, .. })
-                        | NoiseNode::Fbm(FractalNode { seed, .. })
-                        | NoiseNode::HybridMulti(FractalNode { seed, .. })
-                        | NoiseNode::OpenSimplex(GeneratorNode { seed, .. })
-                        | NoiseNode::Perlin(GeneratorNode { seed, .. })
-                        | NoiseNode::PerlinSurflet(GeneratorNode { seed, .. })
-                        | NoiseNode::RigidMulti(RigidFractalNode { seed, .. })
-                        | NoiseNode::Simplex(GeneratorNode { seed, .. })
-                        | NoiseNode::SuperSimplex(GeneratorNode { seed, .. })
-                        | NoiseNode::Value(GeneratorNode { seed, .. })
-                        | NoiseNode::Worley(WorleyNode { seed, .. }),
+                        0,
+                        NoiseNode::BasicMulti(FractalNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::Billow(FractalNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::BlueNoise(GeneratorNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::FastNoise(FastNoiseNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::Fault(FaultNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::Fbm(FractalNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::HybridMulti(FractalNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::OpenSimplex(GeneratorNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::Perlin(GeneratorNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::PerlinSurflet(GeneratorNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::RigidMulti(RigidFractalNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::Simplex(GeneratorNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::SuperSimplex(GeneratorNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::Value(GeneratorNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::WhiteNoise(GeneratorNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        })
+                        | NoiseNode::Worley(WorleyNode {
+                            image,
+                            seed,
+                            master_seed,
+                            ..
+                        }),
+                    ) => {
+                        ui.label(if master_seed.is_some() {
+                            "Seed Offset"
+                        } else {
+                            "Seed"
+                        });
+
+                        if let Some(value) = seed.as_value_mut() {
+                            self.drag_value_seed(ui, scale, value, image, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", seed.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::Checkerboard(CheckerboardNode { size, .. })) => {
+                        ui.label("Size");
+
+                        if let Some(value) = size.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", size.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1..=2, NoiseNode::Checkerboard(node)) => {
+                        ui.label(format!("Offset {}", Self::AXES[pin.id.input - 1]));
+
+                        if let Some(value) = node.offset[pin.id.input - 1].as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.offset[pin.id.input - 1].as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (3, NoiseNode::Checkerboard(node)) => {
+                        ui.label("Rotation");
+
+                        if let Some(value) = node.rotation.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.rotation.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::ControlPoint(node)) => {
+                        ui.label("Input");
+
+                        if let Some(value) = node.input.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.input.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::Cylinders(node)) => {
+                        ui.label("Frequency");
+
+                        if let Some(value) = node.frequency.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.frequency.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::Heightmap(node)) => {
+                        ui.label("Size");
+
+                        if let Some(value) = node.size.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.size.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::Gradient(node)) => {
+                        ui.label("Angle");
+
+                        if let Some(value) = node.angle.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.angle.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (
+                        0 | 1,
+                        NoiseNode::Add(_)
+                        | NoiseNode::Min(_)
+                        | NoiseNode::Max(_)
+                        | NoiseNode::Multiply(_)
+                        | NoiseNode::Power(_),
+                    ) => {
+                        ui.label("Source");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (0, NoiseNode::PowSignal(_)) => {
+                        ui.label("Base");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (1, NoiseNode::PowSignal(_)) => {
+                        ui.label("Exponent");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (0 | 1, NoiseNode::Blend(_) | NoiseNode::Mix(_) | NoiseNode::Select(_)) => {
+                        ui.label("Source");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (0 | 1, NoiseNode::MaskedBlend(_)) => {
+                        ui.label(if pin.id.input == 0 { "Below" } else { "Above" })
+                            .on_hover_text(
+                                "The source used where the mask is below or above the threshold",
+                            );
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (0 | 1, NoiseNode::F64Operation(node)) => {
+                        ui.label("Input");
+
+                        if let Some(value) = node.inputs[pin.id.input].as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.inputs[pin.id.input].as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0 | 1, NoiseNode::Operation(node)) => {
+                        ui.label("Input");
+
+                        if node.inputs[pin.id.input].as_node_index().is_none() {
+                            Self::operation_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.inputs[pin.id.input].as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::operation_pin_info(true, true)
+                        }
+                    }
+                    (0 | 1, NoiseNode::U32Operation(node)) => {
+                        ui.label("Input");
+
+                        if let Some(value) = node.inputs[pin.id.input].as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.inputs[pin.id.input].as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::ControlPoint(node)) => {
+                        ui.label("Output");
+
+                        if let Some(value) = node.output.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.output.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (
+                        1,
+                        NoiseNode::BasicMulti(FractalNode { octaves, .. })
+                        | NoiseNode::Billow(FractalNode { octaves, .. })
+                        | NoiseNode::FastNoise(FastNoiseNode { octaves, .. })
+                        | NoiseNode::Fbm(FractalNode { octaves, .. })
+                        | NoiseNode::HybridMulti(FractalNode { octaves, .. })
+                        | NoiseNode::RigidMulti(RigidFractalNode { octaves, .. }),
                     ) => {
-                        ui.label("Seed");
+                        ui.label("Octaves");
 
-                        if let Some(value) = seed.as_value_mut() {
+                        if let Some(value) = octaves.as_value_mut() {
+                            self.drag_value_octaves(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", octaves.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Bake(node)) => {
+                        ui.label("Resolution");
+
+                        if let Some(value) = node.resolution.as_value_mut() {
                             self.drag_value_u32(ui, scale, value, pin.id.node);
 
                             Self::u32_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", seed.as_node_index().unwrap()))
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.resolution.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Clamp(node)) => {
+                        ui.label("Lower Bound");
+
+                        if let Some(value) = node.lower_bound.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.lower_bound.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Exponent(node)) => {
+                        ui.label("Exponent");
+
+                        if let Some(value) = node.exponent.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.exponent.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Fault(node)) => {
+                        ui.label("Iterations");
+
+                        if let Some(value) = node.iterations.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.iterations.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Gradient(node)) => {
+                        ui.label("Offset");
+
+                        if let Some(value) = node.offset.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.offset.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Jitter(node)) => {
+                        ui.label("Seed");
+
+                        if let Some(value) = node.seed.as_value_mut() {
+                            self.drag_value_seed(ui, scale, value, &node.image, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.seed.as_node_index().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::u32_pin_info(true, true)
                         }
                     }
-                    (0, NoiseNode::Checkerboard(CheckerboardNode { size, .. })) => {
-                        ui.label("Size");
+                    (1, NoiseNode::Quantize(node)) => {
+                        ui.label("Levels");
+
+                        if let Some(value) = node.levels.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.levels.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Turbulence(node)) => {
+                        ui.label(if node.master_seed.is_some() {
+                            "Seed Offset"
+                        } else {
+                            "Seed"
+                        });
 
-                        if let Some(value) = size.as_value_mut() {
-                            self.drag_value_u32(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.seed.as_value_mut() {
+                            self.drag_value_seed(ui, scale, value, &node.image, pin.id.node);
 
                             Self::u32_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", size.as_node_index().unwrap()))
+                                RichText::new(format!("#{:?}", node.seed.as_node_index().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::u32_pin_info(true, true)
                         }
                     }
-                    (0, NoiseNode::ControlPoint(node)) => {
-                        ui.label("Input");
+                    (1..=3, NoiseNode::Cylinders(node)) => {
+                        ui.label(format!("Frequency {}", Self::AXES[pin.id.input - 1]));
 
-                        if let Some(value) = node.input.as_value_mut() {
+                        if let Some(value) = node.axis_frequencies[pin.id.input - 1].as_value_mut()
+                        {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -1864,7 +5454,9 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.input.as_node_index().unwrap()
+                                    node.axis_frequencies[pin.id.input - 1]
+                                        .as_node_index()
+                                        .unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -1872,10 +5464,29 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (0, NoiseNode::Cylinders(node)) => {
-                        ui.label("Frequency");
+                    (1..=4, NoiseNode::Displace(_)) => {
+                        let is_connected = !snarl.in_pin(pin.id).remotes.is_empty();
+                        moved_axis = Self::reorder_label(
+                            ui,
+                            Self::AXES[pin.id.input - 1],
+                            pin.id.node,
+                            pin.id.input - 1,
+                            Self::AXES.len(),
+                            is_connected && !self.read_only,
+                        );
 
-                        if let Some(value) = node.frequency.as_value_mut() {
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, is_connected)
+                    }
+                    (1..=3, NoiseNode::Repeat(node)) => {
+                        ui.label(Self::AXES[pin.id.input - 1]);
+
+                        if let Some(value) = node.periods[pin.id.input - 1].as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -1884,7 +5495,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.frequency.as_node_index().unwrap()
+                                    node.periods[pin.id.input - 1].as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -1893,38 +5504,14 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         }
                     }
                     (
-                        0 | 1,
-                        NoiseNode::Add(_)
-                        | NoiseNode::Min(_)
-                        | NoiseNode::Max(_)
-                        | NoiseNode::Multiply(_)
-                        | NoiseNode::Power(_),
+                        1..=4,
+                        NoiseNode::RotatePoint(node)
+                        | NoiseNode::ScalePoint(node)
+                        | NoiseNode::TranslatePoint(node),
                     ) => {
-                        ui.label("Source");
-
-                        #[cfg(debug_assertions)]
-                        ui.label(
-                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
-                                .color(Color32::DEBUG_COLOR),
-                        );
-
-                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
-                    }
-                    (0 | 1, NoiseNode::Blend(_) | NoiseNode::Select(_)) => {
-                        ui.label("Source");
-
-                        #[cfg(debug_assertions)]
-                        ui.label(
-                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
-                                .color(Color32::DEBUG_COLOR),
-                        );
-
-                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
-                    }
-                    (0 | 1, NoiseNode::F64Operation(node)) => {
-                        ui.label("Input");
+                        ui.label(Self::AXES[pin.id.input - 1]);
 
-                        if let Some(value) = node.inputs[pin.id.input].as_value_mut() {
+                        if let Some(value) = node.axes[pin.id.input - 1].as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -1933,7 +5520,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.inputs[pin.id.input].as_node_index().unwrap()
+                                    node.axes[pin.id.input - 1].as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -1941,48 +5528,50 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (0 | 1, NoiseNode::Operation(node)) => {
-                        ui.label("Input");
+                    (1, NoiseNode::Plateau(node)) => {
+                        ui.label("Level");
 
-                        if node.inputs[pin.id.input].as_node_index().is_none() {
-                            Self::operation_pin_info(true, false)
+                        if let Some(value) = node.level.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.inputs[pin.id.input].as_node_index().unwrap()
+                                    node.level.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::operation_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (0 | 1, NoiseNode::U32Operation(node)) => {
-                        ui.label("Input");
+                    (1, NoiseNode::Rebase(node)) => {
+                        ui.label("Period");
 
-                        if let Some(value) = node.inputs[pin.id.input].as_value_mut() {
-                            self.drag_value_u32(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.period.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
 
-                            Self::u32_pin_info(true, false)
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.inputs[pin.id.input].as_node_index().unwrap()
+                                    node.period.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::u32_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::ControlPoint(node)) => {
-                        ui.label("Output");
+                    (1, NoiseNode::Ridge(node)) => {
+                        ui.label("Sharpness");
 
-                        if let Some(value) = node.output.as_value_mut() {
+                        if let Some(value) = node.sharpness.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -1991,7 +5580,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.output.as_node_index().unwrap()
+                                    node.sharpness.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -1999,34 +5588,30 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (
-                        1,
-                        NoiseNode::BasicMulti(FractalNode { octaves, .. })
-                        | NoiseNode::Billow(FractalNode { octaves, .. })
-                        | NoiseNode::Fbm(FractalNode { octaves, .. })
-                        | NoiseNode::HybridMulti(FractalNode { octaves, .. })
-                        | NoiseNode::RigidMulti(RigidFractalNode { octaves, .. }),
-                    ) => {
-                        ui.label("Octaves");
+                    (1, NoiseNode::ScaleBias(node)) => {
+                        ui.label("Scale");
 
-                        if let Some(value) = octaves.as_value_mut() {
-                            self.drag_value_octaves(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.scale.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
 
-                            Self::u32_pin_info(true, false)
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", octaves.as_node_index().unwrap()))
-                                    .color(Color32::DEBUG_COLOR),
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.scale.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::u32_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::Clamp(node)) => {
-                        ui.label("Lower Bound");
+                    (1, NoiseNode::Threshold(node)) => {
+                        ui.label("Off Threshold");
 
-                        if let Some(value) = node.lower_bound.as_value_mut() {
+                        if let Some(value) = node.off_threshold.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -2035,7 +5620,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.lower_bound.as_node_index().unwrap()
+                                    node.off_threshold.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -2043,10 +5628,10 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::Exponent(node)) => {
-                        ui.label("Exponent");
+                    (1, NoiseNode::Worley(node)) => {
+                        ui.label("Frequency");
 
-                        if let Some(value) = node.exponent.as_value_mut() {
+                        if let Some(value) = node.frequency.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -2055,7 +5640,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.exponent.as_node_index().unwrap()
+                                    node.frequency.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -2063,25 +5648,32 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::Turbulence(node)) => {
-                        ui.label("Seed");
+                    (
+                        2,
+                        NoiseNode::BasicMulti(FractalNode { frequency, .. })
+                        | NoiseNode::Billow(FractalNode { frequency, .. })
+                        | NoiseNode::Fbm(FractalNode { frequency, .. })
+                        | NoiseNode::HybridMulti(FractalNode { frequency, .. })
+                        | NoiseNode::RigidMulti(RigidFractalNode { frequency, .. }),
+                    ) => {
+                        ui.label("Frequency");
 
-                        if let Some(value) = node.seed.as_value_mut() {
-                            self.drag_value_u32(ui, scale, value, pin.id.node);
+                        if let Some(value) = frequency.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
 
-                            Self::u32_pin_info(true, false)
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", node.seed.as_node_index().unwrap()))
+                                RichText::new(format!("#{:?}", frequency.as_node_index().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::u32_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (1..=4, NoiseNode::Displace(_)) => {
-                        ui.label(Self::AXES[pin.id.input - 1]);
+                    (2, NoiseNode::Mix(_)) => {
+                        ui.label("Factor");
 
                         #[cfg(debug_assertions)]
                         ui.label(
@@ -2091,15 +5683,49 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
 
                         Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
                     }
-                    (
-                        1..=4,
-                        NoiseNode::RotatePoint(node)
-                        | NoiseNode::ScalePoint(node)
-                        | NoiseNode::TranslatePoint(node),
-                    ) => {
-                        ui.label(Self::AXES[pin.id.input - 1]);
+                    (2, NoiseNode::Blend(_) | NoiseNode::Select(_)) => {
+                        ui.label("Control");
 
-                        if let Some(value) = node.axes[pin.id.input - 1].as_value_mut() {
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (2, NoiseNode::MaskedBlend(_)) => {
+                        ui.label("Mask");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (2, NoiseNode::Bake(node)) => {
+                        ui.label("Size");
+
+                        if let Some(value) = node.size.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.size.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (2, NoiseNode::Clamp(node)) => {
+                        ui.label("Upper Bound");
+
+                        if let Some(value) = node.upper_bound.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -2108,7 +5734,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.axes[pin.id.input - 1].as_node_index().unwrap()
+                                    node.upper_bound.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -2116,10 +5742,13 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::ScaleBias(node)) => {
-                        ui.label("Scale");
+                    (2, NoiseNode::Fault(node)) => {
+                        ui.label("Extent").on_hover_text(
+                            "The fault planes pass within this distance of the origin; further out \
+                            the terrain turns into spokes",
+                        );
 
-                        if let Some(value) = node.scale.as_value_mut() {
+                        if let Some(value) = node.extent.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -2128,7 +5757,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.scale.as_node_index().unwrap()
+                                    node.extent.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -2136,10 +5765,10 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::Worley(node)) => {
-                        ui.label("Frequency");
+                    (2, NoiseNode::Jitter(node)) => {
+                        ui.label("Amount");
 
-                        if let Some(value) = node.frequency.as_value_mut() {
+                        if let Some(value) = node.amount.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -2148,7 +5777,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.frequency.as_node_index().unwrap()
+                                    node.amount.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -2156,45 +5785,50 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (
-                        2,
-                        NoiseNode::BasicMulti(FractalNode { frequency, .. })
-                        | NoiseNode::Billow(FractalNode { frequency, .. })
-                        | NoiseNode::Fbm(FractalNode { frequency, .. })
-                        | NoiseNode::HybridMulti(FractalNode { frequency, .. })
-                        | NoiseNode::RigidMulti(RigidFractalNode { frequency, .. }),
-                    ) => {
-                        ui.label("Frequency");
+                    (2, NoiseNode::Quantize(node)) => {
+                        ui.label("Dither");
 
-                        if let Some(value) = frequency.as_value_mut() {
+                        if let Some(value) = node.dither.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", frequency.as_node_index().unwrap()))
-                                    .color(Color32::DEBUG_COLOR),
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.dither.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (2, NoiseNode::Blend(_) | NoiseNode::Select(_)) => {
-                        ui.label("Control");
+                    (2, NoiseNode::Plateau(node)) => {
+                        ui.label("Softness");
 
-                        #[cfg(debug_assertions)]
-                        ui.label(
-                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                        if let Some(value) = node.softness.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.softness.as_node_index().unwrap()
+                                ))
                                 .color(Color32::DEBUG_COLOR),
-                        );
+                            );
 
-                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                            Self::f64_pin_info(true, true)
+                        }
                     }
-                    (2, NoiseNode::Clamp(node)) => {
-                        ui.label("Upper Bound");
+                    (2, NoiseNode::Ridge(node)) => {
+                        ui.label("Offset");
 
-                        if let Some(value) = node.upper_bound.as_value_mut() {
+                        if let Some(value) = node.offset.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -2203,7 +5837,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.upper_bound.as_node_index().unwrap()
+                                    node.offset.as_node_index().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -2211,18 +5845,38 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (2, NoiseNode::ScaleBias(node)) => {
-                        ui.label("Bias");
+                    (2, NoiseNode::ScaleBias(node)) => {
+                        ui.label("Bias");
+
+                        if let Some(value) = node.bias.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.bias.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (2, NoiseNode::Threshold(node)) => {
+                        ui.label("On Threshold");
 
-                        if let Some(value) = node.bias.as_value_mut() {
+                        if let Some(value) = node.on_threshold.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", node.bias.as_node_index().unwrap()))
-                                    .color(Color32::DEBUG_COLOR),
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.on_threshold.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::f64_pin_info(true, true)
@@ -2275,6 +5929,26 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
+                    (3, NoiseNode::MaskedBlend(node)) => {
+                        ui.label("Threshold");
+
+                        if let Some(value) = node.threshold.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.threshold.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
                     (3, NoiseNode::Select(node)) => {
                         ui.label("Lower Bound");
 
@@ -2295,6 +5969,48 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
+                    (3, NoiseNode::Quantize(node)) => {
+                        ui.label("Seed").on_hover_text("Seed of the dither offsets");
+
+                        if let Some(value) = node.seed.as_value_mut() {
+                            self.drag_value_seed(ui, scale, value, &node.image, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.seed.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (3, NoiseNode::Threshold(node)) => {
+                        ui.label("Scan Steps").on_hover_text(
+                            "In hysteresis mode, values between the thresholds take the state of \
+                            the nearest sample towards -X which is outside of them, so the mask \
+                            depends on direction; each step samples the source again, 1/32 units \
+                            further away",
+                        );
+
+                        if let Some(value) = node.scan_steps.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.scan_steps.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
                     (3, NoiseNode::Turbulence(node)) => {
                         ui.label("Power");
 
@@ -2342,6 +6058,26 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
+                    (4, NoiseNode::MaskedBlend(node)) => {
+                        ui.label("Feather");
+
+                        if let Some(value) = node.feather.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.feather.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
                     (4, NoiseNode::Select(node)) => {
                         ui.label("Upper Bound");
 
@@ -2382,6 +6118,46 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::u32_pin_info(true, true)
                         }
                     }
+                    (
+                        5,
+                        NoiseNode::BasicMulti(FractalNode {
+                            octave_remainder, ..
+                        })
+                        | NoiseNode::Billow(FractalNode {
+                            octave_remainder, ..
+                        })
+                        | NoiseNode::Fbm(FractalNode {
+                            octave_remainder, ..
+                        })
+                        | NoiseNode::HybridMulti(FractalNode {
+                            octave_remainder, ..
+                        }),
+                    )
+                    | (
+                        6,
+                        NoiseNode::RigidMulti(RigidFractalNode {
+                            octave_remainder, ..
+                        }),
+                    ) => {
+                        ui.label("Octave Remainder");
+
+                        if let Some(value) = octave_remainder.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    octave_remainder.as_node_index().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
                     (5, NoiseNode::RigidMulti(node)) => {
                         ui.label("Attenuation");
 
@@ -2422,10 +6198,69 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (control_point_idx, NoiseNode::Curve(node)) => {
-                        ui.label("Control Point");
+                    (input @ 2..=7, NoiseNode::FastNoise(node)) => {
+                        ui.label(match input {
+                            2 => "Frequency",
+                            3 => "Lacunarity",
+                            4 => "Gain",
+                            5 => "Weighting",
+                            6 => "Ping Pong",
+                            _ => "Jitter",
+                        });
+
+                        let value = node.f64_value_mut(input).unwrap();
+                        if let Some(value) = value.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", value.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (input @ 5..=10, NoiseNode::Turbulence(node)) => {
+                        ui.label(format!(
+                            "{} {}",
+                            if input < 8 { "Frequency" } else { "Power" },
+                            Self::AXES[(input - 5) % 3]
+                        ));
 
+                        let value = node.axis_value_mut(input).unwrap();
+                        if let Some(value) = value.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", value.as_node_index().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (control_point_idx, NoiseNode::Curve(node)) => {
                         let control_point_idx = control_point_idx - 1;
+                        let is_connected = node
+                            .control_point_node_indices
+                            .get(control_point_idx)
+                            .copied()
+                            .flatten()
+                            .is_some();
+                        moved_control_point = Self::reorder_label(
+                            ui,
+                            "Control Point",
+                            pin.id.node,
+                            control_point_idx,
+                            node.control_point_node_indices.len(),
+                            is_connected && !self.read_only,
+                        );
 
                         #[cfg(debug_assertions)]
                         ui.label(
@@ -2438,13 +6273,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             .color(Color32::DEBUG_COLOR),
                         );
 
-                        if node
-                            .control_point_node_indices
-                            .get(control_point_idx)
-                            .copied()
-                            .flatten()
-                            .is_none()
-                        {
+                        if !is_connected {
                             Self::control_point_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
@@ -2464,9 +6293,21 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         }
                     }
                     (control_point_idx, NoiseNode::Terrace(node)) => {
-                        ui.label("Decimal");
-
                         let control_point_idx = control_point_idx - 1;
+                        let is_connected = node
+                            .control_point_node_indices
+                            .get(control_point_idx)
+                            .copied()
+                            .flatten()
+                            .is_some();
+                        moved_control_point = Self::reorder_label(
+                            ui,
+                            "Decimal",
+                            pin.id.node,
+                            control_point_idx,
+                            node.control_point_node_indices.len(),
+                            is_connected && !self.read_only,
+                        );
 
                         #[cfg(debug_assertions)]
                         ui.label(
@@ -2479,13 +6320,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             .color(Color32::DEBUG_COLOR),
                         );
 
-                        if node
-                            .control_point_node_indices
-                            .get(control_point_idx)
-                            .copied()
-                            .flatten()
-                            .is_none()
-                        {
+                        if !is_connected {
                             Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
@@ -2507,8 +6342,21 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     _ => unreachable!(),
                 }
             },
-        )
-        .inner
+        );
+
+        // The axes of a displace node follow its source input
+        if let Some((from, to)) = moved_axis {
+            self.swap_inputs(pin.id.node, from + 1, to + 1, snarl);
+        }
+
+        if let Some((from, to)) = moved_control_point {
+            NoiseNode::move_control_point(pin.id.node, from, to, snarl);
+            self.updated_node_indices.insert(pin.id.node);
+        }
+
+        self.dim_filtered(ui, pin.id.node, snarl);
+
+        pin_info.inner
     }
 
     fn show_output(
@@ -2518,34 +6366,99 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
         scale: f32,
         snarl: &mut Snarl<NoiseNode>,
     ) -> PinInfo {
+        self.wire_layout.record_output(
+            pin.id.node,
+            pos2(
+                ui.max_rect().right(),
+                ui.cursor().top() + ui.spacing().interact_size.y / 2.0,
+            ),
+        );
+
+        let node = snarl.get_node(pin.id.node);
+
+        if let Some(image) = node.image() {
+            if let Some(texture) = &image.texture {
+                let display = image.display;
+                let grid = image.grid.then(|| {
+                    let (x, y, image_scale) = (image.x, image.y, image.scale);
+
+                    [[x, y], [x + 1.0, y + 1.0]].map(|corner| corner.map(|c| c * image_scale))
+                });
+                let legend = image.legend;
+
+                // Textures are rendered at the display density but shown at the same size
+                let size = texture.size_vec2() / image.density as f32 * scale;
+                let rect = ui.image((texture.id(), size)).rect;
+
+                if let Some([min, max]) = grid {
+                    grid::paint(ui.painter(), rect, min, max, scale);
+                }
+
+                self.show_select_bounds(ui, rect, pin, snarl);
+
+                if legend {
+                    Self::show_legend(ui, rect.width(), scale, &display);
+                }
+
+                Self::show_export_region(ui, rect, pin.id.node, snarl);
+
+                // The readout is hidden while nodes are dragged over previews
+                if ui.rect_contains_pointer(rect) && !ui.input(|input| input.pointer.any_down()) {
+                    self.show_readout(ui, rect, pin.id.node, snarl);
+                }
+            }
+        }
+
         let node = snarl.get_node(pin.id.node);
 
-        if let Some(texture) = node.image().and_then(|image| image.texture.as_ref()) {
-            ui.image((texture.id(), texture.size_vec2() * scale));
+        if let NoiseNode::ScaleBias(node) = node {
+            let [lower, upper] = node.output_range(snarl);
+            ui.weak(format!("[{lower:.3}, {upper:.3}]"))
+                .on_hover_text("Output range for inputs in [-1, 1]");
         }
 
+        self.dim_filtered(ui, pin.id.node, snarl);
+
         match node {
             NoiseNode::Abs(_)
             | NoiseNode::Add(_)
+            | NoiseNode::Bake(_)
             | NoiseNode::BasicMulti(_)
             | NoiseNode::Billow(_)
             | NoiseNode::Blend(_)
+            | NoiseNode::BlueNoise(_)
+            | NoiseNode::Cache(_)
             | NoiseNode::Checkerboard(_)
             | NoiseNode::Clamp(_)
             | NoiseNode::Curve(_)
             | NoiseNode::Cylinders(_)
             | NoiseNode::Displace(_)
+            | NoiseNode::Exp(_)
             | NoiseNode::Exponent(_)
+            | NoiseNode::FastNoise(_)
+            | NoiseNode::Fault(_)
             | NoiseNode::Fbm(_)
+            | NoiseNode::Gradient(_)
+            | NoiseNode::Heightmap(_)
             | NoiseNode::HybridMulti(_)
+            | NoiseNode::Jitter(_)
+            | NoiseNode::Log(_)
+            | NoiseNode::MaskedBlend(_)
             | NoiseNode::Min(_)
             | NoiseNode::Max(_)
+            | NoiseNode::Mix(_)
             | NoiseNode::Multiply(_)
             | NoiseNode::Negate(_)
             | NoiseNode::OpenSimplex(_)
             | NoiseNode::Perlin(_)
             | NoiseNode::PerlinSurflet(_)
+            | NoiseNode::Plateau(_)
             | NoiseNode::Power(_)
+            | NoiseNode::PowSignal(_)
+            | NoiseNode::Quantize(_)
+            | NoiseNode::Rebase(_)
+            | NoiseNode::Repeat(_)
+            | NoiseNode::Ridge(_)
             | NoiseNode::RigidMulti(_)
             | NoiseNode::RotatePoint(_)
             | NoiseNode::ScaleBias(_)
@@ -2554,9 +6467,11 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
             | NoiseNode::Simplex(_)
             | NoiseNode::SuperSimplex(_)
             | NoiseNode::Terrace(_)
+            | NoiseNode::Threshold(_)
             | NoiseNode::TranslatePoint(_)
             | NoiseNode::Turbulence(_)
             | NoiseNode::Value(_)
+            | NoiseNode::WhiteNoise(_)
             | NoiseNode::Worley(_) => Self::image_pin_info(
                 false,
                 !snarl
@@ -2629,6 +6544,41 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
     }
 
     fn graph_menu(&mut self, pos: Pos2, ui: &mut Ui, _scale: f32, snarl: &mut Snarl<NoiseNode>) {
+        if self.read_only {
+            ui.weak("This project is open read-only");
+
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Master Seed");
+
+            if ui
+                .add(DragValue::new(self.master_seed))
+                .on_hover_text("Combined with the seed offset of nodes which inherit it")
+                .changed()
+            {
+                self.update_master_seed(snarl);
+            }
+
+            if ui
+                .small_button("🎲")
+                .on_hover_text("Re-roll master seed")
+                .clicked()
+            {
+                self.set_master_seed(ui.ctx(), SeedHistory::random_seed(), snarl);
+            }
+        });
+
+        ui.menu_button("Master Seed History", |ui| {
+            if let Some(master_seed) = self.seed_history.show(ui, None) {
+                self.set_master_seed(ui.ctx(), master_seed, snarl);
+
+                ui.close_menu();
+            }
+        });
+
+        ui.separator();
         ui.label("Add node");
 
         ui.menu_button("Combiners", |ui| {
@@ -2661,8 +6611,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     .insert(snarl.insert_node(pos, NoiseNode::Power(Default::default())));
                 ui.close_menu();
             }
+
+            if ui.button("Pow Signal").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::PowSignal(Default::default())));
+                ui.close_menu();
+            }
         });
         ui.menu_button("Generators", |ui| {
+            if ui.button("Blue Noise").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::BlueNoise(Default::default())));
+                ui.close_menu();
+            }
+
             if ui.button("Checkerboard").clicked() {
                 self.updated_node_indices
                     .insert(snarl.insert_node(pos, NoiseNode::Checkerboard(Default::default())));
@@ -2675,6 +6637,34 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 ui.close_menu();
             }
 
+            if ui.button("FastNoise Lite").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::FastNoise(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Fault").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Fault(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Gradient").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Gradient(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui
+                .button("Heightmap")
+                .on_hover_text("Samples a grayscale PNG image, such as a painted heightmap")
+                .clicked()
+            {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Heightmap(Default::default())));
+                ui.close_menu();
+            }
+
             if ui.button("Open Simplex").clicked() {
                 self.updated_node_indices
                     .insert(snarl.insert_node(pos, NoiseNode::OpenSimplex(Default::default())));
@@ -2711,6 +6701,12 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 ui.close_menu();
             }
 
+            if ui.button("White Noise").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::WhiteNoise(Default::default())));
+                ui.close_menu();
+            }
+
             if ui.button("Worley").clicked() {
                 self.updated_node_indices
                     .insert(snarl.insert_node(pos, NoiseNode::Worley(Default::default())));
@@ -2751,31 +6747,79 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
         ui.menu_button("Modifiers", |ui| {
             if ui.button("Abs").clicked() {
                 self.updated_node_indices
-                    .insert(snarl.insert_node(pos, NoiseNode::Abs(Default::default())));
+                    .insert(snarl.insert_node(pos, NoiseNode::Abs(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Bake").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Bake(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui
+                .button("Cache")
+                .on_hover_text(
+                    "Sample the source once for each point, however many nodes it is connected to",
+                )
+                .clicked()
+            {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Cache(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Clamp").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Clamp(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Curve").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Curve(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Exp").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Exp(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Exponent").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Exponent(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Log").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Log(Default::default())));
                 ui.close_menu();
             }
 
-            if ui.button("Clamp").clicked() {
+            if ui.button("Negate").clicked() {
                 self.updated_node_indices
-                    .insert(snarl.insert_node(pos, NoiseNode::Clamp(Default::default())));
+                    .insert(snarl.insert_node(pos, NoiseNode::Negate(Default::default())));
                 ui.close_menu();
             }
 
-            if ui.button("Curve").clicked() {
+            if ui.button("Plateau").clicked() {
                 self.updated_node_indices
-                    .insert(snarl.insert_node(pos, NoiseNode::Curve(Default::default())));
+                    .insert(snarl.insert_node(pos, NoiseNode::Plateau(Default::default())));
                 ui.close_menu();
             }
 
-            if ui.button("Exponent").clicked() {
+            if ui.button("Quantize").clicked() {
                 self.updated_node_indices
-                    .insert(snarl.insert_node(pos, NoiseNode::Exponent(Default::default())));
+                    .insert(snarl.insert_node(pos, NoiseNode::Quantize(Default::default())));
                 ui.close_menu();
             }
 
-            if ui.button("Negate").clicked() {
+            if ui.button("Ridge").clicked() {
                 self.updated_node_indices
-                    .insert(snarl.insert_node(pos, NoiseNode::Negate(Default::default())));
+                    .insert(snarl.insert_node(pos, NoiseNode::Ridge(Default::default())));
                 ui.close_menu();
             }
 
@@ -2798,11 +6842,29 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 ui.close_menu();
             }
 
+            if ui.button("Masked Blend").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::MaskedBlend(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Mix").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Mix(Default::default())));
+                ui.close_menu();
+            }
+
             if ui.button("Select").clicked() {
                 self.updated_node_indices
                     .insert(snarl.insert_node(pos, NoiseNode::Select(Default::default())));
                 ui.close_menu();
             }
+
+            if ui.button("Threshold").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Threshold(Default::default())));
+                ui.close_menu();
+            }
         });
         ui.menu_button("Transformers", |ui| {
             if ui.button("Displace").clicked() {
@@ -2811,6 +6873,24 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 ui.close_menu();
             }
 
+            if ui.button("Jitter").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Jitter(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Rebase").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Rebase(Default::default())));
+                ui.close_menu();
+            }
+
+            if ui.button("Repeat").clicked() {
+                self.updated_node_indices
+                    .insert(snarl.insert_node(pos, NoiseNode::Repeat(Default::default())));
+                ui.close_menu();
+            }
+
             if ui.button("Rotate Point").clicked() {
                 self.updated_node_indices
                     .insert(snarl.insert_node(pos, NoiseNode::RotatePoint(TransformNode::zero())));
@@ -2842,69 +6922,534 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 ui.close_menu();
             }
 
-            if ui.button("Decimal").clicked() {
-                snarl.insert_node(pos, NoiseNode::F64(Default::default()));
-                ui.close_menu();
-            }
+            if ui.button("Decimal").clicked() {
+                snarl.insert_node(pos, NoiseNode::F64(Default::default()));
+                ui.close_menu();
+            }
+
+            if ui.button("Integer").clicked() {
+                snarl.insert_node(pos, NoiseNode::U32(Default::default()));
+                ui.close_menu();
+            }
+
+            ui.separator();
+            ui.label("Operations");
+
+            if ui.button("Add").clicked() {
+                snarl.insert_node(
+                    pos,
+                    NoiseNode::Operation(ConstantOpNode::new(OpType::Add, ())),
+                );
+                ui.close_menu();
+            }
+
+            if ui.button("Divide").clicked() {
+                snarl.insert_node(
+                    pos,
+                    NoiseNode::Operation(ConstantOpNode::new(OpType::Divide, ())),
+                );
+                ui.close_menu();
+            }
+
+            if ui.button("Multiply").clicked() {
+                snarl.insert_node(
+                    pos,
+                    NoiseNode::Operation(ConstantOpNode::new(OpType::Multiply, ())),
+                );
+                ui.close_menu();
+            }
+
+            if ui.button("Subtract").clicked() {
+                snarl.insert_node(
+                    pos,
+                    NoiseNode::Operation(ConstantOpNode::new(OpType::Subtract, ())),
+                );
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn node_menu(
+        &mut self,
+        node_idx: usize,
+        inputs: &[InPin],
+        outputs: &[OutPin],
+        ui: &mut Ui,
+        _scale: f32,
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        ui.label("Node menu");
+
+        if snarl.get_node(node_idx).has_image() && ui.button("Show Expression").clicked() {
+            *self.selected_node_idx = Some(node_idx);
+
+            ui.close_menu();
+        }
+
+        if snarl.get_node(node_idx).has_image()
+            && ui
+                .button("Explore")
+                .on_hover_text("Pan and zoom around the noise of this node without limits")
+                .clicked()
+        {
+            *self.inspect = Some((Inspector::Explore, node_idx));
+
+            ui.close_menu();
+        }
+
+        if snarl.get_node(node_idx).has_image()
+            && ui
+                .button("Preview On Sphere")
+                .on_hover_text(
+                    "Wrap the noise of this node around a sphere, which shows seams and polar \
+                    distortion",
+                )
+                .clicked()
+        {
+            *self.inspect = Some((Inspector::Sphere, node_idx));
+
+            ui.close_menu();
+        }
+
+        if snarl.get_node(node_idx).has_image()
+            && ui
+                .button("Preview As Terrain")
+                .on_hover_text("Show the values of this node as the heights of a lit terrain")
+                .clicked()
+        {
+            *self.inspect = Some((Inspector::Terrain, node_idx));
+
+            ui.close_menu();
+        }
+
+        if snarl.get_node(node_idx).has_image()
+            && ui
+                .button("Describe Character")
+                .on_hover_text("Describe what the output of this node looks like")
+                .clicked()
+        {
+            *self.inspect = Some((Inspector::Character, node_idx));
+
+            ui.close_menu();
+        }
+
+        if snarl.get_node(node_idx).has_image()
+            && ui
+                .button("Check Chunk Borders")
+                .on_hover_text(
+                    "Sample adjacent chunks of this node and check that their shared borders \
+                    match exactly",
+                )
+                .clicked()
+        {
+            *self.inspect = Some((Inspector::Chunks, node_idx));
+
+            ui.close_menu();
+        }
+
+        if snarl.get_node(node_idx).has_image()
+            && ui
+                .button("Analyze Large Coordinates")
+                .on_hover_text(
+                    "Sample this node far from the origin and report where it loses precision",
+                )
+                .clicked()
+        {
+            *self.inspect = Some((Inspector::Stability, node_idx));
+
+            ui.close_menu();
+        }
+
+        if snarl.get_node(node_idx).has_image()
+            && ui
+                .button("Check Aliasing")
+                .on_hover_text(
+                    "Render this node with more samples per pixel and report where it changes, \
+                    which shimmers when baked",
+                )
+                .clicked()
+        {
+            *self.inspect = Some((Inspector::Aliasing, node_idx));
+
+            ui.close_menu();
+        }
+
+        if let Some(image) = snarl.get_node_mut(node_idx).image_mut() {
+            ui.checkbox(&mut image.grid, "Show Grid")
+                .on_hover_text("Draws world-space grid lines, axis labels and a ruler");
+            ui.checkbox(&mut image.legend, "Show Legend");
+
+            if ui
+                .checkbox(&mut image.tileable, "Tileable")
+                .on_hover_text(
+                    "Blend the far edges of the preview with its near edges, so that the preview \
+                    and its exports tile seamlessly",
+                )
+                .changed()
+            {
+                self.updated_node_indices.insert(node_idx);
+            }
+
+            ui.menu_button("Preview Display", |ui| {
+                let display = image.display;
+
+                Grid::new("preview_display").show(ui, |ui| {
+                    ui.label("Contrast");
+                    ui.add(
+                        DragValue::new(&mut image.display.contrast)
+                            .clamp_range(PreviewDisplay::CONTRAST_RANGE)
+                            .speed(0.01),
+                    );
+                    ui.end_row();
+
+                    ui.label("Gamma");
+                    ui.add(
+                        DragValue::new(&mut image.display.gamma)
+                            .clamp_range(PreviewDisplay::GAMMA_RANGE)
+                            .speed(0.01),
+                    )
+                    .on_hover_text("Values above one brighten dark outputs, such as ridged noise");
+                    ui.end_row();
+
+                    ui.label("Invert");
+                    ui.checkbox(&mut image.display.invert, "");
+                    ui.end_row();
+                });
+
+                if ui
+                    .add_enabled(!image.display.is_default(), Button::new("Reset"))
+                    .clicked()
+                {
+                    image.display = Default::default();
+                }
+
+                ui.weak("Only the preview is changed, not the values of the node");
+
+                if image.display != display {
+                    image.redisplay();
+                }
+            });
+        }
+
+        if self.read_only {
+            return;
+        }
+
+        if let Some(image) = snarl.get_node_mut(node_idx).image_mut() {
+            ui.menu_button("Output Range", |ui| {
+                let output_range = image.output_range;
+                let mut annotated = output_range.is_some();
+
+                ui.checkbox(&mut annotated, "Annotate").on_hover_text(
+                    "Declare the range the values of the node are expected to be within",
+                );
+
+                if annotated != output_range.is_some() {
+                    image.output_range = annotated.then_some([-1.0, 1.0]);
+                }
+
+                if let Some([min, max]) = &mut image.output_range {
+                    Grid::new("output_range").show(ui, |ui| {
+                        ui.label("Min");
+                        ui.add(DragValue::new(min).clamp_range(f64::MIN..=*max).speed(0.01));
+                        ui.end_row();
+
+                        ui.label("Max");
+                        ui.add(DragValue::new(max).clamp_range(*min..=f64::MAX).speed(0.01));
+                        ui.end_row();
+                    });
+                }
+
+                ui.weak("Exported code may check the values, which are not changed");
+
+                if image.output_range != output_range {
+                    self.updated_node_indices.insert(node_idx);
+                }
+            });
+        }
+
+        if snarl.get_node(node_idx).has_image() {
+            ui.menu_button("Add Downstream", |ui| {
+                let mut menus = Favorites::NODES.map(|(menu, ..)| menu).to_vec();
+                menus.dedup();
+
+                for menu in menus {
+                    // Nodes with a source take the output of this node as it
+                    let nodes = Favorites::NODES
+                        .into_iter()
+                        .filter(|&(node_menu, _, new_node)| {
+                            node_menu == menu && new_node().has_source()
+                        })
+                        .collect::<Vec<_>>();
+
+                    if nodes.is_empty() {
+                        continue;
+                    }
+
+                    ui.menu_button(menu, |ui| {
+                        for (_, name, new_node) in nodes {
+                            if ui.button(name).clicked() {
+                                self.add_downstream(ui.ctx(), node_idx, new_node(), snarl);
+
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                }
+            });
+        }
+
+        if matches!(
+            snarl.get_node(node_idx),
+            NoiseNode::Add(_)
+                | NoiseNode::Blend(_)
+                | NoiseNode::MaskedBlend(_)
+                | NoiseNode::Max(_)
+                | NoiseNode::Min(_)
+                | NoiseNode::Mix(_)
+                | NoiseNode::Multiply(_)
+                | NoiseNode::Power(_)
+                | NoiseNode::PowSignal(_)
+                | NoiseNode::Select(_)
+        ) && ui
+            .button("Swap Inputs")
+            .on_hover_text("Swap the nodes connected to the first two inputs")
+            .clicked()
+        {
+            self.swap_inputs(node_idx, 0, 1, snarl);
+
+            ui.close_menu();
+        }
+
+        if let Some(master_seed) = snarl.get_node_mut(node_idx).master_seed_mut() {
+            let mut inherit = master_seed.is_some();
+            if ui
+                .checkbox(&mut inherit, "Inherit Master Seed")
+                .on_hover_text("Use the seed as an offset which is combined with the master seed")
+                .changed()
+            {
+                *master_seed = inherit.then_some(*self.master_seed);
+                self.updated_node_indices.insert(node_idx);
+            }
+
+            ui.separator();
+        }
+
+        if let Some(&mut Value(seed)) = snarl.get_node_mut(node_idx).seed_mut() {
+            ui.menu_button("Seed History", |ui| {
+                if let Some(restored_seed) = self.seed_history.show(ui, Some(node_idx)) {
+                    let node = snarl.get_node_mut(node_idx);
+                    self.seed_history
+                        .push(ui.ctx(), Some(node_idx), seed, node.image());
+                    *node.seed_mut().unwrap() = Value(restored_seed);
+                    self.updated_node_indices.insert(node_idx);
+
+                    ui.close_menu();
+                }
+            });
+
+            ui.separator();
+        }
+
+        if let Some(node) = snarl.get_node_mut(node_idx).as_generator_mut() {
+            ui.menu_button("Frequency", |ui| {
+                let axis_frequencies = node.axis_frequencies;
+                let mut per_axis = axis_frequencies.is_some();
+
+                ui.checkbox(&mut per_axis, "Per Axis").on_hover_text(
+                    "Multiply each coordinate before sampling, which stretches the noise along \
+                    the axes with lower frequencies",
+                );
+
+                if per_axis != axis_frequencies.is_some() {
+                    node.axis_frequencies = per_axis.then_some([1.0; 3]);
+                }
+
+                if let Some(frequencies) = &mut node.axis_frequencies {
+                    Grid::new("axis_frequencies").show(ui, |ui| {
+                        for (axis, frequency) in ["X", "Y", "Z"].into_iter().zip(frequencies) {
+                            ui.label(axis);
+                            ui.add(DragValue::new(frequency).speed(0.01));
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                if node.axis_frequencies != axis_frequencies {
+                    self.updated_node_indices.insert(node_idx);
+                }
+            });
+
+            ui.separator();
+        }
+
+        if let NoiseNode::Turbulence(node) = snarl.get_node(node_idx) {
+            let mut per_axis = node.axis_frequencies.is_some();
+            if ui.checkbox(&mut per_axis, "Per-Axis Overrides").changed() {
+                NoiseNode::set_turbulence_per_axis(node_idx, per_axis, snarl);
+                self.updated_node_indices.insert(node_idx);
+            }
+
+            ui.separator();
+        }
+
+        if let NoiseNode::FastNoise(node) = snarl.get_node_mut(node_idx) {
+            let mut changed = ui
+                .checkbox(&mut node.is_3d, "3D")
+                .on_hover_text(
+                    "Sample 3D noise instead of 2D noise, which ignores the Z coordinate",
+                )
+                .changed();
+
+            ui.add_enabled_ui(node.is_3d, |ui| {
+                ui.menu_button("Rotation", |ui| {
+                    for value in [
+                        FastRotationType::None,
+                        FastRotationType::ImproveXYPlanes,
+                        FastRotationType::ImproveXZPlanes,
+                    ] {
+                        changed |= ui
+                            .radio_value(&mut node.rotation_ty, value, format!("{value:?}"))
+                            .changed();
+                    }
+                });
+            });
+
+            ui.add_enabled_ui(node.noise_ty == FastNoiseType::Cellular, |ui| {
+                ui.menu_button("Cellular Distance", |ui| {
+                    for value in [
+                        CellularDistanceFunction::Euclidean,
+                        CellularDistanceFunction::EuclideanSq,
+                        CellularDistanceFunction::Hybrid,
+                        CellularDistanceFunction::Manhattan,
+                    ] {
+                        changed |= ui
+                            .radio_value(
+                                &mut node.cellular_distance_fn,
+                                value,
+                                format!("{value:?}"),
+                            )
+                            .changed();
+                    }
+                });
+
+                ui.menu_button("Cellular Return", |ui| {
+                    for value in [
+                        CellularReturnType::CellValue,
+                        CellularReturnType::Distance,
+                        CellularReturnType::Distance2,
+                        CellularReturnType::Distance2Add,
+                        CellularReturnType::Distance2Div,
+                        CellularReturnType::Distance2Mul,
+                        CellularReturnType::Distance2Sub,
+                    ] {
+                        changed |= ui
+                            .radio_value(&mut node.cellular_return_ty, value, format!("{value:?}"))
+                            .changed();
+                    }
+                });
+            });
+
+            if changed {
+                self.updated_node_indices.insert(node_idx);
+            }
+
+            ui.separator();
+        }
+
+        if let NoiseNode::Clamp(node) = snarl.get_node(node_idx) {
+            let has_value_bounds =
+                matches!(node.lower_bound, Value(_)) || matches!(node.upper_bound, Value(_));
+
+            if ui
+                .add_enabled(has_value_bounds, Button::new("Fill Bounds From Input"))
+                .on_hover_text(
+                    "Sample the input over the preview and use the 1st and 99th percentiles",
+                )
+                .clicked()
+            {
+                if let Some([lower_bound, upper_bound]) =
+                    node.observed_bounds(node_idx, snarl, self.backend)
+                {
+                    let node = snarl.get_node_mut(node_idx).as_clamp_mut().unwrap();
+
+                    if let Value(value) = &mut node.lower_bound {
+                        *value = lower_bound;
+                    }
+
+                    if let Value(value) = &mut node.upper_bound {
+                        *value = upper_bound;
+                    }
+
+                    self.updated_node_indices.insert(node_idx);
+                }
 
-            if ui.button("Integer").clicked() {
-                snarl.insert_node(pos, NoiseNode::U32(Default::default()));
                 ui.close_menu();
             }
 
             ui.separator();
-            ui.label("Operations");
+        }
+
+        if let NoiseNode::Curve(_) | NoiseNode::Terrace(_) = snarl.get_node(node_idx) {
+            if ui.button("Add Control Point Pins").clicked() {
+                // Four more than the current control point pins (all inputs but the source)
+                let pin_count = self.inputs(snarl.get_node(node_idx)) - 1 + 4;
+
+                match snarl.get_node_mut(node_idx) {
+                    NoiseNode::Curve(CurveNode {
+                        pin_count: pins, ..
+                    })
+                    | NoiseNode::Terrace(TerraceNode {
+                        pin_count: pins, ..
+                    }) => *pins = pin_count,
+                    _ => unreachable!(),
+                }
 
-            if ui.button("Add").clicked() {
-                snarl.insert_node(
-                    pos,
-                    NoiseNode::Operation(ConstantOpNode::new(OpType::Add, ())),
-                );
                 ui.close_menu();
             }
 
-            if ui.button("Divide").clicked() {
-                snarl.insert_node(
-                    pos,
-                    NoiseNode::Operation(ConstantOpNode::new(OpType::Divide, ())),
-                );
+            ui.menu_button("Inline Control Points", |ui| {
+                self.inline_control_points_menu(ui, node_idx, snarl);
+            });
+
+            if ui.button("Remove Empty Pins").clicked() {
+                NoiseNode::remove_empty_control_points(node_idx, snarl);
+
                 ui.close_menu();
             }
 
-            if ui.button("Multiply").clicked() {
-                snarl.insert_node(
-                    pos,
-                    NoiseNode::Operation(ConstantOpNode::new(OpType::Multiply, ())),
-                );
+            if ui.button("Sort Control Points").clicked() {
+                NoiseNode::sort_control_points(node_idx, snarl);
+                self.updated_node_indices.insert(node_idx);
+
                 ui.close_menu();
             }
 
-            if ui.button("Subtract").clicked() {
-                snarl.insert_node(
-                    pos,
-                    NoiseNode::Operation(ConstantOpNode::new(OpType::Subtract, ())),
-                );
+            ui.separator();
+        }
+
+        if snarl.get_node(node_idx).has_image() {
+            ui.menu_button("Export Image", |ui| {
+                self.export_image_menu(ui, node_idx, snarl);
+            });
+
+            if ui
+                .button("Share Preview")
+                .on_hover_text(
+                    "Save a small PNG of the preview, stamped with the hash of the graph, to paste \
+                    into a chat",
+                )
+                .clicked()
+            {
+                Self::share_preview(node_idx, snarl, self.backend);
+
                 ui.close_menu();
             }
-        });
-    }
-
-    fn node_menu(
-        &mut self,
-        node_idx: usize,
-        inputs: &[InPin],
-        outputs: &[OutPin],
-        ui: &mut Ui,
-        _scale: f32,
-        snarl: &mut Snarl<NoiseNode>,
-    ) {
-        ui.label("Node menu");
+        }
 
         #[cfg(not(target_arch = "wasm32"))]
         {
-            let node = snarl.get_node(node_idx);
-
-            match node {
+            match snarl.get_node(node_idx) {
                 NoiseNode::ControlPoint(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
@@ -2914,339 +7459,194 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 _ => {
                     if ui.button("Export File...").clicked() {
                         if let Some(path) = App::file_dialog().save_file() {
-                            App::save_as(path, &node.expr(node_idx, snarl)).unwrap_or_default();
+                            let expr = snarl.get_node(node_idx).expr(node_idx, snarl);
+                            let comment = format!("Graph hash: {}", graph_hash(snarl));
+                            App::save_commented_as(path, Some(&comment), &expr).unwrap_or_default();
                         }
 
                         ui.close_menu();
                     }
 
-                    ui.separator();
-                }
-            }
-        }
+                    ui.menu_button("Verify Export", |ui| {
+                        if ui
+                            .button("Compare With Editor")
+                            .on_hover_text(
+                                "Read back the exported file as games do and check that it \
+                                samples exactly the values shown here",
+                            )
+                            .clicked()
+                        {
+                            Self::verify_export(&Snapshot::new(
+                                &snarl.get_node(node_idx).expr(node_idx, snarl),
+                                self.backend,
+                            ));
 
-        if ui.button("Remove").clicked() {
-            self.removed_node_indices.insert(node_idx);
+                            ui.close_menu();
+                        }
 
-            for remote in outputs.iter().flat_map(|output| output.remotes.iter()) {
-                self.updated_node_indices.insert(remote.node);
-                match (remote.input, snarl.get_node(remote.node)) {
-                    (
-                        0,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_fractal_mut()
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (0, NoiseNode::Checkerboard(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_checkerboard_mut()
-                            .unwrap()
-                            .size = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (0, NoiseNode::ControlPoint(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_control_point_mut()
-                            .unwrap()
-                            .input = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (0, NoiseNode::Cylinders(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_cylinders_mut()
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (
-                        0,
-                        NoiseNode::OpenSimplex(_)
-                        | NoiseNode::Perlin(_)
-                        | NoiseNode::PerlinSurflet(_)
-                        | NoiseNode::Simplex(_)
-                        | NoiseNode::SuperSimplex(_)
-                        | NoiseNode::Value(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_generator_mut()
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (0, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_rigid_fractal_mut()
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (0, NoiseNode::Worley(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_worley_mut()
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (0 | 1, NoiseNode::F64Operation(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_const_op_f64_mut()
-                            .unwrap()
-                            .inputs[remote.input] = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (0 | 1, NoiseNode::Operation(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_const_op_tuple_mut()
-                            .unwrap()
-                            .inputs[remote.input] = Default::default();
-                    }
-                    (0 | 1, NoiseNode::U32Operation(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_const_op_u32_mut()
-                            .unwrap()
-                            .inputs[remote.input] = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (
-                        1,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_fractal_mut()
-                            .unwrap()
-                            .octaves = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (1, NoiseNode::Clamp(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_clamp_mut()
-                            .unwrap()
-                            .lower_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (1, NoiseNode::ControlPoint(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_control_point_mut()
-                            .unwrap()
-                            .output = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (1, NoiseNode::Exponent(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_exponent_mut()
-                            .unwrap()
-                            .exponent = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (1, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_rigid_fractal_mut()
-                            .unwrap()
-                            .octaves = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (1, NoiseNode::ScaleBias(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_scale_bias_mut()
-                            .unwrap()
-                            .scale = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (1, NoiseNode::Turbulence(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_turbulence_mut()
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (1, NoiseNode::Worley(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_worley_mut()
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (
-                        1..=4,
-                        NoiseNode::RotatePoint(_)
-                        | NoiseNode::ScalePoint(_)
-                        | NoiseNode::TranslatePoint(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_transform_mut()
-                            .unwrap()
-                            .axes[remote.input - 1] =
-                            Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (
-                        2,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_fractal_mut()
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (2, NoiseNode::Clamp(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_clamp_mut()
-                            .unwrap()
-                            .upper_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (2, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_rigid_fractal_mut()
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (2, NoiseNode::ScaleBias(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_scale_bias_mut()
-                            .unwrap()
-                            .bias = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (2, NoiseNode::Turbulence(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_turbulence_mut()
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (
-                        3,
+                        if ui
+                            .button("Save Snapshot...")
+                            .on_hover_text(
+                                "Save the expression with the values sampled here, which later \
+                                versions may be checked against with File > Check Snapshot...",
+                            )
+                            .clicked()
+                        {
+                            if let Some(path) = App::file_dialog().save_file() {
+                                Snapshot::new(
+                                    &snarl.get_node(node_idx).expr(node_idx, snarl),
+                                    self.backend,
+                                )
+                                .save(path)
+                                .unwrap_or_default();
+                            }
+
+                            ui.close_menu();
+                        }
+                    });
+
+                    if matches!(
+                        snarl.get_node(node_idx),
                         NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_fractal_mut()
-                            .unwrap()
-                            .lacunarity = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (3, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_rigid_fractal_mut()
-                            .unwrap()
-                            .lacunarity = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (3, NoiseNode::Select(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_select_mut()
-                            .unwrap()
-                            .lower_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (3, NoiseNode::Turbulence(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_turbulence_mut()
-                            .unwrap()
-                            .power = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                            | NoiseNode::Billow(_)
+                            | NoiseNode::Fbm(_)
+                            | NoiseNode::HybridMulti(_)
+                            | NoiseNode::OpenSimplex(_)
+                            | NoiseNode::Perlin(_)
+                            | NoiseNode::PerlinSurflet(_)
+                            | NoiseNode::RigidMulti(_)
+                            | NoiseNode::Simplex(_)
+                            | NoiseNode::SuperSimplex(_)
+                            | NoiseNode::Turbulence(_)
+                            | NoiseNode::Value(_)
+                            | NoiseNode::Worley(_)
+                    ) && ui
+                        .add_enabled(
+                            self.backend == Backend::Noise,
+                            Button::new("Export Permutation Tables..."),
+                        )
+                        .on_hover_text(
+                            "Save the permutation tables and gradients derived from the seed, so \
+                            that a runtime implementation can produce exactly the same noise",
+                        )
+                        .clicked()
+                    {
+                        if let Some(mut path) =
+                            FileDialog::new().add_filter("JSON", &["json"]).save_file()
+                        {
+                            if path.extension().is_none() {
+                                path.set_extension("json");
+                            }
+
+                            save_permutation_tables(
+                                path,
+                                &snarl.get_node(node_idx).expr(node_idx, snarl),
+                                &Provenance::new(snarl, self.backend),
+                            )
+                            .unwrap_or_default();
+                        }
+
+                        ui.close_menu();
                     }
-                    (
-                        4,
+
+                    if matches!(
+                        snarl.get_node(node_idx),
                         NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_fractal_mut()
-                            .unwrap()
-                            .persistence = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (4, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_rigid_fractal_mut()
-                            .unwrap()
-                            .persistence = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (4, NoiseNode::Select(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_select_mut()
-                            .unwrap()
-                            .upper_bound = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (4, NoiseNode::Turbulence(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_turbulence_mut()
-                            .unwrap()
-                            .roughness = Value(snarl.get_node(node_idx).eval_u32(snarl));
-                    }
-                    (5, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_rigid_fractal_mut()
-                            .unwrap()
-                            .attenuation = Value(snarl.get_node(node_idx).eval_f64(snarl));
-                    }
-                    (5, NoiseNode::Select(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .as_select_mut()
-                            .unwrap()
-                            .falloff = Value(snarl.get_node(node_idx).eval_f64(snarl));
+                            | NoiseNode::Billow(_)
+                            | NoiseNode::FastNoise(_)
+                            | NoiseNode::Fbm(_)
+                            | NoiseNode::HybridMulti(_)
+                            | NoiseNode::OpenSimplex(_)
+                            | NoiseNode::Perlin(_)
+                            | NoiseNode::PerlinSurflet(_)
+                            | NoiseNode::RigidMulti(_)
+                            | NoiseNode::Simplex(_)
+                            | NoiseNode::SuperSimplex(_)
+                            | NoiseNode::Value(_)
+                            | NoiseNode::Worley(_)
+                    ) && ui
+                        .button("Export Godot Resource...")
+                        .on_hover_text(
+                            "Save a FastNoiseLite resource which Godot can load, listing the \
+                            settings it cannot reproduce",
+                        )
+                        .clicked()
+                    {
+                        Self::export_godot_resource(
+                            &snarl.get_node(node_idx).expr(node_idx, snarl),
+                        );
+
+                        ui.close_menu();
                     }
-                    (control_point_idx, NoiseNode::Curve(_)) if control_point_idx > 0 => {
-                        let node = snarl.get_node_mut(remote.node).as_curve_mut().unwrap();
-                        node.control_point_node_indices[control_point_idx - 1] = None;
 
-                        while let Some(None) = node.control_point_node_indices.last() {
-                            node.control_point_node_indices.pop();
-                        }
+                    if ui
+                        .add_enabled(self.backend == Backend::Noise, Button::new("Copy GLSL"))
+                        .on_hover_text(
+                            "Copy a GLSL function which samples this node, for use within \
+                            shaders; named decimal variables become uniforms",
+                        )
+                        .clicked()
+                    {
+                        let expr = snarl.get_node(node_idx).expr(node_idx, snarl);
+                        Self::copy_code(ui, "GLSL", expr.to_glsl(), snarl);
+
+                        ui.close_menu();
                     }
-                    (control_point_idx, NoiseNode::Terrace(_)) if control_point_idx > 0 => {
-                        let node = snarl.get_node_mut(remote.node).as_terrace_mut().unwrap();
-                        node.control_point_node_indices[control_point_idx - 1] = None;
 
-                        while let Some(None) = node.control_point_node_indices.last() {
-                            node.control_point_node_indices.pop();
-                        }
+                    if ui
+                        .add_enabled(self.backend == Backend::Noise, Button::new("Copy WGSL"))
+                        .on_hover_text(
+                            "Copy a WGSL function and compute shader which sample this node, for \
+                            use with wgpu; named decimal variables become fields of a uniform \
+                            struct",
+                        )
+                        .clicked()
+                    {
+                        let expr = snarl.get_node(node_idx).expr(node_idx, snarl);
+                        Self::copy_code(ui, "WGSL", expr.to_wgsl(), snarl);
+
+                        ui.close_menu();
                     }
-                    _ => {}
-                }
-            }
 
-            for node_idx in inputs
-                .iter()
-                .flat_map(|input| input.remotes.iter().map(|remote| remote.node))
-                .chain(
-                    outputs
-                        .iter()
-                        .flat_map(|output| output.remotes.iter().map(|remote| remote.node)),
-                )
-            {
-                NoiseNode::propagate_tuple_from_f64_op(node_idx, snarl);
-                NoiseNode::propagate_tuple_from_u32_op(node_idx, snarl);
+                    ui.add_enabled_ui(self.backend == Backend::Noise, |ui| {
+                        ui.menu_button("Copy Rust", |ui| {
+                            for (parameters, text, hover_text) in [
+                                (
+                                    RustParameters::Literals,
+                                    "With Literals",
+                                    "Copy a Rust function which builds this node with the noise \
+                                    crate, with every setting written as a literal",
+                                ),
+                                (
+                                    RustParameters::Parameters,
+                                    "With Seed Parameters",
+                                    "Copy a Rust function which takes the seed and octave count as \
+                                    parameters, so one function builds the noise of any world seed",
+                                ),
+                                (
+                                    RustParameters::ConstGenerics,
+                                    "With Const Generics",
+                                    "Copy a Rust function which takes the seed and octave count as \
+                                    const generic parameters",
+                                ),
+                            ] {
+                                if ui.button(text).on_hover_text(hover_text).clicked() {
+                                    let expr = snarl.get_node(node_idx).expr(node_idx, snarl);
+                                    Self::copy_code(ui, "Rust", expr.to_rust_with(parameters), snarl);
+
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+
+                    ui.separator();
+                }
             }
+        }
 
-            snarl.remove_node(node_idx);
+        if ui.button("Remove").clicked() {
+            self.remove_node(node_idx, inputs, outputs, snarl);
             ui.close_menu();
         }
     }
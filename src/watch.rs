@@ -0,0 +1,102 @@
+use {
+    egui::{Align2, Context, Id, Window},
+    std::{
+        fs::metadata,
+        path::{Path, PathBuf},
+        time::{Duration, Instant, SystemTime},
+    },
+};
+
+/// Notices when the open project is changed on disk by another program, such as a `git pull`,
+/// and asks whether it should be reloaded so that saving does not silently overwrite the change.
+#[derive(Default)]
+pub struct FileWatcher {
+    checked_at: Option<Instant>,
+    is_changed: bool,
+
+    /// The modification time of the file when it was last opened, saved or kept.
+    modified: Option<SystemTime>,
+
+    path: Option<PathBuf>,
+}
+
+impl FileWatcher {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn clear(&mut self) {
+        *self = Default::default();
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        metadata(path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    /// Shows the reload prompt if the file has changed, returning `true` if it should be reloaded.
+    pub fn show(&mut self, ctx: &Context) -> bool {
+        let Some(path) = self.path.clone() else {
+            return false;
+        };
+
+        if !self.is_changed {
+            if let Some(elapsed) = self.checked_at.map(|checked_at| checked_at.elapsed()) {
+                if elapsed < Self::CHECK_INTERVAL {
+                    ctx.request_repaint_after(Self::CHECK_INTERVAL - elapsed);
+
+                    return false;
+                }
+            }
+
+            self.checked_at = Some(Instant::now());
+            ctx.request_repaint_after(Self::CHECK_INTERVAL);
+
+            // Files which have been removed are left alone, as saving puts them back
+            let modified = Self::modified(&path);
+            self.is_changed = modified.is_some() && modified != self.modified;
+
+            if !self.is_changed {
+                return false;
+            }
+        }
+
+        let mut reload = false;
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Window::new("Project Changed")
+            .id(Id::new("file_watcher"))
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{file_name} was changed by another program."));
+                ui.label("Reload it, or keep your edits and overwrite the change when you save?");
+
+                ui.horizontal(|ui| {
+                    if ui.button("Reload").clicked() {
+                        reload = true;
+                    }
+
+                    if ui.button("Keep My Edits").clicked() {
+                        self.modified = Self::modified(&path);
+                        self.is_changed = false;
+                    }
+                });
+            });
+
+        if reload {
+            self.is_changed = false;
+        }
+
+        reload
+    }
+
+    /// Watches a file which has just been opened or saved.
+    pub fn watch(&mut self, path: &Path) {
+        self.checked_at = Some(Instant::now());
+        self.is_changed = false;
+        self.modified = Self::modified(path);
+        self.path = Some(path.to_path_buf());
+    }
+}
@@ -0,0 +1,424 @@
+use {
+    super::{
+        character::{Character, Tag},
+        node::{ClampNode, ConstantNode, NodeValue, NoiseNode, ScaleBiasNode, TerraceNode},
+        view::Viewer,
+    },
+    egui::{pos2, Context, DragValue, Id, TextEdit, Window},
+    egui_snarl::{ui::SnarlViewer, InPinId, OutPinId, Snarl},
+    noise_graph::{Backend, Expr},
+};
+
+/// Starter terrain graphs which may be generated by the wizard.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Preset {
+    #[default]
+    Island,
+    Mountains,
+    Canyonlands,
+    Archipelago,
+}
+
+impl Preset {
+    const ALL: [Self; 4] = [
+        Self::Island,
+        Self::Mountains,
+        Self::Canyonlands,
+        Self::Archipelago,
+    ];
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Island => {
+                "A single landmass: fBm noise is raised by a radial falloff from the origin and \
+                clamped at sea level."
+            }
+            Self::Mountains => {
+                "Ridged peaks masked by a low frequency fBm, so that mountain ranges are separated \
+                by lowlands."
+            }
+            Self::Canyonlands => {
+                "Eroded fBm noise which is stepped into terraces, with the highest steps flattened \
+                into mesas."
+            }
+            Self::Archipelago => {
+                "Scattered islands: high frequency fBm noise clamped at sea level, with the peaks \
+                flattened."
+            }
+        }
+    }
+
+    /// Generates the preset into a graph of its own and returns the expression of its output.
+    fn expr(self, seed: u32, backend: Backend) -> Expr {
+        let mut snarl = Snarl::new();
+        let node_idx = Viewer::hidden(backend, 0, |viewer| {
+            Wizard::generate(self, seed, &mut snarl, viewer)
+        });
+
+        snarl.get_node(node_idx).expr(node_idx, &snarl)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Island => "Island",
+            Self::Mountains => "Mountains",
+            Self::Canyonlands => "Canyonlands",
+            Self::Archipelago => "Archipelago",
+        }
+    }
+
+    /// The named constants exposed by the generated graph, as a name and description.
+    fn parameters(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Island => &[
+                ("seed", "Seed of the land noise"),
+                ("land_frequency", "Size of the hills and bays"),
+                ("island_frequency", "Inverse size of the island"),
+                ("sea_level", "Height below which the sea is flat"),
+            ],
+            Self::Mountains => &[
+                ("seed", "Seed of the peaks and ranges"),
+                ("octaves", "Detail of the peaks"),
+                ("peak_frequency", "Size of the individual peaks"),
+                ("range_frequency", "Size of the mountain ranges"),
+            ],
+            Self::Canyonlands => &[
+                ("seed", "Seed of the land noise"),
+                ("mesa_frequency", "Size of the mesas and canyons"),
+                ("erosion", "Distortion of the canyon walls"),
+                ("mesa_level", "Height at which mesas are flattened"),
+            ],
+            Self::Archipelago => &[
+                ("seed", "Seed of the islands"),
+                ("island_frequency", "Inverse size of the islands"),
+                ("sea_level", "Height below which the sea is flat"),
+                ("peak_level", "Height at which peaks are flattened"),
+            ],
+        }
+    }
+}
+
+/// Inserts nodes into the graph on a grid, connecting them the same way as the graph editor.
+struct GraphBuilder<'a, 'b> {
+    snarl: &'a mut Snarl<NoiseNode>,
+    viewer: &'a mut Viewer<'b>,
+}
+
+impl GraphBuilder<'_, '_> {
+    const COLUMN_WIDTH: f32 = 260.0;
+    const ROW_HEIGHT: f32 = 180.0;
+
+    fn connect(&mut self, from_node_idx: usize, to_node_idx: usize, input: usize) {
+        let from = self.snarl.out_pin(OutPinId {
+            node: from_node_idx,
+            output: 0,
+        });
+        let to = self.snarl.in_pin(InPinId {
+            node: to_node_idx,
+            input,
+        });
+
+        self.viewer.connect(&from, &to, self.snarl);
+    }
+
+    fn f64(&mut self, column: usize, row: usize, name: &str, value: f64) -> usize {
+        self.insert(
+            column,
+            row,
+            NoiseNode::F64(ConstantNode {
+                name: name.to_owned(),
+                value,
+            }),
+        )
+    }
+
+    fn insert(&mut self, column: usize, row: usize, mut node: NoiseNode) -> usize {
+        // Previews are centered on the origin, which is where the island falloff peaks
+        if let Some(image) = node.image_mut() {
+            image.x = -0.5;
+            image.y = -0.5;
+        }
+
+        let has_image = node.has_image();
+        let node_idx = self.snarl.insert_node(
+            pos2(
+                column as f32 * Self::COLUMN_WIDTH,
+                row as f32 * Self::ROW_HEIGHT,
+            ),
+            node,
+        );
+
+        if has_image {
+            self.viewer.updated_node_indices.insert(node_idx);
+        }
+
+        node_idx
+    }
+
+    fn u32(&mut self, column: usize, row: usize, name: &str, value: u32) -> usize {
+        self.insert(
+            column,
+            row,
+            NoiseNode::U32(ConstantNode {
+                name: name.to_owned(),
+                value,
+            }),
+        )
+    }
+}
+
+/// Collapsible window which generates parameterized starter graphs for common kinds of terrain.
+///
+/// The settings of each graph are exposed as named constants so that they may be tweaked in the
+/// graph or set when the expression is used elsewhere.
+#[derive(Default)]
+pub struct Wizard {
+    /// The character of each preset, once described.
+    characters: Vec<(Preset, Character)>,
+
+    /// The seed and backend the presets were described with.
+    characters_version: Option<(u32, Backend)>,
+
+    preset: Preset,
+
+    /// Terms such as "ridged, high-frequency" which the presets are sorted by.
+    query: String,
+
+    seed: u32,
+}
+
+impl Wizard {
+    /// Inserts the nodes of a preset and returns the index of its output node.
+    fn generate(
+        preset: Preset,
+        seed: u32,
+        snarl: &mut Snarl<NoiseNode>,
+        viewer: &mut Viewer<'_>,
+    ) -> usize {
+        let mut graph = GraphBuilder { snarl, viewer };
+        let seed = graph.u32(0, 0, "seed", seed);
+
+        match preset {
+            Preset::Island => {
+                let land_frequency = graph.f64(0, 1, "land_frequency", 1.0);
+                let island_frequency = graph.f64(0, 2, "island_frequency", 0.2);
+                let sea_level = graph.f64(0, 3, "sea_level", -0.2);
+
+                let land = graph.insert(1, 0, NoiseNode::Fbm(Default::default()));
+                graph.connect(seed, land, 0);
+                graph.connect(land_frequency, land, 2);
+
+                let falloff = graph.insert(1, 2, NoiseNode::Cylinders(Default::default()));
+                graph.connect(island_frequency, falloff, 0);
+
+                let hills = graph.insert(
+                    2,
+                    0,
+                    NoiseNode::ScaleBias(ScaleBiasNode {
+                        scale: NodeValue::Value(0.5),
+                        ..Default::default()
+                    }),
+                );
+                graph.connect(land, hills, 0);
+
+                let island = graph.insert(3, 1, NoiseNode::Add(Default::default()));
+                graph.connect(hills, island, 0);
+                graph.connect(falloff, island, 1);
+
+                let sea = graph.insert(
+                    4,
+                    1,
+                    NoiseNode::Clamp(ClampNode {
+                        upper_bound: NodeValue::Value(1.0),
+                        ..Default::default()
+                    }),
+                );
+                graph.connect(island, sea, 0);
+                graph.connect(sea_level, sea, 1);
+
+                sea
+            }
+            Preset::Mountains => {
+                let octaves = graph.u32(0, 1, "octaves", 6);
+                let peak_frequency = graph.f64(0, 2, "peak_frequency", 1.0);
+                let range_frequency = graph.f64(0, 3, "range_frequency", 0.3);
+
+                let peaks = graph.insert(1, 0, NoiseNode::RigidMulti(Default::default()));
+                graph.connect(seed, peaks, 0);
+                graph.connect(octaves, peaks, 1);
+                graph.connect(peak_frequency, peaks, 2);
+
+                let ranges = graph.insert(1, 2, NoiseNode::Fbm(Default::default()));
+                graph.connect(seed, ranges, 0);
+                graph.connect(range_frequency, ranges, 2);
+
+                let mask = graph.insert(
+                    2,
+                    2,
+                    NoiseNode::ScaleBias(ScaleBiasNode {
+                        scale: NodeValue::Value(0.5),
+                        bias: NodeValue::Value(0.5),
+                        ..Default::default()
+                    }),
+                );
+                graph.connect(ranges, mask, 0);
+
+                let mountains = graph.insert(3, 1, NoiseNode::Multiply(Default::default()));
+                graph.connect(peaks, mountains, 0);
+                graph.connect(mask, mountains, 1);
+
+                mountains
+            }
+            Preset::Canyonlands => {
+                let mesa_frequency = graph.f64(0, 1, "mesa_frequency", 1.0);
+                let erosion = graph.f64(0, 2, "erosion", 0.1);
+                let mesa_level = graph.f64(0, 3, "mesa_level", 0.4);
+
+                let land = graph.insert(1, 0, NoiseNode::Fbm(Default::default()));
+                graph.connect(seed, land, 0);
+                graph.connect(mesa_frequency, land, 2);
+
+                let eroded = graph.insert(2, 1, NoiseNode::Turbulence(Default::default()));
+                graph.connect(land, eroded, 0);
+                graph.connect(erosion, eroded, 3);
+
+                let terraces = graph.insert(
+                    3,
+                    1,
+                    NoiseNode::Terrace(TerraceNode {
+                        inline_control_points: vec![-1.0, -0.4, 0.1, 0.5, 1.0],
+                        ..Default::default()
+                    }),
+                );
+                graph.connect(eroded, terraces, 0);
+
+                let mesas = graph.insert(4, 1, NoiseNode::Plateau(Default::default()));
+                graph.connect(terraces, mesas, 0);
+                graph.connect(mesa_level, mesas, 1);
+
+                mesas
+            }
+            Preset::Archipelago => {
+                let island_frequency = graph.f64(0, 1, "island_frequency", 2.0);
+                let sea_level = graph.f64(0, 2, "sea_level", 0.1);
+                let peak_level = graph.f64(0, 3, "peak_level", 0.7);
+
+                let land = graph.insert(1, 0, NoiseNode::Fbm(Default::default()));
+                graph.connect(seed, land, 0);
+                graph.connect(island_frequency, land, 2);
+
+                let sea = graph.insert(
+                    2,
+                    1,
+                    NoiseNode::Clamp(ClampNode {
+                        upper_bound: NodeValue::Value(1.0),
+                        ..Default::default()
+                    }),
+                );
+                graph.connect(land, sea, 0);
+                graph.connect(sea_level, sea, 1);
+
+                let peaks = graph.insert(3, 1, NoiseNode::Plateau(Default::default()));
+                graph.connect(sea, peaks, 0);
+                graph.connect(peak_level, peaks, 1);
+
+                peaks
+            }
+        }
+    }
+
+    /// Describes each preset, which is sampled again when the seed or backend changes.
+    fn characters(&mut self, backend: Backend) -> &[(Preset, Character)] {
+        if self.characters_version != Some((self.seed, backend)) {
+            self.characters = Preset::ALL
+                .into_iter()
+                .map(|preset| {
+                    (
+                        preset,
+                        Character::new(&preset.expr(self.seed, backend), backend),
+                    )
+                })
+                .collect();
+            self.characters_version = Some((self.seed, backend));
+        }
+
+        &self.characters
+    }
+
+    pub fn show(&mut self, ctx: &Context, snarl: &mut Snarl<NoiseNode>, viewer: &mut Viewer<'_>) {
+        Window::new("Terrain Wizard")
+            .id(Id::new("wizard"))
+            .default_open(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Character");
+                    ui.add(
+                        TextEdit::singleline(&mut self.query)
+                            .hint_text("ridged, high-frequency, low-contrast"),
+                    );
+                });
+
+                let (tags, unknown_words) = Tag::parse(&self.query);
+                let characters = self.characters(viewer.backend);
+
+                // Presets are sorted by how well they match the query, best first
+                let mut presets = characters.to_vec();
+                if !tags.is_empty() {
+                    presets.sort_by(|(_, a), (_, b)| b.score(&tags).total_cmp(&a.score(&tags)));
+                }
+
+                ui.horizontal(|ui| {
+                    for (preset, character) in &presets {
+                        let text = if tags.is_empty() {
+                            preset.name().to_owned()
+                        } else {
+                            format!("{} {:.0}%", preset.name(), character.score(&tags) * 100.0)
+                        };
+
+                        ui.selectable_value(&mut self.preset, *preset, text);
+                    }
+                });
+
+                if !unknown_words.is_empty() {
+                    ui.weak(format!("Unknown terms: {}", unknown_words.join(", ")))
+                        .on_hover_text(Tag::ALL.map(|tag| tag.name()).join(", "));
+                }
+
+                ui.label(self.preset.description());
+
+                if let Some((_, character)) =
+                    presets.iter().find(|(preset, _)| *preset == self.preset)
+                {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.weak("Character:");
+
+                        for tag in character.tags() {
+                            ui.weak(tag.name());
+                        }
+                    });
+                }
+
+                ui.separator();
+
+                ui.label("Named constants:");
+
+                for (name, description) in self.preset.parameters() {
+                    ui.horizontal(|ui| {
+                        ui.monospace(*name);
+                        ui.weak(*description);
+                    });
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Seed");
+                    ui.add(DragValue::new(&mut self.seed));
+
+                    if ui.button("Generate").clicked() {
+                        Self::generate(self.preset, self.seed, snarl, viewer);
+                    }
+                });
+            });
+    }
+}